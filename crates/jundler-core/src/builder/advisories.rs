@@ -0,0 +1,221 @@
+//! Checks the version of Node.js a build uses against the vulnerabilities published by the Node.js security working
+//! group, before anything is built with it. The database is cached for a day, so builds only download it once in a
+//! while, and can still be checked without the internet.
+
+use super::node_manager::security::{
+    download_vulnerability_index, parse_vulnerabilities, Vulnerability,
+};
+use super::Builder;
+use crate::ui::messages::ADVISORIES_MSG;
+use anyhow::{anyhow, Result};
+use log::debug;
+use semver::Version;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// The name of the cached vulnerability database, in the `advisories` folder of the cache.
+pub const VULNERABILITIES_FILE_NAME: &str = "node-vulnerabilities.json";
+
+/// How long the cached vulnerability database is used for before it's downloaded again.
+const MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// What to do when the version of Node.js a build uses has published vulnerabilities.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AdvisoryPolicy {
+    /// Don't check the version of Node.js.
+    Off,
+
+    /// Warn about the vulnerabilities, and build anyways.
+    #[default]
+    Warn,
+
+    /// Fail the build.
+    Fail,
+}
+
+impl fmt::Display for AdvisoryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AdvisoryPolicy::Off => write!(f, "off"),
+            AdvisoryPolicy::Warn => write!(f, "warn"),
+            AdvisoryPolicy::Fail => write!(f, "fail"),
+        }
+    }
+}
+
+/// The published vulnerabilities that affect a version of Node.js.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Advisory {
+    /// The version of Node.js.
+    pub version: Version,
+
+    /// The CVEs of every vulnerability that affects it. Vulnerabilities without a CVE aren't listed.
+    pub cves: Vec<String>,
+
+    /// How many vulnerabilities affect it, with or without a CVE.
+    pub count: usize,
+
+    /// The first release in the same major version that fixed all of them, if there is one.
+    pub safe_version: Option<Version>,
+}
+
+impl Advisory {
+    /// Finds the vulnerabilities in `vulnerabilities` that affect `version`. `None` if there aren't any.
+    pub fn of(version: &Version, vulnerabilities: &[Vulnerability]) -> Option<Self> {
+        let affecting: Vec<&Vulnerability> = vulnerabilities
+            .iter()
+            .filter(|vulnerability| vulnerability.affects(version))
+            .collect();
+
+        if affecting.is_empty() {
+            return None;
+        }
+
+        // Every vulnerability has to be fixed, so the safe version is the last of the versions that fixed each of them
+        let safe_version = affecting
+            .iter()
+            .map(|vulnerability| vulnerability.patched_in(version))
+            .collect::<Option<Vec<_>>>()
+            .and_then(|patched| patched.into_iter().max());
+
+        Some(Advisory {
+            version: version.clone(),
+            cves: affecting
+                .iter()
+                .flat_map(|vulnerability| vulnerability.cves.iter().cloned())
+                .collect(),
+            count: affecting.len(),
+            safe_version,
+        })
+    }
+}
+
+impl fmt::Display for Advisory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Node.js v{} has {} published {}",
+            self.version,
+            self.count,
+            if self.count == 1 {
+                "vulnerability"
+            } else {
+                "vulnerabilities"
+            }
+        )?;
+
+        if !self.cves.is_empty() {
+            write!(f, " ({})", self.cves.join(", "))?;
+        }
+
+        match &self.safe_version {
+            Some(safe_version) => write!(
+                f,
+                ", fixed in v{}. Build with `--node-version {}` or later.",
+                safe_version, safe_version
+            ),
+            None => write!(
+                f,
+                ", and no v{} release fixes them all.",
+                self.version.major
+            ),
+        }
+    }
+}
+
+impl Builder {
+    /// Checks the version of Node.js the build uses against the published vulnerabilities, warning about or failing
+    /// on any, as `policy` says. If the vulnerability database can't be downloaded or read, the build goes on with a
+    /// warning.
+    pub(super) fn check_advisories(
+        &mut self,
+        version: &Version,
+        policy: AdvisoryPolicy,
+        frozen: bool,
+    ) -> Result<()> {
+        if policy == AdvisoryPolicy::Off {
+            return Ok(());
+        }
+
+        let spinner = self.tracker.spawn_spinner(ADVISORIES_MSG, 0);
+        let vulnerabilities = self.node_vulnerabilities(frozen);
+        spinner.close();
+
+        let Some(vulnerabilities) = vulnerabilities else {
+            self.tracker.warn(format!(
+                "Could not check Node.js v{} for published vulnerabilities, as the vulnerability database couldn't be downloaded.",
+                version
+            ));
+
+            return Ok(());
+        };
+
+        match Advisory::of(version, &vulnerabilities) {
+            None => Ok(()),
+            Some(advisory) if policy == AdvisoryPolicy::Fail => Err(anyhow!("{}", advisory)),
+            Some(advisory) => {
+                self.tracker.warn(advisory.to_string());
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Gets the published vulnerabilities in Node.js: from the cache if it was downloaded recently (or at all, for
+    /// frozen builds, which can't download anything), and from the internet otherwise. A stale copy is used if the
+    /// database can't be downloaded. `None` if there's no copy at all.
+    fn node_vulnerabilities(&self, frozen: bool) -> Option<Vec<Vulnerability>> {
+        let path = self.vulnerabilities_path();
+
+        let cached = fs::read_to_string(&path).ok().and_then(|index| {
+            parse_vulnerabilities(&index)
+                .inspect_err(|err| debug!("Could not parse {}: {}", path.display(), err))
+                .ok()
+        });
+
+        let fresh = fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age < MAX_AGE);
+
+        // Frozen builds can't download anything, so they use whatever is cached
+        if fresh && cached.is_some() || frozen {
+            return cached;
+        }
+
+        let downloaded = download_vulnerability_index(self.downloader.as_ref()).and_then(|index| {
+            let vulnerabilities = parse_vulnerabilities(&index)?;
+
+            Ok((index, vulnerabilities))
+        });
+
+        match downloaded {
+            Ok((index, vulnerabilities)) => {
+                if let Err(err) =
+                    fs::create_dir_all(path.parent().expect("The database is in a folder!"))
+                        .and_then(|()| fs::write(&path, index))
+                {
+                    debug!("Could not cache the vulnerability database: {}", err);
+                }
+
+                Some(vulnerabilities)
+            }
+
+            Err(err) => {
+                debug!("Could not download the vulnerability database: {}", err);
+
+                cached
+            }
+        }
+    }
+
+    /// The path to the cached vulnerability database.
+    fn vulnerabilities_path(&self) -> PathBuf {
+        self.cache_dir
+            .join("advisories")
+            .join(VULNERABILITIES_FILE_NAME)
+    }
+}
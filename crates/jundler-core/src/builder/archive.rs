@@ -0,0 +1,173 @@
+use super::platforms::{Arch, Os};
+use anyhow::{Context, Result};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+/// The prefixes (compared case-insensitively) of files in the project root that are shipped alongside the binary.
+const EXTRA_FILE_PREFIXES: &[&str] = &["readme", "license", "licence"];
+
+/// The format of a release archive.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    /// A gzipped tarball, the usual format for Linux and macOS releases.
+    #[value(name = "tar.gz")]
+    TarGz,
+
+    /// A zip file, the usual format for Windows releases.
+    Zip,
+}
+
+impl fmt::Display for ArchiveFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArchiveFormat::TarGz => write!(f, "tar.gz"),
+            ArchiveFormat::Zip => write!(f, "zip"),
+        }
+    }
+}
+
+/// A file to put into an archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveEntry {
+    /// The file to put into the archive.
+    pub path: PathBuf,
+
+    /// The name of the file in the archive.
+    pub name: String,
+
+    /// Whether the file should be executable.
+    pub executable: bool,
+}
+
+/// Gets the conventional name of the release archive (without an extension) for an app, i.e.
+/// `mytool-v1.2.3-linux-x64`. `arch` is `None` for universal macOS binaries.
+pub fn archive_stem(name: &str, version: Option<&str>, os: Os, arch: Option<Arch>) -> String {
+    let arch = arch.map_or("universal".to_string(), |arch| arch.to_string());
+
+    match version {
+        Some(version) => format!(
+            "{}-v{}-{}-{}",
+            name,
+            version.trim_start_matches('v'),
+            os,
+            arch
+        ),
+        None => format!("{}-{}-{}", name, os, arch),
+    }
+}
+
+/// Finds the READMEs and licenses in the root of a project, sorted by name.
+pub fn extra_files(project_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = vec![];
+
+    for entry in fs::read_dir(project_dir).context("Error reading the project directory")? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_lowercase();
+
+        if entry.file_type()?.is_file()
+            && EXTRA_FILE_PREFIXES
+                .iter()
+                .any(|prefix| name.starts_with(prefix))
+        {
+            files.push(entry.path());
+        }
+    }
+
+    files.sort();
+
+    Ok(files)
+}
+
+/// Creates an archive at `archive_path` with every entry inside a top-level directory named `root`. If `mtime` is
+/// set, every entry gets that modification time (as a unix timestamp), so the archive is reproducible.
+pub fn create_archive(
+    archive_path: &Path,
+    format: ArchiveFormat,
+    root: &str,
+    entries: &[ArchiveEntry],
+    mtime: Option<i64>,
+) -> Result<()> {
+    let file = File::create(archive_path)
+        .context(format!("Error creating archive {}", archive_path.display()))?;
+
+    match format {
+        ArchiveFormat::TarGz => write_tar_gz(file, root, entries, mtime),
+        ArchiveFormat::Zip => write_zip(file, root, entries, mtime),
+    }
+    .context(format!("Error writing archive {}", archive_path.display()))
+}
+
+/// Writes the entries to a gzipped tarball.
+fn write_tar_gz(
+    file: File,
+    root: &str,
+    entries: &[ArchiveEntry],
+    mtime: Option<i64>,
+) -> Result<()> {
+    let mut tar = tar::Builder::new(GzEncoder::new(file, Compression::best()));
+
+    for entry in entries {
+        let metadata = fs::metadata(&entry.path)?;
+
+        // Build the header by hand, so that nothing about the machine it was built on (owners, etc.) ends up in it.
+        let mut header = tar::Header::new_gnu();
+        header.set_size(metadata.len());
+        header.set_mode(if entry.executable { 0o755 } else { 0o644 });
+        header.set_mtime(match mtime {
+            Some(mtime) => mtime.max(0) as u64,
+            None => metadata
+                .modified()?
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+        });
+        header.set_cksum();
+
+        tar.append_data(
+            &mut header,
+            format!("{}/{}", root, entry.name),
+            File::open(&entry.path)?,
+        )?;
+    }
+
+    tar.into_inner()?.finish()?.flush()?;
+
+    Ok(())
+}
+
+/// Writes the entries to a zip file.
+fn write_zip(file: File, root: &str, entries: &[ArchiveEntry], mtime: Option<i64>) -> Result<()> {
+    let mut zip = ZipWriter::new(file);
+
+    let mut options =
+        SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    if let Some(mtime) = mtime {
+        let mtime = time::OffsetDateTime::from_unix_timestamp(mtime)
+            .context("Invalid archive timestamp")?;
+
+        options = options.last_modified_time(
+            zip::DateTime::try_from(mtime)
+                .context("Archive timestamps must be between 1980 and 2107")?,
+        );
+    }
+
+    for entry in entries {
+        zip.start_file(
+            format!("{}/{}", root, entry.name),
+            options.unix_permissions(if entry.executable { 0o755 } else { 0o644 }),
+        )?;
+
+        io::copy(&mut File::open(&entry.path)?, &mut zip)?;
+    }
+
+    zip.finish()?;
+
+    Ok(())
+}
@@ -0,0 +1,144 @@
+//! Audits the tooling in the cache: where every cached Node.js and ESBuild binary came from, its checksum, when it was
+//! downloaded, and whether the cached versions of Node.js have published vulnerabilities.
+
+use super::esbuild::esbuild_archive_url;
+use super::helpers::calculate_checksum;
+use super::node_manager::node_archive_url;
+use super::node_manager::security::Vulnerability;
+use super::platforms::{get_host_arch, get_host_os};
+use super::Builder;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// A cached tool, and where it came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuditedBinary {
+    /// The name of the tool, `node` or `esbuild`.
+    pub tool: String,
+
+    /// The version of the tool.
+    pub version: String,
+
+    /// The platform the binary is for, i.e. `linux-x64`.
+    pub target: String,
+
+    /// The URL the binary was downloaded from.
+    pub origin: String,
+
+    /// The SHA256 checksum of the download at `origin`, as its publisher published it. Only Node.js binaries cached by
+    /// this version of jundler or later have it, as the cache only keeps the binaries themselves.
+    pub origin_sha256: Option<String>,
+
+    /// The SHA256 checksum of the binary in the cache.
+    pub cached_sha256: String,
+
+    /// The path to the binary in the cache.
+    pub path: PathBuf,
+
+    /// When the binary was downloaded, as a Unix timestamp, if the file system knows.
+    pub downloaded: Option<i64>,
+
+    /// The published vulnerabilities that affect this version of the tool.
+    pub vulnerabilities: Vec<AuditedVulnerability>,
+}
+
+/// A published vulnerability in a cached tool.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuditedVulnerability {
+    /// The CVEs the vulnerability was assigned.
+    pub cves: Vec<String>,
+
+    /// The versions that fixed it.
+    pub patched: Option<String>,
+
+    /// A short description of the vulnerability.
+    pub overview: Option<String>,
+
+    /// Where to read more about the vulnerability.
+    pub reference: Option<String>,
+}
+
+impl AuditedBinary {
+    /// Whether the binary has published vulnerabilities.
+    pub fn vulnerable(&self) -> bool {
+        !self.vulnerabilities.is_empty()
+    }
+}
+
+impl From<&Vulnerability> for AuditedVulnerability {
+    fn from(vulnerability: &Vulnerability) -> Self {
+        Self {
+            cves: vulnerability.cves.clone(),
+            patched: vulnerability.patched.clone(),
+            overview: vulnerability.overview.clone(),
+            reference: vulnerability.reference.clone(),
+        }
+    }
+}
+
+impl Builder {
+    /// Lists every Node.js and ESBuild binary in the cache with where it came from, and checks the versions of Node.js
+    /// against `vulnerabilities` (see [`download_vulnerabilities`](super::node_manager::security::download_vulnerabilities)).
+    pub fn audit(&self, vulnerabilities: &[Vulnerability]) -> Result<Vec<AuditedBinary>> {
+        let node_manager = self.node_manager();
+
+        let mut binaries = node_manager
+            .cached_binaries()
+            .into_iter()
+            .map(|(version, os, arch, path)| {
+                Ok(AuditedBinary {
+                    tool: "node".to_string(),
+                    version: version.to_string(),
+                    target: format!("{}-{}", os, arch),
+                    origin: node_archive_url(&version, os, arch),
+                    origin_sha256: node_manager
+                        .archive_digest(&version, os, arch)
+                        .map(hex::encode),
+                    cached_sha256: checksum(&path)?,
+                    downloaded: downloaded(&path),
+                    vulnerabilities: vulnerabilities
+                        .iter()
+                        .filter(|vulnerability| vulnerability.affects(&version))
+                        .map(AuditedVulnerability::from)
+                        .collect(),
+                    path,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // ESBuild is repacked when it's cached, so only the binary's checksum is known
+        if let Some((version, path)) = self.esbuild.cached_binary() {
+            binaries.push(AuditedBinary {
+                tool: "esbuild".to_string(),
+                version: version.to_string(),
+                target: format!("{}-{}", get_host_os(), get_host_arch()),
+                origin: esbuild_archive_url(&version),
+                origin_sha256: None,
+                cached_sha256: checksum(&path)?,
+                downloaded: downloaded(&path),
+                vulnerabilities: vec![],
+                path,
+            });
+        }
+
+        Ok(binaries)
+    }
+}
+
+/// Gets the SHA256 checksum of a cached binary, in hex.
+fn checksum(path: &Path) -> Result<String> {
+    Ok(hex::encode(calculate_checksum(path).context(format!(
+        "Error calculating checksum of {}",
+        path.display()
+    ))?))
+}
+
+/// Gets when a cached binary was downloaded, as a Unix timestamp: when it was written, as it's never written again.
+fn downloaded(path: &Path) -> Option<i64> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_secs() as i64)
+}
@@ -0,0 +1,140 @@
+//! Reads which platform an executable is for from its header: ELF for Linux, PE for Windows, and Mach-O (thin or
+//! universal) for macOS. Only as much of the header as that takes is read, so this is cheap enough to run on every
+//! Node.js binary that's unpacked, and on every binary that's built.
+
+use super::platforms::{Arch, Os};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// How much of a binary is read to find its platform. Headers (and the PE header's offset) are well within it.
+const HEADER_LEN: u64 = 4096;
+
+/// The platform an executable is for, as its header says.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinaryPlatform {
+    /// The OS the executable's format is for.
+    pub os: Os,
+
+    /// The architectures it has code for. Only universal macOS binaries have more than one.
+    pub arches: Vec<Arch>,
+}
+
+impl BinaryPlatform {
+    /// Whether the executable runs on `os` and `arch`.
+    pub fn matches(&self, os: Os, arch: Arch) -> bool {
+        self.os == os && self.arches.contains(&arch)
+    }
+}
+
+impl fmt::Display for BinaryPlatform {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}-{}",
+            self.os,
+            self.arches
+                .iter()
+                .map(|arch| arch.to_string())
+                .collect::<Vec<_>>()
+                .join("+")
+        )
+    }
+}
+
+/// Reads the platform of the executable at `path`. `None` if it isn't an executable in a format jundler builds, or is
+/// for an architecture it doesn't build for.
+pub fn read_platform(path: &Path) -> io::Result<Option<BinaryPlatform>> {
+    let mut header = vec![];
+
+    File::open(path)?
+        .take(HEADER_LEN)
+        .read_to_end(&mut header)?;
+
+    Ok(parse_platform(&header))
+}
+
+/// Parses the platform of an executable from the start of it. See `read_platform`.
+pub fn parse_platform(header: &[u8]) -> Option<BinaryPlatform> {
+    let u16_le = |offset: usize| {
+        Some(u16::from_le_bytes(
+            header.get(offset..offset + 2)?.try_into().ok()?,
+        ))
+    };
+    let u32_le = |offset: usize| {
+        Some(u32::from_le_bytes(
+            header.get(offset..offset + 4)?.try_into().ok()?,
+        ))
+    };
+    let u32_be = |offset: usize| {
+        Some(u32::from_be_bytes(
+            header.get(offset..offset + 4)?.try_into().ok()?,
+        ))
+    };
+
+    let (os, arches) = match header.get(..4)? {
+        // ELF, with `e_machine` in the byte order `EI_DATA` says
+        [0x7f, b'E', b'L', b'F'] => {
+            let machine = match header.get(5)? {
+                2 => u16::from_be_bytes(header.get(18..20)?.try_into().ok()?),
+                _ => u16_le(18)?,
+            };
+
+            let arch = match machine {
+                0x3e => Arch::X64,
+                0x03 => Arch::X86,
+                0xb7 => Arch::Arm64,
+                _ => return None,
+            };
+
+            (Os::Linux, vec![arch])
+        }
+
+        // PE, whose header is wherever the DOS header's `e_lfanew` says
+        [b'M', b'Z', ..] => {
+            let pe = u32_le(0x3c)? as usize;
+
+            if header.get(pe..pe + 4)? != b"PE\0\0" {
+                return None;
+            }
+
+            let arch = match u16_le(pe + 4)? {
+                0x8664 => Arch::X64,
+                0x014c => Arch::X86,
+                0xaa64 => Arch::Arm64,
+                _ => return None,
+            };
+
+            (Os::Windows, vec![arch])
+        }
+
+        // Thin Mach-O, 32 or 64-bit, in little-endian
+        [0xce | 0xcf, 0xfa, 0xed, 0xfe] => (Os::MacOS, vec![mach_o_arch(u32_le(4)?)?]),
+
+        // Universal Mach-O, with a big-endian list of its slices
+        [0xca, 0xfe, 0xba, 0xbe] => {
+            let slices = u32_be(4)? as usize;
+
+            let arches = (0..slices)
+                .map(|slice| mach_o_arch(u32_be(8 + slice * 20)?))
+                .collect::<Option<Vec<_>>>()?;
+
+            (Os::MacOS, arches)
+        }
+
+        _ => return None,
+    };
+
+    Some(BinaryPlatform { os, arches })
+}
+
+/// Gets the architecture of a Mach-O CPU type.
+fn mach_o_arch(cpu_type: u32) -> Option<Arch> {
+    match cpu_type {
+        0x0100_0007 => Some(Arch::X64),
+        0x0000_0007 => Some(Arch::X86),
+        0x0100_000c => Some(Arch::Arm64),
+        _ => None,
+    }
+}
@@ -0,0 +1,214 @@
+use super::copy::walk_project;
+use super::helpers::calculate_checksum;
+use super::BuildOptions;
+use anyhow::{Context, Result};
+use log::debug;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The outputs of the platform-independent part of a build, which can be reused between builds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildArtifacts {
+    /// The generated SEA blob.
+    pub sea_blob: PathBuf,
+
+    /// The third-party notices for the dependencies included in the blob, if they were collected.
+    pub notices: Option<PathBuf>,
+
+    /// What ESBuild put in the bundle, if the project was bundled with it.
+    pub bundle: Option<PathBuf>,
+}
+
+/// A cache of SEA blobs from previous successful builds. Each project + target pair has a single "slot" holding the
+/// blob of its last successful build, along with a hash of every input that went into it. If the inputs haven't
+/// changed, the blob can be reused as-is, skipping the install, bundling, and blob generation steps entirely.
+pub struct BuildCache {
+    /// The directory where cached builds are stored.
+    cache_dir: PathBuf,
+}
+
+impl BuildCache {
+    /// Creates a new build cache. Expects that `cache_dir` exists and is writable.
+    pub fn new(cache_dir: PathBuf) -> Self {
+        Self { cache_dir }
+    }
+
+    /// Hashes all the inputs of a build: every project file that would be copied (which includes the lockfile and
+    /// `sea-config.json`), the build options, and the version of jundler itself.
+    pub fn inputs_hash(
+        project_dir: &Path,
+        extra_excludes: &[String],
+        options: &BuildOptions,
+    ) -> Result<String> {
+        let mut hasher = Sha256::new();
+
+        hasher.update(env!("CARGO_PKG_VERSION"));
+        hasher.update(options.cache_key());
+
+        for entry in walk_project(project_dir, extra_excludes)? {
+            // Paths are separated by a NUL byte so that they can't run into the checksums.
+            hasher.update(entry.path.to_string_lossy().as_bytes());
+            hasher.update([0]);
+
+            let path = project_dir.join(&entry.path);
+
+            // Links are copied as links (which may dangle), so they're hashed by where they point, along with the
+            // file they point to, if there is one
+            if entry.is_symlink {
+                let target = fs::read_link(&path)
+                    .context(format!("Error reading the link {}", path.display()))?;
+
+                hasher.update(target.to_string_lossy().as_bytes());
+                hasher.update([0]);
+            }
+
+            if !entry.is_dir && path.is_file() {
+                hasher.update(
+                    calculate_checksum(&path)
+                        .context(format!("Error calculating checksum of {}", path.display()))?,
+                );
+            }
+        }
+
+        Ok(hex::encode(hasher.finalize()))
+    }
+
+    /// Gets the cached artifacts for the project + target, if the build inputs haven't changed since they were cached.
+    pub fn get(
+        &self,
+        project_dir: &Path,
+        options: &BuildOptions,
+        inputs_hash: &str,
+    ) -> Option<BuildArtifacts> {
+        let slot = self.slot_paths(project_dir, options);
+
+        let cached_hash = fs::read_to_string(&slot.hash).ok()?;
+
+        if cached_hash.trim() == inputs_hash && slot.artifacts.sea_blob.exists() {
+            debug!("Build cache hit for {}", project_dir.display());
+
+            Some(BuildArtifacts {
+                notices: slot.artifacts.notices.filter(|notices| notices.exists()),
+                bundle: slot.artifacts.bundle.filter(|bundle| bundle.exists()),
+                ..slot.artifacts
+            })
+        } else {
+            debug!("Build cache miss for {}", project_dir.display());
+            None
+        }
+    }
+
+    /// Stores freshly built artifacts for the project + target, replacing whatever was there before. Returns the
+    /// cached artifacts.
+    pub fn insert(
+        &self,
+        project_dir: &Path,
+        options: &BuildOptions,
+        inputs_hash: &str,
+        artifacts: &BuildArtifacts,
+    ) -> Result<BuildArtifacts> {
+        let slot = self.slot_paths(project_dir, options);
+
+        // Remove the old hash first, so that a failure halfway through can't leave a stale hash next to a new blob.
+        if slot.hash.exists() {
+            fs::remove_file(&slot.hash).context("Error removing stale build cache entry")?;
+        }
+
+        fs::copy(&artifacts.sea_blob, &slot.artifacts.sea_blob).context(format!(
+            "Error copying SEA blob to build cache at {}",
+            slot.artifacts.sea_blob.display()
+        ))?;
+
+        let cached_notices = slot
+            .artifacts
+            .notices
+            .expect("Slots always have a notices path!");
+        let cached_bundle = slot
+            .artifacts
+            .bundle
+            .expect("Slots always have a bundle contents path!");
+
+        store_optional(
+            artifacts.notices.as_deref(),
+            &cached_notices,
+            "third-party notices",
+        )?;
+        store_optional(
+            artifacts.bundle.as_deref(),
+            &cached_bundle,
+            "bundle contents",
+        )?;
+
+        fs::write(&slot.hash, inputs_hash).context(format!(
+            "Error writing build cache entry at {}",
+            slot.hash.display()
+        ))?;
+
+        Ok(BuildArtifacts {
+            sea_blob: slot.artifacts.sea_blob,
+            notices: artifacts.notices.as_ref().map(|_| cached_notices),
+            bundle: artifacts.bundle.as_ref().map(|_| cached_bundle),
+        })
+    }
+
+    /// Removes every cached build.
+    pub fn clean_cache(&self) -> Result<()> {
+        fs::remove_dir_all(&self.cache_dir).context("Error deleting build cache directory")?;
+        fs::create_dir_all(&self.cache_dir).context("Error recreating build cache directory")?;
+
+        Ok(())
+    }
+
+    /// Gets the paths to the cached artifacts and inputs hash for a project + target.
+    fn slot_paths(&self, project_dir: &Path, options: &BuildOptions) -> SlotPaths {
+        let mut hasher = Sha256::new();
+
+        hasher.update(project_dir.to_string_lossy().as_bytes());
+        hasher.update([0]);
+        hasher.update(format!("{}-{}", options.target_os, options.target_arch));
+
+        let slot = hex::encode(hasher.finalize());
+
+        SlotPaths {
+            artifacts: BuildArtifacts {
+                sea_blob: self.cache_dir.join(format!("{}.blob", slot)),
+                notices: Some(self.cache_dir.join(format!("{}.notices", slot))),
+                bundle: Some(self.cache_dir.join(format!("{}.bundle", slot))),
+            },
+            hash: self.cache_dir.join(format!("{}.sha256", slot)),
+        }
+    }
+}
+
+/// The paths of a single slot in the build cache.
+struct SlotPaths {
+    /// Where the artifacts are stored.
+    artifacts: BuildArtifacts,
+
+    /// Where the hash of the inputs the artifacts were built from is stored.
+    hash: PathBuf,
+}
+
+/// Copies an artifact that isn't always built to its place in a slot, or removes the one left there by a previous build
+/// if it wasn't built this time. `what` names the artifact in errors.
+fn store_optional(artifact: Option<&Path>, cached: &Path, what: &str) -> Result<()> {
+    match artifact {
+        Some(artifact) => {
+            fs::copy(artifact, cached).context(format!(
+                "Error copying {} to build cache at {}",
+                what,
+                cached.display()
+            ))?;
+        }
+
+        None if cached.exists() => {
+            fs::remove_file(cached)
+                .context(format!("Error removing stale {} from build cache", what))?;
+        }
+
+        None => {}
+    }
+
+    Ok(())
+}
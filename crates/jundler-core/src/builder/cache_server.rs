@@ -0,0 +1,287 @@
+//! A cache server for a team, so that an office or a fleet of CI machines only downloads each Node.js archive, ESBuild
+//! package, and runtime from the internet once. It serves files from [`Storage`] at `/{key}`, like the server an
+//! [`HttpStorage`](super::storage::HttpStorage) reads from, and downloads anything it doesn't have yet from where it
+//! comes from (which has to start with one of [`UPSTREAM_PREFIXES`]), storing it for next time. Builds use it with
+//! `--shared-cache http://<server>:<port>`.
+//!
+//! Only `GET` is supported: clients can't store files on the server, so one of them can't hand everyone else a
+//! tampered Node.js. Nor can they tie it up: requests have to arrive in time and fit in [`MAX_REQUEST_SIZE`], and only
+//! [`MAX_CONNECTIONS`] are handled at once.
+
+use super::downloader::Downloader;
+use super::storage::Storage;
+use crate::ui::NoopReporter;
+use log::{info, warn};
+use reqwest::Url;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// What the server downloads from when it doesn't have a file yet: exactly what jundler downloads, so that it can't be
+/// used to download anything else.
+pub const UPSTREAM_PREFIXES: &[&str] = &[
+    "nodejs.org/dist/",
+    "registry.npmjs.org/@esbuild/",
+    "github.com/oven-sh/bun/releases/download/",
+    "github.com/denoland/deno/releases/download/",
+    "raw.githubusercontent.com/nodejs/security-wg/main/vuln/core/index.json",
+];
+
+/// Where `key` is downloaded from, if it's somewhere in [`UPSTREAM_PREFIXES`]. The URL it parses to has to name the
+/// same file as the key does, so that nothing in it can take the download somewhere else.
+fn upstream_url(key: &str) -> Option<Url> {
+    let url = Url::parse(&format!("https://{}", key)).ok()?;
+    let normalised = format!("{}{}", url.host_str()?, url.path());
+
+    (url.port().is_none()
+        && url.username().is_empty()
+        && url.password().is_none()
+        && url.query().is_none()
+        && url.fragment().is_none()
+        && normalised == key
+        && UPSTREAM_PREFIXES
+            .iter()
+            .any(|prefix| normalised.starts_with(prefix)))
+    .then_some(url)
+}
+
+/// The most a request's line and headers can add up to, in bytes.
+pub const MAX_REQUEST_SIZE: u64 = 8 * 1024;
+
+/// The most connections that are handled at once. Any more wait until one of them is done.
+pub const MAX_CONNECTIONS: usize = 64;
+
+/// How long a client has to send its request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long each write of the response can take before the client is given up on.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Serves storage over HTTP, downloading what it doesn't have from upstream.
+#[derive(Debug, Clone)]
+pub struct CacheServer {
+    /// Where files are served from, and stored once they're downloaded.
+    storage: Arc<dyn Storage>,
+
+    /// What downloads files that aren't in storage yet.
+    upstream: Arc<dyn Downloader>,
+}
+
+/// A response to a request, with its status code and body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    /// The HTTP status code.
+    pub status: u16,
+
+    /// The body of the response.
+    pub body: Vec<u8>,
+}
+
+impl CacheServer {
+    /// Creates a server for `storage`, that downloads what isn't in it with `upstream`.
+    pub fn new(storage: Arc<dyn Storage>, upstream: Arc<dyn Downloader>) -> Self {
+        Self { storage, upstream }
+    }
+
+    /// Serves requests on `listener` until it fails, each on its own thread, with at most [`MAX_CONNECTIONS`] at once.
+    pub fn serve(&self, listener: TcpListener) -> io::Result<()> {
+        let connections = Arc::new((Mutex::new(0), Condvar::new()));
+
+        for stream in listener.incoming() {
+            let stream = stream?;
+            let server = self.clone();
+            let connection = Connection::open(&connections);
+
+            thread::spawn(move || {
+                if let Err(err) = server.handle(stream) {
+                    warn!("Could not respond to a request: {}", err);
+                }
+
+                drop(connection);
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Reads a request from `stream`, and writes the response to it. Connections are closed after every response.
+    fn handle(&self, mut stream: TcpStream) -> io::Result<()> {
+        stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+
+        let mut reader = BufReader::new(
+            DeadlineReader {
+                stream: &stream,
+                deadline: Instant::now() + REQUEST_TIMEOUT,
+            }
+            .take(MAX_REQUEST_SIZE),
+        );
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+
+        // Nothing the server responds to has a body, so the headers are skipped, up to the blank line that ends them.
+        // If there isn't one, the request was cut off at `MAX_REQUEST_SIZE`.
+        let mut header = String::new();
+        let mut complete = false;
+
+        while reader.read_line(&mut header)? > 0 {
+            if header.trim_end_matches(['\r', '\n']).is_empty() {
+                complete = true;
+                break;
+            }
+
+            header.clear();
+        }
+
+        let mut parts = request_line.split_whitespace();
+        let (method, path) = (
+            parts.next().unwrap_or_default(),
+            parts.next().unwrap_or("/"),
+        );
+
+        let response = if complete {
+            self.respond(method, path)
+        } else {
+            Response {
+                status: 431,
+                body: format!("Requests can't be over {} bytes\n", MAX_REQUEST_SIZE).into_bytes(),
+            }
+        };
+
+        info!("{} {} {}", method, path, response.status);
+
+        write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Length: {}\r\nContent-Type: application/octet-stream\r\nConnection: close\r\n\r\n",
+            response.status,
+            reason(response.status),
+            response.body.len()
+        )?;
+        stream.write_all(&response.body)?;
+        stream.flush()
+    }
+
+    /// Responds to a request for `path`: the file stored at it (without the leading `/`), downloaded first if it
+    /// isn't stored yet.
+    pub fn respond(&self, method: &str, path: &str) -> Response {
+        let error = |status: u16, message: String| Response {
+            status,
+            body: (message + "\n").into_bytes(),
+        };
+
+        if method != "GET" {
+            return error(405, format!("{} isn't supported, only GET is", method));
+        }
+
+        let key = path.trim_start_matches('/');
+
+        // Keys are paths in storage, so they can't climb out of it
+        if key.is_empty()
+            || key
+                .split('/')
+                .any(|segment| segment.is_empty() || segment == "." || segment == "..")
+            || key.contains(['\\', '%', '?', '#'])
+        {
+            return error(400, format!("{} isn't a file that can be cached", path));
+        }
+
+        match self.storage.get(key) {
+            Ok(Some(data)) => {
+                return Response {
+                    status: 200,
+                    body: data,
+                }
+            }
+            Ok(None) => {}
+            Err(err) => warn!("Could not read {} from storage: {}", key, err),
+        }
+
+        let Some(url) = upstream_url(key) else {
+            return error(404, format!("{} isn't cached", key));
+        };
+
+        match self.upstream.download(url.as_str(), &NoopReporter) {
+            Ok(data) => {
+                if let Err(err) = self.storage.put(key, &data) {
+                    warn!("Could not store {}: {}", key, err);
+                }
+
+                Response {
+                    status: 200,
+                    body: data,
+                }
+            }
+            Err(err) => error(502, format!("Could not download {}: {}", key, err)),
+        }
+    }
+}
+
+/// Gets the reason phrase of a status code the server responds with.
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        431 => "Request Header Fields Too Large",
+        _ => "Bad Gateway",
+    }
+}
+
+/// One of the connections being handled, which frees its place for another once it's dropped (even if handling it
+/// panicked).
+struct Connection(Arc<(Mutex<usize>, Condvar)>);
+
+impl Connection {
+    /// Waits until fewer than [`MAX_CONNECTIONS`] are being handled, and takes a place among them.
+    fn open(connections: &Arc<(Mutex<usize>, Condvar)>) -> Self {
+        let (count, freed) = &**connections;
+        let mut count = freed
+            .wait_while(
+                count.lock().unwrap_or_else(|err| err.into_inner()),
+                |count| *count >= MAX_CONNECTIONS,
+            )
+            .unwrap_or_else(|err| err.into_inner());
+
+        *count += 1;
+
+        Self(Arc::clone(connections))
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        let (count, freed) = &*self.0;
+
+        *count.lock().unwrap_or_else(|err| err.into_inner()) -= 1;
+        freed.notify_one();
+    }
+}
+
+/// Reads from a stream until a deadline, after which reads time out, so that a client can't hold on to a connection
+/// by sending its request a byte at a time.
+struct DeadlineReader<'a> {
+    /// The stream to read from.
+    stream: &'a TcpStream,
+
+    /// When reading stops.
+    deadline: Instant,
+}
+
+impl Read for DeadlineReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+
+        if remaining.is_zero() {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "the request took too long to arrive",
+            ));
+        }
+
+        self.stream.set_read_timeout(Some(remaining))?;
+        self.stream.read(buf)
+    }
+}
@@ -0,0 +1,190 @@
+use super::Builder;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// What's in the cache, and how much space it takes up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheStats {
+    /// The directory everything is cached in.
+    pub dir: PathBuf,
+
+    /// The cached Node.js binaries.
+    pub node: Vec<CachedBinary>,
+
+    /// The cached ESBuild binaries.
+    pub esbuild: Vec<CachedBinary>,
+
+    /// The SEA blobs cached from previous builds.
+    pub builds: CacheSection,
+
+    /// The dependencies cached from previous installs.
+    pub deps: CacheSection,
+
+    /// The projects from elsewhere (single scripts and npm packages) that were built.
+    pub sources: CacheSection,
+
+    /// The size of everything in the cache, in bytes.
+    pub total_bytes: u64,
+}
+
+/// A binary in the cache.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedBinary {
+    /// What the binary is, i.e. `v20.11.1 linux-x64`.
+    pub name: String,
+
+    /// The size of the (compressed) binary, in bytes.
+    pub bytes: u64,
+
+    /// When the binary was last used (or downloaded), if the file system knows.
+    pub last_used: Option<SystemTime>,
+}
+
+/// A binary in the cache, checked against the checksum it was cached with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedBinary {
+    /// What the binary is, i.e. `Node.js v20.11.1 linux-x64`.
+    pub name: String,
+
+    /// Whether the binary still matches its checksum.
+    pub intact: bool,
+}
+
+/// A part of the cache that isn't binaries.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheSection {
+    /// How many things are cached.
+    pub entries: usize,
+
+    /// The size of everything cached, in bytes.
+    pub bytes: u64,
+
+    /// When anything in it was last used, if the file system knows.
+    pub last_used: Option<SystemTime>,
+}
+
+impl Builder {
+    /// Gets what's in the cache, and how much space it takes up.
+    pub fn cache_stats(&self) -> Result<CacheStats> {
+        let node = self
+            .node_manager()
+            .cached_binaries()
+            .into_iter()
+            .map(|(version, os, arch, path)| {
+                cached_binary(format!("v{} {}-{}", version, os, arch), &path)
+            })
+            .collect();
+
+        let esbuild = self
+            .esbuild
+            .cached_binary()
+            .into_iter()
+            .map(|(version, path)| cached_binary(format!("v{}", version), &path))
+            .collect();
+
+        // Each cached build is a blob, with its inputs hash and maybe its third-party notices next to it
+        let builds = CacheSection {
+            entries: fs::read_dir(self.cache_dir.join("builds"))
+                .context("Could not read the build cache!")?
+                .filter_map(Result::ok)
+                .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "blob"))
+                .count(),
+            ..cache_section(&self.cache_dir.join("builds"))
+        };
+
+        // Each project + target's cached dependencies are a directory, with the key they were installed with next to it
+        let deps = CacheSection {
+            entries: fs::read_dir(self.cache_dir.join("deps"))
+                .context("Could not read the dependency cache!")?
+                .filter_map(Result::ok)
+                .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "key"))
+                .count(),
+            ..cache_section(&self.cache_dir.join("deps"))
+        };
+
+        let sources = CacheSection {
+            entries: fs::read_dir(&self.sources_dir)
+                .context("Could not read the sources cache!")?
+                .count(),
+            ..cache_section(&self.sources_dir)
+        };
+
+        Ok(CacheStats {
+            dir: self.cache_dir.clone(),
+            node,
+            esbuild,
+            builds,
+            deps,
+            sources,
+            total_bytes: cache_section(&self.cache_dir).bytes,
+        })
+    }
+
+    /// Checks every Node.js and ESBuild binary in the cache against the checksum it was cached with, so corrupt ones
+    /// can be found and removed.
+    pub fn verify_cache(&self) -> Vec<VerifiedBinary> {
+        let node =
+            self.node_manager()
+                .verify_cache()
+                .into_iter()
+                .map(|(version, os, arch, intact)| VerifiedBinary {
+                    name: format!("Node.js v{} {}-{}", version, os, arch),
+                    intact,
+                });
+
+        let esbuild = self
+            .esbuild
+            .verify_cache()
+            .into_iter()
+            .map(|(version, intact)| VerifiedBinary {
+                name: format!("ESBuild v{}", version),
+                intact,
+            });
+
+        node.chain(esbuild).collect()
+    }
+}
+
+/// Gets the size and last use of a cached binary.
+fn cached_binary(name: String, path: &Path) -> CachedBinary {
+    let metadata = fs::metadata(path).ok();
+
+    CachedBinary {
+        name,
+        bytes: metadata.as_ref().map_or(0, |metadata| metadata.len()),
+        last_used: metadata.as_ref().and_then(last_used),
+    }
+}
+
+/// Gets the size of everything in a directory, and when anything in it was last used. Anything that can't be read is
+/// skipped.
+fn cache_section(dir: &Path) -> CacheSection {
+    let mut section = CacheSection::default();
+
+    for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+
+        let (bytes, used) = if metadata.is_dir() {
+            let inner = cache_section(&entry.path());
+
+            (inner.bytes, inner.last_used)
+        } else {
+            (metadata.len(), last_used(&metadata))
+        };
+
+        section.bytes += bytes;
+        section.last_used = section.last_used.max(used);
+    }
+
+    section
+}
+
+/// Gets when a file was last used: the later of when it was last read (if the file system keeps track of that), and
+/// when it was last written.
+fn last_used(metadata: &fs::Metadata) -> Option<SystemTime> {
+    metadata.accessed().ok().max(metadata.modified().ok())
+}
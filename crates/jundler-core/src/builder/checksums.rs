@@ -0,0 +1,157 @@
+use super::helpers::calculate_checksum;
+use anyhow::{anyhow, Context, Result};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The name of the file that lists the checksums of every binary in an output directory.
+pub const CHECKSUMS_FILE_NAME: &str = "SHA256SUMS";
+
+/// The format to write the checksums of built binaries in.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChecksumFormat {
+    /// A `SHA256SUMS` file in the format of GNU `sha256sum`, which can be checked with `sha256sum -c SHA256SUMS`.
+    /// This is what most GitHub Releases use.
+    #[default]
+    Gnu,
+
+    /// A `SHA256SUMS` file in the format of BSD `sha256`/`shasum --tag`.
+    Bsd,
+
+    /// A `<binary>.sha256` file next to every binary, which is what package managers like Homebrew and Scoop expect.
+    Sidecar,
+}
+
+impl fmt::Display for ChecksumFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChecksumFormat::Gnu => write!(f, "gnu"),
+            ChecksumFormat::Bsd => write!(f, "bsd"),
+            ChecksumFormat::Sidecar => write!(f, "sidecar"),
+        }
+    }
+}
+
+impl ChecksumFormat {
+    /// Formats a single line of a checksums file.
+    fn line(&self, name: &str, digest: &str) -> String {
+        match self {
+            ChecksumFormat::Gnu | ChecksumFormat::Sidecar => format!("{}  {}", digest, name),
+            ChecksumFormat::Bsd => format!("SHA256 ({}) = {}", name, digest),
+        }
+    }
+
+    /// Parses a single line of a checksums file in any format into the name and digest, if it's valid.
+    fn parse_any_line(line: &str) -> Option<(&str, &str)> {
+        [ChecksumFormat::Gnu, ChecksumFormat::Bsd]
+            .iter()
+            .find_map(|format| format.parse_line(line))
+    }
+
+    /// Parses a single line of a checksums file into the name and digest, if it's valid.
+    fn parse_line<'a>(&self, line: &'a str) -> Option<(&'a str, &'a str)> {
+        match self {
+            ChecksumFormat::Gnu | ChecksumFormat::Sidecar => {
+                let (digest, name) = line.split_once("  ")?;
+                // A leading `*` marks binary mode in GNU `sha256sum`
+                Some((name.strip_prefix('*').unwrap_or(name), digest))
+            }
+            ChecksumFormat::Bsd => {
+                let (name, digest) = line.strip_prefix("SHA256 (")?.split_once(") = ")?;
+                Some((name, digest))
+            }
+        }
+    }
+}
+
+/// Writes the checksums of the built binaries in `outputs` in the given format. For `SHA256SUMS` files, any entries
+/// for other binaries already in the file are kept (even if it was written in the other format), so that separate
+/// builds into the same directory add up to a single list. Returns the paths to the written checksum files.
+pub fn write_checksums(outputs: &[PathBuf], format: ChecksumFormat) -> Result<Vec<PathBuf>> {
+    let mut written = vec![];
+
+    if format == ChecksumFormat::Sidecar {
+        for output in outputs {
+            let (checksum_name, contents) = checksums_for(output, format)?;
+            let checksum_path = output.with_file_name(checksum_name);
+
+            fs::write(&checksum_path, contents).context(format!(
+                "Error writing checksum file {}",
+                checksum_path.display()
+            ))?;
+
+            written.push(checksum_path);
+        }
+
+        return Ok(written);
+    }
+
+    // Group the outputs by directory, as each directory gets its own `SHA256SUMS`.
+    let mut by_dir: BTreeMap<&Path, Vec<&PathBuf>> = BTreeMap::new();
+
+    for output in outputs {
+        let dir = output
+            .parent()
+            .ok_or_else(|| anyhow!("{} is not in a directory!", output.display()))?;
+
+        by_dir.entry(dir).or_default().push(output);
+    }
+
+    for (dir, outputs) in by_dir {
+        let checksum_path = dir.join(CHECKSUMS_FILE_NAME);
+
+        // Sorted by name, so the file is the same no matter what order the binaries were built in.
+        let mut entries: BTreeMap<String, String> = BTreeMap::new();
+
+        if let Ok(existing) = fs::read_to_string(&checksum_path) {
+            for (name, digest) in existing.lines().filter_map(ChecksumFormat::parse_any_line) {
+                entries.insert(name.to_string(), digest.to_string());
+            }
+        }
+
+        for output in outputs {
+            entries.insert(file_name(output)?.to_string(), digest(output)?);
+        }
+
+        let contents: String = entries
+            .iter()
+            .map(|(name, digest)| format.line(name, digest) + "\n")
+            .collect();
+
+        fs::write(&checksum_path, contents).context(format!(
+            "Error writing checksum file {}",
+            checksum_path.display()
+        ))?;
+
+        written.push(checksum_path);
+    }
+
+    Ok(written)
+}
+
+/// Gets the name and contents of the checksum file for a single built binary in the given format, without writing it.
+pub fn checksums_for(output: &Path, format: ChecksumFormat) -> Result<(String, String)> {
+    let name = file_name(output)?;
+    let line = format.line(name, &digest(output)?) + "\n";
+
+    match format {
+        ChecksumFormat::Sidecar => Ok((format!("{}.sha256", name), line)),
+        ChecksumFormat::Gnu | ChecksumFormat::Bsd => Ok((CHECKSUMS_FILE_NAME.to_string(), line)),
+    }
+}
+
+/// Calculates the hex-encoded SHA256 digest of a built binary.
+fn digest(output: &Path) -> Result<String> {
+    Ok(hex::encode(calculate_checksum(output).context(format!(
+        "Error calculating checksum of {}",
+        output.display()
+    ))?))
+}
+
+/// Gets the file name of a built binary as a string.
+fn file_name(path: &Path) -> Result<&str> {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| anyhow!("Invalid binary name {}", path.display()))
+}
@@ -0,0 +1,150 @@
+use super::checksums::CHECKSUMS_FILE_NAME;
+use super::helpers::ESBUILD_METAFILE;
+use super::licenses::NOTICES_FILE_NAME;
+use super::{get_configs, output_name, Builder};
+use crate::js_config::PackageConfig;
+use crate::ui::messages::{CLEAN_DEPS_MSG, CLEAN_ESBUILD_MSG, CLEAN_NODE_MSG, CLEAN_PROJECT_MSG};
+use anyhow::{Context, Result};
+use ignore::overrides::OverrideBuilder;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// What to clean, instead of the whole cache.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CleanScope {
+    /// The Node.js binaries in the cache.
+    Node,
+
+    /// ESBuild in the cache.
+    Esbuild,
+
+    /// The dependencies cached from previous installs.
+    Deps,
+
+    /// The build outputs in the project.
+    Project,
+}
+
+impl Builder {
+    /// Removes every Node.js binary from the cache.
+    pub fn clean_node_cache(&mut self) -> Result<()> {
+        let spinner = self.tracker.spawn_spinner(CLEAN_NODE_MSG, 0);
+
+        let cleaned = self
+            .node_manager()
+            .clean_cache()
+            .context("Could not clean the Node.js cache!");
+
+        spinner.finish(cleaned)
+    }
+
+    /// Removes ESBuild from the cache.
+    pub fn clean_esbuild_cache(&mut self) -> Result<()> {
+        let spinner = self.tracker.spawn_spinner(CLEAN_ESBUILD_MSG, 0);
+
+        let cleaned = self
+            .esbuild
+            .clean_cache()
+            .context("Could not clean the ESBuild cache!");
+
+        spinner.finish(cleaned)
+    }
+
+    /// Removes every cached `node_modules` from the cache.
+    pub fn clean_deps_cache(&mut self) -> Result<()> {
+        let spinner = self.tracker.spawn_spinner(CLEAN_DEPS_MSG, 0);
+
+        let cleaned = self
+            .deps_cache
+            .clean_cache()
+            .context("Could not clean the dependency cache!");
+
+        spinner.finish(cleaned)
+    }
+
+    /// Removes what builds leave in the project: built binaries, and their checksums, signatures, archives, packages,
+    /// and installers, along with any leftover bundle. Returns the removed files, with their sizes.
+    pub fn clean_project(&mut self, project_dir: &Path) -> Result<Vec<(PathBuf, u64)>> {
+        let spinner = self.tracker.spawn_spinner(CLEAN_PROJECT_MSG, 0);
+
+        let removed = get_configs(project_dir)
+            .and_then(|(_, package_config)| project_artifacts(project_dir, &package_config))
+            .and_then(|artifacts| {
+                artifacts
+                    .into_iter()
+                    .map(|path| {
+                        let size = fs::metadata(&path).map_or(0, |metadata| metadata.len());
+
+                        fs::remove_file(&path)
+                            .context(format!("Could not remove {}", path.display()))?;
+
+                        Ok((path, size))
+                    })
+                    .collect()
+            });
+
+        spinner.finish(removed)
+    }
+}
+
+/// Finds the files that builds leave in the project. Only files right in the project directory are matched, and
+/// manifests aren't, as `<name>.json` could just as well be one of the project's own files.
+pub fn project_artifacts(
+    project_dir: &Path,
+    package_config: &PackageConfig,
+) -> Result<Vec<PathBuf>> {
+    let name = &package_config.name;
+
+    let mut globs = vec![
+        name.clone(),
+        format!("{}.exe", name),
+        format!("{}*.sha256", name),
+        format!("{}*.sig", name),
+        format!("{}*.minisig", name),
+        format!("{}-*.tar.gz", name),
+        format!("{}-*.zip", name),
+        format!("{}*.deb", name),
+        format!("{}*.rpm", name),
+        format!("{}*.AppImage", name),
+        format!("{}*.msi", name),
+        format!("{}.rb", name),
+        CHECKSUMS_FILE_NAME.to_string(),
+        format!("{}.sig", CHECKSUMS_FILE_NAME),
+        format!("{}.minisig", CHECKSUMS_FILE_NAME),
+        NOTICES_FILE_NAME.to_string(),
+        format!("{}.sig", NOTICES_FILE_NAME),
+        format!("{}.minisig", NOTICES_FILE_NAME),
+        "bundled.js".to_string(),
+        ESBUILD_METAFILE.to_string(),
+    ];
+
+    if let Some(template) = &package_config.jundler.output_name {
+        globs.push(output_name::glob(template, package_config));
+    }
+
+    let mut overrides = OverrideBuilder::new(project_dir);
+
+    for glob in &globs {
+        overrides
+            .add(&format!("/{}", glob))
+            .context(format!("Invalid glob `{}`", glob))?;
+    }
+
+    let overrides = overrides.build()?;
+    let mut artifacts = vec![];
+
+    for entry in fs::read_dir(project_dir).context(format!(
+        "Could not read the project directory at {}",
+        project_dir.display()
+    ))? {
+        let path = entry?.path();
+
+        if path.is_file() && overrides.matched(&path, false).is_whitelist() {
+            artifacts.push(path);
+        }
+    }
+
+    artifacts.sort();
+
+    Ok(artifacts)
+}
@@ -0,0 +1,91 @@
+use super::platforms::Arch;
+use crate::js_config::PackageConfig;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+
+/// The image that container images are built on, unless the project names another one. Node.js needs glibc and
+/// libstdc++, which this has, but nothing else.
+pub const DEFAULT_BASE_IMAGE: &str = "gcr.io/distroless/cc-debian12";
+
+/// The name of the directory next to the binary that the container build context is written to, i.e.
+/// `mytool-docker`.
+pub fn context_dir_name(name: &str) -> String {
+    format!("{}-docker", name)
+}
+
+/// Gets the Docker platform of a Linux architecture.
+pub fn platform(arch: Arch) -> &'static str {
+    match arch {
+        Arch::X64 => "linux/amd64",
+        Arch::X86 => "linux/386",
+        Arch::Arm64 => "linux/arm64",
+    }
+}
+
+/// Generates a Dockerfile that copies the binary called `app_name` onto the base image, and runs it.
+pub fn dockerfile(package_config: &PackageConfig, app_name: &str, arch: Arch) -> String {
+    let base_image = package_config
+        .jundler
+        .docker
+        .base_image
+        .as_deref()
+        .unwrap_or(DEFAULT_BASE_IMAGE);
+
+    let mut labels = vec![("title", package_config.name.as_str())];
+
+    if let Some(version) = &package_config.version {
+        labels.push(("version", version));
+    }
+
+    if let Some(description) = &package_config.description {
+        labels.push(("description", description));
+    }
+
+    let labels = labels
+        .iter()
+        .map(|(key, value)| {
+            format!(
+                "org.opencontainers.image.{}=\"{}\"",
+                key,
+                value.replace('\\', "\\\\").replace('"', "\\\"")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" \\\n      ");
+
+    format!(
+        "# Generated by jundler. Build it with `docker build --platform {platform} .`\n\
+         FROM --platform={platform} {base_image}\n\
+         LABEL {labels}\n\
+         COPY {app_name} /{app_name}\n\
+         ENTRYPOINT [\"/{app_name}\"]\n",
+        platform = platform(arch),
+    )
+}
+
+/// Writes the build context of a container image for the built binary to `context_dir`: the binary, and a
+/// Dockerfile that runs it.
+pub fn write_context(
+    context_dir: &Path,
+    app_path: &Path,
+    package_config: &PackageConfig,
+    arch: Arch,
+) -> Result<()> {
+    let app_name = &package_config.name;
+
+    fs::create_dir_all(context_dir).context(format!(
+        "Error creating the container build context {}",
+        context_dir.display()
+    ))?;
+
+    // Copying keeps the binary's permissions, so it stays executable in the image
+    fs::copy(app_path, context_dir.join(app_name))
+        .context("Error copying the built binary into the container build context")?;
+
+    fs::write(
+        context_dir.join("Dockerfile"),
+        dockerfile(package_config, app_name, arch),
+    )
+    .context("Error writing the Dockerfile")
+}
@@ -0,0 +1,322 @@
+use anyhow::{Context, Result};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::WalkBuilder;
+use log::debug;
+use std::path::{Path, PathBuf};
+use std::{fmt, fs, io};
+
+/// The name of the ignore file (gitignore syntax) that controls which files are copied into the build directory.
+pub const IGNORE_FILE_NAME: &str = ".jundlerignore";
+
+/// Paths that are never worth copying into the build directory. `node_modules` is reinstalled anyways, and VCS
+/// directories are never needed for a build. These can be re-included with a `!` pattern in `.jundlerignore`.
+pub const DEFAULT_EXCLUDES: &[&str] = &[
+    "node_modules/",
+    ".git/",
+    ".hg/",
+    ".svn/",
+    ".DS_Store",
+    IGNORE_FILE_NAME,
+];
+
+/// Files that the build may write to after copying. These are always byte-copied, even with hardlinks, so that the
+/// build can never modify the original project through a shared inode.
+const MUTABLE_FILES: &[&str] = &[
+    "package.json",
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "sea-config.json",
+];
+
+/// Everything that installing a project's dependencies reads, besides the project itself: its manifest, lockfiles,
+/// package manager configuration, and patches. Bundled projects only copy these, as their sources are read in place.
+const INSTALL_FILES: &[&str] = &[
+    "package.json",
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "bun.lockb",
+    "bun.lock",
+    ".npmrc",
+    ".yarnrc",
+    ".yarnrc.yml",
+    ".yarn",
+    ".pnpmfile.cjs",
+    "bunfig.toml",
+    "patches",
+];
+
+/// How files are copied from the original project into the build directory.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CopyStrategy {
+    /// Use copy-on-write reflinks where the filesystem supports them, and fall back to byte copies otherwise.
+    #[default]
+    Auto,
+
+    /// Only use copy-on-write reflinks, and fail if the filesystem doesn't support them.
+    Reflink,
+
+    /// Hardlink files into the build directory, falling back to byte copies across filesystems. The build never
+    /// writes to hardlinked files, but the project must not be edited while the build is running.
+    Hardlink,
+
+    /// Always do a full byte copy.
+    Copy,
+}
+
+impl fmt::Display for CopyStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CopyStrategy::Auto => write!(f, "auto"),
+            CopyStrategy::Reflink => write!(f, "reflink"),
+            CopyStrategy::Hardlink => write!(f, "hardlink"),
+            CopyStrategy::Copy => write!(f, "copy"),
+        }
+    }
+}
+
+impl CopyStrategy {
+    /// Copies a single file from `src` to `dest` using this strategy.
+    fn copy_file(&self, src: &Path, dest: &Path) -> Result<(), io::Error> {
+        match self {
+            CopyStrategy::Auto => reflink_copy::reflink_or_copy(src, dest).map(|_| ()),
+            CopyStrategy::Reflink => reflink_copy::reflink(src, dest),
+            CopyStrategy::Hardlink => {
+                let is_mutable = src
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| MUTABLE_FILES.contains(&name));
+
+                if is_mutable {
+                    return fs::copy(src, dest).map(|_| ());
+                }
+
+                // Hardlinks can't cross filesystems, so fall back to a plain copy if linking fails.
+                fs::hard_link(src, dest).or_else(|_| fs::copy(src, dest).map(|_| ()))
+            }
+            CopyStrategy::Copy => fs::copy(src, dest).map(|_| ()),
+        }
+    }
+}
+
+/// Copies the project at `src` into `dest`, skipping anything matched by the default excludes, `extra_excludes`, or
+/// the project's `.jundlerignore` file. Expects that `dest` already exists.
+pub fn copy_project(
+    src: &Path,
+    dest: &Path,
+    extra_excludes: &[String],
+    strategy: CopyStrategy,
+) -> Result<()> {
+    copy_entries(src, dest, walk_project(src, extra_excludes)?, strategy)
+}
+
+/// Copies only what installing the dependencies of the project at `src` needs into `dest` (see `INSTALL_FILES`),
+/// skipping anything excluded like `copy_project` does. Expects that `dest` already exists.
+pub fn copy_install_files(
+    src: &Path,
+    dest: &Path,
+    extra_excludes: &[String],
+    strategy: CopyStrategy,
+) -> Result<()> {
+    let entries =
+        walk_project(src, extra_excludes)?
+            .into_iter()
+            .filter(|entry| {
+                entry.path.components().next().is_some_and(|first| {
+                    INSTALL_FILES.iter().any(|name| first.as_os_str() == *name)
+                })
+            })
+            .collect();
+
+    copy_entries(src, dest, entries, strategy)
+}
+
+/// Copies `entries` of the project at `src` into `dest`. Parent directories have to come before their contents.
+fn copy_entries(
+    src: &Path,
+    dest: &Path,
+    entries: Vec<ProjectEntry>,
+    strategy: CopyStrategy,
+) -> Result<()> {
+    for entry in entries {
+        let src_path = src.join(&entry.path);
+        let dest_path = dest.join(&entry.path);
+
+        if entry.is_symlink {
+            copy_symlink(&src_path, &dest_path)
+                .context(format!("Error copying the link {}", src_path.display()))?;
+        } else if entry.is_dir {
+            fs::create_dir_all(&dest_path)
+                .context(format!("Error creating directory {}", dest_path.display()))?;
+        } else {
+            strategy.copy_file(&src_path, &dest_path).context(format!(
+                "Error copying {} to {} ({} strategy)",
+                src_path.display(),
+                dest_path.display(),
+                strategy
+            ))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Copies the whole directory tree at `src` into `dest` (which mustn't exist yet), like an installed `node_modules`.
+/// Unlike `copy_project`, nothing is excluded, and symlinks are copied as links to the same target, so the relative
+/// links that package managers write (in `.bin`, or pnpm's whole layout) still work in the copy.
+pub fn copy_tree(src: &Path, dest: &Path, strategy: CopyStrategy) -> Result<()> {
+    fs::create_dir_all(dest).context(format!("Error creating directory {}", dest.display()))?;
+
+    for entry in fs::read_dir(src).context(format!("Error reading directory {}", src.display()))? {
+        let entry = entry?;
+        let (src_path, dest_path) = (entry.path(), dest.join(entry.file_name()));
+        let file_type = entry.file_type()?;
+
+        if file_type.is_symlink() {
+            copy_symlink(&src_path, &dest_path)
+                .context(format!("Error copying the link {}", src_path.display()))?;
+        } else if file_type.is_dir() {
+            copy_tree(&src_path, &dest_path, strategy)?;
+        } else {
+            strategy.copy_file(&src_path, &dest_path).context(format!(
+                "Error copying {} to {} ({} strategy)",
+                src_path.display(),
+                dest_path.display(),
+                strategy
+            ))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Copies the symlink at `src` to `dest`, pointing at the same target.
+#[cfg(unix)]
+fn copy_symlink(src: &Path, dest: &Path) -> Result<(), io::Error> {
+    std::os::unix::fs::symlink(fs::read_link(src)?, dest)
+}
+
+/// Copies the symlink at `src` to `dest`, pointing at the same target. Windows has separate links to directories and
+/// files, so the link is made for whichever its target is (or to a file, if the link is dangling).
+#[cfg(windows)]
+fn copy_symlink(src: &Path, dest: &Path) -> Result<(), io::Error> {
+    let target = fs::read_link(src)?;
+
+    if fs::metadata(src).is_ok_and(|metadata| metadata.is_dir()) {
+        std::os::windows::fs::symlink_dir(target, dest)
+    } else {
+        std::os::windows::fs::symlink_file(target, dest)
+    }
+}
+
+/// A file or directory in the project that would be copied into the build directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectEntry {
+    /// The path of the entry, relative to the project root.
+    pub path: PathBuf,
+
+    /// Whether the entry is a directory.
+    pub is_dir: bool,
+
+    /// Whether the entry is a symlink, which is copied as a link to the same target rather than followed (so it's
+    /// never a directory, even if it links to one).
+    pub is_symlink: bool,
+}
+
+/// Lists every entry of the project at `src` that isn't excluded, in a stable (sorted) order. Parent directories
+/// always come before their contents.
+pub fn walk_project(src: &Path, extra_excludes: &[String]) -> Result<Vec<ProjectEntry>> {
+    let filter = ProjectFilter::new(src, extra_excludes)?;
+
+    let walker = WalkBuilder::new(src)
+        .standard_filters(false)
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .filter_entry(move |entry| {
+            let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+            !filter.matcher.matched(entry.path(), is_dir).is_ignore()
+        })
+        .build();
+
+    let mut entries = vec![];
+
+    for entry in walker {
+        let entry = entry.context("Error walking the project directory")?;
+
+        let relative_path = entry
+            .path()
+            .strip_prefix(src)
+            .expect("Walked paths should always be inside the project directory!");
+
+        // Skip the root itself
+        if relative_path.as_os_str().is_empty() {
+            continue;
+        }
+
+        entries.push(ProjectEntry {
+            path: relative_path.to_path_buf(),
+            is_dir: entry.file_type().is_some_and(|t| t.is_dir()),
+            is_symlink: entry.path_is_symlink(),
+        });
+    }
+
+    Ok(entries)
+}
+
+/// A filter for which files in a project are part of the build.
+pub struct ProjectFilter {
+    /// The root of the project.
+    root: PathBuf,
+
+    /// The matcher for excluded files.
+    matcher: Gitignore,
+}
+
+impl ProjectFilter {
+    /// Builds the filter for the project at `root`. Patterns are layered so that later ones win: first the defaults,
+    /// then `extra_excludes`, and finally the user's `.jundlerignore`.
+    pub fn new(root: &Path, extra_excludes: &[String]) -> Result<Self> {
+        let mut builder = GitignoreBuilder::new(root);
+
+        for pattern in DEFAULT_EXCLUDES
+            .iter()
+            .copied()
+            .chain(extra_excludes.iter().map(String::as_str))
+        {
+            builder
+                .add_line(None, pattern)
+                .context(format!("Invalid exclude pattern `{}`", pattern))?;
+        }
+
+        let ignore_file = root.join(IGNORE_FILE_NAME);
+
+        if ignore_file.exists() {
+            debug!("Using ignore file at {}", ignore_file.display());
+
+            if let Some(err) = builder.add(&ignore_file) {
+                return Err(err).context(format!("Could not parse {}", ignore_file.display()));
+            }
+        }
+
+        let matcher = builder
+            .build()
+            .context("Could not build the project copy filter")?;
+
+        Ok(Self {
+            root: root.to_path_buf(),
+            matcher,
+        })
+    }
+
+    /// Whether a path (or any of its parent directories) is excluded from the build. Paths outside of the project
+    /// are always excluded.
+    pub fn is_excluded(&self, path: &Path) -> bool {
+        if !path.starts_with(&self.root) {
+            return true;
+        }
+
+        self.matcher
+            .matched_path_or_any_parents(path, path.is_dir())
+            .is_ignore()
+    }
+}
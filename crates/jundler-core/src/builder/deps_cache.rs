@@ -0,0 +1,150 @@
+use super::copy::{copy_tree, CopyStrategy};
+use super::BuildOptions;
+use anyhow::{Context, Result};
+use log::debug;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A cache of the `node_modules` installed for previous builds, so builds whose dependencies haven't changed restore
+/// them instead of installing them all over again. Like the build cache, each project + target pair has a single
+/// "slot", holding the `node_modules` of its last install along with the key it was installed with: the hash of the
+/// project's manifest, lockfile, and npm and Yarn configuration, the package manager, the target, and the version of
+/// Node.js.
+pub struct DepsCache {
+    /// The directory where cached dependencies are stored.
+    cache_dir: PathBuf,
+}
+
+impl DepsCache {
+    /// Creates a new dependency cache. Expects that `cache_dir` exists and is writable.
+    pub fn new(cache_dir: PathBuf) -> Self {
+        Self { cache_dir }
+    }
+
+    /// Restores the cached `node_modules` of the project + target into `dest` (the project's copy in the build
+    /// directory), if they were installed with `key`. Returns whether they were.
+    pub fn restore(
+        &self,
+        project_dir: &Path,
+        options: &BuildOptions,
+        key: &str,
+        dest: &Path,
+    ) -> Result<bool> {
+        let slot = self.slot_paths(project_dir, options);
+
+        let cached_key = fs::read_to_string(&slot.key).ok();
+
+        if cached_key.as_deref().map(str::trim) != Some(key) || !slot.node_modules.is_dir() {
+            debug!("Dependency cache miss for {}", project_dir.display());
+            return Ok(false);
+        }
+
+        debug!("Dependency cache hit for {}", project_dir.display());
+
+        // The build directory is reused between builds (i.e. in watch mode), so it may have other dependencies already
+        let node_modules = dest.join("node_modules");
+
+        if node_modules.exists() {
+            fs::remove_dir_all(&node_modules)
+                .context("Error removing the installed dependencies")?;
+        }
+
+        copy_tree(
+            &slot.node_modules,
+            &node_modules,
+            cache_strategy(options.copy_strategy),
+        )
+        .context("Error restoring the dependencies from the cache")?;
+
+        Ok(true)
+    }
+
+    /// Stores the `node_modules` installed in `src` (the project's copy in the build directory) with `key` for the
+    /// project + target, replacing whatever was there before. Nothing is stored if there's no `node_modules`, i.e. with
+    /// Yarn Plug'n'Play.
+    pub fn insert(
+        &self,
+        project_dir: &Path,
+        options: &BuildOptions,
+        key: &str,
+        src: &Path,
+    ) -> Result<()> {
+        let node_modules = src.join("node_modules");
+
+        if !node_modules.is_dir() {
+            return Ok(());
+        }
+
+        let slot = self.slot_paths(project_dir, options);
+
+        // Remove the old key first, so a failure halfway through can't leave a stale key next to new dependencies.
+        if slot.key.exists() {
+            fs::remove_file(&slot.key).context("Error removing stale dependency cache entry")?;
+        }
+
+        if slot.dir.exists() {
+            fs::remove_dir_all(&slot.dir).context("Error removing stale cached dependencies")?;
+        }
+
+        copy_tree(
+            &node_modules,
+            &slot.node_modules,
+            cache_strategy(options.copy_strategy),
+        )
+        .context("Error copying the dependencies to the cache")?;
+
+        fs::write(&slot.key, key).context(format!(
+            "Error writing dependency cache entry at {}",
+            slot.key.display()
+        ))
+    }
+
+    /// Removes every cached `node_modules`.
+    pub fn clean_cache(&self) -> Result<()> {
+        fs::remove_dir_all(&self.cache_dir).context("Error deleting dependency cache directory")?;
+        fs::create_dir_all(&self.cache_dir)
+            .context("Error recreating dependency cache directory")?;
+
+        Ok(())
+    }
+
+    /// Gets the paths to the cached `node_modules` and key for a project + target.
+    fn slot_paths(&self, project_dir: &Path, options: &BuildOptions) -> SlotPaths {
+        let mut hasher = Sha256::new();
+
+        hasher.update(project_dir.to_string_lossy().as_bytes());
+        hasher.update([0]);
+        hasher.update(format!("{}-{}", options.target_os, options.target_arch));
+
+        let slot = hex::encode(hasher.finalize());
+        let dir = self.cache_dir.join(&slot);
+
+        SlotPaths {
+            node_modules: dir.join("node_modules"),
+            dir,
+            key: self.cache_dir.join(format!("{}.key", slot)),
+        }
+    }
+}
+
+/// The paths of a single slot in the dependency cache.
+struct SlotPaths {
+    /// The directory of the slot.
+    dir: PathBuf,
+
+    /// Where the `node_modules` are stored.
+    node_modules: PathBuf,
+
+    /// Where the key the dependencies were installed with is stored.
+    key: PathBuf,
+}
+
+/// Gets how to copy dependencies in and out of the cache with `strategy`. Later steps, like rebuilding native addons,
+/// write to the installed dependencies, so they're never hardlinked, which would write to the cached ones too.
+fn cache_strategy(strategy: CopyStrategy) -> CopyStrategy {
+    match strategy {
+        CopyStrategy::Hardlink => CopyStrategy::Auto,
+        strategy => strategy,
+    }
+}
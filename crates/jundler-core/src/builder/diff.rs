@@ -0,0 +1,257 @@
+//! Compares two builds of an application, from their build reports or from the binaries themselves: how much the
+//! binary grew or shrank, what changed in the bundle, and which versions of Node.js, ESBuild, and the bundled packages
+//! changed. Useful for catching accidental bundle bloat in pull requests.
+
+use super::metadata;
+use super::report::{BuildReport, BundleContents};
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+/// A build to compare. Binaries only record what jundler embeds in them, so anything they don't have is `None`, while
+/// build reports have everything but the bundle hash.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildSummary {
+    /// The platform the build is for, i.e. `linux-x64`, or `darwin-universal`.
+    pub target: String,
+
+    /// The size of the binary, in bytes.
+    pub size: u64,
+
+    /// The runtime the binary was built with and its version, i.e. `node v22.3.0`.
+    pub runtime: Option<String>,
+
+    /// The version of ESBuild that bundled the application, if it did.
+    pub esbuild_version: Option<String>,
+
+    /// The SHA256 checksum of the application's entrypoint.
+    pub bundle_hash: Option<String>,
+
+    /// What ESBuild put in the bundle.
+    pub bundle: Option<BundleContents>,
+}
+
+/// How a build changed between two versions of it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BuildDiff {
+    /// The platform the builds are for.
+    pub target: String,
+
+    /// The size of the old binary, in bytes.
+    pub old_size: u64,
+
+    /// The size of the new binary, in bytes.
+    pub new_size: u64,
+
+    /// The runtime and its version, if they changed.
+    pub runtime: Option<Change>,
+
+    /// The version of ESBuild, if it changed.
+    pub esbuild_version: Option<Change>,
+
+    /// Whether the entrypoint changed, if both builds recorded its hash.
+    pub bundle_changed: Option<bool>,
+
+    /// Whether both builds recorded what was in their bundles, so that `inputs` and `packages` could be compared.
+    pub contents_compared: bool,
+
+    /// Every file in the bundle whose size in it changed, the biggest changes first.
+    pub inputs: Vec<InputChange>,
+
+    /// Every package that was added to or removed from the bundle, or bundled in a different version.
+    pub packages: Vec<PackageChange>,
+}
+
+/// A value that changed between builds. `None` if a build didn't have it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Change {
+    /// The value in the old build.
+    pub old: Option<String>,
+
+    /// The value in the new build.
+    pub new: Option<String>,
+}
+
+/// A file whose size in the bundle changed. Files that were added or removed have a size of 0 in the build without them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InputChange {
+    /// The path to the file, relative to the project.
+    pub path: String,
+
+    /// How many bytes of the file were in the old bundle.
+    pub old_bytes: u64,
+
+    /// How many bytes of the file are in the new bundle.
+    pub new_bytes: u64,
+}
+
+/// A package whose bundled versions changed. Added packages have no old versions, and removed packages no new ones.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PackageChange {
+    /// The name of the package.
+    pub name: String,
+
+    /// The versions of the package in the old bundle.
+    pub old: BTreeSet<String>,
+
+    /// The versions of the package in the new bundle.
+    pub new: BTreeSet<String>,
+}
+
+impl BuildDiff {
+    /// How many bytes the binary grew by, or shrank by if it's negative.
+    pub fn size_delta(&self) -> i64 {
+        self.new_size as i64 - self.old_size as i64
+    }
+}
+
+impl InputChange {
+    /// How many bytes of the file the bundle grew by, or shrank by if it's negative.
+    pub fn delta(&self) -> i64 {
+        self.new_bytes as i64 - self.old_bytes as i64
+    }
+}
+
+/// Loads the builds to compare from `path`: every build in a JSON build report (from `jundler build --report`), or a
+/// single built binary.
+pub fn load(path: &Path) -> Result<Vec<BuildSummary>> {
+    if path
+        .extension()
+        .is_some_and(|extension| extension == "json")
+    {
+        let report: BuildReport = serde_json::from_str(
+            &fs::read_to_string(path)
+                .context(format!("Error reading build report {}", path.display()))?,
+        )
+        .context(format!("Error parsing build report {}", path.display()))?;
+
+        return Ok(report
+            .builds
+            .into_iter()
+            // The binary is always the first file a build writes
+            .filter_map(|build| {
+                Some(BuildSummary {
+                    target: format!("{}-{}", build.os, build.arch),
+                    size: build.outputs.first()?.size,
+                    runtime: Some(format!("{} v{}", build.runtime, build.runtime_version)),
+                    esbuild_version: build.esbuild_version,
+                    bundle_hash: None,
+                    bundle: build.bundle,
+                })
+            })
+            .collect());
+    }
+
+    let size = fs::metadata(path)
+        .context(format!("Error reading metadata of {}", path.display()))?
+        .len();
+
+    let metadata = metadata::inspect(path)?.metadata;
+
+    Ok(vec![BuildSummary {
+        target: metadata
+            .as_ref()
+            .map_or_else(|| "unknown".to_string(), |metadata| metadata.target.clone()),
+        size,
+        runtime: metadata
+            .as_ref()
+            .map(|metadata| format!("node v{}", metadata.node_version)),
+        esbuild_version: None,
+        bundle_hash: metadata.map(|metadata| metadata.bundle_hash),
+        bundle: None,
+    }])
+}
+
+/// Compares the builds in `old` with those in `new`. If each side has a single build they're compared with each other,
+/// and otherwise builds are paired up by target. Errors if no builds could be paired up.
+pub fn compare(old: &[BuildSummary], new: &[BuildSummary]) -> Result<Vec<BuildDiff>> {
+    let pairs: Vec<(&BuildSummary, &BuildSummary)> = match (old, new) {
+        ([old], [new]) => vec![(old, new)],
+        _ => new
+            .iter()
+            .filter_map(|new| {
+                old.iter()
+                    .find(|old| old.target == new.target)
+                    .map(|old| (old, new))
+            })
+            .collect(),
+    };
+
+    if pairs.is_empty() {
+        return Err(anyhow!("The builds have no targets in common to compare!"));
+    }
+
+    Ok(pairs
+        .into_iter()
+        .map(|(old, new)| compare_build(old, new))
+        .collect())
+}
+
+/// Compares two builds for the same target.
+fn compare_build(old: &BuildSummary, new: &BuildSummary) -> BuildDiff {
+    let change = |old: &Option<String>, new: &Option<String>| {
+        (old != new).then(|| Change {
+            old: old.clone(),
+            new: new.clone(),
+        })
+    };
+
+    let bundle_changed = match (&old.bundle_hash, &new.bundle_hash) {
+        (Some(old), Some(new)) => Some(old != new),
+        _ => None,
+    };
+
+    let (inputs, packages) = match (&old.bundle, &new.bundle) {
+        (Some(old), Some(new)) => (compare_inputs(old, new), compare_packages(old, new)),
+        _ => (vec![], vec![]),
+    };
+
+    BuildDiff {
+        target: new.target.clone(),
+        old_size: old.size,
+        new_size: new.size,
+        runtime: change(&old.runtime, &new.runtime),
+        esbuild_version: change(&old.esbuild_version, &new.esbuild_version),
+        bundle_changed,
+        contents_compared: old.bundle.is_some() && new.bundle.is_some(),
+        inputs,
+        packages,
+    }
+}
+
+/// Finds every file whose size in the bundle changed, the biggest changes first.
+fn compare_inputs(old: &BundleContents, new: &BundleContents) -> Vec<InputChange> {
+    let paths: BTreeSet<&String> = old.inputs.keys().chain(new.inputs.keys()).collect();
+
+    let mut changes: Vec<InputChange> = paths
+        .into_iter()
+        .map(|path| InputChange {
+            path: path.clone(),
+            old_bytes: old.inputs.get(path).copied().unwrap_or_default(),
+            new_bytes: new.inputs.get(path).copied().unwrap_or_default(),
+        })
+        .filter(|change| change.old_bytes != change.new_bytes)
+        .collect();
+
+    // Sorting is stable, so changes of the same size stay sorted by path
+    changes.sort_by_key(|change| std::cmp::Reverse(change.delta().unsigned_abs()));
+
+    changes
+}
+
+/// Finds every package that was added, removed, or bundled in a different version, sorted by name.
+fn compare_packages(old: &BundleContents, new: &BundleContents) -> Vec<PackageChange> {
+    let names: BTreeSet<&String> = old.packages.keys().chain(new.packages.keys()).collect();
+
+    names
+        .into_iter()
+        .map(|name| PackageChange {
+            name: name.clone(),
+            old: old.packages.get(name).cloned().unwrap_or_default(),
+            new: new.packages.get(name).cloned().unwrap_or_default(),
+        })
+        .filter(|change| change.old != change.new)
+        .collect()
+}
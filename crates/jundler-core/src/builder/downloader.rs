@@ -0,0 +1,82 @@
+use crate::ui::{events, Reporter};
+use std::fmt;
+use std::fs::File;
+use std::io;
+use std::path::PathBuf;
+
+/// Where Node.js (along with its checksums) and ESBuild are downloaded from. [`HttpDownloader`] downloads them from
+/// the internet, while [`FileDownloader`] reads them from a directory laid out like the servers, so tests can run
+/// offline and always get the same files.
+pub trait Downloader: fmt::Debug + Send + Sync {
+    /// Downloads the file at `url`, reporting the progress to `reporter`.
+    fn download(&self, url: &str, reporter: &dyn Reporter) -> Result<Vec<u8>, DownloadError>;
+}
+
+/// Any errors that can occur while downloading a file
+#[derive(Debug, thiserror::Error)]
+pub enum DownloadError {
+    /// The request failed, or the server responded with an error
+    #[error("{0}")]
+    Http(#[from] reqwest::Error),
+
+    /// There's no file for the URL in the directory it's read from
+    #[error("There's no file for it at {}", .0.display())]
+    Missing(PathBuf),
+
+    /// Reading the file failed
+    #[error("{0}")]
+    Io(#[from] io::Error),
+}
+
+/// Downloads files over HTTP(S).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HttpDownloader;
+
+impl Downloader for HttpDownloader {
+    fn download(&self, url: &str, reporter: &dyn Reporter) -> Result<Vec<u8>, DownloadError> {
+        let response = reqwest::blocking::get(url)?.error_for_status()?;
+        let total = response.content_length();
+
+        Ok(events::read_download(url, response, total, reporter)?)
+    }
+}
+
+/// "Downloads" files from a directory instead of the internet. The file for a URL is at its host and path inside the
+/// directory, i.e. `https://nodejs.org/dist/index.json` is read from `{dir}/nodejs.org/dist/index.json`.
+#[derive(Debug, Clone)]
+pub struct FileDownloader {
+    /// The directory to read files from.
+    dir: PathBuf,
+}
+
+impl FileDownloader {
+    /// Creates a downloader that reads files from `dir`.
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Gets where the file for `url` is.
+    pub fn path(&self, url: &str) -> PathBuf {
+        let url = url.split_once("://").map_or(url, |(_, rest)| rest);
+
+        self.dir.join(url)
+    }
+}
+
+impl Downloader for FileDownloader {
+    fn download(&self, url: &str, reporter: &dyn Reporter) -> Result<Vec<u8>, DownloadError> {
+        let path = self.path(url);
+
+        let file = match File::open(&path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Err(DownloadError::Missing(path))
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        let total = file.metadata()?.len();
+
+        Ok(events::read_download(url, file, Some(total), reporter)?)
+    }
+}
@@ -0,0 +1,131 @@
+//! The environment build commands run in. npm, ESBuild, Node.js, `postject`, and the other tools a build runs only get
+//! the variables they need to work from jundler's environment, so that tokens and secrets in it don't leak into
+//! install scripts, and variables like `NODE_OPTIONS` or `npm_config_*` can't change the build behind its back. Any
+//! other variable has to be passed through deliberately, with `--pass-env`.
+
+use std::collections::HashSet;
+use std::env;
+use std::ffi::{OsStr, OsString};
+use std::process::Command;
+
+/// The variables build commands always get: where to find programs and put temporary files, who and where the user is,
+/// their locale and terminal, how to reach the internet through a proxy and which certificates to trust, and what
+/// Windows and Xcode need to run at all.
+pub const ALLOWED_VARS: &[&str] = &[
+    // Programs, files, and the user
+    "PATH",
+    "HOME",
+    "USER",
+    "LOGNAME",
+    "SHELL",
+    "TMPDIR",
+    "TMP",
+    "TEMP",
+    "XDG_CACHE_HOME",
+    "XDG_CONFIG_HOME",
+    "XDG_DATA_HOME",
+    "XDG_RUNTIME_DIR",
+    // Locale and terminal
+    "LANG",
+    "LANGUAGE",
+    "TZ",
+    "TERM",
+    "COLORTERM",
+    "NO_COLOR",
+    // Networking
+    "HTTP_PROXY",
+    "HTTPS_PROXY",
+    "NO_PROXY",
+    "http_proxy",
+    "https_proxy",
+    "no_proxy",
+    "SSL_CERT_FILE",
+    "SSL_CERT_DIR",
+    "NODE_EXTRA_CA_CERTS",
+    // Reproducible builds
+    "SOURCE_DATE_EPOCH",
+    // Windows
+    "SYSTEMROOT",
+    "SYSTEMDRIVE",
+    "WINDIR",
+    "COMSPEC",
+    "PATHEXT",
+    "APPDATA",
+    "LOCALAPPDATA",
+    "USERPROFILE",
+    "HOMEDRIVE",
+    "HOMEPATH",
+    "PROGRAMDATA",
+    "PROGRAMFILES",
+    "PROGRAMFILES(X86)",
+    "NUMBER_OF_PROCESSORS",
+    "PROCESSOR_ARCHITECTURE",
+    // Xcode
+    "DEVELOPER_DIR",
+    "SDKROOT",
+];
+
+/// The prefixes of variables build commands always get, for the locale.
+const ALLOWED_PREFIXES: &[&str] = &["LC_"];
+
+/// Runs commands in the sanitized environment of a build.
+pub trait SanitizedEnv {
+    /// Removes every variable in jundler's environment from the command's, except for [`ALLOWED_VARS`] and those
+    /// matched by `pass_env`: names of variables, or prefixes ending in `*`, like `npm_config_*`. Variables set on the
+    /// command itself are kept, so this can be called at any point before it's run.
+    fn sanitized_env(&mut self, pass_env: &[String]) -> &mut Self {
+        self.sanitized_env_from(env::vars_os(), pass_env)
+    }
+
+    /// Like `sanitized_env`, but removes the variables of `vars` instead of jundler's environment, i.e. so it can be
+    /// tested without changing the environment of every other test.
+    fn sanitized_env_from(
+        &mut self,
+        vars: impl IntoIterator<Item = (OsString, OsString)>,
+        pass_env: &[String],
+    ) -> &mut Self;
+}
+
+impl SanitizedEnv for Command {
+    fn sanitized_env_from(
+        &mut self,
+        vars: impl IntoIterator<Item = (OsString, OsString)>,
+        pass_env: &[String],
+    ) -> &mut Self {
+        let set: HashSet<OsString> = self
+            .get_envs()
+            .map(|(name, _)| name.to_os_string())
+            .collect();
+
+        for (name, _) in vars {
+            if !set.contains(&name) && !is_allowed(&name, pass_env) {
+                self.env_remove(name);
+            }
+        }
+
+        self
+    }
+}
+
+/// Whether build commands get a variable from jundler's environment. Names are case-insensitive on Windows, like the
+/// environment itself.
+pub fn is_allowed(name: &OsStr, pass_env: &[String]) -> bool {
+    let Some(name) = name.to_str() else {
+        return false;
+    };
+
+    let matches = |pattern: &str| match pattern.strip_suffix('*') {
+        Some(prefix) if cfg!(windows) => name
+            .get(..prefix.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(prefix)),
+        Some(prefix) => name.starts_with(prefix),
+        None if cfg!(windows) => name.eq_ignore_ascii_case(pattern),
+        None => name == pattern,
+    };
+
+    ALLOWED_VARS.iter().any(|var| matches(var))
+        || ALLOWED_PREFIXES
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+        || pass_env.iter().any(|pattern| matches(pattern))
+}
@@ -0,0 +1,314 @@
+use super::platforms::PlatformError;
+use serde::Serialize;
+use std::fmt;
+
+/// Any errors that can occur when interacting with the NodeManager
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    // An error from the NodeManager
+    #[error(transparent)]
+    NodeManager(#[from] crate::builder::node_manager::Error),
+
+    /// An error from the esbuild API
+    #[error(transparent)]
+    ESBuild(#[from] crate::builder::esbuild::Error),
+}
+
+/// The class of an error that stopped a command, so scripts can tell failures apart by exit code. The exit codes are
+/// part of Jundler's interface, so they must never change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorClass {
+    /// Anything else.
+    Other,
+
+    /// Downloading Node.js, ESBuild, or anything else failed.
+    Network,
+
+    /// A download or binary didn't match its checksum, or a checksum isn't pinned in `jundler.lock` for a frozen build.
+    Checksum,
+
+    /// The project's configuration, or the options, are invalid.
+    Config,
+
+    /// Installing the project's dependencies, or bundling it, failed.
+    Bundling,
+
+    /// Generating the SEA blob, or injecting it into the Node.js binary, failed.
+    Injection,
+
+    /// Signing, notarizing, or writing signatures failed.
+    Signing,
+
+    /// This machine's OS or architecture isn't supported.
+    Platform,
+}
+
+impl ErrorClass {
+    /// Finds the class of an error. Errors from downloads and checksums are recognized wherever they come from, and
+    /// everything else goes by what it was classified as with [`Classify::classify`].
+    pub fn of(err: &anyhow::Error) -> Self {
+        use crate::builder::{esbuild, node_manager};
+
+        let recognized = err.chain().find_map(|cause| {
+            // Classified errors stand in for the error they wrap, so look at that instead
+            let cause: &(dyn std::error::Error + 'static) = match cause.downcast_ref::<Classified>()
+            {
+                Some(classified) => &*classified.err,
+                None => cause,
+            };
+
+            if cause.is::<reqwest::Error>() {
+                return Some(ErrorClass::Network);
+            }
+
+            if cause.is::<PlatformError>() {
+                return Some(ErrorClass::Platform);
+            }
+
+            match (
+                cause.downcast_ref::<node_manager::Error>(),
+                cause.downcast_ref::<esbuild::Error>(),
+            ) {
+                (Some(node_manager::Error::Download { .. }), _)
+                | (_, Some(esbuild::Error::Download { .. })) => Some(ErrorClass::Network),
+
+                (Some(node_manager::Error::ChecksumMismatch { .. }), _)
+                | (_, Some(esbuild::Error::ChecksumMismatch { .. }))
+                | (Some(node_manager::Error::Frozen(_)), _)
+                | (_, Some(esbuild::Error::Frozen(_))) => Some(ErrorClass::Checksum),
+
+                _ => None,
+            }
+        });
+
+        recognized
+            .or_else(|| {
+                err.chain()
+                    .find_map(|cause| cause.downcast_ref::<Classified>())
+                    .map(|classified| classified.class)
+            })
+            .unwrap_or(ErrorClass::Other)
+    }
+
+    /// The code Jundler exits with for errors of this class. `2` is left out, as that's what invalid arguments exit
+    /// with.
+    pub fn exit_code(self) -> u8 {
+        match self {
+            ErrorClass::Other => 1,
+            ErrorClass::Network => 10,
+            ErrorClass::Checksum => 11,
+            ErrorClass::Config => 12,
+            ErrorClass::Bundling => 13,
+            ErrorClass::Injection => 14,
+            ErrorClass::Signing => 15,
+            ErrorClass::Platform => 16,
+        }
+    }
+}
+
+/// An error with a class attached. It shows (and has the same sources) as the error it wraps, so classifying an error
+/// doesn't change how it's printed.
+#[derive(Debug)]
+pub struct Classified {
+    /// The class of the error.
+    class: ErrorClass,
+
+    /// The error itself.
+    err: anyhow::Error,
+}
+
+impl fmt::Display for Classified {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.err)
+    }
+}
+
+impl std::error::Error for Classified {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.err.source()
+    }
+}
+
+/// Attaches an [`ErrorClass`] to errors, like [`anyhow::Context`] attaches context.
+pub trait Classify<T> {
+    /// Classifies the error, if there is one. Errors that were already classified keep their class.
+    fn classify(self, class: ErrorClass) -> anyhow::Result<T>;
+}
+
+impl<T, E: Into<anyhow::Error>> Classify<T> for Result<T, E> {
+    fn classify(self, class: ErrorClass) -> anyhow::Result<T> {
+        self.map_err(|err| {
+            let err = err.into();
+
+            if err.chain().any(|cause| cause.is::<Classified>()) {
+                err
+            } else {
+                anyhow::Error::new(Classified { class, err })
+            }
+        })
+    }
+}
+
+/// What to tell people about an error on top of its message: a code that stays the same between versions (so it can
+/// be searched for), what might fix it, and where to read more.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    /// The code of the error, i.e. `node::checksum_mismatch`.
+    pub code: &'static str,
+
+    /// Suggestions for fixing the error, each printed after `help:`.
+    pub help: Vec<String>,
+
+    /// A link to the documentation about the error, if there is any.
+    pub docs: Option<String>,
+}
+
+/// Errors that can diagnose themselves.
+pub trait Diagnose {
+    /// Gets the diagnostic for this error.
+    fn diagnostic(&self) -> Diagnostic;
+}
+
+impl Diagnostic {
+    /// Creates a diagnostic with a code and suggestions, and without documentation.
+    pub fn new(code: &'static str, help: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Diagnostic {
+            code,
+            help: help.into_iter().map(Into::into).collect(),
+            docs: None,
+        }
+    }
+
+    /// Links to the answer to a question in the FAQ of the README, by its anchor.
+    pub fn with_faq(mut self, anchor: &str) -> Self {
+        self.docs = Some(format!("{}#{}", env!("CARGO_PKG_REPOSITORY"), anchor));
+        self
+    }
+
+    /// Finds the diagnostic of an error: that of the outermost cause that can diagnose itself, if any.
+    pub fn of(err: &anyhow::Error) -> Option<Self> {
+        use crate::builder::{esbuild, node_manager};
+
+        err.chain().find_map(|cause| {
+            // Classified errors stand in for the error they wrap, so look at that instead
+            let cause: &(dyn std::error::Error + 'static) = match cause.downcast_ref::<Classified>()
+            {
+                Some(classified) => &*classified.err,
+                None => cause,
+            };
+
+            cause
+                .downcast_ref::<node_manager::Error>()
+                .map(Diagnose::diagnostic)
+                .or_else(|| {
+                    cause
+                        .downcast_ref::<esbuild::Error>()
+                        .map(Diagnose::diagnostic)
+                })
+        })
+    }
+}
+
+/// How the error that stopped a command is printed.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ErrorFormat {
+    /// The error and its causes, for people to read.
+    #[default]
+    Human,
+
+    /// A single line of JSON on stderr, with the class, exit code, message, and causes of the error.
+    Json,
+}
+
+/// The error that stopped a command, as printed with `--error-format json`.
+#[derive(Debug, Serialize)]
+pub struct ErrorReport {
+    /// The class of the error.
+    pub class: ErrorClass,
+
+    /// The code Jundler exits with.
+    pub exit_code: u8,
+
+    /// The error itself.
+    pub message: String,
+
+    /// What caused the error, outermost first.
+    pub causes: Vec<String>,
+
+    /// The code of the error, if it has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<&'static str>,
+
+    /// Suggestions for fixing the error.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub help: Vec<String>,
+
+    /// A link to the documentation about the error.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub docs: Option<String>,
+}
+
+impl ErrorReport {
+    /// Creates the report for an error.
+    pub fn new(err: &anyhow::Error) -> Self {
+        let class = ErrorClass::of(err);
+        let diagnostic = Diagnostic::of(err);
+
+        ErrorReport {
+            class,
+            exit_code: class.exit_code(),
+            message: err.to_string(),
+            causes: err.chain().skip(1).map(ToString::to_string).collect(),
+            code: diagnostic.as_ref().map(|diagnostic| diagnostic.code),
+            help: diagnostic
+                .as_ref()
+                .map(|diagnostic| diagnostic.help.clone())
+                .unwrap_or_default(),
+            docs: diagnostic.and_then(|diagnostic| diagnostic.docs),
+        }
+    }
+
+    /// Renders the error for people to read: the error with its code, its causes, and then any suggestions and
+    /// documentation.
+    pub fn render(&self) -> String {
+        let mut rendered = match self.code {
+            Some(code) => format!(
+                "{}: {}",
+                console::style(format!("Error[{}]", code)).red().bold(),
+                self.message
+            ),
+            None => format!("{}: {}", console::style("Error").red().bold(), self.message),
+        };
+
+        if !self.causes.is_empty() {
+            rendered.push_str("\n\nCaused by:");
+
+            for (i, cause) in self.causes.iter().enumerate() {
+                rendered.push_str(&format!("\n    {}: {}", i, cause));
+            }
+        }
+
+        if !self.help.is_empty() || self.docs.is_some() {
+            rendered.push('\n');
+        }
+
+        for help in &self.help {
+            rendered.push_str(&format!(
+                "\n{} {}",
+                console::style("help:").cyan().bold(),
+                help
+            ));
+        }
+
+        if let Some(docs) = &self.docs {
+            rendered.push_str(&format!(
+                "\n{} {}",
+                console::style("docs:").cyan().bold(),
+                docs
+            ));
+        }
+
+        rendered
+    }
+}
@@ -0,0 +1,100 @@
+use super::lock::Checksum;
+use crate::builder::downloader::DownloadError;
+use crate::builder::errors::{Diagnose, Diagnostic};
+use std::io;
+use std::path::PathBuf;
+
+/// Any errors that can occur when interacting with the NodeManager
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// An IO error occurred
+    #[error("An IO error occurred on while {action} {path}: {err}")]
+    Io {
+        /// The source of the error
+        #[source]
+        err: io::Error,
+
+        /// The path that caused the error
+        path: PathBuf,
+
+        /// The action that caused the error. Should be insertable into a string of "...while {action} {path}:"
+        action: String,
+    },
+
+    /// An error serializing/deserializing the lockfile occured
+    #[error("An error occurred while serializing/deserializing the lockfile: {0}")]
+    LockfileSerialization(#[from] bincode::Error),
+
+    /// An error occured while trying to download a file
+    #[error("An error occurred while trying to download a file from {url}: {err}")]
+    Download {
+        /// The source of the error
+        #[source]
+        err: DownloadError,
+
+        /// The URL that caused the error
+        url: String,
+    },
+
+    /// Something would have to change in the cache, but it's frozen
+    #[error("Can't {0} while the cache is frozen! Run `jundler fetch` first.")]
+    Frozen(String),
+
+    /// There was a mismatch between the expected checksum and the actual checksum
+    #[error(
+        "Checksum mismatch for file {path}! Expected: {}, Actual: {}",
+        hex::encode(expected),
+        hex::encode(actual)
+    )]
+    ChecksumMismatch {
+        /// The path to the file
+        path: PathBuf,
+
+        /// The expected checksum
+        expected: Checksum,
+
+        /// The actual checksum
+        actual: Checksum,
+    },
+}
+
+impl Diagnose for Error {
+    fn diagnostic(&self) -> Diagnostic {
+        match self {
+            Error::Io { .. } => Diagnostic::new(
+                "esbuild::io",
+                ["Check that the cache directory can be written to, or choose another one with `--cache-dir`."],
+            )
+            .with_faq("can-i-change-where-jundler-keeps-its-cache"),
+
+            Error::LockfileSerialization(_) => Diagnostic::new(
+                "esbuild::corrupt_lockfile",
+                ["The cache's record of ESBuild is corrupt, or from an incompatible version of jundler. Run `jundler clean esbuild` to start over."],
+            ),
+
+            Error::Download { .. } => Diagnostic::new(
+                "esbuild::download",
+                [
+                    "Check your internet connection, and your proxy settings (`HTTPS_PROXY`) if you're behind one.",
+                    "To download ESBuild once for a whole team or CI fleet, build with `--shared-cache`.",
+                ],
+            )
+            .with_faq("can-i-change-where-jundler-keeps-its-cache"),
+
+            Error::Frozen(_) => Diagnostic::new(
+                "esbuild::frozen",
+                ["Run `jundler fetch` to download and pin everything the build needs, then build with `--frozen` again."],
+            )
+            .with_faq("can-i-make-sure-builds-never-download-anything"),
+
+            Error::ChecksumMismatch { .. } => Diagnostic::new(
+                "esbuild::checksum_mismatch",
+                [
+                    "Run `jundler cache verify` to check whether ESBuild is corrupt in the cache, and `jundler clean esbuild` to remove it.",
+                    "If every download of ESBuild is rejected, a proxy or shared cache may be changing them on the way.",
+                ],
+            )
+            .with_faq("can-i-change-where-jundler-keeps-its-cache"),
+        }
+    }
+}
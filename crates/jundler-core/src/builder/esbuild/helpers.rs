@@ -1,9 +1,10 @@
 use crate::builder::platforms::{get_host_arch, get_host_os, Os};
 
 use super::Error;
+use crate::builder::downloader::Downloader;
+use crate::ui::Reporter;
 use flate2::read::GzDecoder;
 use log::debug;
-use reqwest::blocking::get;
 use semver::Version;
 use std::{fs::File, path::Path};
 use std::{
@@ -101,24 +102,30 @@ pub fn unpack_downloaded_esbuild_archive(
     Ok(bin_path)
 }
 
-/// Download the esbuild.js archive from the official website, and returns the path to the downloaded archive.
-pub fn download_esbuild_archive(download_dir: &Path, version: &Version) -> Result<PathBuf, Error> {
-    let url = format!(
+/// The URL of the package on npm that ESBuild is downloaded in, for this machine.
+pub fn esbuild_archive_url(version: &Version) -> String {
+    format!(
         "https://registry.npmjs.org/@esbuild/{os}-{arch}/-/{os}-{arch}-{version}.tgz",
         os = get_host_os(),     // TODO: Change
         arch = get_host_arch(), // TODO: Change
         version = version
-    );
+    )
+}
+
+/// Download the esbuild.js archive from the official website with `downloader`, and returns the path to the downloaded archive.
+pub fn download_esbuild_archive(
+    download_dir: &Path,
+    version: &Version,
+    downloader: &dyn Downloader,
+    reporter: &dyn Reporter,
+) -> Result<PathBuf, Error> {
+    let url = esbuild_archive_url(version);
 
     debug!("Downloading esbuild.js from: {}", url); // TODO: Better UI
 
     // Download the file from the URL
-    let content = get(&url)
-        .map_err(|err| Error::Download {
-            err,
-            url: url.clone(),
-        })?
-        .bytes()
+    let content = downloader
+        .download(&url, reporter)
         .map_err(|err| Error::Download {
             err,
             url: url.clone(),
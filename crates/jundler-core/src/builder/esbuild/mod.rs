@@ -4,8 +4,11 @@ mod lock;
 mod tests;
 
 pub use errors::Error;
+pub use helpers::esbuild_archive_url;
 
-use crate::builder::helpers::calculate_checksum;
+use crate::builder::downloader::{Downloader, HttpDownloader};
+use crate::builder::helpers::{calculate_checksum, make_executable};
+use crate::ui::{NoopReporter, Reporter};
 use helpers::{download_esbuild_archive, repack_esbuild_binary, unpack_downloaded_esbuild_archive};
 use lock::{ESBuildExecutable, ESBuildLock};
 use log::warn;
@@ -14,12 +17,13 @@ use std::{
     fs::{self, File},
     io::{Read, Write},
     path::PathBuf,
+    sync::Arc,
 };
 use tempdir::TempDir;
 
 /// The version of ESBuild to use. This should be updated whenever the version of ESBuild is updated. The version is specified as
 /// `Version::new(<major>, <minor>, <patch>)`.
-const ESBUILD_VERSION: Version = Version::new(0, 23, 0);
+pub const ESBUILD_VERSION: Version = Version::new(0, 23, 0);
 
 /// An esbuild instance
 pub struct ESBuild {
@@ -31,6 +35,15 @@ pub struct ESBuild {
 
     /// The directory where the esbuild instance is located
     cache_dir: PathBuf,
+
+    /// Whether the cache is frozen, in which case nothing is downloaded and the lockfile is never written.
+    frozen: bool,
+
+    /// Where to report the progress of downloads.
+    reporter: Arc<dyn Reporter>,
+
+    /// What downloads ESBuild.
+    downloader: Arc<dyn Downloader>,
 }
 
 impl ESBuild {
@@ -64,9 +77,28 @@ impl ESBuild {
             cache_dir: esbuild_cache_dir,
             lockfile,
             tmp_dir,
+            frozen: false,
+            reporter: Arc::new(NoopReporter),
+            downloader: Arc::new(HttpDownloader),
         })
     }
 
+    /// Reports the progress of downloads to `reporter`.
+    pub fn with_reporter(self, reporter: Arc<dyn Reporter>) -> Self {
+        Self { reporter, ..self }
+    }
+
+    /// Downloads ESBuild with `downloader` from now on.
+    pub fn set_downloader(&mut self, downloader: Arc<dyn Downloader>) {
+        self.downloader = downloader;
+    }
+
+    /// Freezes (or unfreezes) the cache. While it's frozen, a binary that isn't in the cache (or is corrupted) is an
+    /// error instead of being downloaded.
+    pub fn set_frozen(&mut self, frozen: bool) {
+        self.frozen = frozen;
+    }
+
     /// Downloads a target binary if it doesn't exist, and returns the path to the binary.
     pub fn get_binary(&mut self) -> Result<PathBuf, Error> {
         let binary = self.lockfile.get();
@@ -94,19 +126,31 @@ impl ESBuild {
         };
 
         // Make the binary executable on Unix-based systems
-        #[cfg(unix)]
-        {
-            use super::helpers::make_executable;
-
-            make_executable(&binary_path).map_err(|err| Error::Io {
-                err,
-                path: binary_path.to_path_buf(),
-                action: "making binary executable at".to_string(),
-            })?;
-        }
+        make_executable(&binary_path).map_err(|err| Error::Io {
+            err,
+            path: binary_path.to_path_buf(),
+            action: "making binary executable at".to_string(),
+        })?;
         Ok(binary_path)
     }
 
+    /// Gets the ESBuild binary in the cache, if there is one, as its version and the path to its (compressed) archive.
+    pub fn cached_binary(&self) -> Option<(Version, PathBuf)> {
+        self.lockfile
+            .get()
+            .map(|executable| (executable.version, executable.path))
+    }
+
+    /// Checks the ESBuild binary in the cache against the checksum it was cached with, as its version along with
+    /// whether it's intact. A binary that can't be read isn't.
+    pub fn verify_cache(&self) -> Option<(Version, bool)> {
+        self.lockfile.get().map(|executable| {
+            let intact = executable.validate_checksum().unwrap_or(false);
+
+            (executable.version, intact)
+        })
+    }
+
     /// Cleans the cache directory by removing all node binaries and clearing the lockfile.
     pub fn clean_cache(&mut self) -> Result<(), Error> {
         // First, clean the lockfile by removing all entries.
@@ -136,8 +180,17 @@ impl ESBuild {
 impl ESBuild {
     /// Download a new node binary, and store it in the cache. Returns a tuple of the form `(path to the binary, path to the archive)`.
     fn download(&mut self, version: &Version) -> Result<PathBuf, Error> {
+        if self.frozen {
+            return Err(Error::Frozen(format!("download ESBuild v{}", version)));
+        }
+
         // Download the node archive
-        let downloaded_archive_path = download_esbuild_archive(self.tmp_dir.path(), version)?;
+        let downloaded_archive_path = download_esbuild_archive(
+            self.tmp_dir.path(),
+            version,
+            &*self.downloader,
+            &*self.reporter,
+        )?;
 
         // Unpack the archive. Needs version, os, and arch to determine the correct path to the binary (named folder).
         let node_executable_path =
@@ -211,6 +264,13 @@ impl ESBuild {
 
     /// Remove the binary from the cache. Returns the path to the binary.
     pub fn remove(&mut self, esbuild_archive: &ESBuildExecutable) -> Result<PathBuf, Error> {
+        if self.frozen {
+            return Err(Error::Frozen(format!(
+                "remove ESBuild v{} from the cache",
+                esbuild_archive.version
+            )));
+        }
+
         // Remove the binary from the cache
         fs::remove_file(&esbuild_archive.path).map_err(|err| Error::Io {
             err,
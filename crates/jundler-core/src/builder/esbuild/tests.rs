@@ -1,8 +1,46 @@
 #![cfg(test)]
 
 use super::*;
+use crate::builder::downloader::FileDownloader;
+use crate::builder::platforms::{get_host_arch, get_host_os, Os};
 use assert_fs::{NamedTempFile, TempDir};
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use lock::{ESBuildExecutable, ESBuildLock};
+use std::path::Path;
+
+/// Lays out a fake ESBuild package for this machine in `fixtures` like the npm registry does, and returns a
+/// downloader that reads from it.
+fn esbuild_fixtures(fixtures: &Path) -> Arc<dyn Downloader> {
+    let binary = if get_host_os() == Os::Windows {
+        "package/bin/esbuild.exe"
+    } else {
+        "package/bin/esbuild"
+    };
+    let contents = format!("#!/bin/sh\necho {}\n", ESBUILD_VERSION);
+
+    let mut header = tar::Header::new_gnu();
+    header.set_size(contents.len() as u64);
+    header.set_mode(0o755);
+    header.set_cksum();
+
+    let mut archive = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    archive
+        .append_data(&mut header, binary, contents.as_bytes())
+        .unwrap();
+
+    let platform = format!("{}-{}", get_host_os(), get_host_arch());
+    let package_dir = fixtures.join(format!("registry.npmjs.org/@esbuild/{}/-", platform));
+
+    fs::create_dir_all(&package_dir).unwrap();
+    fs::write(
+        package_dir.join(format!("{}-{}.tgz", platform, ESBUILD_VERSION)),
+        archive.into_inner().unwrap().finish().unwrap(),
+    )
+    .unwrap();
+
+    Arc::new(FileDownloader::new(fixtures.to_path_buf()))
+}
 
 /// Test that we can create a new NodeManager
 #[test]
@@ -27,13 +65,14 @@ fn create_esbuild() {
     assert_eq!(lockfile_contents, expected_lockfile_contents);
 }
 
-/// Test we can download node and calculate checksums
+/// Test we can download esbuild and calculate checksums
 #[test]
 fn download_save_unpack_esbuild() {
     let tmp_dir = TempDir::new().unwrap();
     let tmp_path = tmp_dir.path().to_path_buf();
 
     let mut esbuild = ESBuild::new(tmp_path.clone()).unwrap();
+    esbuild.set_downloader(esbuild_fixtures(&tmp_path.join("fixtures")));
 
     let executable_path = esbuild.download(&ESBUILD_VERSION).unwrap();
 
@@ -73,6 +112,7 @@ fn clear_cache() {
     let tmp_path = tmp_dir.path().to_path_buf();
 
     let mut esbuild = ESBuild::new(tmp_path.clone()).unwrap();
+    esbuild.set_downloader(esbuild_fixtures(&tmp_path.join("fixtures")));
 
     let executable_path = esbuild.download(&ESBUILD_VERSION).unwrap();
 
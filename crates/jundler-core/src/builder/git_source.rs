@@ -0,0 +1,41 @@
+/// The URL schemes of git repositories that projects can be built from.
+const GIT_SCHEMES: &[&str] = &["https://", "http://", "ssh://", "git://", "file://"];
+
+/// A project in a git repository, i.e. `https://github.com/org/repo#v1.2.3`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitSource {
+    /// The URL of the repository.
+    pub url: String,
+
+    /// The branch, tag, or commit to build, after the `#` in the URL. The default branch is built if there is none.
+    pub reference: Option<String>,
+}
+
+impl GitSource {
+    /// Parses a git URL, with an optional `#<branch, tag, or commit>` at the end. Also accepts `git+` URLs like in
+    /// `package.json`, and SSH URLs like `git@github.com:org/repo.git`. Returns `None` if it isn't a git URL.
+    pub fn parse(source: &str) -> Option<Self> {
+        let source = source.strip_prefix("git+").unwrap_or(source);
+
+        let (url, reference) = match source.rsplit_once('#') {
+            Some((url, reference)) => (url, Some(reference)),
+            None => (source, None),
+        };
+
+        let is_url = GIT_SCHEMES.iter().any(|scheme| url.starts_with(scheme))
+            || url
+                .split_once(':')
+                .is_some_and(|(host, _)| host.contains('@') && !host.contains('/'));
+
+        if !is_url {
+            return None;
+        }
+
+        Some(Self {
+            url: url.to_string(),
+            reference: reference
+                .filter(|reference| !reference.is_empty())
+                .map(str::to_string),
+        })
+    }
+}
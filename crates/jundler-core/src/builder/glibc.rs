@@ -0,0 +1,262 @@
+//! Works out the oldest glibc that a Linux binary runs on, from the symbol versions it needs (its `.gnu.version_r`
+//! section), and checks it against the distributions the project says it supports. Node.js is built against a
+//! particular glibc, and a binary built with it fails to start on distributions with an older one.
+
+use super::errors::{Classify, ErrorClass};
+use super::Builder;
+use crate::js_config::LinuxConfig;
+use crate::ui::messages::GLIBC_MSG;
+use anyhow::{anyhow, Context, Result};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::str::FromStr;
+
+/// The type of the section with the symbol versions a binary needs.
+const SHT_GNU_VERNEED: u32 = 0x6fff_fffe;
+
+/// The glibc of distributions that binaries are commonly checked against, by the names they're configured with.
+pub const DISTROS: &[(&str, GlibcVersion)] = &[
+    ("centos-7", GlibcVersion::new(2, 17)),
+    ("rhel-7", GlibcVersion::new(2, 17)),
+    ("amazonlinux-2", GlibcVersion::new(2, 26)),
+    ("ubuntu-18.04", GlibcVersion::new(2, 27)),
+    ("debian-10", GlibcVersion::new(2, 28)),
+    ("rhel-8", GlibcVersion::new(2, 28)),
+    ("ubuntu-20.04", GlibcVersion::new(2, 31)),
+    ("debian-11", GlibcVersion::new(2, 31)),
+    ("rhel-9", GlibcVersion::new(2, 34)),
+    ("amazonlinux-2023", GlibcVersion::new(2, 34)),
+    ("ubuntu-22.04", GlibcVersion::new(2, 35)),
+    ("debian-12", GlibcVersion::new(2, 36)),
+    ("ubuntu-24.04", GlibcVersion::new(2, 39)),
+];
+
+/// A version of glibc, i.e. `2.28`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GlibcVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl GlibcVersion {
+    pub const fn new(major: u32, minor: u32) -> GlibcVersion {
+        GlibcVersion { major, minor }
+    }
+
+    /// Gets the glibc of a distribution in [`DISTROS`], by name.
+    pub fn of_distro(distro: &str) -> Option<GlibcVersion> {
+        DISTROS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(distro))
+            .map(|(_, version)| *version)
+    }
+}
+
+impl fmt::Display for GlibcVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+impl FromStr for GlibcVersion {
+    type Err = String;
+
+    /// Parses a version like `2.28`. Patch versions (which symbols are never versioned with) are ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split('.').map(str::parse::<u32>);
+
+        match (parts.next(), parts.next()) {
+            (Some(Ok(major)), Some(Ok(minor))) => Ok(GlibcVersion::new(major, minor)),
+            _ => Err(format!("`{}` isn't a glibc version, i.e. `2.28`", s)),
+        }
+    }
+}
+
+/// Reads the oldest glibc that the ELF binary at `path` runs on: the newest `GLIBC_*` symbol version it needs. `None`
+/// if it isn't a little-endian ELF binary, or doesn't need glibc (i.e. it's static).
+pub fn read_min_glibc(path: &Path) -> io::Result<Option<GlibcVersion>> {
+    let mut file = File::open(path)?;
+
+    let mut header = [0; 64];
+    if file.read(&mut header)? < header.len()
+        || header[..4] != [0x7f, b'E', b'L', b'F']
+        || header[5] != 1
+    {
+        return Ok(None);
+    }
+
+    // Where the section headers are, and how to read them, for 32 and 64-bit binaries
+    let is_64 = match header[4] {
+        1 => false,
+        2 => true,
+        _ => return Ok(None),
+    };
+
+    let (shoff, shentsize, shnum) = if is_64 {
+        (
+            u64_at(&header, 0x28),
+            u16_at(&header, 0x3a),
+            u16_at(&header, 0x3c),
+        )
+    } else {
+        (
+            u32_at(&header, 0x20) as u64,
+            u16_at(&header, 0x2e),
+            u16_at(&header, 0x30),
+        )
+    };
+
+    if shentsize < if is_64 { 64 } else { 40 } {
+        return Ok(None);
+    }
+
+    let mut sections = vec![0; shentsize as usize * shnum as usize];
+    file.seek(SeekFrom::Start(shoff))?;
+    file.read_exact(&mut sections)?;
+
+    // A section's type, offset, size, and linked section
+    let section = |index: usize| {
+        let header = sections.get(index * shentsize as usize..(index + 1) * shentsize as usize)?;
+
+        Some(if is_64 {
+            (
+                u32_at(header, 4),
+                u64_at(header, 24),
+                u64_at(header, 32),
+                u32_at(header, 40),
+            )
+        } else {
+            (
+                u32_at(header, 4),
+                u32_at(header, 16) as u64,
+                u32_at(header, 20) as u64,
+                u32_at(header, 24),
+            )
+        })
+    };
+
+    let Some((_, verneed_offset, verneed_size, strtab)) = (0..shnum as usize)
+        .filter_map(section)
+        .find(|(kind, ..)| *kind == SHT_GNU_VERNEED)
+    else {
+        return Ok(None);
+    };
+
+    let Some((_, strtab_offset, strtab_size, _)) = section(strtab as usize) else {
+        return Ok(None);
+    };
+
+    let mut read_section = |offset: u64, size: u64| -> io::Result<Vec<u8>> {
+        let mut contents = vec![0; size as usize];
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut contents)?;
+        Ok(contents)
+    };
+
+    let verneed = read_section(verneed_offset, verneed_size)?;
+    let strtab = read_section(strtab_offset, strtab_size)?;
+
+    Ok(parse_verneed(&verneed, &strtab))
+}
+
+/// Finds the newest `GLIBC_*` version in a `.gnu.version_r` section, with the names in `strtab`. Every library the
+/// binary links against has an entry, with the versions it needs from that library.
+fn parse_verneed(verneed: &[u8], strtab: &[u8]) -> Option<GlibcVersion> {
+    let name = |offset: u32| {
+        let name = strtab.get(offset as usize..)?;
+        let end = name.iter().position(|&byte| byte == 0)?;
+        std::str::from_utf8(&name[..end]).ok()
+    };
+
+    let mut newest = None;
+    let mut entry = 0;
+
+    loop {
+        let vn = verneed.get(entry..entry + 16)?;
+        let mut aux = entry + u32_at(vn, 8) as usize;
+
+        for _ in 0..u16_at(vn, 2) {
+            let vna = verneed.get(aux..aux + 16)?;
+
+            if let Some(version) = name(u32_at(vna, 8))
+                .and_then(|name| name.strip_prefix("GLIBC_"))
+                .and_then(|version| version.parse::<GlibcVersion>().ok())
+            {
+                newest = newest.max(Some(version));
+            }
+
+            aux += u32_at(vna, 12) as usize;
+        }
+
+        match u32_at(vn, 12) {
+            0 => return newest,
+            next => entry += next as usize,
+        }
+    }
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn u64_at(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+impl Builder {
+    /// Reports the oldest glibc that the Linux binary at `app_path` runs on, and warns about every distribution in
+    /// `linux.distros` that has an older one. Returns that glibc, if the binary needs it.
+    pub(super) fn check_glibc(
+        &mut self,
+        app_path: &Path,
+        config: &LinuxConfig,
+    ) -> Result<Option<GlibcVersion>> {
+        let distros = config
+            .distros
+            .iter()
+            .map(|distro| {
+                GlibcVersion::of_distro(distro).map(|glibc| (distro, glibc)).ok_or_else(|| {
+                    anyhow!(
+                        "`linux.distros` has `{}`, which jundler doesn't know the glibc of! Use one of: {}.",
+                        distro,
+                        DISTROS.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(", ")
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>>>()
+            .classify(ErrorClass::Config)?;
+
+        let spinner = self.tracker.spawn_spinner(GLIBC_MSG, 0);
+
+        let min_glibc = read_min_glibc(app_path).context(format!(
+            "Error reading the symbol versions of the built binary at {}",
+            app_path.display()
+        ))?;
+
+        spinner.close();
+
+        let Some(min_glibc) = min_glibc else {
+            return Ok(None);
+        };
+
+        self.tracker
+            .println(format!("The binary needs glibc {} or newer.", min_glibc));
+
+        for (distro, glibc) in distros {
+            if glibc < min_glibc {
+                self.tracker.warn(format!(
+                    "Warning: The binary won't run on {}, which has glibc {}, but it needs glibc {}. Build with an older Node.js version (or one built for older glibc) to support it.",
+                    distro, glibc, min_glibc
+                ));
+            }
+        }
+
+        Ok(Some(min_glibc))
+    }
+}
@@ -0,0 +1,1753 @@
+use super::archive::{archive_stem, create_archive, extra_files, ArchiveEntry, ArchiveFormat};
+use super::checksums::checksums_for;
+use super::container;
+use super::copy::{copy_install_files, copy_project};
+use super::environment::SanitizedEnv;
+use super::errors::{Classify, ErrorClass};
+use super::esbuild::ESBUILD_VERSION;
+use super::licenses::{
+    bundled_bytes, bundled_files, included_packages, installed_files, notices, NOTICES_FILE_NAME,
+};
+use super::linux_packages::{self, LinuxPackageFormat, PackageMetadata};
+use super::metadata::SEA_FUSE;
+use super::msi;
+use super::native::{asset_key, find_native_addons, loader_shim};
+use super::node_manager::HostNode;
+use super::npmrc::NpmConfig;
+use super::output_name::command_name;
+use super::package_manager::PackageManager;
+use super::platforms::{get_host_arch, get_host_os, Arch, Os};
+use super::project_lock::esbuild_binary_name;
+use super::report::{BundleContents, BUNDLE_CONTENTS_FILE_NAME};
+use super::reproducible::SOURCE_DATE_EPOCH_VAR;
+use super::runtime::Runtime;
+use super::{output_excludes, should_bundle, BuildOptions, Builder, WindowsSignOptions};
+use crate::js_config::{MacOSConfig, NotarizeConfig, PackageConfig, SEAConfig, WindowsConfig};
+use crate::ui::messages::{
+    BUNDLING_MSG, ESBUILD_BINARY_MSG, INSTALL_DEPS_MSG, RESTORE_DEPS_MSG, STRIP_MSG,
+};
+use crate::ui::{OutputLine, Spinner};
+use anyhow::{anyhow, Context, Result};
+use log::debug;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Output, Stdio};
+use std::sync::{mpsc, Mutex, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{env, fs, io, iter, thread};
+
+/// On Unix-based systems, make the binary executable.
+#[cfg(unix)]
+pub fn make_executable(binary_path: &Path) -> Result<(), io::Error> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut perms = binary_path.metadata()?.permissions();
+
+    perms.set_mode(0o755);
+
+    fs::set_permissions(binary_path, perms)?;
+
+    Ok(())
+}
+
+/// On Windows, there's nothing to do: anything with an `.exe` extension can be run.
+#[cfg(not(unix))]
+pub fn make_executable(_binary_path: &Path) -> Result<(), io::Error> {
+    Ok(())
+}
+
+/// The size of the chunks that files are read in to checksum them. Big enough that even Node.js binaries only take a
+/// few hundred reads.
+const CHECKSUM_CHUNK_SIZE: usize = 1 << 20;
+
+/// The checksums already calculated in this process, by path. Cached binaries are checked several times in every build,
+/// but hardly ever change.
+static CHECKSUMS: Mutex<BTreeMap<PathBuf, KnownChecksum>> = Mutex::new(BTreeMap::new());
+
+/// The checksum of a file, along with the size and modification time the file had when it was calculated.
+struct KnownChecksum {
+    size: u64,
+    modified: SystemTime,
+    checksum: [u8; 32],
+}
+
+/// Calculate the SHA256 checksum of a file. Expects that the file is readable. If the file has the same size and
+/// modification time as the last time its checksum was calculated, that checksum is reused instead of reading it again.
+pub fn calculate_checksum(path: &Path) -> Result<[u8; 32], io::Error> {
+    let file = File::open(path)?;
+
+    let metadata = file.metadata()?;
+    let modified = metadata.modified().ok();
+
+    // Filesystems without modification times can't tell if the file changed, so those are always read
+    if let Some(modified) = modified {
+        let checksums = CHECKSUMS.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if let Some(known) = checksums
+            .get(path)
+            .filter(|known| (known.size, known.modified) == (metadata.len(), modified))
+        {
+            return Ok(known.checksum);
+        }
+    }
+
+    // Prepare the hasher
+    let mut hasher = Sha256::new();
+    let mut reader = BufReader::with_capacity(CHECKSUM_CHUNK_SIZE, file);
+
+    loop {
+        let chunk = reader.fill_buf()?;
+
+        if chunk.is_empty() {
+            break;
+        }
+
+        hasher.update(chunk);
+
+        let read = chunk.len();
+        reader.consume(read);
+    }
+
+    // Output the hash and convert it into a 32-byte array
+    let checksum: [u8; 32] = hasher.finalize().into();
+
+    if let Some(modified) = modified {
+        CHECKSUMS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(
+                path.to_path_buf(),
+                KnownChecksum {
+                    size: metadata.len(),
+                    modified,
+                    checksum,
+                },
+            );
+    }
+
+    Ok(checksum)
+}
+
+/// The name of the entrypoint written when shims are added to the application (i.e. to load native addons), which runs
+/// the shims before the original entrypoint.
+const ENTRYPOINT: &str = "jundler-entrypoint.js";
+
+/// The name of the metafile esbuild writes, which lists every file included in the bundle.
+pub(super) const ESBUILD_METAFILE: &str = "jundler-esbuild-meta.json";
+
+/// The entitlements macOS binaries are signed with when they use the hardened runtime, unless the project has its own.
+/// V8 needs to JIT-compile code, and native addons aren't signed with the same identity as the binary.
+const DEFAULT_ENTITLEMENTS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>com.apple.security.cs.allow-jit</key>
+    <true/>
+    <key>com.apple.security.cs.allow-unsigned-executable-memory</key>
+    <true/>
+    <key>com.apple.security.cs.disable-library-validation</key>
+    <true/>
+</dict>
+</plist>
+"#;
+
+/// The environment variable with the password of the PFX file to sign Windows binaries with, unless the project
+/// names another one.
+const CERTIFICATE_PASSWORD_VAR: &str = "JUNDLER_CERTIFICATE_PASSWORD";
+
+/// The files that determine which dependencies get installed for a project: its manifest, lockfiles, and the package
+/// managers' configuration (which can change the registry or how `node_modules` is laid out).
+const DEPENDENCY_MANIFESTS: &[&str] = &[
+    "package.json",
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "bun.lockb",
+    "bun.lock",
+    ".npmrc",
+    ".yarnrc.yml",
+];
+
+/// Hash all of the dependency manifests (`package.json`, any lockfiles, and package manager configuration) in a
+/// project directory, along with the user-level `.npmrc` if there is one.
+pub fn dependency_manifests_hash(project_dir: &Path, userconfig: Option<&Path>) -> Result<String> {
+    let mut hasher = Sha256::new();
+
+    let manifests = DEPENDENCY_MANIFESTS
+        .iter()
+        .map(|manifest| (*manifest, project_dir.join(manifest)))
+        .chain(userconfig.map(|path| ("userconfig", path.to_path_buf())));
+
+    for (manifest, path) in manifests {
+        if path.exists() {
+            hasher.update(manifest);
+            hasher.update(
+                calculate_checksum(&path)
+                    .context(format!("Error calculating checksum of {}", path.display()))?,
+            );
+        }
+    }
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Remove everything inside of `dir`, except for the entries named in `keep`.
+fn clear_dir_except(dir: &Path, keep: &[&str]) -> Result<(), io::Error> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+
+        if keep.iter().any(|name| entry.file_name() == *name) {
+            continue;
+        }
+
+        if entry.file_type()?.is_dir() {
+            fs::remove_dir_all(entry.path())?;
+        } else {
+            fs::remove_file(entry.path())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether a tool is installed, by checking that `<tool> --version` runs.
+pub fn is_available(tool: &str) -> bool {
+    Command::new(tool)
+        .arg("--version")
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+/// Find a tool that can strip binaries for the target OS. `llvm-strip` handles every format, but the system `strip`
+/// can only be trusted with binaries for the host OS.
+fn find_strip_tool(target_os: Os) -> Option<&'static str> {
+    if is_available("llvm-strip") {
+        Some("llvm-strip")
+    } else if get_host_os() == target_os {
+        // BSD `strip` on macOS doesn't have `--version`, so just assume it's there.
+        Some("strip")
+    } else {
+        None
+    }
+}
+
+/// Runs commands like `Command::output`, logging the command line first and what it printed after.
+pub trait LoggedOutput {
+    /// Runs the command, collecting everything it prints. The command line and its output are logged at the debug
+    /// level.
+    fn logged_output(&mut self) -> io::Result<Output>;
+}
+
+impl LoggedOutput for Command {
+    fn logged_output(&mut self) -> io::Result<Output> {
+        debug!("Running {}", command_line(self));
+
+        let output = self.output()?;
+
+        for line in String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .chain(String::from_utf8_lossy(&output.stderr).lines())
+        {
+            debug!("{}", line);
+        }
+
+        Ok(output)
+    }
+}
+
+/// Gets the command line a command runs, with any arguments that have spaces in them quoted.
+pub fn command_line(cmd: &Command) -> String {
+    iter::once(cmd.get_program())
+        .chain(cmd.get_args())
+        .map(|arg| {
+            let arg = arg.to_string_lossy();
+
+            if arg.is_empty() || arg.contains(char::is_whitespace) {
+                format!("{:?}", arg)
+            } else {
+                arg.into_owned()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Run a command, streaming each line it prints (on stdout or stderr) to `output_line` as it runs. Returns the exit
+/// status of the command, along with everything it printed.
+pub fn run_streaming(
+    cmd: &mut Command,
+    output_line: &OutputLine,
+) -> Result<(ExitStatus, String), io::Error> {
+    run_streaming_with(cmd, output_line, |_| {})
+}
+
+/// Like `run_streaming`, but also calls `on_line` with each line the command prints, i.e. to update a spinner with it.
+pub fn run_streaming_with<F>(
+    cmd: &mut Command,
+    output_line: &OutputLine,
+    mut on_line: F,
+) -> Result<(ExitStatus, String), io::Error>
+where
+    F: FnMut(&str),
+{
+    debug!("Running {}", command_line(cmd));
+
+    let mut child = cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+
+    let (tx, rx) = mpsc::channel();
+
+    let stdout = child
+        .stdout
+        .take()
+        .map(|r| Box::new(r) as Box<dyn Read + Send>);
+    let stderr = child
+        .stderr
+        .take()
+        .map(|r| Box::new(r) as Box<dyn Read + Send>);
+
+    // Read stdout and stderr on their own threads, so neither pipe can fill up and block the command.
+    for reader in [stdout, stderr].into_iter().flatten() {
+        let tx = tx.clone();
+
+        thread::spawn(move || {
+            for line in BufReader::new(reader).lines().map_while(Result::ok) {
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    // Drop our sender, so the channel closes once both readers are done.
+    drop(tx);
+
+    let mut output = String::new();
+
+    for line in rx {
+        output_line.update(&line);
+        on_line(&line);
+        output.push_str(&line);
+        output.push('\n');
+    }
+
+    Ok((child.wait()?, output))
+}
+
+/// Finds the package a line of package manager output is about, like `lodash@4.17.21` in `+ lodash@4.17.21`.
+pub fn installed_package(line: &str) -> Option<&str> {
+    line.split_whitespace()
+        .map(|word| word.trim_start_matches('+'))
+        .find(|word| {
+            // Scoped packages start with an `@` too, so look for the one before the version
+            let Some((name, version)) = word.get(1..).and_then(|rest| rest.split_once('@')) else {
+                return false;
+            };
+
+            !name.is_empty()
+                && version.starts_with(|c: char| c.is_ascii_digit())
+                && word
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "@/._-+".contains(c))
+        })
+}
+
+/// Puts `shim` in front of the script `main`. A hashbang (`#!/usr/bin/env node`) is only valid on the first line of a
+/// script, so if `main` starts with one, it's kept there, and the shim goes right after it.
+pub fn prepend_shim(main: &str, shim: &str) -> String {
+    if !main.starts_with("#!") {
+        return format!("{}\n{}", shim, main);
+    }
+
+    let (hashbang, rest) = main.split_once('\n').unwrap_or((main, ""));
+
+    format!("{}\n{}\n{}", hashbang, shim, rest)
+}
+
+/// The environment to pass to child processes so that they use the same timestamp as the rest of a reproducible build.
+/// Empty if the build isn't reproducible.
+fn source_date_epoch_env(options: &BuildOptions) -> Option<(&'static str, String)> {
+    options
+        .source_date_epoch()
+        .map(|epoch| (SOURCE_DATE_EPOCH_VAR, epoch.to_string()))
+}
+
+// Private helper functions to do steps of the build process
+impl Builder {
+    /// Copy the project to the build directory, into a project folder.
+    pub(super) fn copy_and_prepare_project(
+        &mut self,
+        original_project_dir: &Path,
+        package_config: &PackageConfig,
+        options: &BuildOptions,
+    ) -> Result<()> {
+        let project_dir = self.working_dir.path().join("project");
+
+        // A previous build (i.e. in watch mode) may have left a copy of the project behind. Clear it out, but keep the
+        // installed `node_modules` so it can be reused if the dependencies haven't changed.
+        if project_dir.exists() {
+            clear_dir_except(&project_dir, &["node_modules"]).context(format!(
+                "Error clearing temporary project directory at {}",
+                project_dir.display()
+            ))?;
+        } else {
+            // Create the project directory in the build directory
+            fs::create_dir(&project_dir).context(format!(
+                "Error creating temporary project directory at {}",
+                project_dir.display()
+            ))?;
+        }
+
+        // Copy the project to the build directory, skipping anything that's excluded. If it's bundled in place, only
+        // what installing its dependencies needs is.
+        let copy = if self.bundles_in_place(original_project_dir, package_config, options) {
+            copy_install_files
+        } else {
+            copy_project
+        };
+
+        copy(
+            original_project_dir,
+            &project_dir,
+            &output_excludes(package_config, options),
+            options.copy_strategy,
+        )
+        .context(format!(
+            "Error copying project from {} to {}",
+            original_project_dir.display(),
+            project_dir.display()
+        ))?;
+
+        // The install needs the lockfile that decided the package manager, even if it's a workspace's
+        PackageManager::copy_workspace_lockfile(original_project_dir, &project_dir)
+            .context("Error copying the workspace lockfile into the build directory")?;
+
+        Ok(())
+    }
+
+    /// Whether the project is bundled with ESBuild straight from `original_project_dir`, instead of from a full copy
+    /// of it. Its dependencies are still installed in the build directory, but only what that needs is copied. Custom
+    /// bundlers always get a full copy, as do projects whose install needs their sources (see
+    /// `PackageConfig::install_needs_sources`). Reproducible builds do too, as ESBuild would name the build directory
+    /// in the bundle. So do projects with a `node_modules` of their own, or in a directory above them, as ESBuild finds
+    /// packages there before the ones installed for the target in the build directory.
+    pub(super) fn bundles_in_place(
+        &self,
+        original_project_dir: &Path,
+        package_config: &PackageConfig,
+        options: &BuildOptions,
+    ) -> bool {
+        options.runtime == Runtime::Node
+            && self.bundler.is_none()
+            && !options.reproducible
+            && should_bundle(package_config, options)
+            && !package_config.install_needs_sources()
+            && !original_project_dir.join("pnpm-workspace.yaml").exists()
+            && !has_node_modules(original_project_dir)
+    }
+
+    /// Install the dependencies of the copied project with its package manager, streaming the output to the UI. If
+    /// the project has a lockfile, and its dependencies haven't changed since they were last installed for the target,
+    /// the `node_modules` from then are restored from the dependency cache instead.
+    pub(super) fn install_dependencies(
+        &mut self,
+        original_project_dir: &Path,
+        package_config: &PackageConfig,
+        host_node: &HostNode,
+        options: &BuildOptions,
+        parent: &mut Spinner,
+    ) -> Result<()> {
+        let project_dir = self.working_dir.path().join("project");
+
+        let package_manager = options
+            .package_manager
+            .unwrap_or_else(|| PackageManager::detect(original_project_dir));
+
+        // The version the project pins is only used if it's for the package manager that's installing
+        let pinned = PackageManager::pinned(original_project_dir)
+            .filter(|(pinned, _)| *pinned == package_manager)
+            .map(|(_, spec)| spec);
+
+        let npm_config = NpmConfig::find(original_project_dir, options.npmrc.as_deref())
+            .classify(ErrorClass::Config)?;
+
+        npm_config.copy_into(&project_dir)?;
+
+        // Skip installing if the dependencies are the same as the last install in this build directory
+        let installed_deps = format!(
+            "{}-{}-{}-{}-{}-{}-{}",
+            dependency_manifests_hash(&project_dir, npm_config.userconfig.as_deref())?,
+            package_manager,
+            options.target_os,
+            options.target_arch,
+            options.node_version,
+            options.reproducible || options.frozen,
+            env!("CARGO_PKG_VERSION")
+        );
+
+        if self.installed_deps.as_ref() == Some(&installed_deps) {
+            debug!("Dependencies unchanged since the last install, skipping install");
+            return Ok(());
+        }
+
+        // Installs without a lockfile can resolve different versions every time, and installs that run the project's
+        // own scripts can write more than `node_modules`, so neither are cached
+        let cacheable = PackageManager::find_lockfile(&project_dir).is_some()
+            && !package_config.install_needs_sources();
+
+        if cacheable {
+            let spinner = self.tracker.spawn_child(parent, RESTORE_DEPS_MSG);
+
+            let restored = self.deps_cache.restore(
+                original_project_dir,
+                options,
+                &installed_deps,
+                &project_dir,
+            );
+
+            match restored {
+                Ok(true) => {
+                    spinner.close();
+
+                    self.installed_deps = Some(installed_deps);
+
+                    return Ok(());
+                }
+
+                Ok(false) => spinner.close(),
+
+                // Anything half-restored is removed, and the dependencies are installed as if nothing was cached
+                Err(err) => {
+                    spinner.close();
+
+                    self.tracker.warn(format!(
+                        "Warning: Could not restore the dependencies from the cache, so they're installed instead: {:#}",
+                        err
+                    ));
+
+                    if project_dir.join("node_modules").exists() {
+                        fs::remove_dir_all(project_dir.join("node_modules"))?;
+                    }
+                }
+            }
+        }
+
+        let message = format!("{} {}", INSTALL_DEPS_MSG, package_manager);
+        let mut spinner = self.tracker.spawn_child(parent, &message);
+        let output_line = self.tracker.spawn_output_line();
+
+        // Install any and all packages required for the project, showing the latest one next to the spinner
+        let installed = run_streaming_with(
+            npm_config.apply(
+                package_manager
+                    .install_command(
+                        host_node,
+                        &project_dir,
+                        options.target_os,
+                        options.target_arch,
+                        options.reproducible || options.frozen,
+                        pinned.as_deref(),
+                    )
+                    .envs(source_date_epoch_env(options)),
+                &options.pass_env,
+            ),
+            &output_line,
+            |line| {
+                if let Some(package) = installed_package(line) {
+                    spinner.set_message(format!("{} ({})", message, package));
+                }
+            },
+        )
+        .context(format!("Error running {} install", package_manager))
+        .and_then(|(status, output)| match status.success() {
+            true => Ok(()),
+            false => Err(anyhow!(
+                "Error running {} install:\n{}",
+                package_manager,
+                output
+            )),
+        });
+
+        output_line.close();
+        spinner.finish(installed)?;
+
+        // Frozen builds leave the cache as it is, and a dependency cache that can't be written to only slows builds
+        if cacheable && !options.frozen {
+            if let Err(err) =
+                self.deps_cache
+                    .insert(original_project_dir, options, &installed_deps, &project_dir)
+            {
+                self.tracker.warn(format!(
+                    "Warning: Could not cache the installed dependencies: {:#}",
+                    err
+                ));
+            }
+        }
+
+        self.installed_deps = Some(installed_deps);
+
+        Ok(())
+    }
+
+    /// Bundle the project using `esbuild` (or the custom bundler, if there is one) if desired by the user.
+    pub(super) fn bundle_project(
+        &mut self,
+        original_project_dir: &Path,
+        package_config: &PackageConfig,
+        sea_config: &mut SEAConfig,
+        options: &BuildOptions,
+        parent: &mut Spinner,
+    ) -> Result<()> {
+        let entrypoint = package_config.main.as_ref().unwrap_or(&sea_config.main);
+
+        if let Some(bundler) = self.bundler.clone() {
+            let spinner = self.tracker.spawn_child(parent, BUNDLING_MSG);
+            let project_dir = self.working_dir.path().join("project");
+
+            bundler
+                .bundle(
+                    &project_dir,
+                    Path::new(entrypoint),
+                    &project_dir.join("bundled.js"),
+                )
+                .context("Error bundling project with the custom bundler")?;
+
+            spinner.close();
+
+            return self.use_bundle(sea_config);
+        }
+
+        // Get the ESBuild binary
+        let spinner = self.tracker.spawn_child(parent, ESBUILD_BINARY_MSG);
+
+        let esbuild_bin = self.esbuild.get_binary()?;
+
+        self.check_pin(
+            &esbuild_binary_name(&ESBUILD_VERSION, get_host_os(), get_host_arch()),
+            &esbuild_bin,
+            options.frozen,
+        )?;
+
+        spinner.close();
+
+        let spinner = self.tracker.spawn_child(parent, BUNDLING_MSG);
+
+        let project_dir = self.working_dir.path().join("project");
+
+        // Bundled in place, the sources are read from the original project, and the dependencies installed in the build
+        // directory are found through `NODE_PATH`
+        let in_place = self.bundles_in_place(original_project_dir, package_config, options);
+        let bundle_dir = if in_place {
+            original_project_dir
+        } else {
+            &project_dir
+        };
+
+        // Run the esbuild command
+        let esbuild_cmd_output = Command::new(esbuild_bin)
+            .current_dir(bundle_dir) // Run the command in the project directory
+            .arg(entrypoint) // Use the main entrypoint from the package.json file, or the default from the sea-config.json file
+            .arg("--bundle")
+            .arg("--platform=node") // Bundle for Node.js
+            .arg("--external:*.node") // Native addons are loaded at runtime, see `embed_native_addons`
+            .arg(format!(
+                "--outfile={}",
+                project_dir.join("bundled.js").display()
+            )) // Output to `bundled.js` in the build directory
+            .arg(format!(
+                "--metafile={}",
+                project_dir.join(ESBUILD_METAFILE).display()
+            )) // List the bundled files, see `collect_third_party_notices`
+            .envs(in_place.then(|| ("NODE_PATH", project_dir.join("node_modules"))))
+            .envs(source_date_epoch_env(options))
+            .sanitized_env(&options.pass_env)
+            .logged_output()
+            .context("Error bundling project with esbuild")?;
+
+        if !esbuild_cmd_output.status.success() {
+            return Err(anyhow!(
+                "Error bundling project with esbuild:\n{}\n{}",
+                String::from_utf8_lossy(&esbuild_cmd_output.stdout),
+                String::from_utf8_lossy(&esbuild_cmd_output.stderr)
+            ));
+        }
+
+        spinner.close();
+
+        // The assets weren't copied either, so they're embedded straight from the original project
+        if in_place {
+            let original_project_dir =
+                std::path::absolute(original_project_dir).context(format!(
+                    "Error getting the absolute path of {}",
+                    original_project_dir.display()
+                ))?;
+
+            for path in sea_config.assets.values_mut() {
+                *path = original_project_dir.join(&*path).to_string_lossy().into();
+            }
+        }
+
+        self.use_bundle(sea_config)
+    }
+
+    /// Rewrites `sea-config.json` to point to the bundled file, `bundled.js`.
+    fn use_bundle(&mut self, sea_config: &mut SEAConfig) -> Result<()> {
+        let new_sea_config = SEAConfig {
+            main: "bundled.js".to_string(),
+            ..sea_config.clone()
+        };
+
+        *sea_config = new_sea_config;
+
+        self.write_sea_config(sea_config)
+    }
+
+    /// Embed any native addons in the project as SEA assets. Returns the loader shim to prepend to the entrypoint so
+    /// they can be required at runtime, if there were any.
+    pub(super) fn embed_native_addons(
+        &mut self,
+        sea_config: &mut SEAConfig,
+        options: &BuildOptions,
+    ) -> Result<Option<String>> {
+        let project_dir = self.working_dir.path().join("project");
+
+        let addons = find_native_addons(&project_dir, options.target_os, options.target_arch)?;
+
+        if addons.is_empty() {
+            return Ok(None);
+        }
+
+        self.tracker.warn(format!(
+            "Embedding {} native addon(s) as SEA assets. They will be extracted to a temporary directory at runtime.",
+            addons.len()
+        ));
+
+        let mut asset_keys = vec![];
+
+        for addon in addons {
+            let key = asset_key(&addon);
+
+            debug!("Embedding native addon {}", key);
+
+            sea_config.assets.insert(
+                key.clone(),
+                project_dir.join(&addon).to_string_lossy().into(),
+            );
+            asset_keys.push(key);
+        }
+
+        Ok(Some(loader_shim(&asset_keys)))
+    }
+
+    /// Collect the third-party notices for every dependency included in the application, writing them to the build
+    /// directory. If the project was bundled, only the packages esbuild actually included are listed. Otherwise, only
+    /// the packages of embedded native addons are, as nothing else in `node_modules` ends up in the SEA blob. If they're
+    /// embedded, they're added as an asset, for the licenses shim to print.
+    pub(super) fn collect_third_party_notices(
+        &mut self,
+        original_project_dir: &Path,
+        package_config: &PackageConfig,
+        sea_config: &mut SEAConfig,
+        bundled: bool,
+        options: &BuildOptions,
+    ) -> Result<PathBuf> {
+        let project_dir = self.working_dir.path().join("project");
+
+        let mut included_files =
+            find_native_addons(&project_dir, options.target_os, options.target_arch)?;
+
+        if bundled {
+            included_files.extend(match self.bundler {
+                Some(_) => installed_files(&project_dir),
+                None => bundled_files(
+                    &project_dir.join(ESBUILD_METAFILE),
+                    &self.bundle_dir(original_project_dir, package_config, options),
+                    &project_dir,
+                )?,
+            });
+        }
+
+        let packages = included_packages(&project_dir, &included_files)?;
+
+        debug!("Found {} third-party packages in the build", packages.len());
+
+        let notices_path = self.working_dir.path().join(NOTICES_FILE_NAME);
+
+        fs::write(&notices_path, notices(&project_dir, &packages)?).context(format!(
+            "Error writing third-party notices to {}",
+            notices_path.display()
+        ))?;
+
+        if options.embed_licenses {
+            sea_config.assets.insert(
+                NOTICES_FILE_NAME.to_string(),
+                notices_path.to_string_lossy().into(),
+            );
+        }
+
+        Ok(notices_path)
+    }
+
+    /// Writes what ESBuild put in the bundle to the build directory, so that it's kept with the build's report and can
+    /// be compared between builds. Returns the path to the file.
+    pub(super) fn write_bundle_contents(
+        &self,
+        original_project_dir: &Path,
+        package_config: &PackageConfig,
+        options: &BuildOptions,
+    ) -> Result<PathBuf> {
+        let project_dir = self.working_dir.path().join("project");
+
+        let inputs = bundled_bytes(
+            &project_dir.join(ESBUILD_METAFILE),
+            &self.bundle_dir(original_project_dir, package_config, options),
+            &project_dir,
+        )?;
+
+        let files: Vec<PathBuf> = inputs.keys().cloned().collect();
+        let mut packages: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+        for package in included_packages(&project_dir, &files)? {
+            packages
+                .entry(package.name)
+                .or_default()
+                .insert(package.version);
+        }
+
+        let contents = BundleContents {
+            inputs: inputs
+                .into_iter()
+                // Windows paths are written with forward slashes, so builds from any host can be compared
+                .map(|(path, size)| (path.to_string_lossy().replace('\\', "/"), size))
+                .collect(),
+            packages,
+        };
+
+        let contents_path = self.working_dir.path().join(BUNDLE_CONTENTS_FILE_NAME);
+        contents.write(&contents_path)?;
+
+        Ok(contents_path)
+    }
+
+    /// Gets the directory ESBuild runs in: the original project if it's bundled in place, or the copy in the build
+    /// directory otherwise.
+    fn bundle_dir(
+        &self,
+        original_project_dir: &Path,
+        package_config: &PackageConfig,
+        options: &BuildOptions,
+    ) -> PathBuf {
+        if self.bundles_in_place(original_project_dir, package_config, options) {
+            original_project_dir.to_path_buf()
+        } else {
+            self.working_dir.path().join("project")
+        }
+    }
+
+    /// Write a new entrypoint with a shim in front of the current one, and point `sea-config.json` to it. Every shim
+    /// of a build is prepended at once, so the entrypoint is only rewritten once.
+    pub(super) fn prepend_to_entrypoint(
+        &self,
+        sea_config: &mut SEAConfig,
+        shim: &str,
+    ) -> Result<()> {
+        let project_dir = self.working_dir.path().join("project");
+
+        let main_path = project_dir.join(&sea_config.main);
+        let main = fs::read_to_string(&main_path)
+            .context(format!("Error reading entrypoint {}", main_path.display()))?;
+
+        let new_main_path = project_dir.join(ENTRYPOINT);
+        fs::write(&new_main_path, prepend_shim(&main, shim)).context(format!(
+            "Error writing entrypoint {}",
+            new_main_path.display()
+        ))?;
+
+        sea_config.main = ENTRYPOINT.to_string();
+
+        self.write_sea_config(sea_config)
+    }
+
+    /// Write a new `sea-config.json` to the project in the build directory.
+    pub(super) fn write_sea_config(&self, sea_config: &SEAConfig) -> Result<()> {
+        let sea_config_path = self
+            .working_dir
+            .path()
+            .join("project")
+            .join("sea-config.json");
+
+        let sea_config_file =
+            File::create(&sea_config_path).context("Error creating new `sea-config.json` file")?;
+
+        serde_json::to_writer_pretty(sea_config_file, sea_config).context(format!(
+            "Error writing new `sea-config.json` file to {}",
+            sea_config_path.display()
+        ))?;
+
+        Ok(())
+    }
+
+    /// Generate the SEA blob for the Node.js binary.
+    pub(super) fn gen_sea_blob(
+        &self,
+        host_node_bin: &Path,
+        sea_config: SEAConfig,
+        options: &BuildOptions,
+    ) -> Result<PathBuf> {
+        // Get the path to `sea-config.json` from `{build-dir}/project/sea-config.json` because we want to use the
+        // configuration that points to the bundled file IF the project was bundled (which is modified in the project
+        // directory). Otherwise, this is the same as the original `sea-config.json` file.
+        let sea_conf_path = self
+            .working_dir
+            .path()
+            .join("project")
+            .join("sea-config.json");
+        // Generate the SEA blob
+        let sea_blob_cmd_output = Command::new(host_node_bin)
+            .current_dir(self.working_dir.path().join("project")) // Run the command in the project directory
+            .arg("--experimental-sea-config")
+            .arg(sea_conf_path)
+            .envs(source_date_epoch_env(options))
+            .sanitized_env(&options.pass_env)
+            .logged_output()
+            .context("Error generating SEA blob file")?;
+
+        if !sea_blob_cmd_output.status.success() {
+            return Err(anyhow!(
+                "Error generating SEA blob file:\n{}\n{}",
+                String::from_utf8_lossy(&sea_blob_cmd_output.stdout),
+                String::from_utf8_lossy(&sea_blob_cmd_output.stderr)
+            ));
+        }
+
+        let sea_blob = self
+            .working_dir
+            .path()
+            .join("project") // Expect the sea blob to be in the project directory
+            .join(&sea_config.output);
+
+        let new_sea_blob_path = self.working_dir.path().join(&sea_config.output);
+
+        // Move the sea blob to the build directory
+        fs::rename(sea_blob, &new_sea_blob_path)
+            .context("Error moving SEA blob file to build directory")?;
+
+        Ok(new_sea_blob_path)
+    }
+
+    /// Injects the app into the node binary, running `postject` with the host Node.js's npm.
+    pub(super) fn inject_app(
+        &self,
+        host_node: &HostNode,
+        node_bin: &Path,
+        sea_blob: &Path,
+        options: &BuildOptions,
+    ) -> Result<()> {
+        // Run the postject command
+        let postject_cmd_output = host_node
+            .npm()
+            .current_dir(&self.working_dir)
+            .arg("exec")
+            .arg("--yes")
+            .arg("--")
+            .arg("postject")
+            .arg(node_bin)
+            .arg("NODE_SEA_BLOB")
+            .arg(sea_blob)
+            .arg("--sentinel-fuse")
+            .arg(SEA_FUSE)
+            .args(if options.target_os == Os::MacOS {
+                &["--macho-segment-name", "NODE_SEA"]
+            } else {
+                &["", ""]
+            })
+            .sanitized_env(&options.pass_env)
+            .logged_output()
+            .context("Error injecting app into node binary")?;
+
+        if !postject_cmd_output.status.success() {
+            return Err(anyhow!(
+                "Error injecting app into node binary:\n{}\n{}\n",
+                String::from_utf8_lossy(&postject_cmd_output.stdout).trim(),
+                String::from_utf8_lossy(&postject_cmd_output.stderr).trim()
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Strip symbols from the target Node.js binary to reduce its size. Must be done before injecting the app, as
+    /// stripping can mess with the injected blob. Skips stripping with a warning if there's no suitable tool.
+    pub(super) fn strip_binary(&mut self, binary: &Path, target_os: Os) -> Result<()> {
+        if target_os == Os::Windows {
+            self.tracker
+                .warn("Not stripping the binary because Windows binaries don't contain symbols.");
+            return Ok(());
+        }
+
+        let Some(strip_tool) = find_strip_tool(target_os) else {
+            self.tracker.warn(format!(
+                "Not stripping the binary because neither `llvm-strip` nor a `strip` for {} was found.",
+                target_os
+            ));
+            return Ok(());
+        };
+
+        let spinner = self.tracker.spawn_spinner(STRIP_MSG, 0);
+
+        let strip_cmd_output = Command::new(strip_tool)
+            // Native addons link against Node's exported symbols, so only strip what's safe to strip for each
+            // format. `--strip-all` keeps the dynamic symbol table on ELF, and `-x` keeps global symbols on Mach-O.
+            .arg(if target_os == Os::MacOS {
+                "-x"
+            } else {
+                "--strip-all"
+            })
+            .arg(binary)
+            .logged_output()
+            .context("Error stripping the binary")?;
+
+        if !strip_cmd_output.status.success() {
+            return Err(anyhow!(
+                "Error stripping the binary:\n{}\n{}",
+                String::from_utf8_lossy(&strip_cmd_output.stdout),
+                String::from_utf8_lossy(&strip_cmd_output.stderr)
+            ));
+        }
+
+        spinner.close();
+
+        Ok(())
+    }
+
+    /// Combine single-architecture macOS binaries into a universal binary at `output`. Uses `lipo` on macOS, and
+    /// `llvm-lipo` everywhere else.
+    pub(super) fn lipo(&self, slices: &[PathBuf], output: &Path) -> Result<()> {
+        let lipo_tool = if get_host_os() == Os::MacOS {
+            "lipo"
+        } else {
+            "llvm-lipo"
+        };
+
+        let lipo_cmd_output = Command::new(lipo_tool)
+            .arg("-create")
+            .args(slices)
+            .arg("-output")
+            .arg(output)
+            .logged_output()
+            .context(format!(
+                "Error running `{}` to create a universal binary",
+                lipo_tool
+            ))?;
+
+        if !lipo_cmd_output.status.success() {
+            return Err(anyhow!(
+                "Error creating universal binary:\n{}\n{}",
+                String::from_utf8_lossy(&lipo_cmd_output.stdout),
+                String::from_utf8_lossy(&lipo_cmd_output.stderr)
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Packages the built binary into a Linux package in the same directory. Returns the path to the package.
+    pub(super) fn package_linux(
+        &self,
+        project_dir: &Path,
+        app_path: &Path,
+        package_config: &PackageConfig,
+        options: &BuildOptions,
+        format: LinuxPackageFormat,
+    ) -> Result<PathBuf> {
+        let metadata = PackageMetadata::new(package_config, format)?;
+        let package_path = app_path.with_file_name(metadata.file_name(format, options.target_arch));
+
+        // The binary is installed by the command's name, whatever it's named in the output directory
+        let app_name = &command_name(package_config, Os::Linux);
+
+        match format {
+            LinuxPackageFormat::Deb => {
+                let mtime = match options.source_date_epoch() {
+                    Some(epoch) => epoch.max(0) as u64,
+                    None => SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map_or(0, |d| d.as_secs()),
+                };
+
+                linux_packages::write_deb(
+                    &package_path,
+                    app_path,
+                    app_name,
+                    &metadata,
+                    options.target_arch,
+                    mtime,
+                )
+                .context(format!("Error writing package {}", package_path.display()))?;
+            }
+
+            LinuxPackageFormat::Rpm => {
+                if !is_available("rpmbuild") {
+                    return Err(anyhow!(
+                        "Building `.rpm` packages needs `rpmbuild`! Install it with your package manager (it's usually in the `rpm` or `rpm-build` package)."
+                    ));
+                }
+
+                let rpm_dir = self.working_dir.path().join("rpmbuild");
+                let sources_dir = rpm_dir.join("SOURCES");
+
+                fs::create_dir_all(&sources_dir)
+                    .context("Error creating the rpmbuild directory")?;
+                fs::copy(app_path, sources_dir.join(app_name))
+                    .context("Error copying the binary to the rpmbuild directory")?;
+
+                let spec_path = rpm_dir.join(format!("{}.spec", metadata.name));
+                fs::write(&spec_path, linux_packages::rpm_spec(&metadata, app_name))
+                    .context("Error writing the RPM spec")?;
+
+                let output_dir = package_path
+                    .parent()
+                    .context("Built binary is not in a directory")?;
+
+                let output_line = self.tracker.spawn_output_line();
+
+                let (status, output) = run_streaming(
+                    Command::new("rpmbuild")
+                        .arg("-bb")
+                        .arg("--target")
+                        .arg(linux_packages::rpm_arch(options.target_arch))
+                        .arg("--define")
+                        .arg(format!("_topdir {}", rpm_dir.display()))
+                        .arg("--define")
+                        .arg(format!("_sourcedir {}", sources_dir.display()))
+                        .arg("--define")
+                        .arg(format!("_rpmdir {}", output_dir.display()))
+                        .arg("--define")
+                        .arg("_build_name_fmt %{NAME}-%{VERSION}-%{RELEASE}.%{ARCH}.rpm")
+                        .arg("--define")
+                        .arg("use_source_date_epoch_as_buildtime 1")
+                        .arg(&spec_path)
+                        .envs(source_date_epoch_env(options)),
+                    &output_line,
+                )
+                .context("Error running `rpmbuild`")?;
+
+                output_line.close();
+
+                if !status.success() {
+                    return Err(anyhow!("Error building the `.rpm` package:\n{}", output));
+                }
+            }
+
+            LinuxPackageFormat::AppImage => {
+                if !is_available("appimagetool") {
+                    return Err(anyhow!(
+                        "Building AppImages needs `appimagetool`! Download it from https://github.com/AppImage/appimagetool/releases."
+                    ));
+                }
+
+                let app_dir = self
+                    .working_dir
+                    .path()
+                    .join(format!("{}.AppDir", metadata.name));
+
+                if app_dir.exists() {
+                    fs::remove_dir_all(&app_dir).context("Error removing the old AppDir")?;
+                }
+
+                linux_packages::write_app_dir(
+                    &app_dir,
+                    app_path,
+                    &metadata,
+                    project_dir,
+                    package_config,
+                )?;
+
+                let output_line = self.tracker.spawn_output_line();
+
+                let (status, output) = run_streaming(
+                    Command::new("appimagetool")
+                        .arg(&app_dir)
+                        .arg(&package_path)
+                        .env("ARCH", linux_packages::rpm_arch(options.target_arch))
+                        .envs(source_date_epoch_env(options)),
+                    &output_line,
+                )
+                .context("Error running `appimagetool`")?;
+
+                output_line.close();
+
+                if !status.success() {
+                    return Err(anyhow!("Error building the AppImage:\n{}", output));
+                }
+            }
+        }
+
+        Ok(package_path)
+    }
+
+    /// Builds an MSI installer for the built Windows binary in the same directory, with `wixl` (from msitools) or
+    /// WiX 3's `candle` and `light`. Returns the path to the installer.
+    pub(super) fn build_msi(
+        &self,
+        app_path: &Path,
+        package_config: &PackageConfig,
+        options: &BuildOptions,
+    ) -> Result<PathBuf> {
+        let stem = archive_stem(
+            &package_config.name,
+            package_config.version.as_deref(),
+            options.target_os,
+            Some(options.target_arch),
+        );
+
+        let msi_path = app_path.with_file_name(format!("{}.msi", stem));
+        let wxs_path = self.working_dir.path().join(format!("{}.wxs", stem));
+
+        fs::write(
+            &wxs_path,
+            msi::wxs(package_config, app_path, options.target_arch)?,
+        )
+        .context("Error writing the WiX source of the installer")?;
+
+        let arch = options.target_arch.to_string();
+        let output_line = self.tracker.spawn_output_line();
+
+        let (status, output) = if is_available("wixl") {
+            run_streaming(
+                Command::new("wixl")
+                    .arg("--arch")
+                    .arg(&arch)
+                    .arg("--output")
+                    .arg(&msi_path)
+                    .arg(&wxs_path),
+                &output_line,
+            )
+            .context("Error running `wixl`")?
+        // WiX 3 has no `--version`, so just check that `candle` runs
+        } else if Command::new("candle").arg("-?").output().is_ok() {
+            let wixobj_path = wxs_path.with_extension("wixobj");
+
+            let (status, output) = run_streaming(
+                Command::new("candle")
+                    .arg("-nologo")
+                    .arg("-arch")
+                    .arg(&arch)
+                    .arg("-out")
+                    .arg(&wixobj_path)
+                    .arg(&wxs_path),
+                &output_line,
+            )
+            .context("Error running `candle`")?;
+
+            if status.success() {
+                run_streaming(
+                    Command::new("light")
+                        .arg("-nologo")
+                        .arg("-out")
+                        .arg(&msi_path)
+                        .arg(&wixobj_path),
+                    &output_line,
+                )
+                .context("Error running `light`")?
+            } else {
+                (status, output)
+            }
+        } else {
+            output_line.close();
+
+            return Err(anyhow!(
+                "Building MSI installers needs `wixl` (from msitools) or WiX 3 (`candle` and `light`)! Install one of them, and make sure it's in your PATH."
+            ));
+        };
+
+        output_line.close();
+
+        if !status.success() {
+            return Err(anyhow!("Error building the MSI installer:\n{}", output));
+        }
+
+        Ok(msi_path)
+    }
+
+    /// Runs git in `dir` with `args`.
+    pub(super) fn git(&self, dir: &Path, args: &[&str]) -> Result<()> {
+        let output_line = self.tracker.spawn_output_line();
+
+        let (status, output) = run_streaming(
+            Command::new("git")
+                .args(args)
+                .current_dir(dir)
+                .env("GIT_TERMINAL_PROMPT", "0"),
+            &output_line,
+        )
+        .context("Error running git")?;
+
+        output_line.close();
+
+        if !status.success() {
+            return Err(anyhow!(
+                "Error running `git {}`:\n{}",
+                args.join(" "),
+                output
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Builds a container image from the build context in `context_dir` with Docker, and tags it as `image`.
+    pub(super) fn docker_build(&self, context_dir: &Path, image: &str, arch: Arch) -> Result<()> {
+        let output_line = self.tracker.spawn_output_line();
+
+        let (status, output) = run_streaming(
+            Command::new("docker")
+                .arg("build")
+                .arg("--platform")
+                .arg(container::platform(arch))
+                .arg("--tag")
+                .arg(image)
+                .arg(context_dir),
+            &output_line,
+        )
+        .context("Error running `docker build`")?;
+
+        output_line.close();
+
+        if !status.success() {
+            return Err(anyhow!("Error building the container image:\n{}", output));
+        }
+
+        Ok(())
+    }
+
+    /// Packages the built binary into a release archive in the same directory, along with the project's README and
+    /// license, the third-party notices, and the binary's checksum (if they were asked for). Returns the path to the
+    /// archive.
+    pub(super) fn package_archive(
+        &self,
+        project_dir: &Path,
+        app_path: &Path,
+        package_config: &PackageConfig,
+        notices: Option<&Path>,
+        options: &BuildOptions,
+        format: ArchiveFormat,
+    ) -> Result<PathBuf> {
+        let file_name = |path: &Path| path.file_name().map(|name| name.to_string_lossy().into());
+
+        // The binary is called by the command's name in the archive, whatever it's named in the output directory
+        let mut entries = vec![ArchiveEntry {
+            path: app_path.to_path_buf(),
+            name: command_name(package_config, options.target_os),
+            executable: true,
+        }];
+
+        for extra_file in extra_files(project_dir)? {
+            entries.push(ArchiveEntry {
+                name: file_name(&extra_file).context("Project file has no file name")?,
+                path: extra_file,
+                executable: false,
+            });
+        }
+
+        if let (true, Some(notices)) = (options.licenses, notices) {
+            entries.push(ArchiveEntry {
+                path: notices.to_path_buf(),
+                name: NOTICES_FILE_NAME.to_string(),
+                executable: false,
+            });
+        }
+
+        // Only the checksum of this binary goes into the archive, not of everything else in the output directory
+        if let Some(checksum_format) = options.checksums {
+            let (name, contents) = checksums_for(app_path, checksum_format)?;
+            let checksums_path = self.working_dir.path().join(&name);
+
+            fs::write(&checksums_path, contents).context(format!(
+                "Error writing checksums to {}",
+                checksums_path.display()
+            ))?;
+
+            entries.push(ArchiveEntry {
+                path: checksums_path,
+                name,
+                executable: false,
+            });
+        }
+
+        let stem = archive_stem(
+            &package_config.name,
+            package_config.version.as_deref(),
+            options.target_os,
+            (!options.universal).then_some(options.target_arch),
+        );
+
+        let archive_path = app_path.with_file_name(format!("{}.{}", stem, format));
+
+        create_archive(
+            &archive_path,
+            format,
+            &stem,
+            &entries,
+            options.source_date_epoch(),
+        )?;
+
+        Ok(archive_path)
+    }
+
+    /// Codesign the binary for MacOS. Without an identity, the binary is ad-hoc signed, which is enough to run it on
+    /// the machine it was built on. With one, it's signed with the hardened runtime and a secure timestamp, so that it
+    /// can be notarized and distributed.
+    pub(super) fn macos_codesign(
+        &self,
+        binary: &Path,
+        identity: Option<&str>,
+        project_dir: &Path,
+        config: &MacOSConfig,
+    ) -> Result<()> {
+        let mut codesign_cmd = Command::new("codesign");
+
+        codesign_cmd
+            .arg("--force")
+            .arg("--sign")
+            .arg(identity.unwrap_or("-"));
+
+        if identity.is_some() {
+            codesign_cmd
+                .arg("--options")
+                .arg("runtime")
+                .arg("--timestamp")
+                .arg("--entitlements")
+                .arg(self.macos_entitlements(project_dir, config)?);
+        }
+
+        let codesign_cmd_output = codesign_cmd
+            .arg(binary)
+            .logged_output()
+            .context("Error codesigning the binary")?;
+
+        if !codesign_cmd_output.status.success() {
+            return Err(anyhow!(
+                "Error codesigning the binary:\n{}\n{}",
+                String::from_utf8_lossy(&codesign_cmd_output.stdout),
+                String::from_utf8_lossy(&codesign_cmd_output.stderr)
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Notarize the (signed) binary with Apple, waiting for the result. Command-line binaries can't have the ticket
+    /// stapled to them, so Gatekeeper looks it up online the first time the binary runs.
+    pub(super) fn macos_notarize(
+        &self,
+        binary: &Path,
+        project_dir: &Path,
+        config: &NotarizeConfig,
+    ) -> Result<()> {
+        let credentials = match config {
+            NotarizeConfig {
+                keychain_profile: Some(profile),
+                ..
+            } => vec!["--keychain-profile".into(), profile.into()],
+
+            NotarizeConfig {
+                api_key: Some(key),
+                api_key_id: Some(key_id),
+                api_issuer: Some(issuer),
+                ..
+            } => vec![
+                "--key".into(),
+                project_dir.join(key).into_os_string(),
+                "--key-id".into(),
+                key_id.into(),
+                "--issuer".into(),
+                issuer.into(),
+            ],
+
+            _ => return Err(anyhow!("No credentials to notarize with! Set either `keychainProfile`, or `apiKey`, `apiKeyId`, and `apiIssuer` in `jundler.macos.notarize` in `package.json`.")),
+        };
+
+        let zip = self.notarization_zip(binary)?;
+
+        let output_line = self.tracker.spawn_output_line();
+
+        let (status, output) = run_streaming(
+            Command::new("xcrun")
+                .arg("notarytool")
+                .arg("submit")
+                .arg(&zip)
+                .args(credentials)
+                .arg("--wait"),
+            &output_line,
+        )
+        .context("Error notarizing the binary")?;
+
+        output_line.close();
+
+        if !status.success() || !output.contains("status: Accepted") {
+            let submission = output
+                .lines()
+                .find_map(|line| line.trim().strip_prefix("id: "))
+                .unwrap_or("<submission id>");
+
+            return Err(anyhow!(
+                "Error notarizing the binary:\n{}\nRun `xcrun notarytool log {}` to see why.",
+                output.trim(),
+                submission
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Codesign the binary for MacOS on another OS, with `rcodesign`. Without a certificate, the binary is ad-hoc
+    /// signed. With one, it's signed with the hardened runtime (and timestamped by Apple), so that it can be notarized
+    /// and distributed.
+    pub(super) fn rcodesign(
+        &self,
+        binary: &Path,
+        certificate: Option<&Path>,
+        project_dir: &Path,
+        config: &MacOSConfig,
+    ) -> Result<()> {
+        let mut rcodesign_cmd = Command::new("rcodesign");
+
+        rcodesign_cmd.arg("sign");
+
+        if let Some(certificate) = certificate {
+            rcodesign_cmd.arg("--p12-file").arg(certificate);
+
+            let password_env = config
+                .certificate_password_env
+                .as_deref()
+                .unwrap_or(CERTIFICATE_PASSWORD_VAR);
+
+            if let Ok(password) = env::var(password_env) {
+                rcodesign_cmd.arg("--p12-password").arg(password);
+            }
+
+            rcodesign_cmd
+                .arg("--code-signature-flags")
+                .arg("runtime")
+                .arg("--entitlements-xml-file")
+                .arg(self.macos_entitlements(project_dir, config)?);
+        }
+
+        let rcodesign_cmd_output = rcodesign_cmd
+            .arg(binary)
+            .logged_output()
+            .context("Error codesigning the binary")?;
+
+        if !rcodesign_cmd_output.status.success() {
+            return Err(anyhow!(
+                "Error codesigning the binary:\n{}\n{}",
+                String::from_utf8_lossy(&rcodesign_cmd_output.stdout),
+                String::from_utf8_lossy(&rcodesign_cmd_output.stderr)
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Notarize the (signed) binary with Apple on another OS, with `rcodesign`. Only App Store Connect API keys can be
+    /// used, as keychain profiles only exist on macOS.
+    pub(super) fn rcodesign_notarize(
+        &self,
+        binary: &Path,
+        project_dir: &Path,
+        config: &NotarizeConfig,
+    ) -> Result<()> {
+        let NotarizeConfig {
+            api_key: Some(key),
+            api_key_id: Some(key_id),
+            api_issuer: Some(issuer),
+            ..
+        } = config
+        else {
+            return Err(anyhow!("No credentials to notarize with! Set `apiKey`, `apiKeyId`, and `apiIssuer` in `jundler.macos.notarize` in `package.json`. Keychain profiles only work on macOS."));
+        };
+
+        // `rcodesign` looks for the key as `private_keys/AuthKey_<id>.p8` in the directory it's run in.
+        let keys_dir = self.working_dir.path().join("private_keys");
+
+        fs::create_dir_all(&keys_dir).context("Error creating the API key directory")?;
+        fs::copy(
+            project_dir.join(key),
+            keys_dir.join(format!("AuthKey_{}.p8", key_id)),
+        )
+        .context(format!("Error reading the API key from {}", key))?;
+
+        let zip = self.notarization_zip(binary)?;
+
+        let output_line = self.tracker.spawn_output_line();
+
+        let (status, output) = run_streaming(
+            Command::new("rcodesign")
+                .current_dir(self.working_dir.path())
+                .arg("notary-submit")
+                .arg("--api-issuer")
+                .arg(issuer)
+                .arg("--api-key")
+                .arg(key_id)
+                .arg("--wait")
+                .arg(&zip),
+            &output_line,
+        )
+        .context("Error notarizing the binary")?;
+
+        output_line.close();
+
+        if !status.success() {
+            return Err(anyhow!("Error notarizing the binary:\n{}", output.trim()));
+        }
+
+        Ok(())
+    }
+
+    /// Gets the entitlements to sign macOS binaries with the hardened runtime with: the project's own, or the ones
+    /// Node.js needs.
+    fn macos_entitlements(&self, project_dir: &Path, config: &MacOSConfig) -> Result<PathBuf> {
+        match &config.entitlements {
+            Some(entitlements) => Ok(project_dir.join(entitlements)),
+
+            None => {
+                let entitlements = self.working_dir.path().join("entitlements.plist");
+
+                fs::write(&entitlements, DEFAULT_ENTITLEMENTS)
+                    .context("Error writing the default entitlements")?;
+
+                Ok(entitlements)
+            }
+        }
+    }
+
+    /// Zips up a binary to be notarized. Apple only accepts zips, disk images, and installers.
+    fn notarization_zip(&self, binary: &Path) -> Result<PathBuf> {
+        let name = binary
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let zip = self
+            .working_dir
+            .path()
+            .join(format!("{}-notarize.zip", name));
+
+        create_archive(
+            &zip,
+            ArchiveFormat::Zip,
+            &name,
+            &[ArchiveEntry {
+                path: binary.to_path_buf(),
+                name: name.clone(),
+                executable: true,
+            }],
+            None,
+        )?;
+
+        Ok(zip)
+    }
+
+    /// Codesign the binary for Windows. The certificate is picked from a PFX file, or by its thumbprint from the
+    /// certificate store, or automatically if neither is given.
+    pub(super) fn windows_sign(
+        &self,
+        binary: &Path,
+        project_dir: &Path,
+        certificate: Option<&Path>,
+        options: &WindowsSignOptions,
+        config: &WindowsConfig,
+        pass_env: &[String],
+    ) -> Result<()> {
+        self.tracker.warn("Windows signing is in beta and may not work as expected. Please report any issues here: https://github.com/cogsandsquigs/jundler/issues/new");
+
+        let mut sign_cmd = Command::new("signtool");
+
+        sign_cmd
+            .sanitized_env(pass_env)
+            .arg("sign")
+            .arg("/fd")
+            .arg("SHA256");
+
+        let certificate = certificate.map(Path::to_path_buf).or_else(|| {
+            config
+                .certificate
+                .as_ref()
+                .map(|path| project_dir.join(path))
+        });
+        let thumbprint = options
+            .thumbprint
+            .as_ref()
+            .or(config.certificate_thumbprint.as_ref());
+
+        match (certificate, thumbprint) {
+            (Some(certificate), _) => {
+                sign_cmd.arg("/f").arg(certificate);
+
+                let password_env = config
+                    .certificate_password_env
+                    .as_deref()
+                    .unwrap_or(CERTIFICATE_PASSWORD_VAR);
+
+                if let Ok(password) = env::var(password_env) {
+                    sign_cmd.arg("/p").arg(password);
+                }
+            }
+
+            (None, Some(thumbprint)) => {
+                sign_cmd.arg("/sha1").arg(thumbprint);
+            }
+
+            (None, None) => {
+                sign_cmd.arg("/a");
+            }
+        }
+
+        if let Some(timestamp_url) = options
+            .timestamp_url
+            .as_ref()
+            .or(config.timestamp_url.as_ref())
+        {
+            sign_cmd
+                .arg("/tr")
+                .arg(timestamp_url)
+                .arg("/td")
+                .arg("SHA256");
+        }
+
+        if let Some(description) = options
+            .description
+            .as_ref()
+            .or(config.sign_description.as_ref())
+        {
+            sign_cmd.arg("/d").arg(description);
+        }
+
+        let sign_cmd_output = sign_cmd
+            .arg(binary)
+            .logged_output()
+            .context("Error signing the binary")?;
+
+        if !sign_cmd_output.status.success() {
+            return Err(anyhow!(
+                "Error signing the binary:\n{}\n{}",
+                String::from_utf8_lossy(&sign_cmd_output.stdout),
+                String::from_utf8_lossy(&sign_cmd_output.stderr)
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether there's a `node_modules` directory in `project_dir`, or in any directory above it, where Node.js (and ESBuild)
+/// would look for packages.
+fn has_node_modules(project_dir: &Path) -> bool {
+    std::path::absolute(project_dir)
+        .unwrap_or_else(|_| project_dir.to_path_buf())
+        .ancestors()
+        .any(|dir| dir.join("node_modules").is_dir())
+}
@@ -0,0 +1,49 @@
+use super::platforms::Os;
+use crate::ui::Step;
+use anyhow::Result;
+use std::fmt;
+use std::path::Path;
+
+/// Callbacks for tools that build with jundler, to act on what builds do as they do it: i.e. to time steps, upload
+/// each file as soon as it's written, or collect warnings. Every method does nothing by default, so tools only need
+/// to implement the hooks they want.
+pub trait Hooks: fmt::Debug + Send + Sync {
+    /// A step of a build finished.
+    fn on_step(&self, step: &Step) {
+        let _ = step;
+    }
+
+    /// A build wrote the file at `path`: the binary, or one of its checksums, signatures, archives, packages, and so
+    /// on. Called once the build is done with every file, so files are final. Returning an error fails the build.
+    fn on_artifact(&self, path: &Path) -> Result<()> {
+        let _ = path;
+        Ok(())
+    }
+
+    /// A build warned about something, i.e. that signing was skipped.
+    fn on_warning(&self, message: &str) {
+        let _ = message;
+    }
+}
+
+/// Hooks that do nothing.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoHooks;
+
+impl Hooks for NoHooks {}
+
+/// Bundles projects into a single JavaScript file, in place of ESBuild. Used whenever a project is bundled (see
+/// `BuildOptions::bundle`).
+pub trait Bundler: fmt::Debug + Send + Sync {
+    /// Bundles the project in `project_dir` (a copy of it, with its dependencies installed), starting from
+    /// `entrypoint` (relative to the project), into the file at `output`.
+    fn bundle(&self, project_dir: &Path, entrypoint: &Path, output: &Path) -> Result<()>;
+}
+
+/// Signs built binaries, in place of `codesign`, `rcodesign`, and `signtool`. Used whenever a build is signed (see
+/// `BuildOptions::sign`), for every target, along with Windows installers. macOS binaries are still notarized with
+/// the built-in tools.
+pub trait Signer: fmt::Debug + Send + Sync {
+    /// Signs the file at `path`, which was built for `os`.
+    fn sign(&self, path: &Path, os: Os) -> Result<()>;
+}
@@ -0,0 +1,282 @@
+use anyhow::{Context, Result};
+use ignore::WalkBuilder;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// The name of the third-party notices file, both next to the built binary and as a SEA asset.
+pub const NOTICES_FILE_NAME: &str = "THIRD_PARTY_NOTICES";
+
+/// The shim that is prepended to the application's entrypoint when the notices are embedded, so that they can be
+/// printed with `--licenses`.
+const LICENSES_SHIM: &str = include_str!("licenses_shim.js");
+
+/// The placeholder in the licenses shim that is replaced with the asset key of the notices.
+const NOTICES_ASSET_PLACEHOLDER: &str = "__JUNDLER_NOTICES_ASSET__";
+
+/// The prefixes (compared case-insensitively) of files in a package that hold its license text.
+const LICENSE_FILE_PREFIXES: &[&str] = &["license", "licence", "copying", "notice"];
+
+/// A dependency that is included in the built application.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Package {
+    /// The name of the package.
+    pub name: String,
+
+    /// The version of the package.
+    pub version: String,
+
+    /// The license of the package, as declared in its `package.json`.
+    pub license: Option<String>,
+
+    /// The license files shipped with the package, relative to the project directory.
+    pub license_files: Vec<PathBuf>,
+}
+
+/// Gets the files that esbuild included in the bundle from its metafile, relative to `project_dir`. esbuild lists them
+/// relative to the directory it ran in, `bundle_dir`, which is only somewhere else if the project was bundled in place.
+pub fn bundled_files(
+    metafile: &Path,
+    bundle_dir: &Path,
+    project_dir: &Path,
+) -> Result<Vec<PathBuf>> {
+    let metafile = read_metafile(metafile)?;
+
+    Ok(metafile["inputs"]
+        .as_object()
+        .map(|inputs| {
+            inputs
+                .keys()
+                // Skip virtual modules, like `(disabled):fs`
+                .filter(|input| !input.starts_with('('))
+                .map(|input| relative_input(input, bundle_dir, project_dir))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Gets how many bytes of each file esbuild included in the bundle ended up in it, from its metafile, relative to
+/// `project_dir` like [`bundled_files`]. Files that were tree-shaken away entirely are left out.
+pub fn bundled_bytes(
+    metafile: &Path,
+    bundle_dir: &Path,
+    project_dir: &Path,
+) -> Result<BTreeMap<PathBuf, u64>> {
+    let metafile = read_metafile(metafile)?;
+
+    let mut bytes = BTreeMap::new();
+
+    for output in metafile["outputs"]
+        .as_object()
+        .into_iter()
+        .flat_map(|outputs| outputs.values())
+    {
+        for (input, details) in output["inputs"].as_object().into_iter().flatten() {
+            let size = details["bytesInOutput"].as_u64().unwrap_or_default();
+
+            if size > 0 && !input.starts_with('(') {
+                *bytes
+                    .entry(relative_input(input, bundle_dir, project_dir))
+                    .or_default() += size;
+            }
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// Reads and parses an esbuild metafile.
+fn read_metafile(metafile: &Path) -> Result<Value> {
+    serde_json::from_str(&fs::read_to_string(metafile).context(format!(
+        "Error reading esbuild metafile {}",
+        metafile.display()
+    ))?)
+    .context("Error parsing esbuild metafile")
+}
+
+/// Makes a path that esbuild listed relative to `bundle_dir` relative to `project_dir` instead, if it's inside of it.
+/// Anything else (i.e. the project's own sources, when bundled in place) is left as it is.
+fn relative_input(input: &str, bundle_dir: &Path, project_dir: &Path) -> PathBuf {
+    if bundle_dir == project_dir {
+        return PathBuf::from(input);
+    }
+
+    // Temporary directories can be behind symlinks (like `/tmp` on macOS), so compare the real paths
+    let project_dir = project_dir
+        .canonicalize()
+        .unwrap_or_else(|_| project_dir.to_path_buf());
+
+    bundle_dir
+        .join(input)
+        .canonicalize()
+        .ok()
+        .and_then(|path| path.strip_prefix(&project_dir).ok().map(Path::to_path_buf))
+        .unwrap_or_else(|| PathBuf::from(input))
+}
+
+/// Gets the `package.json` of every package installed in `node_modules`, relative to the project directory. Custom
+/// bundlers don't say which files they bundled, so every installed package is assumed to be included.
+pub fn installed_files(project_dir: &Path) -> Vec<PathBuf> {
+    WalkBuilder::new(project_dir.join("node_modules"))
+        .standard_filters(false)
+        .build()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name() == "package.json")
+        .filter_map(|entry| {
+            entry
+                .path()
+                .strip_prefix(project_dir)
+                .ok()
+                .map(Path::to_path_buf)
+        })
+        .collect()
+}
+
+/// Finds every package in `node_modules` that one of `included_files` (relative to the project directory) belongs
+/// to, sorted by name and version.
+pub fn included_packages(project_dir: &Path, included_files: &[PathBuf]) -> Result<Vec<Package>> {
+    let package_dirs: BTreeSet<PathBuf> = included_files
+        .iter()
+        .filter_map(|file| package_dir(file))
+        .collect();
+
+    let mut packages = vec![];
+
+    for dir in package_dirs {
+        let manifest_path = project_dir.join(&dir).join("package.json");
+
+        // Stray files in `node_modules` (i.e. `.bin` links) don't belong to a package, and have nothing to report.
+        let Ok(manifest) = fs::read_to_string(&manifest_path) else {
+            continue;
+        };
+
+        let manifest: Value = serde_json::from_str(&manifest)
+            .context(format!("Error parsing {}", manifest_path.display()))?;
+
+        let mut license_files = vec![];
+
+        for entry in fs::read_dir(project_dir.join(&dir))
+            .context(format!("Error reading package directory {}", dir.display()))?
+        {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_lowercase();
+
+            if entry.file_type()?.is_file()
+                && LICENSE_FILE_PREFIXES
+                    .iter()
+                    .any(|prefix| name.starts_with(prefix))
+            {
+                license_files.push(dir.join(entry.file_name()));
+            }
+        }
+
+        license_files.sort();
+
+        packages.push(Package {
+            name: manifest["name"]
+                .as_str()
+                .map(String::from)
+                .unwrap_or_else(|| dir.to_string_lossy().into()),
+            version: manifest["version"].as_str().unwrap_or("unknown").into(),
+            license: declared_license(&manifest),
+            license_files,
+        });
+    }
+
+    packages.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+    packages.dedup_by(|a, b| a.name == b.name && a.version == b.version);
+
+    Ok(packages)
+}
+
+/// Renders the third-party notices for the given packages, with the full text of each of their license files.
+pub fn notices(project_dir: &Path, packages: &[Package]) -> Result<String> {
+    let mut notices = String::from(
+        "THIRD-PARTY SOFTWARE NOTICES\n\nThis application includes the following third-party software.\n",
+    );
+
+    for package in packages {
+        notices += &format!(
+            "\n{}\n{}@{}\nLicense: {}\n{}\n",
+            "=".repeat(80),
+            package.name,
+            package.version,
+            package.license.as_deref().unwrap_or("unknown"),
+            "-".repeat(80)
+        );
+
+        if package.license_files.is_empty() {
+            notices += "\nNo license file was found in this package.\n";
+        }
+
+        for license_file in &package.license_files {
+            let path = project_dir.join(license_file);
+            let text = fs::read_to_string(&path)
+                .context(format!("Error reading license file {}", path.display()))?;
+
+            notices += "\n";
+            notices += text.trim_end();
+            notices += "\n";
+        }
+    }
+
+    Ok(notices)
+}
+
+/// Gets the licenses shim for the given notices asset key. This should be prepended to the application's entrypoint.
+pub fn licenses_shim(asset_key: &str) -> String {
+    LICENSES_SHIM.replace(
+        NOTICES_ASSET_PLACEHOLDER,
+        &serde_json::to_string(asset_key).expect("A string should always serialize!"),
+    )
+}
+
+/// Gets the directory of the innermost package in `node_modules` that a file belongs to, i.e.
+/// `node_modules/@scope/pkg` for `node_modules/a/node_modules/@scope/pkg/lib/index.js`.
+fn package_dir(file: &Path) -> Option<PathBuf> {
+    let components: Vec<Component> = file.components().collect();
+
+    let node_modules = components
+        .iter()
+        .rposition(|c| c.as_os_str() == "node_modules")?;
+
+    let name = components.get(node_modules + 1)?;
+
+    let end = if name.as_os_str().to_string_lossy().starts_with('@') {
+        node_modules + 3
+    } else {
+        node_modules + 2
+    };
+
+    // The file has to be inside the package, not the package directory itself
+    if end >= components.len() {
+        return None;
+    }
+
+    Some(components[..end].iter().collect())
+}
+
+/// Gets the license declared in a `package.json`, which can be a SPDX expression, a `{ "type": ... }` object, or a
+/// (deprecated) list of those under `licenses`.
+fn declared_license(manifest: &Value) -> Option<String> {
+    let license_type = |license: &Value| {
+        license
+            .as_str()
+            .or_else(|| license["type"].as_str())
+            .map(String::from)
+    };
+
+    if let Some(license) = manifest.get("license") {
+        return license_type(license);
+    }
+
+    let licenses = manifest
+        .get("licenses")?
+        .as_array()?
+        .iter()
+        .filter_map(license_type)
+        .collect::<Vec<_>>();
+
+    (!licenses.is_empty()).then(|| licenses.join(" OR "))
+}
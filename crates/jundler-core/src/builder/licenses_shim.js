@@ -0,0 +1,10 @@
+// Injected by jundler: prints the third-party notices embedded in the executable when it's run with `--licenses`.
+((seaRequire) => {
+    const sea = seaRequire("node:sea");
+
+    if (sea.isSea() && process.argv.slice(1).includes("--licenses")) {
+        // Replaced by jundler at build time.
+        process.stdout.write(sea.getAsset(__JUNDLER_NOTICES_ASSET__, "utf8"));
+        process.exit(0);
+    }
+})(require);
@@ -0,0 +1,438 @@
+use super::platforms::Arch;
+use crate::js_config::PackageConfig;
+use anyhow::{anyhow, Context, Result};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
+
+/// The packages that `.deb` packages depend on, unless the project names others. These are the libraries that
+/// Node.js links against.
+const DEFAULT_DEB_DEPENDS: &[&str] = &["libc6 (>= 2.28)", "libstdc++6", "libgcc-s1"];
+
+/// The packages that `.rpm` packages require, unless the project names others.
+const DEFAULT_RPM_REQUIRES: &[&str] = &["glibc >= 2.28", "libstdc++", "libgcc"];
+
+/// The icon of AppImages, unless the project has its own.
+const DEFAULT_ICON: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 64 64"><rect width="64" height="64" rx="12" fill="#3c873a"/><path d="M18 22l12 10-12 10M34 44h12" stroke="#fff" stroke-width="5" fill="none" stroke-linecap="round" stroke-linejoin="round"/></svg>
+"##;
+
+/// The format of a Linux package.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinuxPackageFormat {
+    /// A `.deb` package, for Debian, Ubuntu, and their derivatives.
+    Deb,
+
+    /// An `.rpm` package, for Fedora, RHEL, openSUSE, and their derivatives. Needs `rpmbuild`.
+    Rpm,
+
+    /// An AppImage, which runs on most distributions without being installed. Needs `appimagetool`.
+    #[value(name = "appimage")]
+    AppImage,
+}
+
+impl fmt::Display for LinuxPackageFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LinuxPackageFormat::Deb => write!(f, "deb"),
+            LinuxPackageFormat::Rpm => write!(f, "rpm"),
+            LinuxPackageFormat::AppImage => write!(f, "appimage"),
+        }
+    }
+}
+
+/// The metadata of a Linux package, from `package.json`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageMetadata {
+    /// The name of the package, made valid for package managers.
+    pub name: String,
+
+    /// The version of the package, with any prerelease separated by `~` so that it sorts before the release.
+    pub version: String,
+
+    /// A short description of the package.
+    pub description: String,
+
+    /// The SPDX identifier of the package's license, if there is one.
+    pub license: Option<String>,
+
+    /// The maintainer of the package, as `Name <email>`.
+    pub maintainer: Option<String>,
+
+    /// The URL of the package's homepage, if there is one.
+    pub homepage: Option<String>,
+
+    /// The packages the package depends on.
+    pub depends: Vec<String>,
+}
+
+impl PackageMetadata {
+    /// Gets the metadata of a package in the given format from `package.json`. Fails if the project has no version.
+    pub fn new(package_config: &PackageConfig, format: LinuxPackageFormat) -> Result<Self> {
+        let config = &package_config.jundler.linux;
+
+        let version = package_config.version.as_deref().ok_or_else(|| {
+            anyhow!("Linux packages need a version! Set `version` in `package.json`.")
+        })?;
+
+        let depends = match format {
+            LinuxPackageFormat::Deb if config.deb_depends.is_empty() => {
+                to_strings(DEFAULT_DEB_DEPENDS)
+            }
+            LinuxPackageFormat::Deb => config.deb_depends.clone(),
+            LinuxPackageFormat::Rpm if config.rpm_requires.is_empty() => {
+                to_strings(DEFAULT_RPM_REQUIRES)
+            }
+            LinuxPackageFormat::Rpm => config.rpm_requires.clone(),
+            // AppImages bring everything but the C libraries with them
+            LinuxPackageFormat::AppImage => vec![],
+        };
+
+        Ok(PackageMetadata {
+            name: package_config
+                .name
+                .trim_start_matches('@')
+                .replace(['/', '_'], "-")
+                .to_lowercase(),
+            version: version.trim_start_matches('v').replacen('-', "~", 1),
+            description: package_config
+                .description
+                .clone()
+                .unwrap_or_else(|| package_config.name.clone()),
+            license: package_config.license.clone(),
+            maintainer: config.maintainer.clone().or_else(|| {
+                package_config
+                    .author
+                    .as_ref()
+                    .map(|author| author.contact())
+            }),
+            homepage: package_config.homepage.clone(),
+            depends,
+        })
+    }
+
+    /// Gets the conventional file name of the package in the given format, i.e. `mytool_1.2.3_amd64.deb`.
+    pub fn file_name(&self, format: LinuxPackageFormat, arch: Arch) -> String {
+        match format {
+            LinuxPackageFormat::Deb => {
+                format!("{}_{}_{}.deb", self.name, self.version, deb_arch(arch))
+            }
+            LinuxPackageFormat::Rpm => {
+                format!("{}-{}-1.{}.rpm", self.name, self.version, rpm_arch(arch))
+            }
+            LinuxPackageFormat::AppImage => {
+                format!("{}-{}-{}.AppImage", self.name, self.version, rpm_arch(arch))
+            }
+        }
+    }
+}
+
+/// Gets the Debian name of an architecture.
+pub fn deb_arch(arch: Arch) -> &'static str {
+    match arch {
+        Arch::X64 => "amd64",
+        Arch::X86 => "i386",
+        Arch::Arm64 => "arm64",
+    }
+}
+
+/// Gets the RPM (and AppImage) name of an architecture.
+pub fn rpm_arch(arch: Arch) -> &'static str {
+    match arch {
+        Arch::X64 => "x86_64",
+        Arch::X86 => "i686",
+        Arch::Arm64 => "aarch64",
+    }
+}
+
+/// Writes a `.deb` package that installs the binary to `/usr/bin/<app_name>`. Every file in the package gets `mtime`
+/// (a unix timestamp) as its modification time.
+pub fn write_deb(
+    deb_path: &Path,
+    app_path: &Path,
+    app_name: &str,
+    metadata: &PackageMetadata,
+    arch: Arch,
+    mtime: u64,
+) -> Result<()> {
+    let doc_dir = format!("usr/share/doc/{}/", metadata.name);
+
+    let data = tar_gz(
+        &[
+            TarEntry::dir("./"),
+            TarEntry::dir("usr/"),
+            TarEntry::dir("usr/bin/"),
+            TarEntry::file(&format!("usr/bin/{}", app_name), app_path, 0o755),
+            TarEntry::dir("usr/share/"),
+            TarEntry::dir("usr/share/doc/"),
+            TarEntry::dir(&doc_dir),
+            TarEntry::data(
+                &format!("{}copyright", doc_dir),
+                deb_copyright(metadata).into_bytes(),
+            ),
+        ],
+        mtime,
+    )?;
+
+    let installed_size = fs::metadata(app_path)?.len().div_ceil(1024);
+
+    let control = tar_gz(
+        &[
+            TarEntry::dir("./"),
+            TarEntry::data(
+                "control",
+                deb_control(metadata, arch, installed_size).into_bytes(),
+            ),
+        ],
+        mtime,
+    )?;
+
+    let mut deb =
+        File::create(deb_path).context(format!("Error creating package {}", deb_path.display()))?;
+
+    // A `.deb` is an `ar` archive of the format version, the control files, and the installed files, in that order
+    deb.write_all(b"!<arch>\n")?;
+    ar_entry(&mut deb, "debian-binary", b"2.0\n", mtime)?;
+    ar_entry(&mut deb, "control.tar.gz", &control, mtime)?;
+    ar_entry(&mut deb, "data.tar.gz", &data, mtime)?;
+
+    Ok(())
+}
+
+/// Generates the `control` file of a `.deb` package.
+pub fn deb_control(metadata: &PackageMetadata, arch: Arch, installed_size: u64) -> String {
+    let mut control = format!(
+        "Package: {}\nVersion: {}\nArchitecture: {}\n",
+        metadata.name,
+        metadata.version,
+        deb_arch(arch)
+    );
+
+    if let Some(maintainer) = &metadata.maintainer {
+        control += &format!("Maintainer: {}\n", maintainer);
+    }
+
+    control += &format!("Installed-Size: {}\n", installed_size);
+
+    if !metadata.depends.is_empty() {
+        control += &format!("Depends: {}\n", metadata.depends.join(", "));
+    }
+
+    control += "Section: utils\nPriority: optional\n";
+
+    if let Some(homepage) = &metadata.homepage {
+        control += &format!("Homepage: {}\n", homepage);
+    }
+
+    control + &format!("Description: {}\n", metadata.description)
+}
+
+/// Generates the machine-readable `copyright` file of a `.deb` package.
+fn deb_copyright(metadata: &PackageMetadata) -> String {
+    let mut copyright = format!(
+        "Format: https://www.debian.org/doc/packaging-manuals/copyright-format/1.0/\nUpstream-Name: {}\n",
+        metadata.name
+    );
+
+    copyright += "\nFiles: *\n";
+
+    if let Some(maintainer) = &metadata.maintainer {
+        copyright += &format!("Copyright: {}\n", maintainer);
+    }
+
+    copyright
+        + &format!(
+            "License: {}\n",
+            metadata.license.as_deref().unwrap_or("UNLICENSED")
+        )
+}
+
+/// Generates the spec of an `.rpm` package that installs the binary called `app_name` from the sources directory to
+/// `/usr/bin`.
+pub fn rpm_spec(metadata: &PackageMetadata, app_name: &str) -> String {
+    let mut spec = format!(
+        "Name: {}\nVersion: {}\nRelease: 1\nSummary: {}\nLicense: {}\n",
+        metadata.name,
+        metadata.version,
+        metadata.description,
+        metadata.license.as_deref().unwrap_or("UNLICENSED")
+    );
+
+    if let Some(homepage) = &metadata.homepage {
+        spec += &format!("URL: {}\n", homepage);
+    }
+
+    if let Some(maintainer) = &metadata.maintainer {
+        spec += &format!("Packager: {}\n", maintainer);
+    }
+
+    for requires in &metadata.depends {
+        spec += &format!("Requires: {}\n", requires);
+    }
+
+    // Stripping the binary (or splitting out its debug info) would throw away the injected application
+    spec += "AutoReqProv: no\n%global debug_package %{nil}\n%global __os_install_post %{nil}\n";
+
+    spec + &format!(
+        "\n%description\n{}\n\n%install\nmkdir -p %{{buildroot}}/usr/bin\ninstall -m 0755 %{{_sourcedir}}/{app} %{{buildroot}}/usr/bin/{app}\n\n%files\n/usr/bin/{app}\n",
+        metadata.description,
+        app = app_name
+    )
+}
+
+/// Writes an AppDir for `appimagetool` to `app_dir`: the binary, a desktop entry, and an icon.
+pub fn write_app_dir(
+    app_dir: &Path,
+    app_path: &Path,
+    metadata: &PackageMetadata,
+    project_dir: &Path,
+    package_config: &PackageConfig,
+) -> Result<()> {
+    let app_name = &package_config.name;
+
+    let bin_dir = app_dir.join("usr").join("bin");
+
+    fs::create_dir_all(&bin_dir).context("Error creating the AppDir")?;
+    fs::copy(app_path, bin_dir.join(app_name)).context("Error copying the binary to the AppDir")?;
+
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(format!("usr/bin/{}", app_name), app_dir.join("AppRun"))
+        .context("Error linking the AppDir's AppRun")?;
+
+    fs::write(
+        app_dir.join(format!("{}.desktop", metadata.name)),
+        format!(
+            "[Desktop Entry]\nType=Application\nName={}\nComment={}\nExec={}\nIcon={}\nTerminal=true\nCategories=Utility;\n",
+            package_config.name, metadata.description, app_name, metadata.name
+        ),
+    )
+    .context("Error writing the AppDir's desktop entry")?;
+
+    match &package_config.jundler.linux.icon {
+        Some(icon) => {
+            let icon = project_dir.join(icon);
+            let extension = icon
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .unwrap_or("png");
+
+            fs::copy(
+                &icon,
+                app_dir.join(format!("{}.{}", metadata.name, extension)),
+            )
+            .context(format!("Error copying the icon {}", icon.display()))?;
+        }
+
+        None => fs::write(app_dir.join(format!("{}.svg", metadata.name)), DEFAULT_ICON)
+            .context("Error writing the AppDir's icon")?,
+    }
+
+    Ok(())
+}
+
+/// What goes into an entry of a tarball.
+enum TarContents<'a> {
+    Dir,
+    Data(Vec<u8>),
+    File(&'a Path),
+}
+
+/// An entry of a tarball inside a `.deb` package.
+struct TarEntry<'a> {
+    path: String,
+    contents: TarContents<'a>,
+    mode: u32,
+}
+
+impl<'a> TarEntry<'a> {
+    fn dir(path: &str) -> Self {
+        TarEntry {
+            path: path.to_string(),
+            contents: TarContents::Dir,
+            mode: 0o755,
+        }
+    }
+
+    fn data(path: &str, data: Vec<u8>) -> Self {
+        TarEntry {
+            path: path.to_string(),
+            contents: TarContents::Data(data),
+            mode: 0o644,
+        }
+    }
+
+    fn file(path: &str, file: &'a Path, mode: u32) -> Self {
+        TarEntry {
+            path: path.to_string(),
+            contents: TarContents::File(file),
+            mode,
+        }
+    }
+}
+
+/// Writes the entries to a gzipped tarball in memory, owned by root.
+fn tar_gz(entries: &[TarEntry], mtime: u64) -> Result<Vec<u8>> {
+    let mut tar = tar::Builder::new(GzEncoder::new(vec![], Compression::best()));
+
+    for entry in entries {
+        let mut header = tar::Header::new_gnu();
+        header.set_mode(entry.mode);
+        header.set_mtime(mtime);
+        header.set_uid(0);
+        header.set_gid(0);
+        header.set_username("root")?;
+        header.set_groupname("root")?;
+
+        match &entry.contents {
+            TarContents::Dir => {
+                header.set_entry_type(tar::EntryType::Directory);
+                header.set_size(0);
+                header.set_cksum();
+                tar.append_data(&mut header, &entry.path, io::empty())?;
+            }
+
+            TarContents::Data(data) => {
+                header.set_size(data.len() as u64);
+                header.set_cksum();
+                tar.append_data(&mut header, &entry.path, data.as_slice())?;
+            }
+
+            TarContents::File(path) => {
+                header.set_size(fs::metadata(path)?.len());
+                header.set_cksum();
+                tar.append_data(&mut header, &entry.path, File::open(path)?)?;
+            }
+        }
+    }
+
+    Ok(tar.into_inner()?.finish()?)
+}
+
+/// Writes a single file to an `ar` archive.
+fn ar_entry(out: &mut impl Write, name: &str, data: &[u8], mtime: u64) -> io::Result<()> {
+    writeln!(
+        out,
+        "{:<16}{:<12}{:<6}{:<6}{:<8}{:<10}`",
+        name,
+        mtime,
+        0,
+        0,
+        "100644",
+        data.len()
+    )?;
+    out.write_all(data)?;
+
+    // Every entry starts on an even offset
+    if data.len() % 2 == 1 {
+        out.write_all(b"\n")?;
+    }
+
+    Ok(())
+}
+
+/// Converts a list of string slices to owned strings.
+fn to_strings(strings: &[&str]) -> Vec<String> {
+    strings.iter().map(|string| string.to_string()).collect()
+}
@@ -0,0 +1,277 @@
+use super::archive::{archive_stem, ArchiveFormat};
+use super::helpers::calculate_checksum;
+use super::platforms::{Arch, Os};
+use crate::js_config::PackageConfig;
+use anyhow::{anyhow, Context, Result};
+use serde_json::{json, Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A release archive found in the output directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReleaseArchive {
+    /// The operating system the archive is for.
+    pub os: Os,
+
+    /// The architecture the archive is for, or `None` for universal macOS binaries.
+    pub arch: Option<Arch>,
+
+    /// The URL the archive is downloaded from.
+    pub url: String,
+
+    /// The hex-encoded SHA256 digest of the archive.
+    pub sha256: String,
+
+    /// The name of the top-level directory in the archive.
+    pub root: String,
+}
+
+/// Writes a Homebrew formula (`<name>.rb`) and a Scoop manifest (`<name>.json`) for every release archive of the
+/// project's current version in `output_dir`, so that separate builds into the same directory add up to complete
+/// manifests. `release_url` is where the archives are uploaded to, and `{version}` in it is replaced with the
+/// version. Returns the paths to the written manifests.
+pub fn write_manifests(
+    output_dir: &Path,
+    package_config: &PackageConfig,
+    release_url: &str,
+) -> Result<Vec<PathBuf>> {
+    let archives = find_archives(output_dir, package_config, release_url)?;
+    let mut written = vec![];
+
+    if let Some(formula) = homebrew_formula(package_config, &archives) {
+        let path = output_dir.join(format!("{}.rb", package_config.name));
+
+        fs::write(&path, formula).context("Error writing the Homebrew formula")?;
+
+        written.push(path);
+    }
+
+    if let Some(manifest) = scoop_manifest(package_config, &archives) {
+        let path = output_dir.join(format!("{}.json", package_config.name));
+
+        fs::write(&path, manifest).context("Error writing the Scoop manifest")?;
+
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+/// Finds the release archives of the project's current version in `output_dir`, for every OS and architecture.
+pub fn find_archives(
+    output_dir: &Path,
+    package_config: &PackageConfig,
+    release_url: &str,
+) -> Result<Vec<ReleaseArchive>> {
+    let version = package_config.version.as_deref().ok_or_else(|| {
+        anyhow!("Package manifests need a version! Set `version` in `package.json`.")
+    })?;
+
+    let base_url = release_url
+        .replace("{version}", version.trim_start_matches('v'))
+        .trim_end_matches('/')
+        .to_string();
+
+    let mut archives = vec![];
+
+    for os in [Os::MacOS, Os::Linux, Os::Windows] {
+        for arch in [None, Some(Arch::X64), Some(Arch::X86), Some(Arch::Arm64)] {
+            let root = archive_stem(&package_config.name, Some(version), os, arch);
+
+            for format in [ArchiveFormat::TarGz, ArchiveFormat::Zip] {
+                let file_name = format!("{}.{}", root, format);
+                let path = output_dir.join(&file_name);
+
+                if !path.is_file() {
+                    continue;
+                }
+
+                let sha256 = hex::encode(
+                    calculate_checksum(&path)
+                        .context(format!("Error calculating checksum of {}", path.display()))?,
+                );
+
+                archives.push(ReleaseArchive {
+                    os,
+                    arch,
+                    url: format!("{}/{}", base_url, file_name),
+                    sha256,
+                    root: root.clone(),
+                });
+            }
+        }
+    }
+
+    Ok(archives)
+}
+
+/// Generates a Homebrew formula for the macOS and Linux archives, if there are any.
+pub fn homebrew_formula(
+    package_config: &PackageConfig,
+    archives: &[ReleaseArchive],
+) -> Option<String> {
+    let sections: Vec<String> = [(Os::MacOS, "on_macos"), (Os::Linux, "on_linux")]
+        .into_iter()
+        .filter_map(|(os, block)| {
+            let archives: Vec<&ReleaseArchive> =
+                archives.iter().filter(|archive| archive.os == os).collect();
+
+            // Prefer a universal binary, then the native binary for each CPU
+            let body = match (
+                archives.iter().find(|archive| archive.arch.is_none()),
+                archives
+                    .iter()
+                    .find(|archive| archive.arch == Some(Arch::Arm64)),
+                archives
+                    .iter()
+                    .find(|archive| archive.arch == Some(Arch::X64)),
+            ) {
+                (Some(universal), _, _) => homebrew_url(universal, "    "),
+                (None, Some(arm), Some(intel)) => format!(
+                    "    if Hardware::CPU.arm?\n{}    else\n{}    end\n",
+                    homebrew_url(arm, "      "),
+                    homebrew_url(intel, "      ")
+                ),
+                (None, Some(archive), None) | (None, None, Some(archive)) => {
+                    homebrew_url(archive, "    ")
+                }
+                (None, None, None) => return None,
+            };
+
+            Some(format!("  {} do\n{}  end\n", block, body))
+        })
+        .collect();
+
+    if sections.is_empty() {
+        return None;
+    }
+
+    let mut formula = format!("class {} < Formula\n", formula_class(&package_config.name));
+
+    if let Some(description) = &package_config.description {
+        formula += &format!("  desc {}\n", ruby_string(description));
+    }
+
+    if let Some(homepage) = &package_config.homepage {
+        formula += &format!("  homepage {}\n", ruby_string(homepage));
+    }
+
+    if let Some(version) = &package_config.version {
+        formula += &format!(
+            "  version {}\n",
+            ruby_string(version.trim_start_matches('v'))
+        );
+    }
+
+    if let Some(license) = &package_config.license {
+        formula += &format!("  license {}\n", ruby_string(license));
+    }
+
+    formula += "\n";
+    formula += &sections.join("\n");
+
+    Some(
+        formula
+            + &format!(
+                "\n  def install\n    bin.install {}\n  end\nend\n",
+                ruby_string(&package_config.name)
+            ),
+    )
+}
+
+/// Generates a Scoop manifest for the Windows archives, if there are any.
+pub fn scoop_manifest(
+    package_config: &PackageConfig,
+    archives: &[ReleaseArchive],
+) -> Option<String> {
+    let mut architectures = Map::new();
+
+    for archive in archives.iter().filter(|archive| archive.os == Os::Windows) {
+        let arch = match archive.arch {
+            Some(Arch::X64) => "64bit",
+            Some(Arch::X86) => "32bit",
+            Some(Arch::Arm64) => "arm64",
+            None => continue,
+        };
+
+        architectures.entry(arch).or_insert_with(|| {
+            json!({
+                "url": archive.url,
+                "hash": archive.sha256,
+                "extract_dir": archive.root,
+            })
+        });
+    }
+
+    if architectures.is_empty() {
+        return None;
+    }
+
+    let mut manifest = Map::new();
+
+    manifest.insert(
+        "version".to_string(),
+        json!(package_config
+            .version
+            .as_deref()
+            .map(|version| version.trim_start_matches('v'))),
+    );
+
+    if let Some(description) = &package_config.description {
+        manifest.insert("description".to_string(), json!(description));
+    }
+
+    if let Some(homepage) = &package_config.homepage {
+        manifest.insert("homepage".to_string(), json!(homepage));
+    }
+
+    if let Some(license) = &package_config.license {
+        manifest.insert("license".to_string(), json!(license));
+    }
+
+    manifest.insert("architecture".to_string(), Value::Object(architectures));
+    manifest.insert(
+        "bin".to_string(),
+        json!(format!("{}.exe", package_config.name)),
+    );
+
+    Some(
+        serde_json::to_string_pretty(&Value::Object(manifest))
+            .expect("Scoop manifests should always serialize!")
+            + "\n",
+    )
+}
+
+/// Formats the `url` and `sha256` of an archive in a Homebrew formula.
+fn homebrew_url(archive: &ReleaseArchive, indent: &str) -> String {
+    format!(
+        "{indent}url {}\n{indent}sha256 {}\n",
+        ruby_string(&archive.url),
+        ruby_string(&archive.sha256),
+    )
+}
+
+/// Gets the class name of a Homebrew formula from the project name, i.e. `my-tool` becomes `MyTool`.
+pub fn formula_class(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Quotes a string for Ruby.
+fn ruby_string(string: &str) -> String {
+    format!(
+        "\"{}\"",
+        string
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('#', "\\#")
+    )
+}
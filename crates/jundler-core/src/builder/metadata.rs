@@ -0,0 +1,128 @@
+use super::{BuildOptions, Builder};
+use crate::js_config::{PackageConfig, SEAConfig};
+use anyhow::{Context, Result};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The key of the SEA asset with the build's metadata in it. Applications can read it with
+/// `require("node:sea").getAsset("jundler.json", "utf8")`.
+pub const METADATA_ASSET: &str = "jundler.json";
+
+/// The sentinel fuse that `postject` flips, so Node.js knows there's a SEA blob to run.
+pub const SEA_FUSE: &str = "NODE_SEA_FUSE_fce680ab2cc467b6e072b8b5df1996b2";
+
+/// What the metadata starts with, to find it in a built binary. The version of jundler is always serialized first.
+const METADATA_PREFIX: &str = "{\"jundlerVersion\":\"";
+
+/// How an application was built, embedded in its SEA blob so a binary can be traced back to its build.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildMetadata {
+    /// The version of jundler that built the binary.
+    pub jundler_version: String,
+
+    /// The name of the application, from `package.json`.
+    pub name: String,
+
+    /// The version of the application, from `package.json`, if it has one.
+    pub version: Option<String>,
+
+    /// The version of Node.js the application was built with.
+    pub node_version: Version,
+
+    /// The platform the binary was built for, i.e. `linux-x64`, or `darwin-universal`.
+    pub target: String,
+
+    /// When the SEA blob was built, as a Unix timestamp. For reproducible builds, this is `SOURCE_DATE_EPOCH`.
+    pub built_at: i64,
+
+    /// The SHA256 checksum of the entrypoint embedded in the SEA blob, which is the bundle for bundled projects.
+    pub bundle_hash: String,
+}
+
+/// What was found in a built binary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Inspection {
+    /// Whether the SEA fuse is flipped, i.e. whether a SEA blob was injected. `None` if the binary doesn't have the
+    /// fuse at all, so it isn't a Node.js binary.
+    pub fuse: Option<bool>,
+
+    /// The metadata embedded by jundler. `None` for binaries built without jundler, or by older versions of it.
+    pub metadata: Option<BuildMetadata>,
+}
+
+impl Builder {
+    /// Writes the build's metadata to the build directory, and embeds it in the SEA blob as an asset. This has to go
+    /// after everything that changes the entrypoint, as the entrypoint's checksum is part of the metadata.
+    pub(super) fn embed_metadata(
+        &self,
+        sea_config: &mut SEAConfig,
+        package_config: &PackageConfig,
+        options: &BuildOptions,
+    ) -> Result<()> {
+        let project_dir = self.working_dir.path().join("project");
+
+        let main_path = project_dir.join(&sea_config.main);
+        let main = fs::read(&main_path)
+            .context(format!("Error reading entrypoint {}", main_path.display()))?;
+
+        let built_at = options.source_date_epoch().unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_secs() as i64)
+        });
+
+        let metadata = BuildMetadata {
+            jundler_version: env!("CARGO_PKG_VERSION").to_string(),
+            name: package_config.name.clone(),
+            version: package_config.version.clone(),
+            node_version: options.node_version.clone(),
+            target: options.target_name(),
+            built_at,
+            bundle_hash: hex::encode(Sha256::digest(main)),
+        };
+
+        let metadata_path = self.working_dir.path().join(METADATA_ASSET);
+
+        fs::write(&metadata_path, serde_json::to_string(&metadata)?)
+            .context("Error writing the build metadata")?;
+
+        sea_config.assets.insert(
+            METADATA_ASSET.to_string(),
+            metadata_path.to_string_lossy().into(),
+        );
+
+        self.write_sea_config(sea_config)
+    }
+}
+
+/// Reads the SEA fuse and the build metadata from a built binary. Universal binaries have the same metadata in both
+/// slices, so the first one found is used.
+pub fn inspect(binary: &Path) -> Result<Inspection> {
+    let content =
+        fs::read(binary).context(format!("Could not read the binary at {}", binary.display()))?;
+
+    let fuse = find(&content, format!("{}:", SEA_FUSE).as_bytes())
+        .and_then(|position| content.get(position + SEA_FUSE.len() + 1))
+        .map(|state| *state == b'1');
+
+    let metadata = find(&content, METADATA_PREFIX.as_bytes()).and_then(|position| {
+        serde_json::Deserializer::from_slice(&content[position..])
+            .into_iter::<BuildMetadata>()
+            .next()?
+            .ok()
+    });
+
+    Ok(Inspection { fuse, metadata })
+}
+
+/// Finds where `needle` first appears in `haystack`.
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
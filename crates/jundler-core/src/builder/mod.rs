@@ -0,0 +1,2139 @@
+pub mod advisories;
+pub mod archive;
+pub mod audit;
+pub mod binary_format;
+mod build_cache;
+pub mod cache_server;
+pub mod cache_stats;
+pub mod checksums;
+pub mod clean;
+mod container;
+pub mod copy;
+mod deps_cache;
+pub mod diff;
+pub mod downloader;
+pub mod environment;
+pub mod errors;
+mod esbuild;
+pub mod git_source;
+pub mod glibc;
+mod helpers;
+pub mod hooks;
+mod licenses;
+pub mod linux_packages;
+mod manifests;
+pub mod metadata;
+mod msi;
+mod native;
+mod native_rebuild;
+pub mod node_manager;
+pub mod node_version;
+mod npm_package;
+pub mod npmrc;
+pub mod output_name;
+pub mod package_manager;
+pub mod plan;
+pub mod platforms;
+pub mod project_lock;
+pub mod provenance;
+pub mod report;
+pub mod reproducible;
+pub mod runtime;
+mod script;
+pub mod settings;
+pub mod signatures;
+pub mod size;
+mod step_times;
+pub mod storage;
+pub mod test_exec;
+pub mod test_suite;
+mod tests;
+mod watch;
+mod windows_resources;
+
+use crate::js_config::{JundlerConfig, PackageConfig, ProjectType, SEAConfig};
+use crate::ui::messages::{
+    ARCHIVE_MSG, BUNDLE_PROJ_MSG, CACHED_BUILD_MSG, CHECKSUMS_MSG, CLEAN_CACHE_MSG, COPY_PROJ_MSG,
+    CUSTOM_SIGN_MSG, DOCKER_MSG, ESBUILD_BINARY_MSG, FETCH_NODE_MSG, GEN_SEA_BLOB_MSG,
+    GIT_CLONE_MSG, HASH_PROJ_MSG, HOST_NODE_MSG, INJECT_APP_MSG, INSTALL_DEPS_MSG, LICENSES_MSG,
+    LINUX_PACKAGES_MSG, LIPO_MSG, MACOS_CODESIGN_MSG, MANIFESTS_MSG, MSI_MSG, NOTARIZE_MSG,
+    NPM_PACKAGE_MSG, PREPARE_APP_MSG, PROVENANCE_MSG, RUNTIME_BINARY_MSG, RUNTIME_COMPILE_MSG,
+    SIGNATURES_MSG, TARGET_NODE_MSG, WINDOWS_CODESIGN_MSG, WINDOWS_RESOURCES_MSG,
+};
+use crate::ui::{NoopReporter, Reporter, Step, Tracker};
+use advisories::AdvisoryPolicy;
+use anyhow::{anyhow, Context, Ok, Result};
+use archive::ArchiveFormat;
+use build_cache::{BuildArtifacts, BuildCache};
+use checksums::{ChecksumFormat, CHECKSUMS_FILE_NAME};
+use copy::CopyStrategy;
+use deps_cache::DepsCache;
+use downloader::{Downloader, HttpDownloader};
+use environment::SanitizedEnv;
+use errors::{Classify, ErrorClass};
+use esbuild::{ESBuild, ESBUILD_VERSION};
+use git_source::GitSource;
+use helpers::{is_available, LoggedOutput};
+use hooks::{Bundler, Hooks, NoHooks, Signer};
+use licenses::{licenses_shim, NOTICES_FILE_NAME};
+use linux_packages::LinuxPackageFormat;
+use log::debug;
+use node_manager::{HostNode, NodeFetcher, NodeManager};
+use npmrc::NpmConfig;
+use package_manager::PackageManager;
+use platforms::{get_host_arch, get_host_os, Arch, Os, Target};
+use project_lock::esbuild_binary_name;
+use project_lock::{node_binary_name, runtime_binary_name, ProjectLock, LOCK_FILE_NAME};
+use rand::distributions::{Alphanumeric, DistString};
+use runtime::{Runtime, RuntimeManager};
+use semver::Version;
+use sha2::{Digest, Sha256};
+use signatures::SignatureFormat;
+use std::env;
+use std::fs::{self, File};
+use std::iter;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{mpsc, Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
+use step_times::StepTimes;
+use tempdir::TempDir;
+use windows_resources::VersionInfo;
+
+pub struct Builder {
+    /// The directory to build the project in.
+    working_dir: TempDir,
+
+    /// The directory everything is cached in.
+    cache_dir: PathBuf,
+
+    /// The Node.js manager. Shared, so that Node.js binaries can be downloaded in the background while the rest of the
+    /// build runs.
+    node_manager: Arc<Mutex<NodeManager>>,
+
+    /// The host Node.js binary (and npm) from the last build, if any.
+    host_node: Option<HostNode>,
+
+    /// The Node.js binaries still being downloaded in the background, one thread each.
+    target_downloads: Vec<JoinHandle<Result<()>>>,
+
+    /// The ESBuild instance
+    esbuild: ESBuild,
+
+    /// The manager of the binaries of the other runtimes (Bun and Deno)
+    runtimes: RuntimeManager,
+
+    /// The cache of previously built SEA blobs
+    build_cache: BuildCache,
+
+    /// The cache of previously installed dependencies
+    deps_cache: DepsCache,
+
+    /// A key for the dependencies currently installed in the build directory, if any. Used to skip reinstalling when
+    /// the same build directory is reused (i.e. in watch mode).
+    installed_deps: Option<String>,
+
+    /// The directory that projects from elsewhere (single scripts and npm packages) are put in, one directory per
+    /// source.
+    sources_dir: PathBuf,
+
+    /// The directory with how long the steps of each project's last builds took.
+    timings_dir: PathBuf,
+
+    /// The `jundler.lock` of the project being built, if it has one.
+    project_lock: Option<ProjectLock>,
+
+    /// Keeps track of the steps of builds, and reports them.
+    pub tracker: Tracker,
+
+    /// What to call with every file builds write (along with every step and warning, through the tracker).
+    hooks: Arc<dyn Hooks>,
+
+    /// What bundles projects in place of ESBuild, if anything.
+    bundler: Option<Arc<dyn Bundler>>,
+
+    /// What signs binaries in place of the built-in tools, if anything.
+    signer: Option<Arc<dyn Signer>>,
+
+    /// What downloads everything that isn't a binary, like the database of vulnerabilities in Node.js.
+    downloader: Arc<dyn Downloader>,
+}
+
+impl Builder {
+    /// Creates a new builder instance, which builds without any output. Expects that `cache_dir` is a valid directory.
+    pub fn new(cache_dir: PathBuf) -> Result<Self> {
+        Self::with_reporter(cache_dir, Arc::new(NoopReporter))
+    }
+
+    /// Creates a new builder instance that reports what it's doing to `reporter`, i.e. a `TerminalReporter` to show
+    /// spinners. Expects that `cache_dir` is a valid directory.
+    pub fn with_reporter(cache_dir: PathBuf, reporter: Arc<dyn Reporter>) -> Result<Self> {
+        // Nothing can be built (or downloaded) for a host that Node.js doesn't support
+        Os::host()?;
+        Arch::host()?;
+
+        // Create a temporary directory to store the build files.
+        let temp_dir = TempDir::new(
+            format!(
+                "node-build-{}",
+                Alphanumeric.sample_string(&mut rand::thread_rng(), 16)
+            )
+            .as_str(),
+        )
+        .context("Could not create a temporary directory to build in!")?;
+
+        // Create the node cache dir
+        let node_cache_dir = cache_dir.join("node");
+        fs::create_dir_all(&node_cache_dir).context("Could not create the cache directory!")?;
+
+        // Create the esbuild cache dir
+        let esbuild_cache_dir = cache_dir.join("esbuild");
+        fs::create_dir_all(&esbuild_cache_dir).context("Could not create the cache directory!")?;
+
+        // Create the build cache dir
+        let build_cache_dir = cache_dir.join("builds");
+        fs::create_dir_all(&build_cache_dir).context("Could not create the cache directory!")?;
+
+        // Create the dependency cache dir
+        let deps_cache_dir = cache_dir.join("deps");
+        fs::create_dir_all(&deps_cache_dir).context("Could not create the cache directory!")?;
+
+        // Create the sources dir
+        let sources_dir = cache_dir.join("sources");
+        fs::create_dir_all(&sources_dir).context("Could not create the cache directory!")?;
+
+        // Create the timings dir
+        let timings_dir = cache_dir.join("timings");
+        fs::create_dir_all(&timings_dir).context("Could not create the cache directory!")?;
+
+        // The runtimes cache dir is created when the first runtime is downloaded
+        let runtimes_cache_dir = cache_dir.join("runtimes");
+
+        let builder = Self {
+            working_dir: temp_dir,
+            cache_dir,
+            node_manager: Arc::new(Mutex::new(
+                NodeManager::new(node_cache_dir)?.with_reporter(reporter.clone()),
+            )),
+            host_node: None,
+            target_downloads: Vec::new(),
+            esbuild: ESBuild::new(esbuild_cache_dir)?.with_reporter(reporter.clone()),
+            runtimes: RuntimeManager::new(runtimes_cache_dir).with_reporter(reporter.clone()),
+            build_cache: BuildCache::new(build_cache_dir),
+            deps_cache: DepsCache::new(deps_cache_dir),
+            installed_deps: None,
+            sources_dir,
+            timings_dir,
+            project_lock: None,
+            tracker: Tracker::new(reporter),
+            hooks: Arc::new(NoHooks),
+            bundler: None,
+            signer: None,
+            downloader: Arc::new(HttpDownloader),
+        };
+
+        Ok(builder)
+    }
+
+    /// Downloads Node.js, ESBuild, the other runtimes, and the vulnerability database with `downloader` from now on,
+    /// instead of from the internet.
+    pub fn set_downloader(&mut self, downloader: Arc<dyn Downloader>) {
+        self.node_manager().set_downloader(downloader.clone());
+        self.runtimes.set_downloader(downloader.clone());
+        self.esbuild.set_downloader(downloader.clone());
+        self.downloader = downloader;
+    }
+
+    /// What Node.js, ESBuild, the other runtimes, and everything else are downloaded with.
+    pub fn downloader(&self) -> &dyn Downloader {
+        self.downloader.as_ref()
+    }
+
+    /// Calls `hooks` with every step, file, and warning of builds from now on.
+    pub fn set_hooks(&mut self, hooks: Arc<dyn Hooks>) {
+        self.tracker.set_hooks(hooks.clone());
+        self.hooks = hooks;
+    }
+
+    /// Bundles projects with `bundler` from now on, instead of ESBuild.
+    pub fn set_bundler(&mut self, bundler: Arc<dyn Bundler>) {
+        self.bundler = Some(bundler);
+    }
+
+    /// Signs binaries with `signer` from now on, instead of `codesign`, `rcodesign`, or `signtool`.
+    pub fn set_signer(&mut self, signer: Arc<dyn Signer>) {
+        self.signer = Some(signer);
+    }
+
+    /// Cleans the cache directory of the Node.js manager.
+    pub fn clean_cache(&mut self) -> Result<()> {
+        let spinner = self.tracker.spawn_spinner(CLEAN_CACHE_MSG, 0);
+
+        let cleaned = self.clean_caches();
+
+        spinner.finish(cleaned)
+    }
+
+    /// Cleans every cache. See `clean_cache`.
+    fn clean_caches(&mut self) -> Result<()> {
+        self.node_manager().clean_cache()?;
+
+        self.esbuild.clean_cache()?;
+
+        self.runtimes.clean_cache()?;
+
+        self.build_cache.clean_cache()?;
+
+        self.deps_cache.clean_cache()?;
+
+        fs::remove_dir_all(&self.sources_dir).context("Could not clean the sources cache!")?;
+        fs::create_dir_all(&self.sources_dir).context("Could not create the cache directory!")?;
+
+        fs::remove_dir_all(&self.timings_dir).context("Could not clean the timings cache!")?;
+        fs::create_dir_all(&self.timings_dir).context("Could not create the cache directory!")?;
+
+        Ok(())
+    }
+
+    /// Downloads the host Node.js binary, the Node.js binaries for each of the targets, and ESBuild into the cache
+    /// without building anything, so later builds (even offline ones) don't need to download them. If `project_dir` is
+    /// a project, their checksums (and the version of Node.js) are pinned in its `jundler.lock`.
+    pub fn fetch(
+        &mut self,
+        project_dir: &Path,
+        node_version: &Version,
+        targets: &[Target],
+    ) -> Result<()> {
+        let fetched = self.fetch_binaries(project_dir, node_version, targets);
+
+        self.report_failure(fetched)
+    }
+
+    /// Downloads and pins the binaries. See `fetch`.
+    fn fetch_binaries(
+        &mut self,
+        project_dir: &Path,
+        node_version: &Version,
+        targets: &[Target],
+    ) -> Result<()> {
+        self.set_frozen(false);
+
+        self.project_lock = match ProjectLock::load(project_dir)? {
+            Some(lock) => Some(lock),
+            None if project_dir.join("package.json").is_file() => {
+                Some(ProjectLock::new(project_dir))
+            }
+            None => None,
+        };
+
+        let spinner = self.tracker.spawn_spinner(HOST_NODE_MSG, 0);
+
+        let host_node = self.host_node(node_version)?;
+
+        self.check_pin(
+            &node_binary_name(node_version, get_host_os(), get_host_arch()),
+            &host_node.node,
+            false,
+        )?;
+
+        spinner.close();
+
+        for target in targets {
+            let spinner = self
+                .tracker
+                .spawn_spinner(format!("{} {}", FETCH_NODE_MSG, target), 0);
+
+            let binary = self
+                .node_manager()
+                .get_binary(node_version, target.os, target.arch)?;
+
+            self.check_pin(
+                &node_binary_name(node_version, target.os, target.arch),
+                &binary,
+                false,
+            )?;
+
+            spinner.close();
+        }
+
+        let spinner = self.tracker.spawn_spinner(ESBUILD_BINARY_MSG, 0);
+
+        let esbuild_bin = self.esbuild.get_binary()?;
+
+        self.check_pin(
+            &esbuild_binary_name(&ESBUILD_VERSION, get_host_os(), get_host_arch()),
+            &esbuild_bin,
+            false,
+        )?;
+
+        spinner.close();
+
+        if let Some(lock) = &mut self.project_lock {
+            lock.node_version = Some(node_version.clone());
+            lock.save()?;
+        }
+
+        Ok(())
+    }
+
+    /// Builds the Node.js binary with the SEA blob, outputting it in the output directory (or the project directory
+    /// if there is none). `project_dir` can also be a single JavaScript or TypeScript file, which is built into a
+    /// binary named after it, next to it. Returns what was built. If the build fails, the error is shown next to the
+    /// step that failed.
+    pub fn build(&mut self, project_dir: &Path, options: &BuildOptions) -> Result<BuildOutput> {
+        // Long steps show how much longer they'll probably take, from how long they took in the last builds
+        let mut step_times = StepTimes::load(&self.timings_dir, project_dir);
+        let started = Instant::now();
+        let recording = self.tracker.recording();
+        let (first_step, first_warning) = (recording.steps.len(), recording.warnings.len());
+
+        self.tracker.set_estimates(step_times.estimates());
+
+        let mut output = if project_dir.is_file() {
+            self.build_script(project_dir, options)
+        } else {
+            self.build_project(project_dir, options)
+        };
+
+        if let Result::Ok(output) = &mut output {
+            let mut recording = self.tracker.recording();
+
+            output.steps = recording.steps.split_off(first_step);
+            output.warnings = recording.warnings.split_off(first_warning);
+            output.duration = started.elapsed();
+
+            step_times.record(&output.steps);
+
+            if let Err(err) = step_times.save() {
+                debug!("{:?}", err);
+            }
+        }
+
+        self.report_failure(output)
+    }
+
+    /// Shows the error next to the step that failed, if `result` is one, and passes it on.
+    fn report_failure<T>(&self, result: Result<T>) -> Result<T> {
+        if let Err(err) = &result {
+            self.tracker.fail_step(err);
+        }
+
+        result
+    }
+
+    /// Builds the project in `project_dir`. See `build`.
+    fn build_project(&mut self, project_dir: &Path, options: &BuildOptions) -> Result<BuildOutput> {
+        let started = SystemTime::now();
+        let (target_os, target_arch) = (options.target_os, options.target_arch);
+
+        // Get the configuration
+        let (sea_config, package_config) = get_configs(project_dir).classify(ErrorClass::Config)?;
+        let host_os = get_host_os();
+        let excludes = output_excludes(&package_config, options);
+
+        check_options(project_dir, &package_config, options).classify(ErrorClass::Config)?;
+
+        self.set_frozen(options.frozen);
+        self.project_lock = ProjectLock::load(project_dir).classify(ErrorClass::Config)?;
+
+        match &mut self.project_lock {
+            None if options.frozen => {
+                return Err(anyhow!(
+                    "Frozen builds check the checksums pinned in `{}`, but the project has none! Run `jundler fetch` first.",
+                    LOCK_FILE_NAME
+                )).classify(ErrorClass::Checksum);
+            }
+
+            Some(lock)
+                if !options.frozen
+                    && options.runtime == Runtime::Node
+                    && lock.node_version.as_ref() != Some(&options.node_version) =>
+            {
+                lock.node_version = Some(options.node_version.clone());
+                lock.save()?;
+            }
+
+            _ => {}
+        }
+
+        if options.runtime == Runtime::Node {
+            self.check_advisories(
+                &options.node_version,
+                options.node_advisories,
+                options.frozen,
+            )
+            .classify(ErrorClass::Config)?;
+        }
+
+        let codesign_identity = codesign_identity(&package_config, options);
+        let macos_certificate = macos_certificate(project_dir, &package_config, options);
+        let release_url = release_url(&package_config, options);
+
+        debug!("Build in directory: {}", self.working_dir.path().display());
+
+        // Other runtimes compile the project into a binary themselves, so there's no SEA blob to cache or inject
+        let (target_bin, sea_blob, notices, bundle, cached) = if options.runtime == Runtime::Node {
+            let spinner = self.tracker.spawn_spinner(HASH_PROJ_MSG, 0);
+
+            let inputs_hash =
+                spinner.finish(BuildCache::inputs_hash(project_dir, &excludes, options))?;
+
+            let cached_artifacts = if options.force {
+                None
+            } else {
+                self.build_cache.get(project_dir, options, &inputs_hash)
+            };
+
+            let cached = cached_artifacts.is_some();
+
+            let artifacts = match cached_artifacts {
+                Some(artifacts) => {
+                    let spinner = self.tracker.spawn_spinner(CACHED_BUILD_MSG, 0);
+                    spinner.close();
+
+                    artifacts
+                }
+
+                None => {
+                    let artifacts =
+                        self.prepare_sea_blob(project_dir, options, sea_config, &package_config)?;
+
+                    // Frozen builds leave the cache as it is
+                    if options.frozen {
+                        artifacts
+                    } else {
+                        self.build_cache
+                            .insert(project_dir, options, &inputs_hash, &artifacts)?
+                    }
+                }
+            };
+
+            let target_bin = if options.universal {
+                self.build_universal(&artifacts.sea_blob, options)?
+            } else {
+                self.prepare_target_binary(&artifacts.sea_blob, target_arch, options)?
+            };
+
+            (
+                target_bin,
+                Some(artifacts.sea_blob),
+                artifacts.notices,
+                artifacts.bundle,
+                cached,
+            )
+        } else {
+            let binary =
+                self.compile_with_runtime(project_dir, &sea_config, &package_config, options)?;
+
+            (binary, None, None, None, false)
+        };
+
+        // Move the binary to the current directory
+        let app_name = output_name::render(
+            output_name_template(&package_config, options),
+            &package_config,
+            target_os,
+            (!options.universal).then_some(target_arch),
+        )?;
+
+        let output_dir = options.output_dir.as_deref().unwrap_or(project_dir);
+        let app_path = output_dir.join(app_name);
+
+        fs::copy(target_bin, &app_path)
+            .context("Error moving built binary to current working directory")?;
+
+        // Give Windows binaries the application's own version information, instead of Node.js's
+        if target_os == Os::Windows && options.runtime != Runtime::Node {
+            self.tracker.warn(format!(
+                "Warning: Not writing Windows version information, as it's only written to Node.js binaries, not {} ones.",
+                options.runtime
+            ));
+        } else if target_os == Os::Windows {
+            let spinner = self.tracker.spawn_spinner(WINDOWS_RESOURCES_MSG, 0);
+
+            let config = &package_config.jundler.windows;
+
+            windows_resources::set_resources(
+                &app_path,
+                &VersionInfo::new(&package_config, config),
+                windows_resources::manifest(project_dir, config)?.as_deref(),
+            )
+            .context("Error writing version information to the Windows binary")?;
+
+            spinner.close();
+        }
+
+        if let Some(epoch) = options.source_date_epoch() {
+            reproducible::set_mtime(&app_path, epoch)
+                .context("Error setting the timestamp of the built binary")?;
+        }
+
+        debug!("Binary moved to: {}", app_path.display());
+
+        let mut files = vec![app_path.clone()];
+
+        if let (true, Some(notices)) = (options.licenses, &notices) {
+            let notices_path = output_dir.join(NOTICES_FILE_NAME);
+
+            fs::copy(notices, &notices_path)
+                .context("Error copying third-party notices to the output directory")?;
+
+            files.push(notices_path);
+        }
+
+        // Codesign the binary if we're on MacOS
+        match (host_os, target_os) {
+            _ if options.sign && self.signer.is_some() => {
+                let spinner = self.tracker.spawn_spinner(CUSTOM_SIGN_MSG, 0);
+                let signer = self.signer.as_ref().expect("Checked for a signer above!");
+
+                spinner
+                    .finish(signer.sign(&app_path, target_os))
+                    .classify(ErrorClass::Signing)?;
+
+                if options.notarize && target_os == Os::MacOS {
+                    let spinner = self.tracker.spawn_spinner(NOTARIZE_MSG, 0);
+                    let config = &package_config.jundler.macos.notarize;
+
+                    if host_os == Os::MacOS {
+                        self.macos_notarize(&app_path, project_dir, config)
+                    } else {
+                        self.rcodesign_notarize(&app_path, project_dir, config)
+                    }
+                    .classify(ErrorClass::Signing)?;
+
+                    spinner.close();
+                }
+            }
+
+            (_, Os::MacOS) if !options.sign => {
+                self.tracker
+                    .warn("Warning: Not codesigning the binary because signing is turned off.");
+                self.tracker
+                    .warn("The binary has to be signed (even ad-hoc) before it can run on macOS.");
+            }
+
+            _ if !options.sign => {
+                // Signing is turned off
+            }
+
+            (Os::MacOS, Os::MacOS) => {
+                let spinner = self.tracker.spawn_spinner(MACOS_CODESIGN_MSG, 0);
+                let config = &package_config.jundler.macos;
+
+                self.macos_codesign(&app_path, codesign_identity, project_dir, config)
+                    .classify(ErrorClass::Signing)?;
+                spinner.close();
+
+                if options.notarize {
+                    let spinner = self.tracker.spawn_spinner(NOTARIZE_MSG, 0);
+                    self.macos_notarize(&app_path, project_dir, &config.notarize)
+                        .classify(ErrorClass::Signing)?;
+                    spinner.close();
+                }
+            }
+
+            (_, Os::MacOS) if is_available("rcodesign") => {
+                let spinner = self.tracker.spawn_spinner(MACOS_CODESIGN_MSG, 0);
+                let config = &package_config.jundler.macos;
+
+                if codesign_identity.is_some() && macos_certificate.is_none() {
+                    spinner.suspend(|| {
+                        self.tracker.warn("Warning: Codesigning identities only work on MacOS, so the binary is ad-hoc signed instead.");
+                        self.tracker.warn(
+                            "Pass `--sign-certificate` to sign it with a certificate on this OS.",
+                        );
+                    });
+                }
+
+                self.rcodesign(&app_path, macos_certificate.as_deref(), project_dir, config)
+                    .classify(ErrorClass::Signing)?;
+                spinner.close();
+
+                if options.notarize {
+                    let spinner = self.tracker.spawn_spinner(NOTARIZE_MSG, 0);
+                    self.rcodesign_notarize(&app_path, project_dir, &config.notarize)
+                        .classify(ErrorClass::Signing)?;
+                    spinner.close();
+                }
+            }
+
+            (_, Os::MacOS) => {
+                self.tracker
+                    .warn("Warning: Not codesigning the binary because the host OS is not MacOS, and `rcodesign` isn't installed.");
+                self.tracker
+                    .warn("This will cause an error when running the binary on MacOS.");
+                self.tracker
+                    .warn("Please codesign the binary manually, or install `rcodesign` to sign it on this OS.");
+
+                if options.notarize {
+                    return Err(anyhow!("Notarizing on other OSes needs `rcodesign`!"))
+                        .classify(ErrorClass::Signing);
+                }
+            }
+
+            (Os::Windows, Os::Windows) => {
+                let spinner = self.tracker.spawn_spinner(WINDOWS_CODESIGN_MSG, 0);
+                self.windows_sign(
+                    &app_path,
+                    project_dir,
+                    options.sign_certificate.as_deref(),
+                    &options.windows_sign,
+                    &package_config.jundler.windows,
+                    &options.pass_env,
+                )
+                .classify(ErrorClass::Signing)?;
+                spinner.close();
+            }
+
+            (_, Os::Windows) => {
+                self.tracker
+                    .warn("Warning: Not signing the binary because the host OS is not Windows.");
+                self.tracker
+                    .warn("The binary will still be runnable, but it will raise a warning message with the user.");
+                self.tracker
+                    .warn("Please sign the binary manually before distributing or running it.");
+            }
+
+            _ => {
+                // Don't codesign the binary
+            }
+        }
+
+        check_output_platform(&app_path, options)?;
+
+        let min_glibc = match target_os {
+            Os::Linux => self.check_glibc(&app_path, &package_config.jundler.linux)?,
+            _ => None,
+        };
+
+        // Nothing changes the binary after codesigning, so this is its final size
+        let bundle = bundle
+            .map(|bundle| report::BundleContents::read(&bundle))
+            .transpose()?;
+        let size = sea_blob
+            .map(|sea_blob| {
+                size::SizeBreakdown::new(&app_path, &sea_blob, options.universal, bundle.as_ref())
+            })
+            .transpose()?;
+
+        self.check_max_size(&app_path, size.as_ref(), &package_config.jundler, options)?;
+
+        // Tested after codesigning, as macOS only runs signed binaries
+        if let Some(args) = &options.test_exec {
+            self.test_exec(&app_path, args, options)?;
+        }
+
+        // The installer goes after codesigning, so it installs the signed binary
+        if options.msi {
+            let spinner = self.tracker.spawn_spinner(MSI_MSG, 0);
+
+            let msi_path = self.build_msi(&app_path, &package_config, options)?;
+
+            if let (true, Some(signer)) = (options.sign, &self.signer) {
+                signer
+                    .sign(&msi_path, Os::Windows)
+                    .classify(ErrorClass::Signing)?;
+            } else if host_os == Os::Windows && options.sign {
+                self.windows_sign(
+                    &msi_path,
+                    project_dir,
+                    options.sign_certificate.as_deref(),
+                    &options.windows_sign,
+                    &package_config.jundler.windows,
+                    &options.pass_env,
+                )
+                .classify(ErrorClass::Signing)?;
+            }
+
+            spinner.close();
+
+            write_extra_checksums(&msi_path, options, &mut files)?;
+
+            debug!("Installer written to: {}", msi_path.display());
+
+            files.push(msi_path);
+        }
+
+        // Checksums and archives go last, as codesigning changes the binary
+        if let Some(format) = options.checksums {
+            let spinner = self.tracker.spawn_spinner(CHECKSUMS_MSG, 0);
+            files.extend(checksums::write_checksums(
+                std::slice::from_ref(&app_path),
+                format,
+            )?);
+            spinner.close();
+        }
+
+        if let Some(format) = options.archive {
+            let spinner = self.tracker.spawn_spinner(ARCHIVE_MSG, 0);
+
+            let archive_path = self.package_archive(
+                project_dir,
+                &app_path,
+                &package_config,
+                notices.as_deref(),
+                options,
+                format,
+            )?;
+
+            write_extra_checksums(&archive_path, options, &mut files)?;
+
+            spinner.close();
+
+            debug!("Archive written to: {}", archive_path.display());
+
+            files.push(archive_path);
+        }
+
+        if let (true, Some(release_url)) = (options.manifests, release_url) {
+            let spinner = self.tracker.spawn_spinner(MANIFESTS_MSG, 0);
+
+            for manifest_path in
+                manifests::write_manifests(output_dir, &package_config, release_url)?
+            {
+                debug!("Manifest written to: {}", manifest_path.display());
+
+                files.push(manifest_path);
+            }
+
+            spinner.close();
+        }
+
+        if !options.linux_packages.is_empty() {
+            let spinner = self.tracker.spawn_spinner(LINUX_PACKAGES_MSG, 0);
+
+            for format in &options.linux_packages {
+                let package_path =
+                    self.package_linux(project_dir, &app_path, &package_config, options, *format)?;
+
+                write_extra_checksums(&package_path, options, &mut files)?;
+
+                debug!("Package written to: {}", package_path.display());
+
+                files.push(package_path);
+            }
+
+            spinner.close();
+        }
+
+        // Other runtimes bundle the project themselves
+        let bundled = options.runtime == Runtime::Node && should_bundle(&package_config, options);
+
+        // Provenance goes right before signatures, so it's signed along with everything it describes
+        if options.provenance {
+            let spinner = self.tracker.spawn_spinner(PROVENANCE_MSG, 0);
+
+            let provenance_path = self.write_provenance(
+                project_dir,
+                &app_path,
+                &files,
+                bundled && self.bundler.is_none(),
+                options,
+                started,
+            )?;
+
+            spinner.close();
+
+            debug!("Provenance written to: {}", provenance_path.display());
+
+            files.push(provenance_path);
+        }
+
+        // Signatures go after everything else, so they cover the final checksums and archive
+        if let Some(format) = options.signatures {
+            let spinner = self.tracker.spawn_spinner(SIGNATURES_MSG, 0);
+            let signed = signatures::write_signatures(
+                &files,
+                format,
+                options.signing_key.as_deref(),
+                project_dir,
+                &package_config.jundler.signatures,
+            )
+            .classify(ErrorClass::Signing)?;
+            spinner.close();
+
+            files.extend(signed);
+        }
+
+        let mut image = None;
+
+        if let Some(tag) = &options.docker_image {
+            let spinner = self.tracker.spawn_spinner(DOCKER_MSG, 0);
+
+            let context_dir = output_dir.join(container::context_dir_name(&package_config.name));
+            container::write_context(&context_dir, &app_path, &package_config, target_arch)?;
+
+            if is_available("docker") {
+                self.docker_build(&context_dir, tag, target_arch)?;
+                image = Some(tag.clone());
+            } else {
+                spinner.suspend(|| {
+                    self.tracker.warn(
+                        "Warning: Not building the container image because Docker isn't installed.",
+                    );
+                    self.tracker.warn(format!(
+                        "The build context is in {}; build it with `docker build --platform {} --tag {} {}`.",
+                        context_dir.display(),
+                        container::platform(target_arch),
+                        tag,
+                        context_dir.display()
+                    ));
+                });
+            }
+
+            spinner.close();
+
+            files.push(context_dir.join("Dockerfile"));
+        }
+
+        let files = files
+            .into_iter()
+            .map(OutputFile::read)
+            .collect::<Result<Vec<_>>>()?;
+
+        for file in &files {
+            self.hooks.on_artifact(&file.path).context(format!(
+                "Error handing {} to the build hooks",
+                file.path.display()
+            ))?;
+        }
+
+        Ok(BuildOutput {
+            app_path,
+            files,
+            target_os,
+            target_arch,
+            universal: options.universal,
+            runtime: options.runtime,
+            node_version: options.node_version.clone(),
+            cached,
+            bundled,
+            esbuild_version: (bundled && self.bundler.is_none()).then_some(ESBUILD_VERSION),
+            bundle,
+            image,
+            min_glibc,
+            size,
+            // Filled in by `build`, which sees every step of the build
+            steps: vec![],
+            warnings: vec![],
+            duration: Duration::ZERO,
+        })
+    }
+}
+
+impl Builder {
+    /// Clones a project from a git repository into the cache, at the branch, tag, or commit it names. Only that commit is
+    /// fetched. Returns the path to the clone, which is built like any other project. It's the same for every build of
+    /// the repository and reference (see `source_dir`), so the build and dependency caches still work.
+    pub fn clone_git(&mut self, source: &GitSource) -> Result<PathBuf> {
+        if !is_available("git") {
+            return Err(anyhow!(
+                "Building from a git repository needs git! Install it from https://git-scm.com."
+            ));
+        }
+
+        let spinner = self.tracker.spawn_spinner(GIT_CLONE_MSG, 0);
+
+        // Fetching just the one commit works for branches, tags, and commits alike, unlike `git clone --branch`
+        let reference = source.reference.as_deref().unwrap_or("HEAD");
+
+        // Every repository and reference gets its own directory, so several can be built at once
+        let clone_dir = self.source_dir(&format!("git+{}#{}", source.url, reference));
+
+        if clone_dir.exists() {
+            fs::remove_dir_all(&clone_dir).context("Could not clean the clone directory!")?;
+        }
+
+        fs::create_dir_all(&clone_dir).context("Could not create the clone directory!")?;
+
+        self.git(&clone_dir, &["init", "--quiet"])?;
+        self.git(&clone_dir, &["remote", "add", "origin", &source.url])?;
+        self.git(&clone_dir, &["fetch", "--depth", "1", "origin", reference])?;
+        self.git(&clone_dir, &["checkout", "--quiet", "FETCH_HEAD"])?;
+
+        if clone_dir.join(".gitmodules").exists() {
+            self.git(
+                &clone_dir,
+                &[
+                    "submodule",
+                    "update",
+                    "--init",
+                    "--recursive",
+                    "--depth",
+                    "1",
+                ],
+            )?;
+        }
+
+        spinner.close();
+
+        debug!(
+            "Cloned {} ({}) to {}",
+            source.url,
+            reference,
+            clone_dir.display()
+        );
+
+        Ok(clone_dir)
+    }
+
+    /// Builds the command of a package from the npm registry, i.e. `cowsay@1.6.0`, into a binary named after the
+    /// command, in the output directory (or the current directory if there is none). Returns what was built.
+    pub fn build_package(&mut self, spec: &str, options: &BuildOptions) -> Result<BuildOutput> {
+        let spinner = self.tracker.spawn_spinner(NPM_PACKAGE_MSG, 0);
+
+        let host_node = self.host_node(&options.node_version)?;
+        let project_dir = self.source_dir(spec);
+        let pack_dir = self.working_dir.path().join("pack");
+
+        for dir in [&project_dir, &pack_dir] {
+            if dir.exists() {
+                fs::remove_dir_all(dir).context(format!("Could not clean {}", dir.display()))?;
+            }
+
+            fs::create_dir_all(dir).context(format!("Could not create {}", dir.display()))?;
+        }
+
+        // Packages from private registries need the same configuration as installing them does
+        let npm_config = NpmConfig::find(&env::current_dir()?, options.npmrc.as_deref())
+            .classify(ErrorClass::Config)?;
+
+        let pack_output = npm_config
+            .apply(
+                host_node
+                    .npm()
+                    .arg("pack")
+                    .arg(spec)
+                    .arg("--pack-destination")
+                    .arg(&pack_dir),
+                &options.pass_env,
+            )
+            .logged_output()
+            .context("Error running npm pack")?;
+
+        if !pack_output.status.success() {
+            return Err(anyhow!(
+                "Error downloading {} from the npm registry:\n{}\n{}",
+                spec,
+                String::from_utf8_lossy(&pack_output.stdout),
+                String::from_utf8_lossy(&pack_output.stderr)
+            ));
+        }
+
+        // `npm pack` prints the name of the tarball last
+        let tarball = String::from_utf8_lossy(&pack_output.stdout)
+            .lines()
+            .last()
+            .map(|name| pack_dir.join(name.trim()))
+            .ok_or_else(|| anyhow!("npm pack didn't download {}!", spec))?;
+
+        npm_package::unpack(&tarball, &project_dir)?;
+        let command = npm_package::prepare_project(&project_dir)?;
+
+        spinner.close();
+
+        debug!("Building `{}` from {}", command, spec);
+
+        let output_dir = match &options.output_dir {
+            Some(output_dir) => output_dir.clone(),
+            None => std::env::current_dir().context("Could not get the current directory!")?,
+        };
+
+        self.build(
+            &project_dir,
+            &BuildOptions {
+                bundle: true,
+                output_dir: Some(output_dir),
+                ..options.clone()
+            },
+        )
+    }
+
+    /// Builds a single script, by turning it into a minimal project in the cache. The project's directory is the same
+    /// for every build of the script, so the build cache still works.
+    fn build_script(&mut self, script: &Path, options: &BuildOptions) -> Result<BuildOutput> {
+        let project_dir = self.source_dir(&script.to_string_lossy());
+
+        script::write_script_project(script, &project_dir)?;
+
+        let options = BuildOptions {
+            bundle: true,
+            output_dir: options
+                .output_dir
+                .clone()
+                .or_else(|| script.parent().map(Path::to_path_buf)),
+            ..options.clone()
+        };
+
+        self.build(&project_dir, &options)
+    }
+
+    /// Gets the directory in the cache to put a project from elsewhere in, which is the same every time for the same
+    /// source.
+    fn source_dir(&self, source: &str) -> PathBuf {
+        let hash = hex::encode(Sha256::digest(source.as_bytes()));
+
+        self.sources_dir.join(&hash[..16])
+    }
+
+    /// Gets the target Node.js binary for an architecture and injects the SEA blob into it. Returns the path to the
+    /// injected binary.
+    fn prepare_target_binary(
+        &mut self,
+        sea_blob: &Path,
+        target_arch: Arch,
+        options: &BuildOptions,
+    ) -> Result<PathBuf> {
+        let spinner = self.tracker.spawn_spinner(TARGET_NODE_MSG, 0);
+
+        for download in self.target_downloads.drain(..) {
+            download.join().map_err(|_| {
+                anyhow!("Error retrieving Node.js binaries: the download thread panicked!")
+            })??;
+        }
+
+        let target_node_bin = self.node_manager().get_binary(
+            &options.node_version,
+            options.target_os,
+            target_arch,
+        )?;
+
+        self.check_pin(
+            &node_binary_name(&options.node_version, options.target_os, target_arch),
+            &target_node_bin,
+            options.frozen,
+        )?;
+
+        spinner.close();
+
+        if options.strip {
+            self.strip_binary(&target_node_bin, options.target_os)
+                .classify(ErrorClass::Injection)?;
+        }
+
+        let spinner = self.tracker.spawn_spinner(HOST_NODE_MSG, 0);
+
+        let host_node = self.host_node(&options.node_version)?;
+
+        self.check_pin(
+            &node_binary_name(&options.node_version, get_host_os(), get_host_arch()),
+            &host_node.node,
+            options.frozen,
+        )?;
+
+        spinner.close();
+
+        let spinner = self.tracker.spawn_spinner(INJECT_APP_MSG, 0);
+
+        // Inject the app into the node binary
+        spinner
+            .finish(self.inject_app(&host_node, &target_node_bin, sea_blob, options))
+            .classify(ErrorClass::Injection)?;
+
+        Ok(target_node_bin)
+    }
+
+    /// Builds both the x64 and arm64 macOS binaries, and combines them into a single universal binary. Returns the
+    /// path to the universal binary.
+    fn build_universal(&mut self, sea_blob: &Path, options: &BuildOptions) -> Result<PathBuf> {
+        let slices = [Arch::X64, Arch::Arm64]
+            .into_iter()
+            .map(|arch| self.prepare_target_binary(sea_blob, arch, options))
+            .collect::<Result<Vec<_>>>()?;
+
+        let spinner = self.tracker.spawn_spinner(LIPO_MSG, 0);
+
+        let universal_bin = self.working_dir.path().join("universal");
+
+        self.lipo(&slices, &universal_bin)
+            .classify(ErrorClass::Injection)?;
+
+        spinner.close();
+
+        Ok(universal_bin)
+    }
+
+    /// Copies, installs, and (if needed) bundles the project, then generates the SEA blob from it. Returns the
+    /// generated artifacts.
+    fn prepare_sea_blob(
+        &mut self,
+        project_dir: &Path,
+        options: &BuildOptions,
+        mut sea_config: SEAConfig,
+        package_config: &PackageConfig,
+    ) -> Result<BuildArtifacts> {
+        // Download the Node.js binaries in the background. The host binary is needed to install dependencies, but the
+        // target binaries aren't needed until the very end, so they keep downloading while the project is installed
+        // and bundled.
+        let host_node = self.fetch_node_binaries(options);
+
+        let spinner = self.tracker.spawn_spinner(COPY_PROJ_MSG, 0);
+
+        // Copy the project to the build directory
+        self.copy_and_prepare_project(project_dir, package_config, options)?;
+
+        spinner.close();
+
+        let spinner = self.tracker.spawn_spinner(HOST_NODE_MSG, 0);
+
+        let host_node = host_node.recv().map_err(|_| {
+            anyhow!("Error retrieving Node.js binaries: the download thread panicked!")
+        })??;
+
+        self.host_node = Some(host_node.clone());
+
+        self.check_pin(
+            &node_binary_name(&options.node_version, get_host_os(), get_host_arch()),
+            &host_node.node,
+            options.frozen,
+        )?;
+
+        spinner.close();
+
+        // Everything from installing the dependencies to generating the SEA blob is a step of preparing the app
+        let mut parent = self.tracker.spawn_spinner(PREPARE_APP_MSG, 0);
+
+        self.install_dependencies(
+            project_dir,
+            package_config,
+            &host_node,
+            options,
+            &mut parent,
+        )
+        .classify(ErrorClass::Bundling)?;
+
+        self.rebuild_native_dependencies(&host_node, options, &mut parent)
+            .classify(ErrorClass::Bundling)?;
+
+        if let Some(epoch) = options.source_date_epoch() {
+            self.prepare_reproducible_build(&sea_config, epoch)?;
+        }
+
+        let bundled = should_bundle(package_config, options);
+
+        if bundled {
+            let mut spinner = self.tracker.spawn_child(&mut parent, BUNDLE_PROJ_MSG);
+
+            let bundle = self.bundle_project(
+                project_dir,
+                package_config,
+                &mut sea_config,
+                options,
+                &mut spinner,
+            );
+
+            spinner.finish(bundle).classify(ErrorClass::Bundling)?;
+        }
+
+        let bundle = if bundled && self.bundler.is_none() {
+            Some(self.write_bundle_contents(project_dir, package_config, options)?)
+        } else {
+            None
+        };
+
+        let loader_shim = self.embed_native_addons(&mut sea_config, options)?;
+
+        let notices = if options.licenses || options.embed_licenses {
+            let spinner = self.tracker.spawn_child(&mut parent, LICENSES_MSG);
+            let notices = self.collect_third_party_notices(
+                project_dir,
+                package_config,
+                &mut sea_config,
+                bundled,
+                options,
+            )?;
+            spinner.close();
+
+            Some(notices)
+        } else {
+            None
+        };
+
+        // Both shims are prepended at once, with the licenses shim first, so `--licenses` is handled before anything
+        // else runs
+        let shims: Vec<String> = [
+            options
+                .embed_licenses
+                .then(|| licenses_shim(NOTICES_FILE_NAME)),
+            loader_shim,
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        if !shims.is_empty() {
+            self.prepend_to_entrypoint(&mut sea_config, &shims.join("\n"))?;
+        }
+
+        self.embed_metadata(&mut sea_config, package_config, options)?;
+
+        let spinner = self.tracker.spawn_child(&mut parent, GEN_SEA_BLOB_MSG);
+
+        // Generate the SEA blob
+        let sea_blob = spinner
+            .finish(self.gen_sea_blob(&host_node.node, sea_config, options))
+            .classify(ErrorClass::Injection)?;
+
+        parent.close();
+
+        Ok(BuildArtifacts {
+            sea_blob,
+            notices,
+            bundle,
+        })
+    }
+
+    /// Compiles the project into a binary for the target with another runtime (`bun build --compile`, or
+    /// `deno compile`), instead of injecting a SEA blob into Node.js. Returns the path to the binary.
+    fn compile_with_runtime(
+        &mut self,
+        project_dir: &Path,
+        sea_config: &SEAConfig,
+        package_config: &PackageConfig,
+        options: &BuildOptions,
+    ) -> Result<PathBuf> {
+        let runtime = options.runtime;
+        let (target_os, target_arch) = (options.target_os, options.target_arch);
+
+        let spinner = self.tracker.spawn_spinner(COPY_PROJ_MSG, 0);
+
+        self.copy_and_prepare_project(project_dir, package_config, options)?;
+
+        // The runtime installs its own dependencies, which Node.js builds in this directory can't reuse
+        self.installed_deps = None;
+
+        spinner.close();
+
+        let spinner = self
+            .tracker
+            .spawn_spinner(format!("{} {}", RUNTIME_BINARY_MSG, runtime), 0);
+
+        let runtime_bin = self.runtimes.get_binary(runtime)?;
+        let version = runtime
+            .version()
+            .expect("Node.js builds don't compile with another runtime!");
+
+        self.check_pin(
+            &runtime_binary_name(runtime, &version, get_host_os(), get_host_arch()),
+            &runtime_bin,
+            options.frozen,
+        )?;
+
+        spinner.close();
+
+        let project_dir = self.working_dir.path().join("project");
+        let entrypoint = package_config.main.as_ref().unwrap_or(&sea_config.main);
+
+        let spinner = self
+            .tracker
+            .spawn_spinner(format!("{} {}", INSTALL_DEPS_MSG, runtime), 0);
+
+        let install_output = Command::new(&runtime_bin)
+            .current_dir(&project_dir)
+            .arg("install")
+            .args(if options.frozen {
+                match runtime {
+                    Runtime::Bun => &["--frozen-lockfile"][..],
+                    _ => &["--frozen"][..],
+                }
+            } else {
+                &[]
+            })
+            .sanitized_env(&options.pass_env)
+            .logged_output()
+            .context(format!("Error running {} install", runtime.command()))
+            .classify(ErrorClass::Bundling)?;
+
+        if !install_output.status.success() {
+            return Err(anyhow!(
+                "Error running {} install:\n{}\n{}",
+                runtime.command(),
+                String::from_utf8_lossy(&install_output.stdout).trim(),
+                String::from_utf8_lossy(&install_output.stderr).trim()
+            ))
+            .classify(ErrorClass::Bundling);
+        }
+
+        spinner.close();
+
+        let spinner = self
+            .tracker
+            .spawn_spinner(format!("{} {}", RUNTIME_COMPILE_MSG, runtime), 0);
+
+        let output = self.working_dir.path().join(if target_os == Os::Windows {
+            "compiled.exe"
+        } else {
+            "compiled"
+        });
+
+        let mut command = Command::new(&runtime_bin);
+        command
+            .current_dir(&project_dir)
+            .sanitized_env(&options.pass_env);
+
+        match runtime {
+            Runtime::Bun => command
+                .args(["build", entrypoint, "--compile"])
+                .arg(format!(
+                    "--target={}",
+                    runtime.compile_target(target_os, target_arch)
+                ))
+                .arg("--outfile")
+                .arg(&output),
+            _ => command
+                .args(["compile", "--allow-all", "--target"])
+                .arg(runtime.compile_target(target_os, target_arch))
+                .arg("--output")
+                .arg(&output)
+                .arg(entrypoint),
+        };
+
+        let compile_output = command
+            .logged_output()
+            .context(format!("Error compiling project with {}", runtime))
+            .classify(ErrorClass::Bundling)?;
+
+        if !compile_output.status.success() {
+            return Err(anyhow!(
+                "Error compiling project with {}:\n{}\n{}",
+                runtime,
+                String::from_utf8_lossy(&compile_output.stdout).trim(),
+                String::from_utf8_lossy(&compile_output.stderr).trim()
+            ))
+            .classify(ErrorClass::Bundling);
+        }
+
+        spinner.close();
+
+        Ok(output)
+    }
+
+    /// Starts downloading the host and target Node.js binaries in the background, each in its own thread, so they're
+    /// in the cache by the time they're needed. Returns a channel that receives the host Node.js as soon as it's ready.
+    /// The target binaries are waited on when they're needed.
+    fn fetch_node_binaries(&mut self, options: &BuildOptions) -> mpsc::Receiver<Result<HostNode>> {
+        let version = options.node_version.clone();
+        let (host_os, host_arch) = (get_host_os(), get_host_arch());
+
+        let target_arches = if options.universal {
+            vec![Arch::X64, Arch::Arm64]
+        } else {
+            vec![options.target_arch]
+        };
+
+        // The manager is only locked to check the cache and to record each binary once it's downloaded
+        let fetcher = self.node_manager().fetcher();
+        let (host_node_tx, host_node_rx) = mpsc::channel();
+
+        let host_download = {
+            let (node_manager, fetcher, version) = (
+                Arc::clone(&self.node_manager),
+                fetcher.clone(),
+                version.clone(),
+            );
+
+            thread::spawn(move || {
+                let host_node =
+                    download_node(&node_manager, &fetcher, &version, host_os, host_arch).and_then(
+                        |()| {
+                            let mut node_manager =
+                                node_manager.lock().expect("Node.js manager lock poisoned!");
+
+                            Ok(node_manager.get_host_node(&version)?)
+                        },
+                    );
+
+                // The receiver only goes away if the build has already failed, so there's no one left to tell.
+                let _ = host_node_tx.send(host_node);
+
+                Ok(())
+            })
+        };
+
+        self.target_downloads = iter::once(host_download)
+            .chain(
+                target_arches
+                    .into_iter()
+                    // The host's binary is already being downloaded
+                    .filter(|&arch| (options.target_os, arch) != (host_os, host_arch))
+                    .map(|arch| {
+                        let (node_manager, fetcher, version, target_os) = (
+                            Arc::clone(&self.node_manager),
+                            fetcher.clone(),
+                            version.clone(),
+                            options.target_os,
+                        );
+
+                        // Only download the target binaries for now. They're unpacked when they're needed.
+                        thread::spawn(move || {
+                            download_node(&node_manager, &fetcher, &version, target_os, arch)
+                        })
+                    }),
+            )
+            .collect();
+
+        host_node_rx
+    }
+
+    /// Gets the host Node.js binary (and npm) for a version of Node.js, reusing the one from the last build if it
+    /// matches.
+    fn host_node(&mut self, version: &Version) -> Result<HostNode> {
+        match &self.host_node {
+            Some(host_node) if host_node.version == *version => Ok(host_node.clone()),
+
+            _ => {
+                let host_node = self.node_manager().get_host_node(version)?;
+                self.host_node = Some(host_node.clone());
+
+                Ok(host_node)
+            }
+        }
+    }
+
+    /// Checks a binary against the checksum pinned in the project's `jundler.lock` (if it has one), pinning it if it
+    /// isn't yet (unless `frozen`).
+    fn check_pin(&mut self, name: &str, binary: &Path, frozen: bool) -> Result<()> {
+        if let Some(lock) = &mut self.project_lock {
+            if lock.pin(name, binary, frozen)? {
+                lock.save()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Freezes (or unfreezes) the caches of Node.js, ESBuild, and the other runtimes, so nothing can be downloaded into
+    /// them.
+    fn set_frozen(&mut self, frozen: bool) {
+        self.node_manager().set_frozen(frozen);
+        self.esbuild.set_frozen(frozen);
+        self.runtimes.set_frozen(frozen);
+    }
+
+    /// Gets exclusive access to the Node.js manager.
+    fn node_manager(&self) -> MutexGuard<'_, NodeManager> {
+        self.node_manager
+            .lock()
+            .expect("Node.js manager lock poisoned!")
+    }
+
+    /// Sets every timestamp in the copied project to `epoch`, and warns about any `sea-config.json` options that
+    /// can't be reproduced.
+    fn prepare_reproducible_build(&mut self, sea_config: &SEAConfig, epoch: i64) -> Result<()> {
+        for option in reproducible::NON_REPRODUCIBLE_SEA_OPTIONS {
+            if sea_config.other.get(*option).is_some_and(|v| v == true) {
+                self.tracker.warn(format!(
+                    "Warning: `{}` is enabled in `sea-config.json`, so the build will not be reproducible.",
+                    option
+                ));
+            }
+        }
+
+        let project_dir = self.working_dir.path().join("project");
+
+        reproducible::normalize_mtimes(&project_dir, epoch).context(format!(
+            "Error normalizing timestamps in {}",
+            project_dir.display()
+        ))
+    }
+}
+
+/// What a single build of a project produced: every file it wrote, what it was built for, and how the build went.
+/// Both the summary at the end of a build and JSON reports are made from it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildOutput {
+    /// The path to the built binary.
+    pub app_path: PathBuf,
+
+    /// Every file the build wrote, including the binary.
+    pub files: Vec<OutputFile>,
+
+    /// The operating system that was built for.
+    pub target_os: Os,
+
+    /// The architecture that was built for. Universal macOS binaries are built for both architectures, though.
+    pub target_arch: Arch,
+
+    /// Whether a universal macOS binary was built.
+    pub universal: bool,
+
+    /// The runtime the binary was built with.
+    pub runtime: Runtime,
+
+    /// The version of Node.js that the application was bundled with, if it was built with Node.js.
+    pub node_version: Version,
+
+    /// Whether the SEA blob was reused from a previous build.
+    pub cached: bool,
+
+    /// Whether the project was bundled (with ESBuild or a custom bundler).
+    pub bundled: bool,
+
+    /// The version of ESBuild that bundled the project, if it did.
+    pub esbuild_version: Option<Version>,
+
+    /// What ESBuild put in the bundle, if it bundled the project.
+    pub bundle: Option<report::BundleContents>,
+
+    /// The tag of the container image that was built, if any.
+    pub image: Option<String>,
+
+    /// The oldest glibc the binary runs on, if it was built for Linux.
+    pub min_glibc: Option<glibc::GlibcVersion>,
+
+    /// What the size of the binary is made of, if it was built with Node.js.
+    pub size: Option<size::SizeBreakdown>,
+
+    /// Every step of the build that was completed, in order.
+    pub steps: Vec<Step>,
+
+    /// Every warning the build showed, in order.
+    pub warnings: Vec<String>,
+
+    /// How long the build took.
+    pub duration: Duration,
+}
+
+/// A file written by a build.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputFile {
+    /// The path to the file.
+    pub path: PathBuf,
+
+    /// The size of the file, in bytes.
+    pub size: u64,
+
+    /// The SHA256 digest of the file.
+    pub sha256: [u8; 32],
+}
+
+impl OutputFile {
+    /// Reads the size and checksum of the file at `path`.
+    pub fn read(path: PathBuf) -> Result<OutputFile> {
+        let size = fs::metadata(&path)
+            .context(format!("Error reading metadata of {}", path.display()))?
+            .len();
+
+        let sha256 = helpers::calculate_checksum(&path)
+            .context(format!("Error calculating checksum of {}", path.display()))?;
+
+        Ok(OutputFile { path, size, sha256 })
+    }
+}
+
+/// Options for a single build of a project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildOptions {
+    /// The version of Node.js to bundle the application with.
+    pub node_version: Version,
+
+    /// The operating system to build for.
+    pub target_os: Os,
+
+    /// The architecture to build for.
+    pub target_arch: Arch,
+
+    /// Whether to always bundle the project with ESBuild.
+    pub bundle: bool,
+
+    /// How to copy the project into the build directory.
+    pub copy_strategy: CopyStrategy,
+
+    /// Whether to rebuild everything, even if a cached build with the same inputs exists.
+    pub force: bool,
+
+    /// The directory to put the built binary in. Defaults to the project directory.
+    pub output_dir: Option<PathBuf>,
+
+    /// The package manager to install dependencies with. Detected from the project's lockfile if not set.
+    pub package_manager: Option<PackageManager>,
+
+    /// The runtime to build the binary with. Anything other than Node.js compiles the project with the runtime's own
+    /// compiler, instead of injecting a SEA blob into Node.js, and ignores `node_version`.
+    pub runtime: Runtime,
+
+    /// Whether to strip symbols from the target Node.js binary to reduce its size.
+    pub strip: bool,
+
+    /// Whether to build a universal macOS binary containing both x64 and arm64 slices. Ignores `target_arch`.
+    pub universal: bool,
+
+    /// Whether to make the build reproducible: dependencies are installed strictly from the lockfile, and every
+    /// timestamp is set to `SOURCE_DATE_EPOCH`.
+    pub reproducible: bool,
+
+    /// The format to write the checksums of the built binary in, if any.
+    pub checksums: Option<ChecksumFormat>,
+
+    /// Whether to write the licenses of every included dependency to a `THIRD_PARTY_NOTICES` file next to the binary.
+    pub licenses: bool,
+
+    /// Whether to embed the licenses of every included dependency in the binary, printed when it's run with
+    /// `--licenses`.
+    pub embed_licenses: bool,
+
+    /// The format to package the built binary into a release archive with, if any.
+    pub archive: Option<ArchiveFormat>,
+
+    /// The identity to codesign macOS binaries with, overriding the one in `package.json`. Binaries are ad-hoc signed
+    /// if there's neither.
+    pub codesign_identity: Option<String>,
+
+    /// Whether to notarize macOS binaries with Apple after signing them, so Gatekeeper lets them run anywhere.
+    pub notarize: bool,
+
+    /// Whether to sign the built binary. Windows binaries can only be signed on Windows, and macOS binaries on macOS
+    /// or with `rcodesign`.
+    pub sign: bool,
+
+    /// The PFX (or P12) file with the certificate to sign Windows binaries, or macOS binaries on other OSes, with.
+    /// Overrides the one in `package.json`.
+    pub sign_certificate: Option<PathBuf>,
+
+    /// How to sign Windows binaries, overriding the configuration in `package.json`.
+    pub windows_sign: WindowsSignOptions,
+
+    /// Whether to write the SLSA provenance of the outputs next to the binary, as `<binary>.intoto.json`.
+    pub provenance: bool,
+
+    /// The tool to write detached signatures of every output (the binary, checksums, and archive) with, if any.
+    pub signatures: Option<SignatureFormat>,
+
+    /// The GPG key ID, minisign secret key, or cosign key to write detached signatures with, overriding the one in
+    /// `package.json`.
+    pub signing_key: Option<String>,
+
+    /// Whether to write a Homebrew formula and Scoop manifest for the release archives in the output directory.
+    pub manifests: bool,
+
+    /// The URL that release archives are uploaded to, overriding the one in `package.json`.
+    pub release_url: Option<String>,
+
+    /// The formats to package the built Linux binary into, if any.
+    pub linux_packages: Vec<LinuxPackageFormat>,
+
+    /// Whether to build an MSI installer for the built Windows binary.
+    pub msi: bool,
+
+    /// The tag to build a container image of the built Linux binary as, if any.
+    pub docker_image: Option<String>,
+
+    /// The template to name the built binary with, overriding the one in `package.json`.
+    pub output_name: Option<String>,
+
+    /// Whether to build without changing the cache: nothing is downloaded, every binary has to match the checksums
+    /// pinned in the project's `jundler.lock`, and dependencies are installed strictly from the lockfile.
+    pub frozen: bool,
+
+    /// Variables from jundler's environment to pass through to the commands a build runs, on top of
+    /// [`environment::ALLOWED_VARS`]. Names ending in `*` pass every variable with that prefix.
+    pub pass_env: Vec<String>,
+
+    /// The `.npmrc` to install dependencies with instead of the user's own, i.e. one with the auth tokens for CI.
+    pub npmrc: Option<PathBuf>,
+
+    /// What to do if the version of Node.js has published vulnerabilities.
+    pub node_advisories: AdvisoryPolicy,
+
+    /// Whether to rebuild native dependencies without prebuilt addons for the target with `node-gyp`, when building
+    /// for another architecture.
+    pub rebuild_native: bool,
+
+    /// The prefix of the cross toolchain to rebuild native dependencies with, i.e. `aarch64-linux-gnu-`.
+    pub native_toolchain: Option<String>,
+
+    /// The arguments to run the built binary with as a smoke test, if it should be. Binaries for another architecture
+    /// are run under emulation.
+    pub test_exec: Option<Vec<String>>,
+
+    /// The maximum size of the built binary, in bytes, instead of the one in the project's configuration.
+    pub max_size: Option<u64>,
+
+    /// What to do if the built binary is bigger than its maximum size.
+    pub max_size_policy: size::MaxSizePolicy,
+}
+
+/// How to sign Windows binaries with `signtool`. Anything that isn't set is taken from `package.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WindowsSignOptions {
+    /// The SHA1 thumbprint of the certificate to sign with, from the certificate store.
+    pub thumbprint: Option<String>,
+
+    /// The URL of the RFC 3161 timestamp server to timestamp signatures with.
+    pub timestamp_url: Option<String>,
+
+    /// The description of the signed content.
+    pub description: Option<String>,
+}
+
+impl BuildOptions {
+    /// Options to build for this machine with `node_version`, signing the binary if it can be, and with everything else
+    /// (bundling, archives, checksums, and so on) off. Set the fields to change them.
+    pub fn new(node_version: Version) -> BuildOptions {
+        BuildOptions {
+            node_version,
+            target_os: get_host_os(),
+            target_arch: get_host_arch(),
+            bundle: false,
+            copy_strategy: CopyStrategy::default(),
+            force: false,
+            output_dir: None,
+            package_manager: None,
+            runtime: Runtime::Node,
+            strip: false,
+            universal: false,
+            reproducible: false,
+            checksums: None,
+            licenses: false,
+            embed_licenses: false,
+            archive: None,
+            codesign_identity: None,
+            notarize: false,
+            sign: true,
+            sign_certificate: None,
+            windows_sign: WindowsSignOptions::default(),
+            provenance: false,
+            signatures: None,
+            signing_key: None,
+            manifests: false,
+            release_url: None,
+            linux_packages: vec![],
+            msi: false,
+            docker_image: None,
+            output_name: None,
+            frozen: false,
+            pass_env: vec![],
+            npmrc: None,
+            node_advisories: AdvisoryPolicy::default(),
+            rebuild_native: false,
+            native_toolchain: None,
+            test_exec: None,
+            max_size: None,
+            max_size_policy: size::MaxSizePolicy::default(),
+        }
+    }
+
+    /// The timestamp to use for everything in the build, if it's reproducible.
+    pub fn source_date_epoch(&self) -> Option<i64> {
+        self.reproducible.then(|| {
+            reproducible::source_date_epoch().unwrap_or(reproducible::DEFAULT_SOURCE_DATE_EPOCH)
+        })
+    }
+
+    /// The name of the platform the binary is built for, i.e. `linux-x64`, or `darwin-universal`.
+    pub fn target_name(&self) -> String {
+        if self.universal {
+            format!("{}-universal", self.target_os)
+        } else {
+            format!("{}-{}", self.target_os, self.target_arch)
+        }
+    }
+
+    /// A string of every option that affects the generated SEA blob, for use in the build cache key. Options that
+    /// only affect *how* the build runs (like the copy strategy) are left out.
+    fn cache_key(&self) -> String {
+        format!(
+            "node={};os={};arch={};universal={};bundle={};package_manager={:?};reproducible={:?};licenses={};embed_licenses={}",
+            self.node_version,
+            self.target_os,
+            self.target_arch,
+            self.universal,
+            self.bundle,
+            self.package_manager,
+            self.source_date_epoch(),
+            self.licenses,
+            self.embed_licenses
+        )
+    }
+}
+
+/// Downloads the Node.js binary for a platform into the cache if it isn't there, without holding on to the manager while
+/// it downloads, so that other platforms can be downloaded at the same time.
+fn download_node(
+    node_manager: &Mutex<NodeManager>,
+    fetcher: &NodeFetcher,
+    version: &Version,
+    os: Os,
+    arch: Arch,
+) -> Result<()> {
+    let lock = || node_manager.lock().expect("Node.js manager lock poisoned!");
+
+    if lock().needs_download(version, os, arch) {
+        let fetched = fetcher.download(version, os, arch)?;
+
+        lock().record(fetched)?;
+    }
+
+    Ok(())
+}
+
+/// Checks that the options make sense for the target, and together with the project's configuration, before anything
+/// is built.
+fn check_options(
+    project_dir: &Path,
+    package_config: &PackageConfig,
+    options: &BuildOptions,
+) -> Result<()> {
+    let target_os = options.target_os;
+    let host_os = get_host_os();
+
+    if options.universal && target_os != Os::MacOS {
+        return Err(anyhow!("Universal binaries can only be built for macOS!"));
+    }
+
+    if options.runtime != Runtime::Node {
+        let runtime = options.runtime;
+
+        if options.universal || options.strip || options.reproducible {
+            return Err(anyhow!("Universal, stripped, and reproducible binaries can only be built with Node.js, not {}!", runtime));
+        }
+
+        if options.licenses || options.embed_licenses {
+            return Err(anyhow!("Licenses of dependencies can only be collected when building with Node.js, not {}!", runtime));
+        }
+
+        if !runtime.supports(target_os, options.target_arch) {
+            return Err(anyhow!(
+                "{} can't build binaries for {}-{}!",
+                runtime,
+                target_os,
+                options.target_arch
+            ));
+        }
+    }
+
+    if options.notarize && !options.sign {
+        return Err(anyhow!("Can't notarize a binary that isn't signed!"));
+    }
+
+    if options.notarize && target_os == Os::MacOS {
+        if host_os == Os::MacOS && codesign_identity(package_config, options).is_none() {
+            return Err(anyhow!("Notarizing needs a codesigning identity! Pass `--codesign-identity`, or set `jundler.macos.codesignIdentity` in `package.json`."));
+        }
+
+        if host_os != Os::MacOS && macos_certificate(project_dir, package_config, options).is_none()
+        {
+            return Err(anyhow!("Notarizing on other OSes needs a certificate! Pass `--sign-certificate`, or set `jundler.macos.certificate` in `package.json`."));
+        }
+    }
+
+    if options.manifests
+        && (options.archive.is_none() || release_url(package_config, options).is_none())
+    {
+        return Err(anyhow!("Package manifests need release archives (`--archive`), and the URL they're uploaded to! Pass `--release-url`, or set `jundler.release.url` in `package.json`."));
+    }
+
+    if options.msi && target_os != Os::Windows {
+        return Err(anyhow!("MSI installers can only be built for Windows!"));
+    }
+
+    if !options.linux_packages.is_empty() && target_os != Os::Linux {
+        return Err(anyhow!("Linux packages can only be built for Linux!"));
+    }
+
+    if options.docker_image.is_some() && target_os != Os::Linux {
+        return Err(anyhow!("Container images can only be built for Linux!"));
+    }
+
+    Ok(())
+}
+
+/// Gets the identity to codesign macOS binaries with, from the options or the project's configuration.
+fn codesign_identity<'a>(
+    package_config: &'a PackageConfig,
+    options: &'a BuildOptions,
+) -> Option<&'a str> {
+    options.codesign_identity.as_deref().or(package_config
+        .jundler
+        .macos
+        .codesign_identity
+        .as_deref())
+}
+
+/// Gets the certificate to sign macOS binaries with on other OSes, from the options or the project's configuration.
+fn macos_certificate(
+    project_dir: &Path,
+    package_config: &PackageConfig,
+    options: &BuildOptions,
+) -> Option<PathBuf> {
+    options.sign_certificate.clone().or_else(|| {
+        package_config
+            .jundler
+            .macos
+            .certificate
+            .as_ref()
+            .map(|certificate| project_dir.join(certificate))
+    })
+}
+
+/// Gets the URL release archives are uploaded to, from the options or the project's configuration.
+fn release_url<'a>(
+    package_config: &'a PackageConfig,
+    options: &'a BuildOptions,
+) -> Option<&'a str> {
+    options
+        .release_url
+        .as_deref()
+        .or(package_config.jundler.release.url.as_deref())
+}
+
+/// Gets the template to name the built binary with, from the options or the project's configuration.
+fn output_name_template<'a>(
+    package_config: &'a PackageConfig,
+    options: &'a BuildOptions,
+) -> &'a str {
+    options
+        .output_name
+        .as_deref()
+        .or(package_config.jundler.output_name.as_deref())
+        .unwrap_or(output_name::DEFAULT_TEMPLATE)
+}
+
+/// Excludes for the previously built binaries of a project (and their checksums and signatures). These are just dead weight in the
+/// build directory, and would invalidate the build cache every time.
+fn output_excludes(package_config: &PackageConfig, options: &BuildOptions) -> Vec<String> {
+    let mut excludes = vec![
+        format!("/{}", package_config.name),
+        format!("/{}.exe", package_config.name),
+        format!("/{}.sha256", package_config.name),
+        format!("/{}.exe.sha256", package_config.name),
+        format!("/{}", CHECKSUMS_FILE_NAME),
+        format!("/{}", NOTICES_FILE_NAME),
+        format!("/{}", LOCK_FILE_NAME),
+        format!("/{}-*.tar.gz", package_config.name),
+        format!("/{}-*.tar.gz.sha256", package_config.name),
+        format!("/{}-*.zip", package_config.name),
+        format!("/{}-*.zip.sha256", package_config.name),
+        format!("/{}*.sig", package_config.name),
+        format!("/{}*.minisig", package_config.name),
+        format!("/{}.sig", CHECKSUMS_FILE_NAME),
+        format!("/{}.minisig", CHECKSUMS_FILE_NAME),
+        format!("/{}.sig", NOTICES_FILE_NAME),
+        format!("/{}.minisig", NOTICES_FILE_NAME),
+        format!("/{}*.sigstore.json", package_config.name),
+        format!("/{}.sigstore.json", CHECKSUMS_FILE_NAME),
+        format!("/{}.sigstore.json", NOTICES_FILE_NAME),
+        format!("/{}", container::context_dir_name(&package_config.name)),
+        format!("/{}*.deb", package_config.name),
+        format!("/{}*.rpm", package_config.name),
+        format!("/{}*.AppImage", package_config.name),
+        format!("/{}*.msi", package_config.name),
+        format!("/{}.rb", package_config.name),
+        format!("/{}.json", package_config.name),
+        format!("/{}*.sha256", package_config.name),
+        format!(
+            "/{}*.{}",
+            package_config.name,
+            provenance::PROVENANCE_EXTENSION
+        ),
+    ];
+
+    // A cache in the project isn't part of it
+    if let Some(cache_dir) = &package_config.jundler.cache_dir {
+        excludes.push(format!(
+            "/{}",
+            cache_dir
+                .to_string_lossy()
+                .replace('\\', "/")
+                .trim_start_matches("./")
+                .trim_end_matches('/')
+        ));
+    }
+
+    let template = output_name_template(package_config, options);
+
+    if template != output_name::DEFAULT_TEMPLATE {
+        excludes.push(format!("/{}", output_name::glob(template, package_config)));
+    }
+
+    excludes
+}
+
+/// Writes the checksum of an extra output (like an archive or a package) if checksums are turned on, and adds any
+/// checksum file that isn't in `files` yet to it.
+fn write_extra_checksums(
+    path: &Path,
+    options: &BuildOptions,
+    files: &mut Vec<PathBuf>,
+) -> Result<()> {
+    if let Some(format) = options.checksums {
+        for checksums_path in checksums::write_checksums(&[path.to_path_buf()], format)? {
+            if !files.contains(&checksums_path) {
+                files.push(checksums_path);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that the built binary is for the target, going by its header, so a binary for the wrong platform is never
+/// shipped. Universal binaries have to have both macOS slices.
+fn check_output_platform(app_path: &Path, options: &BuildOptions) -> Result<()> {
+    let platform = binary_format::read_platform(app_path).context(format!(
+        "Error reading the header of the built binary at {}",
+        app_path.display()
+    ))?;
+
+    let arches = if options.universal {
+        vec![Arch::X64, Arch::Arm64]
+    } else {
+        vec![options.target_arch]
+    };
+
+    match platform {
+        Some(platform)
+            if arches
+                .iter()
+                .all(|&arch| platform.matches(options.target_os, arch)) =>
+        {
+            Ok(())
+        }
+
+        platform => Err(anyhow!(
+            "The built binary is for {}, not {}! The Node.js (or {}) binary it was built from may be for the wrong platform.",
+            platform.map_or_else(
+                || "an unrecognized format".to_string(),
+                |platform| platform.to_string()
+            ),
+            options.target_name(),
+            options.runtime
+        )),
+    }
+}
+
+/// Whether the project should be bundled: if the user wants to, or if the project is a module or TypeScript project.
+fn should_bundle(package_config: &PackageConfig, options: &BuildOptions) -> bool {
+    options.bundle
+        || package_config.project_type == ProjectType::Module
+        || package_config
+            .main
+            .as_ref()
+            .is_some_and(|m| m.ends_with(".mjs") || m.ends_with(".ts"))
+}
+
+/// Gets the `sea-config.json` and `package.json` configurations from the project directory. If the project has a
+/// `jundler.toml`, it replaces the `jundler` section of `package.json`.
+pub fn get_configs(project_dir: &Path) -> Result<(SEAConfig, PackageConfig)> {
+    let sea_config = serde_json::from_reader(
+        File::open(project_dir.join("sea-config.json"))
+            .context("Could not find or open the `sea-config.json` file!")?,
+    )
+    .context("Could not parse the `sea-config.json` file!")?;
+
+    let package_config = serde_json::from_reader(
+        File::open(project_dir.join("package.json"))
+            .context("Could not find or open the `sea-config.json` file!")?,
+    )
+    .context("Could not parse the `package.json` file!")?;
+
+    let mut package_config: PackageConfig = package_config;
+
+    if let Some(jundler_config) = JundlerConfig::from_project(project_dir)? {
+        package_config.jundler = jundler_config;
+    }
+
+    Ok((sea_config, package_config))
+}
@@ -0,0 +1,143 @@
+use super::output_name::command_name;
+use super::platforms::{Arch, Os};
+use crate::js_config::PackageConfig;
+use anyhow::{anyhow, Result};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+/// Gets the version of an MSI from a project version. MSI versions are `major.minor.build`, where the major and minor
+/// versions are at most 255 and the build at most 65535, so any prerelease or build metadata is dropped.
+pub fn msi_version(version: &str) -> Result<String> {
+    let release = version
+        .trim_start_matches('v')
+        .split(['-', '+'])
+        .next()
+        .unwrap_or_default();
+
+    let parts = release
+        .split('.')
+        .map(|part| part.parse::<u32>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| anyhow!("Invalid version {} for an MSI installer", version))?;
+
+    match parts.as_slice() {
+        [major, minor, build, ..] if *major <= 255 && *minor <= 255 && *build <= 65535 => {
+            Ok(format!("{}.{}.{}", major, minor, build))
+        }
+        _ => Err(anyhow!(
+            "Invalid version {} for an MSI installer! It has to be `major.minor.patch`, with the major and minor versions at most 255, and the patch at most 65535.",
+            version
+        )),
+    }
+}
+
+/// Derives a stable GUID from a name, so the same project always gets the same upgrade code.
+pub fn name_guid(name: &str) -> String {
+    let digest = Sha256::digest(format!("jundler-msi:{}", name));
+    let mut bytes = [0; 16];
+    bytes.copy_from_slice(&digest[..16]);
+
+    // Mark it as a name-based (version 5 style), RFC 4122 GUID
+    bytes[6] = (bytes[6] & 0x0f) | 0x50;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    let hex = hex::encode_upper(bytes);
+
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+/// Generates the WiX source of an installer that installs the executable at `exe_path` to Program Files, and adds it
+/// to the system `PATH` unless the project turns that off. Only uses what both WiX 3 and `wixl` understand.
+pub fn wxs(package_config: &PackageConfig, exe_path: &Path, arch: Arch) -> Result<String> {
+    let config = &package_config.jundler.msi;
+
+    let version = msi_version(package_config.version.as_deref().ok_or_else(|| {
+        anyhow!("MSI installers need a version! Set `version` in `package.json`.")
+    })?)?;
+
+    let product_name = config
+        .product_name
+        .as_deref()
+        .unwrap_or(&package_config.name);
+
+    let manufacturer = config
+        .manufacturer
+        .clone()
+        .or_else(|| {
+            package_config
+                .author
+                .as_ref()
+                .map(|author| author.name().to_string())
+        })
+        .unwrap_or_else(|| product_name.to_string());
+
+    let upgrade_code = config
+        .upgrade_code
+        .clone()
+        .unwrap_or_else(|| name_guid(&package_config.name));
+
+    let description = package_config
+        .description
+        .as_deref()
+        .unwrap_or(product_name);
+
+    let exe_name = command_name(package_config, Os::Windows);
+
+    let (platform, program_files, win64) = match arch {
+        Arch::X64 => ("x64", "ProgramFiles64Folder", "yes"),
+        Arch::X86 => ("x86", "ProgramFilesFolder", "no"),
+        Arch::Arm64 => ("arm64", "ProgramFiles64Folder", "yes"),
+    };
+
+    let path = if config.add_to_path.unwrap_or(true) {
+        "\n            <Environment Id=\"PathEntry\" Name=\"PATH\" Value=\"[INSTALLDIR]\" Permanent=\"no\" Part=\"last\" Action=\"set\" System=\"yes\" />"
+    } else {
+        ""
+    };
+
+    Ok(format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<Wix xmlns="http://schemas.microsoft.com/wix/2006/wi">
+  <Product Id="*" Name="{product_name}" Language="1033" Version="{version}" Manufacturer="{manufacturer}" UpgradeCode="{upgrade_code}">
+    <Package InstallerVersion="500" Compressed="yes" InstallScope="perMachine" Platform="{platform}" Description="{description}" />
+    <MajorUpgrade DowngradeErrorMessage="A newer version of {product_name} is already installed." />
+    <Media Id="1" Cabinet="app.cab" EmbedCab="yes" />
+    <Directory Id="TARGETDIR" Name="SourceDir">
+      <Directory Id="{program_files}">
+        <Directory Id="INSTALLDIR" Name="{product_name}">
+          <Component Id="MainExecutable" Guid="{component_guid}" Win64="{win64}">
+            <File Id="MainExecutableFile" Name="{exe_name}" Source="{source}" KeyPath="yes" />{path}
+          </Component>
+        </Directory>
+      </Directory>
+    </Directory>
+    <Feature Id="Main" Level="1">
+      <ComponentRef Id="MainExecutable" />
+    </Feature>
+  </Product>
+</Wix>
+"#,
+        product_name = escape(product_name),
+        manufacturer = escape(&manufacturer),
+        upgrade_code = escape(&upgrade_code),
+        description = escape(description),
+        component_guid = name_guid(&format!("{}:{}", package_config.name, exe_name)),
+        exe_name = escape(&exe_name),
+        source = escape(&exe_path.display().to_string()),
+    ))
+}
+
+/// Escapes text for an XML attribute.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -0,0 +1,201 @@
+use super::platforms::{Arch, Os};
+use anyhow::{Context, Result};
+use ignore::WalkBuilder;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The loader shim that is prepended to the application's entrypoint when it uses native addons.
+const NATIVE_LOADER_SHIM: &str = include_str!("native_loader.js");
+
+/// The placeholder in the loader shim that is replaced with the list of embedded addons.
+const NATIVE_ADDONS_PLACEHOLDER: &str = "__JUNDLER_NATIVE_ADDONS__";
+
+/// Finds every native addon (`.node` file) in the project, returning their paths relative to the project directory.
+/// Addons that are prebuilt for a different platform than the target (in `prebuilds/{platform}-{arch}` directories, or
+/// in packages whose `package.json` is for other platforms) are skipped.
+pub fn find_native_addons(
+    project_dir: &Path,
+    target_os: Os,
+    target_arch: Arch,
+) -> Result<Vec<PathBuf>> {
+    let target_prebuild = format!("{}-{}", node_platform(target_os), node_arch(target_arch));
+
+    let mut addons = vec![];
+
+    for entry in WalkBuilder::new(project_dir)
+        .standard_filters(false)
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .build()
+    {
+        let entry = entry.context("Error searching the project for native addons")?;
+        let path = entry.path();
+
+        if !entry.file_type().is_some_and(|t| t.is_file())
+            || path.extension().is_none_or(|ext| ext != "node")
+        {
+            continue;
+        }
+
+        let relative_path = path
+            .strip_prefix(project_dir)
+            .expect("Walked paths should always be inside the project directory!");
+
+        // Check if this is a prebuild for another platform, i.e. `prebuilds/darwin-arm64/addon.node`.
+        let is_other_prebuild = relative_path
+            .components()
+            .collect::<Vec<_>>()
+            .windows(2)
+            .any(|window| {
+                window[0].as_os_str() == "prebuilds"
+                    && !window[1]
+                        .as_os_str()
+                        .to_string_lossy()
+                        .starts_with(&target_prebuild)
+            });
+
+        // `node-gyp` links addons in `obj.target` before copying them next to it, where they're loaded from
+        let is_intermediate = relative_path
+            .components()
+            .any(|component| component.as_os_str() == "obj.target");
+
+        if !is_other_prebuild
+            && !is_intermediate
+            && supports_target(project_dir, relative_path, target_os, target_arch)
+        {
+            addons.push(relative_path.to_path_buf());
+        }
+    }
+
+    Ok(addons)
+}
+
+/// Finds the dependencies of the project that build their native addons with `node-gyp` (those with a `binding.gyp`),
+/// but don't ship a prebuilt addon for the target in `prebuilds/{platform}-{arch}`. Their addons were built for the host
+/// when they were installed, if at all, so they have to be rebuilt for the target. Returns the paths to the packages,
+/// relative to the project directory.
+pub fn packages_to_rebuild(
+    project_dir: &Path,
+    target_os: Os,
+    target_arch: Arch,
+) -> Result<Vec<PathBuf>> {
+    let target_prebuild = format!("{}-{}", node_platform(target_os), node_arch(target_arch));
+    let node_modules = project_dir.join("node_modules");
+
+    if !node_modules.is_dir() {
+        return Ok(vec![]);
+    }
+
+    let mut packages = vec![];
+
+    for entry in WalkBuilder::new(&node_modules)
+        .standard_filters(false)
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .build()
+    {
+        let entry = entry.context("Error searching the dependencies for native addons")?;
+        let path = entry.path();
+
+        if path.file_name().is_none_or(|name| name != "binding.gyp") {
+            continue;
+        }
+
+        let package_dir = path
+            .parent()
+            .expect("Walked files should always be in a directory!");
+
+        // Only the `binding.gyp` at the root of a package builds its addon
+        if !package_dir.join("package.json").is_file()
+            || package_dir
+                .join("prebuilds")
+                .join(&target_prebuild)
+                .is_dir()
+        {
+            continue;
+        }
+
+        packages.push(
+            package_dir
+                .strip_prefix(project_dir)
+                .expect("Walked paths should always be inside the project directory!")
+                .to_path_buf(),
+        );
+    }
+
+    Ok(packages)
+}
+
+/// Gets the SEA asset key for a native addon, which is its relative path with forward slashes.
+pub fn asset_key(addon: &Path) -> String {
+    addon
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Gets the native addon loader shim for the given asset keys. This should be prepended to the application's
+/// entrypoint.
+pub fn loader_shim(asset_keys: &[String]) -> String {
+    NATIVE_LOADER_SHIM.replace(
+        NATIVE_ADDONS_PLACEHOLDER,
+        &serde_json::to_string(asset_keys).expect("A list of strings should always serialize!"),
+    )
+}
+
+/// The name Node.js uses for an OS in `process.platform`, which is what prebuild directories are named after, and what
+/// `prebuild-install` and the `os` field of `package.json` expect.
+pub fn node_platform(os: Os) -> &'static str {
+    match os {
+        Os::MacOS => "darwin",
+        Os::Linux => "linux",
+        Os::Windows => "win32",
+    }
+}
+
+/// The name Node.js uses for an architecture in `process.arch`, which is what prebuild directories are named after, and
+/// what `prebuild-install` and the `cpu` field of `package.json` expect.
+pub fn node_arch(arch: Arch) -> &'static str {
+    match arch {
+        Arch::X64 => "x64",
+        Arch::X86 => "ia32",
+        Arch::Arm64 => "arm64",
+    }
+}
+
+/// Whether the package an addon is in can run on the target, going by the `os` and `cpu` fields of its `package.json`.
+/// napi-rs and friends publish an addon for each platform as its own package, i.e. `@swc/core-linux-x64-gnu`, which
+/// only the matching platform should embed. Addons outside of such packages always can.
+fn supports_target(project_dir: &Path, addon: &Path, target_os: Os, target_arch: Arch) -> bool {
+    let Some(manifest) = addon
+        .ancestors()
+        .skip(1)
+        .map(|dir| project_dir.join(dir).join("package.json"))
+        .find(|manifest| manifest.is_file())
+    else {
+        return true;
+    };
+
+    let Some(package) = fs::read_to_string(&manifest)
+        .ok()
+        .and_then(|manifest| serde_json::from_str::<Value>(&manifest).ok())
+    else {
+        return true;
+    };
+
+    // Entries are either allowed values, or disallowed ones starting with `!`
+    let allows = |field: &str, value: &str| {
+        let Some(entries) = package[field].as_array() else {
+            return true;
+        };
+        let entries: Vec<&str> = entries.iter().filter_map(Value::as_str).collect();
+
+        if entries.contains(&format!("!{}", value).as_str()) {
+            return false;
+        }
+
+        entries.iter().all(|entry| entry.starts_with('!')) || entries.contains(&value)
+    };
+
+    allows("os", node_platform(target_os)) && allows("cpu", node_arch(target_arch))
+}
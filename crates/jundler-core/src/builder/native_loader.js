@@ -0,0 +1,56 @@
+// Injected by jundler: loads native addons (`.node` files) that are embedded as SEA assets. Node.js can only load native
+// addons from disk, so they are extracted to a temporary directory the first time they are required.
+require = ((seaRequire) => {
+    const sea = seaRequire("node:sea");
+
+    if (!sea.isSea()) {
+        return seaRequire;
+    }
+
+    const fs = seaRequire("node:fs");
+    const os = seaRequire("node:os");
+    const path = seaRequire("node:path");
+
+    // The asset keys of every embedded native addon. Replaced by jundler at build time.
+    const addons = __JUNDLER_NATIVE_ADDONS__;
+    const loaded = new Map();
+    let extractDir;
+
+    // Finds the embedded addon best matching a required path. The original directory layout is lost when bundling, so
+    // match on the longest common suffix, falling back to just the file name.
+    const findAddon = (id) => {
+        const parts = id.replace(/\\/g, "/").split("/").filter((p) => p && p !== "." && p !== "..");
+        const tail = parts.join("/");
+
+        return (
+            addons.find((key) => key === tail || key.endsWith("/" + tail)) ||
+            addons.find((key) => key.endsWith("/" + parts[parts.length - 1]))
+        );
+    };
+
+    return function require(id) {
+        if (typeof id !== "string" || !id.endsWith(".node")) {
+            return seaRequire(id);
+        }
+
+        const key = findAddon(id);
+
+        if (key === undefined) {
+            throw new Error(`Cannot find native addon '${id}' in the executable`);
+        }
+
+        if (!loaded.has(key)) {
+            extractDir ??= fs.mkdtempSync(path.join(os.tmpdir(), "jundler-native-"));
+
+            const extracted = path.join(extractDir, key);
+            fs.mkdirSync(path.dirname(extracted), { recursive: true });
+            fs.writeFileSync(extracted, Buffer.from(sea.getAsset(key)));
+
+            const module = { exports: {} };
+            process.dlopen(module, extracted);
+            loaded.set(key, module.exports);
+        }
+
+        return loaded.get(key);
+    };
+})(require);
@@ -0,0 +1,142 @@
+//! Rebuilds native dependencies that don't publish prebuilt addons for the target, when building for another
+//! architecture. They're compiled with the `node-gyp` that came with npm, against the headers of the Node.js the build
+//! uses, with a cross toolchain the user provides. The rebuilt addons are where `node-gyp` always puts them, in the
+//! package's `build/Release`, so they're embedded like any other addon (see `embed_native_addons`).
+
+use super::environment::SanitizedEnv;
+use super::helpers::{is_available, LoggedOutput};
+use super::native::{node_arch, packages_to_rebuild};
+use super::node_manager::HostNode;
+use super::platforms::{get_host_arch, get_host_os, Os};
+use super::{BuildOptions, Builder};
+use crate::ui::messages::REBUILD_NATIVE_MSG;
+use crate::ui::Spinner;
+use anyhow::{anyhow, Context, Result};
+use log::debug;
+
+/// The tools of a cross toolchain, as the variables `node-gyp` reads them from and the name of each tool after the
+/// toolchain's prefix, i.e. `aarch64-linux-gnu-gcc`.
+const TOOLCHAIN_TOOLS: &[(&str, &str)] = &[
+    ("CC", "gcc"),
+    ("CXX", "g++"),
+    ("LINK", "g++"),
+    ("AR", "ar"),
+    ("RANLIB", "ranlib"),
+];
+
+impl Builder {
+    /// Rebuilds the native dependencies of the copied project that have no prebuilt addon for the target, if the
+    /// build asked for it and is for another platform than the host.
+    pub(super) fn rebuild_native_dependencies(
+        &mut self,
+        host_node: &HostNode,
+        options: &BuildOptions,
+        parent: &mut Spinner,
+    ) -> Result<()> {
+        let (target_os, target_arch) = (options.target_os, options.target_arch);
+
+        if !options.rebuild_native || (target_os, target_arch) == (get_host_os(), get_host_arch()) {
+            return Ok(());
+        }
+
+        let project_dir = self.working_dir.path().join("project");
+        let packages = packages_to_rebuild(&project_dir, target_os, target_arch)?;
+
+        if packages.is_empty() {
+            return Ok(());
+        }
+
+        // `node-gyp` only cross-compiles between architectures: it needs the target's SDK for other OSes, and on
+        // Windows, an import library that Node.js only publishes for its own builds
+        if target_os != get_host_os() || target_os == Os::Windows {
+            return Err(anyhow!(
+                "Can't rebuild {} for {}: `--rebuild-native` only cross-compiles native addons for another architecture of Linux or macOS, on a host with the same OS. Use dependencies with prebuilt addons for {}, or build on {}.",
+                packages
+                    .iter()
+                    .map(|package| package.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                options.target_name(),
+                options.target_name(),
+                target_os
+            ));
+        }
+
+        let toolchain = match &options.native_toolchain {
+            Some(prefix) => {
+                let compiler = format!("{}gcc", prefix);
+
+                if !is_available(&compiler) {
+                    return Err(anyhow!(
+                        "Could not find `{}`, the compiler of the cross toolchain `{}`! Install it, or check that it's on the `PATH`.",
+                        compiler,
+                        prefix
+                    ));
+                }
+
+                TOOLCHAIN_TOOLS
+                    .iter()
+                    .map(|(var, tool)| (*var, format!("{}{}", prefix, tool)))
+                    .collect()
+            }
+
+            None => vec![],
+        };
+
+        let spinner = self.tracker.spawn_child(parent, REBUILD_NATIVE_MSG);
+
+        let nodedir = self.node_manager().get_headers(&options.node_version)?;
+
+        // `node-gyp` comes with npm, which is kept next to the host Node.js
+        let node_gyp = host_node
+            .npm_cli
+            .parent()
+            .and_then(|bin| bin.parent())
+            .map(|npm| {
+                npm.join("node_modules")
+                    .join("node-gyp")
+                    .join("bin")
+                    .join("node-gyp.js")
+            })
+            .filter(|node_gyp| node_gyp.is_file())
+            .ok_or_else(|| anyhow!("Could not find the node-gyp that comes with npm!"))?;
+
+        for package in packages {
+            debug!(
+                "Rebuilding {} for {}",
+                package.display(),
+                options.target_name()
+            );
+
+            let output = host_node
+                .command(&host_node.node)
+                .arg(&node_gyp)
+                .arg("rebuild")
+                .arg(format!("--nodedir={}", nodedir.display()))
+                .arg(format!("--arch={}", node_arch(target_arch)))
+                .envs(
+                    toolchain
+                        .iter()
+                        .map(|(var, tool): &(&str, String)| (var, tool)),
+                )
+                .sanitized_env(&options.pass_env)
+                .current_dir(project_dir.join(&package))
+                .logged_output()
+                .context(format!("Error running node-gyp for {}", package.display()))?;
+
+            if !output.status.success() {
+                return Err(anyhow!(
+                    "Error rebuilding {} for {} with node-gyp:\n{}\n{}",
+                    package.display(),
+                    options.target_name(),
+                    String::from_utf8_lossy(&output.stdout),
+                    String::from_utf8_lossy(&output.stderr)
+                ));
+            }
+        }
+
+        spinner.close();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,196 @@
+use super::{lock::Checksum, Arch, Os};
+use crate::builder::downloader::DownloadError;
+use crate::builder::errors::{Diagnose, Diagnostic};
+use semver::Version;
+use std::io;
+use std::path::PathBuf;
+
+/// Any errors that can occur when interacting with the NodeManager
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// An error occurred while parsing the checksum file
+    #[error("An error occurred while parsing the checksum file!")]
+    UnparseableChecksumFile,
+
+    /// A line of the checksum file isn't a checksum and a file name
+    #[error("Line {line} of the checksum file isn't a checksum and a file name: {contents}")]
+    MalformedChecksumLine {
+        /// The number of the line, counting from 1
+        line: usize,
+
+        /// What's on the line
+        contents: String,
+    },
+
+    /// An error occurred while parsing the index of Node.js releases
+    #[error("An error occurred while parsing the Node.js release index!")]
+    UnparseableReleaseIndex,
+
+    /// An error occurred while parsing the database of vulnerabilities in Node.js
+    #[error("An error occurred while parsing the Node.js vulnerability database!")]
+    UnparseableVulnerabilityIndex,
+
+    /// An IO error occurred
+    #[error("An IO error occurred on while {action} {path}: {err}")]
+    Io {
+        /// The source of the error
+        #[source]
+        err: io::Error,
+
+        /// The path that caused the error
+        path: PathBuf,
+
+        /// The action that caused the error. Should be insertable into a string of "...while {action} {path}:"
+        action: String,
+    },
+
+    /// An error serializing/deserializing the lockfile occured
+    #[error("An error occurred while serializing/deserializing the lockfile: {0}")]
+    LockfileSerialization(#[from] bincode::Error),
+
+    /// An error occured while trying to download a file
+    #[error("An error occurred while trying to download a file from {url}: {err}")]
+    Download {
+        /// The source of the error
+        #[source]
+        err: DownloadError,
+
+        /// The URL that caused the error
+        url: String,
+    },
+
+    /// Something would have to change in the cache, but it's frozen
+    #[error("Can't {0} while the cache is frozen! Run `jundler fetch` first.")]
+    Frozen(String),
+
+    /// There is no node binary found for the requested version, arch, and os
+    #[error("No node binary found for version Node.js v{version} {arch} {os}")]
+    NodeBinaryDNE {
+        /// The version of Node.js
+        version: Version,
+
+        /// The target operating system
+        os: Os,
+
+        /// The target architecture
+        arch: Arch,
+    },
+
+    /// There are no headers published for the requested version
+    #[error("No headers found for Node.js v{0}")]
+    NodeHeadersDNE(Version),
+
+    /// A Node.js binary's header says it's for another platform than it's supposed to be
+    #[error("The Node.js binary at {path} is for {actual}, not {expected}!")]
+    WrongPlatform {
+        /// The path to the binary
+        path: PathBuf,
+
+        /// The platform it's supposed to be for
+        expected: String,
+
+        /// The platform its header says it's for
+        actual: String,
+    },
+
+    /// There was a mismatch between the expected checksum and the actual checksum
+    #[error(
+        "Checksum mismatch for file {path}! Expected: {}, Actual: {}",
+        hex::encode(expected),
+        hex::encode(actual)
+    )]
+    ChecksumMismatch {
+        /// The path to the file
+        path: PathBuf,
+
+        /// The expected checksum
+        expected: Checksum,
+
+        /// The actual checksum
+        actual: Checksum,
+    },
+}
+
+impl Diagnose for Error {
+    fn diagnostic(&self) -> Diagnostic {
+        match self {
+            Error::UnparseableChecksumFile | Error::MalformedChecksumLine { .. } => Diagnostic::new(
+                "node::unparseable_checksums",
+                [
+                    "The checksums from nodejs.org couldn't be read. If you download through a proxy or a shared cache, it may have changed them or cut them short.",
+                ],
+            )
+            .with_faq("can-i-change-where-jundler-keeps-its-cache"),
+
+            Error::UnparseableReleaseIndex => Diagnostic::new(
+                "node::unparseable_release_index",
+                ["Pass the exact version of Node.js to build with, i.e. `--node-version 22.3.0`, which doesn't need the release index."],
+            ),
+
+            Error::UnparseableVulnerabilityIndex => Diagnostic::new(
+                "node::unparseable_vulnerability_index",
+                ["The Node.js security working group may have changed the format of its database. Check for a newer version of jundler."],
+            ),
+
+            Error::Io { .. } => Diagnostic::new(
+                "node::io",
+                ["Check that the cache directory can be written to, or choose another one with `--cache-dir`."],
+            )
+            .with_faq("can-i-change-where-jundler-keeps-its-cache"),
+
+            Error::LockfileSerialization(_) => Diagnostic::new(
+                "node::corrupt_lockfile",
+                ["The cache's list of Node.js binaries is corrupt, or from an incompatible version of jundler. Run `jundler clean node` to start over."],
+            ),
+
+            Error::Download { .. } => Diagnostic::new(
+                "node::download",
+                [
+                    "Check your internet connection, and your proxy settings (`HTTPS_PROXY`) if you're behind one.",
+                    "To download Node.js once for a whole team or CI fleet, build with `--shared-cache`.",
+                ],
+            )
+            .with_faq("can-i-change-where-jundler-keeps-its-cache"),
+
+            Error::Frozen(_) => Diagnostic::new(
+                "node::frozen",
+                ["Run `jundler fetch` to download and pin everything the build needs, then build with `--frozen` again."],
+            )
+            .with_faq("can-i-make-sure-builds-never-download-anything"),
+
+            Error::NodeBinaryDNE { version, os, arch } => Diagnostic::new(
+                "node::no_binary",
+                [format!(
+                    "Node.js v{} may not be published for {}-{}. Check https://nodejs.org/dist/v{}/ for the platforms it is.",
+                    version, os, arch, version
+                )],
+            ),
+
+            Error::NodeHeadersDNE(version) => Diagnostic::new(
+                "node::no_headers",
+                [format!(
+                    "Node.js v{} may not have published headers for building native addons. Check https://nodejs.org/dist/v{}/ for them.",
+                    version, version
+                )],
+            ),
+
+            Error::WrongPlatform { .. } => Diagnostic::new(
+                "node::wrong_platform",
+                [
+                    "If you download through a mirror or a shared cache, check that it serves the right archive for each platform.",
+                    "Run `jundler cache verify` to find any corrupt Node.js binaries in the cache, and `jundler clean node` to remove them.",
+                ],
+            )
+            .with_faq("can-i-change-where-jundler-keeps-its-cache"),
+
+            Error::ChecksumMismatch { .. } => Diagnostic::new(
+                "node::checksum_mismatch",
+                [
+                    "Run `jundler cache verify` to find any corrupt Node.js binaries in the cache, and `jundler clean node` to remove them.",
+                    "If every download of Node.js is rejected, a proxy or shared cache may be changing them on the way.",
+                ],
+            )
+            .with_faq("can-i-change-where-jundler-keeps-its-cache"),
+        }
+    }
+}
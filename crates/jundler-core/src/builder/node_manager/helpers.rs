@@ -0,0 +1,390 @@
+use super::lock::{Checksum, NodeExecutableMeta};
+use super::sumfile_parser::{self, NodeArchiveFormat, NodeFile, SumfileEntry};
+use super::Error;
+use crate::builder::binary_format::read_platform;
+use crate::builder::downloader::Downloader;
+pub use crate::builder::platforms::{Arch, Os};
+use crate::ui::{NoopReporter, Reporter};
+use flate2::read::GzDecoder;
+use log::debug;
+use semver::Version;
+use std::{
+    fs::{self, File},
+    io,
+    path::Path,
+};
+use std::{
+    io::{Read, Write},
+    path::PathBuf,
+};
+use tar::Archive;
+use zstd::Encoder;
+
+/// Rearchive *just* the binary and copy the node binary into the cache directory. Returns the path to the copied binary.
+pub fn repack_node_binary(
+    node_executable_path: &Path,
+    version: &Version,
+    os: Os,
+    arch: Arch,
+    cache_dir: &Path,
+) -> Result<PathBuf, Error> {
+    let archive_path = cache_dir.join(format!("node-v{}-{}-{}.zst", version, os, arch));
+
+    let archive = File::create(&archive_path).map_err(|err| Error::Io {
+        err,
+        path: archive_path.clone(),
+        action: "creating archive file at".to_string(),
+    })?;
+
+    let mut node_executable = File::open(node_executable_path).map_err(|err| Error::Io {
+        err,
+        path: node_executable_path.to_path_buf(),
+        action: "opening node executable file at".to_string(),
+    })?;
+
+    let mut zstd_encoder = Encoder::new(archive, 0).map_err(|err| Error::Io {
+        err,
+        path: archive_path.clone(),
+        action: "creating zstd encoder for archive file at".to_string(),
+    })?;
+
+    // Encode!
+    let mut buf: Vec<u8> = vec![];
+
+    node_executable
+        .read_to_end(&mut buf)
+        .map_err(|err| Error::Io {
+            err,
+            path: node_executable_path.to_path_buf(),
+            action: "reading from node executable file at".to_string(),
+        })?;
+
+    zstd_encoder.write_all(&buf).map_err(|err| Error::Io {
+        err,
+        path: archive_path.clone(),
+        action: "writing to archive file at".to_string(),
+    })?;
+
+    zstd_encoder.finish().map_err(|err| Error::Io {
+        err,
+        path: archive_path.clone(),
+        action: "finishing zstd encoder for archive file at".to_string(),
+    })?;
+
+    Ok(archive_path)
+}
+
+/// Extract the Node.js archive, and returns the path to the extracted binary. `extract_dir` is the directory where the archive will
+/// be extracted to.
+pub fn unpack_downloaded_node_archive(
+    extract_dir: &Path,
+    archive_path: &Path,
+    version: &Version,
+    os: Os,
+    arch: Arch,
+) -> Result<PathBuf, Error> {
+    // Extract the archive to `{build-dir}/node-v{version}-{os}-{arch}`
+    let bin_path = match os {
+        Os::MacOS | Os::Linux => {
+            // Extract the tarball
+            let tar_gz = File::open(archive_path).map_err(|err| Error::Io {
+                err,
+                path: archive_path.to_path_buf(),
+                action: "opening node archive file at".to_string(),
+            })?;
+
+            let tar = GzDecoder::new(tar_gz);
+
+            let mut archive = Archive::new(tar);
+
+            archive
+                .unpack(extract_dir)
+                .map_err(|err: std::io::Error| Error::Io {
+                    err,
+                    path: archive_path.into(),
+                    action: "extracting node archive file from".to_string(),
+                })?;
+
+            extract_dir
+                .join(format!("node-v{}-{}-{}", version, os, arch))
+                .join("bin")
+                .join("node")
+        }
+
+        Os::Windows => {
+            // Extract the zip file
+            let file = File::open(archive_path).map_err(|err| Error::Io {
+                err,
+                path: archive_path.to_path_buf(),
+                action: "opening node archive file at".to_string(),
+            })?;
+
+            let mut archive = zip::ZipArchive::new(file).map_err(|err| Error::Io {
+                err: err.into(),
+                path: archive_path.into(),
+                action: "extracting node archive file from".to_string(),
+            })?;
+
+            archive.extract(extract_dir).map_err(|err| Error::Io {
+                err: err.into(),
+                path: archive_path.into(),
+                action: "extracting node archive file from".to_string(),
+            })?;
+
+            extract_dir
+                .join(format!("node-v{}-{}-{}", version, os, arch))
+                .join("node.exe")
+        }
+    };
+
+    // A mirror (or a cache in between) could have served the archive of another platform
+    check_platform(&bin_path, os, arch)?;
+
+    Ok(bin_path)
+}
+
+/// Checks that the Node.js binary at `path` is for `os` and `arch`, going by its header.
+pub fn check_platform(path: &Path, os: Os, arch: Arch) -> Result<(), Error> {
+    let platform = read_platform(path).map_err(|err| Error::Io {
+        err,
+        path: path.to_path_buf(),
+        action: "reading the header of node binary at".to_string(),
+    })?;
+
+    match platform {
+        Some(platform) if platform.matches(os, arch) => Ok(()),
+        platform => Err(Error::WrongPlatform {
+            path: path.to_path_buf(),
+            expected: format!("{}-{}", os, arch),
+            actual: platform.map_or_else(
+                || "an unrecognized format".to_string(),
+                |platform| platform.to_string(),
+            ),
+        }),
+    }
+}
+
+/// Copy the npm that comes with every Node.js release out of the extracted Node.js archive and into the cache
+/// directory. npm is plain JavaScript, so the copy from any platform's archive works on the host. Returns the path to
+/// the copied npm directory.
+pub fn save_npm(
+    extract_dir: &Path,
+    version: &Version,
+    os: Os,
+    arch: Arch,
+    cache_dir: &Path,
+) -> Result<PathBuf, Error> {
+    save_bundled_package(extract_dir, version, os, arch, cache_dir, "npm")
+}
+
+/// Copy the Corepack that comes with Node.js releases from 16.9 to 24 out of the extracted Node.js archive and into the
+/// cache directory, like `save_npm` does with npm. Returns the path to the copied Corepack directory, or `None` if the
+/// release doesn't have it.
+pub fn save_corepack(
+    extract_dir: &Path,
+    version: &Version,
+    os: Os,
+    arch: Arch,
+    cache_dir: &Path,
+) -> Result<Option<PathBuf>, Error> {
+    if !bundled_package_dir(extract_dir, version, os, arch, "corepack").exists() {
+        return Ok(None);
+    }
+
+    save_bundled_package(extract_dir, version, os, arch, cache_dir, "corepack").map(Some)
+}
+
+/// Where a package that comes with Node.js, like npm, is in the extracted Node.js archive.
+fn bundled_package_dir(
+    extract_dir: &Path,
+    version: &Version,
+    os: Os,
+    arch: Arch,
+    package: &str,
+) -> PathBuf {
+    let node_dir = extract_dir.join(format!("node-v{}-{}-{}", version, os, arch));
+
+    match os {
+        Os::MacOS | Os::Linux => node_dir.join("lib").join("node_modules").join(package),
+        Os::Windows => node_dir.join("node_modules").join(package),
+    }
+}
+
+/// Copy a package that comes with Node.js out of the extracted Node.js archive and into `{package}-v{version}` in the
+/// cache directory. Returns the path to the copy.
+fn save_bundled_package(
+    extract_dir: &Path,
+    version: &Version,
+    os: Os,
+    arch: Arch,
+    cache_dir: &Path,
+    package: &str,
+) -> Result<PathBuf, Error> {
+    let package_dir = bundled_package_dir(extract_dir, version, os, arch, package);
+
+    let saved_dir = cache_dir.join(format!("{}-v{}", package, version));
+
+    // Copy into a scratch directory first, so an interrupted copy is never mistaken for a complete one. It's named
+    // after the platform too, as the archives for several platforms of a version can be downloaded at once.
+    let partial_dir = cache_dir.join(format!("{}-v{}-{}-{}.partial", package, version, os, arch));
+
+    if partial_dir.exists() {
+        fs::remove_dir_all(&partial_dir).map_err(|err| Error::Io {
+            err,
+            path: partial_dir.clone(),
+            action: format!("removing partially copied {} at", package),
+        })?;
+    }
+
+    copy_dir(&package_dir, &partial_dir).map_err(|err| Error::Io {
+        err,
+        path: package_dir.clone(),
+        action: format!("copying {} from", package),
+    })?;
+
+    if let Err(err) = fs::rename(&partial_dir, &saved_dir) {
+        // Another download of the same version got there first
+        if saved_dir.is_dir() {
+            fs::remove_dir_all(&partial_dir).map_err(|err| Error::Io {
+                err,
+                path: partial_dir.clone(),
+                action: format!("removing copied {} at", package),
+            })?;
+        } else {
+            return Err(Error::Io {
+                err,
+                path: saved_dir,
+                action: format!("moving copied {} to", package),
+            });
+        }
+    }
+
+    Ok(saved_dir)
+}
+
+/// Recursively copy a directory, following any symlinks inside of it.
+fn copy_dir(from: &Path, to: &Path) -> Result<(), io::Error> {
+    fs::create_dir_all(to)?;
+
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let to = to.join(entry.file_name());
+
+        if entry.path().is_dir() {
+            copy_dir(&entry.path(), &to)?;
+        } else {
+            fs::copy(entry.path(), &to)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The URL of the official Node.js archive for a platform: a zip file for Windows, and a tarball for everything else.
+pub fn node_archive_url(version: &Version, os: Os, arch: Arch) -> String {
+    let url = format!("https://nodejs.org/dist/v{version}/node-v{version}-{os}-{arch}");
+
+    if os == Os::Windows {
+        url + ".zip"
+    } else {
+        url + ".tar.gz"
+    }
+}
+
+/// Download the Node.js archive from the official website with `downloader`, and returns the path to the downloaded archive.
+pub fn download_node_archive(
+    download_dir: &Path,
+    version: &Version,
+    os: Os,
+    arch: Arch,
+    downloader: &dyn Downloader,
+    reporter: &dyn Reporter,
+) -> Result<PathBuf, Error> {
+    let url = node_archive_url(version, os, arch);
+
+    debug!("Downloading Node.js from: {}", url); // TODO: Better UI
+
+    // Download the file from the URL
+    let content = downloader
+        .download(&url, reporter)
+        .map_err(|err| Error::Download {
+            err,
+            url: url.clone(),
+        })?;
+
+    // Named after the platform, so that archives for several platforms can be downloaded at once
+    let file_name = download_dir.join(format!(
+        "node-v{}-{}-{}.{}",
+        version,
+        os,
+        arch,
+        if os == Os::Windows { "zip" } else { "tar.gz" }
+    ));
+
+    let mut file = File::create(&file_name).map_err(|err| Error::Io {
+        err,
+        path: file_name.clone(),
+        action: "creating node archive file at".to_string(),
+    })?;
+
+    // Writing the content to the file
+    let mut pos = 0;
+    while pos < content.len() {
+        let bytes_written = file.write(&content[pos..]).map_err(|err| Error::Io {
+            err,
+            path: file_name.clone(),
+            action: "writing to node archive file at".to_string(),
+        })?;
+        pos += bytes_written;
+    }
+
+    Ok(file_name)
+}
+
+/// Download (with `downloader`) and parse the checksum file for a specific version of node
+pub fn download_checksums(
+    version: &Version,
+    downloader: &dyn Downloader,
+) -> Result<Vec<(Checksum, NodeExecutableMeta)>, Error> {
+    Ok(node_archives(download_checksum_entries(
+        version, downloader,
+    )?))
+}
+
+/// Download (with `downloader`) and parse every entry of the checksum file for a specific version of node
+pub fn download_checksum_entries(
+    version: &Version,
+    downloader: &dyn Downloader,
+) -> Result<Vec<SumfileEntry>, Error> {
+    let checksum_file_url = format!("https://nodejs.org/dist/v{}/SHASUMS256.txt", version);
+
+    let checksum_file = downloader
+        .download(&checksum_file_url, &NoopReporter)
+        .map_err(|err| Error::Download {
+            err,
+            url: checksum_file_url,
+        })?;
+
+    sumfile_parser::parse_checksum_file(&String::from_utf8_lossy(&checksum_file))
+}
+
+/// The URL of the headers for building native addons against a version of Node.js.
+pub fn node_headers_url(version: &Version) -> String {
+    format!("https://nodejs.org/dist/v{version}/node-v{version}-headers.tar.gz")
+}
+
+/// Picks the checksums of the archives that `download_node_archive` downloads out of the `entries` of a checksum file
+pub fn node_archives(entries: Vec<SumfileEntry>) -> Vec<(Checksum, NodeExecutableMeta)> {
+    entries
+        .into_iter()
+        .filter_map(|entry| match entry.file {
+            NodeFile::Build {
+                version,
+                os,
+                arch,
+                format: NodeArchiveFormat::TarGz | NodeArchiveFormat::Zip,
+            } => Some((entry.checksum, NodeExecutableMeta { version, arch, os })),
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,724 @@
+mod errors;
+mod helpers;
+mod lock;
+pub mod releases;
+pub mod security;
+pub mod sumfile_parser;
+mod tests;
+
+// Re-export error types
+pub use errors::Error;
+pub use helpers::node_archive_url;
+
+use crate::builder::downloader::{Downloader, HttpDownloader};
+use crate::ui::{NoopReporter, Reporter};
+use helpers::*;
+use lock::{NodeExecutable, NodeManagerLock};
+use log::warn;
+use semver::Version;
+use std::{
+    env,
+    ffi::OsStr,
+    fs::{self, File},
+    io::{Read, Write},
+    iter,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::Arc,
+};
+use sumfile_parser::{NodeArchiveFormat, NodeFile};
+use tempdir::TempDir;
+
+use super::helpers::{calculate_checksum, make_executable};
+use super::platforms::{get_host_arch, get_host_os};
+
+pub struct NodeManager {
+    /// The directory where different node versions are stored.
+    node_cache_dir: PathBuf,
+
+    /// Loaded lockfile information
+    lockfile: NodeManagerLock,
+
+    /// A temporary directory for downloading and extracting node binaries. Need this b/c for as long as
+    /// `NodeManager` is held, we may need to download and extract node binaries at arbitrary times during
+    /// it's lifetime.
+    tmp_dir: TempDir,
+
+    /// Whether the cache is frozen, in which case nothing is downloaded and the lockfile is never written.
+    frozen: bool,
+
+    /// Where to report the progress of downloads.
+    reporter: Arc<dyn Reporter>,
+
+    /// What downloads Node.js binaries and their checksums.
+    downloader: Arc<dyn Downloader>,
+}
+
+impl NodeManager {
+    /// Creates a new NodeManager. We expect that `node_cache_dir` exists and is writable.
+    pub fn new(node_cache_dir: PathBuf) -> Result<Self, Error> {
+        let lockfile_path = node_cache_dir.join("jundler.lockb");
+
+        let lockfile = if lockfile_path.exists() {
+            match NodeManagerLock::load(lockfile_path.clone()) {
+                Ok(lockfile) => lockfile,
+
+                // If we can't load the lockfile, we'll just create a new one
+                Err(Error::LockfileSerialization { .. }) => {
+                    warn!("Failed to load lockfile, creating a new one"); // TODO: Better UI
+                    NodeManagerLock::new(Vec::new(), lockfile_path.clone())
+                }
+
+                Err(e) => return Err(e),
+            }
+        } else {
+            NodeManagerLock::new(Vec::new(), lockfile_path.clone())
+        };
+
+        let tmp_dir = TempDir::new("jundler-node-scratch").map_err(|err| Error::Io {
+            err,
+            path: PathBuf::from("tempdir"),
+            action: "creating temp dir for node scratch at".to_string(),
+        })?;
+
+        Ok(Self {
+            node_cache_dir,
+            lockfile,
+            tmp_dir,
+            frozen: false,
+            reporter: Arc::new(NoopReporter),
+            downloader: Arc::new(HttpDownloader),
+        })
+    }
+
+    /// Reports the progress of downloads to `reporter`.
+    pub fn with_reporter(self, reporter: Arc<dyn Reporter>) -> Self {
+        Self { reporter, ..self }
+    }
+
+    /// Downloads Node.js binaries (and their checksums) with `downloader` from now on.
+    pub fn set_downloader(&mut self, downloader: Arc<dyn Downloader>) {
+        self.downloader = downloader;
+    }
+
+    /// Freezes (or unfreezes) the cache. While it's frozen, binaries that aren't in the cache (or are corrupted) are
+    /// an error instead of being downloaded.
+    pub fn set_frozen(&mut self, frozen: bool) {
+        self.frozen = frozen;
+    }
+
+    /// Downloads a target binary if it doesn't exist, and returns the path to the binary.
+    pub fn get_binary(&mut self, version: &Version, os: Os, arch: Arch) -> Result<PathBuf, Error> {
+        let binary = self.lockfile.find(version, os, arch);
+
+        // Return it if it exists
+        let binary_path = if let Some(archive) = binary {
+            // Check the checksum of the binary. If it's invalid, re-download it.
+            if !archive.validate_checksum()? {
+                warn!("Checksum mismatch for node binary, re-downloading"); // TODO: Better UI
+
+                // Remove the binary from the cache
+                self.remove(archive)?;
+
+                // Download the binary again
+                self.download(version, os, arch)?.0
+            }
+            // If the binary exists, and the checksum is valid, return the path to the binary
+            else {
+                self.unpack_archive(&archive)?
+            }
+        }
+        // If it doesn't exist, download it
+        else {
+            self.download(version, os, arch)?.0
+        };
+
+        // Make the binary executable on Unix-based systems
+        make_executable(&binary_path).map_err(|err| Error::Io {
+            err,
+            path: binary_path.to_path_buf(),
+            action: "making binary executable at".to_string(),
+        })?;
+
+        Ok(binary_path)
+    }
+
+    /// Downloads a target binary into the cache if it isn't already there, without unpacking it from the cache.
+    pub fn fetch(&mut self, version: &Version, os: Os, arch: Arch) -> Result<(), Error> {
+        match self.lockfile.find(version, os, arch) {
+            Some(archive) if archive.validate_checksum()? => Ok(()),
+
+            // Let `get_binary` deal with re-downloading binaries with invalid checksums
+            Some(_) => self.get_binary(version, os, arch).map(|_| ()),
+
+            None => self.download(version, os, arch).map(|_| ()),
+        }
+    }
+
+    /// Gets a Node.js binary for the host, along with the npm that came with it, downloading them if they don't exist.
+    pub fn get_host_node(&mut self, version: &Version) -> Result<HostNode, Error> {
+        let (os, arch) = (get_host_os(), get_host_arch());
+
+        let npm_dir = self.npm_dir(version);
+
+        // Caches from before npm was kept only have the binary, so download the whole archive again to get npm.
+        if !npm_dir.exists() {
+            if let Some(archive) = self.lockfile.find(version, os, arch) {
+                self.remove(archive)?;
+            }
+        }
+
+        let binary_path = self.get_binary(version, os, arch)?;
+
+        if !npm_dir.exists() {
+            self.download(version, os, arch)?;
+        }
+
+        // The binary has to be called `node` and live in its own directory, so that it can be put on the `PATH` for
+        // npm and any scripts it runs. This also keeps it apart from a target binary for the same platform.
+        let bin_dir = self.tmp_dir.path().join(format!("host-node-v{}", version));
+
+        fs::create_dir_all(&bin_dir).map_err(|err| Error::Io {
+            err,
+            path: bin_dir.clone(),
+            action: "creating host node directory at".to_string(),
+        })?;
+
+        let node = bin_dir.join(if os == Os::Windows {
+            "node.exe"
+        } else {
+            "node"
+        });
+
+        fs::copy(&binary_path, &node).map_err(|err| Error::Io {
+            err,
+            path: node.clone(),
+            action: "copying host node binary to".to_string(),
+        })?;
+
+        Ok(HostNode {
+            version: version.clone(),
+            node,
+            npm_cli: npm_dir.join("bin").join("npm-cli.js"),
+            corepack_cli: Some(self.corepack_dir(version).join("dist").join("corepack.js"))
+                .filter(|corepack_cli| corepack_cli.is_file()),
+        })
+    }
+
+    /// Gets the headers for building native addons against a version of Node.js, downloading them if they aren't in
+    /// the cache. Returns the directory to pass to `node-gyp` as `--nodedir`.
+    pub fn get_headers(&mut self, version: &Version) -> Result<PathBuf, Error> {
+        let headers_dir = self.node_cache_dir.join(format!("headers-v{}", version));
+        let node_dir = headers_dir.join(format!("node-v{}", version));
+
+        if node_dir
+            .join("include")
+            .join("node")
+            .join("common.gypi")
+            .exists()
+        {
+            return Ok(node_dir);
+        }
+
+        if self.frozen {
+            return Err(Error::Frozen(format!(
+                "download the headers of Node.js v{}",
+                version
+            )));
+        }
+
+        let checksum = download_checksum_entries(version, &*self.downloader)?
+            .into_iter()
+            .find_map(|entry| match entry.file {
+                NodeFile::Headers {
+                    version: ref headers_version,
+                    format: NodeArchiveFormat::TarGz,
+                } if headers_version == version => Some(entry.checksum),
+                _ => None,
+            })
+            .ok_or_else(|| Error::NodeHeadersDNE(version.clone()))?;
+
+        let url = node_headers_url(version);
+
+        let content = self
+            .downloader
+            .download(&url, &*self.reporter)
+            .map_err(|err| Error::Download {
+                err,
+                url: url.clone(),
+            })?;
+
+        let archive_path = self.tmp_dir.path().join("headers.tar.gz");
+
+        fs::write(&archive_path, &content).map_err(|err| Error::Io {
+            err,
+            path: archive_path.clone(),
+            action: "writing node headers archive to".to_string(),
+        })?;
+
+        let actual_checksum = calculate_checksum(&archive_path).map_err(|err| Error::Io {
+            err,
+            path: archive_path.clone(),
+            action: "calculating checksum of node headers at".into(),
+        })?;
+
+        if actual_checksum != checksum {
+            return Err(Error::ChecksumMismatch {
+                path: archive_path,
+                expected: checksum,
+                actual: actual_checksum,
+            });
+        }
+
+        // Anything left over from an interrupted unpack is started over
+        if headers_dir.exists() {
+            fs::remove_dir_all(&headers_dir).map_err(|err| Error::Io {
+                err,
+                path: headers_dir.clone(),
+                action: "removing incomplete node headers at".to_string(),
+            })?;
+        }
+
+        tar::Archive::new(flate2::read::GzDecoder::new(content.as_slice()))
+            .unpack(&headers_dir)
+            .map_err(|err| Error::Io {
+                err,
+                path: archive_path,
+                action: "extracting node headers from".to_string(),
+            })?;
+
+        Ok(node_dir)
+    }
+
+    /// Gets every Node.js binary in the cache, as its version, OS, and architecture, along with the path to its
+    /// (compressed) archive.
+    pub fn cached_binaries(&self) -> Vec<(Version, Os, Arch, PathBuf)> {
+        self.lockfile
+            .node_executables
+            .iter()
+            .map(|exec| {
+                (
+                    exec.meta.version.clone(),
+                    exec.meta.os,
+                    exec.meta.arch,
+                    exec.path.clone(),
+                )
+            })
+            .collect()
+    }
+
+    /// Checks every Node.js binary in the cache against the checksum it was cached with, as its version, OS, and
+    /// architecture, along with whether it's intact. Binaries that can't be read aren't.
+    pub fn verify_cache(&self) -> Vec<(Version, Os, Arch, bool)> {
+        self.lockfile
+            .node_executables
+            .iter()
+            .map(|exec| {
+                (
+                    exec.meta.version.clone(),
+                    exec.meta.os,
+                    exec.meta.arch,
+                    exec.validate_checksum().unwrap_or(false),
+                )
+            })
+            .collect()
+    }
+
+    /// Removes a node binary from the cache.
+    pub fn remove(&mut self, node_executable: NodeExecutable) -> Result<(), Error> {
+        let path = &node_executable.path;
+
+        if self.frozen {
+            return Err(Error::Frozen(format!(
+                "remove Node.js v{} {}-{} from the cache",
+                node_executable.meta.version, node_executable.meta.os, node_executable.meta.arch
+            )));
+        }
+
+        // Remove the binary from the lockfile
+        self.lockfile.remove(&node_executable);
+
+        // Save the lockfile
+        self.lockfile.save()?;
+
+        // Delete the file from the cache
+        fs::remove_file(path).map_err(|err| Error::Io {
+            err,
+            path: path.clone(),
+            action: "deleting node binary archive at".to_string(),
+        })?;
+
+        // So is the digest of its official archive, if it was kept
+        let meta = &node_executable.meta;
+        let digest_path = self.archive_digest_path(&meta.version, meta.os, meta.arch);
+
+        if digest_path.exists() {
+            fs::remove_file(&digest_path).map_err(|err| Error::Io {
+                err,
+                path: digest_path,
+                action: "deleting checksum of node archive at".to_string(),
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Cleans the cache directory by removing all node binaries and clearing the lockfile.
+    pub fn clean_cache(&mut self) -> Result<(), Error> {
+        // First, clean the lockfile by removing all entries.
+        self.lockfile.node_executables.clear();
+
+        // Delete the entire cache directory
+        fs::remove_dir_all(&self.node_cache_dir).map_err(|err| Error::Io {
+            err,
+            path: self.node_cache_dir.clone(),
+            action: "deleting node cache directory at".to_string(),
+        })?;
+
+        // Recreate the cache directory
+        fs::create_dir_all(&self.node_cache_dir).map_err(|err| Error::Io {
+            err,
+            path: self.node_cache_dir.clone(),
+            action: "recreating node cache directory at".to_string(),
+        })?;
+
+        // Save the lockfile
+        self.lockfile.save()?;
+
+        Ok(())
+    }
+
+    /// Download a new node binary, and store it in the cache. Returns a tuple of the form `(path to the binary, path to the archive)`.
+    fn download(
+        &mut self,
+        version: &Version,
+        os: Os,
+        arch: Arch,
+    ) -> Result<(PathBuf, PathBuf), Error> {
+        let fetched = self.fetcher().download(version, os, arch)?;
+        let paths = (fetched.binary.clone(), fetched.archive.path.clone());
+
+        self.record(fetched)?;
+
+        Ok(paths)
+    }
+
+    /// Gets a [`NodeFetcher`] that downloads Node.js binaries into this cache without holding on to the manager.
+    pub fn fetcher(&self) -> NodeFetcher {
+        NodeFetcher {
+            node_cache_dir: self.node_cache_dir.clone(),
+            scratch_dir: self.tmp_dir.path().to_path_buf(),
+            frozen: self.frozen,
+            reporter: Arc::clone(&self.reporter),
+            downloader: Arc::clone(&self.downloader),
+        }
+    }
+
+    /// Whether the binary for a platform has to be downloaded, because it isn't in the cache or its checksum doesn't
+    /// match.
+    pub fn needs_download(&self, version: &Version, os: Os, arch: Arch) -> bool {
+        self.lockfile
+            .find(version, os, arch)
+            .is_none_or(|archive| !matches!(archive.validate_checksum(), Ok(true)))
+    }
+
+    /// Adds a binary that a [`NodeFetcher`] downloaded to the lockfile, in place of any that was there for its platform.
+    pub fn record(&mut self, fetched: FetchedNode) -> Result<(), Error> {
+        let meta = &fetched.archive.meta;
+
+        if let Some(previous) = self.lockfile.find(&meta.version, meta.os, meta.arch) {
+            self.lockfile.remove(&previous);
+        }
+
+        self.lockfile.add(fetched.archive);
+        self.lockfile.save()
+    }
+
+    /// Gets the SHA256 digest of the official archive (see [`node_archive_url`]) that a cached Node.js binary was
+    /// downloaded in, as Node.js publishes it. `None` if it isn't cached, or was cached by an older version of jundler
+    /// that didn't keep it.
+    pub fn archive_digest(&self, version: &Version, os: Os, arch: Arch) -> Option<[u8; 32]> {
+        let digest = fs::read_to_string(self.archive_digest_path(version, os, arch)).ok()?;
+        let mut checksum = [0; 32];
+
+        hex::decode_to_slice(digest.trim(), &mut checksum).ok()?;
+
+        Some(checksum)
+    }
+
+    /// The path in the cache where the digest of the official archive of a Node.js binary is kept.
+    fn archive_digest_path(&self, version: &Version, os: Os, arch: Arch) -> PathBuf {
+        archive_digest_path(&self.node_cache_dir, version, os, arch)
+    }
+
+    /// The directory in the cache where the npm that came with a version of Node.js is kept.
+    fn npm_dir(&self, version: &Version) -> PathBuf {
+        npm_dir(&self.node_cache_dir, version)
+    }
+
+    /// The directory in the cache where the Corepack that came with a version of Node.js is kept, if it came with one.
+    fn corepack_dir(&self, version: &Version) -> PathBuf {
+        corepack_dir(&self.node_cache_dir, version)
+    }
+
+    /// Unpack a node binary from the cache. Returns the path to the binary.
+    pub fn unpack_archive(&self, node_archive: &NodeExecutable) -> Result<PathBuf, Error> {
+        // Undo the process in `repack_node_binary`
+        let archived_binary = File::open(&node_archive.path).map_err(|err| Error::Io {
+            err,
+            path: node_archive.path.clone(),
+            action: "opening node archive file at".to_string(),
+        })?;
+
+        let mut zstd_decoder = zstd::Decoder::new(archived_binary).map_err(|err| Error::Io {
+            err,
+            path: node_archive.path.clone(),
+            action: "creating zstd decoder for archive file at".to_string(),
+        })?;
+
+        let extracted_binary_path = self.tmp_dir.path().join(format!(
+            // .exe for windows, doesn't matter for other platforms. Also, avoids collision with folders of the same name.
+            "node-v{}-{}-{}.exe",
+            node_archive.meta.version, node_archive.meta.os, node_archive.meta.arch
+        ));
+
+        let mut extracted_binary =
+            File::create(&extracted_binary_path).map_err(|err| Error::Io {
+                err,
+                path: extracted_binary_path.clone(),
+                action: "creating extracted node binary file at".to_string(),
+            })?;
+
+        let mut buf: Vec<u8> = vec![];
+
+        zstd_decoder
+            .read_to_end(&mut buf)
+            .map_err(|err| Error::Io {
+                err,
+                path: node_archive.path.clone(),
+                action: "reading from archive file at".to_string(),
+            })?;
+
+        extracted_binary.write_all(&buf).map_err(|err| Error::Io {
+            err,
+            path: extracted_binary_path.clone(),
+            action: "writing to extracted node binary file at".to_string(),
+        })?;
+
+        // The binary was checked when it was downloaded, but the cache could have been changed since
+        check_platform(
+            &extracted_binary_path,
+            node_archive.meta.os,
+            node_archive.meta.arch,
+        )?;
+
+        Ok(extracted_binary_path)
+    }
+}
+
+/// Downloads Node.js binaries into the cache of a [`NodeManager`] without holding on to it, so that several can be
+/// downloaded at once. See [`NodeManager::fetcher`].
+#[derive(Clone)]
+pub struct NodeFetcher {
+    /// The directory where different node versions are stored.
+    node_cache_dir: PathBuf,
+
+    /// The manager's temporary directory, to download and extract the archives in.
+    scratch_dir: PathBuf,
+
+    /// Whether the cache is frozen, in which case nothing is downloaded.
+    frozen: bool,
+
+    /// Where to report the progress of downloads.
+    reporter: Arc<dyn Reporter>,
+
+    /// What downloads Node.js binaries and their checksums.
+    downloader: Arc<dyn Downloader>,
+}
+
+impl NodeFetcher {
+    /// Downloads a Node.js binary into the cache, along with the npm and Corepack that came with it if they aren't
+    /// there yet. It isn't used from the cache until it's [recorded](NodeManager::record).
+    pub fn download(&self, version: &Version, os: Os, arch: Arch) -> Result<FetchedNode, Error> {
+        if self.frozen {
+            return Err(Error::Frozen(format!(
+                "download Node.js v{} {}-{}",
+                version, os, arch
+            )));
+        }
+
+        // Download the checksum file
+        let checksums = download_checksums(version, &*self.downloader)?;
+
+        // TODO: Check the signature of the checksum file (if available)
+
+        // Find the correct checksum for the requested platform
+        let (checksum, meta) = checksums
+            .into_iter()
+            .find(|(_, meta)| meta.version == *version && meta.os == os && meta.arch == arch)
+            .ok_or_else(|| Error::NodeBinaryDNE {
+                version: version.clone(),
+                os,
+                arch,
+            })?;
+
+        // Download the node archive
+        let downloaded_archive_path = download_node_archive(
+            &self.scratch_dir,
+            version,
+            os,
+            arch,
+            &*self.downloader,
+            &*self.reporter,
+        )?;
+
+        let actual_checksum =
+            calculate_checksum(&downloaded_archive_path).map_err(|err| Error::Io {
+                err,
+                path: downloaded_archive_path.clone(),
+                action: "calculating checksum of node executable at".into(),
+            })?;
+
+        // Error out if the checksums don't match
+        if actual_checksum != checksum {
+            return Err(Error::ChecksumMismatch {
+                path: downloaded_archive_path,
+                expected: checksum,
+                actual: actual_checksum,
+            });
+        }
+
+        // Unpack the archive. Needs version, os, and arch to determine the correct path to the binary (named folder).
+        let node_executable_path = unpack_downloaded_node_archive(
+            &self.scratch_dir,
+            &downloaded_archive_path,
+            version,
+            os,
+            arch,
+        )?;
+
+        if !npm_dir(&self.node_cache_dir, version).exists() {
+            save_npm(&self.scratch_dir, version, os, arch, &self.node_cache_dir)?;
+        }
+
+        if !corepack_dir(&self.node_cache_dir, version).exists() {
+            save_corepack(&self.scratch_dir, version, os, arch, &self.node_cache_dir)?;
+        }
+
+        let node_archive_path = repack_node_binary(
+            &node_executable_path,
+            version,
+            os,
+            arch,
+            &self.node_cache_dir,
+        )?;
+
+        let archive_checksum = calculate_checksum(&node_archive_path).map_err(|err| Error::Io {
+            err,
+            path: node_archive_path.clone(),
+            action: "calculating checksum of node executable at".into(),
+        })?;
+
+        // Keep the checksum of the official archive too, as the repacked one doesn't match anything Node.js publishes
+        let digest_path = archive_digest_path(&self.node_cache_dir, version, os, arch);
+
+        fs::write(&digest_path, hex::encode(checksum)).map_err(|err| Error::Io {
+            err,
+            path: digest_path,
+            action: "writing checksum of node archive to".into(),
+        })?;
+
+        Ok(FetchedNode {
+            binary: node_executable_path,
+            archive: NodeExecutable {
+                meta,
+                path: node_archive_path,
+                checksum: archive_checksum,
+            },
+        })
+    }
+}
+
+/// A Node.js binary that a [`NodeFetcher`] downloaded into the cache, but that isn't in its lockfile yet.
+pub struct FetchedNode {
+    /// The path to the extracted binary.
+    binary: PathBuf,
+
+    /// The repacked binary in the cache.
+    archive: NodeExecutable,
+}
+
+/// The path in a cache where the digest of the official archive of a Node.js binary is kept.
+fn archive_digest_path(cache_dir: &Path, version: &Version, os: Os, arch: Arch) -> PathBuf {
+    cache_dir.join(format!("node-v{}-{}-{}.sha256", version, os, arch))
+}
+
+/// The directory in a cache where the npm that came with a version of Node.js is kept.
+fn npm_dir(cache_dir: &Path, version: &Version) -> PathBuf {
+    cache_dir.join(format!("npm-v{}", version))
+}
+
+/// The directory in a cache where the Corepack that came with a version of Node.js is kept, if it came with one.
+fn corepack_dir(cache_dir: &Path, version: &Version) -> PathBuf {
+    cache_dir.join(format!("corepack-v{}", version))
+}
+
+/// A Node.js binary that runs on the host, along with the npm that came with it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostNode {
+    /// The version of Node.js.
+    pub version: Version,
+
+    /// The path to the Node.js binary.
+    pub node: PathBuf,
+
+    /// The path to npm's entrypoint script.
+    pub npm_cli: PathBuf,
+
+    /// The path to Corepack's entrypoint script, if this version of Node.js came with Corepack (and it was kept).
+    pub corepack_cli: Option<PathBuf>,
+}
+
+impl HostNode {
+    /// Gets a command that runs npm with this Node.js.
+    pub fn npm(&self) -> Command {
+        let mut cmd = self.command(&self.node);
+
+        cmd.arg(&self.npm_cli);
+
+        cmd
+    }
+
+    /// Gets a command that runs Corepack with this Node.js: the Corepack that came with it, or the one on the npm
+    /// registry for versions of Node.js without one.
+    pub fn corepack(&self) -> Command {
+        match &self.corepack_cli {
+            Some(corepack_cli) => {
+                let mut cmd = self.command(&self.node);
+                cmd.arg(corepack_cli);
+                cmd
+            }
+
+            None => {
+                let mut cmd = self.npm();
+                cmd.args(["exec", "--yes", "--package=corepack", "--", "corepack"]);
+                cmd
+            }
+        }
+    }
+
+    /// Gets a command that runs `program` with this Node.js first on the `PATH`, so that any scripts it runs use it.
+    pub fn command(&self, program: impl AsRef<OsStr>) -> Command {
+        let mut cmd = Command::new(program);
+
+        let paths = env::var_os("PATH").unwrap_or_default();
+
+        if let Some(Ok(paths)) = self.node.parent().map(|bin_dir| {
+            env::join_paths(iter::once(bin_dir.to_path_buf()).chain(env::split_paths(&paths)))
+        }) {
+            cmd.env("PATH", paths);
+        }
+
+        cmd
+    }
+}
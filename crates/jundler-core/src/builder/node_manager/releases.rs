@@ -0,0 +1,79 @@
+use super::Error;
+use crate::builder::downloader::Downloader;
+use crate::ui::NoopReporter;
+use semver::{Version, VersionReq};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// The URL of the index of every Node.js release.
+const RELEASE_INDEX_URL: &str = "https://nodejs.org/dist/index.json";
+
+/// A single Node.js release.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Release {
+    /// The version of the release.
+    pub version: Version,
+
+    /// The codename of the LTS line the release belongs to (i.e. `Iron`), if it's an LTS release.
+    pub lts: Option<String>,
+}
+
+/// A release as listed in the release index.
+#[derive(Deserialize)]
+struct IndexEntry {
+    version: String,
+    lts: Value,
+}
+
+/// Downloads the index of every Node.js release with `downloader`, sorted from newest to oldest.
+pub fn download_releases(downloader: &dyn Downloader) -> Result<Vec<Release>, Error> {
+    let index = downloader
+        .download(RELEASE_INDEX_URL, &NoopReporter)
+        .map_err(|err| Error::Download {
+            err,
+            url: RELEASE_INDEX_URL.to_string(),
+        })?;
+
+    parse_releases(&String::from_utf8_lossy(&index))
+}
+
+/// Parses the release index, sorted from newest to oldest. Releases with versions that aren't valid semver are
+/// skipped.
+pub fn parse_releases(index: &str) -> Result<Vec<Release>, Error> {
+    let entries: Vec<IndexEntry> =
+        serde_json::from_str(index).map_err(|_| Error::UnparseableReleaseIndex)?;
+
+    let mut releases: Vec<Release> = entries
+        .into_iter()
+        .filter_map(|entry| {
+            Some(Release {
+                version: Version::parse(entry.version.strip_prefix('v')?).ok()?,
+                // Not an LTS release if `lts` is `false`
+                lts: entry.lts.as_str().map(String::from),
+            })
+        })
+        .collect();
+
+    releases.sort_by(|a, b| b.version.cmp(&a.version));
+
+    Ok(releases)
+}
+
+/// Gets the newest release matching `req`.
+pub fn latest_matching<'a>(releases: &'a [Release], req: &VersionReq) -> Option<&'a Release> {
+    releases
+        .iter()
+        .find(|release| req.matches(&release.version))
+}
+
+/// Gets the newest LTS release, or the newest release of a specific LTS line if `codename` is set (compared
+/// case-insensitively).
+pub fn latest_lts<'a>(releases: &'a [Release], codename: Option<&str>) -> Option<&'a Release> {
+    releases
+        .iter()
+        .find(|release| match (&release.lts, codename) {
+            (Some(lts), Some(codename)) => lts.eq_ignore_ascii_case(codename),
+            (Some(_), None) => true,
+            (None, _) => false,
+        })
+}
@@ -0,0 +1,164 @@
+use super::Error;
+use crate::builder::downloader::Downloader;
+use crate::ui::NoopReporter;
+use semver::{Op, Version, VersionReq};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// The URL of the Node.js security working group's database of vulnerabilities in Node.js itself.
+const VULNERABILITY_INDEX_URL: &str =
+    "https://raw.githubusercontent.com/nodejs/security-wg/main/vuln/core/index.json";
+
+/// A published vulnerability in Node.js.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vulnerability {
+    /// The CVEs the vulnerability was assigned, i.e. `CVE-2024-27983`.
+    pub cves: Vec<String>,
+
+    /// The versions of Node.js that are vulnerable. A version is vulnerable if it matches any of them.
+    pub vulnerable: Vec<VersionReq>,
+
+    /// The versions of Node.js that fixed it, as published, i.e. `^18.20.1 || ^20.12.1`.
+    pub patched: Option<String>,
+
+    /// A short description of the vulnerability.
+    pub overview: Option<String>,
+
+    /// Where to read more about the vulnerability, usually the security release's announcement.
+    pub reference: Option<String>,
+}
+
+/// A vulnerability as listed in the database.
+#[derive(Deserialize)]
+struct IndexEntry {
+    #[serde(default)]
+    cve: Vec<String>,
+    vulnerable: String,
+    patched: Option<String>,
+    overview: Option<String>,
+    #[serde(rename = "ref")]
+    reference: Option<String>,
+}
+
+impl Vulnerability {
+    /// Whether `version` of Node.js is vulnerable.
+    pub fn affects(&self, version: &Version) -> bool {
+        self.vulnerable.iter().any(|req| req.matches(version))
+    }
+
+    /// Gets the first release that fixed the vulnerability in the same major version as `version`, i.e. `20.12.1` for
+    /// `20.11.0`. `None` if no release in that line fixed it.
+    pub fn patched_in(&self, version: &Version) -> Option<Version> {
+        parse_range(self.patched.as_deref()?)?
+            .iter()
+            .filter_map(|req| {
+                let lowest = req.comparators.iter().find(|comparator| {
+                    matches!(
+                        comparator.op,
+                        Op::Caret | Op::Tilde | Op::GreaterEq | Op::Exact
+                    )
+                })?;
+
+                Some(Version::new(
+                    lowest.major,
+                    lowest.minor.unwrap_or(0),
+                    lowest.patch.unwrap_or(0),
+                ))
+            })
+            .filter(|patched| patched.major == version.major && patched > version)
+            .min()
+    }
+}
+
+/// Downloads every published vulnerability in Node.js with `downloader`.
+pub fn download_vulnerabilities(downloader: &dyn Downloader) -> Result<Vec<Vulnerability>, Error> {
+    parse_vulnerabilities(&download_vulnerability_index(downloader)?)
+}
+
+/// Downloads the vulnerability database with `downloader`, as it's published, to be parsed with
+/// [`parse_vulnerabilities`].
+pub fn download_vulnerability_index(downloader: &dyn Downloader) -> Result<String, Error> {
+    let index = downloader
+        .download(VULNERABILITY_INDEX_URL, &NoopReporter)
+        .map_err(|err| Error::Download {
+            err,
+            url: VULNERABILITY_INDEX_URL.to_string(),
+        })?;
+
+    Ok(String::from_utf8_lossy(&index).into_owned())
+}
+
+/// Parses the vulnerability database, in the order of its IDs. Vulnerabilities whose ranges of versions can't be
+/// understood are skipped.
+pub fn parse_vulnerabilities(index: &str) -> Result<Vec<Vulnerability>, Error> {
+    let entries: BTreeMap<String, IndexEntry> =
+        serde_json::from_str(index).map_err(|_| Error::UnparseableVulnerabilityIndex)?;
+
+    let mut entries: Vec<(String, IndexEntry)> = entries.into_iter().collect();
+
+    // IDs are numbers, so they're sorted as such
+    entries.sort_by_key(|(id, _)| id.parse::<u64>().unwrap_or(u64::MAX));
+
+    Ok(entries
+        .into_iter()
+        .filter_map(|(_, entry)| {
+            Some(Vulnerability {
+                cves: entry.cve,
+                vulnerable: parse_range(&entry.vulnerable)?,
+                patched: entry.patched.filter(|patched| !patched.is_empty()),
+                overview: entry.overview,
+                reference: entry.reference,
+            })
+        })
+        .collect())
+}
+
+/// Parses a range of versions the way npm writes them, i.e. `^18.0.0 || >= 20.0.0 < 20.12.1`, into the requirements
+/// it's made of. `None` if any of them can't be parsed.
+fn parse_range(range: &str) -> Option<Vec<VersionReq>> {
+    range
+        .split("||")
+        .map(|set| {
+            let mut tokens: Vec<String> = vec![];
+            let mut words = set.split_whitespace().peekable();
+
+            while let Some(word) = words.next() {
+                // Operators can be written apart from their versions, i.e. `>= 20.0.0`
+                let mut token = if word.chars().all(|c| "<>=~^".contains(c)) {
+                    format!("{}{}", word, words.next()?)
+                } else {
+                    word.to_string()
+                };
+
+                // Hyphen ranges, i.e. `1.2.3 - 2.3.4`, include both ends
+                if words.peek() == Some(&"-") {
+                    words.next();
+                    token = format!(">={}, <={}", token, words.next()?);
+                }
+
+                tokens.push(without_wildcards(&token));
+            }
+
+            VersionReq::parse(&tokens.join(", ")).ok()
+        })
+        .collect()
+}
+
+/// Removes the wildcards from a comparison like `<= 4.x`, which npm allows but semver doesn't. Wildcards on their own,
+/// like `4.x`, are left alone.
+fn without_wildcards(token: &str) -> String {
+    if !token.starts_with(['<', '>', '=', '~', '^']) {
+        return token.to_string();
+    }
+
+    let mut token = token;
+
+    while let Some(stripped) = [".x", ".X", ".*"]
+        .iter()
+        .find_map(|wildcard| token.strip_suffix(wildcard))
+    {
+        token = stripped;
+    }
+
+    token.to_string()
+}
@@ -0,0 +1,307 @@
+//! A parser for the checksum files that Node.js publishes with each release (`SHASUMS256.txt`), which list the SHA256
+//! digest of every file in the release: the builds for each platform, the headers, the source code, the installers,
+//! and so on.
+//!
+//! ```
+//! use jundler_core::builder::node_manager::sumfile_parser::{parse_checksum_file, NodeArchiveFormat, NodeFile};
+//! use jundler_core::builder::platforms::{Arch, Os};
+//!
+//! let entries = parse_checksum_file(
+//!     "a6d4fbf4306a883b8e1d235a8a890be84b9d95d2d39b929520bed64da41ce540  node-v22.3.0-linux-x64.tar.gz\n",
+//! )
+//! .unwrap();
+//!
+//! assert_eq!(entries[0].name, "node-v22.3.0-linux-x64.tar.gz");
+//! assert_eq!(
+//!     entries[0].file,
+//!     NodeFile::Build {
+//!         version: "22.3.0".parse().unwrap(),
+//!         os: Os::Linux,
+//!         arch: Arch::X64,
+//!         format: NodeArchiveFormat::TarGz,
+//!     }
+//! );
+//! ```
+
+use super::Error;
+use crate::builder::platforms::{Arch, Os};
+use nom::branch::alt;
+use nom::character::complete::one_of;
+use nom::combinator::{all_consuming, map, recognize, value};
+use nom::multi::{many0, many1};
+use nom::sequence::{preceded, terminated, tuple};
+use nom::{
+    bytes::complete::{tag, take},
+    character::complete::char,
+    IResult,
+};
+use semver::Version;
+use std::fmt;
+
+// TODO: Consider replacing nom with winnow: https://docs.rs/winnow/latest/winnow/
+
+/// A single line of a checksum file: a file in the release, and its digest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SumfileEntry {
+    /// The SHA256 digest of the file.
+    pub checksum: [u8; 32],
+
+    /// The name of the file, as it's listed: i.e. `node-v22.3.0-linux-x64.tar.gz` or `win-x64/node.exe`. It's relative
+    /// to the release's directory on nodejs.org.
+    pub name: String,
+
+    /// What the file is.
+    pub file: NodeFile,
+}
+
+/// What a file in a Node.js release is, from its name. More kinds of files may be recognized in the future, so match
+/// on this with a wildcard arm.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum NodeFile {
+    /// A build of Node.js for a platform that jundler builds for, i.e. `node-v22.3.0-linux-x64.tar.gz`.
+    Build {
+        /// The version of Node.js.
+        version: Version,
+
+        /// The operating system it's built for.
+        os: Os,
+
+        /// The architecture it's built for.
+        arch: Arch,
+
+        /// The format of the archive.
+        format: NodeArchiveFormat,
+    },
+
+    /// The headers for building native addons, i.e. `node-v22.3.0-headers.tar.gz`.
+    Headers {
+        /// The version of Node.js.
+        version: Version,
+
+        /// The format of the archive.
+        format: NodeArchiveFormat,
+    },
+
+    /// The source code of Node.js, i.e. `node-v22.3.0.tar.xz`.
+    Source {
+        /// The version of Node.js.
+        version: Version,
+
+        /// The format of the archive.
+        format: NodeArchiveFormat,
+    },
+
+    /// A Windows installer, i.e. `node-v22.3.0-x64.msi`.
+    Msi {
+        /// The version of Node.js.
+        version: Version,
+
+        /// The architecture it installs Node.js for.
+        arch: Arch,
+    },
+
+    /// The macOS installer, i.e. `node-v22.3.0.pkg`.
+    Pkg {
+        /// The version of Node.js.
+        version: Version,
+    },
+
+    /// Anything else: builds for platforms that jundler doesn't build for (i.e. `node-v22.3.0-aix-ppc64.tar.gz`), or
+    /// single files of the Windows builds (i.e. `win-x64/node.exe`).
+    Other,
+}
+
+/// The format of an archive in a Node.js release.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeArchiveFormat {
+    /// A gzipped tarball (`.tar.gz`).
+    TarGz,
+
+    /// An xz-compressed tarball (`.tar.xz`).
+    TarXz,
+
+    /// A zip file (`.zip`).
+    Zip,
+
+    /// A 7-Zip archive (`.7z`).
+    SevenZip,
+}
+
+impl fmt::Display for NodeArchiveFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NodeArchiveFormat::TarGz => write!(f, "tar.gz"),
+            NodeArchiveFormat::TarXz => write!(f, "tar.xz"),
+            NodeArchiveFormat::Zip => write!(f, "zip"),
+            NodeArchiveFormat::SevenZip => write!(f, "7z"),
+        }
+    }
+}
+
+/// Parses a checksum file, with one `<sha256>  <file name>` line per file (as written by `sha256sum`), into its
+/// entries in order. Every file is included, whether or not it's recognized (see [`NodeFile`]); blank lines are
+/// skipped. Errors if any other line isn't a checksum and a file name, or if there are no entries at all.
+pub fn parse_checksum_file(input: &str) -> Result<Vec<SumfileEntry>, Error> {
+    let mut entries = Vec::new();
+
+    for (number, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let (_, (checksum, name)) = parse_checksum_file_entry(line.trim_end()).map_err(|_| {
+            Error::MalformedChecksumLine {
+                line: number + 1,
+                contents: line.to_string(),
+            }
+        })?;
+
+        entries.push(SumfileEntry {
+            checksum,
+            name: name.to_string(),
+            file: parse_file_name(name),
+        });
+    }
+
+    // If there are no entries, return an error
+    if entries.is_empty() {
+        return Err(Error::UnparseableChecksumFile);
+    }
+
+    Ok(entries)
+}
+
+/// A function to parse a single line in a checksum file using Nom, into the checksum and the file name
+fn parse_checksum_file_entry(input: &str) -> IResult<&str, ([u8; 32], &str)> {
+    let (input, checksum) = parse_checksum(input)?;
+    // `sha256sum` marks files read in binary mode with a `*`
+    let (name, _) = alt((tag("  "), tag(" *")))(input)?;
+
+    if name.is_empty() {
+        return Err(nom::Err::Error(nom::error::Error::new(
+            name,
+            nom::error::ErrorKind::Eof,
+        )));
+    }
+
+    Ok(("", (checksum, name)))
+}
+
+/// Recognizes a file from its name, or returns `NodeFile::Other` if it isn't recognized
+fn parse_file_name(name: &str) -> NodeFile {
+    let build = map(
+        tuple((
+            parse_version,
+            preceded(char('-'), parse_os),
+            preceded(char('-'), parse_arch),
+            parse_archive_format,
+        )),
+        |(version, os, arch, format)| NodeFile::Build {
+            version,
+            os,
+            arch,
+            format,
+        },
+    );
+
+    let headers = map(
+        tuple((
+            parse_version,
+            preceded(tag("-headers"), parse_archive_format),
+        )),
+        |(version, format)| NodeFile::Headers { version, format },
+    );
+
+    let source = map(
+        tuple((parse_version, parse_archive_format)),
+        |(version, format)| NodeFile::Source { version, format },
+    );
+
+    let msi = map(
+        tuple((parse_version, preceded(char('-'), parse_arch), tag(".msi"))),
+        |(version, arch, _)| NodeFile::Msi { version, arch },
+    );
+
+    let pkg = map(terminated(parse_version, tag(".pkg")), |version| {
+        NodeFile::Pkg { version }
+    });
+
+    let result: IResult<&str, NodeFile> = all_consuming(preceded(
+        tag("node-v"),
+        alt((build, headers, source, msi, pkg)),
+    ))(name);
+
+    result.map_or(NodeFile::Other, |(_, file)| file)
+}
+
+/// Parses a checksum
+fn parse_checksum(input: &str) -> IResult<&str, [u8; 32]> {
+    // Get the first 64 characters of the input --- these are the hex characters of the checksum
+    let (rest, checksum_str) = take(64usize)(input)?;
+
+    let mut checksum = [0u8; 32];
+
+    // Convert the hex characters to a [u8; 32]
+    hex::decode_to_slice(checksum_str, &mut checksum as &mut [u8]).map_err(|_| {
+        nom::Err::Error(nom::error::Error::new(
+            input,
+            nom::error::ErrorKind::HexDigit,
+        ))
+    })?;
+
+    Ok((rest, checksum))
+}
+
+/// Parses a semver version
+fn parse_version(input: &str) -> IResult<&str, semver::Version> {
+    let (rest, version_str) = tuple((
+        parse_decimal_number,
+        char('.'),
+        parse_decimal_number,
+        char('.'),
+        parse_decimal_number,
+    ))(input)?;
+
+    let version_str = version_str.0.to_owned() + "." + version_str.2 + "." + version_str.4;
+
+    // Semver doesn't allow leading zeroes, like in `01.2.3`
+    let version = Version::parse(&version_str).map_err(|_| {
+        nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Verify))
+    })?;
+
+    Ok((rest, version))
+}
+
+/// Parses an operating system
+fn parse_os(input: &str) -> IResult<&str, Os> {
+    alt((
+        value(Os::Windows, tag("win")),
+        value(Os::MacOS, tag("darwin")),
+        value(Os::Linux, tag("linux")),
+    ))(input)
+}
+
+/// Parses an architecture
+fn parse_arch(input: &str) -> IResult<&str, Arch> {
+    alt((
+        value(Arch::Arm64, alt((tag("arm64"), tag("aarch64")))),
+        value(Arch::X64, alt((tag("x64"), tag("x86_64")))),
+        value(Arch::X86, tag("x86")),
+    ))(input)
+}
+
+/// Parses the extension of an archive
+fn parse_archive_format(input: &str) -> IResult<&str, NodeArchiveFormat> {
+    alt((
+        value(NodeArchiveFormat::TarGz, tag(".tar.gz")),
+        value(NodeArchiveFormat::TarXz, tag(".tar.xz")),
+        value(NodeArchiveFormat::Zip, tag(".zip")),
+        value(NodeArchiveFormat::SevenZip, tag(".7z")),
+    ))(input)
+}
+
+/// Parse a decimal number
+fn parse_decimal_number(input: &str) -> IResult<&str, &str> {
+    recognize(many1(terminated(one_of("0123456789"), many0(char('_')))))(input)
+}
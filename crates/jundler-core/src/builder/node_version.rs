@@ -0,0 +1,330 @@
+use super::downloader::Downloader;
+use super::node_manager::releases::{download_releases, latest_lts, latest_matching, Release};
+use super::project_lock::{ProjectLock, LOCK_FILE_NAME};
+use anyhow::{anyhow, Context, Result};
+use log::warn;
+use semver::{Version, VersionReq};
+use serde_json::Value;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The files that can pin the Node.js version of a project, in order of priority.
+const VERSION_FILES: &[&str] = &[".nvmrc", ".node-version"];
+
+/// Where the version of Node.js to build with came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionSource {
+    /// A version file like `.nvmrc`.
+    VersionFile(PathBuf),
+
+    /// The `engines.node` field of `package.json`.
+    Engines,
+
+    /// The latest LTS release of Node.js.
+    LatestLts,
+
+    /// The Node.js installed on this machine.
+    SystemNode,
+
+    /// The project's `jundler.lock`.
+    Lockfile,
+}
+
+impl fmt::Display for VersionSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VersionSource::VersionFile(path) => write!(f, "{}", path.display()),
+            VersionSource::Engines => write!(f, "`engines.node` in package.json"),
+            VersionSource::LatestLts => write!(f, "the latest LTS release"),
+            VersionSource::SystemNode => write!(f, "the installed Node.js"),
+            VersionSource::Lockfile => write!(f, "{}", LOCK_FILE_NAME),
+        }
+    }
+}
+
+/// A specification of which Node.js version to use, as written in `.nvmrc` or `engines.node`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionSpec {
+    /// An exact version, like `20.11.0`.
+    Exact(Version),
+
+    /// A range of versions, which matches if any of the alternatives match. Like `^18 || >=20.5`.
+    Range(Vec<VersionReq>),
+
+    /// The latest LTS release, or the latest release of a specific LTS line (like `lts/iron`).
+    Lts(Option<String>),
+
+    /// The latest release.
+    Latest,
+}
+
+impl VersionSpec {
+    /// Parses a version specification. Supports exact versions, npm-style ranges, and the `lts/*`, `lts/<codename>`
+    /// and `node` aliases of nvm.
+    pub fn parse(spec: &str) -> Result<VersionSpec> {
+        let spec = spec.trim();
+
+        match spec.to_lowercase().as_str() {
+            "node" | "latest" | "current" | "stable" => return Ok(VersionSpec::Latest),
+            "lts" | "lts/*" => return Ok(VersionSpec::Lts(None)),
+            lts if lts.starts_with("lts/") => {
+                return Ok(VersionSpec::Lts(Some(lts["lts/".len()..].to_string())))
+            }
+            _ => {}
+        }
+
+        if let Ok(version) = Version::parse(spec.trim_start_matches('v')) {
+            return Ok(VersionSpec::Exact(version));
+        }
+
+        spec.split("||")
+            .map(parse_npm_range)
+            .collect::<Result<_>>()
+            .map(VersionSpec::Range)
+    }
+
+    /// Whether a version satisfies the specification. Aliases are satisfied by any version, as there is no way to
+    /// check them without the release index.
+    fn matches(&self, version: &Version) -> bool {
+        match self {
+            VersionSpec::Exact(exact) => exact == version,
+            VersionSpec::Range(reqs) => reqs.iter().any(|req| req.matches(version)),
+            VersionSpec::Lts(_) | VersionSpec::Latest => true,
+        }
+    }
+
+    /// Finds the newest release matching the specification.
+    fn resolve<'a>(&self, releases: &'a [Release]) -> Option<&'a Release> {
+        match self {
+            VersionSpec::Exact(version) => releases.iter().find(|r| r.version == *version),
+            VersionSpec::Range(_) => releases.iter().find(|r| self.matches(&r.version)),
+            VersionSpec::Lts(codename) => latest_lts(releases, codename.as_deref()),
+            VersionSpec::Latest => latest_matching(releases, &VersionReq::STAR),
+        }
+    }
+}
+
+impl fmt::Display for VersionSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VersionSpec::Exact(version) => write!(f, "{}", version),
+            VersionSpec::Range(reqs) => write!(
+                f,
+                "{}",
+                reqs.iter()
+                    .map(|req| req.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" || ")
+            ),
+            VersionSpec::Lts(Some(codename)) => write!(f, "lts/{}", codename),
+            VersionSpec::Lts(None) => write!(f, "lts/*"),
+            VersionSpec::Latest => write!(f, "node"),
+        }
+    }
+}
+
+/// Works out which version of Node.js to build the project with: from `.nvmrc` (or `.node-version`), then
+/// `engines.node` in `package.json`, and finally the latest LTS release. If the release index can't be downloaded,
+/// the installed Node.js is used as a last resort, as long as it matches. The index is downloaded with `downloader`.
+pub fn resolve_node_version(
+    project_dir: &Path,
+    downloader: &dyn Downloader,
+) -> Result<(Version, VersionSource)> {
+    let (spec, source) = find_version_spec(project_dir)?;
+
+    // Exact versions don't need the release index
+    if let VersionSpec::Exact(version) = spec {
+        return Ok((version, source));
+    }
+
+    let releases = match download_releases(downloader) {
+        Ok(releases) => releases,
+
+        Err(err) => {
+            return match system_node_version().filter(|version| spec.matches(version)) {
+                Some(version) => {
+                    warn!(
+                        "Could not download the Node.js release index ({}), using the installed Node.js v{} for `{}`",
+                        err, version, spec
+                    );
+
+                    Ok((version, VersionSource::SystemNode))
+                }
+
+                None => Err(err).context(format!(
+                    "Could not resolve Node.js `{}` from {}. Pass the version to build with using `--node-version`",
+                    spec, source
+                )),
+            };
+        }
+    };
+
+    let release = spec
+        .resolve(&releases)
+        .ok_or_else(|| anyhow!("No Node.js release matches `{}` from {}!", spec, source))?;
+
+    Ok((release.version.clone(), source))
+}
+
+/// Works out which version of Node.js to build the project with, without downloading anything: an exact version from
+/// `.nvmrc` (or `.node-version`) or `engines.node`, or else the version in the project's `jundler.lock`, as long as
+/// it matches. Used by frozen builds.
+pub fn resolve_locked_node_version(project_dir: &Path) -> Result<(Version, VersionSource)> {
+    let (spec, source) = find_version_spec(project_dir)?;
+
+    if let VersionSpec::Exact(version) = spec {
+        return Ok((version, source));
+    }
+
+    match ProjectLock::load(project_dir)?.and_then(|lock| lock.node_version) {
+        Some(version) if spec.matches(&version) => Ok((version, VersionSource::Lockfile)),
+
+        Some(version) => Err(anyhow!(
+            "Node.js v{} from {} doesn't match `{}` from {}! Run `jundler fetch` to update it.",
+            version,
+            LOCK_FILE_NAME,
+            spec,
+            source
+        )),
+
+        None => Err(anyhow!(
+            "Could not resolve Node.js `{}` from {} without downloading the release index. Pass the version to build with using `--node-version`, or run `jundler fetch` to pin it in {}.",
+            spec,
+            source,
+            LOCK_FILE_NAME
+        )),
+    }
+}
+
+/// Finds the specification of which Node.js version to use for the project, and where it came from.
+fn find_version_spec(project_dir: &Path) -> Result<(VersionSpec, VersionSource)> {
+    if let Some((path, spec)) = find_version_file(project_dir)? {
+        let spec = VersionSpec::parse(&spec).context(format!(
+            "Could not parse the Node.js version in {}",
+            path.display()
+        ))?;
+
+        Ok((spec, VersionSource::VersionFile(path)))
+    } else if let Some(engines) = engines_node(project_dir)? {
+        let spec = VersionSpec::parse(&engines)
+            .context("Could not parse `engines.node` in package.json")?;
+
+        Ok((spec, VersionSource::Engines))
+    } else {
+        Ok((VersionSpec::Lts(None), VersionSource::LatestLts))
+    }
+}
+
+/// Gets the version of the Node.js installed on this machine, if there is one.
+pub fn system_node_version() -> Option<Version> {
+    let output = Command::new("node").arg("--version").output().ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    Version::parse(
+        String::from_utf8(output.stdout)
+            .ok()?
+            .trim()
+            .strip_prefix('v')?,
+    )
+    .ok()
+}
+
+/// Finds the closest version file for the project, returning its path and contents.
+fn find_version_file(project_dir: &Path) -> Result<Option<(PathBuf, String)>> {
+    for dir in project_dir.ancestors() {
+        for file in VERSION_FILES {
+            let path = dir.join(file);
+
+            if path.is_file() {
+                let spec = fs::read_to_string(&path)
+                    .context(format!("Could not read {}", path.display()))?;
+
+                // Only the first line matters, the rest may be comments
+                let spec = spec.lines().next().unwrap_or_default().trim().to_string();
+
+                return Ok(Some((path, spec)));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Gets `engines.node` from the project's `package.json`, if it's set.
+fn engines_node(project_dir: &Path) -> Result<Option<String>> {
+    let Ok(package_json) = fs::read_to_string(project_dir.join("package.json")) else {
+        return Ok(None);
+    };
+
+    let package_json: Value =
+        serde_json::from_str(&package_json).context("Could not parse the `package.json` file!")?;
+
+    Ok(package_json["engines"]["node"].as_str().map(String::from))
+}
+
+/// Parses a single npm-style range (without `||`), where comparators are separated by spaces instead of commas.
+fn parse_npm_range(range: &str) -> Result<VersionReq> {
+    let range = range.trim();
+
+    // Hyphen ranges, like `18 - 20`
+    if let Some((low, high)) = range.split_once(" - ") {
+        let (low, high) = (low.trim(), high.trim());
+
+        return VersionReq::parse(&format!(
+            ">={}, <={}",
+            low.strip_prefix('v').unwrap_or(low),
+            high.strip_prefix('v').unwrap_or(high)
+        ))
+        .context(format!("Invalid version range `{}`", range));
+    }
+
+    let mut comparators: Vec<String> = vec![];
+
+    for token in range.split_whitespace() {
+        // Join operators separated from their version, like `>= 18`
+        match comparators.last_mut() {
+            Some(last) if last.chars().all(|c| "<>=~^".contains(c)) => last.push_str(token),
+            _ => comparators.push(token.to_string()),
+        }
+    }
+
+    if comparators.is_empty() {
+        return Ok(VersionReq::STAR);
+    }
+
+    let comparators: Vec<String> = comparators
+        .iter()
+        .map(|comparator| normalize_comparator(comparator))
+        .collect();
+
+    VersionReq::parse(&comparators.join(", ")).context(format!("Invalid version range `{}`", range))
+}
+
+/// Rewrites a single npm comparator the way the `semver` crate reads it. It doesn't take a leading `v`, and it reads a
+/// bare version as a caret range, where npm reads it as exactly that version, or as any version starting with it if
+/// it's partial (`18.2` is `~18.2`).
+fn normalize_comparator(comparator: &str) -> String {
+    let (op, version) = comparator.split_at(
+        comparator
+            .find(|c| !"<>=~^".contains(c))
+            .unwrap_or(comparator.len()),
+    );
+    let version = version.strip_prefix('v').unwrap_or(version);
+
+    let parts: Vec<&str> = version.split('.').collect();
+
+    let op = match op {
+        // Wildcards, like `22.x`, already mean the same to both
+        "" if parts.iter().any(|part| matches!(*part, "x" | "X" | "*")) => "",
+        "" if parts.len() < 3 => "~",
+        "" => "=",
+        op => op,
+    };
+
+    format!("{}{}", op, version)
+}
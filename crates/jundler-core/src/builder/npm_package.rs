@@ -0,0 +1,133 @@
+use anyhow::{anyhow, Context, Result};
+use flate2::read::GzDecoder;
+use serde_json::{json, Value};
+use std::fs::{self, File};
+use std::path::{Component, Path};
+
+/// The fields of a published package's `package.json` that are dropped before building it. Its development
+/// dependencies and lifecycle scripts are for working on the package itself, not for running it.
+const DROPPED_FIELDS: &[&str] = &["devDependencies", "scripts"];
+
+/// Unpacks a package tarball from `npm pack` into `project_dir`, without the `package/` directory everything in it
+/// is under.
+pub fn unpack(tarball: &Path, project_dir: &Path) -> Result<()> {
+    let mut archive = tar::Archive::new(GzDecoder::new(
+        File::open(tarball).context(format!("Could not open {}", tarball.display()))?,
+    ));
+
+    for entry in archive
+        .entries()
+        .context("Could not read the package tarball!")?
+    {
+        let mut entry = entry.context("Could not read the package tarball!")?;
+        let path = entry.path()?.into_owned();
+
+        // Skip the top-level directory, and anything that would end up outside the project
+        let relative: std::path::PathBuf = path.components().skip(1).collect();
+
+        if relative.as_os_str().is_empty()
+            || relative
+                .components()
+                .any(|component| !matches!(component, Component::Normal(_)))
+        {
+            continue;
+        }
+
+        let dest = project_dir.join(&relative);
+
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        entry
+            .unpack(&dest)
+            .context(format!("Could not unpack {}", relative.display()))?;
+    }
+
+    Ok(())
+}
+
+/// Gets the name and path of the command a package provides, from the `bin` field of its `package.json`. If it
+/// provides more than one, the one named after the package is used.
+pub fn resolve_bin(package_json: &Value) -> Result<(String, String)> {
+    let name = package_json["name"]
+        .as_str()
+        .ok_or_else(|| anyhow!("The package has no name!"))?;
+
+    // The command of a scoped package is named without the scope
+    let unscoped_name = name.rsplit('/').next().unwrap_or(name);
+
+    match &package_json["bin"] {
+        Value::String(path) => Ok((unscoped_name.to_string(), path.clone())),
+
+        Value::Object(bins) => {
+            let (bin_name, path) = match bins.get(unscoped_name) {
+                Some(path) => (unscoped_name, path),
+                None if bins.len() == 1 => bins
+                    .iter()
+                    .next()
+                    .map(|(bin_name, path)| (bin_name.as_str(), path))
+                    .expect("There should be exactly one command!"),
+                None => {
+                    return Err(anyhow!(
+                    "{} provides more than one command ({}), and none of them are named after it!",
+                    name,
+                    bins.keys().cloned().collect::<Vec<_>>().join(", ")
+                ))
+                }
+            };
+
+            let path = path
+                .as_str()
+                .ok_or_else(|| anyhow!("Invalid `bin` entry for {} in {}", bin_name, name))?;
+
+            Ok((bin_name.to_string(), path.to_string()))
+        }
+
+        _ => Err(anyhow!("{} doesn't provide a command (`bin`)!", name)),
+    }
+}
+
+/// Turns an unpacked package into a project that builds its command: the `package.json` is named after the
+/// command, with the command as its entrypoint, and a `sea-config.json` is added. Returns the name of the command.
+pub fn prepare_project(project_dir: &Path) -> Result<String> {
+    let package_json_path = project_dir.join("package.json");
+
+    let mut package_json: Value = serde_json::from_str(
+        &fs::read_to_string(&package_json_path)
+            .context("Could not read the package's `package.json`!")?,
+    )
+    .context("Could not parse the package's `package.json`!")?;
+
+    let (bin_name, bin_path) = resolve_bin(&package_json)?;
+    let bin_path = bin_path.trim_start_matches("./").to_string();
+
+    if let Value::Object(fields) = &mut package_json {
+        for field in DROPPED_FIELDS {
+            fields.remove(*field);
+        }
+
+        fields.insert("name".to_string(), json!(bin_name));
+        fields.insert("main".to_string(), json!(bin_path));
+    }
+
+    fs::write(
+        &package_json_path,
+        serde_json::to_string_pretty(&package_json)?,
+    )
+    .context("Could not write the package's `package.json`!")?;
+
+    let sea_config = json!({
+        "main": bin_path,
+        "output": "sea-prep.blob",
+        "disableExperimentalSEAWarning": true,
+    });
+
+    fs::write(
+        project_dir.join("sea-config.json"),
+        serde_json::to_string_pretty(&sea_config)?,
+    )
+    .context("Could not write the package's `sea-config.json`!")?;
+
+    Ok(bin_name)
+}
@@ -0,0 +1,165 @@
+//! The npm configuration (`.npmrc`) that installs in the build directory use, so that private registries and their
+//! auth tokens work like they do in the project. The project's own `.npmrc` is copied with it, and if it doesn't have
+//! one, the nearest one above it (i.e. of the workspace it's in) is used instead. The user's `.npmrc` (or the one given
+//! with `--npmrc`, i.e. in CI) is passed on explicitly, as `NPM_CONFIG_USERCONFIG` doesn't survive the sanitized
+//! environment. Environment variables that these files interpolate, like `${NPM_TOKEN}`, are passed through too.
+
+use super::environment::SanitizedEnv;
+use anyhow::{anyhow, Context, Result};
+use log::debug;
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The name of npm's configuration file, which pnpm, Yarn 1, and Bun read too.
+pub const NPMRC_FILE_NAME: &str = ".npmrc";
+
+/// The npm configuration of a project's build.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NpmConfig {
+    /// The `.npmrc` that the project inherits from a directory above it, if it doesn't have its own.
+    pub inherited: Option<PathBuf>,
+
+    /// The user-level `.npmrc`: the one given with `--npmrc`, or the user's own, if there is one.
+    pub userconfig: Option<PathBuf>,
+
+    /// The environment variables that the project's and user's `.npmrc` interpolate.
+    pub pass_env: Vec<String>,
+}
+
+impl NpmConfig {
+    /// Finds the npm configuration of the project at `project_dir`, using `npmrc` as the user's `.npmrc` if it's
+    /// given.
+    pub fn find(project_dir: &Path, npmrc: Option<&Path>) -> Result<NpmConfig> {
+        let home = env::var_os("HOME")
+            .or_else(|| env::var_os("USERPROFILE"))
+            .map(PathBuf::from);
+
+        // The user's `.npmrc` isn't a project's, even if the project is in their home directory
+        let inherited = match project_dir.join(NPMRC_FILE_NAME).is_file() {
+            true => None,
+            false => project_dir
+                .ancestors()
+                .skip(1)
+                .take_while(|dir| Some(*dir) != home.as_deref())
+                .map(|dir| dir.join(NPMRC_FILE_NAME))
+                .find(|path| path.is_file()),
+        };
+
+        let userconfig = match npmrc {
+            Some(npmrc) if npmrc.is_file() => Some(npmrc.to_path_buf()),
+            Some(npmrc) => {
+                return Err(anyhow!(
+                    "The npm configuration at {} doesn't exist!",
+                    npmrc.display()
+                ))
+            }
+            None => env::var_os("NPM_CONFIG_USERCONFIG")
+                .or_else(|| env::var_os("npm_config_userconfig"))
+                .map(PathBuf::from)
+                .or_else(|| home.map(|home| home.join(NPMRC_FILE_NAME)))
+                .filter(|path| path.is_file()),
+        };
+
+        let mut pass_env = vec![];
+
+        for path in [
+            Some(project_dir.join(NPMRC_FILE_NAME)),
+            inherited.clone(),
+            userconfig.clone(),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|path| path.is_file())
+        {
+            let contents = fs::read_to_string(&path).context(format!(
+                "Error reading the npm configuration at {}",
+                path.display()
+            ))?;
+
+            for var in interpolated_vars(&contents) {
+                if !pass_env.contains(&var) {
+                    pass_env.push(var);
+                }
+            }
+        }
+
+        debug!(
+            "npm configuration: inherited {:?}, user {:?}, passing {:?}",
+            inherited, userconfig, pass_env
+        );
+
+        Ok(NpmConfig {
+            inherited,
+            userconfig,
+            pass_env,
+        })
+    }
+
+    /// Copies the inherited `.npmrc` (if there is one) into the copy of the project at `project_dir`, unless it has
+    /// one already.
+    pub fn copy_into(&self, project_dir: &Path) -> Result<()> {
+        let dest = project_dir.join(NPMRC_FILE_NAME);
+
+        match &self.inherited {
+            Some(inherited) if !dest.exists() => {
+                fs::copy(inherited, &dest).map(|_| ()).context(format!(
+                    "Error copying the npm configuration at {} into the build directory",
+                    inherited.display()
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Has `cmd` use this configuration, and sanitizes its environment like `sanitized_env` does, but keeping the
+    /// variables the configuration interpolates as well as those matched by `pass_env`.
+    pub fn apply<'a>(&self, cmd: &'a mut Command, pass_env: &[String]) -> &'a mut Command {
+        self.apply_from(cmd, env::vars_os(), pass_env)
+    }
+
+    /// Like `apply`, but sanitizes the variables of `vars` instead of jundler's environment (see
+    /// `SanitizedEnv::sanitized_env_from`).
+    pub fn apply_from<'a>(
+        &self,
+        cmd: &'a mut Command,
+        vars: impl IntoIterator<Item = (OsString, OsString)>,
+        pass_env: &[String],
+    ) -> &'a mut Command {
+        if let Some(userconfig) = &self.userconfig {
+            cmd.env("npm_config_userconfig", userconfig);
+        }
+
+        cmd.sanitized_env_from(vars, &[pass_env, &self.pass_env].concat())
+    }
+}
+
+/// Gets the names of the environment variables that an `.npmrc` interpolates, like `${NPM_TOKEN}` (or `${NPM_TOKEN?}`,
+/// which npm leaves be if it isn't set), in the order they first appear.
+pub fn interpolated_vars(contents: &str) -> Vec<String> {
+    let mut vars: Vec<String> = vec![];
+    let mut rest = contents;
+
+    while let Some(start) = rest.find("${") {
+        rest = &rest[start + 2..];
+
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+
+        let name = rest[..end].trim_end_matches('?');
+
+        if !name.is_empty()
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && !vars.iter().any(|var| var == name)
+        {
+            vars.push(name.to_string());
+        }
+
+        rest = &rest[end + 1..];
+    }
+
+    vars
+}
@@ -0,0 +1,104 @@
+use super::platforms::{Arch, Os};
+use crate::js_config::PackageConfig;
+use anyhow::{anyhow, Result};
+
+/// The template built binaries are named with by default, which is just the project's name.
+pub const DEFAULT_TEMPLATE: &str = "{name}{ext}";
+
+/// The placeholders that can be used in an output name template.
+const PLACEHOLDERS: &[&str] = &["name", "version", "os", "arch", "ext"];
+
+/// Gets the name of the command a project builds, i.e. `mytool`, or `mytool.exe` on Windows. This is what the binary
+/// is called inside archives, packages, and installers, whatever the built binary itself is named.
+pub fn command_name(package_config: &PackageConfig, os: Os) -> String {
+    format!("{}{}", package_config.name, extension(os))
+}
+
+/// Names a built binary from a template, i.e. `{name}-{version}-{os}-{arch}{ext}`, which gives names like
+/// `mytool-1.2.3-linux-x64` and `mytool-1.2.3-win-x64.exe`. `arch` is `None` for universal macOS binaries, which are
+/// named `universal`.
+pub fn render(
+    template: &str,
+    package_config: &PackageConfig,
+    os: Os,
+    arch: Option<Arch>,
+) -> Result<String> {
+    let mut name = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        name += &rest[..start];
+
+        let end = rest[start..]
+            .find('}')
+            .map(|end| start + end)
+            .ok_or_else(|| anyhow!("Unclosed `{{` in output name template {}", template))?;
+
+        name += &match &rest[start + 1..end] {
+            "name" => package_config.name.clone(),
+            "version" => package_config
+                .version
+                .as_deref()
+                .map(|version| version.trim_start_matches('v').to_string())
+                .ok_or_else(|| {
+                    anyhow!(
+                        "The output name template uses `{{version}}`, but the project has no version! Set `version` in `package.json`."
+                    )
+                })?,
+            "os" => os.to_string(),
+            "arch" => arch.map_or("universal".to_string(), |arch| arch.to_string()),
+            "ext" => extension(os).to_string(),
+            placeholder => {
+                return Err(anyhow!(
+                    "Unknown placeholder `{{{}}}` in output name template {}! It can use {}.",
+                    placeholder,
+                    template,
+                    PLACEHOLDERS
+                        .iter()
+                        .map(|placeholder| format!("`{{{}}}`", placeholder))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))
+            }
+        };
+
+        rest = &rest[end + 1..];
+    }
+
+    name += rest;
+
+    if name.is_empty() || name.contains(['/', '\\']) {
+        return Err(anyhow!(
+            "Invalid output name {} from template {}! It has to be a file name, not a path.",
+            name,
+            template
+        ));
+    }
+
+    Ok(name)
+}
+
+/// Gets a glob that matches every binary a template could name for the project, by putting the project's name in, and
+/// a wildcard for everything else. Used to keep previously built binaries out of the build.
+pub fn glob(template: &str, package_config: &PackageConfig) -> String {
+    let mut glob = template.replace("{name}", &package_config.name);
+
+    for placeholder in PLACEHOLDERS {
+        glob = glob.replace(&format!("{{{}}}", placeholder), "*");
+    }
+
+    // Placeholders next to each other, like `{arch}{ext}`, only need one wildcard
+    while glob.contains("**") {
+        glob = glob.replace("**", "*");
+    }
+
+    glob
+}
+
+/// Gets the extension of binaries for an operating system.
+fn extension(os: Os) -> &'static str {
+    match os {
+        Os::Windows => ".exe",
+        _ => "",
+    }
+}
@@ -0,0 +1,259 @@
+use super::native::{node_arch, node_platform};
+use super::node_manager::HostNode;
+use super::platforms::{get_host_arch, get_host_os, Arch, Os};
+use log::debug;
+use serde_json::Value;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A package manager that can install a project's dependencies.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageManager {
+    Npm,
+    Pnpm,
+    Yarn,
+    Bun,
+}
+
+impl fmt::Display for PackageManager {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PackageManager::Npm => write!(f, "npm"),
+            PackageManager::Pnpm => write!(f, "pnpm"),
+            PackageManager::Yarn => write!(f, "yarn"),
+            PackageManager::Bun => write!(f, "bun"),
+        }
+    }
+}
+
+impl PackageManager {
+    /// The lockfile each package manager writes, in the order they are checked.
+    const LOCKFILES: &'static [(&'static str, PackageManager)] = &[
+        ("pnpm-lock.yaml", PackageManager::Pnpm),
+        ("yarn.lock", PackageManager::Yarn),
+        ("bun.lockb", PackageManager::Bun),
+        ("bun.lock", PackageManager::Bun),
+        ("package-lock.json", PackageManager::Npm),
+        ("npm-shrinkwrap.json", PackageManager::Npm),
+    ];
+
+    /// Detects the package manager used by a project from the `packageManager` field of its `package.json` (see
+    /// [`PackageManager::pinned`]), or else its lockfile (see [`PackageManager::find_lockfile`]). Defaults to npm if
+    /// neither is found.
+    pub fn detect(project_dir: &Path) -> PackageManager {
+        if let Some((package_manager, spec)) = Self::pinned(project_dir) {
+            debug!(
+                "Detected {} from `packageManager: {}`",
+                package_manager, spec
+            );
+
+            return package_manager;
+        }
+
+        match Self::find_lockfile(project_dir) {
+            Some((lockfile, package_manager)) => {
+                debug!("Detected {} from {}", package_manager, lockfile.display());
+
+                package_manager
+            }
+
+            None => PackageManager::Npm,
+        }
+    }
+
+    /// Finds the package manager that a project pins with the `packageManager` field of its `package.json`, and the
+    /// whole spec it's pinned to, i.e. `pnpm@9.1.0` (with an optional `+sha512.…` hash). Like the lockfile, the field
+    /// of a workspace lives in the workspace root, so the nearest `package.json` that has it is used (see
+    /// [`PackageManager::search_dirs`]).
+    pub fn pinned(project_dir: &Path) -> Option<(PackageManager, String)> {
+        let spec = Self::search_dirs(project_dir).into_iter().find_map(|dir| {
+            let manifest = fs::read_to_string(dir.join("package.json")).ok()?;
+            let manifest: Value = serde_json::from_str(&manifest).ok()?;
+
+            manifest.get("packageManager")?.as_str().map(str::to_string)
+        })?;
+
+        let package_manager = match spec.split_once('@')?.0 {
+            "npm" => PackageManager::Npm,
+            "pnpm" => PackageManager::Pnpm,
+            "yarn" => PackageManager::Yarn,
+            "bun" => PackageManager::Bun,
+            _ => return None,
+        };
+
+        Some((package_manager, spec))
+    }
+
+    /// Whether Corepack can run the package manager at a pinned version. Bun isn't one of the package managers it
+    /// knows, so pinned versions of Bun aren't enforced.
+    pub fn supports_corepack(&self) -> bool {
+        *self != PackageManager::Bun
+    }
+
+    /// Finds the lockfile of a project, and the package manager that wrote it. The lockfile of a workspace lives in the
+    /// workspace root, so parent directories are searched as well (see [`PackageManager::search_dirs`]).
+    pub fn find_lockfile(project_dir: &Path) -> Option<(PathBuf, PackageManager)> {
+        Self::search_dirs(project_dir).into_iter().find_map(|dir| {
+            Self::LOCKFILES
+                .iter()
+                .map(|(lockfile, package_manager)| (dir.join(lockfile), *package_manager))
+                .find(|(lockfile, _)| lockfile.exists())
+        })
+    }
+
+    /// Copies the lockfile of the workspace that the project at `project_dir` is in to `dest` (the project's copy in
+    /// the build directory), as it's outside of the project, and so isn't copied with it. Does nothing if the project
+    /// has a lockfile of its own. Returns the lockfile that was copied, if any.
+    pub fn copy_workspace_lockfile(project_dir: &Path, dest: &Path) -> io::Result<Option<PathBuf>> {
+        match Self::find_lockfile(project_dir) {
+            Some((lockfile, _)) if lockfile.parent() != Some(project_dir) => {
+                let Some(name) = lockfile.file_name() else {
+                    return Ok(None);
+                };
+
+                debug!("Copying the workspace lockfile at {}", lockfile.display());
+
+                fs::copy(&lockfile, dest.join(name))?;
+
+                Ok(Some(lockfile))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Whether the project at `project_dir` is installed with Yarn 2 or later ("Berry"), which has different flags
+    /// than Yarn 1: from the version it's `pinned` to, or else from its `.yarnrc.yml` (which only Berry reads), or its
+    /// lockfile being in Berry's format.
+    fn is_yarn_berry(project_dir: &Path, pinned: Option<&str>) -> bool {
+        if let Some(version) = pinned.and_then(|spec| spec.strip_prefix("yarn@")) {
+            return !version.starts_with("1.");
+        }
+
+        let is_berry_lockfile = |lockfile: &Path| {
+            fs::read_to_string(lockfile).is_ok_and(|lockfile| lockfile.contains("__metadata:"))
+        };
+
+        Self::search_dirs(project_dir)
+            .into_iter()
+            .any(|dir| dir.join(".yarnrc.yml").exists())
+            || Self::find_lockfile(project_dir).is_some_and(|(lockfile, package_manager)| {
+                package_manager == PackageManager::Yarn && is_berry_lockfile(&lockfile)
+            })
+    }
+
+    /// Gets the directories whose lockfile and `packageManager` apply to a project, nearest first: the project itself,
+    /// and its parents up to the root of the workspace or repository that it's in. Without one, only the project is
+    /// searched, so a stray lockfile in, say, the user's home directory can't change how the project is installed.
+    /// The search never goes into the home directory itself either.
+    fn search_dirs(project_dir: &Path) -> Vec<&Path> {
+        let home = env::var_os("HOME")
+            .or_else(|| env::var_os("USERPROFILE"))
+            .map(PathBuf::from);
+
+        let mut dirs = vec![];
+
+        for dir in project_dir.ancestors() {
+            if !dirs.is_empty() && Some(dir) == home.as_deref() {
+                break;
+            }
+
+            dirs.push(dir);
+
+            if is_workspace_root(dir) {
+                return dirs;
+            }
+        }
+
+        vec![project_dir]
+    }
+
+    /// Gets the name of the package manager's program on `host_os`. On Windows, pnpm and Yarn are installed as `.cmd`
+    /// shims (by npm or corepack), which can only be run by their full name. Bun has a real `.exe`.
+    pub fn program(&self, host_os: Os) -> String {
+        match (self, host_os) {
+            (PackageManager::Pnpm | PackageManager::Yarn, Os::Windows) => format!("{}.cmd", self),
+            _ => self.to_string(),
+        }
+    }
+
+    /// Gets the command to install the dependencies of a project for the given target, so that native dependencies
+    /// get the target's prebuilt addons rather than the host's. The target is passed on through the `npm_config_*`
+    /// environment variables, which are respected by `prebuild-install`, `node-pre-gyp`, `node-gyp`, and friends
+    /// regardless of which package manager is running them. When the target isn't the host, npm, pnpm, and Bun are
+    /// also told to install the optional dependencies for it (with `--os` and `--cpu`), which is how napi-rs and
+    /// similar tools publish their addons. The command runs in `project_dir`, the project's copy in the build directory.
+    ///
+    /// npm is run with the host Node.js and the npm that came with it, so it doesn't need to be installed. The other
+    /// package managers still need to be, but any scripts they run use the host Node.js. If the project `pinned` the
+    /// package manager to a version (see [`PackageManager::pinned`]), it's run through Corepack with the host Node.js
+    /// instead, so that exact version is installed and used, whatever is installed on the machine.
+    ///
+    /// If `frozen` is set, the install fails instead of updating the lockfile, so that the exact same dependencies
+    /// are installed every time. Yarn 2 and later ("Berry") do this with `--immutable`, and ignore Yarn 1's
+    /// `--frozen-lockfile`.
+    pub fn install_command(
+        &self,
+        host_node: &HostNode,
+        project_dir: &Path,
+        target_os: Os,
+        target_arch: Arch,
+        frozen: bool,
+        pinned: Option<&str>,
+    ) -> Command {
+        let mut cmd = match (self, pinned) {
+            (_, Some(spec)) if self.supports_corepack() => {
+                let mut cmd = host_node.corepack();
+
+                // Corepack asks before downloading a package manager, which would hang the build
+                cmd.arg(spec).env("COREPACK_ENABLE_DOWNLOAD_PROMPT", "0");
+                cmd
+            }
+            (PackageManager::Npm, _) => host_node.npm(),
+            _ => host_node.command(self.program(get_host_os())),
+        };
+
+        match (self, frozen) {
+            (_, false) => cmd.arg("install"),
+            (PackageManager::Npm, true) => cmd.arg("ci"),
+            (PackageManager::Yarn, true) if Self::is_yarn_berry(project_dir, pinned) => {
+                cmd.args(["install", "--immutable"])
+            }
+            (_, true) => cmd.args(["install", "--frozen-lockfile"]),
+        };
+
+        cmd.current_dir(project_dir);
+
+        let (platform, arch) = (node_platform(target_os), node_arch(target_arch));
+
+        // `prebuild-install` reads `platform` and `arch`, and `node-pre-gyp` and `node-gyp` read the `target_` ones
+        cmd.env("npm_config_platform", platform)
+            .env("npm_config_arch", arch)
+            .env("npm_config_target_platform", platform)
+            .env("npm_config_target_arch", arch);
+
+        if (target_os, target_arch) != (get_host_os(), get_host_arch())
+            && *self != PackageManager::Yarn
+        {
+            cmd.arg(format!("--os={}", platform))
+                .arg(format!("--cpu={}", arch));
+        }
+
+        cmd
+    }
+}
+
+/// Whether `dir` is the root of a repository or a workspace, which the lockfile of anything inside of it is never above.
+fn is_workspace_root(dir: &Path) -> bool {
+    let has_workspaces = || {
+        fs::read_to_string(dir.join("package.json"))
+            .ok()
+            .and_then(|manifest| serde_json::from_str::<Value>(&manifest).ok())
+            .is_some_and(|manifest| manifest.get("workspaces").is_some())
+    };
+
+    dir.join(".git").exists() || dir.join("pnpm-workspace.yaml").exists() || has_workspaces()
+}
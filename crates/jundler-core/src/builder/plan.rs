@@ -0,0 +1,565 @@
+//! Plans of builds: what a build would do, without doing any of it. Plans are printed by dry runs, and can be written
+//! to a file with `--plan-out`, reviewed, and built with `--plan-in`, which only builds if the build would still do
+//! exactly what the plan says: with the same versions of everything, binaries downloaded from the same URLs and pinned
+//! to the same checksums, the same project inputs, and the same settings.
+
+use super::advisories::AdvisoryPolicy;
+use super::build_cache::BuildCache;
+use super::errors::{Classify, ErrorClass};
+use super::esbuild::{esbuild_archive_url, ESBUILD_VERSION};
+use super::helpers::is_available;
+use super::node_manager::node_archive_url;
+use super::package_manager::PackageManager;
+use super::platforms::{get_host_arch, get_host_os, Arch, Os};
+use super::project_lock::{
+    esbuild_binary_name, node_binary_name, runtime_binary_name, ProjectLock, LOCK_FILE_NAME,
+};
+use super::runtime::Runtime;
+use super::settings::{self, Source};
+use super::{
+    check_options, get_configs, output_excludes, output_name, output_name_template, should_bundle,
+    BuildOptions, Builder,
+};
+use crate::js_config::PackageConfig;
+use crate::ui::messages::*;
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The version of the format of plan files, which changes whenever what plans record does.
+pub const PLAN_FORMAT_VERSION: u32 = 1;
+
+/// A file of the plans of builds, as written with `--plan-out`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanFile {
+    /// The version of the format of the file.
+    pub version: u32,
+
+    /// The plan of every build, one for each project.
+    pub plans: Vec<BuildPlan>,
+}
+
+impl PlanFile {
+    /// Writes `plans` to a plan file at `path`.
+    pub fn write(path: &Path, plans: &[BuildPlan]) -> Result<()> {
+        let file = PlanFile {
+            version: PLAN_FORMAT_VERSION,
+            plans: plans.to_vec(),
+        };
+
+        fs::write(path, serde_json::to_string_pretty(&file)? + "\n").context(format!(
+            "Error writing the build plan to {}",
+            path.display()
+        ))
+    }
+
+    /// Reads the plan file at `path`.
+    pub fn read(path: &Path) -> Result<PlanFile> {
+        let contents = fs::read_to_string(path).context(format!(
+            "Could not read the build plan at {}!",
+            path.display()
+        ))?;
+
+        let file: PlanFile = serde_json::from_str(&contents).context(format!(
+            "Could not parse the build plan at {}!",
+            path.display()
+        ))?;
+
+        if file.version != PLAN_FORMAT_VERSION {
+            return Err(anyhow!(
+                "The build plan at {} is in version {} of the format, but this version of Jundler only builds plans in version {}! Plan the build again with `--plan-out`.",
+                path.display(),
+                file.version,
+                PLAN_FORMAT_VERSION
+            ));
+        }
+
+        Ok(file)
+    }
+
+    /// Checks that `plan` is what the plan file says for its project and target, so it can be built. Fails with every
+    /// way it differs if it isn't, or if the file doesn't have a plan for it.
+    pub fn approve(&self, plan: &BuildPlan) -> Result<()> {
+        let approved = self
+            .plans
+            .iter()
+            .find(|approved| {
+                (&approved.project_dir, &approved.target) == (&plan.project_dir, &plan.target)
+            })
+            .ok_or_else(|| {
+                anyhow!(
+                    "The build plan doesn't have a build of {} for {}!",
+                    plan.project_dir.display(),
+                    plan.target
+                )
+            })?;
+
+        let differences = plan.differences(approved);
+
+        if differences.is_empty() {
+            return Ok(());
+        }
+
+        Err(anyhow!(
+            "The build of {} for {} doesn't match its plan, so it wasn't built:\n{}\nReview the changes, and plan the build again with `--plan-out`.",
+            plan.project_dir.display(),
+            plan.target,
+            differences
+                .iter()
+                .map(|difference| format!("  - {}", difference))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ))
+    }
+}
+
+/// The parts of a plan that depend on the runtime: whether the SEA blob would be reused, what would be downloaded,
+/// which steps would run, and the hash of the project's inputs.
+type PartialPlan = (bool, Vec<String>, Vec<String>, Option<String>);
+
+/// What a build would do, without doing any of it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildPlan {
+    /// The directory of the project.
+    pub project_dir: PathBuf,
+
+    /// The runtime the application would be built with.
+    pub runtime: Runtime,
+
+    /// The version of the runtime the application would be built with.
+    pub runtime_version: String,
+
+    /// The version of Node.js the application would be built with.
+    pub node_version: String,
+
+    /// The platform the binary would be built for, i.e. `linux-x64`, or `darwin-universal`.
+    pub target: String,
+
+    /// Whether the SEA blob from the last build would be reused.
+    pub cached: bool,
+
+    /// Everything that would be downloaded, because it isn't in the cache yet.
+    pub downloads: Vec<String>,
+
+    /// Every step that would run, in order.
+    pub steps: Vec<String>,
+
+    /// Where the built binary would go.
+    pub app_path: PathBuf,
+
+    /// The hash of the project's inputs: every file that would be copied (including its lockfile), and the options.
+    /// Only builds with Node.js have one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inputs_hash: Option<String>,
+
+    /// Where every binary the build uses (Node.js, ESBuild, or another runtime) is downloaded from, whether it's cached
+    /// already or not.
+    pub urls: Vec<String>,
+
+    /// The checksums of the binaries the build uses that are pinned in `jundler.lock`, by name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub checksums: BTreeMap<String, String>,
+
+    /// The effective value of every setting the build uses (see `jundler config show`).
+    pub settings: BTreeMap<String, String>,
+}
+
+impl BuildPlan {
+    /// Describes every way this plan differs from the `approved` one that changes what's built. Whether the SEA blob
+    /// is cached, and what has to be downloaded, depend on the machine rather than the build, so they aren't compared.
+    pub fn differences(&self, approved: &BuildPlan) -> Vec<String> {
+        let mut differences = vec![];
+
+        let mut compare = |what: &str, value: String, approved: String| {
+            if value != approved {
+                differences.push(format!(
+                    "{} is {}, but the plan has {}",
+                    what, value, approved
+                ));
+            }
+        };
+
+        compare(
+            "The runtime",
+            self.runtime.to_string(),
+            approved.runtime.to_string(),
+        );
+        compare(
+            "The runtime's version",
+            self.runtime_version.clone(),
+            approved.runtime_version.clone(),
+        );
+        compare(
+            "The version of Node.js",
+            self.node_version.clone(),
+            approved.node_version.clone(),
+        );
+        compare(
+            "The output",
+            self.app_path.display().to_string(),
+            approved.app_path.display().to_string(),
+        );
+        compare(
+            "The hash of the project's inputs",
+            self.inputs_hash
+                .clone()
+                .unwrap_or_else(|| "none".to_string()),
+            approved
+                .inputs_hash
+                .clone()
+                .unwrap_or_else(|| "none".to_string()),
+        );
+        compare("The URLs", self.urls.join(", "), approved.urls.join(", "));
+
+        // Each name is compared once, whether it's in either plan or both
+        let names: BTreeSet<_> = self
+            .checksums
+            .keys()
+            .chain(approved.checksums.keys())
+            .collect();
+
+        for name in names {
+            compare(
+                &format!("The checksum of {}", name),
+                self.checksums
+                    .get(name)
+                    .cloned()
+                    .unwrap_or_else(|| "unpinned".to_string()),
+                approved
+                    .checksums
+                    .get(name)
+                    .cloned()
+                    .unwrap_or_else(|| "unpinned".to_string()),
+            );
+        }
+
+        let keys: BTreeSet<_> = self
+            .settings
+            .keys()
+            .chain(approved.settings.keys())
+            .collect();
+
+        for key in keys {
+            compare(
+                &format!("`{}`", key),
+                self.settings
+                    .get(key)
+                    .cloned()
+                    .unwrap_or_else(|| "unset".to_string()),
+                approved
+                    .settings
+                    .get(key)
+                    .cloned()
+                    .unwrap_or_else(|| "unset".to_string()),
+            );
+        }
+
+        differences
+    }
+}
+
+impl Builder {
+    /// Works out what building the project would do: what would be downloaded, which steps would run, and where the
+    /// binary would go. Nothing is downloaded or written, and the options are checked just like a real build would.
+    pub fn plan(&self, project_dir: &Path, options: &BuildOptions) -> Result<BuildPlan> {
+        if project_dir.is_file() {
+            return Err(anyhow!(
+                "Dry runs can only plan builds of project directories, not single scripts!"
+            ));
+        }
+
+        let (_, package_config) = get_configs(project_dir).classify(ErrorClass::Config)?;
+
+        check_options(project_dir, &package_config, options).classify(ErrorClass::Config)?;
+
+        if options.frozen
+            && ProjectLock::load(project_dir)
+                .classify(ErrorClass::Config)?
+                .is_none()
+        {
+            return Err(anyhow!(
+                "Frozen builds check the checksums pinned in `{}`, but the project has none! Run `jundler fetch` first.",
+                LOCK_FILE_NAME
+            ))
+            .classify(ErrorClass::Checksum);
+        }
+
+        let (cached, downloads, mut steps, inputs_hash) = if options.runtime == Runtime::Node {
+            self.plan_node_build(project_dir, &package_config, options)?
+        } else {
+            self.plan_runtime_build(options)
+        };
+
+        let binaries = plan_binaries(&package_config, options);
+        let lock = ProjectLock::load(project_dir).classify(ErrorClass::Config)?;
+
+        let checksums = binaries
+            .iter()
+            .filter_map(|(name, _)| {
+                let checksum = lock.as_ref()?.checksums.get(name)?;
+                Some((name.clone(), checksum.clone()))
+            })
+            .collect();
+
+        let settings = settings::settings(project_dir, options, Source::Default, |_| None)
+            .classify(ErrorClass::Config)?
+            .into_iter()
+            .filter_map(|setting| Some((setting.key.to_string(), setting.value?)))
+            .collect();
+
+        let host_os = get_host_os();
+        let target_os = options.target_os;
+
+        let optional_steps = [
+            (options.universal, LIPO_MSG),
+            (
+                target_os == Os::Windows && options.runtime == Runtime::Node,
+                WINDOWS_RESOURCES_MSG,
+            ),
+            (
+                options.sign
+                    && target_os == Os::MacOS
+                    && (host_os == Os::MacOS || is_available("rcodesign")),
+                MACOS_CODESIGN_MSG,
+            ),
+            (options.notarize && target_os == Os::MacOS, NOTARIZE_MSG),
+            (
+                options.sign && (host_os, target_os) == (Os::Windows, Os::Windows),
+                WINDOWS_CODESIGN_MSG,
+            ),
+            (target_os == Os::Linux, GLIBC_MSG),
+            (options.test_exec.is_some(), TEST_EXEC_MSG),
+            (options.msi, MSI_MSG),
+            (options.checksums.is_some(), CHECKSUMS_MSG),
+            (options.archive.is_some(), ARCHIVE_MSG),
+            (options.manifests, MANIFESTS_MSG),
+            (!options.linux_packages.is_empty(), LINUX_PACKAGES_MSG),
+            (options.provenance, PROVENANCE_MSG),
+            (options.signatures.is_some(), SIGNATURES_MSG),
+            (options.docker_image.is_some(), DOCKER_MSG),
+        ];
+
+        steps.extend(
+            optional_steps
+                .into_iter()
+                .filter(|(runs, _)| *runs)
+                .map(|(_, step)| step.to_string()),
+        );
+
+        let app_name = output_name::render(
+            output_name_template(&package_config, options),
+            &package_config,
+            target_os,
+            (!options.universal).then_some(options.target_arch),
+        )?;
+
+        Ok(BuildPlan {
+            project_dir: project_dir.to_path_buf(),
+            runtime: options.runtime,
+            runtime_version: options
+                .runtime
+                .version()
+                .unwrap_or_else(|| options.node_version.clone())
+                .to_string(),
+            node_version: options.node_version.to_string(),
+            target: options.target_name(),
+            cached,
+            downloads,
+            steps,
+            app_path: options
+                .output_dir
+                .as_deref()
+                .unwrap_or(project_dir)
+                .join(app_name),
+            inputs_hash,
+            urls: binaries.into_iter().map(|(_, url)| url).collect(),
+            checksums,
+            settings,
+        })
+    }
+
+    /// Works out whether the SEA blob would be reused, what would be downloaded, and which steps would run up to the
+    /// Node.js binaries being injected, when building with Node.js, along with the hash of the project's inputs.
+    fn plan_node_build(
+        &self,
+        project_dir: &Path,
+        package_config: &PackageConfig,
+        options: &BuildOptions,
+    ) -> Result<PartialPlan> {
+        let excludes = output_excludes(package_config, options);
+        let inputs_hash = BuildCache::inputs_hash(project_dir, &excludes, options)?;
+
+        let cached = !options.force
+            && self
+                .build_cache
+                .get(project_dir, options, &inputs_hash)
+                .is_some();
+
+        let bundled = should_bundle(package_config, options);
+
+        let target_arches = if options.universal {
+            vec![Arch::X64, Arch::Arm64]
+        } else {
+            vec![options.target_arch]
+        };
+
+        // The host binary comes first, as it's needed first
+        let mut binaries = vec![(get_host_os(), get_host_arch())];
+
+        for arch in &target_arches {
+            if !binaries.contains(&(options.target_os, *arch)) {
+                binaries.push((options.target_os, *arch));
+            }
+        }
+
+        let cached_binaries = self.node_manager().cached_binaries();
+
+        let mut downloads: Vec<String> = binaries
+            .into_iter()
+            .filter(|(os, arch)| {
+                !cached_binaries
+                    .iter()
+                    .any(|(version, cached_os, cached_arch, _)| {
+                        (version, cached_os, cached_arch) == (&options.node_version, os, arch)
+                    })
+            })
+            .map(|(os, arch)| format!("Node.js v{} {}-{}", options.node_version, os, arch))
+            .collect();
+
+        if bundled
+            && !cached
+            && self
+                .esbuild
+                .cached_binary()
+                .is_none_or(|(version, _)| version != ESBUILD_VERSION)
+        {
+            downloads.push(format!("ESBuild v{}", ESBUILD_VERSION));
+        }
+
+        let mut steps = vec![];
+
+        if options.node_advisories != AdvisoryPolicy::Off {
+            steps.push(ADVISORIES_MSG.to_string());
+        }
+
+        steps.push(HASH_PROJ_MSG.to_string());
+
+        if cached {
+            steps.push(CACHED_BUILD_MSG.to_string());
+        } else {
+            let package_manager = options
+                .package_manager
+                .unwrap_or_else(|| PackageManager::detect(project_dir));
+
+            steps.extend([
+                COPY_PROJ_MSG.to_string(),
+                HOST_NODE_MSG.to_string(),
+                PREPARE_APP_MSG.to_string(),
+                format!("{} {}", INSTALL_DEPS_MSG, package_manager),
+            ]);
+
+            if options.rebuild_native
+                && (options.target_os, options.target_arch) != (get_host_os(), get_host_arch())
+            {
+                steps.push(REBUILD_NATIVE_MSG.to_string());
+            }
+
+            if bundled {
+                steps.push(BUNDLE_PROJ_MSG.to_string());
+            }
+
+            if options.licenses || options.embed_licenses {
+                steps.push(LICENSES_MSG.to_string());
+            }
+
+            steps.push(GEN_SEA_BLOB_MSG.to_string());
+        }
+
+        for _ in &target_arches {
+            steps.push(TARGET_NODE_MSG.to_string());
+
+            if options.strip {
+                steps.push(STRIP_MSG.to_string());
+            }
+
+            steps.push(INJECT_APP_MSG.to_string());
+        }
+
+        Ok((cached, downloads, steps, Some(inputs_hash)))
+    }
+
+    /// Works out what would be downloaded, and which steps would run up to the binary being compiled, when building
+    /// with another runtime. Nothing is cached between those builds.
+    fn plan_runtime_build(&self, options: &BuildOptions) -> PartialPlan {
+        let runtime = options.runtime;
+
+        let downloads = match (self.runtimes.cached_binary(runtime), runtime.version()) {
+            (None, Some(version)) => vec![format!(
+                "{} v{} {}-{}",
+                runtime,
+                version,
+                get_host_os(),
+                get_host_arch()
+            )],
+            _ => vec![],
+        };
+
+        let steps = vec![
+            COPY_PROJ_MSG.to_string(),
+            format!("{} {}", RUNTIME_BINARY_MSG, runtime),
+            format!("{} {}", INSTALL_DEPS_MSG, runtime.command()),
+            format!("{} {}", RUNTIME_COMPILE_MSG, runtime),
+        ];
+
+        (false, downloads, steps, None)
+    }
+}
+
+/// Gets the name (as it's pinned in `jundler.lock`) and URL of every binary a build uses: Node.js for this machine and
+/// the target (and ESBuild, if it's bundled), or the other runtime for this machine, which compiles for the target.
+fn plan_binaries(package_config: &PackageConfig, options: &BuildOptions) -> Vec<(String, String)> {
+    let (host_os, host_arch) = (get_host_os(), get_host_arch());
+
+    if let Some(version) = options.runtime.version() {
+        return vec![(
+            runtime_binary_name(options.runtime, &version, host_os, host_arch),
+            options.runtime.archive_url(&version, host_os, host_arch),
+        )];
+    }
+
+    let target_arches = if options.universal {
+        vec![Arch::X64, Arch::Arm64]
+    } else {
+        vec![options.target_arch]
+    };
+
+    // The host binary comes first, as it's needed first
+    let mut platforms = vec![(host_os, host_arch)];
+
+    for arch in target_arches {
+        if !platforms.contains(&(options.target_os, arch)) {
+            platforms.push((options.target_os, arch));
+        }
+    }
+
+    let mut binaries: Vec<(String, String)> = platforms
+        .into_iter()
+        .map(|(os, arch)| {
+            (
+                node_binary_name(&options.node_version, os, arch),
+                node_archive_url(&options.node_version, os, arch),
+            )
+        })
+        .collect();
+
+    if should_bundle(package_config, options) {
+        binaries.push((
+            esbuild_binary_name(&ESBUILD_VERSION, host_os, host_arch),
+            esbuild_archive_url(&ESBUILD_VERSION),
+        ));
+    }
+
+    binaries
+}
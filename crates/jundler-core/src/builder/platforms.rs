@@ -0,0 +1,262 @@
+use core::fmt;
+use std::{
+    env::consts::{ARCH, OS},
+    str::FromStr,
+};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// An operating system that Node.js has binaries for. Named like Node.js names them (`darwin`, `linux`, and `win`),
+/// but parsed from any of the usual spellings, i.e. `macos`, `win32`, or `windows`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Os {
+    MacOS,
+    Linux,
+    Windows,
+}
+
+impl Os {
+    /// Gets the operating system of this machine, or an error if Node.js doesn't support it.
+    pub fn host() -> Result<Os, PlatformError> {
+        match OS {
+            "macos" | "darwin" => Ok(Os::MacOS),
+            "linux" => Ok(Os::Linux),
+            "windows" => Ok(Os::Windows),
+            os => Err(PlatformError::UnsupportedOs(os)),
+        }
+    }
+}
+
+impl FromStr for Os {
+    type Err = PlatformError;
+
+    fn from_str(os: &str) -> Result<Self, Self::Err> {
+        match os.to_lowercase().as_str() {
+            "darwin" | "macos" | "mac-os" | "osx" => Ok(Os::MacOS),
+            "linux" => Ok(Os::Linux),
+            "win" | "win32" | "windows" => Ok(Os::Windows),
+            _ => Err(PlatformError::UnknownOs(os.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Os {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Os::MacOS => write!(f, "darwin"),
+            Os::Linux => write!(f, "linux"),
+            Os::Windows => write!(f, "win"),
+        }
+    }
+}
+
+/// An architecture that Node.js has binaries for. Named like Node.js names them (`x64`, `x86`, and `arm64`), but
+/// parsed from any of the usual spellings, i.e. `x86_64`, `i686`, or `aarch64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Arch {
+    X64,
+    X86,
+    Arm64,
+}
+
+impl Arch {
+    /// Gets the architecture of this machine, or an error if Node.js doesn't support it.
+    pub fn host() -> Result<Arch, PlatformError> {
+        match ARCH {
+            "x86" => Ok(Arch::X86), // "x86" is not a valid value for ARCH, but we'll include it for completeness
+            "x64" | "x86_64" => Ok(Arch::X64),
+            "arm" | "aarch64" => Ok(Arch::Arm64),
+            arch => Err(PlatformError::UnsupportedArch(arch)),
+        }
+    }
+}
+
+impl FromStr for Arch {
+    type Err = PlatformError;
+
+    fn from_str(arch: &str) -> Result<Self, Self::Err> {
+        match arch.to_lowercase().as_str() {
+            "x64" | "x86_64" | "amd64" => Ok(Arch::X64),
+            "x86" | "i386" | "i586" | "i686" | "ia32" => Ok(Arch::X86),
+            "arm64" | "aarch64" => Ok(Arch::Arm64),
+            _ => Err(PlatformError::UnknownArch(arch.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Arch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Arch::X64 => write!(f, "x64"),
+            Arch::X86 => write!(f, "x86"),
+            Arch::Arm64 => write!(f, "arm64"),
+        }
+    }
+}
+
+/// How operating systems are indexed in binary formats. Don't reorder them!
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Os")]
+enum OsIndex {
+    MacOS,
+    Linux,
+    Windows,
+}
+
+/// How architectures are indexed in binary formats. Don't reorder them!
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Arch")]
+enum ArchIndex {
+    X64,
+    X86,
+    Arm64,
+}
+
+/// Implements `Serialize` and `Deserialize` for a platform enum: as its name in human-readable formats like JSON and
+/// TOML, and as the index of its variant in binary formats, which keeps the node manager's lockfile loading.
+macro_rules! serde_by_name {
+    ($platform:ident, $index:ident) => {
+        impl Serialize for $platform {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                if serializer.is_human_readable() {
+                    serializer.collect_str(self)
+                } else {
+                    $index::serialize(self, serializer)
+                }
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $platform {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                if deserializer.is_human_readable() {
+                    String::deserialize(deserializer)?
+                        .parse()
+                        .map_err(de::Error::custom)
+                } else {
+                    $index::deserialize(deserializer)
+                }
+            }
+        }
+    };
+}
+
+serde_by_name!(Os, OsIndex);
+serde_by_name!(Arch, ArchIndex);
+
+/// This machine's platform isn't one that Node.js (and so Jundler) runs on.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PlatformError {
+    /// The operating system isn't supported.
+    #[error("Jundler doesn't run on {0}, only on macOS, Linux, and Windows, as those are the only OSes Node.js has binaries for. Build on one of them instead, with `--target` to build for another.")]
+    UnsupportedOs(&'static str),
+
+    /// The architecture isn't supported.
+    #[error("Jundler doesn't run on {0} machines, only on x64, x86, and arm64 ones, as those are the only architectures Node.js has binaries for. Build on one of them instead, with `--target` to build for another.")]
+    UnsupportedArch(&'static str),
+
+    /// An operating system couldn't be parsed.
+    #[error("`{0}` isn't an OS that Node.js has binaries for! Use `darwin`, `linux`, or `win`.")]
+    UnknownOs(String),
+
+    /// An architecture couldn't be parsed.
+    #[error(
+        "`{0}` isn't an architecture that Node.js has binaries for! Use `x64`, `x86`, or `arm64`."
+    )]
+    UnknownArch(String),
+}
+
+/// Get the host operating system. Panics if it isn't supported, which `Builder::new` checks first with `Os::host`, so
+/// it's only for the builder's own use. Everything else should use `Os::host`.
+pub(crate) fn get_host_os() -> Os {
+    Os::host().expect("The host platform is checked before building!")
+}
+
+/// Get the host architecture. Panics if it isn't supported, which `Builder::new` checks first with `Arch::host`, so
+/// it's only for the builder's own use. Everything else should use `Arch::host`.
+pub(crate) fn get_host_arch() -> Arch {
+    Arch::host().expect("The host platform is checked before building!")
+}
+
+/// A platform to build for, given as one name like `linux-x64`, `darwin-arm64`, or a Rust-style triple like
+/// `x86_64-unknown-linux-gnu`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Target {
+    /// The operating system of the target.
+    pub os: Os,
+
+    /// The architecture of the target.
+    pub arch: Arch,
+}
+
+impl FromStr for Target {
+    type Err = String;
+
+    fn from_str(target: &str) -> Result<Self, Self::Err> {
+        let mut os = None;
+        let mut arch = None;
+
+        // Vendors and ABIs in triples (like `unknown`, `apple`, or `gnu`) don't change what's built, so they're skipped
+        for part in target.split('-') {
+            let duplicate = if let Ok(part) = part.parse() {
+                os.replace(part).is_some()
+            } else if let Ok(part) = part.parse() {
+                arch.replace(part).is_some()
+            } else {
+                false
+            };
+
+            if duplicate {
+                return Err(format!("`{}` has more than one OS or architecture", target));
+            }
+        }
+
+        match (os, arch) {
+            (Some(os), Some(arch)) => Ok(Target { os, arch }),
+            _ => Err(format!(
+                "`{}` needs both an OS and an architecture, i.e. `linux-x64` or `aarch64-apple-darwin`",
+                target
+            )),
+        }
+    }
+}
+
+impl Target {
+    /// Gets the Rust-style target tuple (or "triple") of the target, i.e. `x86_64-unknown-linux-gnu` or
+    /// `aarch64-apple-darwin`, for tools that name platforms that way.
+    pub fn target_tuple(&self) -> String {
+        let arch = match self.arch {
+            Arch::X64 => "x86_64",
+            Arch::X86 => "i686",
+            Arch::Arm64 => "aarch64",
+        };
+
+        let rest = match self.os {
+            Os::MacOS => "apple-darwin",
+            Os::Linux => "unknown-linux-gnu",
+            Os::Windows => "pc-windows-msvc",
+        };
+
+        format!("{}-{}", arch, rest)
+    }
+}
+
+impl TryFrom<String> for Target {
+    type Error = String;
+
+    fn try_from(target: String) -> Result<Self, Self::Error> {
+        target.parse()
+    }
+}
+
+impl From<Target> for String {
+    fn from(target: Target) -> Self {
+        target.to_string()
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.os, self.arch)
+    }
+}
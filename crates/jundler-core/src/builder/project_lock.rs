@@ -0,0 +1,115 @@
+use super::errors::{Classify, ErrorClass};
+use super::helpers::calculate_checksum;
+use super::platforms::{Arch, Os};
+use super::runtime::Runtime;
+use anyhow::{anyhow, Context, Result};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The name of the file in a project that pins the Node.js and ESBuild binaries it's built with.
+pub const LOCK_FILE_NAME: &str = "jundler.lock";
+
+/// The comment at the top of the lock file.
+const HEADER: &str = "# This file is written by Jundler, and pins the checksums of the Node.js and ESBuild binaries\n# the project is built with. Commit it, and build with `--frozen` to check against it.\n\n";
+
+/// The checksums of the binaries a project is built with, pinned in the project's `jundler.lock`. Builds fail if a
+/// binary doesn't match its pinned checksum, and frozen builds also fail if a binary isn't pinned yet.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectLock {
+    /// The version of Node.js the project was last built with, which frozen builds use if the project doesn't pin an
+    /// exact version itself.
+    pub node_version: Option<Version>,
+
+    /// The SHA-256 checksums of the binaries, by name (see `node_binary_name` and `esbuild_binary_name`).
+    pub checksums: BTreeMap<String, String>,
+
+    /// The path to the lock file. This is not (de)serialized.
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl ProjectLock {
+    /// Creates an empty lock file for the project. It isn't written until it's saved.
+    pub fn new(project_dir: &Path) -> ProjectLock {
+        ProjectLock {
+            path: project_dir.join(LOCK_FILE_NAME),
+            ..Default::default()
+        }
+    }
+
+    /// Reads the project's lock file, if it has one.
+    pub fn load(project_dir: &Path) -> Result<Option<ProjectLock>> {
+        let path = project_dir.join(LOCK_FILE_NAME);
+
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let lock: ProjectLock = toml::from_str(
+            &fs::read_to_string(&path)
+                .context(format!("Could not read the `{}` file!", LOCK_FILE_NAME))?,
+        )
+        .context(format!("Could not parse the `{}` file!", LOCK_FILE_NAME))?;
+
+        Ok(Some(ProjectLock { path, ..lock }))
+    }
+
+    /// Writes the lock file to the project.
+    pub fn save(&self) -> Result<()> {
+        fs::write(&self.path, format!("{}{}", HEADER, toml::to_string(self)?))
+            .context(format!("Could not write the `{}` file!", LOCK_FILE_NAME))
+    }
+
+    /// Checks a binary against its pinned checksum, pinning it if it isn't yet (unless `frozen`). Returns whether the
+    /// lock changed, and needs to be saved.
+    pub fn pin(&mut self, name: &str, binary: &Path, frozen: bool) -> Result<bool> {
+        let checksum = hex::encode(calculate_checksum(binary).context(format!(
+            "Error calculating checksum of {}",
+            binary.display()
+        ))?);
+
+        match self.checksums.get(name) {
+            Some(pinned) if *pinned == checksum => Ok(false),
+
+            Some(pinned) => Err(anyhow!(
+                "{} doesn't match the checksum pinned in `{}`! Expected: {}, Actual: {}",
+                name,
+                LOCK_FILE_NAME,
+                pinned,
+                checksum
+            ))
+            .classify(ErrorClass::Checksum),
+
+            None if frozen => Err(anyhow!(
+                "{} isn't pinned in `{}`, and it can't be written in frozen mode! Run `jundler fetch` first.",
+                name,
+                LOCK_FILE_NAME
+            ))
+            .classify(ErrorClass::Checksum),
+
+            None => {
+                self.checksums.insert(name.to_string(), checksum);
+                Ok(true)
+            }
+        }
+    }
+}
+
+/// Gets the name a Node.js binary is pinned by, i.e. `node-v20.11.1-linux-x64`.
+pub fn node_binary_name(version: &Version, os: Os, arch: Arch) -> String {
+    format!("node-v{}-{}-{}", version, os, arch)
+}
+
+/// Gets the name an ESBuild binary is pinned by, i.e. `esbuild-v0.23.0-linux-x64`.
+pub fn esbuild_binary_name(version: &Version, os: Os, arch: Arch) -> String {
+    format!("esbuild-v{}-{}-{}", version, os, arch)
+}
+
+/// Gets the name the binary of another runtime is pinned by, i.e. `bun-v1.1.38-linux-x64`.
+pub fn runtime_binary_name(runtime: Runtime, version: &Version, os: Os, arch: Arch) -> String {
+    format!("{}-v{}-{}-{}", runtime.command(), version, os, arch)
+}
@@ -0,0 +1,381 @@
+//! Provenance of the built outputs: an [in-toto](https://in-toto.io) statement with a
+//! [SLSA provenance](https://slsa.dev/provenance/v1) predicate, which says what was built (every output and its
+//! digest), what it was built from (the project's git commit, the official Node.js archives, and the lockfile), and
+//! what built it (this version of jundler), so that anyone downstream can check how a binary was made.
+
+use super::esbuild::ESBUILD_VERSION;
+use super::helpers::{calculate_checksum, LoggedOutput};
+use super::node_manager::node_archive_url;
+use super::package_manager::PackageManager;
+use super::platforms::{get_host_arch, get_host_os, Arch};
+use super::runtime::Runtime;
+use super::{BuildOptions, Builder};
+use anyhow::{Context, Result};
+use log::debug;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::SystemTime;
+use time::OffsetDateTime;
+
+/// The extension appended to the name of the binary for its provenance.
+pub const PROVENANCE_EXTENSION: &str = "intoto.json";
+
+/// The type of every in-toto statement.
+const STATEMENT_TYPE: &str = "https://in-toto.io/Statement/v1";
+
+/// The type of SLSA provenance predicates.
+const PREDICATE_TYPE: &str = "https://slsa.dev/provenance/v1";
+
+/// An in-toto statement about a set of outputs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Statement {
+    /// The type of the statement, always [`STATEMENT_TYPE`].
+    #[serde(rename = "_type")]
+    pub statement_type: String,
+
+    /// The outputs the statement is about.
+    pub subject: Vec<ResourceDescriptor>,
+
+    /// The type of the predicate, always [`PREDICATE_TYPE`].
+    pub predicate_type: String,
+
+    /// How the outputs were built.
+    pub predicate: Provenance,
+}
+
+/// A file, repository, or download, identified by its name or URI and its digests.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ResourceDescriptor {
+    /// The name of the resource, i.e. the file name of an output.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// Where the resource comes from, i.e. the URL of a download.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+
+    /// The digests of the resource, by algorithm, i.e. `sha256` or `gitCommit`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub digest: BTreeMap<String, String>,
+
+    /// Anything else worth knowing about the resource.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<String, Value>,
+}
+
+/// A SLSA provenance predicate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Provenance {
+    /// What was built, and with what.
+    pub build_definition: BuildDefinition,
+
+    /// What built it, and when.
+    pub run_details: RunDetails,
+}
+
+/// The inputs of a build.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildDefinition {
+    /// What kind of build this is, which says how to read the parameters.
+    pub build_type: String,
+
+    /// The options the build was run with.
+    pub external_parameters: BTreeMap<String, Value>,
+
+    /// What the build depended on that wasn't chosen, like the machine it ran on.
+    pub internal_parameters: BTreeMap<String, Value>,
+
+    /// Everything the outputs were built from.
+    pub resolved_dependencies: Vec<ResourceDescriptor>,
+}
+
+/// How a build was run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunDetails {
+    /// What ran the build.
+    pub builder: BuilderInfo,
+
+    /// When the build ran.
+    pub metadata: RunMetadata,
+}
+
+/// The tool that ran a build.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BuilderInfo {
+    /// Identifies the tool.
+    pub id: String,
+
+    /// The versions of the tool and everything it ran the build with, by name.
+    pub version: BTreeMap<String, String>,
+}
+
+/// When a build ran.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunMetadata {
+    /// When the build started, in RFC 3339 format.
+    pub started_on: String,
+
+    /// When the build finished, in RFC 3339 format.
+    pub finished_on: String,
+}
+
+impl Builder {
+    /// Writes the provenance of `subjects` next to the binary at `app_path`, as `<binary>.intoto.json`. Returns the
+    /// path to the provenance.
+    pub(super) fn write_provenance(
+        &self,
+        project_dir: &Path,
+        app_path: &Path,
+        subjects: &[PathBuf],
+        bundled_with_esbuild: bool,
+        options: &BuildOptions,
+        started: SystemTime,
+    ) -> Result<PathBuf> {
+        let statement = self.provenance(
+            project_dir,
+            subjects,
+            bundled_with_esbuild,
+            options,
+            started,
+        )?;
+
+        let mut path = OsString::from(app_path.as_os_str());
+        path.push(".");
+        path.push(PROVENANCE_EXTENSION);
+        let path = PathBuf::from(path);
+
+        fs::write(
+            &path,
+            serde_json::to_string_pretty(&statement).expect("Provenance should always serialize!")
+                + "\n",
+        )
+        .context(format!("Error writing provenance to {}", path.display()))?;
+
+        Ok(path)
+    }
+
+    /// Describes how the outputs in `subjects` were built from the project in `project_dir`.
+    pub(super) fn provenance(
+        &self,
+        project_dir: &Path,
+        subjects: &[PathBuf],
+        bundled_with_esbuild: bool,
+        options: &BuildOptions,
+        started: SystemTime,
+    ) -> Result<Statement> {
+        let subject = subjects
+            .iter()
+            .map(|path| {
+                let checksum = calculate_checksum(path)
+                    .context(format!("Error calculating checksum of {}", path.display()))?;
+
+                Ok(ResourceDescriptor {
+                    name: path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned()),
+                    digest: BTreeMap::from([("sha256".to_string(), hex::encode(checksum))]),
+                    ..Default::default()
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let runtime_version = options
+            .runtime
+            .version()
+            .unwrap_or_else(|| options.node_version.clone());
+
+        let external_parameters = BTreeMap::from([
+            ("target".to_string(), Value::from(options.target_name())),
+            (
+                "runtime".to_string(),
+                Value::from(options.runtime.command()),
+            ),
+            (
+                "runtimeVersion".to_string(),
+                Value::from(runtime_version.to_string()),
+            ),
+            ("bundle".to_string(), Value::from(options.bundle)),
+            (
+                "reproducible".to_string(),
+                Value::from(options.reproducible),
+            ),
+        ]);
+
+        let internal_parameters = BTreeMap::from([(
+            "host".to_string(),
+            Value::from(format!("{}-{}", get_host_os(), get_host_arch())),
+        )]);
+
+        let mut version =
+            BTreeMap::from([("jundler".to_string(), env!("CARGO_PKG_VERSION").to_string())]);
+
+        if bundled_with_esbuild {
+            version.insert("esbuild".to_string(), ESBUILD_VERSION.to_string());
+        }
+
+        Ok(Statement {
+            statement_type: STATEMENT_TYPE.to_string(),
+            subject,
+            predicate_type: PREDICATE_TYPE.to_string(),
+            predicate: Provenance {
+                build_definition: BuildDefinition {
+                    build_type: concat!(env!("CARGO_PKG_REPOSITORY"), "/provenance/v1").to_string(),
+                    external_parameters,
+                    internal_parameters,
+                    resolved_dependencies: self.resolved_dependencies(project_dir, options)?,
+                },
+                run_details: RunDetails {
+                    builder: BuilderInfo {
+                        id: env!("CARGO_PKG_REPOSITORY").to_string(),
+                        version,
+                    },
+                    metadata: RunMetadata {
+                        started_on: timestamp(started),
+                        finished_on: timestamp(SystemTime::now()),
+                    },
+                },
+            },
+        })
+    }
+
+    /// Gets everything a build was made from: the git commit of the project, the archive of the runtime for every
+    /// architecture in the binary, and the project's lockfile.
+    fn resolved_dependencies(
+        &self,
+        project_dir: &Path,
+        options: &BuildOptions,
+    ) -> Result<Vec<ResourceDescriptor>> {
+        let mut dependencies: Vec<ResourceDescriptor> =
+            git_source(project_dir).into_iter().collect();
+
+        let arches = if options.universal {
+            vec![Arch::X64, Arch::Arm64]
+        } else {
+            vec![options.target_arch]
+        };
+
+        for arch in arches {
+            dependencies.push(match options.runtime {
+                Runtime::Node => {
+                    let digest = self.node_manager().archive_digest(
+                        &options.node_version,
+                        options.target_os,
+                        arch,
+                    );
+
+                    ResourceDescriptor {
+                        uri: Some(node_archive_url(
+                            &options.node_version,
+                            options.target_os,
+                            arch,
+                        )),
+                        digest: digest
+                            .map(|digest| ("sha256".to_string(), hex::encode(digest)))
+                            .into_iter()
+                            .collect(),
+                        ..Default::default()
+                    }
+                }
+
+                // Other runtimes check their archives when they're downloaded, but don't keep their digests
+                runtime => ResourceDescriptor {
+                    uri: Some(runtime.archive_url(
+                        &runtime.version().expect("Other runtimes have a version!"),
+                        options.target_os,
+                        arch,
+                    )),
+                    ..Default::default()
+                },
+            });
+        }
+
+        if let Some((lockfile, _)) = PackageManager::find_lockfile(project_dir) {
+            let checksum = calculate_checksum(&lockfile).context(format!(
+                "Error calculating checksum of {}",
+                lockfile.display()
+            ))?;
+
+            dependencies.push(ResourceDescriptor {
+                name: lockfile
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned()),
+                digest: BTreeMap::from([("sha256".to_string(), hex::encode(checksum))]),
+                ..Default::default()
+            });
+        }
+
+        Ok(dependencies)
+    }
+}
+
+/// Describes the git commit that the project in `project_dir` is checked out at, and the remote it came from. Projects
+/// with uncommitted changes are annotated as `dirty`. `None` if the project isn't in a git repository, or git isn't
+/// installed.
+fn git_source(project_dir: &Path) -> Option<ResourceDescriptor> {
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .current_dir(project_dir)
+            .args(args)
+            .logged_output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+
+    let Some(commit) = git(&["rev-parse", "HEAD"]) else {
+        debug!("{} isn't in a git repository", project_dir.display());
+        return None;
+    };
+
+    let remote = git(&["config", "--get", "remote.origin.url"]).filter(|url| !url.is_empty());
+    let dirty = git(&["status", "--porcelain"]).is_some_and(|status| !status.is_empty());
+
+    Some(ResourceDescriptor {
+        name: remote.is_none().then(|| "source".to_string()),
+        uri: remote.map(|url| format!("git+{}", without_credentials(&url))),
+        digest: BTreeMap::from([("gitCommit".to_string(), commit)]),
+        annotations: if dirty {
+            BTreeMap::from([("dirty".to_string(), Value::Bool(true))])
+        } else {
+            BTreeMap::new()
+        },
+    })
+}
+
+/// Removes any credentials from a URL, like the token in `https://token@github.com/me/app.git`, so they aren't
+/// published with the provenance.
+fn without_credentials(url: &str) -> String {
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return url.to_string();
+    };
+
+    let host_end = rest.find('/').unwrap_or(rest.len());
+
+    match rest[..host_end].rfind('@') {
+        Some(at) => format!("{}://{}", scheme, &rest[at + 1..]),
+        None => url.to_string(),
+    }
+}
+
+/// Formats a time in RFC 3339 format, in UTC.
+fn timestamp(time: SystemTime) -> String {
+    let time = OffsetDateTime::from(time);
+
+    format!(
+        "{}T{:02}:{:02}:{:02}Z",
+        time.date(),
+        time.hour(),
+        time.minute(),
+        time.second()
+    )
+}
@@ -0,0 +1,211 @@
+use super::size::SizeBreakdown;
+use super::BuildOutput;
+use crate::ui::Recording;
+use anyhow::{Context, Result};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// The name of the file in the build directory that lists what ESBuild put in the bundle.
+pub const BUNDLE_CONTENTS_FILE_NAME: &str = "jundler-bundle.json";
+
+/// A machine-readable report of a run of jundler, for CI pipelines and other tools.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildReport {
+    /// The version of jundler that built everything.
+    pub jundler_version: String,
+
+    /// Every build that was done, one per target.
+    pub builds: Vec<TargetReport>,
+
+    /// Every step that was completed, in order.
+    pub steps: Vec<StepReport>,
+
+    /// Every warning that was shown, in order.
+    pub warnings: Vec<String>,
+
+    /// How long everything took, in milliseconds.
+    pub duration_ms: u128,
+}
+
+/// A report of a single build for a target.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetReport {
+    /// The operating system that was built for.
+    pub os: String,
+
+    /// The architecture that was built for, or `universal` for universal macOS binaries.
+    pub arch: String,
+
+    /// The runtime the binary was built with: `node`, `bun`, or `deno`.
+    pub runtime: String,
+
+    /// The version of the runtime that the application was bundled with.
+    pub runtime_version: String,
+
+    /// The version of Node.js that the application was bundled with.
+    pub node_version: String,
+
+    /// The version of ESBuild that bundled the application, if it was bundled.
+    pub esbuild_version: Option<String>,
+
+    /// Whether the SEA blob was reused from a previous build.
+    pub cached: bool,
+
+    /// Every file the build wrote.
+    pub outputs: Vec<OutputReport>,
+
+    /// The tag of the container image that was built, if any.
+    pub image: Option<String>,
+
+    /// What ESBuild put in the bundle, if it bundled the application.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bundle: Option<BundleContents>,
+
+    /// The oldest glibc the binary runs on, if it was built for Linux.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_glibc: Option<String>,
+
+    /// What the size of the binary is made of, if it was built with Node.js.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<SizeBreakdown>,
+
+    /// How long the build took, in milliseconds.
+    pub duration_ms: u128,
+}
+
+/// What ESBuild put in a bundle, from its metafile. Kept with each build so that builds can be compared with `jundler
+/// diff`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleContents {
+    /// Every file in the bundle, relative to the project, and how many bytes of it ended up in the bundle.
+    pub inputs: BTreeMap<String, u64>,
+
+    /// Every package in the bundle, and the versions of it that were bundled.
+    pub packages: BTreeMap<String, BTreeSet<String>>,
+}
+
+/// A report of a file written by a build.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputReport {
+    /// The path to the file.
+    pub path: PathBuf,
+
+    /// The size of the file, in bytes.
+    pub size: u64,
+
+    /// The hex-encoded SHA256 digest of the file.
+    pub sha256: String,
+}
+
+/// A report of a completed step.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepReport {
+    /// The name of the step.
+    pub name: String,
+
+    /// How long the step took, in milliseconds.
+    pub duration_ms: u128,
+}
+
+impl BuildReport {
+    /// Creates a report from the builds that were done and everything the interface recorded along the way.
+    pub fn new(builds: Vec<TargetReport>, recording: Recording, duration: Duration) -> BuildReport {
+        BuildReport {
+            jundler_version: env!("CARGO_PKG_VERSION").to_string(),
+            builds,
+            steps: recording
+                .steps
+                .into_iter()
+                .map(|step| StepReport {
+                    // Strip the leading emoji from the spinner message
+                    name: step
+                        .message
+                        .split_once(' ')
+                        .map_or(step.message.as_str(), |(_, name)| name)
+                        .to_string(),
+                    duration_ms: step.duration.as_millis(),
+                })
+                .collect(),
+            warnings: recording.warnings,
+            duration_ms: duration.as_millis(),
+        }
+    }
+
+    /// Serializes the report as pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Build reports should always serialize!")
+    }
+
+    /// Writes the report as JSON to `path`.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_json() + "\n")
+            .context(format!("Error writing build report to {}", path.display()))
+    }
+}
+
+impl TargetReport {
+    /// Creates a report of a single build from what it produced.
+    pub fn new(output: &BuildOutput) -> TargetReport {
+        TargetReport {
+            os: output.target_os.to_string(),
+            arch: if output.universal {
+                "universal".to_string()
+            } else {
+                output.target_arch.to_string()
+            },
+            runtime: output.runtime.command().to_string(),
+            runtime_version: output
+                .runtime
+                .version()
+                .unwrap_or_else(|| output.node_version.clone())
+                .to_string(),
+            node_version: output.node_version.to_string(),
+            esbuild_version: output.esbuild_version.as_ref().map(Version::to_string),
+            cached: output.cached,
+            outputs: output
+                .files
+                .iter()
+                .map(|file| OutputReport {
+                    path: file.path.clone(),
+                    size: file.size,
+                    sha256: hex::encode(file.sha256),
+                })
+                .collect(),
+            image: output.image.clone(),
+            bundle: output.bundle.clone(),
+            min_glibc: output.min_glibc.map(|version| version.to_string()),
+            size: output.size.clone(),
+            duration_ms: output.duration.as_millis(),
+        }
+    }
+}
+
+impl BundleContents {
+    /// Reads the contents of a bundle written by [`BundleContents::write`].
+    pub fn read(path: &Path) -> Result<BundleContents> {
+        serde_json::from_str(&fs::read_to_string(path).context(format!(
+            "Error reading bundle contents from {}",
+            path.display()
+        ))?)
+        .context(format!(
+            "Error parsing bundle contents in {}",
+            path.display()
+        ))
+    }
+
+    /// Writes the contents of the bundle as JSON to `path`.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        fs::write(
+            path,
+            serde_json::to_string(self).expect("Bundle contents should always serialize!"),
+        )
+        .context(format!(
+            "Error writing bundle contents to {}",
+            path.display()
+        ))
+    }
+}
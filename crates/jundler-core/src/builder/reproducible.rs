@@ -0,0 +1,49 @@
+use filetime::FileTime;
+use ignore::WalkBuilder;
+use std::env;
+use std::io;
+use std::path::Path;
+
+/// The environment variable for the timestamp to use in reproducible builds. See
+/// https://reproducible-builds.org/specs/source-date-epoch/.
+pub const SOURCE_DATE_EPOCH_VAR: &str = "SOURCE_DATE_EPOCH";
+
+/// The timestamp used for reproducible builds when `SOURCE_DATE_EPOCH` isn't set: 1980-01-01T00:00:00Z, which is the
+/// earliest timestamp that every archive format (i.e. zip) can represent.
+pub const DEFAULT_SOURCE_DATE_EPOCH: i64 = 315_532_800;
+
+/// Options in `sea-config.json` that make Node.js embed V8 caches in the SEA blob, which are not reproducible.
+pub const NON_REPRODUCIBLE_SEA_OPTIONS: &[&str] = &["useCodeCache", "useSnapshot"];
+
+/// Gets the timestamp from `SOURCE_DATE_EPOCH`, if it is set to a valid timestamp.
+pub fn source_date_epoch() -> Option<i64> {
+    env::var(SOURCE_DATE_EPOCH_VAR)
+        .ok()
+        .and_then(|epoch| epoch.trim().parse().ok())
+}
+
+/// Sets the modification time of every file and directory in `dir` (including `dir` itself) to `epoch`, so that
+/// no tool in the build can pick up the time the build happened at.
+pub fn normalize_mtimes(dir: &Path, epoch: i64) -> Result<(), io::Error> {
+    let mtime = FileTime::from_unix_time(epoch, 0);
+
+    // Children are visited after their parents, so set times in reverse, as changing a file changes its parent's mtime.
+    let mut paths = vec![];
+
+    for entry in WalkBuilder::new(dir).standard_filters(false).build() {
+        paths.push(entry.map_err(io::Error::other)?.into_path());
+    }
+
+    for path in paths.iter().rev() {
+        filetime::set_symlink_file_times(path, mtime, mtime)?;
+    }
+
+    Ok(())
+}
+
+/// Sets the modification time of a single file to `epoch`.
+pub fn set_mtime(path: &Path, epoch: i64) -> Result<(), io::Error> {
+    let mtime = FileTime::from_unix_time(epoch, 0);
+
+    filetime::set_file_times(path, mtime, mtime)
+}
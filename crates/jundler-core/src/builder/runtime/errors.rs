@@ -0,0 +1,85 @@
+use super::Runtime;
+use crate::builder::downloader::DownloadError;
+use crate::builder::platforms::{Arch, Os};
+use std::io;
+use std::path::PathBuf;
+
+/// Any errors that can occur when managing the binaries of other runtimes
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// An IO error occurred
+    #[error("An IO error occurred on while {action} {path}: {err}")]
+    Io {
+        /// The source of the error
+        #[source]
+        err: io::Error,
+
+        /// The path that caused the error
+        path: PathBuf,
+
+        /// The action that caused the error. Should be insertable into a string of "...while {action} {path}:"
+        action: String,
+    },
+
+    /// An error occured while trying to download a file
+    #[error("An error occurred while trying to download a file from {url}: {err}")]
+    Download {
+        /// The source of the error
+        #[source]
+        err: DownloadError,
+
+        /// The URL that caused the error
+        url: String,
+    },
+
+    /// The downloaded archive isn't a zip file with the runtime's binary in it
+    #[error("The archive downloaded from {url} doesn't have the binary in it: {reason}")]
+    BadArchive {
+        /// The URL the archive was downloaded from
+        url: String,
+
+        /// What's wrong with the archive
+        reason: String,
+    },
+
+    /// Something would have to change in the cache, but it's frozen
+    #[error("Can't {0} while the cache is frozen! Run `jundler fetch` first.")]
+    Frozen(String),
+
+    /// The runtime has no binaries for the platform
+    #[error("{runtime} doesn't have binaries for {os}-{arch}!")]
+    Unsupported {
+        /// The runtime
+        runtime: Runtime,
+
+        /// The operating system
+        os: Os,
+
+        /// The architecture
+        arch: Arch,
+    },
+
+    /// The checksums published with the release don't list the archive
+    #[error("There's no checksum of {url} in the checksums published with it!")]
+    MissingChecksum {
+        /// The URL of the archive
+        url: String,
+    },
+
+    /// There was a mismatch between the expected checksum and the actual checksum
+    #[error(
+        "Checksum mismatch for {url}! Expected: {}, Actual: {}",
+        hex::encode(expected),
+        hex::encode(actual)
+    )]
+    ChecksumMismatch {
+        /// The URL of the archive
+        url: String,
+
+        /// The expected checksum
+        expected: [u8; 32],
+
+        /// The actual checksum
+        actual: [u8; 32],
+    },
+}
@@ -0,0 +1,378 @@
+pub mod errors;
+mod tests;
+
+pub use errors::Error;
+
+use super::downloader::{Downloader, HttpDownloader};
+use super::node_manager::sumfile_parser::parse_checksum_file;
+use super::platforms::{get_host_arch, get_host_os, Arch, Os, Target};
+use crate::ui::{NoopReporter, Reporter};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::fs;
+use std::io::{self, Cursor};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// The version of Bun that projects are compiled with. Like `ESBUILD_VERSION`, this should be updated along with
+/// jundler.
+pub const BUN_VERSION: Version = Version::new(1, 1, 38);
+
+/// The version of Deno that projects are compiled with. Like `ESBUILD_VERSION`, this should be updated along with
+/// jundler.
+pub const DENO_VERSION: Version = Version::new(2, 1, 4);
+
+/// The runtime that built binaries run the application with.
+#[derive(
+    clap::ValueEnum, Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Runtime {
+    /// Node.js, as a single executable application.
+    #[default]
+    Node,
+
+    /// Bun, compiled with `bun build --compile`.
+    Bun,
+
+    /// Deno, compiled with `deno compile`.
+    Deno,
+}
+
+impl Runtime {
+    /// Gets the version of the runtime that projects are compiled with, or `None` for Node.js, whose version is
+    /// chosen for each build.
+    pub fn version(self) -> Option<Version> {
+        match self {
+            Runtime::Node => None,
+            Runtime::Bun => Some(BUN_VERSION),
+            Runtime::Deno => Some(DENO_VERSION),
+        }
+    }
+
+    /// Whether the runtime has binaries for (and can compile for) `os` and `arch`.
+    pub fn supports(self, os: Os, arch: Arch) -> bool {
+        match self {
+            Runtime::Node => true,
+            Runtime::Bun | Runtime::Deno => {
+                matches!(
+                    (os, arch),
+                    (_, Arch::X64) | (Os::MacOS | Os::Linux, Arch::Arm64)
+                )
+            }
+        }
+    }
+
+    /// Gets the name of the runtime's command, i.e. `bun`.
+    pub fn command(self) -> &'static str {
+        match self {
+            Runtime::Node => "node",
+            Runtime::Bun => "bun",
+            Runtime::Deno => "deno",
+        }
+    }
+
+    /// Gets the name of the runtime's executable on `os`.
+    fn executable(self, os: Os) -> String {
+        if os == Os::Windows {
+            format!("{}.exe", self.command())
+        } else {
+            self.command().to_string()
+        }
+    }
+
+    /// Gets the URL of the runtime's release archive for `os` and `arch`.
+    pub fn archive_url(self, version: &Version, os: Os, arch: Arch) -> String {
+        match self {
+            Runtime::Node => unreachable!("Node.js is downloaded by the node manager!"),
+            Runtime::Bun => format!(
+                "https://github.com/oven-sh/bun/releases/download/bun-v{}/bun-{}.zip",
+                version,
+                self.platform(os, arch)
+            ),
+            Runtime::Deno => format!(
+                "https://github.com/denoland/deno/releases/download/v{}/deno-{}.zip",
+                version,
+                self.platform(os, arch)
+            ),
+        }
+    }
+
+    /// Gets the URL of the checksums published with the runtime's release archive for `os` and `arch`: every
+    /// archive's in `SHASUMS256.txt` for Bun, and just the one in `<archive>.sha256sum` for Deno.
+    fn checksums_url(self, version: &Version, os: Os, arch: Arch) -> String {
+        match self {
+            Runtime::Node => unreachable!("Node.js is downloaded by the node manager!"),
+            Runtime::Bun => format!(
+                "https://github.com/oven-sh/bun/releases/download/bun-v{}/SHASUMS256.txt",
+                version
+            ),
+            Runtime::Deno => format!("{}.sha256sum", self.archive_url(version, os, arch)),
+        }
+    }
+
+    /// Gets how the runtime names a platform in the names of its archives: i.e. `linux-aarch64` for Bun, or
+    /// `aarch64-unknown-linux-gnu` for Deno.
+    fn platform(self, os: Os, arch: Arch) -> String {
+        match self {
+            Runtime::Bun => format!(
+                "{}-{}",
+                bun_os(os),
+                if arch == Arch::Arm64 {
+                    "aarch64"
+                } else {
+                    "x64"
+                }
+            ),
+            _ => self.compile_target(os, arch),
+        }
+    }
+
+    /// Gets the `--target` that the runtime compiles for `os` and `arch` with: i.e. `bun-linux-arm64` for Bun, or
+    /// `aarch64-unknown-linux-gnu` for Deno.
+    pub fn compile_target(self, os: Os, arch: Arch) -> String {
+        match self {
+            Runtime::Node => Target { os, arch }.to_string(),
+            Runtime::Bun => format!("bun-{}-{}", bun_os(os), arch),
+            Runtime::Deno => Target { os, arch }.target_tuple(),
+        }
+    }
+}
+
+/// Gets how Bun names `os`.
+fn bun_os(os: Os) -> &'static str {
+    match os {
+        Os::MacOS => "darwin",
+        Os::Linux => "linux",
+        Os::Windows => "windows",
+    }
+}
+
+impl fmt::Display for Runtime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Runtime::Node => write!(f, "Node.js"),
+            Runtime::Bun => write!(f, "Bun"),
+            Runtime::Deno => write!(f, "Deno"),
+        }
+    }
+}
+
+/// Manages the binaries of the runtimes other than Node.js (which has its own manager): downloads them for this
+/// machine, checks them against the checksums published with them, and caches them, one directory per version.
+pub struct RuntimeManager {
+    /// The directory the binaries are cached in.
+    cache_dir: PathBuf,
+
+    /// Whether the cache is frozen, in which case nothing is downloaded.
+    frozen: bool,
+
+    /// Where to report the progress of downloads.
+    reporter: Arc<dyn Reporter>,
+
+    /// What downloads the binaries.
+    downloader: Arc<dyn Downloader>,
+}
+
+impl RuntimeManager {
+    /// Creates a manager that caches binaries in `cache_dir`, which is created when the first one is downloaded.
+    pub fn new(cache_dir: PathBuf) -> Self {
+        Self {
+            cache_dir,
+            frozen: false,
+            reporter: Arc::new(NoopReporter),
+            downloader: Arc::new(HttpDownloader),
+        }
+    }
+
+    /// Reports the progress of downloads to `reporter`.
+    pub fn with_reporter(self, reporter: Arc<dyn Reporter>) -> Self {
+        Self { reporter, ..self }
+    }
+
+    /// Downloads the binaries with `downloader` from now on.
+    pub fn set_downloader(&mut self, downloader: Arc<dyn Downloader>) {
+        self.downloader = downloader;
+    }
+
+    /// Freezes (or unfreezes) the cache. While it's frozen, a binary that isn't in the cache is an error instead of
+    /// being downloaded.
+    pub fn set_frozen(&mut self, frozen: bool) {
+        self.frozen = frozen;
+    }
+
+    /// Gets the binary of `runtime` for this machine, downloading it if it isn't in the cache yet.
+    pub fn get_binary(&self, runtime: Runtime) -> Result<PathBuf, Error> {
+        match self.cached_binary(runtime) {
+            Some(binary) => Ok(binary),
+            None => self.download(runtime),
+        }
+    }
+
+    /// Gets the binary of `runtime` for this machine in the cache, if it's there.
+    pub fn cached_binary(&self, runtime: Runtime) -> Option<PathBuf> {
+        let binary = self
+            .binary_dir(runtime)
+            .join(runtime.executable(get_host_os()));
+
+        binary.is_file().then_some(binary)
+    }
+
+    /// Removes every binary from the cache.
+    pub fn clean_cache(&self) -> Result<(), Error> {
+        if self.cache_dir.exists() {
+            fs::remove_dir_all(&self.cache_dir).map_err(|err| Error::Io {
+                err,
+                path: self.cache_dir.clone(),
+                action: "deleting runtime cache directory at".to_string(),
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Gets the directory the binary of `runtime` for this machine is cached in, i.e. `bun-v1.1.38-linux-x64`.
+    fn binary_dir(&self, runtime: Runtime) -> PathBuf {
+        let version = runtime
+            .version()
+            .expect("Node.js is managed by the node manager!");
+
+        self.cache_dir.join(format!(
+            "{}-v{}-{}-{}",
+            runtime.command(),
+            version,
+            get_host_os(),
+            get_host_arch()
+        ))
+    }
+
+    /// Downloads the binary of `runtime` for this machine into the cache, and returns its path.
+    fn download(&self, runtime: Runtime) -> Result<PathBuf, Error> {
+        let version = runtime
+            .version()
+            .expect("Node.js is managed by the node manager!");
+        let (os, arch) = (get_host_os(), get_host_arch());
+
+        if self.frozen {
+            return Err(Error::Frozen(format!("download {} v{}", runtime, version)));
+        }
+
+        if !runtime.supports(os, arch) {
+            return Err(Error::Unsupported { runtime, os, arch });
+        }
+
+        let archive_url = runtime.archive_url(&version, os, arch);
+        let checksums_url = runtime.checksums_url(&version, os, arch);
+
+        let checksums = self.fetch(&checksums_url, &NoopReporter)?;
+        let expected = find_checksum(&String::from_utf8_lossy(&checksums), &archive_url)
+            .ok_or_else(|| Error::MissingChecksum {
+                url: archive_url.clone(),
+            })?;
+
+        let archive = self.fetch(&archive_url, &*self.reporter)?;
+        let actual: [u8; 32] = Sha256::digest(&archive).into();
+
+        if actual != expected {
+            return Err(Error::ChecksumMismatch {
+                url: archive_url,
+                expected,
+                actual,
+            });
+        }
+
+        let binary = unpack_binary(&archive, &runtime.executable(os)).map_err(|reason| {
+            Error::BadArchive {
+                url: archive_url.clone(),
+                reason,
+            }
+        })?;
+
+        // Write next to the cached binary first, so a half-written one is never used
+        let binary_dir = self.binary_dir(runtime);
+        let partial_dir = binary_dir.with_extension("partial");
+        let partial_binary = partial_dir.join(runtime.executable(os));
+
+        let io_error = |action: &str, path: &Path| {
+            let (action, path) = (action.to_string(), path.to_path_buf());
+
+            move |err| Error::Io { err, path, action }
+        };
+
+        if partial_dir.exists() {
+            fs::remove_dir_all(&partial_dir)
+                .map_err(io_error("clearing partial download at", &partial_dir))?;
+        }
+
+        fs::create_dir_all(&partial_dir).map_err(io_error(
+            "creating runtime cache directory at",
+            &partial_dir,
+        ))?;
+        fs::write(&partial_binary, binary)
+            .map_err(io_error("writing runtime binary at", &partial_binary))?;
+
+        super::helpers::make_executable(&partial_binary)
+            .map_err(io_error("making binary executable at", &partial_binary))?;
+
+        fs::rename(&partial_dir, &binary_dir).map_err(io_error(
+            "moving runtime binary into the cache at",
+            &binary_dir,
+        ))?;
+
+        Ok(binary_dir.join(runtime.executable(os)))
+    }
+
+    /// Downloads the file at `url`.
+    fn fetch(&self, url: &str, reporter: &dyn Reporter) -> Result<Vec<u8>, Error> {
+        self.downloader
+            .download(url, reporter)
+            .map_err(|err| Error::Download {
+                err,
+                url: url.to_string(),
+            })
+    }
+}
+
+/// Finds the checksum of the archive at `archive_url` in `checksums`: either a checksum file listing every archive
+/// (like Bun's), or a file with just the checksum of the one archive (like Deno's, which are formatted differently on
+/// Windows).
+fn find_checksum(checksums: &str, archive_url: &str) -> Option<[u8; 32]> {
+    let file_name = archive_url.rsplit('/').next().unwrap_or(archive_url);
+
+    if let Ok(entries) = parse_checksum_file(checksums) {
+        if let Some(entry) = entries.iter().find(|entry| entry.name == file_name) {
+            return Some(entry.checksum);
+        }
+    }
+
+    // Otherwise, it's the only checksum in the file
+    checksums.split_whitespace().find_map(|word| {
+        let mut checksum = [0u8; 32];
+
+        hex::decode_to_slice(word.to_lowercase(), &mut checksum)
+            .ok()
+            .map(|()| checksum)
+    })
+}
+
+/// Reads the file named `executable` out of the zip `archive`, wherever it is in it.
+fn unpack_binary(archive: &[u8], executable: &str) -> Result<Vec<u8>, String> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(archive)).map_err(|err| err.to_string())?;
+
+    let index = (0..archive.len())
+        .find(|&index| {
+            archive
+                .name_for_index(index)
+                .is_some_and(|name| name.rsplit('/').next() == Some(executable))
+        })
+        .ok_or_else(|| format!("there's no `{}` in it", executable))?;
+
+    let mut file = archive.by_index(index).map_err(|err| err.to_string())?;
+    let mut binary = vec![];
+
+    io::copy(&mut file, &mut binary).map_err(|err| err.to_string())?;
+
+    Ok(binary)
+}
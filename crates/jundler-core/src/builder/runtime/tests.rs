@@ -0,0 +1,173 @@
+#![cfg(test)]
+
+use super::*;
+use crate::builder::downloader::FileDownloader;
+use assert_fs::TempDir;
+use std::io::Write;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+/// Lays out fake Bun and Deno releases for this machine in `fixtures` like GitHub does, with their checksums, and
+/// returns a downloader that reads from them.
+fn runtime_fixtures(fixtures: &Path) -> Arc<dyn Downloader> {
+    let (os, arch) = (get_host_os(), get_host_arch());
+
+    for (runtime, path_in_archive) in [
+        (
+            Runtime::Bun,
+            format!(
+                "bun-{}/{}",
+                Runtime::Bun.platform(os, arch),
+                Runtime::Bun.executable(os)
+            ),
+        ),
+        (Runtime::Deno, Runtime::Deno.executable(os)),
+    ] {
+        let version = runtime.version().unwrap();
+
+        let mut archive = ZipWriter::new(Cursor::new(Vec::new()));
+        archive
+            .start_file(path_in_archive, SimpleFileOptions::default())
+            .unwrap();
+        write!(archive, "#!/bin/sh\necho {}\n", version).unwrap();
+        let archive = archive.finish().unwrap().into_inner();
+
+        let archive_url = runtime.archive_url(&version, os, arch);
+        let archive_path = FileDownloader::new(fixtures.to_path_buf()).path(&archive_url);
+        let file_name = archive_url.rsplit('/').next().unwrap();
+
+        fs::create_dir_all(archive_path.parent().unwrap()).unwrap();
+        fs::write(&archive_path, &archive).unwrap();
+
+        let checksum = hex::encode(Sha256::digest(&archive));
+        let checksums = match runtime {
+            // Bun lists every archive, so there's another one to skip over
+            Runtime::Bun => format!(
+                "{}  bun-freebsd-riscv.zip\n{}  {}\n",
+                "0".repeat(64),
+                checksum,
+                file_name
+            ),
+            _ => format!("{}  {}\n", checksum, file_name),
+        };
+
+        fs::write(
+            FileDownloader::new(fixtures.to_path_buf())
+                .path(&runtime.checksums_url(&version, os, arch)),
+            checksums,
+        )
+        .unwrap();
+    }
+
+    Arc::new(FileDownloader::new(fixtures.to_path_buf()))
+}
+
+/// Test that Bun and Deno are downloaded, checked, and cached, and that frozen caches don't download them.
+#[test]
+fn download_runtimes() {
+    if !Runtime::Bun.supports(get_host_os(), get_host_arch()) {
+        return;
+    }
+
+    let tmp_dir = TempDir::new().unwrap();
+    let fixtures = tmp_dir.path().join("fixtures");
+
+    let mut runtimes = RuntimeManager::new(tmp_dir.path().join("runtimes"));
+    runtimes.set_downloader(runtime_fixtures(&fixtures));
+
+    runtimes.set_frozen(true);
+    assert!(matches!(
+        runtimes.get_binary(Runtime::Bun),
+        Err(Error::Frozen(_))
+    ));
+    runtimes.set_frozen(false);
+
+    for runtime in [Runtime::Bun, Runtime::Deno] {
+        assert!(runtimes.cached_binary(runtime).is_none());
+
+        let binary = runtimes.get_binary(runtime).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&binary).unwrap(),
+            format!("#!/bin/sh\necho {}\n", runtime.version().unwrap())
+        );
+        assert_eq!(runtimes.cached_binary(runtime), Some(binary));
+    }
+
+    // Cached binaries aren't downloaded again
+    fs::remove_dir_all(&fixtures).unwrap();
+    runtimes.set_frozen(true);
+    assert!(runtimes.get_binary(Runtime::Deno).is_ok());
+
+    runtimes.clean_cache().unwrap();
+    assert!(runtimes.cached_binary(Runtime::Deno).is_none());
+}
+
+/// Test that archives that don't match their published checksums aren't cached.
+#[test]
+fn runtime_checksum_mismatch() {
+    if !Runtime::Deno.supports(get_host_os(), get_host_arch()) {
+        return;
+    }
+
+    let tmp_dir = TempDir::new().unwrap();
+    let fixtures = tmp_dir.path().join("fixtures");
+    let downloader = runtime_fixtures(&fixtures);
+
+    let archive_url = Runtime::Deno.archive_url(&DENO_VERSION, get_host_os(), get_host_arch());
+    fs::write(
+        FileDownloader::new(fixtures.clone()).path(&archive_url),
+        "tampered",
+    )
+    .unwrap();
+
+    let mut runtimes = RuntimeManager::new(tmp_dir.path().join("runtimes"));
+    runtimes.set_downloader(downloader);
+
+    assert!(matches!(
+        runtimes.get_binary(Runtime::Deno),
+        Err(Error::ChecksumMismatch { .. })
+    ));
+    assert!(runtimes.cached_binary(Runtime::Deno).is_none());
+}
+
+/// Test that runtimes are pointed at the right targets, and only support the platforms they have binaries for.
+#[test]
+fn runtime_targets() {
+    assert_eq!(
+        Runtime::Bun.compile_target(Os::Linux, Arch::Arm64),
+        "bun-linux-arm64"
+    );
+    assert_eq!(
+        Runtime::Bun.compile_target(Os::Windows, Arch::X64),
+        "bun-windows-x64"
+    );
+    assert_eq!(
+        Runtime::Deno.compile_target(Os::MacOS, Arch::Arm64),
+        "aarch64-apple-darwin"
+    );
+    assert_eq!(
+        Runtime::Bun.archive_url(&BUN_VERSION, Os::MacOS, Arch::Arm64),
+        format!(
+            "https://github.com/oven-sh/bun/releases/download/bun-v{}/bun-darwin-aarch64.zip",
+            BUN_VERSION
+        )
+    );
+
+    assert!(Runtime::Node.supports(Os::Windows, Arch::X86));
+    assert!(!Runtime::Bun.supports(Os::Windows, Arch::X86));
+    assert!(!Runtime::Deno.supports(Os::Windows, Arch::Arm64));
+
+    // Deno's Windows checksums are formatted by PowerShell
+    let checksum = "A".repeat(64);
+    assert_eq!(
+        find_checksum(
+            &format!(
+                "\r\nAlgorithm : SHA256\r\nHash      : {}\r\nPath      : D:\\deno.zip\r\n",
+                checksum
+            ),
+            "https://example.com/deno.zip"
+        ),
+        Some([0xaa; 32])
+    );
+}
@@ -0,0 +1,52 @@
+use anyhow::{anyhow, Context, Result};
+use serde_json::json;
+use std::fs;
+use std::path::Path;
+
+/// Gets the name of the binary built from a single script, i.e. `tool` for `./scripts/tool.ts`.
+pub fn script_name(script: &Path) -> Result<String> {
+    script
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .filter(|stem| !stem.is_empty())
+        .map(|stem| stem.to_string())
+        .ok_or_else(|| anyhow!("Invalid script name {}", script.display()))
+}
+
+/// Writes a minimal project for a single script to `project_dir`: a copy of the script, and the `package.json` and
+/// `sea-config.json` to build it with. Scripts are always bundled, so they can be TypeScript or ES modules.
+pub fn write_script_project(script: &Path, project_dir: &Path) -> Result<()> {
+    let file_name = script
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| anyhow!("Invalid script name {}", script.display()))?;
+
+    fs::create_dir_all(project_dir).context("Could not create the script's project directory!")?;
+
+    fs::copy(script, project_dir.join(file_name))
+        .context(format!("Could not copy {}", script.display()))?;
+
+    let package_json = json!({
+        "name": script_name(script)?,
+        "private": true,
+        "main": file_name,
+    });
+
+    let sea_config = json!({
+        "main": file_name,
+        "output": "sea-prep.blob",
+        "disableExperimentalSEAWarning": true,
+    });
+
+    fs::write(
+        project_dir.join("package.json"),
+        serde_json::to_string_pretty(&package_json)?,
+    )
+    .context("Could not write the script's `package.json`!")?;
+
+    fs::write(
+        project_dir.join("sea-config.json"),
+        serde_json::to_string_pretty(&sea_config)?,
+    )
+    .context("Could not write the script's `sea-config.json`!")
+}
@@ -0,0 +1,302 @@
+use super::package_manager::PackageManager;
+use super::platforms::Os;
+use super::signatures::SignatureFormat;
+use super::{get_configs, output_name, should_bundle, BuildOptions};
+use crate::js_config::CONFIG_FILE_NAME;
+use anyhow::Result;
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::path::Path;
+
+/// Where the value of a setting came from. Settings are taken from the first of these that has one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// An option on the command line.
+    CommandLine,
+
+    /// An environment variable, with its name.
+    Environment(String),
+
+    /// A key in the project's configuration, and the file it's in: `jundler.toml`, or `package.json`.
+    Config(String, &'static str),
+
+    /// Worked out from the project, i.e. from its `.nvmrc` or lockfile.
+    Project(String),
+
+    /// The default.
+    Default,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Source::CommandLine => write!(f, "command line"),
+            Source::Environment(name) => write!(f, "${}", name),
+            Source::Config(key, file) => write!(f, "`{}` in {}", key, file),
+            Source::Project(source) => write!(f, "{}", source),
+            Source::Default => write!(f, "default"),
+        }
+    }
+}
+
+impl Serialize for Source {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// The effective value of a setting, and where it came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Setting {
+    /// The name of the setting, as a command-line option without the dashes in front, i.e. `node-version`.
+    pub key: &'static str,
+
+    /// The value of the setting, if it has one.
+    pub value: Option<String>,
+
+    /// Where the value came from.
+    pub source: Source,
+}
+
+impl Setting {
+    /// A setting with a value.
+    pub fn new<V: ToString>(key: &'static str, value: V, source: Source) -> Setting {
+        Setting {
+            key,
+            value: Some(value.to_string()),
+            source,
+        }
+    }
+}
+
+/// Works out the effective value of every setting that builds of the project use, and where each came from: the
+/// command line or environment variables, the project's configuration, the project itself, or the defaults. `given`
+/// says where an option (by its ID on the command line, i.e. `node_version`) was given, or `None` if it wasn't.
+pub fn settings<F>(
+    project_dir: &Path,
+    options: &BuildOptions,
+    node_source: Source,
+    given: F,
+) -> Result<Vec<Setting>>
+where
+    F: Fn(&str) -> Option<Source>,
+{
+    let (_, package_config) = get_configs(project_dir)?;
+    let config = &package_config.jundler;
+
+    // `jundler.toml` replaces the `jundler` field of `package.json`
+    let from_config = |key: &str| {
+        if project_dir.join(CONFIG_FILE_NAME).exists() {
+            Source::Config(key.to_string(), CONFIG_FILE_NAME)
+        } else {
+            Source::Config(format!("jundler.{}", key), "package.json")
+        }
+    };
+
+    // Options given on the command line override the project's configuration
+    let layered =
+        |key: &'static str, option: Option<String>, config_key: &str, config: Option<&String>| {
+            match (option, config) {
+                (Some(value), _) => Setting::new(
+                    key,
+                    value,
+                    given(&key.replace('-', "_")).unwrap_or(Source::CommandLine),
+                ),
+                (None, Some(value)) => Setting::new(key, value, from_config(config_key)),
+                (None, None) => Setting {
+                    key,
+                    value: None,
+                    source: Source::Default,
+                },
+            }
+        };
+
+    // Options that are only given on the command line
+    let option = |key: &'static str, value: Option<String>| Setting {
+        key,
+        value,
+        source: given(&key.replace('-', "_")).unwrap_or(Source::Default),
+    };
+
+    let flag = |key: &'static str, value: bool| option(key, Some(value.to_string()));
+
+    let target_source = ["target", "os", "arch"]
+        .into_iter()
+        .find_map(&given)
+        .unwrap_or(Source::Default);
+
+    let bundle = match given("bundle") {
+        Some(source) if options.bundle => Setting::new("bundle", true, source),
+        _ if should_bundle(&package_config, options) => Setting::new(
+            "bundle",
+            true,
+            Source::Project("the project is an ES module or TypeScript".to_string()),
+        ),
+        _ => Setting::new("bundle", false, Source::Default),
+    };
+
+    let package_manager = match (options.package_manager, PackageManager::pinned(project_dir)) {
+        (Some(package_manager), _) => option("package-manager", Some(package_manager.to_string())),
+        (None, Some((_, spec))) => Setting::new(
+            "package-manager",
+            spec,
+            Source::Project("pinned by `packageManager` in package.json".to_string()),
+        ),
+        (None, None) => Setting::new(
+            "package-manager",
+            PackageManager::detect(project_dir),
+            Source::Project("detected from the project's lockfile".to_string()),
+        ),
+    };
+
+    let output_name = match layered(
+        "output-name",
+        options.output_name.clone(),
+        "outputName",
+        config.output_name.as_ref(),
+    ) {
+        Setting { value: None, .. } => Setting::new(
+            "output-name",
+            output_name::DEFAULT_TEMPLATE,
+            Source::Default,
+        ),
+        setting => setting,
+    };
+
+    let reproducible = flag("reproducible", options.reproducible);
+
+    let max_size = match super::size::max_size(options, config) {
+        Ok(Some(bytes)) if options.max_size.is_none() => {
+            Setting::new("max-size", bytes, from_config("maxSize"))
+        }
+        _ => option("max-size", options.max_size.map(|bytes| bytes.to_string())),
+    };
+
+    let (certificate_key, certificate) = match options.target_os {
+        Os::Windows => ("windows.certificate", config.windows.certificate.as_ref()),
+        _ => ("macos.certificate", config.macos.certificate.as_ref()),
+    };
+
+    let (signing_key_key, signing_key) = match options.signatures {
+        Some(SignatureFormat::Minisign) => (
+            "signatures.minisignKey",
+            config.signatures.minisign_key.as_ref(),
+        ),
+        Some(SignatureFormat::Cosign) => (
+            "signatures.cosignKey",
+            config.signatures.cosign_key.as_ref(),
+        ),
+        _ => ("signatures.gpgKey", config.signatures.gpg_key.as_ref()),
+    };
+
+    Ok(vec![
+        Setting::new("node-version", &options.node_version, node_source),
+        Setting::new("target", options.target_name(), target_source),
+        bundle,
+        package_manager,
+        option("copy-strategy", Some(options.copy_strategy.to_string())),
+        output_name,
+        flag("force", options.force),
+        flag("strip", options.strip),
+        flag("universal", options.universal),
+        reproducible,
+        flag("frozen", options.frozen),
+        option(
+            "pass-env",
+            (!options.pass_env.is_empty()).then(|| options.pass_env.join(",")),
+        ),
+        option(
+            "npmrc",
+            options
+                .npmrc
+                .as_ref()
+                .map(|path| path.display().to_string()),
+        ),
+        option("node-advisories", Some(options.node_advisories.to_string())),
+        flag("rebuild-native", options.rebuild_native),
+        option("native-toolchain", options.native_toolchain.clone()),
+        option(
+            "test-exec",
+            options.test_exec.as_ref().map(|args| args.join(" ")),
+        ),
+        max_size,
+        option("max-size-policy", Some(options.max_size_policy.to_string())),
+        option(
+            "checksums",
+            options.checksums.map(|format| format.to_string()),
+        ),
+        flag("licenses", options.licenses),
+        flag("embed-licenses", options.embed_licenses),
+        option("archive", options.archive.map(|format| format.to_string())),
+        flag("manifests", options.manifests),
+        flag("provenance", options.provenance),
+        layered(
+            "release-url",
+            options.release_url.clone(),
+            "release.url",
+            package_config.jundler.release.url.as_ref(),
+        ),
+        Setting::new(
+            "sign",
+            options.sign,
+            given("no_sign").unwrap_or(Source::Default),
+        ),
+        layered(
+            "codesign-identity",
+            options.codesign_identity.clone(),
+            "macos.codesignIdentity",
+            config.macos.codesign_identity.as_ref(),
+        ),
+        flag("notarize", options.notarize),
+        layered(
+            "sign-certificate",
+            options
+                .sign_certificate
+                .as_ref()
+                .map(|path| path.display().to_string()),
+            certificate_key,
+            certificate,
+        ),
+        layered(
+            "sign-thumbprint",
+            options.windows_sign.thumbprint.clone(),
+            "windows.certificateThumbprint",
+            config.windows.certificate_thumbprint.as_ref(),
+        ),
+        layered(
+            "timestamp-url",
+            options.windows_sign.timestamp_url.clone(),
+            "windows.timestampUrl",
+            config.windows.timestamp_url.as_ref(),
+        ),
+        layered(
+            "sign-description",
+            options.windows_sign.description.clone(),
+            "windows.signDescription",
+            config.windows.sign_description.as_ref(),
+        ),
+        option(
+            "signatures",
+            options.signatures.map(|format| format.to_string()),
+        ),
+        layered(
+            "signing-key",
+            options.signing_key.clone(),
+            signing_key_key,
+            signing_key,
+        ),
+        option(
+            "linux-package",
+            (!options.linux_packages.is_empty()).then(|| {
+                options
+                    .linux_packages
+                    .iter()
+                    .map(|format| format.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            }),
+        ),
+        flag("msi", options.msi),
+        option("docker", options.docker_image.clone()),
+    ])
+}
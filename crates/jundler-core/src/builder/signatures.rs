@@ -0,0 +1,208 @@
+use super::helpers::command_line;
+use crate::js_config::SignaturesConfig;
+use anyhow::{anyhow, Context, Result};
+use log::debug;
+use std::env;
+use std::ffi::OsString;
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// The environment variable with the passphrase of the GPG key, unless the project names another one. If it isn't
+/// set, GPG asks its agent for it.
+const GPG_PASSPHRASE_VAR: &str = "JUNDLER_GPG_PASSPHRASE";
+
+/// The environment variable with the password of the minisign secret key, unless the project names another one. If
+/// it isn't set, the key must not be encrypted.
+const MINISIGN_PASSWORD_VAR: &str = "JUNDLER_MINISIGN_PASSWORD";
+
+/// The environment variable that cosign reads the password of its key from.
+const COSIGN_PASSWORD_VAR: &str = "COSIGN_PASSWORD";
+
+/// The tool to write detached signatures of the built outputs with.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureFormat {
+    /// A `<file>.sig` GPG signature, which can be checked with `gpg --verify <file>.sig <file>`.
+    Gpg,
+
+    /// A `<file>.minisig` minisign signature, which can be checked with `minisign -Vm <file> -P <public key>`.
+    Minisign,
+
+    /// A `<file>.sigstore.json` Sigstore bundle written by cosign, which can be checked with `cosign verify-blob
+    /// --bundle <file>.sigstore.json <file>` and either `--key <public key>`, or the `--certificate-identity` and
+    /// `--certificate-oidc-issuer` of a keyless signature. Without a key, cosign signs keylessly with an OIDC identity,
+    /// like the one CI providers give workflows.
+    Cosign,
+}
+
+impl fmt::Display for SignatureFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SignatureFormat::Gpg => write!(f, "gpg"),
+            SignatureFormat::Minisign => write!(f, "minisign"),
+            SignatureFormat::Cosign => write!(f, "cosign"),
+        }
+    }
+}
+
+impl SignatureFormat {
+    /// The extension appended to the name of a signed file for its signature.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SignatureFormat::Gpg => "sig",
+            SignatureFormat::Minisign => "minisig",
+            SignatureFormat::Cosign => "sigstore.json",
+        }
+    }
+
+    /// The tool that writes signatures in this format.
+    pub fn tool(&self) -> &'static str {
+        match self {
+            SignatureFormat::Gpg => "gpg",
+            SignatureFormat::Minisign => "minisign",
+            SignatureFormat::Cosign => "cosign",
+        }
+    }
+}
+
+/// Writes a detached signature of every file in `files` next to it, with the key from `key` (a GPG key ID, the path of a
+/// minisign secret key, or a cosign key) or the project configuration. Without either, GPG uses its default key,
+/// minisign uses `~/.minisign/minisign.key`, and cosign signs keylessly. Returns the paths to the written signatures.
+pub fn write_signatures(
+    files: &[PathBuf],
+    format: SignatureFormat,
+    key: Option<&str>,
+    project_dir: &Path,
+    config: &SignaturesConfig,
+) -> Result<Vec<PathBuf>> {
+    files
+        .iter()
+        .map(|file| sign(file, format, key, project_dir, config))
+        .collect()
+}
+
+/// Writes a detached signature of a single file next to it. Returns the path to the signature.
+fn sign(
+    file: &Path,
+    format: SignatureFormat,
+    key: Option<&str>,
+    project_dir: &Path,
+    config: &SignaturesConfig,
+) -> Result<PathBuf> {
+    let mut signature = OsString::from(file.as_os_str());
+    signature.push(".");
+    signature.push(format.extension());
+    let signature = PathBuf::from(signature);
+
+    let mut sign_cmd = Command::new(format.tool());
+
+    let password = match format {
+        SignatureFormat::Gpg => {
+            sign_cmd.args(["--batch", "--yes", "--detach-sign"]);
+
+            if let Some(key) = key.or(config.gpg_key.as_deref()) {
+                sign_cmd.arg("--local-user").arg(key);
+            }
+
+            let password =
+                env::var(config.password_env.as_deref().unwrap_or(GPG_PASSPHRASE_VAR)).ok();
+
+            // GPG only reads the passphrase from stdin in loopback mode
+            if password.is_some() {
+                sign_cmd.args(["--pinentry-mode", "loopback", "--passphrase-fd", "0"]);
+            }
+
+            sign_cmd.arg("--output").arg(&signature).arg(file);
+
+            password
+        }
+
+        SignatureFormat::Minisign => {
+            sign_cmd.arg("-S");
+
+            if let Some(key) = key {
+                sign_cmd.arg("-s").arg(key);
+            } else if let Some(key) = &config.minisign_key {
+                sign_cmd.arg("-s").arg(project_dir.join(key));
+            }
+
+            sign_cmd.arg("-m").arg(file).arg("-x").arg(&signature);
+
+            env::var(
+                config
+                    .password_env
+                    .as_deref()
+                    .unwrap_or(MINISIGN_PASSWORD_VAR),
+            )
+            .ok()
+        }
+
+        SignatureFormat::Cosign => {
+            // `--yes` agrees to the terms of the transparency log up front, as there's no one to ask
+            sign_cmd.args(["sign-blob", "--yes"]);
+
+            if let Some(key) = key {
+                sign_cmd.arg("--key").arg(key);
+            } else if let Some(key) = &config.cosign_key {
+                // Keys in a KMS (i.e. `awskms://...`) aren't files in the project
+                if key.contains("://") {
+                    sign_cmd.arg("--key").arg(key);
+                } else {
+                    sign_cmd.arg("--key").arg(project_dir.join(key));
+                }
+            }
+
+            sign_cmd.arg("--bundle").arg(&signature).arg(file);
+
+            // cosign reads the password of the key from `COSIGN_PASSWORD` itself, unless the project names another
+            // variable
+            if let Some(password) = config
+                .password_env
+                .as_deref()
+                .and_then(|var| env::var(var).ok())
+            {
+                sign_cmd.env(COSIGN_PASSWORD_VAR, password);
+            }
+
+            None
+        }
+    };
+
+    debug!("Running {}", command_line(&sign_cmd));
+
+    let mut child = sign_cmd
+        .stdin(if password.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context(format!(
+            "Error running `{}`. Is it installed?",
+            format.tool()
+        ))?;
+
+    if let (Some(password), Some(mut stdin)) = (password, child.stdin.take()) {
+        writeln!(stdin, "{}", password)
+            .context(format!("Error passing the password to `{}`", format.tool()))?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .context(format!("Error running `{}`", format.tool()))?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "Error signing {} with {}:\n{}\n{}",
+            file.display(),
+            format.tool(),
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(signature)
+}
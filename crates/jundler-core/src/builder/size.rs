@@ -0,0 +1,368 @@
+//! Breaks the size of a built binary down into what it's made of: the Node.js binary it was built from, and the SEA
+//! blob injected into it, which is the application's code, its code cache, and its assets. The code is broken down
+//! further into the packages it bundles, from ESBuild's metafile, so it's clear whether a binary is big because of its
+//! bundle (which can be slimmed down) or because of Node.js (which can't).
+
+use super::errors::{Classify, ErrorClass};
+use super::platforms::Target;
+use super::report::BundleContents;
+use super::{BuildOptions, Builder};
+use crate::js_config::JundlerConfig;
+use anyhow::{anyhow, Context, Result};
+use indicatif::HumanBytes;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// The magic number that SEA blobs start with.
+const SEA_MAGIC: u32 = 0x0143_da20;
+
+/// The flag of SEA blobs whose main script is a startup snapshot.
+const SEA_USE_SNAPSHOT: u32 = 1 << 1;
+
+/// The flag of SEA blobs with a code cache.
+const SEA_USE_CODE_CACHE: u32 = 1 << 2;
+
+/// The flag of SEA blobs with assets.
+const SEA_INCLUDE_ASSETS: u32 = 1 << 3;
+
+/// How many of the biggest packages the breakdown lists.
+const TOP_PACKAGES: usize = 5;
+
+/// The units sizes can be given in, i.e. `80MB` or `75 MiB`.
+const UNITS: &[(&str, u64)] = &[
+    ("b", 1),
+    ("kb", 1000),
+    ("mb", 1000 * 1000),
+    ("gb", 1000 * 1000 * 1000),
+    ("kib", 1024),
+    ("mib", 1024 * 1024),
+    ("gib", 1024 * 1024 * 1024),
+];
+
+/// What to do when a built binary is bigger than its maximum size.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MaxSizePolicy {
+    /// Warn about it, and finish the build anyways.
+    Warn,
+
+    /// Fail the build.
+    #[default]
+    Fail,
+}
+
+impl fmt::Display for MaxSizePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MaxSizePolicy::Warn => write!(f, "warn"),
+            MaxSizePolicy::Fail => write!(f, "fail"),
+        }
+    }
+}
+
+/// What the size of a built binary is made of, in bytes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SizeBreakdown {
+    /// The size of the whole binary.
+    pub total: u64,
+
+    /// The size of the Node.js binary it was built from, and anything added to it besides the SEA blob, like its
+    /// code signature. Universal binaries have two of them.
+    pub node: u64,
+
+    /// The size of the SEA blob, in total. Universal binaries have one in each slice, so this counts both.
+    pub blob: u64,
+
+    /// The size of the application's main script, or of its startup snapshot.
+    pub code: u64,
+
+    /// The size of the V8 code cache of the main script, if there is one.
+    pub code_cache: u64,
+
+    /// The size of every asset, by its key.
+    pub assets: BTreeMap<String, u64>,
+
+    /// How much of the main script each package makes up, from ESBuild's metafile, if the application was bundled
+    /// with it. The application's own code is under `(project)`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub packages: BTreeMap<String, u64>,
+}
+
+impl SizeBreakdown {
+    /// Breaks down the size of the binary at `app_path`, which has the SEA blob at `sea_blob` injected into it (twice,
+    /// if it's `universal`), and bundles `bundle`, if it was bundled.
+    pub fn new(
+        app_path: &Path,
+        sea_blob: &Path,
+        universal: bool,
+        bundle: Option<&BundleContents>,
+    ) -> Result<SizeBreakdown> {
+        let total = fs::metadata(app_path)
+            .context(format!("Error reading the size of {}", app_path.display()))?
+            .len();
+
+        let blob = fs::read(sea_blob).context(format!(
+            "Error reading the SEA blob at {}",
+            sea_blob.display()
+        ))?;
+
+        let copies = if universal { 2 } else { 1 };
+
+        let mut breakdown = parse_blob(&blob).unwrap_or_default();
+        breakdown.total = total;
+        breakdown.blob = blob.len() as u64 * copies;
+        breakdown.node = total.saturating_sub(breakdown.blob);
+
+        breakdown.code *= copies;
+        breakdown.code_cache *= copies;
+        breakdown
+            .assets
+            .values_mut()
+            .for_each(|size| *size *= copies);
+
+        if let Some(bundle) = bundle {
+            for (input, bytes) in &bundle.inputs {
+                *breakdown
+                    .packages
+                    .entry(package_name(input).unwrap_or("(project)").to_string())
+                    .or_default() += bytes * copies;
+            }
+        }
+
+        Ok(breakdown)
+    }
+
+    /// The size of every asset together.
+    pub fn assets_total(&self) -> u64 {
+        self.assets.values().sum()
+    }
+
+    /// The biggest packages in the bundle, biggest first.
+    pub fn top_packages(&self) -> Vec<(&str, u64)> {
+        let mut packages: Vec<(&str, u64)> = self
+            .packages
+            .iter()
+            .map(|(name, bytes)| (name.as_str(), *bytes))
+            .collect();
+
+        packages.sort_by(|(a_name, a), (b_name, b)| b.cmp(a).then(a_name.cmp(b_name)));
+        packages.truncate(TOP_PACKAGES);
+
+        packages
+    }
+}
+
+impl fmt::Display for SizeBreakdown {
+    /// Writes the breakdown as lines of `part: size (percent)`, with the biggest packages under the code.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let percent = |bytes: u64| match self.total {
+            0 => 0.0,
+            total => bytes as f64 / total as f64 * 100.0,
+        };
+
+        let line = |f: &mut fmt::Formatter, name: &str, bytes: u64| {
+            writeln!(
+                f,
+                "{:<12} {:>10} ({:.1}%)",
+                name,
+                HumanBytes(bytes).to_string(),
+                percent(bytes)
+            )
+        };
+
+        line(f, "Node.js", self.node)?;
+        line(f, "Code", self.code)?;
+
+        for (name, bytes) in self.top_packages() {
+            writeln!(f, "  {:<10} {:>10}", name, HumanBytes(bytes).to_string())?;
+        }
+
+        if self.code_cache > 0 {
+            line(f, "Code cache", self.code_cache)?;
+        }
+
+        if !self.assets.is_empty() {
+            line(f, "Assets", self.assets_total())?;
+        }
+
+        write!(
+            f,
+            "{:<12} {:>10}",
+            "Total",
+            HumanBytes(self.total).to_string()
+        )
+    }
+}
+
+/// Parses a size in bytes, like `80MB`, `75 MiB`, or `1048576`. Units are case-insensitive; `KB`, `MB`, and `GB` are
+/// powers of 1000, and `KiB`, `MiB`, and `GiB` of 1024.
+pub fn parse_size(size: &str) -> Result<u64, String> {
+    let size = size.trim();
+    let split = size
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(size.len());
+    let (number, unit) = (size[..split].trim(), size[split..].trim().to_lowercase());
+
+    let multiplier = match unit.as_str() {
+        "" => 1,
+        unit => UNITS
+            .iter()
+            .find(|(name, _)| *name == unit)
+            .map(|(_, multiplier)| *multiplier)
+            .ok_or_else(|| {
+                format!(
+                    "`{}` isn't a unit of size; use B, KB, MB, GB, KiB, MiB, or GiB",
+                    unit
+                )
+            })?,
+    };
+
+    number
+        .parse::<f64>()
+        .ok()
+        .filter(|number| number.is_finite() && *number >= 0.0)
+        .map(|number| (number * multiplier as f64).round() as u64)
+        .ok_or_else(|| format!("`{}` isn't a size, i.e. `80MB` or `75 MiB`", size))
+}
+
+/// Gets the maximum size of the binary built with `options`: the one given with `--max-size`, or else the one in
+/// `maxSize` for its target (by name, i.e. `linux-x64` or `darwin-universal`), or for every target (`*`).
+pub fn max_size(options: &BuildOptions, config: &JundlerConfig) -> Result<Option<u64>> {
+    if options.max_size.is_some() {
+        return Ok(options.max_size);
+    }
+
+    let target_name = options.target_name();
+
+    let for_target = config.max_size.iter().find(|(target, _)| {
+        **target == target_name
+            || target.parse::<Target>().is_ok_and(|target| {
+                !options.universal
+                    && (target.os, target.arch) == (options.target_os, options.target_arch)
+            })
+    });
+
+    match for_target.or_else(|| config.max_size.get_key_value("*")) {
+        Some((target, size)) => parse_size(size)
+            .map(Some)
+            .map_err(|err| anyhow!("`maxSize` of `{}` is invalid: {}", target, err)),
+        None => Ok(None),
+    }
+}
+
+/// Parses the sizes of the sections of a SEA blob, as Node.js writes it: the magic number and flags, then the path and
+/// contents of the main script (or snapshot), the code cache, and the assets, each prefixed by their length. `None` if
+/// it isn't a SEA blob.
+pub fn parse_blob(blob: &[u8]) -> Option<SizeBreakdown> {
+    let mut offset = 0;
+
+    if take_u32(blob, &mut offset)? != SEA_MAGIC {
+        return None;
+    }
+
+    let flags = take_u32(blob, &mut offset)?;
+
+    let mut breakdown = SizeBreakdown::default();
+
+    let _code_path = take_sized(blob, &mut offset)?;
+    breakdown.code = take_sized(blob, &mut offset)?.len() as u64;
+
+    // Snapshots don't have a code cache, even with `useCodeCache`
+    if flags & SEA_USE_CODE_CACHE != 0 && flags & SEA_USE_SNAPSHOT == 0 {
+        breakdown.code_cache = take_sized(blob, &mut offset)?.len() as u64;
+    }
+
+    if flags & SEA_INCLUDE_ASSETS != 0 {
+        for _ in 0..take_u64(blob, &mut offset)? {
+            let key = String::from_utf8_lossy(take_sized(blob, &mut offset)?).into_owned();
+            let value = take_sized(blob, &mut offset)?;
+
+            breakdown.assets.insert(key, value.len() as u64);
+        }
+    }
+
+    Some(breakdown)
+}
+
+/// Takes `len` bytes of `bytes` at `offset`, moving past them.
+fn take<'a>(bytes: &'a [u8], offset: &mut usize, len: usize) -> Option<&'a [u8]> {
+    let taken = bytes.get(*offset..offset.checked_add(len)?)?;
+    *offset += len;
+    Some(taken)
+}
+
+fn take_u32(bytes: &[u8], offset: &mut usize) -> Option<u32> {
+    Some(u32::from_le_bytes(take(bytes, offset, 4)?.try_into().ok()?))
+}
+
+/// Lengths in SEA blobs are `size_t`s, and SEA blobs are only made on 64-bit machines.
+fn take_u64(bytes: &[u8], offset: &mut usize) -> Option<u64> {
+    Some(u64::from_le_bytes(take(bytes, offset, 8)?.try_into().ok()?))
+}
+
+/// Takes a string or buffer prefixed by its length.
+fn take_sized<'a>(bytes: &'a [u8], offset: &mut usize) -> Option<&'a [u8]> {
+    let len = take_u64(bytes, offset)?;
+    take(bytes, offset, usize::try_from(len).ok()?)
+}
+
+/// Gets the name of the package in `node_modules` that a bundled file belongs to, i.e. `@scope/pkg` for
+/// `node_modules/a/node_modules/@scope/pkg/lib/index.js`. `None` if it's the project's own.
+fn package_name(input: &str) -> Option<&str> {
+    let (_, path) = input.rsplit_once("node_modules/")?;
+
+    let end = match path.starts_with('@') {
+        true => path.match_indices('/').nth(1)?.0,
+        false => path.find('/')?,
+    };
+
+    Some(&path[..end])
+}
+
+impl Builder {
+    /// Checks the binary at `app_path` against its maximum size (see [`max_size`]), if it has one, and fails the build
+    /// or warns (as `options` say) if it's bigger, with the `breakdown` of its size if there is one.
+    pub(super) fn check_max_size(
+        &mut self,
+        app_path: &Path,
+        breakdown: Option<&SizeBreakdown>,
+        config: &JundlerConfig,
+        options: &BuildOptions,
+    ) -> Result<()> {
+        let Some(max_size) = max_size(options, config).classify(ErrorClass::Config)? else {
+            return Ok(());
+        };
+
+        let size = match breakdown {
+            Some(breakdown) => breakdown.total,
+            None => fs::metadata(app_path)
+                .context(format!("Error reading the size of {}", app_path.display()))?
+                .len(),
+        };
+
+        if size <= max_size {
+            return Ok(());
+        }
+
+        let message = format!(
+            "The built binary for {} is {}, {} over its maximum size of {}!{}",
+            options.target_name(),
+            HumanBytes(size),
+            HumanBytes(size - max_size),
+            HumanBytes(max_size),
+            breakdown.map_or_else(String::new, |breakdown| format!("\n{}", breakdown))
+        );
+
+        match options.max_size_policy {
+            MaxSizePolicy::Warn => {
+                self.tracker.warn(format!("Warning: {}", message));
+
+                Ok(())
+            }
+
+            MaxSizePolicy::Fail => Err(anyhow!(message)),
+        }
+    }
+}
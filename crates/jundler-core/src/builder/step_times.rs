@@ -0,0 +1,65 @@
+use crate::ui::Step;
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How long each step took in the last builds of a project, kept in the cache so long steps can show how much longer
+/// they'll probably take.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StepTimes {
+    /// Where the times are stored.
+    path: PathBuf,
+
+    /// How long each step takes, in seconds, by its message.
+    times: BTreeMap<String, f64>,
+}
+
+impl StepTimes {
+    /// Loads the times of the project's last builds from `cache_dir`. Times that can't be read are just forgotten, as
+    /// they're only used for estimates.
+    pub fn load(cache_dir: &Path, project_dir: &Path) -> StepTimes {
+        let slot = hex::encode(Sha256::digest(project_dir.to_string_lossy().as_bytes()));
+        let path = cache_dir.join(format!("{}.json", slot));
+
+        let times = fs::read(&path)
+            .ok()
+            .and_then(|content| serde_json::from_slice(&content).ok())
+            .unwrap_or_default();
+
+        StepTimes { path, times }
+    }
+
+    /// How long each step will probably take, by its message.
+    pub fn estimates(&self) -> HashMap<String, Duration> {
+        self.times
+            .iter()
+            .map(|(message, secs)| (message.clone(), Duration::from_secs_f64(*secs)))
+            .collect()
+    }
+
+    /// Adds the times of the steps of a build. Each time is averaged with the last one, so one unusually slow (or
+    /// fast) build doesn't throw the estimates off completely.
+    pub fn record(&mut self, steps: &[Step]) {
+        for step in steps {
+            let secs = step.duration.as_secs_f64();
+
+            self.times
+                .entry(step.message.clone())
+                .and_modify(|time| *time = (*time + secs) / 2.0)
+                .or_insert(secs);
+        }
+    }
+
+    /// Saves the times, for the next build of the project.
+    pub fn save(&self) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).context("Could not create the cache directory!")?;
+        }
+
+        fs::write(&self.path, serde_json::to_vec(&self.times)?)
+            .context("Could not save how long the build's steps took!")
+    }
+}
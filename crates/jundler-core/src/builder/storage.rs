@@ -0,0 +1,181 @@
+use super::downloader::{DownloadError, Downloader};
+use crate::ui::{events, Reporter};
+use log::{debug, warn};
+use reqwest::blocking::Client;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// A cache of downloads that's shared between machines, i.e. by a team or every job in CI, so the same Node.js archive
+/// is only downloaded from the internet once. Files are stored by key, which is the host and path of the URL they
+/// were downloaded from (i.e. `nodejs.org/dist/v22.3.0/SHASUMS256.txt`), so a [`DirStorage`] is laid out just like
+/// the directory a [`FileDownloader`](super::downloader::FileDownloader) reads from.
+///
+/// Node.js's checksums are shared through storage along with its archives, so storage has to be trusted as much as
+/// nodejs.org and npm are.
+pub trait Storage: fmt::Debug + Send + Sync {
+    /// Gets the file stored at `key`, or `None` if there isn't one.
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, StorageError>;
+
+    /// Stores `data` at `key`, replacing anything that was there.
+    fn put(&self, key: &str, data: &[u8]) -> Result<(), StorageError>;
+}
+
+/// Any errors that can occur while reading or writing storage
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    /// The request to the storage server failed, or it responded with an error
+    #[error("{0}")]
+    Http(#[from] reqwest::Error),
+
+    /// Reading or writing the storage directory failed
+    #[error("{0}")]
+    Io(#[from] io::Error),
+}
+
+/// Opens the storage at `location`: a server for `http://` and `https://` URLs, or a directory for anything else
+/// (with or without `file://`).
+pub fn open(location: &str) -> Arc<dyn Storage> {
+    if location.starts_with("http://") || location.starts_with("https://") {
+        Arc::new(HttpStorage::new(location))
+    } else {
+        let dir = location.strip_prefix("file://").unwrap_or(location);
+
+        Arc::new(DirStorage::new(PathBuf::from(dir)))
+    }
+}
+
+/// Storage in a directory, i.e. on a network drive or a volume that's kept between CI jobs.
+#[derive(Debug, Clone)]
+pub struct DirStorage {
+    /// The directory files are stored in.
+    dir: PathBuf,
+}
+
+impl DirStorage {
+    /// Creates storage in `dir`, which is created when the first file is stored.
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+}
+
+impl Storage for DirStorage {
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, StorageError> {
+        match fs::read(self.dir.join(key)) {
+            Ok(data) => Ok(Some(data)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn put(&self, key: &str, data: &[u8]) -> Result<(), StorageError> {
+        let path = self.dir.join(key);
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // Write next to the file first, so other machines never read half of it
+        let partial = path.with_extension("partial");
+        fs::write(&partial, data)?;
+        fs::rename(&partial, &path)?;
+
+        Ok(())
+    }
+}
+
+/// Storage on a server that files can be `GET` from and `PUT` to at `{url}/{key}`, i.e. a read-through HTTP cache, or
+/// a bucket behind a proxy that signs requests.
+#[derive(Debug, Clone)]
+pub struct HttpStorage {
+    /// The URL that keys are added to.
+    url: String,
+
+    /// The client to make requests with.
+    client: Client,
+}
+
+impl HttpStorage {
+    /// Creates storage on the server at `url`.
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.trim_end_matches('/').to_string(),
+            client: Client::new(),
+        }
+    }
+}
+
+impl Storage for HttpStorage {
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, StorageError> {
+        let response = self.client.get(format!("{}/{}", self.url, key)).send()?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        Ok(Some(response.error_for_status()?.bytes()?.to_vec()))
+    }
+
+    fn put(&self, key: &str, data: &[u8]) -> Result<(), StorageError> {
+        self.client
+            .put(format!("{}/{}", self.url, key))
+            .body(data.to_vec())
+            .send()?
+            .error_for_status()?;
+
+        Ok(())
+    }
+}
+
+/// Downloads files from storage when they're there, and with another downloader (storing what it downloads) when
+/// they're not. Storage is only ever a shortcut: if it can't be read or written, that's a warning, not an error.
+#[derive(Debug, Clone)]
+pub struct StorageDownloader {
+    /// Where downloads are shared.
+    storage: Arc<dyn Storage>,
+
+    /// What downloads files that aren't in storage yet.
+    downloader: Arc<dyn Downloader>,
+}
+
+impl StorageDownloader {
+    /// Creates a downloader that goes to `storage` first, then to `downloader`.
+    pub fn new(storage: Arc<dyn Storage>, downloader: Arc<dyn Downloader>) -> Self {
+        Self {
+            storage,
+            downloader,
+        }
+    }
+}
+
+impl Downloader for StorageDownloader {
+    fn download(&self, url: &str, reporter: &dyn Reporter) -> Result<Vec<u8>, DownloadError> {
+        let key = url.split_once("://").map_or(url, |(_, rest)| rest);
+
+        match self.storage.get(key) {
+            Ok(Some(data)) => {
+                debug!("Got {} from the shared cache", url);
+
+                // Still report the "download", so it looks the same as any other
+                return Ok(events::read_download(
+                    url,
+                    data.as_slice(),
+                    Some(data.len() as u64),
+                    reporter,
+                )?);
+            }
+            Ok(None) => {}
+            Err(err) => warn!("Could not read {} from the shared cache: {}", key, err),
+        }
+
+        let data = self.downloader.download(url, reporter)?;
+
+        if let Err(err) = self.storage.put(key, &data) {
+            warn!("Could not store {} in the shared cache: {}", key, err);
+        }
+
+        Ok(data)
+    }
+}
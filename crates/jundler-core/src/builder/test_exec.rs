@@ -0,0 +1,226 @@
+//! Runs the built binary once as a smoke test. Binaries for another architecture of the host's OS are run under
+//! emulation where it's available: QEMU user mode on Linux (registered with `binfmt_misc`, or run by hand), Rosetta 2 on
+//! Apple silicon Macs, and Windows' own x64 emulation on Windows on Arm. If the binary can't run here at all, the test
+//! fails and says why, rather than being skipped.
+
+use super::errors::{Classify, ErrorClass};
+use super::helpers::is_available;
+use super::platforms::{get_host_arch, get_host_os, Arch, Os};
+use super::{BuildOptions, Builder};
+use crate::ui::messages::TEST_EXEC_MSG;
+use anyhow::{anyhow, Context, Result};
+use log::debug;
+use std::fmt;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long the binary gets to exit before the test fails.
+pub const TEST_EXEC_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Where Rosetta 2 is installed on macOS, if it is.
+const ROSETTA_PATH: &str = "/Library/Apple/usr/share/rosetta/rosetta";
+
+/// How a binary for the target is run on the host.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Emulator {
+    /// The host runs it itself.
+    Native,
+
+    /// The kernel runs it under QEMU, which is registered with `binfmt_misc`.
+    Binfmt,
+
+    /// QEMU user mode runs it, with this program, i.e. `qemu-aarch64`.
+    Qemu(String),
+
+    /// Rosetta 2 runs it, on Apple silicon.
+    Rosetta,
+
+    /// Windows on Arm runs it with its built-in emulation.
+    WindowsOnArm,
+}
+
+impl Emulator {
+    /// Finds how a binary for `target_os` and `target_arch` can run on the host. `universal` binaries run natively on
+    /// any Mac. Fails, saying why, if there's no way to.
+    pub fn find(target_os: Os, target_arch: Arch, universal: bool) -> Result<Self> {
+        let (host_os, host_arch) = (get_host_os(), get_host_arch());
+
+        if target_os != host_os {
+            return Err(anyhow!(
+                "Can't run a {} binary on {}: only binaries for the host's OS can be tested, natively or under emulation. Build on {} to test it.",
+                target_os,
+                host_os,
+                target_os
+            ));
+        }
+
+        match (host_os, host_arch, target_arch) {
+            _ if universal || host_arch == target_arch => Ok(Emulator::Native),
+
+            // 64-bit x86 runs 32-bit binaries itself
+            (Os::Linux | Os::Windows, Arch::X64, Arch::X86) => Ok(Emulator::Native),
+
+            (Os::Linux, _, _) => {
+                let qemu_arch = qemu_arch(target_arch);
+
+                if Path::new("/proc/sys/fs/binfmt_misc")
+                    .join(format!("qemu-{}", qemu_arch))
+                    .is_file()
+                {
+                    return Ok(Emulator::Binfmt);
+                }
+
+                [format!("qemu-{}", qemu_arch), format!("qemu-{}-static", qemu_arch)]
+                    .into_iter()
+                    .find(|program| is_available(program))
+                    .map(Emulator::Qemu)
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "Can't run a linux-{} binary on this linux-{} machine: QEMU user mode isn't installed. Install `qemu-user-static` (or `qemu-user` with `binfmt-support`) to test it under emulation.",
+                            target_arch,
+                            host_arch
+                        )
+                    })
+            }
+
+            (Os::MacOS, Arch::Arm64, Arch::X64) if Path::new(ROSETTA_PATH).exists() => {
+                Ok(Emulator::Rosetta)
+            }
+
+            (Os::MacOS, Arch::Arm64, Arch::X64) => Err(anyhow!(
+                "Can't run a darwin-x64 binary on this Mac: Rosetta 2 isn't installed. Install it with `softwareupdate --install-rosetta` to test it under emulation."
+            )),
+
+            (Os::Windows, Arch::Arm64, _) => Ok(Emulator::WindowsOnArm),
+
+            _ => Err(anyhow!(
+                "Can't run a {}-{} binary on this {}-{} machine, as there's no emulation for it. Test it on a {}-{} machine instead.",
+                target_os,
+                target_arch,
+                host_os,
+                host_arch,
+                target_os,
+                target_arch
+            )),
+        }
+    }
+
+    /// Gets a command that runs `binary` with this emulator.
+    pub fn command(&self, binary: &Path) -> Command {
+        match self {
+            Emulator::Qemu(program) => {
+                let mut cmd = Command::new(program);
+                cmd.arg(binary);
+                cmd
+            }
+
+            _ => Command::new(binary),
+        }
+    }
+}
+
+impl fmt::Display for Emulator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Emulator::Native => write!(f, "natively"),
+            Emulator::Binfmt => write!(f, "under QEMU"),
+            Emulator::Qemu(program) => write!(f, "under QEMU ({})", program),
+            Emulator::Rosetta => write!(f, "under Rosetta 2"),
+            Emulator::WindowsOnArm => write!(f, "under Windows' emulation"),
+        }
+    }
+}
+
+/// The name QEMU gives an architecture, i.e. `qemu-aarch64`.
+fn qemu_arch(arch: Arch) -> &'static str {
+    match arch {
+        Arch::X64 => "x86_64",
+        Arch::X86 => "i386",
+        Arch::Arm64 => "aarch64",
+    }
+}
+
+impl Builder {
+    /// Runs the built binary at `app_path` with `args`, under emulation if it has to be, and fails if it can't run,
+    /// exits with an error, or doesn't exit within [`TEST_EXEC_TIMEOUT`].
+    pub(super) fn test_exec(
+        &mut self,
+        app_path: &Path,
+        args: &[String],
+        options: &BuildOptions,
+    ) -> Result<()> {
+        let emulator = Emulator::find(options.target_os, options.target_arch, options.universal)
+            .classify(ErrorClass::Platform)?;
+
+        let spinner = self.tracker.spawn_spinner(
+            match emulator {
+                Emulator::Native => TEST_EXEC_MSG.to_string(),
+                _ => format!("{} {}", TEST_EXEC_MSG, emulator),
+            },
+            0,
+        );
+
+        debug!("Testing {} {}", app_path.display(), emulator);
+
+        let tested = self.run_test(&emulator, app_path, args);
+
+        spinner.finish(tested)
+    }
+
+    /// Runs the test. See `test_exec`.
+    fn run_test(&self, emulator: &Emulator, app_path: &Path, args: &[String]) -> Result<()> {
+        // The output goes to files, so the binary can't block on a full pipe while it's waited on
+        let (stdout_path, stderr_path) = (
+            self.working_dir.path().join("test-exec.stdout"),
+            self.working_dir.path().join("test-exec.stderr"),
+        );
+        let output = |path: &PathBuf| fs::read_to_string(path).unwrap_or_default();
+
+        let mut child = emulator
+            .command(app_path)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(File::create(&stdout_path)?)
+            .stderr(File::create(&stderr_path)?)
+            .spawn()
+            .context(format!("Error running {} {}", app_path.display(), emulator))?;
+
+        let started = Instant::now();
+
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+
+            if started.elapsed() > TEST_EXEC_TIMEOUT {
+                child.kill()?;
+                child.wait()?;
+
+                return Err(anyhow!(
+                    "The built binary didn't exit within {} seconds when run {}! Pass arguments that make it exit, i.e. `--test-exec=--version`.\n{}{}",
+                    TEST_EXEC_TIMEOUT.as_secs(),
+                    emulator,
+                    output(&stdout_path),
+                    output(&stderr_path)
+                ));
+            }
+
+            thread::sleep(Duration::from_millis(50));
+        };
+
+        if !status.success() {
+            return Err(anyhow!(
+                "The built binary failed its test when run {} ({}):\n{}{}",
+                emulator,
+                status,
+                output(&stdout_path),
+                output(&stderr_path)
+            ));
+        }
+
+        Ok(())
+    }
+}
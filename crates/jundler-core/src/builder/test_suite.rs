@@ -0,0 +1,106 @@
+//! Runs a project's own test suite against its built binary, so that the SEA is what gets tested, not just the source
+//! it was built from. The test command is run in the project's directory, with `JUNDLER_BINARY` set to the binary, and
+//! with the project's environment (not a sanitized one), as it's the project's own command. Commands given after `--`
+//! are run as they are, and the ones from the configuration or the `test` script by the shell.
+
+use super::package_manager::PackageManager;
+use super::platforms::{get_host_os, Os};
+use crate::js_config::PackageConfig;
+use anyhow::{anyhow, Context, Result};
+use log::debug;
+use std::fmt;
+use std::path::Path;
+use std::process::{Command, ExitStatus};
+
+/// The environment variable that has the path to the built binary, for the test suite to run.
+pub const BINARY_ENV_VAR: &str = "JUNDLER_BINARY";
+
+/// The `test` script that `npm init` writes, which only fails.
+const NPM_INIT_TEST_SCRIPT: &str = "echo \"Error: no test specified\" && exit 1";
+
+/// A command to test a project with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TestCommand {
+    /// A program and its arguments, as they were given after `--`. They're run without a shell, so they're passed on
+    /// exactly as they were given, however they're quoted.
+    Args(Vec<String>),
+
+    /// A command line from the configuration (or one that runs the `test` script), run by the shell.
+    Shell(String),
+}
+
+impl fmt::Display for TestCommand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TestCommand::Args(args) => write!(f, "{}", args.join(" ")),
+            TestCommand::Shell(command) => write!(f, "{}", command),
+        }
+    }
+}
+
+/// Gets the command to test the project at `project_dir` with: `command` if it's given, or else `test` in the project's
+/// configuration, or else its `test` script, run with its package manager.
+pub fn test_command(
+    project_dir: &Path,
+    package_config: &PackageConfig,
+    command: &[String],
+) -> Result<TestCommand> {
+    if !command.is_empty() {
+        return Ok(TestCommand::Args(command.to_vec()));
+    }
+
+    if let Some(test) = &package_config.jundler.test {
+        return Ok(TestCommand::Shell(test.clone()));
+    }
+
+    match package_config.script("test") {
+        // Run by the shell, which finds the package manager's `.cmd` shim on Windows
+        Some(script) if script != NPM_INIT_TEST_SCRIPT => Ok(TestCommand::Shell(format!(
+            "{} run test",
+            PackageManager::detect(project_dir).program(get_host_os())
+        ))),
+        _ => Err(anyhow!(
+            "The project doesn't have a test command! Set `test` in its configuration (i.e. `test = \"bats test/\"`), add a `test` script to its `package.json`, or pass one after `--`."
+        )),
+    }
+}
+
+/// Runs the test `command` in `project_dir` against the binary at `app_path`, with its output streamed straight
+/// through. Returns how it exited.
+pub fn run_tests(project_dir: &Path, command: &TestCommand, app_path: &Path) -> Result<ExitStatus> {
+    let app_path = app_path.canonicalize().context(format!(
+        "Could not find the built binary at {}!",
+        app_path.display()
+    ))?;
+
+    debug!(
+        "Running `{}` in {} with {}={}",
+        command,
+        project_dir.display(),
+        BINARY_ENV_VAR,
+        app_path.display()
+    );
+
+    let mut cmd = match (command, get_host_os()) {
+        (TestCommand::Args(args), _) => {
+            let mut cmd = Command::new(&args[0]);
+            cmd.args(&args[1..]);
+            cmd
+        }
+        (TestCommand::Shell(command), Os::Windows) => {
+            let mut cmd = Command::new("cmd");
+            cmd.arg("/C").arg(command);
+            cmd
+        }
+        (TestCommand::Shell(command), _) => {
+            let mut cmd = Command::new("sh");
+            cmd.arg("-c").arg(command);
+            cmd
+        }
+    };
+
+    cmd.current_dir(project_dir)
+        .env(BINARY_ENV_VAR, &app_path)
+        .status()
+        .context(format!("Error running the test command `{}`", command))
+}
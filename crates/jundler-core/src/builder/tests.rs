@@ -0,0 +1,3754 @@
+#![cfg(test)]
+
+use super::settings::{Setting, Source};
+use super::*;
+use crate::js_config::CONFIG_FILE_NAME;
+use crate::ui::messages::INSTALL_DEPS_MSG;
+use crate::ui::Step;
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::{Duration, SystemTime};
+
+/// Test that we were able to get a new a `Builder` instance from a project.
+#[test]
+fn new_builder() {
+    let result = Builder::new(TempDir::new("test").unwrap().into_path());
+
+    assert!(result.is_ok());
+
+    // let builder = result.unwrap();
+
+    // assert_eq!(builder.package_config.name, "simple");
+    // assert_eq!(builder.sea_config.main, "index.js");
+    // assert_eq!(
+    //     builder.sea_config.output,
+    //     "the-random-name-for-the-sea-prep-blob.blob"
+    // );
+    // // assert!(builder.builder_dir.exists());
+
+    // assert_eq!(builder.sea_config.other.len(), 1);
+    // assert_eq!(
+    //     builder.sea_config.other["disableExperimentalSEAWarning"],
+    //     Value::Bool(true)
+    // );
+}
+
+/// Test that copying a project skips the default excludes and anything in `.jundlerignore`.
+#[test]
+fn copy_project_filters() {
+    let src = TempDir::new("src").unwrap();
+    let dest = TempDir::new("dest").unwrap();
+
+    fs::create_dir_all(src.path().join("node_modules/left-pad")).unwrap();
+    fs::create_dir_all(src.path().join(".git")).unwrap();
+    fs::create_dir_all(src.path().join("lib")).unwrap();
+    fs::create_dir_all(src.path().join("coverage")).unwrap();
+
+    fs::write(src.path().join("index.js"), "").unwrap();
+    fs::write(src.path().join("lib/util.js"), "").unwrap();
+    fs::write(src.path().join("lib/debug.log"), "").unwrap();
+    fs::write(src.path().join("coverage/lcov.info"), "").unwrap();
+    fs::write(src.path().join("node_modules/left-pad/index.js"), "").unwrap();
+    fs::write(src.path().join(".git/HEAD"), "").unwrap();
+    fs::write(src.path().join("simple"), "").unwrap();
+    fs::write(src.path().join(".jundlerignore"), "coverage/\n*.log\n").unwrap();
+
+    copy::copy_project(
+        src.path(),
+        dest.path(),
+        &["/simple".to_string()],
+        CopyStrategy::Auto,
+    )
+    .unwrap();
+
+    assert!(dest.path().join("index.js").exists());
+    assert!(dest.path().join("lib/util.js").exists());
+    assert!(!dest.path().join("lib/debug.log").exists());
+    assert!(!dest.path().join("coverage").exists());
+    assert!(!dest.path().join("node_modules").exists());
+    assert!(!dest.path().join(".git").exists());
+    assert!(!dest.path().join(".jundlerignore").exists());
+    assert!(!dest.path().join("simple").exists());
+}
+
+/// Test that `.jundlerignore` can re-include something that is excluded by default.
+#[test]
+fn copy_project_reinclude() {
+    let src = TempDir::new("src").unwrap();
+    let dest = TempDir::new("dest").unwrap();
+
+    fs::create_dir_all(src.path().join("node_modules/vendored")).unwrap();
+    fs::write(src.path().join("node_modules/vendored/index.js"), "").unwrap();
+    fs::write(src.path().join(".jundlerignore"), "!node_modules/\n").unwrap();
+
+    copy::copy_project(src.path(), dest.path(), &[], CopyStrategy::Auto).unwrap();
+
+    assert!(dest.path().join("node_modules/vendored/index.js").exists());
+}
+
+/// Test that bundled projects only copy what installing their dependencies needs, unless the install needs their
+/// sources too.
+#[test]
+fn copy_install_files() {
+    let src = TempDir::new("src").unwrap();
+    let dest = TempDir::new("dest").unwrap();
+
+    fs::create_dir_all(src.path().join("src")).unwrap();
+    fs::create_dir_all(src.path().join(".yarn/releases")).unwrap();
+
+    fs::write(src.path().join("package.json"), "{}").unwrap();
+    fs::write(src.path().join("yarn.lock"), "").unwrap();
+    fs::write(src.path().join(".yarnrc.yml"), "").unwrap();
+    fs::write(src.path().join(".yarn/releases/yarn.cjs"), "").unwrap();
+    fs::write(src.path().join("src/index.js"), "").unwrap();
+    fs::write(src.path().join("assets.json"), "").unwrap();
+
+    copy::copy_install_files(src.path(), dest.path(), &[], CopyStrategy::Auto).unwrap();
+
+    assert!(dest.path().join("package.json").exists());
+    assert!(dest.path().join("yarn.lock").exists());
+    assert!(dest.path().join(".yarnrc.yml").exists());
+    assert!(dest.path().join(".yarn/releases/yarn.cjs").exists());
+    assert!(!dest.path().join("src").exists());
+    assert!(!dest.path().join("assets.json").exists());
+
+    let package_config = |json: &str| serde_json::from_str::<PackageConfig>(json).unwrap();
+
+    assert!(
+        !package_config(r#"{ "name": "app", "dependencies": { "left-pad": "^1.3.0" } }"#)
+            .install_needs_sources()
+    );
+    assert!(
+        package_config(r#"{ "name": "app", "scripts": { "postinstall": "node setup.js" } }"#)
+            .install_needs_sources()
+    );
+    assert!(package_config(
+        r#"{ "name": "app", "dependencies": { "vendored": "file:./vendor/pkg" } }"#
+    )
+    .install_needs_sources());
+    assert!(
+        package_config(r#"{ "name": "app", "workspaces": ["packages/*"] }"#)
+            .install_needs_sources()
+    );
+}
+
+/// Test that bundled projects are only bundled in place when there's no `node_modules` that ESBuild would find before
+/// the one installed for the target in the build directory.
+#[test]
+fn bundle_in_place_without_node_modules() {
+    let root = TempDir::new("root").unwrap();
+    let project_dir = root.path().join("app");
+    let builder = Builder::new(TempDir::new("cache").unwrap().into_path()).unwrap();
+
+    fs::create_dir_all(&project_dir).unwrap();
+
+    let package_config: PackageConfig =
+        serde_json::from_str(r#"{ "name": "app", "type": "module" }"#).unwrap();
+    let options = BuildOptions::new(Version::new(22, 3, 0));
+
+    assert!(builder.bundles_in_place(&project_dir, &package_config, &options));
+
+    // A local install, i.e. for the host instead of the target
+    fs::create_dir_all(project_dir.join("node_modules/left-pad")).unwrap();
+    fs::write(
+        project_dir.join("node_modules/left-pad/index.js"),
+        "module.exports = 'host';",
+    )
+    .unwrap();
+
+    assert!(!builder.bundles_in_place(&project_dir, &package_config, &options));
+
+    // One hoisted to a directory above the project counts too
+    fs::remove_dir_all(project_dir.join("node_modules")).unwrap();
+    fs::create_dir_all(root.path().join("node_modules")).unwrap();
+
+    assert!(!builder.bundles_in_place(&project_dir, &package_config, &options));
+}
+
+/// Test that hardlinked copies share data with the original, except for files the build writes to.
+#[cfg(unix)]
+#[test]
+fn copy_project_hardlink() {
+    use std::os::unix::fs::MetadataExt;
+
+    let src = TempDir::new("src").unwrap();
+    let dest = TempDir::new("dest").unwrap();
+
+    fs::write(src.path().join("index.js"), "").unwrap();
+    fs::write(src.path().join("sea-config.json"), "{}").unwrap();
+
+    copy::copy_project(src.path(), dest.path(), &[], CopyStrategy::Hardlink).unwrap();
+
+    let inode = |path: &Path| fs::metadata(path).unwrap().ino();
+
+    assert_eq!(
+        inode(&src.path().join("index.js")),
+        inode(&dest.path().join("index.js"))
+    );
+    assert_ne!(
+        inode(&src.path().join("sea-config.json")),
+        inode(&dest.path().join("sea-config.json"))
+    );
+}
+
+/// Test that symlinks in a project are copied as links, whether they point to a directory or to nothing at all.
+#[cfg(unix)]
+#[test]
+fn copy_project_symlinks() {
+    use std::os::unix::fs::symlink;
+
+    let src = TempDir::new("src").unwrap();
+    let dest = TempDir::new("dest").unwrap();
+
+    fs::create_dir_all(src.path().join("lib")).unwrap();
+    fs::write(src.path().join("lib/util.js"), "").unwrap();
+    symlink("lib", src.path().join("src")).unwrap();
+    symlink("missing.js", src.path().join("dangling.js")).unwrap();
+
+    copy::copy_project(src.path(), dest.path(), &[], CopyStrategy::Auto).unwrap();
+
+    assert_eq!(
+        fs::read_link(dest.path().join("src")).unwrap(),
+        PathBuf::from("lib")
+    );
+    assert!(dest.path().join("src/util.js").exists());
+    assert_eq!(
+        fs::read_link(dest.path().join("dangling.js")).unwrap(),
+        PathBuf::from("missing.js")
+    );
+
+    // Links are part of the build inputs, too
+    let options = BuildOptions::new(Version::new(22, 3, 0));
+    let hash = BuildCache::inputs_hash(src.path(), &[], &options).unwrap();
+
+    fs::remove_file(src.path().join("dangling.js")).unwrap();
+    symlink("other.js", src.path().join("dangling.js")).unwrap();
+
+    assert_ne!(
+        BuildCache::inputs_hash(src.path(), &[], &options).unwrap(),
+        hash
+    );
+}
+
+/// Test that the build cache only hits when none of the build inputs have changed.
+#[test]
+fn build_cache_hit_and_miss() {
+    let project = TempDir::new("project").unwrap();
+    let cache_dir = TempDir::new("cache").unwrap();
+    let blob = TempDir::new("blob").unwrap();
+
+    fs::write(project.path().join("index.js"), "console.log(1)").unwrap();
+    fs::write(blob.path().join("sea-prep.blob"), "blob").unwrap();
+
+    let cache = build_cache::BuildCache::new(cache_dir.path().to_path_buf());
+    let mut options = BuildOptions {
+        target_os: Os::Linux,
+        target_arch: Arch::X64,
+        ..BuildOptions::new(Version::new(22, 3, 0))
+    };
+
+    let hash = build_cache::BuildCache::inputs_hash(project.path(), &[], &options).unwrap();
+
+    assert!(cache.get(project.path(), &options, &hash).is_none());
+
+    cache
+        .insert(
+            project.path(),
+            &options,
+            &hash,
+            &build_cache::BuildArtifacts {
+                sea_blob: blob.path().join("sea-prep.blob"),
+                notices: None,
+                bundle: None,
+            },
+        )
+        .unwrap();
+
+    assert!(cache.get(project.path(), &options, &hash).is_some());
+
+    // Changing an excluded file doesn't invalidate the cache...
+    fs::create_dir(project.path().join("node_modules")).unwrap();
+    fs::write(project.path().join("node_modules/dep.js"), "").unwrap();
+    assert_eq!(
+        build_cache::BuildCache::inputs_hash(project.path(), &[], &options).unwrap(),
+        hash
+    );
+
+    // ...but changing a source file or an option does.
+    fs::write(project.path().join("index.js"), "console.log(2)").unwrap();
+    let new_hash = build_cache::BuildCache::inputs_hash(project.path(), &[], &options).unwrap();
+    assert_ne!(new_hash, hash);
+    assert!(cache.get(project.path(), &options, &new_hash).is_none());
+
+    options.bundle = true;
+    assert_ne!(
+        build_cache::BuildCache::inputs_hash(project.path(), &[], &options).unwrap(),
+        new_hash
+    );
+}
+
+/// Test that the project filter excludes paths inside excluded directories, and anything outside of the project.
+#[test]
+fn project_filter_excludes() {
+    let project = TempDir::new("project").unwrap();
+
+    fs::create_dir_all(project.path().join("node_modules/dep")).unwrap();
+    fs::write(project.path().join(".jundlerignore"), "*.log\n").unwrap();
+
+    let filter = copy::ProjectFilter::new(project.path(), &["/simple".to_string()]).unwrap();
+
+    assert!(!filter.is_excluded(&project.path().join("index.js")));
+    assert!(filter.is_excluded(&project.path().join("node_modules/dep/index.js")));
+    assert!(filter.is_excluded(&project.path().join("lib/debug.log")));
+    assert!(filter.is_excluded(&project.path().join("simple")));
+    assert!(filter.is_excluded(Path::new("/somewhere/else.js")));
+}
+
+/// Test that the package manager is detected from the lockfile, including lockfiles in parent (workspace) directories,
+/// but not in directories above the workspace or repository.
+#[test]
+fn detect_package_manager() {
+    let workspace = TempDir::new("workspace").unwrap();
+    let project = workspace.path().join("packages/cli");
+
+    fs::create_dir_all(&project).unwrap();
+
+    assert_eq!(PackageManager::detect(&project), PackageManager::Npm);
+
+    // A stray lockfile above a project that isn't in a workspace is ignored
+    fs::write(workspace.path().join("pnpm-lock.yaml"), "").unwrap();
+    assert_eq!(PackageManager::detect(&project), PackageManager::Npm);
+
+    fs::write(workspace.path().join("pnpm-workspace.yaml"), "").unwrap();
+    assert_eq!(PackageManager::detect(&project), PackageManager::Pnpm);
+
+    // ...as is one above the repository it's in
+    fs::create_dir_all(project.join(".git")).unwrap();
+    assert_eq!(PackageManager::detect(&project), PackageManager::Npm);
+    fs::remove_dir(project.join(".git")).unwrap();
+
+    // The workspace's lockfile is copied into the build directory, as it isn't in the project
+    let build_dir = TempDir::new("build").unwrap();
+    assert_eq!(
+        PackageManager::copy_workspace_lockfile(&project, build_dir.path()).unwrap(),
+        Some(workspace.path().join("pnpm-lock.yaml"))
+    );
+    assert!(build_dir.path().join("pnpm-lock.yaml").exists());
+
+    fs::write(project.join("yarn.lock"), "").unwrap();
+    assert_eq!(PackageManager::detect(&project), PackageManager::Yarn);
+    assert_eq!(
+        PackageManager::copy_workspace_lockfile(&project, build_dir.path()).unwrap(),
+        None
+    );
+}
+
+/// Test that package managers installed as `.cmd` shims are run by their full name on Windows hosts.
+#[test]
+fn package_manager_programs() {
+    assert_eq!(PackageManager::Pnpm.program(Os::Windows), "pnpm.cmd");
+    assert_eq!(PackageManager::Yarn.program(Os::Windows), "yarn.cmd");
+    assert_eq!(PackageManager::Bun.program(Os::Windows), "bun");
+    assert_eq!(PackageManager::Pnpm.program(Os::Linux), "pnpm");
+}
+
+/// Test that streamed commands capture both stdout and stderr, along with the exit status.
+#[cfg(unix)]
+#[test]
+fn run_streaming_captures_output() {
+    let output_line = Tracker::default().spawn_output_line();
+
+    let (status, output) = helpers::run_streaming(
+        std::process::Command::new("sh").args(["-c", "echo out; echo err >&2; exit 3"]),
+        &output_line,
+    )
+    .unwrap();
+
+    output_line.close();
+
+    assert_eq!(status.code(), Some(3));
+    assert!(output.contains("out\n"));
+    assert!(output.contains("err\n"));
+}
+
+/// Test that built binaries are tested natively when they can be, and that binaries that can't run here are reported.
+#[cfg(unix)]
+#[test]
+fn test_exec_built_binary() {
+    use helpers::make_executable;
+    use test_exec::Emulator;
+
+    let (host_os, host_arch) = (get_host_os(), get_host_arch());
+    let other_os = if host_os == Os::Linux {
+        Os::Windows
+    } else {
+        Os::Linux
+    };
+
+    assert_eq!(
+        Emulator::find(host_os, host_arch, false).unwrap(),
+        Emulator::Native
+    );
+    assert!(Emulator::find(other_os, host_arch, false)
+        .unwrap_err()
+        .to_string()
+        .contains(&format!("Build on {} to test it", other_os)));
+
+    let mut builder = Builder::new(TempDir::new("cache").unwrap().into_path()).unwrap();
+    let app_dir = TempDir::new("app").unwrap();
+    let app_path = app_dir.path().join("app");
+    let options = BuildOptions::new(Version::new(22, 3, 0));
+
+    fs::write(
+        &app_path,
+        "#!/bin/sh\n[ \"$1\" = \"--version\" ] || { echo broken >&2; exit 1; }\n",
+    )
+    .unwrap();
+    make_executable(&app_path).unwrap();
+
+    builder
+        .test_exec(&app_path, &["--version".to_string()], &options)
+        .unwrap();
+
+    let err = builder
+        .test_exec(&app_path, &[], &options)
+        .unwrap_err()
+        .to_string();
+
+    assert!(err.contains("failed its test when run natively"));
+    assert!(err.contains("broken"));
+}
+
+/// Test that the dependencies without prebuilt addons for the target are rebuilt, and only their final addons are
+/// embedded.
+#[test]
+fn native_packages_to_rebuild() {
+    let project = TempDir::new("project").unwrap();
+
+    for file in [
+        "node_modules/sqlite3/package.json",
+        "node_modules/sqlite3/binding.gyp",
+        "node_modules/sqlite3/deps/binding.gyp",
+        "node_modules/sqlite3/build/Release/node_sqlite3.node",
+        "node_modules/sqlite3/build/Release/obj.target/node_sqlite3.node",
+        "node_modules/classic-level/package.json",
+        "node_modules/classic-level/binding.gyp",
+        "node_modules/classic-level/prebuilds/linux-arm64/classic-level.node",
+    ] {
+        let path = project.path().join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "").unwrap();
+    }
+
+    assert_eq!(
+        native::packages_to_rebuild(project.path(), Os::Linux, Arch::Arm64).unwrap(),
+        vec![PathBuf::from("node_modules/sqlite3")]
+    );
+    assert_eq!(
+        native::packages_to_rebuild(project.path(), Os::Linux, Arch::X64).unwrap(),
+        vec![
+            PathBuf::from("node_modules/classic-level"),
+            PathBuf::from("node_modules/sqlite3"),
+        ]
+    );
+    assert_eq!(
+        native::find_native_addons(project.path(), Os::Linux, Arch::Arm64).unwrap(),
+        vec![
+            PathBuf::from("node_modules/classic-level/prebuilds/linux-arm64/classic-level.node"),
+            PathBuf::from("node_modules/sqlite3/build/Release/node_sqlite3.node"),
+        ]
+    );
+}
+
+/// Test that installs fetch the target's prebuilt addons, naming it the way Node.js does.
+#[test]
+fn install_for_target() {
+    use node_manager::HostNode;
+
+    let host_node = HostNode {
+        version: Version::new(22, 3, 0),
+        node: PathBuf::from("node"),
+        npm_cli: PathBuf::from("npm-cli.js"),
+        corepack_cli: Some(PathBuf::from("corepack.js")),
+    };
+    let cross = if get_host_os() == Os::Windows {
+        Os::Linux
+    } else {
+        Os::Windows
+    };
+
+    let cmd = PackageManager::Npm.install_command(
+        &host_node,
+        Path::new("."),
+        cross,
+        Arch::X86,
+        false,
+        None,
+    );
+    let envs: BTreeMap<String, String> = cmd
+        .get_envs()
+        .filter_map(|(name, value)| {
+            Some((
+                name.to_string_lossy().into_owned(),
+                value?.to_string_lossy().into_owned(),
+            ))
+        })
+        .collect();
+    let args: Vec<String> = cmd
+        .get_args()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+    let platform = native::node_platform(cross);
+
+    assert_eq!(envs["npm_config_platform"], platform);
+    assert_eq!(envs["npm_config_arch"], "ia32");
+    assert_eq!(envs["npm_config_target_platform"], platform);
+    assert!(args.contains(&format!("--os={}", platform)));
+    assert!(args.contains(&"--cpu=ia32".to_string()));
+
+    // Building for the host installs like it always does
+    let cmd = PackageManager::Npm.install_command(
+        &host_node,
+        Path::new("."),
+        get_host_os(),
+        get_host_arch(),
+        false,
+        None,
+    );
+
+    assert!(!cmd
+        .get_args()
+        .any(|arg| arg.to_string_lossy().starts_with("--os")));
+}
+
+/// Test that package managers pinned with `packageManager` are detected, and run through Corepack.
+#[test]
+fn install_pinned_package_manager() {
+    use node_manager::HostNode;
+    use std::ffi::OsStr;
+
+    let workspace = TempDir::new("workspace").unwrap();
+    let project = workspace.path().join("packages/cli");
+
+    fs::create_dir_all(&project).unwrap();
+    fs::write(project.join("package.json"), r#"{"name": "cli"}"#).unwrap();
+    fs::write(project.join("yarn.lock"), "").unwrap();
+
+    assert_eq!(PackageManager::pinned(&project), None);
+
+    // The workspace's pin wins over the lockfile
+    let spec = "pnpm@9.1.0+sha512.abc123";
+    fs::write(
+        workspace.path().join("package.json"),
+        format!(
+            r#"{{"name": "workspace", "workspaces": ["packages/*"], "packageManager": "{}"}}"#,
+            spec
+        ),
+    )
+    .unwrap();
+
+    assert_eq!(
+        PackageManager::pinned(&project),
+        Some((PackageManager::Pnpm, spec.to_string()))
+    );
+    assert_eq!(PackageManager::detect(&project), PackageManager::Pnpm);
+
+    let host_node = HostNode {
+        version: Version::new(22, 3, 0),
+        node: PathBuf::from("node"),
+        npm_cli: PathBuf::from("npm-cli.js"),
+        corepack_cli: Some(PathBuf::from("corepack.js")),
+    };
+
+    let cmd = PackageManager::Pnpm.install_command(
+        &host_node,
+        &project,
+        get_host_os(),
+        get_host_arch(),
+        true,
+        Some(spec),
+    );
+    let args: Vec<_> = cmd.get_args().collect();
+
+    assert_eq!(cmd.get_program(), "node");
+    assert_eq!(
+        args[..4],
+        ["corepack.js", spec, "install", "--frozen-lockfile"]
+    );
+    assert!(cmd.get_envs().any(|env| env
+        == (
+            OsStr::new("COREPACK_ENABLE_DOWNLOAD_PROMPT"),
+            Some(OsStr::new("0"))
+        )));
+
+    // Corepack doesn't know Bun, so the installed one is used
+    let cmd = PackageManager::Bun.install_command(
+        &host_node,
+        &project,
+        get_host_os(),
+        get_host_arch(),
+        false,
+        Some("bun@1.1.0"),
+    );
+
+    assert_eq!(cmd.get_program(), "bun");
+
+    // Yarn 2 and later only install strictly from the lockfile with `--immutable`
+    let yarn_project = TempDir::new("yarn").unwrap();
+    let frozen_args = |pinned: Option<&str>| {
+        PackageManager::Yarn
+            .install_command(
+                &host_node,
+                yarn_project.path(),
+                get_host_os(),
+                get_host_arch(),
+                true,
+                pinned,
+            )
+            .get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect::<Vec<_>>()
+    };
+
+    fs::write(
+        yarn_project.path().join("yarn.lock"),
+        "# yarn lockfile v1\n",
+    )
+    .unwrap();
+    assert!(frozen_args(None).contains(&"--frozen-lockfile".to_string()));
+    assert!(frozen_args(Some("yarn@4.1.0")).contains(&"--immutable".to_string()));
+
+    fs::write(
+        yarn_project.path().join("yarn.lock"),
+        "__metadata:\n  version: 8\n",
+    )
+    .unwrap();
+    assert!(frozen_args(None).contains(&"--immutable".to_string()));
+    assert!(frozen_args(Some("yarn@1.22.19")).contains(&"--frozen-lockfile".to_string()));
+}
+
+/// Test that native addons are found, skipping prebuilds for other platforms.
+#[test]
+fn find_native_addons_for_target() {
+    let project = TempDir::new("project").unwrap();
+
+    let addons = [
+        "node_modules/sqlite3/build/Release/node_sqlite3.node",
+        "node_modules/sharp/prebuilds/linux-x64/sharp.node",
+        "node_modules/sharp/prebuilds/darwin-arm64/sharp.node",
+        "node_modules/sharp/prebuilds/win32-x64/sharp.node",
+        "node_modules/@swc/core-linux-x64-gnu/swc.linux-x64-gnu.node",
+        "node_modules/@swc/core-darwin-arm64/swc.darwin-arm64.node",
+    ];
+
+    for addon in addons {
+        let path = project.path().join(addon);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "").unwrap();
+    }
+
+    // napi-rs packages say which platform they're for in their `package.json`
+    fs::write(
+        project
+            .path()
+            .join("node_modules/@swc/core-linux-x64-gnu/package.json"),
+        r#"{"os":["linux"],"cpu":["x64"]}"#,
+    )
+    .unwrap();
+    fs::write(
+        project
+            .path()
+            .join("node_modules/@swc/core-darwin-arm64/package.json"),
+        r#"{"os":["darwin"],"cpu":["arm64"]}"#,
+    )
+    .unwrap();
+
+    fs::write(project.path().join("index.js"), "").unwrap();
+
+    let found = native::find_native_addons(project.path(), Os::Linux, Arch::X64).unwrap();
+
+    assert_eq!(
+        found,
+        vec![
+            PathBuf::from("node_modules/@swc/core-linux-x64-gnu/swc.linux-x64-gnu.node"),
+            PathBuf::from("node_modules/sharp/prebuilds/linux-x64/sharp.node"),
+            PathBuf::from("node_modules/sqlite3/build/Release/node_sqlite3.node"),
+        ]
+    );
+
+    let shim = native::loader_shim(
+        &found
+            .iter()
+            .map(|a| native::asset_key(a))
+            .collect::<Vec<_>>(),
+    );
+
+    assert!(shim.contains(
+        r#"["node_modules/@swc/core-linux-x64-gnu/swc.linux-x64-gnu.node","node_modules/sharp/prebuilds/linux-x64/sharp.node","node_modules/sqlite3/build/Release/node_sqlite3.node"]"#
+    ));
+}
+
+/// Test that shims are put after the hashbang of an entrypoint, which has to stay on its first line.
+#[test]
+fn prepend_shim_keeps_hashbang() {
+    assert_eq!(
+        helpers::prepend_shim("console.log(1);\n", "shim();"),
+        "shim();\nconsole.log(1);\n"
+    );
+    assert_eq!(
+        helpers::prepend_shim("#!/usr/bin/env node\nconsole.log(1);\n", "shim();"),
+        "#!/usr/bin/env node\nshim();\nconsole.log(1);\n"
+    );
+    assert_eq!(
+        helpers::prepend_shim("#!/usr/bin/env node", "shim();"),
+        "#!/usr/bin/env node\nshim();\n"
+    );
+
+    // The licenses and addon loader shims go in together, each once, after the hashbang
+    let shims = [
+        licenses::licenses_shim("notices.txt"),
+        native::loader_shim(&["addon.node".to_string()]),
+    ]
+    .join("\n");
+    let entrypoint = helpers::prepend_shim("#!/usr/bin/env node\nmain();\n", &shims);
+
+    assert!(entrypoint.starts_with("#!/usr/bin/env node\n// Injected by jundler: prints"));
+    assert!(entrypoint.ends_with("\nmain();\n"));
+    assert_eq!(entrypoint.matches("Injected by jundler").count(), 2);
+}
+
+/// Test that files bigger than a chunk are checksummed whole, and that changed files aren't given their old checksum.
+#[test]
+fn checksum_files() {
+    let dir = TempDir::new("checksums").unwrap();
+    let path = dir.path().join("binary");
+
+    let contents = vec![7u8; (1 << 20) * 3 + 5];
+    fs::write(&path, &contents).unwrap();
+
+    let checksum: [u8; 32] = Sha256::digest(&contents).into();
+    assert_eq!(helpers::calculate_checksum(&path).unwrap(), checksum);
+    assert_eq!(helpers::calculate_checksum(&path).unwrap(), checksum);
+
+    // Even if it's changed within the same instant, the size gives it away
+    let mtime = filetime::FileTime::from_last_modification_time(&fs::metadata(&path).unwrap());
+    fs::write(&path, "changed").unwrap();
+    filetime::set_file_mtime(&path, mtime).unwrap();
+
+    assert_eq!(
+        helpers::calculate_checksum(&path).unwrap(),
+        <[u8; 32]>::from(Sha256::digest("changed"))
+    );
+}
+
+/// Test that normalizing timestamps sets every file and directory in the project to the same time.
+#[test]
+fn normalize_mtimes() {
+    let project = TempDir::new("project").unwrap();
+
+    fs::create_dir_all(project.path().join("src/lib")).unwrap();
+    fs::write(project.path().join("src/lib/index.js"), "").unwrap();
+    fs::write(project.path().join("package.json"), "{}").unwrap();
+
+    reproducible::normalize_mtimes(project.path(), reproducible::DEFAULT_SOURCE_DATE_EPOCH)
+        .unwrap();
+
+    for path in ["", "src", "src/lib", "src/lib/index.js", "package.json"] {
+        let mtime = filetime::FileTime::from_last_modification_time(
+            &project.path().join(path).metadata().unwrap(),
+        );
+
+        assert_eq!(
+            mtime.unix_seconds(),
+            reproducible::DEFAULT_SOURCE_DATE_EPOCH
+        );
+    }
+}
+
+/// Test that checksum files are written in each format, and that `SHA256SUMS` keeps entries from other builds.
+#[test]
+fn write_checksums() {
+    let output = TempDir::new("output").unwrap();
+
+    let linux_bin = output.path().join("app");
+    let windows_bin = output.path().join("app.exe");
+
+    fs::write(&linux_bin, "linux").unwrap();
+    fs::write(&windows_bin, "windows").unwrap();
+
+    let linux_digest = hex::encode(helpers::calculate_checksum(&linux_bin).unwrap());
+    let windows_digest = hex::encode(helpers::calculate_checksum(&windows_bin).unwrap());
+
+    // Separate builds into the same directory should end up in the same file, sorted by name
+    checksums::write_checksums(std::slice::from_ref(&windows_bin), ChecksumFormat::Gnu).unwrap();
+    checksums::write_checksums(std::slice::from_ref(&linux_bin), ChecksumFormat::Gnu).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(output.path().join(CHECKSUMS_FILE_NAME)).unwrap(),
+        format!("{}  app\n{}  app.exe\n", linux_digest, windows_digest)
+    );
+
+    // Switching formats rewrites the existing entries in the new one
+    checksums::write_checksums(std::slice::from_ref(&linux_bin), ChecksumFormat::Bsd).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(output.path().join(CHECKSUMS_FILE_NAME)).unwrap(),
+        format!(
+            "SHA256 (app) = {}\nSHA256 (app.exe) = {}\n",
+            linux_digest, windows_digest
+        )
+    );
+
+    checksums::write_checksums(std::slice::from_ref(&windows_bin), ChecksumFormat::Gnu).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(output.path().join(CHECKSUMS_FILE_NAME)).unwrap(),
+        format!("{}  app\n{}  app.exe\n", linux_digest, windows_digest)
+    );
+
+    checksums::write_checksums(&[linux_bin], ChecksumFormat::Sidecar).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(output.path().join("app.sha256")).unwrap(),
+        format!("{}  app\n", linux_digest)
+    );
+}
+
+/// Test that detached GPG signatures are written next to each output, and that they verify.
+#[test]
+fn write_gpg_signatures() {
+    if !is_available("gpg") {
+        return;
+    }
+
+    let gnupg_home = TempDir::new("gnupg").unwrap();
+    let output = TempDir::new("output").unwrap();
+
+    // A throwaway keyring, so the test doesn't touch (or need) the user's keys
+    std::env::set_var("GNUPGHOME", gnupg_home.path());
+
+    let status = std::process::Command::new("gpg")
+        .args(["--batch", "--passphrase", "", "--quick-gen-key"])
+        .args([
+            "Jundler Test <test@jundler.invalid>",
+            "default",
+            "default",
+            "never",
+        ])
+        .output()
+        .unwrap()
+        .status;
+    assert!(status.success());
+
+    let binary = output.path().join("app");
+    fs::write(&binary, "app").unwrap();
+
+    let signatures = signatures::write_signatures(
+        std::slice::from_ref(&binary),
+        SignatureFormat::Gpg,
+        Some("test@jundler.invalid"),
+        output.path(),
+        &Default::default(),
+    )
+    .unwrap();
+
+    assert_eq!(signatures, vec![output.path().join("app.sig")]);
+
+    let verified = std::process::Command::new("gpg")
+        .args(["--batch", "--verify"])
+        .arg(&signatures[0])
+        .arg(&binary)
+        .output()
+        .unwrap()
+        .status;
+    assert!(verified.success());
+
+    // A tampered file shouldn't verify
+    fs::write(&binary, "tampered").unwrap();
+
+    let verified = std::process::Command::new("gpg")
+        .args(["--batch", "--verify"])
+        .arg(&signatures[0])
+        .arg(&binary)
+        .output()
+        .unwrap()
+        .status;
+    assert!(!verified.success());
+}
+
+/// Test that cosign writes Sigstore bundles, with a key from the configuration if there's one.
+#[test]
+fn cosign_signature_format() {
+    use clap::ValueEnum;
+
+    let format = SignatureFormat::from_str("cosign", true).unwrap();
+
+    assert_eq!(format, SignatureFormat::Cosign);
+    assert_eq!(format.tool(), "cosign");
+    assert_eq!(format.extension(), "sigstore.json");
+
+    let package_config: PackageConfig = serde_json::from_str(
+        r#"{"name":"app","jundler":{"signatures":{"cosignKey":"keys/cosign.key"}}}"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        package_config.jundler.signatures.cosign_key.as_deref(),
+        Some("keys/cosign.key")
+    );
+}
+
+/// Test that only the packages of included files are listed in the third-party notices, along with their licenses.
+#[test]
+fn third_party_notices() {
+    let project = TempDir::new("project").unwrap();
+
+    let packages = [
+        (
+            "node_modules/left-pad",
+            r#"{"name":"left-pad","version":"1.3.0","license":"WTFPL"}"#,
+        ),
+        (
+            "node_modules/@scope/pkg",
+            r#"{"name":"@scope/pkg","version":"2.0.0","license":{"type":"MIT"}}"#,
+        ),
+        (
+            "node_modules/unused",
+            r#"{"name":"unused","version":"1.0.0","license":"MIT"}"#,
+        ),
+    ];
+
+    for (dir, manifest) in packages {
+        fs::create_dir_all(project.path().join(dir).join("lib")).unwrap();
+        fs::write(project.path().join(dir).join("package.json"), manifest).unwrap();
+        fs::write(project.path().join(dir).join("lib/index.js"), "").unwrap();
+    }
+
+    fs::write(
+        project.path().join("node_modules/@scope/pkg/LICENSE.md"),
+        "MIT License text\n",
+    )
+    .unwrap();
+
+    let metafile = project.path().join("meta.json");
+    fs::write(
+        &metafile,
+        r#"{"inputs":{"index.js":{},"node_modules/left-pad/lib/index.js":{},"node_modules/@scope/pkg/lib/index.js":{},"(disabled):fs":{}}}"#,
+    )
+    .unwrap();
+
+    let included = licenses::included_packages(
+        project.path(),
+        &licenses::bundled_files(&metafile, project.path(), project.path()).unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        included
+            .iter()
+            .map(|p| (p.name.as_str(), p.license.as_deref()))
+            .collect::<Vec<_>>(),
+        vec![("@scope/pkg", Some("MIT")), ("left-pad", Some("WTFPL"))]
+    );
+
+    let notices = licenses::notices(project.path(), &included).unwrap();
+
+    assert!(notices.contains("@scope/pkg@2.0.0\nLicense: MIT"));
+    assert!(notices.contains("MIT License text"));
+    assert!(notices.contains("left-pad@1.3.0\nLicense: WTFPL"));
+    assert!(!notices.contains("unused"));
+
+    // Custom bundlers don't say what they bundled, so every installed package is included
+    let installed =
+        licenses::included_packages(project.path(), &licenses::installed_files(project.path()))
+            .unwrap();
+
+    assert_eq!(
+        installed
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["@scope/pkg", "left-pad", "unused"]
+    );
+}
+
+/// Test that release archives are named conventionally, and hold every entry in a top-level directory.
+#[test]
+fn create_archives() {
+    let project = TempDir::new("project").unwrap();
+
+    fs::write(project.path().join("app"), "binary").unwrap();
+    fs::write(project.path().join("README.md"), "readme").unwrap();
+    fs::write(project.path().join("LICENSE"), "license").unwrap();
+    fs::write(project.path().join("index.js"), "").unwrap();
+
+    assert_eq!(
+        archive::archive_stem("app", Some("v1.2.3"), Os::Linux, Some(Arch::X64)),
+        "app-v1.2.3-linux-x64"
+    );
+    assert_eq!(
+        archive::archive_stem("app", None, Os::MacOS, None),
+        "app-darwin-universal"
+    );
+
+    let mut entries = vec![archive::ArchiveEntry {
+        path: project.path().join("app"),
+        name: "app".into(),
+        executable: true,
+    }];
+
+    for path in archive::extra_files(project.path()).unwrap() {
+        entries.push(archive::ArchiveEntry {
+            name: path.file_name().unwrap().to_string_lossy().into(),
+            path,
+            executable: false,
+        });
+    }
+
+    let tar_gz = project.path().join("app.tar.gz");
+    archive::create_archive(&tar_gz, ArchiveFormat::TarGz, "app", &entries, Some(0)).unwrap();
+
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(File::open(&tar_gz).unwrap()));
+    let tar_entries = tar
+        .entries()
+        .unwrap()
+        .map(|e| {
+            let e = e.unwrap();
+            (
+                e.path().unwrap().to_string_lossy().to_string(),
+                e.header().mode().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        tar_entries,
+        vec![
+            ("app/app".to_string(), 0o755),
+            ("app/LICENSE".to_string(), 0o644),
+            ("app/README.md".to_string(), 0o644),
+        ]
+    );
+
+    let zip_path = project.path().join("app.zip");
+    archive::create_archive(&zip_path, ArchiveFormat::Zip, "app", &entries, None).unwrap();
+
+    let zip = zip::ZipArchive::new(File::open(&zip_path).unwrap()).unwrap();
+    assert_eq!(
+        zip.file_names().collect::<std::collections::BTreeSet<_>>(),
+        ["app/app", "app/LICENSE", "app/README.md"].into()
+    );
+}
+
+/// Test that build reports list every output with its size and checksum, and the steps without their emojis.
+#[test]
+fn build_report() {
+    use crate::ui::{Recording, Step};
+    use std::time::Duration;
+
+    let output_dir = TempDir::new("output").unwrap();
+    let app_path = output_dir.path().join("app");
+
+    fs::write(&app_path, "binary").unwrap();
+
+    let options = BuildOptions {
+        node_version: Version::new(22, 3, 0),
+        target_os: Os::Linux,
+        target_arch: Arch::Arm64,
+        bundle: true,
+        copy_strategy: CopyStrategy::Auto,
+        force: false,
+        output_dir: None,
+        package_manager: None,
+        runtime: Runtime::Node,
+        strip: false,
+        universal: false,
+        reproducible: false,
+        checksums: None,
+        licenses: false,
+        embed_licenses: false,
+        archive: None,
+        codesign_identity: None,
+        notarize: false,
+        sign: true,
+        sign_certificate: None,
+        windows_sign: WindowsSignOptions::default(),
+        provenance: false,
+        signatures: None,
+        signing_key: None,
+        manifests: false,
+        release_url: None,
+        linux_packages: vec![],
+        msi: false,
+        docker_image: None,
+        output_name: None,
+        frozen: false,
+        pass_env: vec![],
+        npmrc: None,
+        node_advisories: AdvisoryPolicy::default(),
+        rebuild_native: false,
+        native_toolchain: None,
+        test_exec: None,
+        max_size: None,
+        max_size_policy: size::MaxSizePolicy::default(),
+    };
+
+    let output = BuildOutput {
+        app_path: app_path.clone(),
+        files: vec![OutputFile::read(app_path.clone()).unwrap()],
+        target_os: options.target_os,
+        target_arch: options.target_arch,
+        universal: options.universal,
+        runtime: options.runtime,
+        node_version: options.node_version.clone(),
+        cached: true,
+        bundled: true,
+        esbuild_version: Some(ESBUILD_VERSION),
+        bundle: None,
+        image: None,
+        min_glibc: None,
+        size: None,
+        steps: vec![],
+        warnings: vec![],
+        duration: Duration::from_millis(1800),
+    };
+
+    let report = report::BuildReport::new(
+        vec![report::TargetReport::new(&output)],
+        Recording {
+            steps: vec![Step {
+                message: COPY_PROJ_MSG.to_string(),
+                depth: 0,
+                duration: Duration::from_millis(1500),
+            }],
+            warnings: vec!["Something's off".to_string()],
+        },
+        Duration::from_secs(2),
+    );
+
+    let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+
+    assert_eq!(json["builds"][0]["os"], "linux");
+    assert_eq!(json["builds"][0]["arch"], "arm64");
+    assert_eq!(json["builds"][0]["runtime"], "node");
+    assert_eq!(json["builds"][0]["runtime_version"], "22.3.0");
+    assert_eq!(json["builds"][0]["node_version"], "22.3.0");
+    assert_eq!(json["builds"][0]["cached"], true);
+    assert_eq!(
+        json["builds"][0]["esbuild_version"],
+        ESBUILD_VERSION.to_string()
+    );
+    assert_eq!(json["builds"][0]["duration_ms"], 1800);
+    assert_eq!(json["builds"][0]["outputs"][0]["size"], 6);
+    assert_eq!(
+        json["builds"][0]["outputs"][0]["sha256"],
+        hex::encode(helpers::calculate_checksum(&app_path).unwrap())
+    );
+    assert_eq!(
+        json["steps"][0]["name"],
+        "Copying project and preparing for build"
+    );
+    assert_eq!(json["steps"][0]["duration_ms"], 1500);
+    assert_eq!(json["warnings"][0], "Something's off");
+    assert_eq!(json["duration_ms"], 2000);
+}
+
+/// Test that Node.js versions from `.nvmrc` and `engines.node` are parsed like nvm and npm do.
+#[test]
+fn parse_node_version_spec() {
+    use node_version::VersionSpec;
+
+    assert_eq!(
+        VersionSpec::parse("v20.11.0\n").unwrap(),
+        VersionSpec::Exact(Version::new(20, 11, 0))
+    );
+    assert_eq!(VersionSpec::parse("lts/*").unwrap(), VersionSpec::Lts(None));
+    assert_eq!(
+        VersionSpec::parse("lts/Iron").unwrap(),
+        VersionSpec::Lts(Some("iron".to_string()))
+    );
+    assert_eq!(VersionSpec::parse("node").unwrap(), VersionSpec::Latest);
+
+    let VersionSpec::Range(reqs) = VersionSpec::parse(">= 18.5 <19 || ^20 || 22.x").unwrap() else {
+        panic!("Expected a range!");
+    };
+
+    let matches = |version| reqs.iter().any(|req| req.matches(&version));
+
+    assert!(matches(Version::new(18, 5, 0)));
+    assert!(!matches(Version::new(18, 4, 0)));
+    assert!(!matches(Version::new(19, 0, 0)));
+    assert!(matches(Version::new(20, 1, 0)));
+    assert!(matches(Version::new(22, 3, 0)));
+
+    // A `v` in front of the version in a comparator, like npm allows
+    let VersionSpec::Range(reqs) = VersionSpec::parse(">=v18 <v20 || v22").unwrap() else {
+        panic!("Expected a range!");
+    };
+
+    let matches = |version| reqs.iter().any(|req| req.matches(&version));
+
+    assert!(matches(Version::new(18, 0, 0)));
+    assert!(matches(Version::new(19, 9, 0)));
+    assert!(!matches(Version::new(20, 0, 0)));
+    assert!(matches(Version::new(22, 3, 0)));
+
+    // Bare partial versions are any version starting with them, and bare full ones are exact
+    let VersionSpec::Range(reqs) = VersionSpec::parse("18.2 || 20.1.0 || v21.x").unwrap() else {
+        panic!("Expected a range!");
+    };
+
+    let matches = |version| reqs.iter().any(|req| req.matches(&version));
+
+    assert!(matches(Version::new(18, 2, 5)));
+    assert!(!matches(Version::new(18, 3, 0)));
+    assert!(matches(Version::new(20, 1, 0)));
+    assert!(!matches(Version::new(20, 1, 1)));
+    assert!(matches(Version::new(21, 4, 0)));
+
+    assert!(VersionSpec::parse("not a version").is_err());
+}
+
+/// Test that the closest `.nvmrc` pins the Node.js version without downloading the release index, and that ranges
+/// are resolved against the index from the downloader.
+#[test]
+fn resolve_node_version_from_nvmrc() {
+    let project = TempDir::new("project").unwrap();
+    let fixtures = TempDir::new("fixtures").unwrap();
+    let downloader = downloader::FileDownloader::new(fixtures.path().to_path_buf());
+
+    fs::write(project.path().join(".nvmrc"), "20.11.0\n").unwrap();
+    fs::write(
+        project.path().join("package.json"),
+        r#"{"name":"app","engines":{"node":">=18"}}"#,
+    )
+    .unwrap();
+
+    let (version, source) =
+        node_version::resolve_node_version(project.path(), &downloader).unwrap();
+
+    assert_eq!(version, Version::new(20, 11, 0));
+    assert_eq!(
+        source,
+        node_version::VersionSource::VersionFile(project.path().join(".nvmrc"))
+    );
+
+    fs::remove_file(project.path().join(".nvmrc")).unwrap();
+    fs::create_dir_all(fixtures.path().join("nodejs.org/dist")).unwrap();
+    fs::write(
+        fixtures.path().join("nodejs.org/dist/index.json"),
+        r#"[{"version":"v22.3.0","lts":false},{"version":"v20.14.0","lts":"Iron"},{"version":"v16.20.2","lts":"Gallium"}]"#,
+    )
+    .unwrap();
+
+    let (version, _) = node_version::resolve_node_version(project.path(), &downloader).unwrap();
+
+    assert_eq!(version, Version::new(22, 3, 0));
+}
+
+/// Test that version information and a manifest are written into the resources of a Windows executable, taking
+/// defaults from `package.json`.
+#[test]
+fn windows_resources() {
+    let package_config: PackageConfig = serde_json::from_str(
+        r#"{
+            "name": "app",
+            "version": "1.2.3",
+            "author": "Jane Doe <jane@example.com>",
+            "jundler": { "windows": { "fileVersion": "1.2.3.4", "longPathAware": true } }
+        }"#,
+    )
+    .unwrap();
+
+    let config = &package_config.jundler.windows;
+    let version_info = VersionInfo::new(&package_config, config);
+
+    assert_eq!(version_info.file_version, "1.2.3.4");
+    assert_eq!(version_info.product_version, "1.2.3");
+    assert_eq!(version_info.strings["CompanyName"], "Jane Doe");
+    assert_eq!(version_info.strings["FileDescription"], "app");
+    assert_eq!(version_info.strings["OriginalFilename"], "app.exe");
+
+    let project = TempDir::new("project").unwrap();
+    let manifest = windows_resources::manifest(project.path(), config)
+        .unwrap()
+        .unwrap();
+
+    assert!(String::from_utf8_lossy(&manifest).contains("<longPathAware"));
+
+    // A minimal 64-bit executable, with a single section and no resources
+    let mut image = vec![0u8; 0x400];
+    let mut put =
+        |offset: usize, bytes: &[u8]| image[offset..offset + bytes.len()].copy_from_slice(bytes);
+
+    put(0, b"MZ");
+    put(0x3C, &0x40u32.to_le_bytes());
+    put(0x40, b"PE\0\0");
+    put(0x44, &0x8664u16.to_le_bytes()); // Machine
+    put(0x46, &1u16.to_le_bytes()); // Number of sections
+    put(0x54, &240u16.to_le_bytes()); // Size of the optional header
+    put(0x58, &0x20Bu16.to_le_bytes()); // PE32+
+    put(0x58 + 32, &0x1000u32.to_le_bytes()); // Section alignment
+    put(0x58 + 36, &0x200u32.to_le_bytes()); // File alignment
+    put(0x58 + 56, &0x2000u32.to_le_bytes()); // Size of image
+    put(0x58 + 60, &0x200u32.to_le_bytes()); // Size of headers
+    put(0x58 + 108, &16u32.to_le_bytes()); // Number of data directories
+    put(0x148, b".text\0\0\0");
+    put(0x148 + 8, &0x10u32.to_le_bytes()); // Virtual size
+    put(0x148 + 12, &0x1000u32.to_le_bytes()); // Virtual address
+    put(0x148 + 16, &0x200u32.to_le_bytes()); // Raw size
+    put(0x148 + 20, &0x200u32.to_le_bytes()); // Raw pointer
+
+    let binary = project.path().join("app.exe");
+    fs::write(&binary, &image).unwrap();
+
+    windows_resources::set_resources(&binary, &version_info, Some(&manifest)).unwrap();
+
+    let written = fs::read(&binary).unwrap();
+    let contains = |needle: &[u8]| written.windows(needle.len()).any(|window| window == needle);
+
+    assert_eq!(u16::from_le_bytes([written[0x46], written[0x47]]), 2);
+    assert_eq!(&written[0x170..0x178], b".rsrc\0\0\0");
+    assert_eq!(
+        &written[0x58 + 112 + 16..0x58 + 112 + 20],
+        &0x2000u32.to_le_bytes()
+    );
+    assert_eq!(written.len() % 0x200, 0);
+    assert!(contains(&version_info.to_resource()));
+    assert!(contains(&manifest));
+
+    // The resource section is now the last one, so it's rewritten instead of adding another
+    windows_resources::set_resources(&binary, &version_info, None).unwrap();
+
+    let rewritten = fs::read(&binary).unwrap();
+
+    assert_eq!(u16::from_le_bytes([rewritten[0x46], rewritten[0x47]]), 2);
+    assert_eq!(rewritten, written);
+}
+
+/// Test that the container build context has the binary and a Dockerfile for the right platform and base image.
+#[test]
+fn container_context() {
+    let package_config: PackageConfig = serde_json::from_str(
+        r#"{
+            "name": "app",
+            "version": "1.2.3",
+            "description": "Says \"hello\"",
+            "jundler": { "docker": { "baseImage": "debian:bookworm-slim" } }
+        }"#,
+    )
+    .unwrap();
+
+    let output = TempDir::new("output").unwrap();
+    let app_path = output.path().join("app");
+    fs::write(&app_path, "app").unwrap();
+
+    let context_dir = output.path().join(container::context_dir_name("app"));
+    container::write_context(&context_dir, &app_path, &package_config, Arch::Arm64).unwrap();
+
+    assert_eq!(fs::read_to_string(context_dir.join("app")).unwrap(), "app");
+    assert_eq!(
+        fs::read_to_string(context_dir.join("Dockerfile")).unwrap(),
+        "# Generated by jundler. Build it with `docker build --platform linux/arm64 .`\n\
+         FROM --platform=linux/arm64 debian:bookworm-slim\n\
+         LABEL org.opencontainers.image.title=\"app\" \\\n      \
+         org.opencontainers.image.version=\"1.2.3\" \\\n      \
+         org.opencontainers.image.description=\"Says \\\"hello\\\"\"\n\
+         COPY app /app\n\
+         ENTRYPOINT [\"/app\"]\n"
+    );
+}
+
+/// Test that `.deb` packages have the metadata from `package.json` and install the binary to `/usr/bin`.
+#[test]
+fn write_deb_package() {
+    let package_config: PackageConfig = serde_json::from_str(
+        r#"{
+            "name": "@acme/my_app",
+            "version": "1.2.3-beta.1",
+            "description": "Does things",
+            "license": "MIT",
+            "author": { "name": "Jane Doe", "email": "jane@example.com" }
+        }"#,
+    )
+    .unwrap();
+
+    let metadata =
+        linux_packages::PackageMetadata::new(&package_config, LinuxPackageFormat::Deb).unwrap();
+
+    assert_eq!(metadata.name, "acme-my-app");
+    assert_eq!(metadata.version, "1.2.3~beta.1");
+    assert_eq!(
+        metadata.maintainer.as_deref(),
+        Some("Jane Doe <jane@example.com>")
+    );
+    assert_eq!(
+        metadata.file_name(LinuxPackageFormat::Deb, Arch::X64),
+        "acme-my-app_1.2.3~beta.1_amd64.deb"
+    );
+    assert_eq!(
+        metadata.file_name(LinuxPackageFormat::Rpm, Arch::Arm64),
+        "acme-my-app-1.2.3~beta.1-1.aarch64.rpm"
+    );
+
+    if !is_available("dpkg-deb") {
+        return;
+    }
+
+    let output = TempDir::new("output").unwrap();
+    let app_path = output.path().join("my_app");
+    let deb_path = output.path().join("app.deb");
+    fs::write(&app_path, "app").unwrap();
+
+    linux_packages::write_deb(
+        &deb_path,
+        &app_path,
+        "my_app",
+        &metadata,
+        Arch::X64,
+        315532800,
+    )
+    .unwrap();
+
+    let dpkg_deb = |args: &[&str]| {
+        let output = std::process::Command::new("dpkg-deb")
+            .args(args)
+            .arg(&deb_path)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    assert_eq!(
+        dpkg_deb(&["--field"]).lines().take(4).collect::<Vec<_>>(),
+        vec![
+            "Package: acme-my-app",
+            "Version: 1.2.3~beta.1",
+            "Architecture: amd64",
+            "Maintainer: Jane Doe <jane@example.com>"
+        ]
+    );
+
+    let contents = dpkg_deb(&["--contents"]);
+    assert!(contents
+        .lines()
+        .any(|line| line.starts_with("-rwxr-xr-x root/root") && line.ends_with(" usr/bin/my_app")));
+    assert!(contents.contains(" usr/share/doc/acme-my-app/copyright"));
+}
+
+/// Test that MSI versions are made valid, and that the installer source has the project's metadata.
+#[test]
+fn msi_installer_source() {
+    assert_eq!(msi::msi_version("v1.2.3-beta.1+build.5").unwrap(), "1.2.3");
+    assert!(msi::msi_version("1.2").is_err());
+    assert!(msi::msi_version("256.0.0").is_err());
+
+    // The upgrade code has to stay the same across versions
+    assert_eq!(msi::name_guid("app"), msi::name_guid("app"));
+    assert_ne!(msi::name_guid("app"), msi::name_guid("other-app"));
+    assert_eq!(&msi::name_guid("app")[14..15], "5");
+
+    let package_config: PackageConfig = serde_json::from_str(
+        r#"{
+            "name": "app",
+            "version": "1.2.3",
+            "author": "Jane & Co <jane@example.com>",
+            "jundler": { "msi": { "productName": "App", "addToPath": false } }
+        }"#,
+    )
+    .unwrap();
+
+    let wxs = msi::wxs(&package_config, Path::new("app.exe"), Arch::X64).unwrap();
+
+    assert!(
+        wxs.contains(r#"Name="App" Language="1033" Version="1.2.3" Manufacturer="Jane &amp; Co""#)
+    );
+    assert!(wxs.contains(&format!(r#"UpgradeCode="{}""#, msi::name_guid("app"))));
+    assert!(wxs.contains(r#"<Directory Id="ProgramFiles64Folder">"#));
+    assert!(wxs.contains(
+        r#"<File Id="MainExecutableFile" Name="app.exe" Source="app.exe" KeyPath="yes" />"#
+    ));
+    assert!(!wxs.contains("<Environment"));
+}
+
+/// Test that `jundler.toml` replaces the `jundler` section of `package.json`.
+#[test]
+fn jundler_toml_config() {
+    let project = TempDir::new("project").unwrap();
+
+    fs::write(
+        project.path().join("sea-config.json"),
+        r#"{ "main": "index.js", "output": "sea-prep.blob" }"#,
+    )
+    .unwrap();
+    fs::write(
+        project.path().join("package.json"),
+        r#"{ "name": "app", "jundler": { "msi": { "productName": "From package.json" } } }"#,
+    )
+    .unwrap();
+
+    let (_, package_config) = get_configs(project.path()).unwrap();
+    assert_eq!(
+        package_config.jundler.msi.product_name.as_deref(),
+        Some("From package.json")
+    );
+
+    fs::write(
+        project.path().join("jundler.toml"),
+        "[msi]\nproductName = \"From jundler.toml\"\naddToPath = false\n",
+    )
+    .unwrap();
+
+    let (_, package_config) = get_configs(project.path()).unwrap();
+    assert_eq!(
+        package_config.jundler.msi.product_name.as_deref(),
+        Some("From jundler.toml")
+    );
+    assert_eq!(package_config.jundler.msi.add_to_path, Some(false));
+}
+
+/// Test that the Homebrew formula and Scoop manifest point at every release archive, with their checksums.
+#[test]
+fn write_package_manifests() {
+    let package_config: PackageConfig = serde_json::from_str(
+        r#"{ "name": "my-tool", "version": "1.2.3", "description": "Does \"things\"", "license": "MIT" }"#,
+    )
+    .unwrap();
+
+    let output = TempDir::new("output").unwrap();
+    let url = "https://example.com/releases/v{version}/";
+
+    // Nothing to write without any archives
+    assert!(
+        manifests::write_manifests(output.path(), &package_config, url)
+            .unwrap()
+            .is_empty()
+    );
+
+    for (name, contents) in [
+        ("my-tool-v1.2.3-darwin-arm64.tar.gz", "mac arm"),
+        ("my-tool-v1.2.3-darwin-x64.tar.gz", "mac intel"),
+        ("my-tool-v1.2.3-win-x64.zip", "windows"),
+        // Archives of other versions are left out
+        ("my-tool-v1.2.2-linux-x64.tar.gz", "old linux"),
+    ] {
+        fs::write(output.path().join(name), contents).unwrap();
+    }
+
+    let digest =
+        |name: &str| hex::encode(helpers::calculate_checksum(&output.path().join(name)).unwrap());
+
+    let written = manifests::write_manifests(output.path(), &package_config, url).unwrap();
+    assert_eq!(
+        written,
+        vec![
+            output.path().join("my-tool.rb"),
+            output.path().join("my-tool.json")
+        ]
+    );
+
+    assert_eq!(
+        fs::read_to_string(output.path().join("my-tool.rb")).unwrap(),
+        format!(
+            r#"class MyTool < Formula
+  desc "Does \"things\""
+  version "1.2.3"
+  license "MIT"
+
+  on_macos do
+    if Hardware::CPU.arm?
+      url "https://example.com/releases/v1.2.3/my-tool-v1.2.3-darwin-arm64.tar.gz"
+      sha256 "{}"
+    else
+      url "https://example.com/releases/v1.2.3/my-tool-v1.2.3-darwin-x64.tar.gz"
+      sha256 "{}"
+    end
+  end
+
+  def install
+    bin.install "my-tool"
+  end
+end
+"#,
+            digest("my-tool-v1.2.3-darwin-arm64.tar.gz"),
+            digest("my-tool-v1.2.3-darwin-x64.tar.gz")
+        )
+    );
+
+    let scoop: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(output.path().join("my-tool.json")).unwrap())
+            .unwrap();
+
+    assert_eq!(scoop["version"], "1.2.3");
+    assert_eq!(scoop["bin"], "my-tool.exe");
+    assert_eq!(
+        scoop["architecture"]["64bit"]["url"],
+        "https://example.com/releases/v1.2.3/my-tool-v1.2.3-win-x64.zip"
+    );
+    assert_eq!(
+        scoop["architecture"]["64bit"]["hash"],
+        digest("my-tool-v1.2.3-win-x64.zip")
+    );
+    assert_eq!(
+        scoop["architecture"]["64bit"]["extract_dir"],
+        "my-tool-v1.2.3-win-x64"
+    );
+}
+
+/// Test that a single script is turned into a project named after it, that builds the script.
+#[test]
+fn script_project() {
+    let scripts = TempDir::new("scripts").unwrap();
+    let script = scripts.path().join("hello-world.ts");
+    fs::write(&script, "console.log('Hello, world!');\n").unwrap();
+
+    assert_eq!(script::script_name(&script).unwrap(), "hello-world");
+
+    let project = TempDir::new("project").unwrap();
+    script::write_script_project(&script, project.path()).unwrap();
+
+    let (sea_config, package_config) = get_configs(project.path()).unwrap();
+
+    assert_eq!(package_config.name, "hello-world");
+    assert_eq!(package_config.main.as_deref(), Some("hello-world.ts"));
+    assert_eq!(sea_config.main, "hello-world.ts");
+    assert_eq!(
+        fs::read_to_string(project.path().join("hello-world.ts")).unwrap(),
+        "console.log('Hello, world!');\n"
+    );
+}
+
+/// Test that the command of an npm package is found from its `bin` field, and that the package is turned into a
+/// project that builds it.
+#[test]
+fn npm_package_project() {
+    use serde_json::json;
+
+    assert_eq!(
+        npm_package::resolve_bin(&json!({"name": "@scope/tool", "bin": "./cli.js"})).unwrap(),
+        ("tool".to_string(), "./cli.js".to_string())
+    );
+    assert_eq!(
+        npm_package::resolve_bin(
+            &json!({"name": "cowsay", "bin": {"cowsay": "./cli.js", "cowthink": "./cli.js"}})
+        )
+        .unwrap(),
+        ("cowsay".to_string(), "./cli.js".to_string())
+    );
+    assert_eq!(
+        npm_package::resolve_bin(&json!({"name": "typescript-cli", "bin": {"tsc": "bin/tsc"}}))
+            .unwrap(),
+        ("tsc".to_string(), "bin/tsc".to_string())
+    );
+    assert!(
+        npm_package::resolve_bin(&json!({"name": "tools", "bin": {"a": "a.js", "b": "b.js"}}))
+            .is_err()
+    );
+    assert!(npm_package::resolve_bin(&json!({"name": "library", "main": "index.js"})).is_err());
+
+    let project = TempDir::new("project").unwrap();
+    fs::write(
+        project.path().join("package.json"),
+        json!({
+            "name": "cowsay",
+            "version": "1.6.0",
+            "main": "index.js",
+            "bin": {"cowsay": "./cli.js"},
+            "scripts": {"postinstall": "node setup.js"},
+            "devDependencies": {"mocha": "^10.0.0"},
+        })
+        .to_string(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        npm_package::prepare_project(project.path()).unwrap(),
+        "cowsay"
+    );
+
+    let (sea_config, package_config) = get_configs(project.path()).unwrap();
+    let package_json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(project.path().join("package.json")).unwrap())
+            .unwrap();
+
+    assert_eq!(package_config.name, "cowsay");
+    assert_eq!(package_config.version.as_deref(), Some("1.6.0"));
+    assert_eq!(package_config.main.as_deref(), Some("cli.js"));
+    assert_eq!(sea_config.main, "cli.js");
+    assert!(package_json.get("scripts").is_none());
+    assert!(package_json.get("devDependencies").is_none());
+}
+
+/// Test that git URLs are told apart from paths, and that a project is cloned at the tag after the `#`.
+#[test]
+fn clone_git_source() {
+    assert_eq!(
+        git_source::GitSource::parse("https://github.com/org/repo#v1.2.3"),
+        Some(git_source::GitSource {
+            url: "https://github.com/org/repo".to_string(),
+            reference: Some("v1.2.3".to_string()),
+        })
+    );
+    assert_eq!(
+        git_source::GitSource::parse("git+ssh://git@github.com/org/repo.git"),
+        Some(git_source::GitSource {
+            url: "ssh://git@github.com/org/repo.git".to_string(),
+            reference: None,
+        })
+    );
+    assert!(git_source::GitSource::parse("git@github.com:org/repo.git").is_some());
+    assert_eq!(git_source::GitSource::parse("./my-project"), None);
+    assert_eq!(git_source::GitSource::parse("scripts/tool.ts"), None);
+
+    if !is_available("git") {
+        return;
+    }
+
+    let repo = TempDir::new("repo").unwrap();
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(repo.path())
+            .status()
+            .unwrap();
+
+        assert!(status.success());
+    };
+
+    git(&["init", "--quiet"]);
+    fs::write(repo.path().join("index.js"), "console.log(1);\n").unwrap();
+    git(&["add", "."]);
+    git(&["commit", "--quiet", "-m", "First"]);
+    git(&["tag", "v1.0.0"]);
+    fs::write(repo.path().join("index.js"), "console.log(2);\n").unwrap();
+    git(&["commit", "--quiet", "-am", "Second"]);
+
+    let cache_dir = TempDir::new("cache").unwrap();
+    let mut builder = Builder::new(cache_dir.path().to_path_buf()).unwrap();
+    let url = format!("file://{}", repo.path().display());
+    let tagged_source = git_source::GitSource::parse(&format!("{}#v1.0.0", url)).unwrap();
+
+    let tagged = builder.clone_git(&tagged_source).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(tagged.join("index.js")).unwrap(),
+        "console.log(1);\n"
+    );
+
+    let latest = builder
+        .clone_git(&git_source::GitSource::parse(&url).unwrap())
+        .unwrap();
+
+    assert_eq!(
+        fs::read_to_string(latest.join("index.js")).unwrap(),
+        "console.log(2);\n"
+    );
+
+    // The same repository and reference is cloned into the same directory every time, even by another builder, so
+    // it's cached like any other project
+    let mut builder = Builder::new(cache_dir.path().to_path_buf()).unwrap();
+
+    assert_eq!(builder.clone_git(&tagged_source).unwrap(), tagged);
+    assert_ne!(tagged, latest);
+}
+
+/// Test that output names are filled in from the template, and that binaries named by it are kept out of the build.
+#[test]
+fn output_name_template() {
+    let package_config: PackageConfig = serde_json::from_str(
+        r#"{
+            "name": "mytool",
+            "version": "v1.2.3",
+            "jundler": { "outputName": "{name}-{version}-{os}-{arch}{ext}" }
+        }"#,
+    )
+    .unwrap();
+
+    let template = package_config.jundler.output_name.as_deref().unwrap();
+
+    assert_eq!(
+        output_name::render(template, &package_config, Os::Linux, Some(Arch::X64)).unwrap(),
+        "mytool-1.2.3-linux-x64"
+    );
+    assert_eq!(
+        output_name::render(template, &package_config, Os::Windows, Some(Arch::Arm64)).unwrap(),
+        "mytool-1.2.3-win-arm64.exe"
+    );
+    assert_eq!(
+        output_name::render(template, &package_config, Os::MacOS, None).unwrap(),
+        "mytool-1.2.3-darwin-universal"
+    );
+    assert_eq!(
+        output_name::render(
+            output_name::DEFAULT_TEMPLATE,
+            &package_config,
+            Os::Windows,
+            Some(Arch::X64)
+        )
+        .unwrap(),
+        "mytool.exe"
+    );
+    assert!(output_name::render("{name}-{target}", &package_config, Os::Linux, None).is_err());
+    assert!(output_name::render("{name", &package_config, Os::Linux, None).is_err());
+    assert!(output_name::render("bin/{name}", &package_config, Os::Linux, None).is_err());
+
+    assert_eq!(output_name::glob(template, &package_config), "mytool-*-*-*");
+    assert_eq!(
+        output_name::command_name(&package_config, Os::Windows),
+        "mytool.exe"
+    );
+
+    let project = TempDir::new("project").unwrap();
+    fs::write(project.path().join("index.js"), "").unwrap();
+    fs::write(project.path().join("mytool-1.2.3-linux-x64"), "").unwrap();
+
+    let options = BuildOptions {
+        node_version: Version::new(20, 0, 0),
+        target_os: Os::Linux,
+        target_arch: Arch::X64,
+        bundle: false,
+        copy_strategy: CopyStrategy::default(),
+        force: false,
+        output_dir: None,
+        package_manager: None,
+        runtime: Runtime::Node,
+        strip: false,
+        universal: false,
+        reproducible: false,
+        checksums: None,
+        licenses: false,
+        embed_licenses: false,
+        archive: None,
+        codesign_identity: None,
+        notarize: false,
+        sign: true,
+        sign_certificate: None,
+        windows_sign: WindowsSignOptions::default(),
+        provenance: false,
+        signatures: None,
+        signing_key: None,
+        manifests: false,
+        release_url: None,
+        linux_packages: vec![],
+        msi: false,
+        docker_image: None,
+        output_name: None,
+        frozen: false,
+        pass_env: vec![],
+        npmrc: None,
+        node_advisories: AdvisoryPolicy::default(),
+        rebuild_native: false,
+        native_toolchain: None,
+        test_exec: None,
+        max_size: None,
+        max_size_policy: size::MaxSizePolicy::default(),
+    };
+
+    let copied = TempDir::new("copied").unwrap();
+    copy::copy_project(
+        project.path(),
+        copied.path(),
+        &output_excludes(&package_config, &options),
+        CopyStrategy::Copy,
+    )
+    .unwrap();
+
+    assert!(copied.path().join("index.js").exists());
+    assert!(!copied.path().join("mytool-1.2.3-linux-x64").exists());
+
+    // A cache directory in the project is left out too
+    let package_config: PackageConfig =
+        serde_json::from_str(r#"{ "name": "mytool", "jundler": { "cacheDir": "./.cache/" } }"#)
+            .unwrap();
+
+    assert!(output_excludes(&package_config, &options).contains(&"/.cache".to_string()));
+}
+
+/// Test that targets are parsed from `<os>-<arch>` names and Rust-style triples.
+#[test]
+fn parse_target() {
+    let target = |target: &str| target.parse::<platforms::Target>();
+
+    assert_eq!(
+        target("linux-x64").unwrap(),
+        platforms::Target {
+            os: Os::Linux,
+            arch: Arch::X64
+        }
+    );
+    assert_eq!(
+        target("darwin-arm64").unwrap(),
+        platforms::Target {
+            os: Os::MacOS,
+            arch: Arch::Arm64
+        }
+    );
+    assert_eq!(
+        target("win-x86").unwrap(),
+        platforms::Target {
+            os: Os::Windows,
+            arch: Arch::X86
+        }
+    );
+    assert_eq!(
+        target("x86_64-unknown-linux-gnu").unwrap(),
+        platforms::Target {
+            os: Os::Linux,
+            arch: Arch::X64
+        }
+    );
+    assert_eq!(
+        target("aarch64-apple-darwin").unwrap(),
+        platforms::Target {
+            os: Os::MacOS,
+            arch: Arch::Arm64
+        }
+    );
+    assert_eq!(
+        target("i686-pc-windows-msvc").unwrap(),
+        platforms::Target {
+            os: Os::Windows,
+            arch: Arch::X86
+        }
+    );
+    assert_eq!(target("Linux-ARM64").unwrap().to_string(), "linux-arm64");
+
+    assert!(target("linux").is_err());
+    assert!(target("x64").is_err());
+    assert!(target("linux-windows-x64").is_err());
+    assert!(target("freebsd-x64").is_err());
+}
+
+/// Test that OSes and architectures are parsed from every usual spelling, and serialized by name (except in the
+/// binary lockfile, where they're indexed like they always were).
+#[test]
+fn parse_os_and_arch() {
+    for (spelling, os) in [
+        ("darwin", Os::MacOS),
+        ("macOS", Os::MacOS),
+        ("linux", Os::Linux),
+        ("win", Os::Windows),
+        ("win32", Os::Windows),
+        ("Windows", Os::Windows),
+    ] {
+        assert_eq!(spelling.parse::<Os>().unwrap(), os);
+        assert_eq!(os.to_string().parse::<Os>().unwrap(), os);
+    }
+
+    for (spelling, arch) in [
+        ("x64", Arch::X64),
+        ("x86_64", Arch::X64),
+        ("ia32", Arch::X86),
+        ("arm64", Arch::Arm64),
+        ("AArch64", Arch::Arm64),
+    ] {
+        assert_eq!(spelling.parse::<Arch>().unwrap(), arch);
+        assert_eq!(arch.to_string().parse::<Arch>().unwrap(), arch);
+    }
+
+    assert!("freebsd".parse::<Os>().is_err());
+    assert!("riscv64".parse::<Arch>().is_err());
+
+    assert_eq!(
+        serde_json::to_string(&(Os::MacOS, Arch::Arm64)).unwrap(),
+        r#"["darwin","arm64"]"#
+    );
+    assert_eq!(
+        serde_json::from_str::<(Os, Arch)>(r#"["win32","x86_64"]"#).unwrap(),
+        (Os::Windows, Arch::X64)
+    );
+    assert!(serde_json::from_str::<Os>(r#""freebsd""#).is_err());
+
+    // The node manager's lockfile has always stored variant indexes
+    assert_eq!(
+        bincode::serialize(&(Os::Windows, Arch::Arm64)).unwrap(),
+        [2, 0, 0, 0, 2, 0, 0, 0]
+    );
+    assert_eq!(
+        bincode::deserialize::<(Os, Arch)>(&[1, 0, 0, 0, 1, 0, 0, 0]).unwrap(),
+        (Os::Linux, Arch::X86)
+    );
+
+    let target = |target: &str| target.parse::<platforms::Target>().unwrap().target_tuple();
+
+    assert_eq!(target("linux-x64"), "x86_64-unknown-linux-gnu");
+    assert_eq!(target("darwin-arm64"), "aarch64-apple-darwin");
+    assert_eq!(target("win-x86"), "i686-pc-windows-msvc");
+}
+
+/// Test that binaries are pinned in `jundler.lock`, and that frozen builds only use what's already pinned.
+#[test]
+fn project_lock() {
+    let project = TempDir::new("project").unwrap();
+    fs::write(
+        project.path().join("package.json"),
+        r#"{"name":"app","engines":{"node":">=20"}}"#,
+    )
+    .unwrap();
+
+    let binary = project.path().join("node");
+    fs::write(&binary, "node").unwrap();
+
+    // Frozen builds can't look up the latest matching release without a pinned version
+    assert!(node_version::resolve_locked_node_version(project.path()).is_err());
+    assert!(ProjectLock::load(project.path()).unwrap().is_none());
+
+    let name = node_binary_name(&Version::new(20, 11, 0), Os::Linux, Arch::X64);
+    assert_eq!(name, "node-v20.11.0-linux-x64");
+
+    let mut lock = ProjectLock::new(project.path());
+
+    // Frozen builds can't pin anything new
+    assert!(lock.pin(&name, &binary, true).is_err());
+    assert!(lock.pin(&name, &binary, false).unwrap());
+    assert!(!lock.pin(&name, &binary, true).unwrap());
+
+    lock.node_version = Some(Version::new(20, 11, 0));
+    lock.save().unwrap();
+
+    let loaded = ProjectLock::load(project.path()).unwrap().unwrap();
+    assert_eq!(loaded, lock);
+
+    let (version, source) = node_version::resolve_locked_node_version(project.path()).unwrap();
+    assert_eq!(version, Version::new(20, 11, 0));
+    assert_eq!(source, node_version::VersionSource::Lockfile);
+
+    // A binary that changed since it was pinned is always an error
+    fs::write(&binary, "tampered").unwrap();
+    assert!(lock.pin(&name, &binary, false).is_err());
+
+    // The pinned version has to match the project's own
+    fs::write(project.path().join(".nvmrc"), "18\n").unwrap();
+    assert!(node_version::resolve_locked_node_version(project.path()).is_err());
+
+    fs::write(project.path().join(".nvmrc"), "22.1.0\n").unwrap();
+    assert_eq!(
+        node_version::resolve_locked_node_version(project.path())
+            .unwrap()
+            .0,
+        Version::new(22, 1, 0)
+    );
+}
+
+/// Test that logged command lines quote arguments with spaces in them.
+#[test]
+fn logged_command_line() {
+    let mut cmd = std::process::Command::new("node");
+    cmd.arg("--experimental-sea-config")
+        .arg("my project/sea-config.json")
+        .arg("");
+
+    assert_eq!(
+        helpers::command_line(&cmd),
+        r#"node --experimental-sea-config "my project/sea-config.json" """#
+    );
+}
+
+/// Test that build commands only get allowed variables and those passed through, along with what's set on them.
+#[test]
+fn sanitized_environment() {
+    use environment::{is_allowed, SanitizedEnv};
+    use std::ffi::OsStr;
+
+    let pass_env = vec!["NPM_TOKEN".to_string(), "npm_config_*".to_string()];
+
+    assert!(is_allowed(OsStr::new("PATH"), &[]));
+    assert!(is_allowed(OsStr::new("LC_ALL"), &[]));
+    assert!(is_allowed(OsStr::new("HTTPS_PROXY"), &[]));
+    assert!(!is_allowed(OsStr::new("NODE_OPTIONS"), &[]));
+    assert!(!is_allowed(OsStr::new("GITHUB_TOKEN"), &pass_env));
+    assert!(!is_allowed(OsStr::new("NPM_TOKEN"), &[]));
+    assert!(is_allowed(OsStr::new("NPM_TOKEN"), &pass_env));
+    assert!(is_allowed(OsStr::new("npm_config_registry"), &pass_env));
+    assert_eq!(
+        is_allowed(OsStr::new("Path"), &[]),
+        cfg!(windows),
+        "Names are only case-insensitive on Windows"
+    );
+
+    let mut cmd = std::process::Command::new("node");
+    cmd.env("NODE_OPTIONS", "--max-old-space-size=4096")
+        .sanitized_env(&pass_env);
+
+    let envs: BTreeMap<_, _> = cmd.get_envs().collect();
+
+    // Variables set on the command are kept, and everything else not allowed is removed
+    assert_eq!(
+        envs.get(OsStr::new("NODE_OPTIONS")),
+        Some(&Some(OsStr::new("--max-old-space-size=4096")))
+    );
+
+    for (name, _) in std::env::vars_os() {
+        if name == "NODE_OPTIONS" {
+            continue;
+        }
+
+        assert_eq!(
+            envs.get(name.as_os_str()),
+            (!is_allowed(&name, &pass_env)).then_some(&None),
+            "{:?}",
+            name
+        );
+    }
+}
+
+/// Test that the package a line of package manager output is about is found, for showing next to the install spinner.
+#[test]
+fn installed_package() {
+    assert_eq!(
+        helpers::installed_package("+ lodash@4.17.21"),
+        Some("lodash@4.17.21")
+    );
+    assert_eq!(
+        helpers::installed_package("  +@types/node@20.11.1 (direct)"),
+        Some("@types/node@20.11.1")
+    );
+    assert_eq!(helpers::installed_package("added 12 packages in 3s"), None);
+    assert_eq!(helpers::installed_package("lodash@latest"), None);
+    assert_eq!(helpers::installed_package("@types/node"), None);
+}
+
+/// Test that the cache stats count what's in each part of the cache, and add up its size.
+#[test]
+fn cache_stats() {
+    let cache_dir = TempDir::new("cache").unwrap();
+    let builder = Builder::new(cache_dir.path().to_path_buf()).unwrap();
+
+    fs::write(cache_dir.path().join("builds/slot.blob"), [0; 100]).unwrap();
+    fs::write(cache_dir.path().join("builds/slot.sha256"), [0; 64]).unwrap();
+    fs::create_dir_all(cache_dir.path().join("sources/script")).unwrap();
+    fs::write(cache_dir.path().join("sources/script/index.js"), [0; 10]).unwrap();
+
+    let stats = builder.cache_stats().unwrap();
+
+    assert!(stats.node.is_empty());
+    assert!(stats.esbuild.is_empty());
+    assert_eq!(stats.builds.entries, 1);
+    assert_eq!(stats.builds.bytes, 164);
+    assert!(stats.builds.last_used.is_some());
+    assert_eq!(stats.sources.entries, 1);
+    assert_eq!(stats.sources.bytes, 10);
+    assert!(stats.total_bytes >= 174);
+}
+
+/// Test that `jundler clean project` only matches what builds leave in the project, and not the project's own files.
+#[test]
+fn project_artifacts() {
+    let package_config: PackageConfig =
+        serde_json::from_str(r#"{ "name": "app", "version": "1.2.3" }"#).unwrap();
+
+    let project = TempDir::new("project").unwrap();
+
+    for file in [
+        "app",
+        "app.exe",
+        "app-linux-x64.tar.gz",
+        "app.sha256",
+        "SHA256SUMS",
+        "bundled.js",
+        "package.json",
+        "app.json",
+        "jundler.lock",
+        "index.js",
+    ] {
+        fs::write(project.path().join(file), file).unwrap();
+    }
+
+    fs::create_dir_all(project.path().join("src/app")).unwrap();
+
+    let artifacts = clean::project_artifacts(project.path(), &package_config).unwrap();
+
+    assert_eq!(
+        artifacts,
+        [
+            "SHA256SUMS",
+            "app",
+            "app-linux-x64.tar.gz",
+            "app.exe",
+            "app.sha256",
+            "bundled.js"
+        ]
+        .map(|file| project.path().join(file))
+    );
+}
+
+/// Test that the platform of a binary is read from its ELF, PE, or Mach-O header.
+#[test]
+fn binary_platforms() {
+    use binary_format::{parse_platform, BinaryPlatform};
+
+    let platform = |os, arches: &[Arch]| {
+        Some(BinaryPlatform {
+            os,
+            arches: arches.to_vec(),
+        })
+    };
+
+    // ELF, little and big-endian
+    let mut elf = vec![0x7f, b'E', b'L', b'F', 2, 1, 1, 0];
+    elf.resize(18, 0);
+    elf.extend(0xb7u16.to_le_bytes());
+    assert_eq!(parse_platform(&elf), platform(Os::Linux, &[Arch::Arm64]));
+
+    elf[5] = 2;
+    elf[18..20].copy_from_slice(&0x3eu16.to_be_bytes());
+    assert_eq!(parse_platform(&elf), platform(Os::Linux, &[Arch::X64]));
+
+    // PE, with its header after the DOS stub
+    let mut pe = vec![b'M', b'Z'];
+    pe.resize(0x3c, 0);
+    pe.extend(0x80u32.to_le_bytes());
+    pe.resize(0x80, 0);
+    pe.extend(b"PE\0\0");
+    pe.extend(0xaa64u16.to_le_bytes());
+    assert_eq!(parse_platform(&pe), platform(Os::Windows, &[Arch::Arm64]));
+
+    // Thin and universal Mach-O
+    let mut mach_o = vec![0xcf, 0xfa, 0xed, 0xfe];
+    mach_o.extend(0x0100_0007u32.to_le_bytes());
+    assert_eq!(parse_platform(&mach_o), platform(Os::MacOS, &[Arch::X64]));
+
+    let mut universal = vec![0xca, 0xfe, 0xba, 0xbe];
+    universal.extend(2u32.to_be_bytes());
+
+    for cpu_type in [0x0100_0007u32, 0x0100_000c] {
+        universal.extend(cpu_type.to_be_bytes());
+        universal.extend([0; 16]);
+    }
+
+    let universal = parse_platform(&universal).unwrap();
+
+    assert!(universal.matches(Os::MacOS, Arch::X64));
+    assert!(universal.matches(Os::MacOS, Arch::Arm64));
+    assert_eq!(universal.to_string(), "darwin-x64+arm64");
+
+    // Anything else isn't a binary jundler builds
+    assert_eq!(parse_platform(b"#!/bin/sh\n"), None);
+    assert_eq!(parse_platform(&[0x7f, b'E']), None);
+}
+
+/// Test that the SEA fuse and the build metadata are found in a built binary, wherever they are in it.
+#[test]
+fn inspect_binary() {
+    let output = TempDir::new("output").unwrap();
+
+    let metadata = metadata::BuildMetadata {
+        jundler_version: "1.0.0".to_string(),
+        name: "app".to_string(),
+        version: Some("1.2.3".to_string()),
+        node_version: Version::new(22, 11, 0),
+        target: "linux-x64".to_string(),
+        built_at: 315_532_800,
+        bundle_hash: "ab".repeat(32),
+    };
+
+    let mut binary = vec![0x7f, b'E', b'L', b'F'];
+    binary.extend(format!("{}:1", metadata::SEA_FUSE).as_bytes());
+    binary.extend([0; 100]);
+    binary.extend(serde_json::to_vec(&metadata).unwrap());
+    binary.extend(b"\0\x01other assets");
+
+    let binary_path = output.path().join("app");
+    fs::write(&binary_path, &binary).unwrap();
+
+    let inspection = metadata::inspect(&binary_path).unwrap();
+
+    assert_eq!(inspection.fuse, Some(true));
+    assert_eq!(inspection.metadata, Some(metadata));
+
+    // Plain Node.js binaries have the fuse, but it isn't flipped, and there's no metadata
+    fs::write(&binary_path, format!("node{}:0", metadata::SEA_FUSE)).unwrap();
+
+    let inspection = metadata::inspect(&binary_path).unwrap();
+
+    assert_eq!(inspection.fuse, Some(false));
+    assert_eq!(inspection.metadata, None);
+}
+
+/// Test that builds are compared from their reports, down to what's in their bundles, and paired up by target.
+#[test]
+fn diff_builds() {
+    let dir = TempDir::new("reports").unwrap();
+
+    let metafile = dir.path().join("meta.json");
+    fs::write(
+        &metafile,
+        r#"{"outputs":{"bundled.js":{"inputs":{"index.js":{"bytesInOutput":120},"node_modules/left-pad/index.js":{"bytesInOutput":0},"(disabled):fs":{"bytesInOutput":10}}}}}"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        licenses::bundled_bytes(&metafile, dir.path(), dir.path()).unwrap(),
+        BTreeMap::from([(PathBuf::from("index.js"), 120)])
+    );
+
+    let build =
+        |os: &str, size: u64, esbuild: &str, inputs: &[(&str, u64)], packages: &[(&str, &str)]| {
+            report::TargetReport {
+                os: os.to_string(),
+                arch: "x64".to_string(),
+                runtime: "node".to_string(),
+                runtime_version: "22.3.0".to_string(),
+                node_version: "22.3.0".to_string(),
+                esbuild_version: Some(esbuild.to_string()),
+                cached: false,
+                outputs: vec![report::OutputReport {
+                    path: PathBuf::from("app"),
+                    size,
+                    sha256: String::new(),
+                }],
+                image: None,
+                min_glibc: None,
+                size: None,
+                bundle: Some(report::BundleContents {
+                    inputs: inputs
+                        .iter()
+                        .map(|(path, size)| (path.to_string(), *size))
+                        .collect(),
+                    packages: packages
+                        .iter()
+                        .map(|(name, version)| {
+                            (name.to_string(), BTreeSet::from([version.to_string()]))
+                        })
+                        .collect(),
+                }),
+                duration_ms: 0,
+            }
+        };
+
+    let write_report = |name: &str, builds: Vec<report::TargetReport>| {
+        let path = dir.path().join(name);
+        report::BuildReport::new(builds, crate::ui::Recording::default(), Duration::ZERO)
+            .write(&path)
+            .unwrap();
+        path
+    };
+
+    let old = write_report(
+        "old.json",
+        vec![
+            build(
+                "linux",
+                1000,
+                "0.21.5",
+                &[("index.js", 100), ("node_modules/a/index.js", 50)],
+                &[("a", "1.0.0"), ("b", "1.0.0")],
+            ),
+            build("darwin", 900, "0.21.5", &[], &[]),
+        ],
+    );
+    let new = write_report(
+        "new.json",
+        vec![build(
+            "linux",
+            1500,
+            "0.22.0",
+            &[
+                ("index.js", 90),
+                ("node_modules/a/index.js", 50),
+                ("node_modules/c/index.js", 400),
+            ],
+            &[("a", "2.0.0"), ("c", "1.0.0")],
+        )],
+    );
+
+    let diffs = diff::compare(&diff::load(&old).unwrap(), &diff::load(&new).unwrap()).unwrap();
+
+    assert_eq!(diffs.len(), 1);
+    assert_eq!(diffs[0].target, "linux-x64");
+    assert_eq!(diffs[0].size_delta(), 500);
+    assert_eq!(diffs[0].runtime, None);
+    assert_eq!(
+        diffs[0].esbuild_version,
+        Some(diff::Change {
+            old: Some("0.21.5".to_string()),
+            new: Some("0.22.0".to_string()),
+        })
+    );
+    assert!(diffs[0].contents_compared);
+    assert_eq!(
+        diffs[0]
+            .inputs
+            .iter()
+            .map(|input| (input.path.as_str(), input.delta()))
+            .collect::<Vec<_>>(),
+        vec![("node_modules/c/index.js", 400), ("index.js", -10)]
+    );
+    assert_eq!(
+        diffs[0]
+            .packages
+            .iter()
+            .map(|package| (package.name.as_str(), package.old.len(), package.new.len()))
+            .collect::<Vec<_>>(),
+        vec![("a", 1, 1), ("b", 1, 0), ("c", 0, 1)]
+    );
+
+    // Binaries only have their metadata to go on, so there's no bundle to compare
+    let binary = dir.path().join("app");
+    fs::write(&binary, "not a Node.js binary").unwrap();
+
+    let diffs = diff::compare(
+        &diff::load(&old).unwrap()[..1],
+        &diff::load(&binary).unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(diffs[0].size_delta(), 20 - 1000);
+    assert!(!diffs[0].contents_compared);
+    assert!(diffs[0].inputs.is_empty());
+
+    // Single builds are compared whatever they're for, but otherwise only builds for the same target are
+    let darwin = diff::load(&old).unwrap().split_off(1);
+    assert!(diff::compare(&darwin, &diff::load(&new).unwrap()).is_ok());
+    assert!(diff::compare(
+        &diff::load(&new).unwrap(),
+        &[darwin[0].clone(), darwin[0].clone()]
+    )
+    .is_err());
+}
+
+/// Test that provenance lists every output, the lockfile and Node.js archives they were built from, and what built them.
+#[test]
+fn provenance_statement() {
+    let project = TempDir::new("project").unwrap();
+    let builder = Builder::new(TempDir::new("cache").unwrap().into_path()).unwrap();
+
+    fs::write(project.path().join("package-lock.json"), "{}").unwrap();
+    fs::write(project.path().join("app"), "binary").unwrap();
+
+    let options = BuildOptions {
+        target_os: Os::MacOS,
+        universal: true,
+        ..BuildOptions::new(Version::new(22, 3, 0))
+    };
+
+    let statement = builder
+        .provenance(
+            project.path(),
+            &[project.path().join("app")],
+            true,
+            &options,
+            SystemTime::UNIX_EPOCH,
+        )
+        .unwrap();
+
+    let json = serde_json::to_value(&statement).unwrap();
+
+    assert_eq!(json["_type"], "https://in-toto.io/Statement/v1");
+    assert_eq!(json["predicateType"], "https://slsa.dev/provenance/v1");
+    assert_eq!(json["subject"][0]["name"], "app");
+    assert_eq!(
+        json["subject"][0]["digest"]["sha256"],
+        hex::encode(Sha256::digest("binary"))
+    );
+
+    let predicate = &json["predicate"];
+
+    assert_eq!(
+        predicate["buildDefinition"]["externalParameters"]["target"],
+        "darwin-universal"
+    );
+    assert_eq!(
+        predicate["runDetails"]["builder"]["version"]["jundler"],
+        env!("CARGO_PKG_VERSION")
+    );
+    assert_eq!(
+        predicate["runDetails"]["builder"]["version"]["esbuild"],
+        ESBUILD_VERSION.to_string()
+    );
+    assert_eq!(
+        predicate["runDetails"]["metadata"]["startedOn"],
+        "1970-01-01T00:00:00Z"
+    );
+
+    // Both slices of the universal binary come from an official archive, and there's no git repository to describe
+    let dependencies = predicate["buildDefinition"]["resolvedDependencies"]
+        .as_array()
+        .unwrap();
+
+    assert_eq!(
+        dependencies
+            .iter()
+            .map(|dependency| dependency["uri"].as_str().or(dependency["name"].as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            Some("https://nodejs.org/dist/v22.3.0/node-v22.3.0-darwin-x64.tar.gz"),
+            Some("https://nodejs.org/dist/v22.3.0/node-v22.3.0-darwin-arm64.tar.gz"),
+            Some("package-lock.json"),
+        ]
+    );
+    assert_eq!(
+        dependencies[2]["digest"]["sha256"],
+        hex::encode(Sha256::digest("{}"))
+    );
+}
+
+/// Test that errors get the class they were classified as, that download and checksum errors are recognized wherever
+/// they come from (along with unsupported hosts), and that classifying an error doesn't change how it's printed.
+#[test]
+fn error_classes() {
+    let err = Err::<(), _>(anyhow!("codesign failed"))
+        .classify(ErrorClass::Signing)
+        .context("Error building .")
+        .unwrap_err();
+
+    let report = errors::ErrorReport::new(&err);
+
+    assert_eq!(report.class, ErrorClass::Signing);
+    assert_eq!(report.exit_code, 15);
+    assert_eq!(report.message, "Error building .");
+    assert_eq!(report.causes, ["codesign failed"]);
+    assert_eq!(
+        serde_json::to_string(&report).unwrap(),
+        r#"{"class":"signing","exit_code":15,"message":"Error building .","causes":["codesign failed"]}"#
+    );
+
+    // The innermost class wins, as it's the most specific
+    let err = Err::<(), _>(anyhow!("bad config"))
+        .classify(ErrorClass::Config)
+        .classify(ErrorClass::Bundling)
+        .unwrap_err();
+
+    assert_eq!(ErrorClass::of(&err), ErrorClass::Config);
+
+    // Checksum mismatches are checksum errors, even while bundling
+    let err = Err::<(), _>(node_manager::Error::ChecksumMismatch {
+        path: PathBuf::from("node.tar.gz"),
+        expected: [0; 32],
+        actual: [1; 32],
+    })
+    .classify(ErrorClass::Bundling)
+    .unwrap_err();
+
+    assert_eq!(ErrorClass::of(&err), ErrorClass::Checksum);
+    assert_eq!(ErrorClass::of(&anyhow!("oops")).exit_code(), 1);
+
+    // Unsupported hosts are platform errors, even when they're found while checking the options
+    let err = Err::<(), _>(platforms::PlatformError::UnsupportedOs("plan9"))
+        .classify(ErrorClass::Config)
+        .unwrap_err();
+
+    assert_eq!(ErrorClass::of(&err), ErrorClass::Platform);
+    assert_eq!(ErrorClass::of(&err).exit_code(), 16);
+    assert!(err.to_string().starts_with("Jundler doesn't run on plan9,"));
+    assert_eq!(Os::host().unwrap(), get_host_os());
+}
+
+/// Test that errors from the Node.js and ESBuild managers are diagnosed wherever they are in the chain, with their
+/// code, suggestions, and documentation in the report, and that other errors aren't.
+#[test]
+fn error_diagnostics() {
+    let err = Err::<(), _>(node_manager::Error::ChecksumMismatch {
+        path: PathBuf::from("node.tar.gz"),
+        expected: [0; 32],
+        actual: [1; 32],
+    })
+    .classify(ErrorClass::Bundling)
+    .context("Error building .")
+    .unwrap_err();
+
+    let report = errors::ErrorReport::new(&err);
+
+    assert_eq!(report.code, Some("node::checksum_mismatch"));
+    assert!(report.help[0].contains("`jundler cache verify`"));
+    assert!(report.help[1].contains("proxy"));
+    assert!(report
+        .docs
+        .as_ref()
+        .is_some_and(|docs| docs.starts_with(env!("CARGO_PKG_REPOSITORY"))));
+
+    console::set_colors_enabled(false);
+
+    let rendered = report.render();
+
+    assert!(rendered.starts_with(
+        "Error[node::checksum_mismatch]: Error building .\n\nCaused by:\n    0: Checksum mismatch"
+    ));
+    assert!(rendered.contains("\nhelp: Run `jundler cache verify`"));
+    assert!(rendered.contains("\ndocs: "));
+
+    let err = anyhow::Error::new(esbuild::Error::Frozen("download ESBuild".to_string()));
+
+    assert_eq!(
+        errors::Diagnostic::of(&err).map(|diagnostic| diagnostic.code),
+        Some("esbuild::frozen")
+    );
+    assert_eq!(errors::Diagnostic::of(&anyhow!("oops")), None);
+    assert_eq!(
+        errors::ErrorReport::new(&anyhow!("oops")).render(),
+        "Error: oops"
+    );
+}
+
+/// Test that planning a build lists what would be downloaded and run, checks the options, and doesn't build anything.
+#[test]
+fn build_plan() {
+    let project = TempDir::new("project").unwrap();
+    let cache_dir = TempDir::new("cache").unwrap();
+    let builder = Builder::new(cache_dir.path().to_path_buf()).unwrap();
+
+    fs::write(project.path().join("package.json"), r#"{ "name": "app" }"#).unwrap();
+    fs::write(
+        project.path().join("sea-config.json"),
+        r#"{ "main": "index.js", "output": "sea-prep.blob" }"#,
+    )
+    .unwrap();
+    fs::write(project.path().join("index.js"), "console.log(1)").unwrap();
+
+    let mut options = BuildOptions {
+        node_version: Version::new(22, 3, 0),
+        target_os: Os::Linux,
+        target_arch: Arch::Arm64,
+        bundle: true,
+        copy_strategy: CopyStrategy::Auto,
+        force: false,
+        output_dir: None,
+        package_manager: Some(PackageManager::Npm),
+        runtime: Runtime::Node,
+        strip: false,
+        universal: false,
+        reproducible: false,
+        checksums: Some(ChecksumFormat::Gnu),
+        licenses: false,
+        embed_licenses: false,
+        archive: None,
+        codesign_identity: None,
+        notarize: false,
+        sign: true,
+        sign_certificate: None,
+        windows_sign: WindowsSignOptions::default(),
+        provenance: false,
+        signatures: None,
+        signing_key: None,
+        manifests: false,
+        release_url: None,
+        linux_packages: vec![],
+        msi: false,
+        docker_image: None,
+        output_name: None,
+        frozen: false,
+        pass_env: vec![],
+        npmrc: None,
+        node_advisories: AdvisoryPolicy::default(),
+        rebuild_native: false,
+        native_toolchain: None,
+        test_exec: None,
+        max_size: None,
+        max_size_policy: size::MaxSizePolicy::default(),
+    };
+
+    let plan = builder.plan(project.path(), &options).unwrap();
+
+    assert_eq!(plan.target, "linux-arm64");
+    assert!(!plan.cached);
+    assert!(plan
+        .downloads
+        .contains(&"Node.js v22.3.0 linux-arm64".to_string()));
+    assert_eq!(
+        plan.downloads.last().unwrap(),
+        &format!("ESBuild v{}", ESBUILD_VERSION)
+    );
+    assert!(plan.steps.contains(&BUNDLE_PROJ_MSG.to_string()));
+    assert_eq!(plan.steps.last().unwrap(), CHECKSUMS_MSG);
+    assert_eq!(plan.app_path, project.path().join("app"));
+
+    // Nothing was built
+    assert_eq!(fs::read_dir(project.path()).unwrap().count(), 3);
+
+    // Other runtimes compile the project themselves, and only build what they can
+    options.runtime = Runtime::Bun;
+
+    let plan = builder.plan(project.path(), &options).unwrap();
+
+    assert_eq!(plan.runtime_version, runtime::BUN_VERSION.to_string());
+    assert_eq!(
+        plan.downloads,
+        vec![format!(
+            "Bun v{} {}-{}",
+            runtime::BUN_VERSION,
+            get_host_os(),
+            get_host_arch()
+        )]
+    );
+    assert!(!plan.steps.contains(&GEN_SEA_BLOB_MSG.to_string()));
+    assert!(plan.steps.contains(&format!("{} Bun", RUNTIME_COMPILE_MSG)));
+
+    options.universal = true;
+    options.target_os = Os::MacOS;
+    assert!(builder.plan(project.path(), &options).is_err());
+
+    options.universal = false;
+    options.target_os = Os::Windows;
+    options.target_arch = Arch::X86;
+    assert!(builder.plan(project.path(), &options).is_err());
+
+    // Options are checked like they are for real builds
+    options.runtime = Runtime::Node;
+    options.target_os = Os::Linux;
+    options.msi = true;
+    assert!(builder.plan(project.path(), &options).is_err());
+}
+
+/// Test that plans written to a file are only approved for builds that would still do exactly what they say.
+#[test]
+fn approved_build_plan() {
+    use plan::PlanFile;
+    use project_lock::{node_binary_name, ProjectLock};
+
+    let project = TempDir::new("project").unwrap();
+    let cache_dir = TempDir::new("cache").unwrap();
+    let builder = Builder::new(cache_dir.path().to_path_buf()).unwrap();
+
+    fs::write(project.path().join("package.json"), r#"{ "name": "app" }"#).unwrap();
+    fs::write(
+        project.path().join("sea-config.json"),
+        r#"{ "main": "index.js", "output": "sea-prep.blob" }"#,
+    )
+    .unwrap();
+    fs::write(project.path().join("index.js"), "console.log(1)").unwrap();
+
+    let node_binary = node_binary_name(&Version::new(22, 3, 0), Os::Linux, Arch::X64);
+    let mut lock = ProjectLock::new(project.path());
+    lock.checksums.insert(node_binary.clone(), "a".repeat(64));
+    lock.save().unwrap();
+
+    let mut options = BuildOptions {
+        target_os: Os::Linux,
+        target_arch: Arch::X64,
+        frozen: true,
+        ..BuildOptions::new(Version::new(22, 3, 0))
+    };
+
+    let plan = builder.plan(project.path(), &options).unwrap();
+
+    assert!(plan.inputs_hash.is_some());
+    assert!(plan
+        .urls
+        .contains(&"https://nodejs.org/dist/v22.3.0/node-v22.3.0-linux-x64.tar.gz".to_string()));
+    assert_eq!(plan.checksums.get(&node_binary), Some(&"a".repeat(64)));
+    assert_eq!(plan.settings.get("frozen"), Some(&"true".to_string()));
+
+    let plan_path = cache_dir.path().join("plan.json");
+    PlanFile::write(&plan_path, &[plan]).unwrap();
+    let plan_file = PlanFile::read(&plan_path).unwrap();
+
+    plan_file
+        .approve(&builder.plan(project.path(), &options).unwrap())
+        .unwrap();
+
+    // Changing the project, the options, or the pinned checksums after the plan is made stops it from being built
+    fs::write(project.path().join("index.js"), "console.log(2)").unwrap();
+    let err = plan_file
+        .approve(&builder.plan(project.path(), &options).unwrap())
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("The hash of the project's inputs"));
+
+    fs::write(project.path().join("index.js"), "console.log(1)").unwrap();
+    options.strip = true;
+    let err = plan_file
+        .approve(&builder.plan(project.path(), &options).unwrap())
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("`strip` is true, but the plan has false"));
+
+    // Each difference is only reported once, even though it's in both plans
+    assert_eq!(err.matches("`strip`").count(), 1);
+
+    options.strip = false;
+    lock.checksums.insert(node_binary.clone(), "b".repeat(64));
+    lock.save().unwrap();
+    let err = plan_file
+        .approve(&builder.plan(project.path(), &options).unwrap())
+        .unwrap_err()
+        .to_string();
+    assert_eq!(
+        err.matches(&format!("The checksum of {}", node_binary))
+            .count(),
+        1
+    );
+
+    // Targets that weren't planned can't be built
+    options.target_arch = Arch::Arm64;
+    assert!(plan_file
+        .approve(&builder.plan(project.path(), &options).unwrap())
+        .unwrap_err()
+        .to_string()
+        .contains("doesn't have a build"));
+
+    fs::write(&plan_path, r#"{ "version": 0, "plans": [] }"#).unwrap();
+    assert!(PlanFile::read(&plan_path).is_err());
+}
+
+/// Test that settings are taken from the command line first, then the project's configuration, then the defaults,
+/// and that each says where it came from.
+#[test]
+fn resolved_settings() {
+    let project = TempDir::new("project").unwrap();
+
+    fs::write(
+        project.path().join("package.json"),
+        r#"{ "name": "app", "jundler": { "outputName": "{name}-{os}{ext}", "release": { "url": "https://a" } } }"#,
+    )
+    .unwrap();
+    fs::write(
+        project.path().join("sea-config.json"),
+        r#"{ "main": "index.js", "output": "sea-prep.blob" }"#,
+    )
+    .unwrap();
+
+    let options = BuildOptions {
+        node_version: Version::new(22, 3, 0),
+        target_os: Os::Linux,
+        target_arch: Arch::Arm64,
+        bundle: false,
+        copy_strategy: CopyStrategy::Auto,
+        force: false,
+        output_dir: None,
+        package_manager: None,
+        runtime: Runtime::Node,
+        strip: true,
+        universal: false,
+        reproducible: false,
+        checksums: None,
+        licenses: false,
+        embed_licenses: false,
+        archive: None,
+        codesign_identity: None,
+        notarize: false,
+        sign: true,
+        sign_certificate: None,
+        windows_sign: WindowsSignOptions::default(),
+        provenance: false,
+        signatures: None,
+        signing_key: None,
+        manifests: false,
+        release_url: Some("https://b".to_string()),
+        linux_packages: vec![],
+        msi: false,
+        docker_image: None,
+        output_name: None,
+        frozen: false,
+        pass_env: vec![],
+        npmrc: None,
+        node_advisories: AdvisoryPolicy::default(),
+        rebuild_native: false,
+        native_toolchain: None,
+        test_exec: None,
+        max_size: None,
+        max_size_policy: size::MaxSizePolicy::default(),
+    };
+
+    let given = |id: &str| match id {
+        "arch" | "release_url" => Some(Source::CommandLine),
+        "strip" => Some(Source::Environment("JUNDLER_STRIP".to_string())),
+        _ => None,
+    };
+
+    let settings = settings::settings(
+        project.path(),
+        &options,
+        Source::Project(".nvmrc".to_string()),
+        given,
+    )
+    .unwrap();
+
+    let setting = |key: &str| {
+        settings
+            .iter()
+            .find(|setting| setting.key == key)
+            .unwrap()
+            .clone()
+    };
+
+    assert_eq!(
+        setting("node-version"),
+        Setting::new(
+            "node-version",
+            "22.3.0",
+            Source::Project(".nvmrc".to_string())
+        )
+    );
+    assert_eq!(
+        setting("target"),
+        Setting::new("target", "linux-arm64", Source::CommandLine)
+    );
+    assert_eq!(
+        setting("strip"),
+        Setting::new(
+            "strip",
+            true,
+            Source::Environment("JUNDLER_STRIP".to_string())
+        )
+    );
+    assert_eq!(
+        setting("output-name"),
+        Setting::new(
+            "output-name",
+            "{name}-{os}{ext}",
+            Source::Config("jundler.outputName".to_string(), "package.json")
+        )
+    );
+    assert_eq!(
+        setting("release-url"),
+        Setting::new("release-url", "https://b", Source::CommandLine)
+    );
+    assert_eq!(setting("docker").value, None);
+    assert_eq!(setting("docker").source, Source::Default);
+
+    // `jundler.toml` replaces the `jundler` field of `package.json`
+    fs::write(
+        project.path().join(CONFIG_FILE_NAME),
+        "outputName = \"{name}{ext}\"",
+    )
+    .unwrap();
+
+    let settings =
+        settings::settings(project.path(), &options, Source::CommandLine, |_| None).unwrap();
+    let output_name = settings
+        .iter()
+        .find(|setting| setting.key == "output-name")
+        .unwrap();
+
+    assert_eq!(
+        output_name.source,
+        Source::Config("outputName".to_string(), CONFIG_FILE_NAME)
+    );
+}
+
+/// Test that step times are averaged with the last build's, and kept per project.
+#[test]
+fn step_times() {
+    let cache_dir = TempDir::new("cache").unwrap();
+    let step = |message: &str, secs: u64| Step {
+        message: message.to_string(),
+        depth: 0,
+        duration: Duration::from_secs(secs),
+    };
+
+    let mut times = StepTimes::load(cache_dir.path(), Path::new("/app"));
+
+    assert!(times.estimates().is_empty());
+
+    times.record(&[step(INSTALL_DEPS_MSG, 20), step(BUNDLE_PROJ_MSG, 4)]);
+    times.save().unwrap();
+
+    let mut times = StepTimes::load(cache_dir.path(), Path::new("/app"));
+
+    times.record(&[step(INSTALL_DEPS_MSG, 10)]);
+
+    let estimates = times.estimates();
+
+    assert_eq!(estimates[INSTALL_DEPS_MSG], Duration::from_secs(15));
+    assert_eq!(estimates[BUNDLE_PROJ_MSG], Duration::from_secs(4));
+
+    // Other projects have their own times
+    assert!(StepTimes::load(cache_dir.path(), Path::new("/other"))
+        .estimates()
+        .is_empty());
+}
+
+/// Test that downloads are shared through storage: stored the first time, and read from storage after that.
+#[test]
+fn shared_downloads() {
+    use downloader::FileDownloader;
+    use storage::{DirStorage, Storage, StorageDownloader};
+
+    let fixtures = TempDir::new("fixtures").unwrap();
+    let shared = TempDir::new("shared").unwrap();
+    let url = "https://nodejs.org/dist/v22.3.0/SHASUMS256.txt";
+    let fixture = fixtures
+        .path()
+        .join("nodejs.org/dist/v22.3.0/SHASUMS256.txt");
+
+    fs::create_dir_all(fixture.parent().unwrap()).unwrap();
+    fs::write(&fixture, "checksums").unwrap();
+
+    let storage = Arc::new(DirStorage::new(shared.path().to_path_buf()));
+    let downloader = StorageDownloader::new(
+        storage.clone(),
+        Arc::new(FileDownloader::new(fixtures.path().to_path_buf())),
+    );
+
+    assert_eq!(
+        downloader.download(url, &NoopReporter).unwrap(),
+        b"checksums"
+    );
+    assert_eq!(
+        storage
+            .get("nodejs.org/dist/v22.3.0/SHASUMS256.txt")
+            .unwrap(),
+        Some(b"checksums".to_vec())
+    );
+
+    // Once it's shared, it doesn't have to be downloaded again
+    fs::remove_file(&fixture).unwrap();
+
+    assert_eq!(
+        downloader.download(url, &NoopReporter).unwrap(),
+        b"checksums"
+    );
+
+    // Files that aren't anywhere are still an error
+    assert!(downloader
+        .download(
+            "https://nodejs.org/dist/v22.4.0/SHASUMS256.txt",
+            &NoopReporter
+        )
+        .is_err());
+    assert_eq!(
+        storage
+            .get("nodejs.org/dist/v22.4.0/SHASUMS256.txt")
+            .unwrap(),
+        None
+    );
+}
+
+/// Test that the cache server serves what it has, downloads what it doesn't from upstream, and refuses anything else.
+#[test]
+fn cache_server() {
+    use cache_server::CacheServer;
+    use downloader::FileDownloader;
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use storage::DirStorage;
+
+    let fixtures = TempDir::new("fixtures").unwrap();
+    let shared = TempDir::new("shared").unwrap();
+    let key = "nodejs.org/dist/v22.3.0/SHASUMS256.txt";
+    let fixture = fixtures.path().join(key);
+
+    fs::create_dir_all(fixture.parent().unwrap()).unwrap();
+    fs::write(&fixture, "checksums").unwrap();
+
+    let server = CacheServer::new(
+        Arc::new(DirStorage::new(shared.path().to_path_buf())),
+        Arc::new(FileDownloader::new(fixtures.path().to_path_buf())),
+    );
+
+    // Downloaded from upstream the first time, and served from storage after that
+    assert_eq!(
+        server.respond("GET", &format!("/{}", key)).body,
+        b"checksums"
+    );
+    fs::remove_file(&fixture).unwrap();
+
+    // Keys are paths in storage, so they can't leave it
+    assert_eq!(
+        server.respond("GET", "/nodejs.org/../../etc/passwd").status,
+        400
+    );
+
+    // Nor can they climb out of where jundler downloads from, encoded or not
+    for path in [
+        "/github.com/oven-sh/bun/releases/download/%2e%2e/%2e%2e/%2e%2e/%2e%2e/attacker/repo/raw/main/payload",
+        "/nodejs.org/dist/v22.3.0/SHASUMS256.txt?x=1",
+        "/nodejs.org:8080/dist/v22.3.0/SHASUMS256.txt",
+        "/NODEJS.ORG/dist/v22.3.0/node-v22.3.0-linux-x64.tar.gz",
+    ] {
+        assert!(
+            [400, 404].contains(&server.respond("GET", path).status),
+            "{} was downloaded",
+            path
+        );
+    }
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let url = format!("http://{}", addr);
+    let server_for_prefixes = server.clone();
+    thread::spawn(move || server.serve(listener));
+
+    let client = storage::open(&url);
+
+    assert_eq!(client.get(key).unwrap(), Some(b"checksums".to_vec()));
+
+    // Only files from where jundler downloads are fetched, and clients can't store anything
+    assert_eq!(client.get("example.com/file.txt").unwrap(), None);
+    assert_eq!(
+        server_for_prefixes
+            .respond("GET", "/github.com/someone/else")
+            .status,
+        404
+    );
+    assert!(client
+        .get("nodejs.org/dist/v22.4.0/SHASUMS256.txt")
+        .is_err());
+    assert!(client.put(key, b"tampered").is_err());
+
+    // Requests that never end are cut off
+    let mut stream = TcpStream::connect(addr).unwrap();
+    let request = format!(
+        "GET /{}",
+        "a".repeat(cache_server::MAX_REQUEST_SIZE as usize - 5)
+    );
+    let mut response = String::new();
+
+    stream.write_all(request.as_bytes()).unwrap();
+    stream.read_to_string(&mut response).unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 431 "));
+    assert_eq!(
+        fs::read(shared.path().join(key)).unwrap(),
+        b"checksums".to_vec()
+    );
+}
+
+/// Test that builds check the version of Node.js against the cached vulnerability database, and name the release
+/// that fixed the vulnerabilities.
+#[test]
+fn node_advisories() {
+    use advisories::{Advisory, AdvisoryPolicy};
+    use downloader::FileDownloader;
+    use node_manager::security::parse_vulnerabilities;
+
+    let index = r#"{
+        "10": {"cve":["CVE-2024-27983"],"vulnerable":"^18.0.0 || >= 20.0.0 < 20.12.1","patched":"^18.20.1 || ^20.12.1"},
+        "11": {"cve":["CVE-2024-27982"],"vulnerable":">= 20.0.0 < 20.12.2","patched":"^20.12.2"},
+        "12": {"cve":[],"vulnerable":"^16.0.0","patched":""}
+    }"#;
+
+    let fixtures = TempDir::new("fixtures").unwrap();
+    let fixture = fixtures
+        .path()
+        .join("raw.githubusercontent.com/nodejs/security-wg/main/vuln/core/index.json");
+
+    fs::create_dir_all(fixture.parent().unwrap()).unwrap();
+    fs::write(&fixture, index).unwrap();
+
+    // The safe version is the first one that fixed every vulnerability
+    let vulnerabilities = parse_vulnerabilities(index).unwrap();
+    let advisory = Advisory::of(&Version::new(20, 11, 0), &vulnerabilities).unwrap();
+
+    assert_eq!(advisory.cves, vec!["CVE-2024-27983", "CVE-2024-27982"]);
+    assert_eq!(advisory.safe_version, Some(Version::new(20, 12, 2)));
+    assert_eq!(
+        Advisory::of(&Version::new(18, 19, 0), &vulnerabilities)
+            .unwrap()
+            .safe_version,
+        Some(Version::new(18, 20, 1))
+    );
+    assert_eq!(
+        Advisory::of(&Version::new(16, 0, 0), &vulnerabilities)
+            .unwrap()
+            .safe_version,
+        None
+    );
+    assert_eq!(
+        Advisory::of(&Version::new(20, 12, 2), &vulnerabilities),
+        None
+    );
+
+    let mut builder = Builder::new(TempDir::new("cache").unwrap().into_path()).unwrap();
+    builder.set_downloader(Arc::new(FileDownloader::new(fixtures.path().to_path_buf())));
+
+    let err = builder
+        .check_advisories(&Version::new(20, 11, 0), AdvisoryPolicy::Fail, false)
+        .unwrap_err()
+        .to_string();
+
+    assert!(err.contains("CVE-2024-27982"));
+    assert!(err.contains("--node-version 20.12.2"));
+
+    // Once it's cached, the database doesn't have to be downloaded again
+    fs::remove_file(&fixture).unwrap();
+
+    assert!(builder
+        .check_advisories(&Version::new(20, 11, 0), AdvisoryPolicy::Fail, true)
+        .is_err());
+    assert!(builder
+        .check_advisories(&Version::new(20, 11, 0), AdvisoryPolicy::Warn, false)
+        .is_ok());
+    assert!(builder
+        .check_advisories(&Version::new(20, 12, 2), AdvisoryPolicy::Fail, false)
+        .is_ok());
+}
+
+/// Test that the schema of the configuration has every field of it, so editors don't flag fields that work.
+#[test]
+fn config_schema() {
+    use crate::js_config::schema::{schema, SchemaFile};
+    use serde_json::Value;
+
+    /// Checks that every field of `config` is in the properties of `schema`, all the way down.
+    fn check(config: &Value, schema: &Value, path: &str) {
+        let Value::Object(fields) = config else {
+            return;
+        };
+
+        for (field, value) in fields {
+            // Maps, like `maxSize`, have their values' schema in `additionalProperties`
+            let property = match &schema["additionalProperties"] {
+                Value::Object(_) => &schema["additionalProperties"],
+                _ => &schema["properties"][field],
+            };
+
+            assert!(
+                !property.is_null(),
+                "`{}{}` isn't in the schema",
+                path,
+                field
+            );
+
+            check(value, property, &format!("{}{}.", path, field));
+        }
+    }
+
+    let config = JundlerConfig {
+        targets: vec!["linux-x64".parse().unwrap()],
+        max_size: BTreeMap::from([("*".to_string(), "80MB".to_string())]),
+        ..JundlerConfig::default()
+    };
+    let config = serde_json::to_value(config).unwrap();
+
+    check(&config, &schema(SchemaFile::Jundler), "");
+    check(
+        &config,
+        &schema(SchemaFile::PackageJson)["properties"]["jundler"],
+        "jundler.",
+    );
+
+    assert_eq!(
+        schema(SchemaFile::SeaConfig)["required"],
+        serde_json::json!(["main", "output"])
+    );
+}
+
+/// Test that the minimum glibc of a binary is the newest `GLIBC_` version it needs from any library, and that
+/// binaries that aren't ELF don't need one.
+#[test]
+fn min_glibc_of_binary() {
+    use glibc::{read_min_glibc, GlibcVersion};
+
+    let mut strtab = vec![0u8];
+    let mut name = |name: &str| {
+        let offset = strtab.len() as u32;
+        strtab.extend(name.as_bytes());
+        strtab.push(0);
+        offset
+    };
+
+    // What the binary needs from libc and libm; `GLIBC_PRIVATE` isn't a version
+    let libraries = [
+        (
+            name("libc.so.6"),
+            vec![
+                name("GLIBC_2.17"),
+                name("GLIBC_2.28"),
+                name("GLIBC_PRIVATE"),
+            ],
+        ),
+        (name("libm.so.6"), vec![name("GLIBC_2.29")]),
+    ];
+
+    let mut verneed = vec![];
+
+    for (index, (file, versions)) in libraries.iter().enumerate() {
+        let next = match index + 1 == libraries.len() {
+            true => 0,
+            false => 16 + 16 * versions.len() as u32,
+        };
+
+        verneed.extend(1u16.to_le_bytes());
+        verneed.extend((versions.len() as u16).to_le_bytes());
+        verneed.extend(file.to_le_bytes());
+        verneed.extend(16u32.to_le_bytes());
+        verneed.extend(next.to_le_bytes());
+
+        for (index, version) in versions.iter().enumerate() {
+            let next = if index + 1 == versions.len() {
+                0u32
+            } else {
+                16
+            };
+
+            verneed.extend([0; 8]);
+            verneed.extend(version.to_le_bytes());
+            verneed.extend(next.to_le_bytes());
+        }
+    }
+
+    // A 64-bit ELF header, the string table and `.gnu.version_r`, and then their section headers
+    let (strtab_offset, verneed_offset) = (64, 64 + strtab.len());
+    let shoff = verneed_offset + verneed.len();
+
+    let mut elf = vec![0x7f, b'E', b'L', b'F', 2, 1, 1, 0];
+    elf.resize(0x28, 0);
+    elf.extend((shoff as u64).to_le_bytes());
+    elf.resize(0x3a, 0);
+    elf.extend(64u16.to_le_bytes());
+    elf.extend(3u16.to_le_bytes());
+    elf.resize(64, 0);
+    elf.extend(&strtab);
+    elf.extend(&verneed);
+
+    let section = |kind: u32, offset: usize, size: usize, link: u32| {
+        let mut header = vec![0; 4];
+        header.extend(kind.to_le_bytes());
+        header.resize(24, 0);
+        header.extend((offset as u64).to_le_bytes());
+        header.extend((size as u64).to_le_bytes());
+        header.extend(link.to_le_bytes());
+        header.resize(64, 0);
+        header
+    };
+
+    elf.extend(section(0, 0, 0, 0));
+    elf.extend(section(3, strtab_offset, strtab.len(), 0));
+    elf.extend(section(0x6fff_fffe, verneed_offset, verneed.len(), 1));
+
+    let dir = TempDir::new("glibc").unwrap();
+    let binary = dir.path().join("app");
+    fs::write(&binary, &elf).unwrap();
+
+    assert_eq!(
+        read_min_glibc(&binary).unwrap(),
+        Some(GlibcVersion::new(2, 29))
+    );
+
+    // Binaries that aren't ELF don't need glibc
+    fs::write(&binary, b"MZ").unwrap();
+    assert_eq!(read_min_glibc(&binary).unwrap(), None);
+
+    // The test binary itself does
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    assert!(read_min_glibc(&std::env::current_exe().unwrap())
+        .unwrap()
+        .is_some_and(|glibc| glibc >= GlibcVersion::new(2, 2)));
+
+    assert_eq!(
+        GlibcVersion::of_distro("CentOS-7"),
+        Some(GlibcVersion::new(2, 17))
+    );
+    assert_eq!(GlibcVersion::of_distro("slackware-1.0"), None);
+    assert_eq!(
+        "2.28".parse::<GlibcVersion>().unwrap(),
+        GlibcVersion::new(2, 28)
+    );
+    assert!("PRIVATE".parse::<GlibcVersion>().is_err());
+}
+
+/// Test that projects inherit the `.npmrc` of the workspace they're in unless they have their own, and that installs
+/// get the user's `.npmrc` and the variables they interpolate, but nothing else from the environment.
+#[test]
+fn npm_config_of_project() {
+    use npmrc::{interpolated_vars, NpmConfig};
+    use std::ffi::OsStr;
+
+    let dir = TempDir::new("npmrc").unwrap();
+    let workspace = dir.path().join("workspace");
+    let project = workspace.join("packages/app");
+    fs::create_dir_all(&project).unwrap();
+
+    // The workspace's `.npmrc` is inherited by projects in it without their own
+    fs::write(
+        workspace.join(".npmrc"),
+        "@corp:registry=https://npm.corp.example/\n//npm.corp.example/:_authToken=${CORP_NPM_TOKEN}\n",
+    )
+    .unwrap();
+
+    let ci_npmrc = dir.path().join("ci.npmrc");
+    fs::write(
+        &ci_npmrc,
+        "//registry.npmjs.org/:_authToken=${NPM_TOKEN?}\ncache=${HOME}/.npm\n",
+    )
+    .unwrap();
+
+    let config = NpmConfig::find(&project, Some(&ci_npmrc)).unwrap();
+
+    assert_eq!(config.inherited, Some(workspace.join(".npmrc")));
+    assert_eq!(config.userconfig, Some(ci_npmrc.clone()));
+    assert_eq!(config.pass_env, ["CORP_NPM_TOKEN", "NPM_TOKEN", "HOME"]);
+
+    // The inherited `.npmrc` goes into the build directory, unless the project has its own
+    let build_dir = dir.path().join("build");
+    fs::create_dir_all(&build_dir).unwrap();
+    config.copy_into(&build_dir).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(build_dir.join(".npmrc")).unwrap(),
+        fs::read_to_string(workspace.join(".npmrc")).unwrap()
+    );
+
+    fs::write(
+        project.join(".npmrc"),
+        "registry=https://registry.example/\n",
+    )
+    .unwrap();
+
+    let config = NpmConfig::find(&project, Some(&ci_npmrc)).unwrap();
+    assert_eq!(config.inherited, None);
+    assert_eq!(config.pass_env, ["NPM_TOKEN", "HOME"]);
+
+    // The user's `.npmrc` is passed on explicitly, along with the variables it interpolates
+    let vars = [
+        ("NPM_TOKEN", "secret"),
+        ("JUNDLER_TEST_NPMRC_SECRET", "secret"),
+    ]
+    .map(|(name, value)| (name.into(), value.into()));
+    let mut cmd = Command::new("npm");
+    config.apply_from(&mut cmd, vars, &[]);
+
+    let envs: BTreeMap<_, _> = cmd.get_envs().collect();
+    assert_eq!(
+        envs.get(OsStr::new("npm_config_userconfig")),
+        Some(&Some(ci_npmrc.as_os_str()))
+    );
+    assert_eq!(envs.get(OsStr::new("NPM_TOKEN")), None);
+    assert_eq!(
+        envs.get(OsStr::new("JUNDLER_TEST_NPMRC_SECRET")),
+        Some(&None)
+    );
+
+    assert!(NpmConfig::find(&project, Some(&dir.path().join("missing.npmrc"))).is_err());
+
+    assert_eq!(
+        interpolated_vars("a=${A}\nb=${A}${B?}\nc=${not a var}\nd=${"),
+        ["A", "B"]
+    );
+}
+
+/// Test that a binary's size is broken down into Node.js, the bundled code, its code cache, and its assets, with the
+/// biggest packages in the bundle, and that universal binaries count the blob once for each slice.
+#[test]
+fn binary_size_breakdown() {
+    use size::{parse_blob, SizeBreakdown};
+
+    let sized = |blob: &mut Vec<u8>, contents: &[u8]| {
+        blob.extend((contents.len() as u64).to_le_bytes());
+        blob.extend(contents);
+    };
+
+    // A SEA blob with a code cache and two assets
+    let mut blob = vec![];
+    blob.extend(0x0143_da20u32.to_le_bytes());
+    blob.extend((1u32 << 2 | 1 << 3).to_le_bytes());
+    sized(&mut blob, b"index.js");
+    sized(&mut blob, &[b'x'; 1000]);
+    sized(&mut blob, &[0; 300]);
+    blob.extend(2u64.to_le_bytes());
+    sized(&mut blob, b"addon.node");
+    sized(&mut blob, &[0; 200]);
+    sized(&mut blob, b"LICENSES.txt");
+    sized(&mut blob, &[0; 50]);
+
+    let dir = TempDir::new("size").unwrap();
+    let (app, sea_blob) = (dir.path().join("app"), dir.path().join("sea-prep.blob"));
+    fs::write(&app, vec![0; 10_000 + blob.len()]).unwrap();
+    fs::write(&sea_blob, &blob).unwrap();
+
+    let bundle = report::BundleContents {
+        inputs: BTreeMap::from([
+            ("src/index.js".to_string(), 100),
+            ("node_modules/lodash/lodash.js".to_string(), 600),
+            (
+                "node_modules/a/node_modules/@scope/pkg/index.js".to_string(),
+                200,
+            ),
+            ("node_modules/@scope/pkg/util.js".to_string(), 100),
+        ]),
+        packages: BTreeMap::new(),
+    };
+
+    let breakdown = SizeBreakdown::new(&app, &sea_blob, false, Some(&bundle)).unwrap();
+
+    assert_eq!(breakdown.total, 10_000 + blob.len() as u64);
+    assert_eq!(breakdown.node, 10_000);
+    assert_eq!(breakdown.blob, blob.len() as u64);
+    assert_eq!(breakdown.code, 1000);
+    assert_eq!(breakdown.code_cache, 300);
+    assert_eq!(breakdown.assets_total(), 250);
+    assert_eq!(
+        breakdown.top_packages(),
+        [("lodash", 600), ("@scope/pkg", 300), ("(project)", 100)]
+    );
+
+    let lines: Vec<String> = breakdown.to_string().lines().map(str::to_string).collect();
+    assert!(lines[0].starts_with("Node.js"));
+    assert!(lines[2].trim_start().starts_with("lodash"));
+    assert!(lines.iter().any(|line| line.starts_with("Code cache")));
+    assert!(lines.last().unwrap().starts_with("Total"));
+
+    // Universal binaries have the blob in both slices
+    let universal = SizeBreakdown::new(&app, &sea_blob, true, None).unwrap();
+    assert_eq!(universal.node, 10_000 - blob.len() as u64);
+    assert_eq!(universal.code, 2000);
+
+    assert_eq!(parse_blob(b"not a blob"), None);
+}
+
+/// Test that maximum sizes are parsed, resolved for the target, and enforced.
+#[test]
+fn max_size_budget() {
+    use size::{max_size, parse_size, MaxSizePolicy};
+
+    assert_eq!(parse_size("80MB").unwrap(), 80_000_000);
+    assert_eq!(parse_size("75 MiB").unwrap(), 75 * 1024 * 1024);
+    assert_eq!(parse_size("1.5kb").unwrap(), 1500);
+    assert_eq!(parse_size("1048576").unwrap(), 1_048_576);
+    assert!(parse_size("80 megs").is_err());
+    assert!(parse_size("MB").is_err());
+
+    let config = JundlerConfig {
+        max_size: BTreeMap::from([
+            ("*".to_string(), "100MB".to_string()),
+            ("linux-x64".to_string(), "80MB".to_string()),
+            ("darwin-universal".to_string(), "200MB".to_string()),
+        ]),
+        ..JundlerConfig::default()
+    };
+
+    let mut options = BuildOptions {
+        target_os: Os::Linux,
+        target_arch: Arch::X64,
+        ..BuildOptions::new(Version::new(22, 3, 0))
+    };
+
+    assert_eq!(max_size(&options, &config).unwrap(), Some(80_000_000));
+
+    options.target_arch = Arch::Arm64;
+    assert_eq!(max_size(&options, &config).unwrap(), Some(100_000_000));
+
+    options.target_os = Os::MacOS;
+    options.universal = true;
+    assert_eq!(max_size(&options, &config).unwrap(), Some(200_000_000));
+
+    // `--max-size` wins over the configuration
+    options.max_size = Some(1000);
+    assert_eq!(max_size(&options, &config).unwrap(), Some(1000));
+
+    options.max_size = None;
+    assert_eq!(max_size(&options, &JundlerConfig::default()).unwrap(), None);
+
+    let invalid = JundlerConfig {
+        max_size: BTreeMap::from([("*".to_string(), "big".to_string())]),
+        ..JundlerConfig::default()
+    };
+    assert!(max_size(&options, &invalid).is_err());
+
+    let dir = TempDir::new("max-size").unwrap();
+    let app = dir.path().join("app");
+    fs::write(&app, vec![0; 2000]).unwrap();
+
+    let mut builder = Builder::new(TempDir::new("cache").unwrap().into_path()).unwrap();
+
+    options.max_size = Some(1000);
+    let err = builder
+        .check_max_size(&app, None, &config, &options)
+        .unwrap_err();
+    assert!(err.to_string().contains("over its maximum size"));
+
+    options.max_size_policy = MaxSizePolicy::Warn;
+    assert!(builder
+        .check_max_size(&app, None, &config, &options)
+        .is_ok());
+
+    options.max_size = Some(2000);
+    options.max_size_policy = MaxSizePolicy::Fail;
+    assert!(builder
+        .check_max_size(&app, None, &config, &options)
+        .is_ok());
+}
+
+/// Test that the test command is found from the arguments, the configuration, or the `test` script, and that it's run
+/// against the built binary.
+#[cfg(unix)]
+#[test]
+fn test_suite_against_binary() {
+    use helpers::make_executable;
+    use test_suite::{run_tests, test_command, TestCommand, BINARY_ENV_VAR};
+
+    let project_dir = TempDir::new("project").unwrap();
+    let package_config = |json: &str| serde_json::from_str::<PackageConfig>(json).unwrap();
+
+    let with_script = package_config(r#"{ "name": "app", "scripts": { "test": "node --test" } }"#);
+    assert_eq!(
+        test_command(project_dir.path(), &with_script, &[]).unwrap(),
+        TestCommand::Shell("npm run test".to_string())
+    );
+    assert_eq!(
+        test_command(
+            project_dir.path(),
+            &with_script,
+            &["bats".to_string(), "test/".to_string()]
+        )
+        .unwrap(),
+        TestCommand::Args(vec!["bats".to_string(), "test/".to_string()])
+    );
+
+    let mut configured = with_script.clone();
+    configured.jundler.test = Some("./e2e.sh".to_string());
+    assert_eq!(
+        test_command(project_dir.path(), &configured, &[]).unwrap(),
+        TestCommand::Shell("./e2e.sh".to_string())
+    );
+
+    // The `test` script that `npm init` writes isn't a test suite
+    let npm_init = package_config(
+        r#"{ "name": "app", "scripts": { "test": "echo \"Error: no test specified\" && exit 1" } }"#,
+    );
+    assert!(test_command(project_dir.path(), &npm_init, &[]).is_err());
+    assert!(test_command(
+        project_dir.path(),
+        &package_config(r#"{ "name": "app" }"#),
+        &[]
+    )
+    .is_err());
+
+    let app_path = project_dir.path().join("app");
+    fs::write(&app_path, "#!/bin/sh\necho \"hello $1\"\n").unwrap();
+    make_executable(&app_path).unwrap();
+
+    let command = format!("[ \"$(\"${}\" world)\" = \"hello world\" ]", BINARY_ENV_VAR);
+    assert!(
+        run_tests(project_dir.path(), &TestCommand::Shell(command), &app_path)
+            .unwrap()
+            .success()
+    );
+
+    let status = run_tests(
+        project_dir.path(),
+        &TestCommand::Shell("exit 3".to_string()),
+        &app_path,
+    )
+    .unwrap();
+    assert_eq!(status.code(), Some(3));
+
+    // Arguments after `--` are passed on exactly, spaces, quotes and all, without a shell splitting them up again
+    let args = [
+        "sh",
+        "-c",
+        "[ \"$1\" = 'a b' ] && [ \"$2\" = '\"c\"' ]",
+        "test",
+        "a b",
+        "\"c\"",
+    ];
+    let command = TestCommand::Args(args.map(String::from).to_vec());
+    assert!(run_tests(project_dir.path(), &command, &app_path)
+        .unwrap()
+        .success());
+}
+
+/// Test that installed dependencies are cached per project and target, restored only with the key they were installed
+/// with, and that the symlinks in them survive.
+#[test]
+fn cached_dependencies() {
+    use deps_cache::DepsCache;
+
+    let cache_dir = TempDir::new("deps").unwrap();
+    let cache = DepsCache::new(cache_dir.path().to_path_buf());
+    let project_dir = TempDir::new("project").unwrap();
+    let options = BuildOptions::new(Version::new(22, 3, 0));
+
+    let installed = TempDir::new("installed").unwrap();
+    let package_dir = installed.path().join("node_modules").join("left-pad");
+    fs::create_dir_all(&package_dir).unwrap();
+    fs::write(package_dir.join("index.js"), "module.exports = 1;").unwrap();
+
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(
+        "../left-pad/index.js",
+        installed.path().join("node_modules").join("pad.js"),
+    )
+    .unwrap();
+
+    cache
+        .insert(project_dir.path(), &options, "k1", installed.path())
+        .unwrap();
+
+    let restored = TempDir::new("restored").unwrap();
+    let stale = restored.path().join("node_modules").join("stale");
+    fs::create_dir_all(&stale).unwrap();
+
+    assert!(cache
+        .restore(project_dir.path(), &options, "k1", restored.path())
+        .unwrap());
+    assert!(!stale.exists());
+    assert_eq!(
+        fs::read_to_string(restored.path().join("node_modules/left-pad/index.js")).unwrap(),
+        "module.exports = 1;"
+    );
+
+    #[cfg(unix)]
+    assert_eq!(
+        fs::read_link(restored.path().join("node_modules/pad.js")).unwrap(),
+        PathBuf::from("../left-pad/index.js")
+    );
+
+    // Different keys, targets, and projects all miss
+    let fresh = TempDir::new("fresh").unwrap();
+    assert!(!cache
+        .restore(project_dir.path(), &options, "k2", fresh.path())
+        .unwrap());
+    assert!(!cache
+        .restore(
+            project_dir.path(),
+            &BuildOptions {
+                target_os: Os::Windows,
+                ..options.clone()
+            },
+            "k1",
+            fresh.path()
+        )
+        .unwrap());
+    assert!(!cache
+        .restore(fresh.path(), &options, "k1", fresh.path())
+        .unwrap());
+
+    // Nothing is stored without `node_modules`, so the last install stays cached
+    cache
+        .insert(project_dir.path(), &options, "k3", fresh.path())
+        .unwrap();
+    assert!(cache
+        .restore(project_dir.path(), &options, "k1", fresh.path())
+        .unwrap());
+
+    cache.clean_cache().unwrap();
+    assert!(cache_dir.path().is_dir());
+    assert!(!cache
+        .restore(project_dir.path(), &options, "k1", restored.path())
+        .unwrap());
+}
+
+/// Test that the key cached dependencies are installed with changes with the package managers' configuration, not just
+/// the manifest and lockfiles.
+#[test]
+fn dependency_manifests_hash_covers_config() {
+    let project_dir = TempDir::new("project").unwrap();
+    let home_dir = TempDir::new("home").unwrap();
+    let userconfig = home_dir.path().join(".npmrc");
+    fs::write(project_dir.path().join("package.json"), "{}").unwrap();
+
+    let hash =
+        || helpers::dependency_manifests_hash(project_dir.path(), Some(&userconfig)).unwrap();
+    let mut hashes = vec![hash()];
+
+    fs::write(project_dir.path().join("bun.lock"), "{}").unwrap();
+    hashes.push(hash());
+
+    fs::write(
+        project_dir.path().join(".npmrc"),
+        "registry=https://example.com/\n",
+    )
+    .unwrap();
+    hashes.push(hash());
+
+    fs::write(project_dir.path().join(".yarnrc.yml"), "nodeLinker: pnp\n").unwrap();
+    hashes.push(hash());
+
+    fs::write(&userconfig, "registry=https://example.org/\n").unwrap();
+    hashes.push(hash());
+
+    assert_eq!(
+        hashes.iter().collect::<BTreeSet<_>>().len(),
+        hashes.len(),
+        "{:?}",
+        hashes
+    );
+    assert_eq!(hash(), hashes[4]);
+}
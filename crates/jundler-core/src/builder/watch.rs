@@ -0,0 +1,97 @@
+use super::copy::{ProjectFilter, IGNORE_FILE_NAME};
+use super::{get_configs, output_excludes, BuildOptions, Builder};
+use crate::ui::messages::WATCHING_MSG;
+use anyhow::{Context, Result};
+use log::debug;
+use notify::event::ModifyKind;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+
+/// How long to wait for more changes after the first one, so that a burst of changes (i.e. saving many files at once)
+/// only triggers a single rebuild.
+const DEBOUNCE_DURATION: Duration = Duration::from_millis(300);
+
+impl Builder {
+    /// Builds the project, and then rebuilds it every time a file in it changes. Build errors are reported, but don't
+    /// stop watching. Only returns if the file watcher itself fails.
+    pub fn watch(&mut self, project_dir: &Path, options: &BuildOptions) -> Result<()> {
+        let (tx, rx) = mpsc::channel();
+
+        let mut watcher =
+            notify::recommended_watcher(tx).context("Could not create a file watcher!")?;
+
+        watcher
+            .watch(project_dir, RecursiveMode::Recursive)
+            .context(format!("Could not watch {}", project_dir.display()))?;
+
+        loop {
+            if let Err(err) = self.build(project_dir, options) {
+                self.tracker.warn(format!("Build failed: {:?}", err));
+            }
+
+            self.tracker.println(WATCHING_MSG);
+
+            wait_for_changes(project_dir, options, &rx)?;
+        }
+    }
+}
+
+/// Blocks until a file that's part of the build changes, and then waits for any other changes to settle.
+fn wait_for_changes(
+    project_dir: &Path,
+    options: &BuildOptions,
+    rx: &Receiver<notify::Result<Event>>,
+) -> Result<()> {
+    // Rebuild the filter every time, as `.jundlerignore` or the output binary name may have changed since last time.
+    // Single scripts are the only file being watched, so they don't need one.
+    let filter = if project_dir.is_file() {
+        None
+    } else {
+        let excludes = get_configs(project_dir)
+            .map(|(_, package_config)| output_excludes(&package_config, options))
+            .unwrap_or_default();
+
+        Some(ProjectFilter::new(project_dir, &excludes)?)
+    };
+
+    loop {
+        let event = rx
+            .recv()
+            .context("The file watcher stopped unexpectedly!")?
+            .context("Error watching the project for changes")?;
+
+        if is_relevant(&event, filter.as_ref()) {
+            debug!("Rebuilding because of changes to {:?}", event.paths);
+            break;
+        }
+    }
+
+    // Swallow everything else that happens in quick succession
+    while rx.recv_timeout(DEBOUNCE_DURATION).is_ok() {}
+
+    Ok(())
+}
+
+/// Whether an event should trigger a rebuild. Only actual changes (not reads or metadata changes, which the build
+/// itself causes) to files that are part of the build are relevant. Without a filter, every change is relevant.
+fn is_relevant(event: &Event, filter: Option<&ProjectFilter>) -> bool {
+    let is_change = match event.kind {
+        EventKind::Create(_) | EventKind::Remove(_) => true,
+        EventKind::Modify(ModifyKind::Metadata(_)) => false,
+        EventKind::Modify(_) => true,
+        _ => false,
+    };
+
+    let Some(filter) = filter else {
+        return is_change;
+    };
+
+    is_change
+        && event.paths.iter().any(|path| {
+            path.file_name()
+                .is_some_and(|name| name == IGNORE_FILE_NAME)
+                || !filter.is_excluded(path)
+        })
+}
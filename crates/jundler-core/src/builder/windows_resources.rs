@@ -0,0 +1,903 @@
+use crate::js_config::{PackageConfig, WindowsConfig};
+use anyhow::{anyhow, Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// The resource type of version information.
+const RT_VERSION: u32 = 16;
+
+/// The resource type of application manifests.
+const RT_MANIFEST: u32 = 24;
+
+/// The language every resource is written in: US English.
+const LANG_EN_US: u32 = 0x0409;
+
+/// The code page of the strings in the version information: UTF-16.
+const CODEPAGE_UTF16: u32 = 1200;
+
+/// The index of the resource table in the data directories of a PE file.
+const RESOURCE_DIRECTORY: usize = 2;
+
+/// The index of the certificate table in the data directories of a PE file.
+const SECURITY_DIRECTORY: usize = 4;
+
+/// The size of a section header in a PE file.
+const SECTION_HEADER_SIZE: usize = 40;
+
+/// The characteristics of a resource section: initialized, readable data.
+const RESOURCE_SECTION_CHARACTERISTICS: u32 = 0x4000_0040;
+
+/// The version information written to a Windows executable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionInfo {
+    /// The version of the executable.
+    pub file_version: String,
+
+    /// The version of the product.
+    pub product_version: String,
+
+    /// The strings shown in the executable's properties, by their key (i.e. `CompanyName`).
+    pub strings: BTreeMap<&'static str, String>,
+}
+
+impl VersionInfo {
+    /// Gets the version information for an application, taking anything that isn't configured from `package.json`.
+    pub fn new(package_config: &PackageConfig, config: &WindowsConfig) -> Self {
+        let version = package_config.version.as_deref().unwrap_or("0.0.0");
+        let file_version = config
+            .file_version
+            .as_deref()
+            .unwrap_or(version)
+            .to_string();
+        let product_version = config
+            .product_version
+            .as_deref()
+            .unwrap_or(version)
+            .to_string();
+
+        let mut strings = BTreeMap::new();
+
+        let mut set = |key: &'static str, value: Option<&str>| {
+            if let Some(value) = value.filter(|value| !value.is_empty()) {
+                strings.insert(key, value.to_string());
+            }
+        };
+
+        set(
+            "CompanyName",
+            config
+                .company_name
+                .as_deref()
+                .or(package_config.author.as_ref().map(|author| author.name())),
+        );
+        set(
+            "FileDescription",
+            config
+                .file_description
+                .as_deref()
+                .or(package_config.description.as_deref())
+                .or(Some(package_config.name.as_str())),
+        );
+        set("FileVersion", Some(file_version.as_str()));
+        set("InternalName", Some(package_config.name.as_str()));
+        set("LegalCopyright", config.copyright.as_deref());
+        set(
+            "OriginalFilename",
+            Some(format!("{}.exe", package_config.name).as_str()),
+        );
+        set(
+            "ProductName",
+            config
+                .product_name
+                .as_deref()
+                .or(Some(package_config.name.as_str())),
+        );
+        set("ProductVersion", Some(product_version.as_str()));
+
+        Self {
+            file_version,
+            product_version,
+            strings,
+        }
+    }
+
+    /// Serializes the version information into a `VS_VERSIONINFO` resource.
+    pub fn to_resource(&self) -> Vec<u8> {
+        let (file_ms, file_ls) = version_words(&self.file_version);
+        let (product_ms, product_ls) = version_words(&self.product_version);
+
+        // VS_FIXEDFILEINFO
+        let fixed_file_info = [
+            0xFEEF_04BD, // Signature
+            0x0001_0000, // Structure version
+            file_ms,
+            file_ls,
+            product_ms,
+            product_ls,
+            0x3F,        // Valid file flags
+            0,           // File flags
+            0x0004_0004, // VOS_NT_WINDOWS32
+            1,           // VFT_APP
+            0,           // File subtype
+            0,           // File date (most significant)
+            0,           // File date (least significant)
+        ]
+        .iter()
+        .flat_map(|word: &u32| word.to_le_bytes())
+        .collect();
+
+        let strings = self
+            .strings
+            .iter()
+            .map(|(key, value)| version_block(key, BlockValue::Text(value), &[]))
+            .collect::<Vec<_>>();
+
+        let string_table = version_block(
+            &format!("{:04X}{:04X}", LANG_EN_US, CODEPAGE_UTF16),
+            BlockValue::None,
+            &strings,
+        );
+
+        let string_file_info = version_block("StringFileInfo", BlockValue::None, &[string_table]);
+
+        let translation = version_block(
+            "Translation",
+            BlockValue::Binary(
+                [LANG_EN_US as u16, CODEPAGE_UTF16 as u16]
+                    .iter()
+                    .flat_map(|word| word.to_le_bytes())
+                    .collect(),
+            ),
+            &[],
+        );
+
+        let var_file_info = version_block("VarFileInfo", BlockValue::None, &[translation]);
+
+        version_block(
+            "VS_VERSION_INFO",
+            BlockValue::Binary(fixed_file_info),
+            &[string_file_info, var_file_info],
+        )
+    }
+}
+
+/// Gets the application manifest to embed for a project, if one is configured. A manifest file is used as-is,
+/// otherwise one is generated from the configured settings.
+pub fn manifest(project_dir: &Path, config: &WindowsConfig) -> Result<Option<Vec<u8>>> {
+    if let Some(manifest) = &config.manifest {
+        let path = project_dir.join(manifest);
+
+        return fs::read(&path)
+            .context(format!(
+                "Error reading application manifest from {}",
+                path.display()
+            ))
+            .map(Some);
+    }
+
+    if config.long_path_aware.is_none() && config.execution_level.is_none() {
+        return Ok(None);
+    }
+
+    let mut manifest = String::from(concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        "\n",
+        r#"<assembly xmlns="urn:schemas-microsoft-com:asm.v1" manifestVersion="1.0">"#,
+        "\n",
+        r#"  <trustInfo xmlns="urn:schemas-microsoft-com:asm.v3">"#,
+        "\n",
+        "    <security>\n",
+        "      <requestedPrivileges>\n",
+    ));
+
+    manifest += &format!(
+        "        <requestedExecutionLevel level=\"{}\" uiAccess=\"false\"/>\n",
+        config.execution_level.unwrap_or_default().as_str()
+    );
+
+    manifest += concat!(
+        "      </requestedPrivileges>\n",
+        "    </security>\n",
+        "  </trustInfo>\n",
+        r#"  <compatibility xmlns="urn:schemas-microsoft-com:compatibility.v1">"#,
+        "\n",
+        "    <application>\n",
+        // Windows 10 and 11
+        r#"      <supportedOS Id="{8e0f7a12-bfb3-4fe8-b9a5-48fd50a15a9a}"/>"#,
+        "\n",
+        // Windows 8.1
+        r#"      <supportedOS Id="{1f676c76-80e1-4239-95bb-83d0f6d0da78}"/>"#,
+        "\n",
+        "    </application>\n",
+        "  </compatibility>\n",
+    );
+
+    if config.long_path_aware == Some(true) {
+        manifest += concat!(
+            r#"  <application xmlns="urn:schemas-microsoft-com:asm.v3">"#,
+            "\n",
+            "    <windowsSettings>\n",
+            r#"      <longPathAware xmlns="http://schemas.microsoft.com/SMI/2016/WindowsSettings">true</longPathAware>"#,
+            "\n",
+            "    </windowsSettings>\n",
+            "  </application>\n",
+        );
+    }
+
+    manifest += "</assembly>\n";
+
+    Ok(Some(manifest.into_bytes()))
+}
+
+/// Replaces the version information (and the application manifest, if given) in the resources of a Windows
+/// executable. Any signature on the executable is removed, as it would be invalid anyway.
+pub fn set_resources(
+    binary: &Path,
+    version_info: &VersionInfo,
+    manifest: Option<&[u8]>,
+) -> Result<()> {
+    let mut image = fs::read(binary).context(format!("Error reading {}", binary.display()))?;
+
+    let pe = PeHeaders::parse(&image)?;
+    let mut resources = read_resources(&image, &pe)?;
+
+    resources.retain(|(resource_type, _, _), _| *resource_type != ResourceId::Id(RT_VERSION));
+    resources.insert(
+        (
+            ResourceId::Id(RT_VERSION),
+            ResourceId::Id(1),
+            ResourceId::Id(LANG_EN_US),
+        ),
+        ResourceData {
+            data: version_info.to_resource(),
+            codepage: 0,
+        },
+    );
+
+    if let Some(manifest) = manifest {
+        resources.retain(|(resource_type, _, _), _| *resource_type != ResourceId::Id(RT_MANIFEST));
+        resources.insert(
+            (
+                ResourceId::Id(RT_MANIFEST),
+                ResourceId::Id(1),
+                ResourceId::Id(LANG_EN_US),
+            ),
+            ResourceData {
+                data: manifest.to_vec(),
+                codepage: 0,
+            },
+        );
+    }
+
+    write_resources(&mut image, &pe, &resources)?;
+
+    fs::write(binary, image).context(format!("Error writing {}", binary.display()))
+}
+
+/// Splits a version like `1.2.3` or `1.2.3.4` into the two 32-bit words of a Windows version. Anything that isn't a
+/// number (like a pre-release tag) is ignored.
+fn version_words(version: &str) -> (u32, u32) {
+    let mut parts = version
+        .trim_start_matches('v')
+        .split(['-', '+'])
+        .next()
+        .unwrap_or_default()
+        .split('.')
+        .map(|part| part.parse::<u16>().unwrap_or(0) as u32)
+        .chain(std::iter::repeat(0));
+
+    let mut next = || parts.next().unwrap_or(0);
+    let (major, minor, patch, build) = (next(), next(), next(), next());
+
+    ((major << 16) | minor, (patch << 16) | build)
+}
+
+/// The value of a block in a `VS_VERSIONINFO` resource.
+enum BlockValue<'a> {
+    /// No value.
+    None,
+
+    /// A string.
+    Text(&'a str),
+
+    /// Raw bytes.
+    Binary(Vec<u8>),
+}
+
+/// Serializes a block of a `VS_VERSIONINFO` resource, with its children. Every part of a block is aligned to 32 bits.
+fn version_block(key: &str, value: BlockValue, children: &[Vec<u8>]) -> Vec<u8> {
+    let (value_length, value_type, value) = match value {
+        BlockValue::None => (0, 1, Vec::new()),
+        BlockValue::Text(text) => {
+            let text = utf16_nul(text);
+            (text.len() / 2, 1, text)
+        }
+        BlockValue::Binary(bytes) => (bytes.len(), 0, bytes),
+    };
+
+    let mut block = vec![0, 0];
+    block.extend((value_length as u16).to_le_bytes());
+    block.extend((value_type as u16).to_le_bytes());
+    block.extend(utf16_nul(key));
+
+    if !value.is_empty() {
+        pad_to(&mut block, 4);
+        block.extend(value);
+    }
+
+    for child in children {
+        pad_to(&mut block, 4);
+        block.extend(child);
+    }
+
+    let length = block.len() as u16;
+    block[..2].copy_from_slice(&length.to_le_bytes());
+
+    block
+}
+
+/// Encodes a string as nul-terminated UTF-16.
+fn utf16_nul(text: &str) -> Vec<u8> {
+    text.encode_utf16()
+        .chain([0])
+        .flat_map(|unit| unit.to_le_bytes())
+        .collect()
+}
+
+/// Pads `bytes` with zeroes to a multiple of `alignment`.
+fn pad_to(bytes: &mut Vec<u8>, alignment: usize) {
+    bytes.resize(bytes.len().next_multiple_of(alignment), 0);
+}
+
+/// The type, name, or language of a resource. Named resources come before numbered ones, as in the PE format.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum ResourceId {
+    /// A name, in UTF-16.
+    Name(Vec<u16>),
+
+    /// A number.
+    Id(u32),
+}
+
+/// The data of a resource.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ResourceData {
+    /// The raw data.
+    data: Vec<u8>,
+
+    /// The code page of the data.
+    codepage: u32,
+}
+
+/// Every resource in an executable, by type, name, and language.
+type Resources = BTreeMap<(ResourceId, ResourceId, ResourceId), ResourceData>;
+
+/// The parts of the headers of a PE file that are needed to edit its resources.
+#[derive(Debug)]
+struct PeHeaders {
+    /// The offset of the COFF file header.
+    coff_header: usize,
+
+    /// The offset of the optional header.
+    optional_header: usize,
+
+    /// Whether the file is a 64-bit (PE32+) executable.
+    pe32_plus: bool,
+
+    /// The sections of the file.
+    sections: Vec<Section>,
+}
+
+/// A section of a PE file.
+#[derive(Debug, Clone, Copy)]
+struct Section {
+    /// The offset of the section's header.
+    header: usize,
+
+    /// The size of the section in memory.
+    virtual_size: u32,
+
+    /// The address of the section in memory, relative to the image base.
+    virtual_address: u32,
+
+    /// The size of the section in the file.
+    raw_size: u32,
+
+    /// The offset of the section in the file.
+    raw_pointer: u32,
+}
+
+impl Section {
+    /// The address just after the end of the section in memory.
+    fn virtual_end(&self) -> u32 {
+        self.virtual_address + self.virtual_size.max(self.raw_size)
+    }
+}
+
+impl PeHeaders {
+    /// Parses the headers of a PE file.
+    fn parse(image: &[u8]) -> Result<Self> {
+        if image.get(..2) != Some(b"MZ") {
+            return Err(anyhow!("Not a Windows executable: missing the DOS header!"));
+        }
+
+        let pe_header = read_u32(image, 0x3C)? as usize;
+
+        if image.get(pe_header..pe_header + 4) != Some(b"PE\0\0") {
+            return Err(anyhow!("Not a Windows executable: missing the PE header!"));
+        }
+
+        let coff_header = pe_header + 4;
+        let optional_header = coff_header + 20;
+
+        let pe32_plus = match read_u16(image, optional_header)? {
+            0x10B => false,
+            0x20B => true,
+            magic => {
+                return Err(anyhow!(
+                    "Unknown optional header in Windows executable: {:#x}",
+                    magic
+                ))
+            }
+        };
+
+        let section_count = read_u16(image, coff_header + 2)? as usize;
+        let section_table = optional_header + read_u16(image, coff_header + 16)? as usize;
+
+        let sections = (0..section_count)
+            .map(|i| {
+                let header = section_table + i * SECTION_HEADER_SIZE;
+
+                Ok(Section {
+                    header,
+                    virtual_size: read_u32(image, header + 8)?,
+                    virtual_address: read_u32(image, header + 12)?,
+                    raw_size: read_u32(image, header + 16)?,
+                    raw_pointer: read_u32(image, header + 20)?,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            coff_header,
+            optional_header,
+            pe32_plus,
+            sections,
+        })
+    }
+
+    /// The offset of a data directory entry in the optional header.
+    fn data_directory(&self, image: &[u8], index: usize) -> Result<Option<usize>> {
+        let (count, directories) = if self.pe32_plus { (108, 112) } else { (92, 96) };
+
+        if index >= read_u32(image, self.optional_header + count)? as usize {
+            return Ok(None);
+        }
+
+        Ok(Some(self.optional_header + directories + index * 8))
+    }
+
+    /// Converts an address in memory to an offset in the file.
+    fn rva_to_offset(&self, rva: u32) -> Result<usize> {
+        self.sections
+            .iter()
+            .find(|section| section.virtual_address <= rva && rva < section.virtual_end())
+            .map(|section| (section.raw_pointer + (rva - section.virtual_address)) as usize)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Address {:#x} isn't in any section of the Windows executable!",
+                    rva
+                )
+            })
+    }
+}
+
+/// Reads every resource in a PE file.
+fn read_resources(image: &[u8], pe: &PeHeaders) -> Result<Resources> {
+    let mut resources = Resources::new();
+
+    let Some(directory) = pe.data_directory(image, RESOURCE_DIRECTORY)? else {
+        return Ok(resources);
+    };
+
+    let rva = read_u32(image, directory)?;
+
+    if rva == 0 {
+        return Ok(resources);
+    }
+
+    read_resource_directory(image, pe, pe.rva_to_offset(rva)?, 0, &[], &mut resources)?;
+
+    Ok(resources)
+}
+
+/// Reads a directory of the resource tree. `base` is the offset of the root of the tree, which every offset in it is
+/// relative to, and `path` is the type and name of the resources in the directory, so far.
+fn read_resource_directory(
+    image: &[u8],
+    pe: &PeHeaders,
+    base: usize,
+    offset: usize,
+    path: &[ResourceId],
+    resources: &mut Resources,
+) -> Result<()> {
+    let directory = base + offset;
+    let entry_count =
+        read_u16(image, directory + 12)? as usize + read_u16(image, directory + 14)? as usize;
+
+    for i in 0..entry_count {
+        let entry = directory + 16 + i * 8;
+        let name = read_u32(image, entry)?;
+        let target = read_u32(image, entry + 4)?;
+
+        let id = if name & 0x8000_0000 != 0 {
+            let string = base + (name & 0x7FFF_FFFF) as usize;
+            let length = read_u16(image, string)? as usize;
+
+            ResourceId::Name(
+                (0..length)
+                    .map(|j| read_u16(image, string + 2 + j * 2))
+                    .collect::<Result<_>>()?,
+            )
+        } else {
+            ResourceId::Id(name)
+        };
+
+        let path = [path, &[id]].concat();
+
+        match (target & 0x8000_0000 != 0, path.as_slice()) {
+            // A subdirectory, for the names or languages of resources
+            (true, [_] | [_, _]) => read_resource_directory(
+                image,
+                pe,
+                base,
+                (target & 0x7FFF_FFFF) as usize,
+                &path,
+                resources,
+            )?,
+
+            // The data of a resource
+            (false, [resource_type, name, language]) => {
+                let data_entry = base + target as usize;
+                let data_offset = pe.rva_to_offset(read_u32(image, data_entry)?)?;
+                let size = read_u32(image, data_entry + 4)? as usize;
+
+                let data = image
+                    .get(data_offset..data_offset + size)
+                    .ok_or_else(|| anyhow!("Resource data is outside of the Windows executable!"))?
+                    .to_vec();
+
+                resources.insert(
+                    (resource_type.clone(), name.clone(), language.clone()),
+                    ResourceData {
+                        data,
+                        codepage: read_u32(image, data_entry + 8)?,
+                    },
+                );
+            }
+
+            _ => {
+                return Err(anyhow!(
+                    "The resources of the Windows executable are malformed!"
+                ))
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Serializes a resource tree into the contents of a resource section at `section_rva`.
+fn build_resource_section(resources: &Resources, section_rva: u32) -> Vec<u8> {
+    let mut tree: BTreeMap<&ResourceId, BTreeMap<&ResourceId, Vec<&ResourceId>>> = BTreeMap::new();
+
+    for (resource_type, name, language) in resources.keys() {
+        tree.entry(resource_type)
+            .or_default()
+            .entry(name)
+            .or_default()
+            .push(language);
+    }
+
+    let directory_size = |entries: usize| 16 + entries * 8;
+
+    // Directories come first (types, then names, then languages), then the data entries, then the names, then the
+    // data itself.
+    let mut name_directories = Vec::new();
+    let mut language_directories = Vec::new();
+    let mut offset = directory_size(tree.len());
+
+    for names in tree.values() {
+        name_directories.push(offset);
+        offset += directory_size(names.len());
+    }
+
+    for languages in tree.values().flat_map(|names| names.values()) {
+        language_directories.push(offset);
+        offset += directory_size(languages.len());
+    }
+
+    let data_entries = offset;
+    let mut section = vec![0; data_entries + resources.len() * 16];
+
+    let mut strings = BTreeMap::new();
+
+    for id in resources
+        .keys()
+        .flat_map(|(resource_type, name, language)| [resource_type, name, language])
+    {
+        if let ResourceId::Name(name) = id {
+            strings.entry(id).or_insert_with(|| {
+                let offset = section.len() as u32;
+
+                section.extend((name.len() as u16).to_le_bytes());
+                section.extend(name.iter().flat_map(|unit| unit.to_le_bytes()));
+
+                offset
+            });
+        }
+    }
+
+    let id_field = |id: &ResourceId| match id {
+        ResourceId::Name(_) => 0x8000_0000 | strings[id],
+        ResourceId::Id(id) => *id,
+    };
+
+    for (i, data) in resources.values().enumerate() {
+        pad_to(&mut section, 8);
+
+        let entry = data_entries + i * 16;
+        let data_rva = section_rva + section.len() as u32;
+
+        section[entry..entry + 4].copy_from_slice(&data_rva.to_le_bytes());
+        section[entry + 4..entry + 8].copy_from_slice(&(data.data.len() as u32).to_le_bytes());
+        section[entry + 8..entry + 12].copy_from_slice(&data.codepage.to_le_bytes());
+
+        section.extend(&data.data);
+    }
+
+    let write_directory =
+        |section: &mut Vec<u8>, offset: usize, entries: Vec<(&ResourceId, u32)>| {
+            let named = entries
+                .iter()
+                .filter(|(id, _)| matches!(id, ResourceId::Name(_)))
+                .count();
+
+            section[offset + 12..offset + 14].copy_from_slice(&(named as u16).to_le_bytes());
+            section[offset + 14..offset + 16]
+                .copy_from_slice(&((entries.len() - named) as u16).to_le_bytes());
+
+            for (i, (id, target)) in entries.into_iter().enumerate() {
+                let entry = offset + 16 + i * 8;
+
+                section[entry..entry + 4].copy_from_slice(&id_field(id).to_le_bytes());
+                section[entry + 4..entry + 8].copy_from_slice(&target.to_le_bytes());
+            }
+        };
+
+    write_directory(
+        &mut section,
+        0,
+        tree.keys()
+            .zip(&name_directories)
+            .map(|(id, offset)| (*id, 0x8000_0000 | *offset as u32))
+            .collect(),
+    );
+
+    let mut language_directory = language_directories.iter();
+    let mut data_entry = (0..resources.len()).map(|i| (data_entries + i * 16) as u32);
+
+    for (names, offset) in tree.values().zip(&name_directories) {
+        let mut entries = Vec::new();
+
+        for (name, languages) in names {
+            let directory = *language_directory
+                .next()
+                .expect("Every name has a directory");
+
+            entries.push((*name, 0x8000_0000 | directory as u32));
+
+            write_directory(
+                &mut section,
+                directory,
+                languages
+                    .iter()
+                    .map(|language| {
+                        (
+                            *language,
+                            data_entry.next().expect("Every resource has an entry"),
+                        )
+                    })
+                    .collect(),
+            );
+        }
+
+        write_directory(&mut section, *offset, entries);
+    }
+
+    section
+}
+
+/// Writes a resource tree into a PE file. The existing resource section is rewritten if it's the last section of
+/// the file, otherwise a new one is added after every other section.
+fn write_resources(image: &mut Vec<u8>, pe: &PeHeaders, resources: &Resources) -> Result<()> {
+    let section_alignment = read_u32(image, pe.optional_header + 32)?;
+    let file_alignment = read_u32(image, pe.optional_header + 36)? as usize;
+
+    // Remove the signature. It's always at the very end of the file, and would be invalid anyway.
+    if let Some(security) = pe.data_directory(image, SECURITY_DIRECTORY)? {
+        let offset = read_u32(image, security)? as usize;
+
+        if offset != 0 && offset <= image.len() {
+            image.truncate(offset);
+            image[security..security + 8].fill(0);
+        }
+    }
+
+    let resource_directory = pe
+        .data_directory(image, RESOURCE_DIRECTORY)?
+        .ok_or_else(|| anyhow!("The Windows executable has no resource directory!"))?;
+
+    let current_rva = read_u32(image, resource_directory)?;
+    let last_in_memory = pe
+        .sections
+        .iter()
+        .max_by_key(|section| section.virtual_address);
+    let last_in_file = pe
+        .sections
+        .iter()
+        .max_by_key(|section| section.raw_pointer + section.raw_size);
+
+    let reusable = last_in_memory.filter(|section| {
+        current_rva != 0
+            && section.virtual_address == current_rva
+            && last_in_file.is_some_and(|last| last.header == section.header)
+            && (section.raw_pointer + section.raw_size) as usize >= image.len()
+    });
+
+    let mut sections = pe.sections.clone();
+
+    let section = match reusable {
+        Some(section) => {
+            image.truncate(section.raw_pointer as usize);
+            *section
+        }
+
+        None => {
+            let header = pe.sections.last().map_or(
+                pe.optional_header + read_u16(image, pe.coff_header + 16)? as usize,
+                |section| section.header + SECTION_HEADER_SIZE,
+            );
+
+            let headers_end = (read_u32(image, pe.optional_header + 60)? as usize).min(
+                pe.sections
+                    .iter()
+                    .filter(|section| section.raw_pointer != 0)
+                    .map(|section| section.raw_pointer as usize)
+                    .min()
+                    .unwrap_or(usize::MAX),
+            );
+
+            if header + SECTION_HEADER_SIZE > headers_end
+                || image[header..header + SECTION_HEADER_SIZE]
+                    .iter()
+                    .any(|byte| *byte != 0)
+            {
+                return Err(anyhow!(
+                    "There's no room for another section in the headers of the Windows executable!"
+                ));
+            }
+
+            image[header..header + 8].copy_from_slice(b".rsrc\0\0\0");
+            image[header + 36..header + 40]
+                .copy_from_slice(&RESOURCE_SECTION_CHARACTERISTICS.to_le_bytes());
+
+            let section_count = read_u16(image, pe.coff_header + 2)? + 1;
+            image[pe.coff_header + 2..pe.coff_header + 4]
+                .copy_from_slice(&section_count.to_le_bytes());
+
+            let section = Section {
+                header,
+                virtual_size: 0,
+                virtual_address: pe
+                    .sections
+                    .iter()
+                    .map(Section::virtual_end)
+                    .max()
+                    .unwrap_or(section_alignment)
+                    .next_multiple_of(section_alignment),
+                raw_size: 0,
+                raw_pointer: 0,
+            };
+
+            sections.push(section);
+
+            section
+        }
+    };
+
+    pad_to(image, file_alignment);
+
+    let contents = build_resource_section(resources, section.virtual_address);
+
+    let section = Section {
+        virtual_size: contents.len() as u32,
+        raw_size: contents.len().next_multiple_of(file_alignment) as u32,
+        raw_pointer: image.len() as u32,
+        ..section
+    };
+
+    image.extend(&contents);
+    pad_to(image, file_alignment);
+
+    image[section.header + 8..section.header + 12]
+        .copy_from_slice(&section.virtual_size.to_le_bytes());
+    image[section.header + 12..section.header + 16]
+        .copy_from_slice(&section.virtual_address.to_le_bytes());
+    image[section.header + 16..section.header + 20]
+        .copy_from_slice(&section.raw_size.to_le_bytes());
+    image[section.header + 20..section.header + 24]
+        .copy_from_slice(&section.raw_pointer.to_le_bytes());
+
+    image[resource_directory..resource_directory + 4]
+        .copy_from_slice(&section.virtual_address.to_le_bytes());
+    image[resource_directory + 4..resource_directory + 8]
+        .copy_from_slice(&section.virtual_size.to_le_bytes());
+
+    let image_size = sections
+        .iter()
+        .map(|other| {
+            if other.header == section.header {
+                section
+            } else {
+                *other
+            }
+        })
+        .map(|section| section.virtual_end())
+        .max()
+        .unwrap_or_default()
+        .next_multiple_of(section_alignment);
+
+    image[pe.optional_header + 56..pe.optional_header + 60]
+        .copy_from_slice(&image_size.to_le_bytes());
+
+    let checksum = pe_checksum(image, pe.optional_header + 64);
+    image[pe.optional_header + 64..pe.optional_header + 68]
+        .copy_from_slice(&checksum.to_le_bytes());
+
+    Ok(())
+}
+
+/// Calculates the checksum of a PE file, skipping the checksum itself at `checksum_offset`.
+fn pe_checksum(image: &[u8], checksum_offset: usize) -> u32 {
+    let mut sum = image
+        .chunks(2)
+        .enumerate()
+        .filter(|(i, _)| i * 2 != checksum_offset && i * 2 != checksum_offset + 2)
+        .fold(0u32, |sum, (_, chunk)| {
+            let sum =
+                sum + u16::from_le_bytes([chunk[0], chunk.get(1).copied().unwrap_or(0)]) as u32;
+            (sum & 0xFFFF) + (sum >> 16)
+        });
+
+    sum = (sum & 0xFFFF) + (sum >> 16);
+
+    sum + image.len() as u32
+}
+
+/// Reads a little-endian `u16` from `image` at `offset`.
+fn read_u16(image: &[u8], offset: usize) -> Result<u16> {
+    image
+        .get(offset..offset + 2)
+        .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+        .ok_or_else(|| anyhow!("The Windows executable is truncated!"))
+}
+
+/// Reads a little-endian `u32` from `image` at `offset`.
+fn read_u32(image: &[u8], offset: usize) -> Result<u32> {
+    image
+        .get(offset..offset + 4)
+        .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .ok_or_else(|| anyhow!("The Windows executable is truncated!"))
+}
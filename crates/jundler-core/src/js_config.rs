@@ -0,0 +1,433 @@
+pub mod schema;
+
+use crate::builder::platforms::Target;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::default::Default;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The name of the file with Jundler's own configuration for a project, used instead of the `jundler` field of
+/// `package.json` if it exists.
+pub const CONFIG_FILE_NAME: &str = "jundler.toml";
+
+/// A representation of the NodeJS `sea-config.json` configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEAConfig {
+    /// The main entrypoint to the file that is to be bundled.
+    pub main: String,
+
+    /// The output SEA blob name.
+    pub output: String,
+
+    /// Assets to embed in the SEA blob, as a map of asset keys to file paths.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub assets: BTreeMap<String, String>,
+
+    // Any other fields that are not explicitly defined. Sorted, so that rewriting the file is deterministic.
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+/// A representation of the NodeJS `package.json` configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageConfig {
+    /// The name of the project.
+    pub name: String,
+
+    /// The version of the project.
+    pub version: Option<String>,
+
+    /// A short description of the project.
+    pub description: Option<String>,
+
+    /// The author of the project.
+    pub author: Option<Person>,
+
+    /// The SPDX identifier of the project's license.
+    pub license: Option<String>,
+
+    /// The URL of the project's homepage.
+    pub homepage: Option<String>,
+
+    /// The main entrypoint as defined by the project.
+    pub main: Option<String>,
+
+    /// The type of project. Defaults to CommonJS.
+    #[serde(rename = "type", default = "ProjectType::default")]
+    pub project_type: ProjectType,
+
+    /// Jundler's own configuration for the project.
+    #[serde(default)]
+    pub jundler: JundlerConfig,
+
+    // Any other fields that are not explicitly defined.
+    #[serde(flatten)]
+    other: BTreeMap<String, Value>,
+}
+
+impl PackageConfig {
+    /// Whether installing the project's dependencies needs more of the project than its manifests: it runs install
+    /// scripts of its own, has workspaces, or depends on packages inside of it (i.e. `file:./vendor/pkg`).
+    pub fn install_needs_sources(&self) -> bool {
+        const INSTALL_SCRIPTS: &[&str] = &["preinstall", "install", "postinstall", "prepare"];
+        const LOCAL_PROTOCOLS: &[&str] = &["file:", "link:", "portal:", "workspace:"];
+
+        let has_install_scripts = self.other.get("scripts").is_some_and(|scripts| {
+            INSTALL_SCRIPTS
+                .iter()
+                .any(|script| scripts.get(script).is_some())
+        });
+
+        let has_local_dependencies = ["dependencies", "devDependencies", "optionalDependencies"]
+            .iter()
+            .filter_map(|field| self.other.get(*field)?.as_object())
+            .flat_map(|dependencies| dependencies.values())
+            .filter_map(Value::as_str)
+            .any(|spec| {
+                LOCAL_PROTOCOLS
+                    .iter()
+                    .any(|protocol| spec.starts_with(protocol))
+            });
+
+        has_install_scripts || has_local_dependencies || self.other.contains_key("workspaces")
+    }
+
+    /// Gets the project's script called `name`, if it has one.
+    pub fn script(&self, name: &str) -> Option<&str> {
+        self.other.get("scripts")?.get(name)?.as_str()
+    }
+}
+
+/// Enum to represent the different types of project.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectType {
+    /// A module
+    Module,
+
+    /// A commonjs project
+    #[default]
+    CommonJS,
+}
+
+/// A person in `package.json`, either as a `"Name <email> (url)"` string or as an object.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Person {
+    /// The shorthand `"Name <email> (url)"` form.
+    Shorthand(String),
+
+    /// The object form.
+    Details {
+        /// The person's name.
+        name: String,
+
+        /// The person's email address.
+        email: Option<String>,
+    },
+}
+
+impl Person {
+    /// Gets the name of the person, without any email or URL.
+    pub fn name(&self) -> &str {
+        match self {
+            Person::Shorthand(person) => person.split(['<', '(']).next().unwrap_or_default().trim(),
+            Person::Details { name, .. } => name,
+        }
+    }
+
+    /// Gets the name and email of the person as `Name <email>`, or just the name if there's no email.
+    pub fn contact(&self) -> String {
+        let email = match self {
+            Person::Shorthand(person) => person
+                .split_once('<')
+                .and_then(|(_, rest)| rest.split_once('>'))
+                .map(|(email, _)| email.trim()),
+            Person::Details { email, .. } => email.as_deref(),
+        };
+
+        match email {
+            Some(email) => format!("{} <{}>", self.name(), email),
+            None => self.name().to_string(),
+        }
+    }
+}
+
+/// Jundler's own configuration for a project, from `jundler.toml` or the `jundler` field of `package.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JundlerConfig {
+    /// Configuration for macOS executables.
+    #[serde(default)]
+    pub macos: MacOSConfig,
+
+    /// Configuration for Windows executables.
+    #[serde(default)]
+    pub windows: WindowsConfig,
+
+    /// The keys to write detached signatures with.
+    #[serde(default)]
+    pub signatures: SignaturesConfig,
+
+    /// Configuration for Linux binaries and packages.
+    #[serde(default)]
+    pub linux: LinuxConfig,
+
+    /// Configuration for container images.
+    #[serde(default)]
+    pub docker: DockerConfig,
+
+    /// Configuration for Windows installers.
+    #[serde(default)]
+    pub msi: MsiConfig,
+
+    /// Configuration for releases.
+    #[serde(default)]
+    pub release: ReleaseConfig,
+
+    /// The template to name built binaries with, i.e. `{name}-{version}-{os}-{arch}{ext}`. Just the project's name
+    /// by default.
+    pub output_name: Option<String>,
+
+    /// The targets the project is released for, i.e. `["linux-x64", "darwin-arm64", "win-x64"]`, which generated CI
+    /// workflows build.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub targets: Vec<Target>,
+
+    /// The maximum size of built binaries, by target (i.e. `linux-x64` or `darwin-universal`, or `*` for every
+    /// target), like `{ "*" = "80MB", "win-x64" = "75 MiB" }`. Builds of bigger binaries fail. `--max-size` overrides it.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub max_size: BTreeMap<String, String>,
+
+    /// The command that `jundler test` tests built binaries with, i.e. `npm test` or `bats test/`, which finds the
+    /// binary in `JUNDLER_BINARY`. Defaults to the project's `test` script.
+    pub test: Option<String>,
+
+    /// The directory (relative to the project) to cache Node.js, ESBuild, and builds in, i.e. a volume that's kept
+    /// between CI jobs. `--cache-dir` and `JUNDLER_CACHE_DIR` override it.
+    pub cache_dir: Option<PathBuf>,
+
+    /// A cache of downloads shared between machines, i.e. by a team: a directory, or an HTTP server that downloads
+    /// can be `GET` from and `PUT` to. `--shared-cache` and `JUNDLER_SHARED_CACHE` override it.
+    pub shared_cache: Option<String>,
+}
+
+/// Configuration for releases of a project.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseConfig {
+    /// The URL that release archives are uploaded to, for the Homebrew formula and Scoop manifest. `{version}` is
+    /// replaced with the version, i.e. `https://github.com/me/mytool/releases/download/v{version}`.
+    pub url: Option<String>,
+}
+
+impl JundlerConfig {
+    /// Reads the configuration from the project's `jundler.toml`, if it has one.
+    pub fn from_project(project_dir: &Path) -> Result<Option<JundlerConfig>> {
+        let path = project_dir.join(CONFIG_FILE_NAME);
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let contents = fs::read_to_string(&path)
+            .context(format!("Could not read the `{}` file!", CONFIG_FILE_NAME))?;
+
+        toml::from_str(&contents)
+            .map(Some)
+            .context(format!("Could not parse the `{}` file!", CONFIG_FILE_NAME))
+    }
+}
+
+/// Configuration for Linux packages.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinuxConfig {
+    /// The maintainer of the packages, as `Name <email>`. Defaults to the author.
+    pub maintainer: Option<String>,
+
+    /// The path (relative to the project) of the PNG or SVG icon of AppImages. Defaults to a generic icon.
+    pub icon: Option<String>,
+
+    /// The packages that `.deb` packages depend on, i.e. `libc6 (>= 2.28)`. Defaults to the libraries Node.js needs.
+    #[serde(default)]
+    pub deb_depends: Vec<String>,
+
+    /// The packages that `.rpm` packages depend on, i.e. `glibc >= 2.28`. Defaults to the libraries Node.js needs.
+    #[serde(default)]
+    pub rpm_requires: Vec<String>,
+
+    /// The distributions the binary has to run on, i.e. `ubuntu-20.04` or `centos-7`. Builds warn about those with a
+    /// glibc older than the binary needs.
+    #[serde(default)]
+    pub distros: Vec<String>,
+}
+
+/// Configuration for MSI installers of Windows executables.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MsiConfig {
+    /// The GUID that identifies every version of the product, so that installing a new version replaces the old one.
+    /// Defaults to one derived from the project's name.
+    pub upgrade_code: Option<String>,
+
+    /// The name of the product, shown in "Apps & features" and used for its directory in Program Files. Defaults to
+    /// the project's name.
+    pub product_name: Option<String>,
+
+    /// The manufacturer of the product, shown in "Apps & features". Defaults to the author.
+    pub manufacturer: Option<String>,
+
+    /// Whether to add the install directory to the system `PATH`. Defaults to `true`.
+    pub add_to_path: Option<bool>,
+}
+
+/// Configuration for container images of Linux executables.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DockerConfig {
+    /// The image to build on. Defaults to `gcr.io/distroless/cc-debian12`, which has just the libraries Node.js
+    /// needs.
+    pub base_image: Option<String>,
+}
+
+/// The keys to write detached signatures of the built outputs with, when they're turned on.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignaturesConfig {
+    /// The ID (or email) of the GPG key to sign with. Defaults to GPG's default key.
+    pub gpg_key: Option<String>,
+
+    /// The path (relative to the project) of the minisign secret key to sign with. Defaults to
+    /// `~/.minisign/minisign.key`.
+    pub minisign_key: Option<String>,
+
+    /// The path (relative to the project) of the cosign key to sign with, or the URI of a key in a KMS, i.e.
+    /// `awskms:///alias/releases`. Defaults to signing keylessly, with an OIDC identity.
+    pub cosign_key: Option<String>,
+
+    /// The name of the environment variable with the password of the key. Defaults to `JUNDLER_GPG_PASSPHRASE`,
+    /// `JUNDLER_MINISIGN_PASSWORD`, or `COSIGN_PASSWORD`.
+    pub password_env: Option<String>,
+}
+
+/// Configuration for macOS executables.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MacOSConfig {
+    /// The identity to codesign with, i.e. `Developer ID Application: ...`. Executables are ad-hoc signed if it
+    /// isn't set.
+    pub codesign_identity: Option<String>,
+
+    /// The path (relative to the project) of the entitlements to codesign with. Defaults to the entitlements Node.js
+    /// needs to run with the hardened runtime.
+    pub entitlements: Option<String>,
+
+    /// The path (relative to the project) of the P12 file with the certificate to sign with on other OSes, with
+    /// `rcodesign`. On macOS, the `codesign_identity` from the keychain is used instead.
+    pub certificate: Option<String>,
+
+    /// The name of the environment variable with the password of the P12 file. Defaults to
+    /// `JUNDLER_CERTIFICATE_PASSWORD`.
+    pub certificate_password_env: Option<String>,
+
+    /// The credentials to notarize executables with, when notarization is turned on.
+    #[serde(default)]
+    pub notarize: NotarizeConfig,
+}
+
+/// The credentials to notarize macOS executables with: either a keychain profile (made with `xcrun notarytool
+/// store-credentials`), or an App Store Connect API key.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotarizeConfig {
+    /// The name of the keychain profile with the credentials.
+    pub keychain_profile: Option<String>,
+
+    /// The path (relative to the project) of the App Store Connect API key.
+    pub api_key: Option<String>,
+
+    /// The ID of the App Store Connect API key.
+    pub api_key_id: Option<String>,
+
+    /// The issuer ID of the App Store Connect API key.
+    pub api_issuer: Option<String>,
+}
+
+/// Configuration for Windows executables: their version information, application manifest, and signing. Anything
+/// that isn't set is taken from `package.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowsConfig {
+    /// The company that makes the application. Defaults to the author of the project.
+    pub company_name: Option<String>,
+
+    /// The name of the product. Defaults to the name of the project.
+    pub product_name: Option<String>,
+
+    /// A description of the application. Defaults to the description of the project.
+    pub file_description: Option<String>,
+
+    /// The version of the executable. Defaults to the version of the project.
+    pub file_version: Option<String>,
+
+    /// The version of the product. Defaults to the version of the project.
+    pub product_version: Option<String>,
+
+    /// The copyright notice of the application.
+    pub copyright: Option<String>,
+
+    /// The path (relative to the project) of an application manifest to embed instead of the one from Node.js.
+    pub manifest: Option<String>,
+
+    /// Whether the application can use paths longer than `MAX_PATH`. Embeds a generated manifest if set.
+    pub long_path_aware: Option<bool>,
+
+    /// The privileges the application asks for when it starts. Embeds a generated manifest if set.
+    pub execution_level: Option<ExecutionLevel>,
+
+    /// The path (relative to the project) of the PFX file with the certificate to sign with.
+    pub certificate: Option<String>,
+
+    /// The name of the environment variable with the password of the PFX file. Defaults to
+    /// `JUNDLER_CERTIFICATE_PASSWORD`.
+    pub certificate_password_env: Option<String>,
+
+    /// The SHA1 thumbprint of the certificate to sign with, from the certificate store.
+    pub certificate_thumbprint: Option<String>,
+
+    /// The URL of the RFC 3161 timestamp server to timestamp signatures with.
+    pub timestamp_url: Option<String>,
+
+    /// The description of the signed content, shown when Windows asks for permission to run it.
+    pub sign_description: Option<String>,
+}
+
+/// The privileges a Windows application asks for when it starts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExecutionLevel {
+    /// The same privileges as whatever started it.
+    #[default]
+    AsInvoker,
+
+    /// The highest privileges the user can get, prompting for them if needed.
+    HighestAvailable,
+
+    /// Administrator privileges, prompting for them if needed.
+    RequireAdministrator,
+}
+
+impl ExecutionLevel {
+    /// The name of the execution level in an application manifest.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExecutionLevel::AsInvoker => "asInvoker",
+            ExecutionLevel::HighestAvailable => "highestAvailable",
+            ExecutionLevel::RequireAdministrator => "requireAdministrator",
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use serde_json::{json, Value};
+
+/// The JSON Schema dialect the schemas are written in.
+const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// The configuration files there are schemas for.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SchemaFile {
+    /// `jundler.toml`, which has the same fields as the `jundler` field of `package.json`.
+    #[default]
+    Jundler,
+
+    /// The `jundler` field of `package.json` (along with the fields of `package.json` that Jundler reads).
+    PackageJson,
+
+    /// `sea-config.json`.
+    SeaConfig,
+}
+
+/// Gets the JSON Schema of `file`, for editors to autocomplete it and CI to validate it.
+pub fn schema(file: SchemaFile) -> Value {
+    match file {
+        SchemaFile::Jundler => {
+            let mut schema = jundler_schema();
+
+            schema["$schema"] = json!(DIALECT);
+            schema["title"] = json!("jundler.toml");
+
+            schema
+        }
+
+        SchemaFile::PackageJson => json!({
+            "$schema": DIALECT,
+            "title": "package.json (as read by Jundler)",
+            "type": "object",
+            "properties": {
+                "name": string("The name of the project, which the binary is named after."),
+                "version": string("The version of the project."),
+                "description": string("A short description of the project."),
+                "author": {
+                    "description": "The author of the project, as `Name <email> (url)` or an object.",
+                    "anyOf": [
+                        { "type": "string" },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "name": string("The person's name."),
+                                "email": string("The person's email address."),
+                            },
+                            "required": ["name"],
+                        },
+                    ],
+                },
+                "license": string("The SPDX identifier of the project's license."),
+                "homepage": string("The URL of the project's homepage."),
+                "main": string("The entrypoint of the project."),
+                "type": {
+                    "description": "The type of project. Defaults to `commonjs`.",
+                    "enum": ["module", "commonjs"],
+                },
+                "jundler": jundler_schema(),
+            },
+            "required": ["name"],
+        }),
+
+        SchemaFile::SeaConfig => json!({
+            "$schema": DIALECT,
+            "title": "sea-config.json",
+            "type": "object",
+            "properties": {
+                "main": string("The entrypoint of the application."),
+                "output": string("Where to write the SEA blob."),
+                "disableExperimentalSEAWarning": boolean("Whether to hide Node.js's warning that SEAs are experimental."),
+                "useSnapshot": boolean("Whether to build a startup snapshot. Can't be used in reproducible builds."),
+                "useCodeCache": boolean("Whether to embed a V8 code cache. Can't be used in reproducible builds."),
+                "execArgv": {
+                    "description": "Node.js options to run the application with.",
+                    "type": "array",
+                    "items": { "type": "string" },
+                },
+                "assets": {
+                    "description": "Files to embed as SEA assets, as a map of asset keys to paths. Jundler adds native addons (and third-party notices) to them.",
+                    "type": "object",
+                    "additionalProperties": { "type": "string" },
+                },
+            },
+            "required": ["main", "output"],
+        }),
+    }
+}
+
+/// Gets the schema of Jundler's own configuration, from `jundler.toml` or the `jundler` field of `package.json`.
+fn jundler_schema() -> Value {
+    json!({
+        "description": "Jundler's configuration for the project.",
+        "type": "object",
+        "properties": {
+            "macos": object("Configuration for macOS executables.", json!({
+                "codesignIdentity": string("The identity to codesign with, i.e. `Developer ID Application: ...`. Executables are ad-hoc signed if it isn't set."),
+                "entitlements": string("The path (relative to the project) of the entitlements to codesign with. Defaults to the entitlements Node.js needs to run with the hardened runtime."),
+                "certificate": string("The path (relative to the project) of the P12 file with the certificate to sign with on other OSes, with `rcodesign`."),
+                "certificatePasswordEnv": string("The name of the environment variable with the password of the P12 file. Defaults to `JUNDLER_CERTIFICATE_PASSWORD`."),
+                "notarize": object("The credentials to notarize executables with: a keychain profile, or an App Store Connect API key.", json!({
+                    "keychainProfile": string("The name of the keychain profile with the credentials (made with `xcrun notarytool store-credentials`)."),
+                    "apiKey": string("The path (relative to the project) of the App Store Connect API key."),
+                    "apiKeyId": string("The ID of the App Store Connect API key."),
+                    "apiIssuer": string("The issuer ID of the App Store Connect API key."),
+                })),
+            })),
+            "windows": object("Configuration for Windows executables: their version information, application manifest, and signing. Anything that isn't set is taken from `package.json`.", json!({
+                "companyName": string("The company that makes the application. Defaults to the author of the project."),
+                "productName": string("The name of the product. Defaults to the name of the project."),
+                "fileDescription": string("A description of the application. Defaults to the description of the project."),
+                "fileVersion": string("The version of the executable. Defaults to the version of the project."),
+                "productVersion": string("The version of the product. Defaults to the version of the project."),
+                "copyright": string("The copyright notice of the application."),
+                "manifest": string("The path (relative to the project) of an application manifest to embed instead of the one from Node.js."),
+                "longPathAware": boolean("Whether the application can use paths longer than `MAX_PATH`. Embeds a generated manifest if set."),
+                "executionLevel": {
+                    "description": "The privileges the application asks for when it starts. Embeds a generated manifest if set.",
+                    "enum": ["asInvoker", "highestAvailable", "requireAdministrator"],
+                },
+                "certificate": string("The path (relative to the project) of the PFX file with the certificate to sign with."),
+                "certificatePasswordEnv": string("The name of the environment variable with the password of the PFX file. Defaults to `JUNDLER_CERTIFICATE_PASSWORD`."),
+                "certificateThumbprint": string("The SHA1 thumbprint of the certificate to sign with, from the certificate store."),
+                "timestampUrl": string("The URL of the RFC 3161 timestamp server to timestamp signatures with."),
+                "signDescription": string("The description of the signed content, shown when Windows asks for permission to run it."),
+            })),
+            "signatures": object("The keys to write detached signatures with.", json!({
+                "gpgKey": string("The ID (or email) of the GPG key to sign with. Defaults to GPG's default key."),
+                "minisignKey": string("The path (relative to the project) of the minisign secret key to sign with. Defaults to `~/.minisign/minisign.key`."),
+                "cosignKey": string("The path (relative to the project) of the cosign key to sign with, or the URI of a key in a KMS, i.e. `awskms:///alias/releases`. Defaults to signing keylessly, with an OIDC identity."),
+                "passwordEnv": string("The name of the environment variable with the password of the key. Defaults to `JUNDLER_GPG_PASSPHRASE`, `JUNDLER_MINISIGN_PASSWORD`, or `COSIGN_PASSWORD`."),
+            })),
+            "linux": object("Configuration for Linux binaries and packages.", json!({
+                "maintainer": string("The maintainer of the packages, as `Name <email>`. Defaults to the author."),
+                "icon": string("The path (relative to the project) of the PNG or SVG icon of AppImages. Defaults to a generic icon."),
+                "debDepends": strings("The packages that `.deb` packages depend on, i.e. `libc6 (>= 2.28)`. Defaults to the libraries Node.js needs."),
+                "rpmRequires": strings("The packages that `.rpm` packages depend on, i.e. `glibc >= 2.28`. Defaults to the libraries Node.js needs."),
+                "distros": strings("The distributions the binary has to run on, i.e. `ubuntu-20.04` or `centos-7`. Builds warn about those with a glibc older than the binary needs."),
+            })),
+            "docker": object("Configuration for container images.", json!({
+                "baseImage": string("The image to build on. Defaults to `gcr.io/distroless/cc-debian12`, which has just the libraries Node.js needs."),
+            })),
+            "msi": object("Configuration for Windows installers.", json!({
+                "upgradeCode": string("The GUID that identifies every version of the product, so that installing a new version replaces the old one. Defaults to one derived from the project's name."),
+                "productName": string("The name of the product, shown in \"Apps & features\". Defaults to the project's name."),
+                "manufacturer": string("The manufacturer of the product, shown in \"Apps & features\". Defaults to the author."),
+                "addToPath": boolean("Whether to add the install directory to the system `PATH`. Defaults to `true`."),
+            })),
+            "release": object("Configuration for releases.", json!({
+                "url": string("The URL that release archives are uploaded to, for the Homebrew formula and Scoop manifest. `{version}` is replaced with the version."),
+            })),
+            "outputName": string("The template to name built binaries with, i.e. `{name}-{version}-{os}-{arch}{ext}`. Just the project's name by default."),
+            "targets": {
+                "description": "The targets the project is released for, which generated CI workflows build.",
+                "type": "array",
+                "items": {
+                    "type": "string",
+                    "examples": ["linux-x64", "linux-arm64", "darwin-x64", "darwin-arm64", "win-x64"],
+                },
+            },
+            "maxSize": {
+                "description": "The maximum size of built binaries, by target (i.e. `linux-x64` or `darwin-universal`, or `*` for every target), like `80MB` or `75 MiB`. Builds of bigger binaries fail. `--max-size` overrides it.",
+                "type": "object",
+                "additionalProperties": { "type": "string" },
+                "examples": [{ "*": "80MB", "win-x64": "75 MiB" }],
+            },
+            "test": string("The command that `jundler test` tests built binaries with, i.e. `npm test` or `bats test/`, which finds the binary in `JUNDLER_BINARY`. Defaults to the project's `test` script."),
+            "cacheDir": string("The directory (relative to the project) to cache Node.js, ESBuild, and builds in. `--cache-dir` and `JUNDLER_CACHE_DIR` override it."),
+            "sharedCache": string("A cache of downloads shared between machines: a directory, or an HTTP server that downloads can be `GET` from and `PUT` to. `--shared-cache` and `JUNDLER_SHARED_CACHE` override it."),
+        },
+        "additionalProperties": false,
+    })
+}
+
+/// A string field.
+fn string(description: &str) -> Value {
+    json!({ "description": description, "type": "string" })
+}
+
+/// A boolean field.
+fn boolean(description: &str) -> Value {
+    json!({ "description": description, "type": "boolean" })
+}
+
+/// A list of strings.
+fn strings(description: &str) -> Value {
+    json!({ "description": description, "type": "array", "items": { "type": "string" } })
+}
+
+/// An object with `properties`, and nothing else.
+fn object(description: &str, properties: Value) -> Value {
+    json!({
+        "description": description,
+        "type": "object",
+        "properties": properties,
+        "additionalProperties": false,
+    })
+}
@@ -0,0 +1,31 @@
+//! The library behind [jundler](https://github.com/cogsandsquigs/jundler), for building Node.js projects into single
+//! executable applications (SEAs) from Rust, without shelling out to the `jundler` CLI.
+//!
+//! A [`Builder`] downloads (and caches) the Node.js binaries and ESBuild, and builds projects with [`BuildOptions`].
+//! Each build returns a [`BuildOutput`], with the path to the built binary, every other file the build wrote (with
+//! their sizes and checksums), what it was built for, and the steps, warnings, and duration of the build:
+//!
+//! ```no_run
+//! use jundler_core::{BuildOptions, Builder};
+//! use std::path::Path;
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let mut builder = Builder::new(Path::new("cache").to_path_buf())?;
+//! let options = BuildOptions::new("22.12.0".parse()?);
+//! let output = builder.build(Path::new("my-app"), &options)?;
+//!
+//! println!("Built {}", output.app_path.display());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Projects are configured just like with the CLI, in their `jundler.toml` or the `jundler` field of their
+//! `package.json` (see [`js_config`]). Errors are [`anyhow::Error`]s, which can be sorted into what went wrong with
+//! [`builder::errors::ErrorClass`].
+
+pub mod builder;
+pub mod js_config;
+pub mod ui;
+
+pub use builder::platforms::{Arch, Os, Target};
+pub use builder::{BuildOptions, BuildOutput, Builder};
@@ -0,0 +1,57 @@
+use console::Term;
+use std::env;
+
+/// When to color output.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Color output going to a terminal, unless `NO_COLOR` (or `CLICOLOR=0`) is set. `CLICOLOR_FORCE` or
+    /// `FORCE_COLOR` color it even when it isn't going to a terminal.
+    #[default]
+    Auto,
+
+    /// Always color output.
+    Always,
+
+    /// Never color output.
+    Never,
+}
+
+impl ColorChoice {
+    /// Works out whether to color output from the choice and the environment variables `var` looks up. `None` if it's
+    /// up to whether the output is going to a terminal.
+    pub fn resolve<F>(self, var: F) -> Option<bool>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        // Empty variables count as unset, see https://no-color.org
+        let is_set = |name: &str| var(name).is_some_and(|value| !value.is_empty());
+        let is_on = |name: &str| var(name).is_some_and(|value| !value.is_empty() && value != "0");
+
+        match self {
+            ColorChoice::Always => Some(true),
+            ColorChoice::Never => Some(false),
+            ColorChoice::Auto if is_set("NO_COLOR") => Some(false),
+            ColorChoice::Auto if is_on("CLICOLOR_FORCE") || is_on("FORCE_COLOR") => Some(true),
+            ColorChoice::Auto if var("CLICOLOR").as_deref() == Some("0") => Some(false),
+            ColorChoice::Auto => None,
+        }
+    }
+
+    /// Turns colors on or off for everything printed from now on. Everything people read is styled the same way, so
+    /// it's colored when the stream it goes to (stderr if `human_on_stderr`, or stdout) is a terminal that can show
+    /// colors.
+    pub fn apply(self, human_on_stderr: bool) {
+        let term = if human_on_stderr {
+            Term::stderr()
+        } else {
+            Term::stdout()
+        };
+
+        let enabled = self
+            .resolve(|name| env::var(name).ok())
+            .unwrap_or_else(|| term.features().colors_supported());
+
+        console::set_colors_enabled(enabled);
+        console::set_colors_enabled_stderr(enabled);
+    }
+}
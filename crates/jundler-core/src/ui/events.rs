@@ -0,0 +1,157 @@
+use super::log_file;
+use super::reporter::Reporter;
+use serde::Serialize;
+use std::io::{self, Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// How often to emit a download event when the size of the download isn't known.
+const UNKNOWN_SIZE_STEP: u64 = 1024 * 1024;
+
+/// Whether events are printed to stdout.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// How progress is shown.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProgressFormat {
+    /// Spinners (or a line per step in CI) for people to read.
+    #[default]
+    Human,
+
+    /// Newline-delimited JSON events on stdout, for other tools to show their own progress with. Everything else is
+    /// printed to stderr.
+    Json,
+}
+
+/// Something that happened while running a command, printed as a line of JSON with `--progress json`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event<'a> {
+    /// A step started.
+    StepStarted {
+        /// The message of the step, i.e. `📦 Bundling project with ESBuild`.
+        step: &'a str,
+
+        /// How deep the step is, as steps can be part of other steps.
+        depth: usize,
+    },
+
+    /// A step finished successfully.
+    StepFinished {
+        /// The message of the step.
+        step: &'a str,
+
+        /// How deep the step is.
+        depth: usize,
+
+        /// How long the step took, in seconds.
+        duration_secs: f64,
+    },
+
+    /// A step failed, and the command stopped.
+    StepFailed {
+        /// The message of the step.
+        step: &'a str,
+
+        /// How deep the step is.
+        depth: usize,
+
+        /// How long the step ran for before it failed, in seconds.
+        duration_secs: f64,
+
+        /// A summary of the error, if the step knows it. Steps that fail by returning early don't.
+        error: Option<&'a str>,
+    },
+
+    /// Part of a file was downloaded.
+    Download {
+        /// The URL being downloaded.
+        url: &'a str,
+
+        /// How many bytes have been downloaded so far.
+        downloaded: u64,
+
+        /// How many bytes there are in total, if the server said.
+        total: Option<u64>,
+    },
+
+    /// A warning was shown.
+    Warning {
+        /// The warning.
+        message: &'a str,
+    },
+}
+
+/// Turns printing events to stdout on or off.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether events are printed to stdout.
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Prints an event to stdout as a line of JSON, if events are enabled. Events are always written to the log file.
+pub fn emit(event: &Event) {
+    log_file::event(event);
+
+    if !is_enabled() {
+        return;
+    }
+
+    let line = serde_json::to_string(event).expect("Events are always serializable!");
+
+    // Lock stdout, so events from other threads (i.e. background downloads) can't end up on the same line
+    let _ = writeln!(io::stdout().lock(), "{}", line);
+}
+
+/// Reads a download into memory, reporting its progress to `reporter` (and emitting download events) along the way:
+/// every percent of the way there, or every MiB if `total` (the size of the download) isn't known.
+pub fn read_download(
+    url: &str,
+    mut reader: impl Read,
+    total: Option<u64>,
+    reporter: &dyn Reporter,
+) -> io::Result<Vec<u8>> {
+    let mut content = Vec::with_capacity(total.unwrap_or_default() as usize);
+
+    let step = total.map_or(UNKNOWN_SIZE_STEP, |total| (total / 100).max(1));
+    let mut buffer = [0; 64 * 1024];
+    let mut last_reported = 0;
+
+    let report = |downloaded| {
+        reporter.download(url, downloaded, total);
+
+        emit(&Event::Download {
+            url,
+            downloaded,
+            total,
+        });
+    };
+
+    report(0);
+
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+
+        content.extend_from_slice(&buffer[..read]);
+
+        let downloaded = content.len() as u64;
+
+        if downloaded - last_reported >= step {
+            last_reported = downloaded;
+            report(downloaded);
+        }
+    }
+
+    if last_reported != content.len() as u64 {
+        report(content.len() as u64);
+    }
+
+    Ok(content)
+}
@@ -0,0 +1,164 @@
+use super::events::Event;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use time::OffsetDateTime;
+
+/// The logger, which is kept around so the interface can check what's logged to the terminal.
+static LOGGER: OnceLock<Logger> = OnceLock::new();
+
+/// Logs to the terminal with `env_logger`, and (with `--log-file`) everything at the debug level or more to a file, so
+/// a bug report can include a full transcript without cluttering the terminal.
+struct Logger {
+    /// The logger for the terminal, filtered by `-v`, `-q`, and `RUST_LOG`.
+    terminal: env_logger::Logger,
+
+    /// The log file, if there is one.
+    file: Option<Mutex<BufWriter<File>>>,
+
+    /// The level logged to the file.
+    file_level: LevelFilter,
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.terminal.enabled(metadata)
+            || (self.file.is_some() && metadata.level() <= self.file_level)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.terminal.matches(record) {
+            self.terminal.log(record);
+        }
+
+        if record.level() <= self.file_level {
+            self.write(
+                &format!("{:<5} {}", record.level(), record.target()),
+                &record.args().to_string(),
+            );
+        }
+    }
+
+    fn flush(&self) {
+        self.terminal.flush();
+
+        if let Some(file) = &self.file {
+            let _ = file.lock().expect("Log file lock poisoned!").flush();
+        }
+    }
+}
+
+impl Logger {
+    /// Writes a line to the log file, if there is one, with the time (in UTC) and `tag` in front of it.
+    fn write(&self, tag: &str, message: &str) {
+        let Some(file) = &self.file else {
+            return;
+        };
+
+        let now = OffsetDateTime::now_utc();
+
+        // Logging can't fail the build, so a log file that can't be written to is ignored
+        let _ = writeln!(
+            file.lock().expect("Log file lock poisoned!"),
+            "[{} {:02}:{:02}:{:02}.{:03} {}] {}",
+            now.date(),
+            now.hour(),
+            now.minute(),
+            now.second(),
+            now.millisecond(),
+            tag,
+            message
+        );
+    }
+}
+
+/// Sets up logging to the terminal with `terminal`, and to `log_file` (truncating it) if there is one.
+pub fn init(terminal: env_logger::Logger, log_file: Option<&Path>) -> io::Result<()> {
+    let file = log_file
+        .map(|path| File::create(path).map(|file| Mutex::new(BufWriter::new(file))))
+        .transpose()?;
+
+    let file_level = if file.is_some() {
+        terminal.filter().max(LevelFilter::Debug)
+    } else {
+        LevelFilter::Off
+    };
+
+    log::set_max_level(terminal.filter().max(file_level));
+
+    let logger = LOGGER.get_or_init(|| Logger {
+        terminal,
+        file,
+        file_level,
+    });
+
+    log::set_logger(logger).map_err(io::Error::other)
+}
+
+/// Whether messages at `level` are shown on the terminal, which can log less than the log file does.
+pub fn terminal_enabled(level: Level) -> bool {
+    LOGGER.get().is_some_and(|logger| {
+        logger.terminal.enabled(
+            &Metadata::builder()
+                .level(level)
+                .target(module_path!())
+                .build(),
+        )
+    })
+}
+
+/// Writes a line of output from a command to the log file. The terminal shows command output itself, so it isn't
+/// logged there.
+pub fn command_output(line: &str) {
+    if let Some(logger) = LOGGER.get() {
+        logger.write("OUTPUT", line);
+    }
+}
+
+/// Writes what happened in the interface to the log file, so the transcript has every step and warning in it.
+pub fn event(event: &Event) {
+    let Some(logger) = LOGGER.get() else {
+        return;
+    };
+
+    match event {
+        Event::StepStarted { step, .. } => logger.write("STEP ", &format!("{}...", step)),
+        Event::StepFinished {
+            step,
+            duration_secs,
+            ..
+        } => logger.write("STEP ", &format!("{} ✓ ({:.2}s)", step, duration_secs)),
+        Event::StepFailed {
+            step,
+            duration_secs,
+            error,
+            ..
+        } => logger.write(
+            "STEP ",
+            &format!(
+                "{} ✗ ({:.2}s){}",
+                step,
+                duration_secs,
+                error
+                    .map(|error| format!(": {}", error))
+                    .unwrap_or_default()
+            ),
+        ),
+        Event::Warning { message } => logger.write("WARN ", message),
+        Event::Download { .. } => {}
+    }
+}
+
+/// Writes the error that stopped the command to the log file.
+pub fn error(message: &str) {
+    if let Some(logger) = LOGGER.get() {
+        logger.write("ERROR", message);
+    }
+}
+
+/// Flushes the log file, as the logger is never dropped.
+pub fn flush() {
+    log::logger().flush();
+}
@@ -0,0 +1,48 @@
+pub const MAX_MSG_LEN: usize = 49;
+
+pub const CLEAN_CACHE_MSG: &str = "🧹 Cleaning cache";
+pub const CLEAN_NODE_MSG: &str = "🧹 Cleaning Node.js binaries from the cache";
+pub const CLEAN_ESBUILD_MSG: &str = "🧹 Cleaning ESBuild from the cache";
+pub const CLEAN_DEPS_MSG: &str = "🧹 Cleaning cached dependencies";
+pub const CLEAN_PROJECT_MSG: &str = "🧹 Cleaning build outputs from the project";
+pub const ADVISORIES_MSG: &str = "🛡️ Checking Node.js for security advisories";
+pub const HASH_PROJ_MSG: &str = "🔍 Checking for changes since the last build";
+pub const CACHED_BUILD_MSG: &str = "⚡ Reusing SEA blob from the last build";
+pub const COPY_PROJ_MSG: &str = "📥 Copying project and preparing for build";
+pub const PREPARE_APP_MSG: &str = "🧩 Preparing application";
+pub const INSTALL_DEPS_MSG: &str = "📚 Installing dependencies with";
+pub const RESTORE_DEPS_MSG: &str = "♻️ Restoring dependencies from the cache";
+pub const REBUILD_NATIVE_MSG: &str = "🔧 Rebuilding native addons for the target";
+pub const BUNDLE_PROJ_MSG: &str = "📦 Bundling project with ESBuild";
+pub const ESBUILD_BINARY_MSG: &str = "🔎 Retrieving ESBuild binary";
+pub const BUNDLING_MSG: &str = "📦 Bundling";
+pub const RUNTIME_BINARY_MSG: &str = "🔎 Retrieving binary of";
+pub const RUNTIME_COMPILE_MSG: &str = "🔨 Compiling application with";
+pub const LICENSES_MSG: &str = "📜 Collecting licenses of dependencies";
+pub const GIT_CLONE_MSG: &str = "🌱 Cloning project from git";
+pub const NPM_PACKAGE_MSG: &str = "📥 Downloading package from the npm registry";
+pub const HOST_NODE_MSG: &str = "🔎 Retrieving Host Node.js binary";
+pub const TARGET_NODE_MSG: &str = "🔎 Retrieving Target Node.js binary";
+pub const FETCH_NODE_MSG: &str = "🔎 Retrieving Node.js binary for";
+pub const GEN_SEA_BLOB_MSG: &str = "🧪 Generating SEA blob";
+pub const STRIP_MSG: &str = "🧽 Stripping symbols from Node.js binary";
+pub const INJECT_APP_MSG: &str = "💉 Injecting application into Node.js binary";
+pub const LIPO_MSG: &str = "🧬 Combining slices into a universal binary";
+pub const WINDOWS_RESOURCES_MSG: &str = "📝 Writing Windows version info and manifest";
+pub const MACOS_CODESIGN_MSG: &str = "🔏 Codesigning macOS binary";
+pub const NOTARIZE_MSG: &str = "📮 Notarizing macOS binary with Apple";
+pub const WINDOWS_CODESIGN_MSG: &str = "🔏 Codesigning Windows binary";
+pub const CUSTOM_SIGN_MSG: &str = "🔏 Signing binary";
+pub const CHECKSUMS_MSG: &str = "🧾 Writing checksums of built binary";
+pub const ARCHIVE_MSG: &str = "🎁 Packaging binary into a release archive";
+pub const MANIFESTS_MSG: &str = "🍺 Writing Homebrew formula and Scoop manifest";
+pub const LINUX_PACKAGES_MSG: &str = "🐧 Building Linux packages";
+pub const GLIBC_MSG: &str = "🐧 Checking glibc compatibility";
+pub const TEST_EXEC_MSG: &str = "🧪 Testing built binary";
+pub const MSI_MSG: &str = "💿 Building Windows installer";
+pub const PROVENANCE_MSG: &str = "🔗 Writing SLSA provenance of outputs";
+pub const SIGNATURES_MSG: &str = "🔐 Writing detached signatures of outputs";
+pub const DOCKER_MSG: &str = "🐳 Building container image";
+pub const WATCHING_MSG: &str = "👀 Watching for changes... (press Ctrl+C to stop)";
+pub const RUN_APP_MSG: &str = "🚀 Running application";
+pub const TEST_SUITE_MSG: &str = "🧪 Running tests against built binary";
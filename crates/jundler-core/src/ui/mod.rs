@@ -0,0 +1,792 @@
+pub mod color;
+pub mod events;
+pub mod log_file;
+pub mod messages;
+pub mod plain;
+mod reporter;
+mod tests;
+mod tracker;
+
+pub use reporter::{NoopReporter, Reporter, StepId};
+pub use tracker::{OutputLine, Spinner, Tracker};
+
+use console::{style, Term};
+use indicatif::{
+    HumanDuration, MultiProgress, ProgressBar, ProgressDrawTarget, ProgressState, ProgressStyle,
+};
+use log::{Level, LevelFilter};
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::iter;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use time::OffsetDateTime;
+
+const SPINNER_FRAMES: &[&str] = &[
+    "⠁", "⠂", "⠄", "⡀", "⡈", "⡐", "⡠", "⣀", "⣁", "⣂", "⣄", "⣌", "⣔", "⣤", "⣥", "⣦", "⣮", "⣶", "⣷",
+    "⣿", "⡿", "⠿", "⢟", "⠟", "⡛", "⠛", "⠫", "⢋", "⠋", "⠍", "⡉", "⠉", "⠑", "⠡", "⢁",
+];
+
+const SPINNER_FRAME_DURATION: Duration = Duration::from_millis(80);
+
+/// Steps that usually take less time than this don't show how much longer they'll take.
+const MIN_ESTIMATE: Duration = Duration::from_secs(5);
+
+/// Draws the steps of builds to the terminal as spinners, or as a line per step when spinners can't be animated (i.e.
+/// in CI, or when the output isn't a terminal). This is a wrapper around `indicatif::MultiProgress`.
+#[derive(Debug)]
+pub struct TerminalReporter {
+    /// The terminal to draw to.
+    term: Term,
+
+    /// The multi-progress bar.
+    mp: MultiProgress,
+
+    /// The largest spinner message length.
+    max_msg_len: usize,
+
+    /// How much to print.
+    verbosity: Verbosity,
+
+    /// Whether to animate spinners. If not (i.e. in CI, or when the output isn't a terminal), a line is printed when
+    /// each step starts and finishes instead.
+    live: bool,
+
+    /// The spinners and command output being drawn.
+    state: Mutex<TerminalState>,
+}
+
+/// What the terminal is drawing right now.
+#[derive(Debug, Default)]
+struct TerminalState {
+    /// The current spinner "depth"
+    current_depth: usize,
+
+    /// The spinners of the steps that are running, by their IDs.
+    spinners: HashMap<StepId, TerminalSpinner>,
+
+    /// The lines showing the output of the commands that are running, by their IDs.
+    output_lines: HashMap<StepId, ProgressBar>,
+
+    /// The spinner of the last step that failed, so the error can be shown next to it once it's known (if it was
+    /// dropped without being closed), and the line it's on can be ended.
+    last_failed: Option<FailedStep>,
+}
+
+/// The spinner of a step that's running.
+#[derive(Debug)]
+struct TerminalSpinner {
+    /// The underlying progress bar.
+    spinner: ProgressBar,
+
+    /// The number of dots to display after the message.
+    num_dots: usize,
+
+    /// The depth of the spinner.
+    depth: usize,
+
+    /// Whether the spinner was created with a new depth.
+    new_depth: bool,
+
+    /// The progress bar to put the next child spinner after: this spinner's, or that of its last child.
+    last_child: ProgressBar,
+
+    /// How long the step will probably take, if it's long enough to show how much longer it'll take.
+    estimate: Option<Duration>,
+}
+
+impl TerminalReporter {
+    /// Creates a new reporter that draws to stdout.
+    pub fn new(max_msg_len: usize) -> TerminalReporter {
+        Self::with_term(Term::stdout(), max_msg_len)
+    }
+
+    /// Creates a new reporter that draws to stderr, keeping stdout free for machine-readable output.
+    pub fn new_stderr(max_msg_len: usize) -> TerminalReporter {
+        Self::with_term(Term::stderr(), max_msg_len)
+    }
+
+    /// Creates a new reporter that draws to `term`.
+    fn with_term(term: Term, max_msg_len: usize) -> TerminalReporter {
+        let live = term.is_term() && !is_ci();
+
+        let draw_target = if live {
+            ProgressDrawTarget::term(term.clone(), 30)
+        } else {
+            ProgressDrawTarget::hidden()
+        };
+
+        TerminalReporter {
+            mp: MultiProgress::with_draw_target(draw_target),
+            term,
+            max_msg_len,
+            verbosity: Verbosity::default(),
+            live,
+            state: Mutex::default(),
+        }
+    }
+
+    /// Sets how much to print. Spinners are hidden when quiet.
+    pub fn set_verbosity(&mut self, verbosity: Verbosity) {
+        self.verbosity = verbosity;
+
+        if verbosity <= Verbosity::Quiet {
+            self.mp.set_draw_target(ProgressDrawTarget::hidden());
+        }
+    }
+
+    /// Prints how long each completed step in `recording` took, and how much of the total time that was.
+    pub fn print_timings(&self, recording: &Recording, total: Duration) {
+        if recording.steps.is_empty() {
+            return;
+        }
+
+        self.println(format!("{}", style("⏱️  Timings").bold()));
+
+        for step in &recording.steps {
+            let tabs = "   ".repeat(step.depth + 1);
+            let num_dots = self
+                .max_msg_len
+                .saturating_sub(step.message.len() + 3 * step.depth);
+
+            self.println(format!(
+                "{}{} {} {:>7.2}s {}",
+                tabs,
+                step.message,
+                style("·".repeat(num_dots)).dim(),
+                step.duration.as_secs_f64(),
+                style(format!(
+                    "{:>3.0}%",
+                    100.0 * step.duration.as_secs_f64() / total.as_secs_f64().max(f64::EPSILON)
+                ))
+                .dim(),
+            ));
+        }
+    }
+
+    /// Prints `warnings` again, numbered, so they aren't lost under the spinners.
+    pub fn print_warnings(&self, warnings: &[String]) {
+        for line in warning_recap(warnings) {
+            self.println(line);
+        }
+    }
+
+    /// Draw a message to the terminal, unless it's quiet.
+    pub fn println<S>(&self, message: S)
+    where
+        S: ToString,
+    {
+        if self.verbosity <= Verbosity::Quiet {
+            return;
+        }
+
+        self.term
+            .write_line(&plain::text(&message.to_string()))
+            .unwrap();
+    }
+
+    /// Starts showing how far along a build of `total` targets is, with a header above the spinners of each.
+    pub fn spawn_progress(&self, total: usize) -> Progress {
+        Progress {
+            mp: self.mp.clone(),
+            header: None,
+            total,
+            position: 0,
+            started: Instant::now(),
+            log: self.log().cloned(),
+        }
+    }
+
+    /// The terminal to print a line to for each step, if spinners aren't animated (and it isn't quiet).
+    fn log(&self) -> Option<&Term> {
+        (!self.live && self.verbosity > Verbosity::Quiet).then_some(&self.term)
+    }
+
+    /// Locks what the terminal is drawing.
+    fn state(&self) -> MutexGuard<'_, TerminalState> {
+        self.state.lock().expect("Terminal lock poisoned!")
+    }
+}
+
+impl Reporter for TerminalReporter {
+    fn step_started(
+        &self,
+        id: StepId,
+        parent: Option<StepId>,
+        step: &str,
+        depth: usize,
+        estimate: Option<Duration>,
+    ) {
+        let mut state = self.state();
+
+        let num_dots = self.max_msg_len.saturating_sub(step.len());
+        let new_depth = depth > state.current_depth;
+        state.current_depth = depth;
+
+        let estimate = estimate.filter(|estimate| *estimate >= MIN_ESTIMATE);
+
+        let pb = ProgressBar::new_spinner()
+            .with_message(step.to_string())
+            .with_style(running_style(num_dots, depth, new_depth, estimate));
+
+        // Steps that are part of another step go underneath it (and any of its other children)
+        let pb = match parent.and_then(|parent| state.spinners.get_mut(&parent)) {
+            Some(parent) => {
+                let pb = self.mp.insert_after(&parent.last_child, pb);
+                parent.last_child = pb.clone();
+                pb
+            }
+            None => self.mp.add(pb),
+        };
+
+        pb.enable_steady_tick(SPINNER_FRAME_DURATION);
+
+        if let Some(term) = self.log() {
+            log_line(term, depth, &format!("{}...", step));
+        }
+
+        state.spinners.insert(
+            id,
+            TerminalSpinner {
+                last_child: pb.clone(),
+                spinner: pb,
+                num_dots,
+                depth,
+                new_depth,
+                estimate,
+            },
+        );
+    }
+
+    fn step_updated(&self, id: StepId, message: &str) {
+        let mut state = self.state();
+
+        let Some(spinner) = state.spinners.get_mut(&id) else {
+            return;
+        };
+
+        spinner.num_dots =
+            (spinner.num_dots + spinner.spinner.message().len()).saturating_sub(message.len());
+
+        spinner.spinner.set_style(running_style(
+            spinner.num_dots,
+            spinner.depth,
+            spinner.new_depth,
+            spinner.estimate,
+        ));
+        spinner.spinner.set_message(message.to_string());
+    }
+
+    fn step_finished(&self, id: StepId, step: &str, depth: usize, duration: Duration) {
+        let Some(spinner) = self.state().spinners.remove(&id) else {
+            return;
+        };
+
+        if let Some(term) = self.log() {
+            log_line(
+                term,
+                depth,
+                &format!(
+                    "{} {} ({:.2}s)",
+                    step,
+                    plain::text("✓"),
+                    duration.as_secs_f64()
+                ),
+            );
+        }
+
+        // The step's duration takes the place of some of the dots, so slow steps stand out
+        let duration = short_duration(duration);
+
+        spinner.spinner.set_style(
+            ProgressStyle::default_spinner()
+                .template(&get_template(
+                    &format!(
+                        "{} {}",
+                        console::style(&duration).dim(),
+                        console::style(plain::text("✓")).green()
+                    ),
+                    spinner.num_dots.saturating_sub(duration.len() + 1),
+                    spinner.depth,
+                    spinner.new_depth,
+                ))
+                .expect("This should not fail!")
+                .tick_strings(spinner_frames()),
+        );
+
+        spinner.spinner.finish();
+    }
+
+    fn step_failed(
+        &self,
+        id: StepId,
+        step: &str,
+        depth: usize,
+        duration: Duration,
+        error: Option<&str>,
+    ) {
+        let mut state = self.state();
+
+        let Some(spinner) = state.spinners.remove(&id) else {
+            return;
+        };
+
+        if let Some(term) = self.log() {
+            log_line(
+                term,
+                depth,
+                &format!(
+                    "{} {} ({:.2}s){}",
+                    step,
+                    plain::text("✗"),
+                    duration.as_secs_f64(),
+                    error
+                        .map(|error| format!(": {}", error))
+                        .unwrap_or_default()
+                ),
+            );
+        }
+
+        let failed = FailedStep {
+            spinner: spinner.spinner,
+            num_dots: spinner.num_dots,
+            depth: spinner.depth,
+            new_depth: spinner.new_depth,
+            has_error: error.is_some(),
+        };
+
+        failed.render(error);
+
+        // Without an error, the step failed by returning early, and the error is shown once it's known. Steps that
+        // this step is part of fail after it, but it's the one that failed first.
+        state.last_failed.get_or_insert(failed);
+    }
+
+    fn build_failed(&self, error: &str) {
+        let failed = self.state().last_failed.take();
+
+        if let (Some(failed), true) = (failed, self.live) {
+            if !failed.has_error {
+                failed.render(Some(error));
+            }
+
+            // Drawing the failed spinner leaves the cursor at the end of its line
+            self.term.write_line("").unwrap();
+        }
+    }
+
+    fn output_started(&self, id: StepId) {
+        let mut state = self.state();
+
+        let pb = ProgressBar::new_spinner().with_style(
+            ProgressStyle::default_spinner()
+                .template(&format!(
+                    "{tabs}{{elapsed:.dim}} {{wide_msg:.dim}}",
+                    tabs = "   ".repeat(state.current_depth + 1) + "    "
+                ))
+                .expect("This should not fail!"),
+        );
+
+        let line = self.mp.add(pb);
+        line.enable_steady_tick(SPINNER_FRAME_DURATION);
+
+        state.output_lines.insert(id, line);
+    }
+
+    fn output(&self, id: StepId, line: &str) {
+        let state = self.state();
+
+        let Some(output_line) = state.output_lines.get(&id) else {
+            return;
+        };
+
+        // With debug logging, every line of output is printed in full instead of being collapsed into a single line
+        if log_file::terminal_enabled(Level::Debug) {
+            if self.live {
+                output_line.println(line);
+            } else {
+                self.term.write_line(line).unwrap();
+            }
+        }
+
+        output_line.set_message(line.to_string());
+    }
+
+    fn output_finished(&self, id: StepId) {
+        if let Some(line) = self.state().output_lines.remove(&id) {
+            line.finish_and_clear();
+        }
+    }
+
+    fn message(&self, message: &str) {
+        self.println(message);
+    }
+
+    fn warning(&self, message: &str) {
+        if self.verbosity == Verbosity::Silent {
+            return;
+        }
+
+        self.term
+            .write_line(
+                &style(plain::text(&format!("❗️ {}", message)))
+                    .yellow()
+                    .to_string(),
+            )
+            .unwrap();
+    }
+
+    fn suspend(&self, _: StepId, f: &mut dyn FnMut()) {
+        self.mp.suspend(f);
+    }
+}
+
+/// Prints a line for people to read to stdout, or to stderr if stdout is reserved for progress events.
+pub fn print_line(message: &str) {
+    if events::is_enabled() {
+        eprintln!("{}", plain::text(message));
+    } else {
+        println!("{}", plain::text(message));
+    }
+}
+
+/// How much is printed to the terminal, from the `-q` and `-v` flags.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// Nothing at all, for `--quiet --json`, where the JSON on stdout is the only output.
+    Silent,
+
+    /// Only warnings and errors.
+    Quiet,
+
+    /// Spinners for each step, and info logs.
+    #[default]
+    Normal,
+
+    /// Also every command line that's run and everything it prints, and debug logs.
+    Verbose,
+
+    /// Also trace logs.
+    Trace,
+}
+
+impl Verbosity {
+    /// Gets the verbosity from the number of times `-v` was given, and whether `-q` was.
+    pub fn from_flags(verbose: u8, quiet: bool) -> Verbosity {
+        match (quiet, verbose) {
+            (true, _) => Verbosity::Quiet,
+            (false, 0) => Verbosity::Normal,
+            (false, 1) => Verbosity::Verbose,
+            (false, _) => Verbosity::Trace,
+        }
+    }
+
+    /// The level to log at. `RUST_LOG` overrides this.
+    pub fn log_level(self) -> LevelFilter {
+        match self {
+            Verbosity::Silent => LevelFilter::Off,
+            Verbosity::Quiet => LevelFilter::Warn,
+            Verbosity::Normal => LevelFilter::Info,
+            Verbosity::Verbose => LevelFilter::Debug,
+            Verbosity::Trace => LevelFilter::Trace,
+        }
+    }
+}
+
+/// Everything that has happened in the interface: the steps that were completed, and the warnings that were shown.
+#[derive(Clone, Debug, Default)]
+pub struct Recording {
+    /// Every completed step, in order.
+    pub steps: Vec<Step>,
+
+    /// Every warning that was shown, in order.
+    pub warnings: Vec<String>,
+}
+
+/// A completed step, which is the message of a spinner and how long it spun for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    /// The message of the spinner.
+    pub message: String,
+
+    /// The depth of the spinner, as steps can be part of other steps.
+    pub depth: usize,
+
+    /// How long the step took.
+    pub duration: Duration,
+}
+
+/// How far along a build of several targets is. Each target gets a header with the overall progress above its
+/// spinners: which target it is, a bar, and how long the whole build has been running.
+#[derive(Debug)]
+pub struct Progress {
+    /// The multi-progress bar to add headers to.
+    mp: MultiProgress,
+
+    /// The header of the target being built, if any.
+    header: Option<ProgressBar>,
+
+    /// How many targets there are.
+    total: usize,
+
+    /// Which target is being built, counting from 1.
+    position: usize,
+
+    /// When the build started.
+    started: Instant,
+
+    /// The terminal to print a line to for each target, if spinners aren't animated.
+    log: Option<Term>,
+}
+
+impl Progress {
+    /// Moves on to the next target, `name`. The header of the last target stays where it is, without the bar.
+    pub fn next(&mut self, name: &str) {
+        self.finish_target();
+        self.position += 1;
+
+        let prefix = format!("[{}/{}]", self.position, self.total);
+        let message = plain::text(&format!("📂 {}", name)).into_owned();
+
+        if let Some(term) = &self.log {
+            log_line(
+                term,
+                0,
+                &format!(
+                    "{} {} {}",
+                    style(&prefix).bold(),
+                    message,
+                    style(format!(
+                        "({} so far)",
+                        HumanDuration(self.started.elapsed())
+                    ))
+                    .dim()
+                ),
+            );
+        }
+
+        let header = ProgressBar::new(self.total as u64)
+            .with_position(self.position as u64 - 1)
+            .with_elapsed(self.started.elapsed())
+            .with_prefix(prefix)
+            .with_message(message)
+            .with_style(
+                ProgressStyle::default_bar()
+                    .template("{prefix:.bold} {msg} {bar:20.cyan/blue} {elapsed:.dim}")
+                    .expect("This should not fail!")
+                    .progress_chars(if plain::is_plain() {
+                        "#>-"
+                    } else {
+                        "█▉░"
+                    }),
+            );
+
+        let header = self.mp.add(header);
+        header.enable_steady_tick(Duration::from_secs(1));
+
+        self.header = Some(header);
+    }
+
+    /// Leaves the header of the target that was being built, without the bar.
+    fn finish_target(&mut self) {
+        if let Some(header) = self.header.take() {
+            header.set_style(
+                ProgressStyle::default_bar()
+                    .template("{prefix:.bold} {msg}")
+                    .expect("This should not fail!"),
+            );
+            header.finish();
+        }
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.finish_target();
+    }
+}
+
+/// A spinner whose step failed, which is left in the terminal with a ✗.
+#[derive(Debug)]
+struct FailedStep {
+    /// The underlying progress bar.
+    spinner: ProgressBar,
+
+    /// The number of dots to display after the message.
+    num_dots: usize,
+
+    /// The depth of the spinner.
+    depth: usize,
+
+    /// Whether the spinner was created with a new depth.
+    new_depth: bool,
+
+    /// Whether the summary of the error is already shown next to the spinner.
+    has_error: bool,
+}
+
+impl FailedStep {
+    /// Draws the spinner with a ✗, and the summary of the error after it if there is one.
+    fn render(&self, error: Option<&str>) {
+        let mut ending = console::style(plain::text("✗")).red().to_string();
+
+        if let Some(error) = error {
+            // Braces would be read as part of the template
+            ending += &format!(
+                " {}",
+                console::style(plain::text(error).replace('{', "{{").replace('}', "}}")).dim()
+            );
+        }
+
+        self.spinner.set_style(
+            ProgressStyle::default_spinner()
+                .template(&get_template(
+                    &ending,
+                    self.num_dots,
+                    self.depth,
+                    self.new_depth,
+                ))
+                .expect("This should not fail!")
+                .tick_strings(spinner_frames()),
+        );
+
+        self.spinner.abandon();
+    }
+}
+
+fn get_template(ending: &str, num_dots: usize, depth: usize, new_depth: bool) -> String {
+    let depth_string = if new_depth {
+        "   ".repeat(depth) + "╰─→ "
+    } else if depth > 0 {
+        "   ".repeat(depth) + "    "
+    } else {
+        "   ".into()
+    };
+
+    let num_dots = if depth > 0 {
+        num_dots.saturating_sub(3 * depth + 1)
+    } else {
+        num_dots
+    };
+
+    format!(
+        "{tabs}{{msg}} {dots} {ending}",
+        tabs = console::style(plain::text(&depth_string)).dim(),
+        dots = console::style(plain::text(&"·".repeat(num_dots))).dim(),
+    )
+}
+
+/// Gets the style of a running spinner, which shows how much longer its step will probably take if there's an
+/// `estimate`.
+fn running_style(
+    num_dots: usize,
+    depth: usize,
+    new_depth: bool,
+    estimate: Option<Duration>,
+) -> ProgressStyle {
+    let ending = match estimate {
+        Some(_) => "{spinner:.blue} {remaining:.dim}",
+        None => "{spinner:.blue}",
+    };
+
+    let style = ProgressStyle::default_spinner()
+        .template(&get_template(ending, num_dots, depth, new_depth))
+        .expect("This should not fail!")
+        .tick_strings(spinner_frames());
+
+    match estimate {
+        Some(estimate) => style.with_key(
+            "remaining",
+            move |state: &ProgressState, w: &mut dyn fmt::Write| {
+                let _ = w.write_str(&remaining(estimate, state.elapsed()));
+            },
+        ),
+        None => style,
+    }
+}
+
+/// Describes how much longer a step that usually takes `estimate` will take, now that it's been running for `elapsed`.
+fn remaining(estimate: Duration, elapsed: Duration) -> String {
+    match estimate.checked_sub(elapsed) {
+        Some(left) if left >= Duration::from_secs(1) => format!("~{} left", HumanDuration(left)),
+        _ => "taking longer than usual".to_string(),
+    }
+}
+
+/// Formats how long a step took to fit next to its spinner, i.e. `4.2s`, or `2m 05s`.
+fn short_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+
+    if secs < 60 {
+        format!("{:.1}s", duration.as_secs_f64())
+    } else {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    }
+}
+
+/// Gets the first line of an error, to show next to the step that failed.
+fn error_summary<E>(error: &E) -> String
+where
+    E: fmt::Display,
+{
+    error
+        .to_string()
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Renders a numbered recap of `warnings`, or nothing if there aren't any.
+fn warning_recap(warnings: &[String]) -> Vec<String> {
+    if warnings.is_empty() {
+        return vec![];
+    }
+
+    let heading = match warnings.len() {
+        1 => "❗️ 1 warning".to_string(),
+        n => format!("❗️ {} warnings", n),
+    };
+
+    iter::once(style(heading).yellow().bold().to_string())
+        .chain(warnings.iter().enumerate().map(|(i, warning)| {
+            style(format!("   {}. {}", i + 1, warning))
+                .yellow()
+                .to_string()
+        }))
+        .collect()
+}
+
+/// Whether we're running in CI, where spinners would just flood the logs.
+fn is_ci() -> bool {
+    env::var("CI").is_ok_and(|ci| matches!(ci.to_lowercase().as_str(), "true" | "1"))
+}
+
+/// Prints a line for a step, with the time (in UTC) in front of it.
+fn log_line(term: &Term, depth: usize, message: &str) {
+    let now = OffsetDateTime::now_utc();
+
+    term.write_line(&format!(
+        "{} {}{}",
+        style(format!(
+            "[{:02}:{:02}:{:02}]",
+            now.hour(),
+            now.minute(),
+            now.second()
+        ))
+        .dim(),
+        "   ".repeat(depth),
+        message
+    ))
+    .unwrap();
+}
+
+/// Gets the frames to animate spinners with, which are plain ASCII when plain output is on.
+fn spinner_frames() -> &'static [&'static str] {
+    if plain::is_plain() {
+        plain::PLAIN_SPINNER_FRAMES
+    } else {
+        SPINNER_FRAMES
+    }
+}
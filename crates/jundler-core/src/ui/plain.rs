@@ -0,0 +1,78 @@
+use console::Term;
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether everything is printed as plain ASCII, without emoji or other Unicode glyphs.
+static PLAIN: AtomicBool = AtomicBool::new(false);
+
+/// The spinner frames to use in plain output.
+pub const PLAIN_SPINNER_FRAMES: &[&str] = &["|", "/", "-", "\\"];
+
+/// The ASCII replacements for the glyphs the interface draws with.
+const REPLACEMENTS: &[(char, &str)] = &[
+    ('✓', "ok"),
+    ('❗', "!"),
+    ('✗', "x"),
+    ('·', "."),
+    ('─', "-"),
+    ('╰', "`"),
+    ('→', ">"),
+    ('↳', "->"),
+    ('…', "..."),
+    ('—', "-"),
+];
+
+/// Turns plain output on or off for everything printed from now on.
+pub fn set_plain(plain: bool) {
+    PLAIN.store(plain, Ordering::Relaxed);
+}
+
+/// Whether plain output is on.
+pub fn is_plain() -> bool {
+    PLAIN.load(Ordering::Relaxed)
+}
+
+/// Whether the terminal probably can't show emoji, like the legacy Windows console, or a terminal without a UTF-8
+/// locale. Output that isn't going to a terminal is left alone, as logs can usually show them fine.
+pub fn detect_plain() -> bool {
+    let term = Term::stdout();
+
+    term.is_term() && !term.features().wants_emoji()
+}
+
+/// Gets the text to print for a message: the message itself, or an ASCII version of it when plain output is on, with
+/// the emoji left out and the glyphs the interface draws with swapped for ASCII ones.
+pub fn text(message: &str) -> Cow<'_, str> {
+    if !is_plain() || message.is_ascii() {
+        return Cow::Borrowed(message);
+    }
+
+    let mut text = String::with_capacity(message.len());
+
+    // Skip the spaces after a left out emoji, so `🔎 Retrieving` becomes `Retrieving` rather than ` Retrieving`
+    let mut skip_spaces = false;
+
+    for c in message.chars() {
+        if let Some((_, replacement)) = REPLACEMENTS.iter().find(|(glyph, _)| *glyph == c) {
+            text += replacement;
+            skip_spaces = false;
+        } else if matches!(c, '\u{FE0F}' | '\u{200D}') {
+            // Variation selectors and joiners only change how the character before them looks
+        } else if is_emoji(c) {
+            skip_spaces = true;
+        } else if !(skip_spaces && c == ' ') {
+            text.push(c);
+            skip_spaces = false;
+        }
+    }
+
+    Cow::Owned(text)
+}
+
+/// Whether a character is an emoji (or part of one), rather than text.
+fn is_emoji(c: char) -> bool {
+    matches!(c as u32,
+        0x2190..=0x2BFF // Arrows, technical symbols, dingbats, etc.
+        | 0x1F000..=0x1FAFF // Emoji
+    )
+}
@@ -0,0 +1,98 @@
+use std::fmt;
+use std::time::Duration;
+
+/// Identifies a step, or a line of command output, across the calls to a reporter about it.
+pub type StepId = usize;
+
+/// Where builds report what they're doing: each step starting, finishing, or failing, the output of the commands they
+/// run, warnings, and download progress. [`TerminalReporter`](super::TerminalReporter) draws spinners for people to
+/// read, while [`NoopReporter`] ignores everything, for running builds headlessly. Every method does nothing by
+/// default, so other tools only need to implement what they want to show (or capture).
+///
+/// Reporters are shared with the threads that download Node.js binaries in the background, so they're called from
+/// more than one thread.
+pub trait Reporter: fmt::Debug + Send + Sync {
+    /// A step started. `parent` is the step it's part of, if any, and `estimate` is how long it took in the last
+    /// builds, if it ran in them.
+    fn step_started(
+        &self,
+        id: StepId,
+        parent: Option<StepId>,
+        step: &str,
+        depth: usize,
+        estimate: Option<Duration>,
+    ) {
+        let _ = (id, parent, step, depth, estimate);
+    }
+
+    /// A step changed its message, i.e. to show what it's doing right now.
+    fn step_updated(&self, id: StepId, message: &str) {
+        let _ = (id, message);
+    }
+
+    /// A step finished successfully, after `duration`.
+    fn step_finished(&self, id: StepId, step: &str, depth: usize, duration: Duration) {
+        let _ = (id, step, depth, duration);
+    }
+
+    /// A step failed after `duration`, with a summary of the error if the step knows it. Steps that fail by returning
+    /// early don't, and the error comes with `build_failed` instead.
+    fn step_failed(
+        &self,
+        id: StepId,
+        step: &str,
+        depth: usize,
+        duration: Duration,
+        error: Option<&str>,
+    ) {
+        let _ = (id, step, depth, duration, error);
+    }
+
+    /// The build stopped with `error`, after the last step that failed.
+    fn build_failed(&self, error: &str) {
+        let _ = error;
+    }
+
+    /// A command started, whose output is reported with `id`.
+    fn output_started(&self, id: StepId) {
+        let _ = id;
+    }
+
+    /// A command printed `line`.
+    fn output(&self, id: StepId, line: &str) {
+        let _ = (id, line);
+    }
+
+    /// A command finished.
+    fn output_finished(&self, id: StepId) {
+        let _ = id;
+    }
+
+    /// A line for people to read, i.e. that a watched project is waiting for changes.
+    fn message(&self, message: &str) {
+        let _ = message;
+    }
+
+    /// A warning, i.e. that signing was skipped.
+    fn warning(&self, message: &str) {
+        let _ = message;
+    }
+
+    /// Part of a file was downloaded. `total` is how many bytes there are, if the server said.
+    fn download(&self, url: &str, downloaded: u64, total: Option<u64>) {
+        let _ = (url, downloaded, total);
+    }
+
+    /// Runs `f` while step `id` is running, with anything the reporter draws out of the way so `f` can print. `f` must
+    /// be run exactly once.
+    fn suspend(&self, id: StepId, f: &mut dyn FnMut()) {
+        let _ = id;
+        f();
+    }
+}
+
+/// A reporter that ignores everything, for running builds without any output.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopReporter;
+
+impl Reporter for NoopReporter {}
@@ -0,0 +1,330 @@
+#![cfg(test)]
+
+use super::messages::*;
+use super::*;
+use crate::builder::hooks::Hooks;
+use std::sync::{Arc, Mutex};
+
+/// Test that plain output leaves out emoji, and swaps the glyphs the tracker draws with for ASCII.
+#[test]
+fn plain_text() {
+    plain::set_plain(true);
+
+    assert_eq!(plain::text(HOST_NODE_MSG), "Retrieving Host Node.js binary");
+    assert_eq!(
+        plain::text(CACHED_BUILD_MSG),
+        "Reusing SEA blob from the last build"
+    );
+    assert_eq!(plain::text("❗️ Careful"), "! Careful");
+    assert_eq!(plain::text("⏱️  Timings"), "Timings");
+    assert_eq!(plain::text("   ╰─→ "), "   `-> ");
+    assert_eq!(plain::text("Done ·· ✓"), "Done .. ok");
+    assert_eq!(plain::text("Café"), "Café");
+
+    plain::set_plain(false);
+
+    assert_eq!(plain::text(HOST_NODE_MSG), HOST_NODE_MSG);
+}
+
+/// Test that progress events are serialized as tagged JSON objects, and that downloads are read in full with them on.
+#[test]
+fn progress_events() {
+    assert_eq!(
+        serde_json::to_string(&events::Event::StepStarted {
+            step: HOST_NODE_MSG,
+            depth: 1
+        })
+        .unwrap(),
+        format!(
+            r#"{{"event":"step_started","step":"{}","depth":1}}"#,
+            HOST_NODE_MSG
+        )
+    );
+    assert_eq!(
+        serde_json::to_string(&events::Event::Download {
+            url: "https://nodejs.org",
+            downloaded: 10,
+            total: None
+        })
+        .unwrap(),
+        r#"{"event":"download","url":"https://nodejs.org","downloaded":10,"total":null}"#
+    );
+
+    let content = vec![7; 300_000];
+
+    events::set_enabled(true);
+
+    assert_eq!(
+        events::read_download(
+            "https://nodejs.org",
+            content.as_slice(),
+            Some(300_000),
+            &NoopReporter
+        )
+        .unwrap(),
+        content
+    );
+
+    events::set_enabled(false);
+
+    assert_eq!(
+        events::read_download(
+            "https://nodejs.org",
+            content.as_slice(),
+            None,
+            &NoopReporter
+        )
+        .unwrap(),
+        content
+    );
+}
+
+/// Test that only spinners that are closed successfully are recorded as completed steps.
+#[test]
+fn failed_spinners() {
+    let mut tracker = Tracker::new(Arc::new(TerminalReporter::new(MAX_MSG_LEN)));
+
+    let spinner = tracker.spawn_spinner(HASH_PROJ_MSG, 0);
+    assert_eq!(spinner.finish(Ok::<_, String>(1)), Ok(1));
+
+    let spinner = tracker.spawn_spinner(COPY_PROJ_MSG, 0);
+    assert!(spinner.finish(Err::<(), _>("Oh no")).is_err());
+
+    // Dropping a spinner without closing it fails its step
+    drop(tracker.spawn_spinner(GEN_SEA_BLOB_MSG, 0));
+    tracker.fail_step("Oh no");
+
+    let recording = tracker.recording();
+
+    assert_eq!(recording.steps.len(), 1);
+    // Compared loosely, as other tests turn plain output on and off
+    assert!(recording.steps[0]
+        .message
+        .ends_with("Checking for changes since the last build"));
+}
+
+/// Test that child spinners are recorded after their parent, even though they finish first.
+#[test]
+fn child_spinners() {
+    let mut tracker = Tracker::default();
+
+    let mut parent = tracker.spawn_spinner(PREPARE_APP_MSG, 0);
+    let mut child = tracker.spawn_child(&mut parent, BUNDLE_PROJ_MSG);
+    let grandchild = tracker.spawn_child(&mut child, BUNDLING_MSG);
+
+    grandchild.close();
+    child.set_message("📦 Bundling project with ESBuild (index.js)");
+    child.close();
+    tracker.spawn_child(&mut parent, GEN_SEA_BLOB_MSG).close();
+    parent.close();
+
+    let depths = tracker
+        .recording()
+        .steps
+        .iter()
+        .map(|step| step.depth)
+        .collect::<Vec<_>>();
+
+    assert_eq!(depths, [0, 1, 2, 1]);
+
+    // Steps keep the message they started with
+    assert!(tracker.recording().steps[1].message.ends_with("ESBuild"));
+}
+
+/// A reporter that keeps what it's told, as lines.
+#[derive(Debug, Default)]
+struct CapturingReporter(Mutex<Vec<String>>);
+
+impl Reporter for CapturingReporter {
+    fn step_started(
+        &self,
+        id: StepId,
+        parent: Option<StepId>,
+        step: &str,
+        _: usize,
+        _: Option<Duration>,
+    ) {
+        self.0
+            .lock()
+            .unwrap()
+            .push(format!("started {} {} {:?}", id, step, parent));
+    }
+
+    fn step_finished(&self, id: StepId, _: &str, _: usize, _: Duration) {
+        self.0.lock().unwrap().push(format!("finished {}", id));
+    }
+
+    fn step_failed(&self, id: StepId, _: &str, _: usize, _: Duration, error: Option<&str>) {
+        self.0
+            .lock()
+            .unwrap()
+            .push(format!("failed {} {:?}", id, error));
+    }
+
+    fn output(&self, id: StepId, line: &str) {
+        self.0
+            .lock()
+            .unwrap()
+            .push(format!("output {} {}", id, line));
+    }
+
+    fn warning(&self, message: &str) {
+        self.0.lock().unwrap().push(format!("warning {}", message));
+    }
+
+    fn download(&self, url: &str, downloaded: u64, total: Option<u64>) {
+        self.0
+            .lock()
+            .unwrap()
+            .push(format!("download {} {} {:?}", url, downloaded, total));
+    }
+}
+
+/// Test that steps, command output, warnings, and downloads are all reported, so other tools can capture them.
+#[test]
+fn reported_events() {
+    let reporter = Arc::new(CapturingReporter::default());
+    let mut tracker = Tracker::new(reporter.clone());
+
+    let mut parent = tracker.spawn_spinner("Parent", 0);
+    let output_line = tracker.spawn_output_line();
+    output_line.update("  added 1 package \n");
+    output_line.close();
+    tracker.spawn_child(&mut parent, "Child").close();
+    tracker.warn("Careful");
+    assert!(parent.finish(Err::<(), _>("Oh no\nMore details")).is_err());
+
+    events::read_download(
+        "https://nodejs.org",
+        [0; 10].as_slice(),
+        Some(10),
+        &*reporter,
+    )
+    .unwrap();
+
+    assert_eq!(
+        *reporter.0.lock().unwrap(),
+        [
+            "started 0 Parent None",
+            "output 1 added 1 package",
+            "started 2 Child Some(0)",
+            "finished 2",
+            "warning Careful",
+            "failed 0 Some(\"Oh no\")",
+            "download https://nodejs.org 0 Some(10)",
+            "download https://nodejs.org 10 Some(10)",
+        ]
+    );
+}
+
+/// Test that `--color` wins over the environment, and that `auto` follows `NO_COLOR`, `CLICOLOR`, and `CLICOLOR_FORCE`.
+#[test]
+fn color_choice() {
+    let env = |vars: &'static [(&'static str, &'static str)]| {
+        move |name: &str| {
+            vars.iter()
+                .find(|(var, _)| *var == name)
+                .map(|(_, value)| value.to_string())
+        }
+    };
+
+    use color::ColorChoice::*;
+
+    assert_eq!(Auto.resolve(env(&[])), None);
+    assert_eq!(Auto.resolve(env(&[("NO_COLOR", "1")])), Some(false));
+    assert_eq!(Auto.resolve(env(&[("NO_COLOR", "")])), None);
+    assert_eq!(Auto.resolve(env(&[("CLICOLOR", "0")])), Some(false));
+    assert_eq!(Auto.resolve(env(&[("CLICOLOR_FORCE", "1")])), Some(true));
+    assert_eq!(Auto.resolve(env(&[("CLICOLOR_FORCE", "0")])), None);
+    assert_eq!(
+        Auto.resolve(env(&[("NO_COLOR", "1"), ("FORCE_COLOR", "1")])),
+        Some(false)
+    );
+    assert_eq!(Always.resolve(env(&[("NO_COLOR", "1")])), Some(true));
+    assert_eq!(Never.resolve(env(&[("CLICOLOR_FORCE", "1")])), Some(false));
+}
+
+/// Test that the recap of warnings is numbered, and left out when there weren't any.
+#[test]
+fn warning_recap() {
+    console::set_colors_enabled(false);
+
+    assert!(super::warning_recap(&[]).is_empty());
+    assert_eq!(
+        super::warning_recap(&["Skipped signing".to_string()]),
+        vec!["❗️ 1 warning", "   1. Skipped signing"]
+    );
+    assert_eq!(
+        super::warning_recap(&["Skipped signing".to_string(), "Native modules".to_string()]),
+        vec![
+            "❗️ 2 warnings",
+            "   1. Skipped signing",
+            "   2. Native modules"
+        ]
+    );
+}
+
+/// Test that long steps show roughly how much longer they'll take, until they run over.
+#[test]
+fn remaining_time() {
+    assert_eq!(
+        super::remaining(Duration::from_secs(150), Duration::from_secs(30)),
+        "~2 minutes left"
+    );
+    assert_eq!(
+        super::remaining(Duration::from_secs(10), Duration::from_secs(3)),
+        "~7 seconds left"
+    );
+    assert_eq!(
+        super::remaining(Duration::from_secs(10), Duration::from_secs(12)),
+        "taking longer than usual"
+    );
+}
+
+/// Test that step durations are short enough to fit next to their spinners.
+#[test]
+fn step_durations() {
+    assert_eq!(super::short_duration(Duration::from_millis(4210)), "4.2s");
+    assert_eq!(
+        super::short_duration(Duration::from_millis(59_900)),
+        "59.9s"
+    );
+    assert_eq!(super::short_duration(Duration::from_secs(125)), "2m 05s");
+}
+
+/// Hooks that record every step and warning they're called with.
+#[derive(Debug, Default)]
+struct RecordingHooks(Mutex<Vec<String>>);
+
+impl Hooks for RecordingHooks {
+    fn on_step(&self, step: &Step) {
+        self.0
+            .lock()
+            .unwrap()
+            .push(format!("step {} {}", step.message, step.depth));
+    }
+
+    fn on_warning(&self, message: &str) {
+        self.0.lock().unwrap().push(format!("warning {}", message));
+    }
+}
+
+/// Test that hooks are called with every step that finishes and every warning, but not with steps that fail.
+#[test]
+fn hooks() {
+    let hooks = Arc::new(RecordingHooks::default());
+    let mut tracker = Tracker::default();
+
+    tracker.set_hooks(hooks.clone());
+
+    let mut parent = tracker.spawn_spinner("Parent", 0);
+    tracker.spawn_child(&mut parent, "Child").close();
+    tracker.warn("Careful");
+    parent.close();
+    drop(tracker.spawn_spinner("Failed", 0));
+
+    assert_eq!(
+        *hooks.0.lock().unwrap(),
+        ["step Child 1", "warning Careful", "step Parent 0"]
+    );
+}
@@ -0,0 +1,329 @@
+use super::events::{self, Event};
+use super::reporter::{NoopReporter, Reporter, StepId};
+use super::{error_summary, log_file, plain, Recording, Step};
+use crate::builder::hooks::{Hooks, NoHooks};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Keeps track of the steps of builds, recording them for build reports and telling a [`Reporter`] about them. Also
+/// emits the progress events for `--progress json`, and writes everything to the log file.
+#[derive(Clone, Debug)]
+pub struct Tracker {
+    /// Where to report what's happening.
+    reporter: Arc<dyn Reporter>,
+
+    /// Everything that has happened so far, for build reports.
+    recording: Arc<Mutex<Recording>>,
+
+    /// How long each step will probably take, by its message, from how long it took before.
+    estimates: HashMap<String, Duration>,
+
+    /// The ID of the next step (or line of command output).
+    next_id: Arc<AtomicUsize>,
+
+    /// What to call with every step that finishes, and every warning.
+    hooks: Arc<dyn Hooks>,
+}
+
+impl Default for Tracker {
+    fn default() -> Tracker {
+        Tracker::new(Arc::new(NoopReporter))
+    }
+}
+
+impl Tracker {
+    /// Creates a tracker that reports to `reporter`.
+    pub fn new(reporter: Arc<dyn Reporter>) -> Tracker {
+        Tracker {
+            reporter,
+            recording: Arc::default(),
+            estimates: HashMap::new(),
+            next_id: Arc::default(),
+            hooks: Arc::new(NoHooks),
+        }
+    }
+
+    /// Gets the reporter that everything is reported to.
+    pub fn reporter(&self) -> Arc<dyn Reporter> {
+        self.reporter.clone()
+    }
+
+    /// Calls `hooks` with every step that finishes, and every warning, from now on.
+    pub fn set_hooks(&mut self, hooks: Arc<dyn Hooks>) {
+        self.hooks = hooks;
+    }
+
+    /// Sets how long each step will probably take, by its message. Long steps show how much longer they'll take.
+    pub fn set_estimates(&mut self, estimates: HashMap<String, Duration>) {
+        self.estimates = estimates;
+    }
+
+    /// Gets everything that has been recorded so far.
+    pub fn recording(&self) -> Recording {
+        self.recording
+            .lock()
+            .expect("Recording lock poisoned!")
+            .clone()
+    }
+
+    /// Shows a line for people to read.
+    pub fn println<S>(&self, message: S)
+    where
+        S: ToString,
+    {
+        self.reporter.message(&message.to_string());
+    }
+
+    /// Reports a warning. Warnings are always recorded for build reports.
+    pub fn warn<S>(&self, message: S)
+    where
+        S: ToString,
+    {
+        let message = message.to_string();
+
+        events::emit(&Event::Warning { message: &message });
+
+        self.recording
+            .lock()
+            .expect("Recording lock poisoned!")
+            .warnings
+            .push(message.clone());
+
+        self.reporter.warning(&message);
+        self.hooks.on_warning(&message);
+    }
+
+    /// Reports that the build stopped with `error`, after the last step that failed. Steps that fail by returning
+    /// early (dropping their spinner without closing it) only know that they failed, not why, so this fills that in.
+    pub fn fail_step<E>(&self, error: E)
+    where
+        E: fmt::Display,
+    {
+        self.reporter.build_failed(&error_summary(&error));
+    }
+
+    /// Starts a step. Returns a handle to it, which is closed when the step finishes.
+    pub fn spawn_spinner<S>(&mut self, message: S, depth: usize) -> Spinner
+    where
+        S: ToString,
+    {
+        self.spawn(&message.to_string(), depth, None)
+    }
+
+    /// Starts a step that's part of `parent`'s step, one level deeper.
+    pub fn spawn_child<S>(&mut self, parent: &mut Spinner, message: S) -> Spinner
+    where
+        S: ToString,
+    {
+        self.spawn(&message.to_string(), parent.depth + 1, Some(parent.id))
+    }
+
+    /// Starts a step at `depth`, as part of the step `parent` if there is one.
+    fn spawn(&mut self, message: &str, depth: usize, parent: Option<StepId>) -> Spinner {
+        let message = plain::text(message).into_owned();
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+
+        self.reporter.step_started(
+            id,
+            parent,
+            &message,
+            depth,
+            self.estimates.get(&message).copied(),
+        );
+
+        events::emit(&Event::StepStarted {
+            step: &message,
+            depth,
+        });
+
+        Spinner {
+            id,
+            depth,
+            position: self
+                .recording
+                .lock()
+                .expect("Recording lock poisoned!")
+                .steps
+                .len(),
+            recording: Some((self.recording.clone(), message, Instant::now())),
+            reporter: self.reporter.clone(),
+            hooks: self.hooks.clone(),
+        }
+    }
+
+    /// Starts reporting the live output of a command, underneath the current step.
+    pub fn spawn_output_line(&self) -> OutputLine {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+
+        self.reporter.output_started(id);
+
+        OutputLine {
+            id,
+            reporter: self.reporter.clone(),
+        }
+    }
+}
+
+/// A step that's running, which is shown as a spinner in the terminal. If it's dropped without being closed, the step
+/// is reported as failed.
+#[derive(Debug)]
+pub struct Spinner {
+    /// The ID of the step.
+    id: StepId,
+
+    /// The depth of the step, as steps can be part of other steps.
+    depth: usize,
+
+    /// Where to record the step when it's closed, with its message and when it was started.
+    recording: Option<(Arc<Mutex<Recording>>, String, Instant)>,
+
+    /// Where the step goes in the recording, so steps come before the steps that are part of them, even though they
+    /// finish after them.
+    position: usize,
+
+    /// Where to report the step.
+    reporter: Arc<dyn Reporter>,
+
+    /// What to call when the step finishes.
+    hooks: Arc<dyn Hooks>,
+}
+
+impl Spinner {
+    /// Changes the message of the step, i.e. to show what it's doing right now. The step keeps the message it started
+    /// with in timings and reports.
+    pub fn set_message<S>(&mut self, message: S)
+    where
+        S: ToString,
+    {
+        self.reporter
+            .step_updated(self.id, &plain::text(&message.to_string()));
+    }
+
+    /// Runs `f` with anything the reporter draws out of the way, so `f` can print.
+    pub fn suspend<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        let mut f = Some(f);
+        let mut result = None;
+
+        self.reporter.suspend(self.id, &mut || {
+            if let Some(f) = f.take() {
+                result = Some(f());
+            }
+        });
+
+        result.expect("Reporters always run suspended code!")
+    }
+
+    /// Closes the spinner, finishing its step.
+    pub fn close(mut self) {
+        let Some((recording, message, started)) = self.recording.take() else {
+            return;
+        };
+
+        let duration = started.elapsed();
+
+        events::emit(&Event::StepFinished {
+            step: &message,
+            depth: self.depth,
+            duration_secs: duration.as_secs_f64(),
+        });
+
+        self.reporter
+            .step_finished(self.id, &message, self.depth, duration);
+
+        let step = Step {
+            message,
+            depth: self.depth,
+            duration,
+        };
+
+        self.hooks.on_step(&step);
+
+        let steps = &mut recording.lock().expect("Recording lock poisoned!").steps;
+
+        steps.insert(self.position.min(steps.len()), step);
+    }
+
+    /// Closes the spinner as failed, with a summary of `error`.
+    pub fn close_failed<E>(mut self, error: E)
+    where
+        E: fmt::Display,
+    {
+        self.fail(Some(&error_summary(&error)));
+    }
+
+    /// Closes the spinner if `result` is `Ok`, or as failed with the error if not, and passes `result` on. For steps
+    /// that are a single fallible call, i.e. `spinner.finish(do_step())?`.
+    pub fn finish<T, E>(self, result: Result<T, E>) -> Result<T, E>
+    where
+        E: fmt::Display,
+    {
+        match &result {
+            Ok(_) => self.close(),
+            Err(err) => self.close_failed(err),
+        }
+
+        result
+    }
+
+    /// Marks the step as failed, if it hasn't been closed yet.
+    fn fail(&mut self, error: Option<&str>) {
+        let Some((_, message, started)) = self.recording.take() else {
+            return;
+        };
+
+        let duration = started.elapsed();
+
+        events::emit(&Event::StepFailed {
+            step: &message,
+            depth: self.depth,
+            duration_secs: duration.as_secs_f64(),
+            error,
+        });
+
+        self.reporter
+            .step_failed(self.id, &message, self.depth, duration, error);
+    }
+}
+
+impl Drop for Spinner {
+    fn drop(&mut self) {
+        // Closing the spinner takes the recording, so if it's still here, the step never finished
+        self.fail(None);
+    }
+}
+
+/// The live output of a command. Every line is written to the log file, and reported.
+#[derive(Clone, Debug)]
+pub struct OutputLine {
+    /// The ID of the output.
+    id: StepId,
+
+    /// Where to report the output.
+    reporter: Arc<dyn Reporter>,
+}
+
+impl OutputLine {
+    /// Reports a new line of output from the command.
+    pub fn update(&self, output: &str) {
+        let output = output.trim();
+
+        if output.is_empty() {
+            return;
+        }
+
+        log_file::command_output(output);
+
+        self.reporter.output(self.id, output);
+    }
+
+    /// Reports that the command finished.
+    pub fn close(self) {
+        self.reporter.output_finished(self.id);
+    }
+}
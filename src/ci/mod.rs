@@ -0,0 +1,172 @@
+mod tests;
+
+use crate::builder::platforms::{Arch, Os, Target};
+use crate::js_config::PackageConfig;
+use anyhow::{anyhow, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where the GitHub Actions workflow is written to, relative to the project.
+pub const GITHUB_WORKFLOW_PATH: &str = ".github/workflows/release.yml";
+
+/// The targets built when the project doesn't list any.
+const DEFAULT_TARGETS: &[Target] = &[
+    Target {
+        os: Os::Linux,
+        arch: Arch::X64,
+    },
+    Target {
+        os: Os::MacOS,
+        arch: Arch::X64,
+    },
+    Target {
+        os: Os::MacOS,
+        arch: Arch::Arm64,
+    },
+    Target {
+        os: Os::Windows,
+        arch: Arch::X64,
+    },
+];
+
+/// Writes a GitHub Actions workflow that builds release archives of the project for each of its targets, to
+/// `.github/workflows/release.yml`. An existing workflow is only overwritten with `force`. Returns the path to the
+/// workflow.
+pub fn write_github_workflow(
+    project_dir: &Path,
+    package_config: &PackageConfig,
+    release: bool,
+    force: bool,
+) -> Result<PathBuf> {
+    let path = project_dir.join(GITHUB_WORKFLOW_PATH);
+
+    if path.exists() && !force {
+        return Err(anyhow!(
+            "{} already exists! Pass `--force` to overwrite it.",
+            path.display()
+        ));
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("Could not create the workflows directory!")?;
+    }
+
+    fs::write(&path, github_workflow(package_config, release))
+        .context("Could not write the GitHub Actions workflow!")?;
+
+    Ok(path)
+}
+
+/// Generates a GitHub Actions workflow that builds a release archive for each of the project's targets on a native
+/// runner, caching Jundler's downloads between runs and uploading the archives as artifacts. With `release`, pushing a
+/// tag also publishes the archives, with their checksums, as a GitHub release.
+pub fn github_workflow(package_config: &PackageConfig, release: bool) -> String {
+    let targets = match package_config.jundler.targets.as_slice() {
+        [] => DEFAULT_TARGETS,
+        targets => targets,
+    };
+
+    let matrix = targets
+        .iter()
+        .map(|target| {
+            let (archive, cache) = match target.os {
+                Os::Linux => ("tar.gz", "~/.cache/jundler"),
+                Os::MacOS => ("tar.gz", "~/Library/Caches/jundler"),
+                Os::Windows => ("zip", "~\\AppData\\Local\\jundler"),
+            };
+
+            format!(
+                "          - target: {}\n            runner: {}\n            archive: {}\n            cache: {}\n",
+                target,
+                runner(*target),
+                archive,
+                cache
+            )
+        })
+        .collect::<String>();
+
+    let matrix = matrix.trim_end();
+
+    let mut workflow = format!(
+        r#"name: Release
+
+on:
+  push:
+    tags: ["v*"]
+  workflow_dispatch:
+
+jobs:
+  build:
+    name: Build ${{{{ matrix.target }}}}
+    runs-on: ${{{{ matrix.runner }}}}
+    strategy:
+      fail-fast: false
+      matrix:
+        include:
+{matrix}
+    steps:
+      - uses: actions/checkout@v4
+
+      - name: Cache Node.js and esbuild downloads
+        uses: actions/cache@v4
+        with:
+          path: ${{{{ matrix.cache }}}}
+          key: jundler-${{{{ matrix.target }}}}-${{{{ hashFiles('package.json', '.nvmrc', '.node-version') }}}}
+          restore-keys: jundler-${{{{ matrix.target }}}}-
+
+      - name: Install Jundler
+        run: cargo install jundler --version {version} --locked
+
+      - name: Build
+        run: jundler build --target ${{{{ matrix.target }}}} --archive ${{{{ matrix.archive }}}}
+
+      - uses: actions/upload-artifact@v4
+        with:
+          name: {name}-${{{{ matrix.target }}}}
+          path: {name}-*.${{{{ matrix.archive }}}}
+          if-no-files-found: error
+"#,
+        matrix = matrix,
+        version = env!("CARGO_PKG_VERSION"),
+        name = package_config.name,
+    );
+
+    if release {
+        workflow += r#"
+  release:
+    name: Publish release
+    if: startsWith(github.ref, 'refs/tags/')
+    needs: build
+    runs-on: ubuntu-latest
+    permissions:
+      contents: write
+    steps:
+      - uses: actions/download-artifact@v4
+        with:
+          path: dist
+          merge-multiple: true
+
+      - name: Write checksums
+        working-directory: dist
+        run: sha256sum * > SHA256SUMS
+
+      - uses: softprops/action-gh-release@v2
+        with:
+          files: dist/*
+"#;
+    }
+
+    workflow
+}
+
+/// Gets the GitHub-hosted runner that builds a target natively, so that macOS binaries can be signed and binaries can
+/// be run after building.
+fn runner(target: Target) -> &'static str {
+    match (target.os, target.arch) {
+        (Os::Linux, Arch::Arm64) => "ubuntu-24.04-arm",
+        (Os::Linux, _) => "ubuntu-latest",
+        (Os::MacOS, Arch::X64) => "macos-13",
+        (Os::MacOS, _) => "macos-latest",
+        (Os::Windows, _) => "windows-latest",
+    }
+}
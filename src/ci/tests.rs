@@ -0,0 +1,46 @@
+#![cfg(test)]
+
+use super::*;
+
+/// Test that the GitHub Actions workflow builds every target of the project on a native runner, and only publishes a
+/// release when asked to.
+#[test]
+fn github_workflow_matrix() {
+    let package_config: PackageConfig = serde_json::from_str(
+        r#"{
+            "name": "mytool",
+            "jundler": { "targets": ["linux-arm64", "darwin-x64", "win-x64"] }
+        }"#,
+    )
+    .unwrap();
+
+    let workflow = github_workflow(&package_config, false);
+
+    assert!(workflow.contains("          - target: linux-arm64\n            runner: ubuntu-24.04-arm\n            archive: tar.gz\n"));
+    assert!(workflow.contains("          - target: darwin-x64\n            runner: macos-13\n"));
+    assert!(workflow.contains("          - target: win-x64\n            runner: windows-latest\n            archive: zip\n"));
+    assert!(workflow.contains(
+        "run: jundler build --target ${{ matrix.target }} --archive ${{ matrix.archive }}"
+    ));
+    assert!(workflow.contains("path: mytool-*.${{ matrix.archive }}"));
+    assert!(!workflow.contains("action-gh-release"));
+
+    assert!(github_workflow(&package_config, true).contains("softprops/action-gh-release"));
+
+    // Projects without targets get the common ones
+    let package_config: PackageConfig = serde_json::from_str(r#"{ "name": "mytool" }"#).unwrap();
+
+    assert_eq!(
+        github_workflow(&package_config, false)
+            .matches("- target:")
+            .count(),
+        DEFAULT_TARGETS.len()
+    );
+
+    let project = tempdir::TempDir::new("project").unwrap();
+    let path = write_github_workflow(project.path(), &package_config, false, false).unwrap();
+
+    assert_eq!(path, project.path().join(GITHUB_WORKFLOW_PATH));
+    assert!(write_github_workflow(project.path(), &package_config, false, false).is_err());
+    assert!(write_github_workflow(project.path(), &package_config, false, true).is_ok());
+}
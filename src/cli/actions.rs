@@ -0,0 +1,743 @@
+use super::print::{count, print_audit, print_diffs, print_output, print_plan, print_settings};
+use super::{BuildArgs, Cli};
+use crate::builder::{
+    cache_server::CacheServer,
+    cache_stats::CachedBinary,
+    clean::{project_artifacts, CleanScope},
+    diff,
+    downloader::{Downloader, HttpDownloader},
+    errors::{Classify, ErrorClass},
+    get_configs,
+    node_manager::security::download_vulnerabilities,
+    node_version::{resolve_locked_node_version, resolve_node_version},
+    plan::PlanFile,
+    platforms::{Arch, Os, Target},
+    report::{BuildReport, TargetReport},
+    settings::{self, Setting, Source},
+    storage::DirStorage,
+    test_suite::{test_command, TestCommand},
+    BuildOptions, BuildOutput, Builder,
+};
+use crate::ci;
+use crate::doctor;
+use crate::js_config::CONFIG_FILE_NAME;
+use crate::migrate::{MigrateFrom, Migration};
+use crate::ui::plain::text;
+use crate::ui::{print_line, TerminalReporter};
+use anyhow::{anyhow, Context, Result};
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory};
+use indicatif::HumanBytes;
+use semver::Version;
+use std::collections::BTreeSet;
+use std::env;
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::net::{SocketAddr, TcpListener};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tempdir::TempDir;
+
+impl Cli {
+    /// Plans the builds of the projects without building anything, and prints the plans. With `plan_out`, they're
+    /// written to a plan file as well.
+    pub(super) fn plan(
+        &self,
+        builder: &mut Builder,
+        args: &BuildArgs,
+        json: bool,
+        plan_out: Option<&Path>,
+    ) -> Result<()> {
+        if args.is_remote() {
+            return Err(anyhow!(
+                "Dry runs can't plan builds of packages from the npm registry or git repositories, as they'd have to be downloaded first!"
+            ));
+        }
+
+        let mut plans = vec![];
+
+        for project in args.projects(builder)? {
+            let options = self
+                .build_options(builder, args, &project)
+                .classify(ErrorClass::Config)?;
+
+            plans.push(builder.plan(&project.dir, &options)?);
+        }
+
+        if let Some(path) = plan_out {
+            PlanFile::write(path, &plans)?;
+        }
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&plans)?);
+        } else {
+            plans.iter().for_each(print_plan);
+        }
+
+        if let Some(path) = plan_out {
+            self.status(format!(
+                "📝 Wrote the plan to {}. Build it with `--plan-in {}`.",
+                path.display(),
+                path.display()
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Builds every project, and prints what each build wrote. With `plan_in`, each project is only built if its
+    /// build would still do exactly what the plan says. Returns what was built.
+    pub(super) fn build(
+        &self,
+        builder: &mut Builder,
+        reporter: &TerminalReporter,
+        args: &BuildArgs,
+        plan_in: Option<&Path>,
+    ) -> Result<Vec<BuildOutput>> {
+        let plan_file = match plan_in {
+            Some(path) if args.is_remote() => {
+                return Err(anyhow!(
+                    "Builds of packages from the npm registry or git repositories can't be planned, so they can't be built from {}!",
+                    path.display()
+                ))
+                .classify(ErrorClass::Config)
+            }
+            Some(path) => Some(PlanFile::read(path).classify(ErrorClass::Config)?),
+            None => None,
+        };
+
+        let projects = args.projects(builder)?;
+        let mut outputs = vec![];
+
+        let mut progress = (projects.len() > 1).then(|| reporter.spawn_progress(projects.len()));
+
+        for project in &projects {
+            if let Some(progress) = &mut progress {
+                progress.next(&project.dir.display().to_string());
+            }
+
+            let options = self
+                .build_options(builder, args, project)
+                .classify(ErrorClass::Config)?;
+
+            if let Some(plan_file) = &plan_file {
+                plan_file
+                    .approve(&builder.plan(&project.dir, &options)?)
+                    .classify(ErrorClass::Config)?;
+            }
+
+            let output = args
+                .build(builder, &project.dir, &options)
+                .context(format!("Error building {}", project.dir.display()))?;
+
+            outputs.push(output);
+        }
+
+        for output in &outputs {
+            print_output(reporter, output);
+        }
+
+        Ok(outputs)
+    }
+
+    /// Watches the project, rebuilding it whenever something changes.
+    pub(super) fn watch(&self, builder: &mut Builder, args: &BuildArgs) -> Result<()> {
+        if args.is_remote() {
+            return Err(anyhow!(
+                "Can't watch a package from the npm registry or a git repository!"
+            ));
+        }
+
+        let project = args.project(builder)?;
+        let options = self
+            .build_options(builder, args, &project)
+            .classify(ErrorClass::Config)?;
+
+        builder.watch(&project.dir, &options)
+    }
+
+    /// Builds the project for this machine into a temporary directory, to run it. Returns the directory, which has to
+    /// be kept until the application exits, and the path to the application.
+    pub(super) fn build_to_run(
+        &self,
+        builder: &mut Builder,
+        args: &BuildArgs,
+    ) -> Result<(TempDir, PathBuf)> {
+        let project = args.project(builder)?;
+        let options = self
+            .build_options(builder, args, &project)
+            .classify(ErrorClass::Config)?;
+
+        if (options.target_os, options.target_arch) != (Os::host()?, Arch::host()?) {
+            return Err(anyhow!(
+                "Can't run a binary built for {}-{} on this machine!",
+                options.target_os,
+                options.target_arch
+            ));
+        }
+
+        // Build into a temporary directory, so the project directory is left untouched.
+        let output_dir =
+            TempDir::new("jundler-run").context("Could not create a temporary directory!")?;
+
+        let app_path = args
+            .build(
+                builder,
+                &project.dir,
+                &BuildOptions {
+                    output_dir: Some(output_dir.path().to_path_buf()),
+                    ..options
+                },
+            )?
+            .app_path;
+
+        Ok((output_dir, app_path))
+    }
+
+    /// Builds the project for this machine, to test it. Returns the directory of the project, the test command, and
+    /// the path to the built binary.
+    pub(super) fn build_to_test(
+        &self,
+        builder: &mut Builder,
+        reporter: &TerminalReporter,
+        args: &BuildArgs,
+        command: &[String],
+    ) -> Result<(PathBuf, TestCommand, PathBuf)> {
+        let project = args.project(builder)?;
+        let options = self
+            .build_options(builder, args, &project)
+            .classify(ErrorClass::Config)?;
+
+        if (options.target_os, options.target_arch) != (Os::host()?, Arch::host()?) {
+            return Err(anyhow!(
+                "Can't test a binary built for {}-{} on this machine!",
+                options.target_os,
+                options.target_arch
+            ));
+        }
+
+        // The test command is found before building, so a project without one fails straight away
+        let (_, package_config) = get_configs(&project.dir)?;
+        let command =
+            test_command(&project.dir, &package_config, command).classify(ErrorClass::Config)?;
+
+        let output = args
+            .build(builder, &project.dir, &options)
+            .context(format!("Error building {}", project.dir.display()))?;
+
+        print_output(reporter, &output);
+
+        Ok((project.dir, command, output.app_path))
+    }
+
+    /// Downloads Node.js for the targets, and ESBuild, into the cache. The version of Node.js is worked out from the
+    /// project if it isn't given.
+    pub(super) fn fetch(
+        &self,
+        builder: &mut Builder,
+        node_version: Option<&Version>,
+        targets: &[Target],
+        project_dir: &Path,
+    ) -> Result<()> {
+        let node_version = match node_version {
+            Some(node_version) => node_version.clone(),
+
+            None => {
+                let (node_version, source) =
+                    resolve_node_version(project_dir, builder.downloader())?;
+
+                self.status(format!(
+                    "📌 Using Node.js v{} from {}",
+                    node_version, source
+                ));
+
+                node_version
+            }
+        };
+
+        builder.fetch(
+            project_dir,
+            &node_version,
+            &fetch_targets(targets, project_dir)?,
+        )
+    }
+
+    /// Serves the shared downloads in the cache over HTTP, until it's stopped.
+    pub(super) fn serve_cache(&self, listen: &SocketAddr) -> Result<()> {
+        let (cache_dir, _) = self.resolve_cache_dir()?;
+        let shared_dir = cache_dir.join("shared");
+        let listener =
+            TcpListener::bind(listen).context(format!("Could not listen on {}", listen))?;
+
+        self.status(format!(
+            "📡 Serving {} at http://{}. Build with `--shared-cache http://<this machine>:{}` to use it, and press Ctrl-C to stop.",
+            shared_dir.display(),
+            listener.local_addr()?,
+            listen.port()
+        ));
+
+        CacheServer::new(
+            Arc::new(DirStorage::new(shared_dir)),
+            Arc::new(HttpDownloader),
+        )
+        .serve(listener)
+        .context("Error serving the cache")
+    }
+
+    /// Writes a GitHub Actions workflow that builds the project's release archives.
+    pub(super) fn init_ci(&self, project_dir: &Path, release: bool, force: bool) -> Result<()> {
+        let (_, package_config) = get_configs(project_dir)?;
+        let path = ci::write_github_workflow(project_dir, &package_config, release, force)?;
+
+        self.status(format!("✨ Wrote {}", path.display()));
+
+        Ok(())
+    }
+
+    /// Cleans the whole cache, part of it, or the project's build outputs, and prints what was removed. Asks first,
+    /// unless there's nothing to remove. With `dry_run`, only lists what would be removed.
+    pub(super) fn clean(
+        &self,
+        builder: &mut Builder,
+        scope: Option<CleanScope>,
+        project_dir: &Path,
+        dry_run: bool,
+    ) -> Result<()> {
+        let stats = builder.cache_stats()?;
+
+        // What would be removed is all a dry run prints, so it's shown even with `--quiet`
+        let show = |line: String| {
+            if dry_run {
+                print_line(&line)
+            } else {
+                self.status(line)
+            }
+        };
+
+        let list_binaries = |name: &str, binaries: &[CachedBinary]| {
+            for binary in binaries {
+                show(format!(
+                    "   {} {:<24} {:>10}",
+                    name,
+                    binary.name,
+                    HumanBytes(binary.bytes).to_string()
+                ));
+            }
+        };
+
+        let (removing, bytes, any) = match scope {
+            None => {
+                list_binaries("Node.js", &stats.node);
+                list_binaries("ESBuild", &stats.esbuild);
+
+                for (name, section) in [
+                    ("Builds", &stats.builds),
+                    ("Dependencies", &stats.deps),
+                    ("Sources", &stats.sources),
+                ] {
+                    if section.entries > 0 {
+                        show(format!(
+                            "   {:<32} {:>10}",
+                            name,
+                            HumanBytes(section.bytes).to_string()
+                        ));
+                    }
+                }
+
+                (
+                    format!("everything in {}", stats.dir.display()),
+                    stats.total_bytes,
+                    stats.total_bytes > 0,
+                )
+            }
+
+            Some(CleanScope::Node) => {
+                list_binaries("Node.js", &stats.node);
+
+                (
+                    count(stats.node.len(), "Node.js binary", "Node.js binaries"),
+                    stats.node.iter().map(|binary| binary.bytes).sum(),
+                    !stats.node.is_empty(),
+                )
+            }
+
+            Some(CleanScope::Esbuild) => {
+                list_binaries("ESBuild", &stats.esbuild);
+
+                (
+                    count(stats.esbuild.len(), "ESBuild binary", "ESBuild binaries"),
+                    stats.esbuild.iter().map(|binary| binary.bytes).sum(),
+                    !stats.esbuild.is_empty(),
+                )
+            }
+
+            Some(CleanScope::Deps) => (
+                count(stats.deps.entries, "cached install", "cached installs"),
+                stats.deps.bytes,
+                stats.deps.entries > 0,
+            ),
+
+            Some(CleanScope::Project) => {
+                let (_, package_config) = get_configs(project_dir).classify(ErrorClass::Config)?;
+                let files = project_artifacts(project_dir, &package_config)?;
+
+                for path in &files {
+                    show(format!("   {}", path.display()));
+                }
+
+                (
+                    count(files.len(), "file", "files"),
+                    files
+                        .iter()
+                        .map(|path| fs::metadata(path).map_or(0, |metadata| metadata.len()))
+                        .sum(),
+                    !files.is_empty(),
+                )
+            }
+        };
+
+        if dry_run {
+            print_line(&format!(
+                "🔍 Would remove {} ({})",
+                removing,
+                HumanBytes(bytes)
+            ));
+
+            return Ok(());
+        }
+
+        if any && !self.confirm(&format!("Remove {} ({})?", removing, HumanBytes(bytes)))? {
+            self.status("👋 Nothing was removed".to_string());
+
+            return Ok(());
+        }
+
+        match scope {
+            None => builder.clean_cache()?,
+            Some(CleanScope::Node) => builder.clean_node_cache()?,
+            Some(CleanScope::Esbuild) => builder.clean_esbuild_cache()?,
+            Some(CleanScope::Deps) => builder.clean_deps_cache()?,
+            Some(CleanScope::Project) => {
+                builder.clean_project(project_dir)?;
+            }
+        }
+
+        self.status(format!("🗑️  Removed {} ({})", removing, HumanBytes(bytes)));
+
+        Ok(())
+    }
+
+    /// Prints the settings a build of the project would use, and where each came from.
+    pub(super) fn show_config(&self, args: &BuildArgs, json: bool) -> Result<()> {
+        let project_dir = args
+            .local_project_dir()
+            .ok_or_else(|| anyhow!("Can only show the configuration of a single local project!"))
+            .classify(ErrorClass::Config)?;
+
+        // Clap only keeps track of where each value came from in the matches, so the arguments are parsed again
+        let settings = self.settings(
+            project_dir,
+            args,
+            &Cli::command().get_matches(),
+            env::vars_os(),
+            self.downloader().as_ref(),
+        )?;
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&settings)?);
+        } else {
+            print_settings(project_dir, &settings);
+        }
+
+        Ok(())
+    }
+
+    /// Works out the settings a build of the project would use, and where each came from. `matches` are the arguments
+    /// `jundler config show` was run with, which say whether each option was given on the command line, and `vars` is
+    /// the environment they were parsed in, which says whether the others were given in it. The release index of
+    /// Node.js is downloaded with `downloader`, if the version has to be looked up in it.
+    pub(super) fn settings(
+        &self,
+        project_dir: &Path,
+        args: &BuildArgs,
+        matches: &ArgMatches,
+        vars: impl IntoIterator<Item = (OsString, OsString)>,
+        downloader: &dyn Downloader,
+    ) -> Result<Vec<Setting>> {
+        let matches = matches
+            .subcommand_matches("config")
+            .and_then(|matches| matches.subcommand_matches("show"))
+            .expect("`jundler config show` was run!");
+
+        // Clap ignores empty variables, so they don't count as given either
+        let set: BTreeSet<OsString> = vars
+            .into_iter()
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(name, _)| name)
+            .collect();
+
+        let given = |id: &str| {
+            let name = format!("JUNDLER_{}", id.to_uppercase());
+
+            match matches.value_source(id) {
+                Some(ValueSource::CommandLine) => Some(Source::CommandLine),
+                Some(_) if set.contains(OsStr::new(&name)) => Some(Source::Environment(name)),
+                _ => None,
+            }
+        };
+
+        let (node_version, node_source) = match &args.node_version {
+            Some(node_version) => (
+                node_version.clone(),
+                given("node_version").unwrap_or(Source::CommandLine),
+            ),
+
+            None => {
+                let (node_version, source) = if args.frozen {
+                    resolve_locked_node_version(project_dir)?
+                } else {
+                    resolve_node_version(project_dir, downloader)?
+                };
+
+                (node_version, Source::Project(source.to_string()))
+            }
+        };
+
+        let mut settings = settings::settings(
+            project_dir,
+            &args.options(node_version)?,
+            node_source,
+            given,
+        )
+        .classify(ErrorClass::Config)?;
+
+        let (_, package_config) = get_configs(project_dir).classify(ErrorClass::Config)?;
+
+        let config_source = |key: &str| {
+            if project_dir.join(CONFIG_FILE_NAME).exists() {
+                Source::Config(key.to_string(), CONFIG_FILE_NAME)
+            } else {
+                Source::Config(format!("jundler.{}", key), "package.json")
+            }
+        };
+
+        let cache_dir = match (&self.cache_dir, &package_config.jundler.cache_dir) {
+            (Some(cache_dir), _) => Setting::new(
+                "cache-dir",
+                cache_dir.display(),
+                given("cache_dir").unwrap_or(Source::CommandLine),
+            ),
+            (None, Some(cache_dir)) => Setting::new(
+                "cache-dir",
+                project_dir.join(cache_dir).display(),
+                config_source("cacheDir"),
+            ),
+            (None, None) => Setting {
+                key: "cache-dir",
+                value: self.cache_dir().map(|dir| dir.display().to_string()),
+                source: Source::Default,
+            },
+        };
+
+        let shared_cache = match (&self.shared_cache, &package_config.jundler.shared_cache) {
+            (Some(shared_cache), _) => Setting::new(
+                "shared-cache",
+                shared_cache,
+                given("shared_cache").unwrap_or(Source::CommandLine),
+            ),
+            (None, Some(_)) => Setting::new(
+                "shared-cache",
+                self.shared_cache().unwrap_or_default(),
+                config_source("sharedCache"),
+            ),
+            (None, None) => Setting {
+                key: "shared-cache",
+                value: None,
+                source: Source::Default,
+            },
+        };
+
+        settings.push(cache_dir);
+        settings.push(shared_cache);
+
+        Ok(settings)
+    }
+}
+
+/// Writes the report of the builds to `path`, and prints it to stdout with `json`.
+pub(super) fn report(
+    builder: &Builder,
+    outputs: &[BuildOutput],
+    path: Option<&Path>,
+    json: bool,
+    duration: Duration,
+) -> Result<()> {
+    let report = BuildReport::new(
+        outputs.iter().map(TargetReport::new).collect(),
+        builder.tracker.recording(),
+        duration,
+    );
+
+    if let Some(path) = path {
+        report.write(path)?;
+    }
+
+    if json {
+        println!("{}", report.to_json());
+    }
+
+    Ok(())
+}
+
+/// Compares two builds, from their reports or binaries, and prints how they changed.
+pub(super) fn diff(old: &Path, new: &Path, json: bool) -> Result<()> {
+    let diffs = diff::compare(&diff::load(old)?, &diff::load(new)?)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&diffs)?);
+    } else {
+        print_diffs(&diffs);
+    }
+
+    Ok(())
+}
+
+/// Checks every cached binary against the checksum it was cached with, and prints whether it's intact. Fails if any
+/// aren't.
+pub(super) fn verify_cache(builder: &Builder) -> Result<()> {
+    let binaries = builder.verify_cache();
+
+    if binaries.is_empty() {
+        print_line("   Nothing is cached yet.");
+    }
+
+    for binary in &binaries {
+        print_line(&format!(
+            "   {} {}",
+            if binary.intact {
+                console::style("✓").green()
+            } else {
+                console::style("✗").red()
+            },
+            binary.name
+        ));
+    }
+
+    let corrupt = binaries.iter().filter(|binary| !binary.intact).count();
+
+    if corrupt > 0 {
+        return Err(anyhow!(
+            "{} in the cache {} corrupt! Remove {} with `jundler clean node` or `jundler clean esbuild`, and {}'ll be downloaded again when needed.",
+            count(corrupt, "binary", "binaries"),
+            if corrupt == 1 { "is" } else { "are" },
+            if corrupt == 1 { "it" } else { "them" },
+            if corrupt == 1 { "it" } else { "they" },
+        ));
+    }
+
+    Ok(())
+}
+
+/// Lists every cached binary, and checks the cached versions of Node.js for published vulnerabilities. Fails if any
+/// have them.
+pub(super) fn audit(builder: &Builder, json: bool) -> Result<()> {
+    // The cache is still listed without the vulnerability database, it just can't be checked against it
+    let vulnerabilities = download_vulnerabilities(&HttpDownloader).unwrap_or_else(|err| {
+        builder.tracker.warn(format!(
+            "Could not download the Node.js vulnerability database, so versions aren't checked for vulnerabilities: {}",
+            err
+        ));
+
+        vec![]
+    });
+
+    let binaries = builder.audit(&vulnerabilities)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&binaries)?);
+    } else {
+        print_audit(&binaries);
+    }
+
+    let vulnerable = binaries.iter().filter(|binary| binary.vulnerable()).count();
+
+    if vulnerable > 0 {
+        return Err(anyhow!(
+            "{} in the cache {} published vulnerabilities! Build with a patched version of Node.js, and remove the old one with `jundler clean`.",
+            count(vulnerable, "version of Node.js", "versions of Node.js"),
+            if vulnerable == 1 { "has" } else { "have" }
+        ));
+    }
+
+    Ok(())
+}
+
+/// Runs every environment check and prints the results. Fails if any check finds a problem that will break builds.
+pub(super) fn doctor(cache_dir: Option<&Path>, project_dir: &Path) -> Result<()> {
+    let checks = doctor::run_checks(cache_dir, project_dir);
+
+    for check in &checks {
+        check.print();
+    }
+
+    let errors = checks
+        .iter()
+        .filter(|check| check.status == doctor::Status::Error)
+        .count();
+
+    if errors > 0 {
+        return Err(anyhow!(
+            "{} check(s) failed. Fix them before building!",
+            errors
+        ));
+    }
+
+    print_line("✨ Everything looks good!");
+
+    Ok(())
+}
+
+/// Gets the targets to fetch Node.js for: the ones given, then the project's `jundler.targets`, and finally this
+/// machine.
+pub(super) fn fetch_targets(targets: &[Target], project_dir: &Path) -> Result<Vec<Target>> {
+    if !targets.is_empty() {
+        return Ok(targets.to_vec());
+    }
+
+    Ok(match get_configs(project_dir) {
+        Ok((_, package_config)) if !package_config.jundler.targets.is_empty() => {
+            package_config.jundler.targets
+        }
+
+        _ => vec![Target {
+            os: Os::host()?,
+            arch: Arch::host()?,
+        }],
+    })
+}
+
+/// Migrates the project in `project_dir` from another tool, and prints what to do next.
+pub(super) fn migrate(project_dir: &Path, from: MigrateFrom, force: bool) -> Result<()> {
+    let migration = Migration::new(project_dir, from)?;
+
+    for path in migration.write(project_dir, force)? {
+        print_line(&format!("📝 Wrote {}", path.display()));
+    }
+
+    for note in &migration.notes {
+        print_line(&format!(
+            "{} {}",
+            console::style(text("📎 Note:")).yellow(),
+            note
+        ));
+    }
+
+    print_line(&format!(
+        "✨ Migrated from {}! Build the project with:\n\n    {}\n",
+        from,
+        migration.build_commands().join("\n    ")
+    ));
+
+    Ok(())
+}
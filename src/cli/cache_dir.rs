@@ -0,0 +1,171 @@
+use super::Cli;
+use crate::builder::errors::{Classify, ErrorClass};
+use crate::builder::get_configs;
+use anyhow::{anyhow, Context, Result};
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tempdir::TempDir;
+
+impl Cli {
+    /// Gets the directory that was chosen to cache everything in: from `--cache-dir` (or `JUNDLER_CACHE_DIR`), then the
+    /// project's configuration. `None` if none was chosen.
+    pub(super) fn chosen_cache_dir(&self) -> Option<PathBuf> {
+        self.cache_dir.clone().or_else(|| {
+            let project_dir = self.config_dir()?;
+            let (_, package_config) = get_configs(project_dir).ok()?;
+
+            package_config
+                .jundler
+                .cache_dir
+                .map(|cache_dir| project_dir.join(cache_dir))
+        })
+    }
+
+    /// Gets the directory to cache everything in: the chosen one, or else the user's cache directory. `None` if
+    /// there's no cache directory at all.
+    pub(super) fn cache_dir(&self) -> Option<PathBuf> {
+        self.chosen_cache_dir()
+            .or_else(|| dirs::cache_dir().map(|dir| dir.join("jundler")))
+    }
+
+    /// Finds the directory to cache everything in, creating it if it doesn't exist yet. A chosen cache directory has to
+    /// be writable, but when the user's cache directory can't be found or written to (as in some containers, or for
+    /// systemd's dynamic users), this falls back to a directory of the user's own in the temporary directory (see
+    /// [`temp_cache_dir`]), then to `.jundler-cache` in the project.
+    /// Returns the directory, along with a warning if it's a fallback.
+    pub(super) fn resolve_cache_dir(&self) -> Result<(PathBuf, Option<String>)> {
+        self.resolve_cache_dir_in(dirs::cache_dir().map(|dir| dir.join("jundler")))
+    }
+
+    /// Like `resolve_cache_dir`, but with `user_cache_dir` as the `jundler` folder of the user's cache directory.
+    pub(super) fn resolve_cache_dir_in(
+        &self,
+        user_cache_dir: Option<PathBuf>,
+    ) -> Result<(PathBuf, Option<String>)> {
+        if let Some(cache_dir) = self.chosen_cache_dir() {
+            return Ok((create_cache_dir(cache_dir)?, None));
+        }
+
+        let reason = match user_cache_dir {
+            Some(cache_dir) => match prepare_dir(&cache_dir) {
+                Ok(()) => return Ok((cache_dir, None)),
+                Err(err) => format!("{} is not writable: {}", cache_dir.display(), err),
+            },
+            None => "there isn't one for this user".to_string(),
+        };
+
+        // The temporary directory is shared, so the one there has to be the user's alone
+        let fallbacks = [
+            Some((temp_cache_dir(), true)),
+            self.config_dir()
+                .map(|dir| (dir.join(".jundler-cache"), false)),
+        ];
+
+        for (cache_dir, private) in fallbacks.into_iter().flatten() {
+            let prepared = if private {
+                prepare_private_dir(&cache_dir)
+            } else {
+                prepare_dir(&cache_dir)
+            };
+
+            if prepared.is_ok() {
+                let warning = format!(
+                    "Could not use your cache directory ({}), so everything is cached in {} instead. Pass `--cache-dir` (or set `JUNDLER_CACHE_DIR`) to choose where.",
+                    reason,
+                    cache_dir.display()
+                );
+
+                return Ok((cache_dir, Some(warning)));
+            }
+        }
+
+        Err(anyhow!(
+            "Could not find a writable cache directory ({}, and neither a directory of your own in the temporary directory nor the project can be used)! Pass `--cache-dir`, or set `JUNDLER_CACHE_DIR`.",
+            reason
+        ))
+        .classify(ErrorClass::Config)
+    }
+}
+
+/// Creates the cache directory if it doesn't exist yet, and returns it. Fails with a way out if it can't be written to.
+fn create_cache_dir(cache_dir: PathBuf) -> Result<PathBuf> {
+    match prepare_dir(&cache_dir) {
+        Ok(()) => Ok(cache_dir),
+        Err(err)
+            if matches!(
+                err.kind(),
+                io::ErrorKind::PermissionDenied | io::ErrorKind::ReadOnlyFilesystem
+            ) =>
+        {
+            Err(err)
+                .context(format!(
+                    "Jundler can't write to the cache directory at {}! Make it writable for your user (i.e. with `chown` or `chmod`), or pass `--cache-dir` (or set `JUNDLER_CACHE_DIR`) to cache somewhere else.",
+                    cache_dir.display()
+                ))
+                .classify(ErrorClass::Config)
+        }
+        Err(err) => Err(err).context(format!(
+            "Could not create the cache directory at {}!",
+            cache_dir.display()
+        )),
+    }
+}
+
+/// Creates `dir` if it doesn't exist yet, and checks that it can be written to.
+fn prepare_dir(dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    TempDir::new_in(dir, ".jundler-probe").map(drop)
+}
+
+/// The cache directory to fall back on in the temporary directory. On Unix the temporary directory is shared by every
+/// user, so it's named after the user's ID, as `jundler-<uid>`. On Windows it's already the user's own.
+pub(crate) fn temp_cache_dir() -> PathBuf {
+    #[cfg(unix)]
+    // SAFETY: `geteuid` can't fail, and doesn't touch any memory
+    let name = format!("jundler-{}", unsafe { libc::geteuid() });
+
+    #[cfg(not(unix))]
+    let name = "jundler".to_string();
+
+    env::temp_dir().join(name)
+}
+
+/// Like `prepare_dir`, but for a directory that has to be the user's alone, like [`temp_cache_dir`]. It's created
+/// with mode 0700, and refused if it already exists but is someone else's, or anyone else can get into it, so that no
+/// one else can swap out what's cached in it.
+#[cfg(unix)]
+pub(super) fn prepare_private_dir(dir: &Path) -> io::Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+
+    match fs::DirBuilder::new().mode(0o700).create(dir) {
+        Err(err) if err.kind() != io::ErrorKind::AlreadyExists => return Err(err),
+        _ => {}
+    }
+
+    // Not following symlinks, as anyone could have made one to a directory of theirs
+    let metadata = fs::symlink_metadata(dir)?;
+
+    // SAFETY: `geteuid` can't fail, and doesn't touch any memory
+    let uid = unsafe { libc::geteuid() };
+
+    if !metadata.is_dir() || metadata.uid() != uid || metadata.mode() & 0o077 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "{} isn't a directory that only you can access",
+                dir.display()
+            ),
+        ));
+    }
+
+    prepare_dir(dir)
+}
+
+/// Like `prepare_dir`, but for a directory that has to be the user's alone. Windows' temporary directory is already
+/// the user's own.
+#[cfg(not(unix))]
+pub(super) fn prepare_private_dir(dir: &Path) -> io::Result<()> {
+    prepare_dir(dir)
+}
@@ -0,0 +1,1338 @@
+mod actions;
+mod cache_dir;
+mod print;
+mod tests;
+
+pub(crate) use cache_dir::temp_cache_dir;
+
+use crate::builder::{
+    advisories::AdvisoryPolicy,
+    archive::ArchiveFormat,
+    checksums::ChecksumFormat,
+    clean::CleanScope,
+    copy::CopyStrategy,
+    downloader::{Downloader, HttpDownloader},
+    errors::{Classify, ErrorClass, ErrorFormat},
+    get_configs,
+    git_source::GitSource,
+    linux_packages::LinuxPackageFormat,
+    metadata,
+    node_version::{resolve_locked_node_version, resolve_node_version},
+    package_manager::PackageManager,
+    platforms::{Arch, Os, Target},
+    runtime::Runtime,
+    signatures::SignatureFormat,
+    size::{parse_size, MaxSizePolicy},
+    storage::{self, StorageDownloader},
+    test_suite::run_tests,
+    BuildOptions, BuildOutput, Builder, WindowsSignOptions,
+};
+use crate::js_config::schema::{self, SchemaFile};
+use crate::migrate::MigrateFrom;
+use crate::ui::color::ColorChoice;
+use crate::ui::events::ProgressFormat;
+use crate::ui::messages::{MAX_MSG_LEN, RUN_APP_MSG, TEST_SUITE_MSG};
+use crate::ui::plain::text;
+use crate::ui::{TerminalReporter, Verbosity};
+use anyhow::{anyhow, Context, Result};
+use clap::builder::FalseyValueParser;
+use clap::{Args, Parser, Subcommand};
+use console::Term;
+use ignore::overrides::OverrideBuilder;
+use ignore::WalkBuilder;
+use indicatif::HumanDuration;
+use print::{print_cache_stats, print_inspection};
+use semver::Version;
+use std::env;
+use std::io::{self, IsTerminal};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Simple program to greet a person
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+pub struct Cli {
+    /// The subcommand to run.
+    #[clap(subcommand)]
+    pub action: Action,
+
+    /// The directory to cache Node.js, ESBuild, and builds in, i.e. a volume that's kept between CI jobs. Defaults to
+    /// `jundler.cacheDir` in the project's configuration, or the `jundler` folder in your cache directory.
+    #[arg(long, global = true, env = "JUNDLER_CACHE_DIR", value_name = "DIR")]
+    cache_dir: Option<PathBuf>,
+
+    /// A cache of downloads shared between machines, i.e. by a team, so each Node.js archive is only downloaded from
+    /// the internet once: a directory (i.e. on a network drive), or an HTTP server that files can be `GET` from and
+    /// `PUT` to. Defaults to `jundler.sharedCache` in the project's configuration.
+    #[arg(
+        long,
+        global = true,
+        env = "JUNDLER_SHARED_CACHE",
+        value_name = "DIR|URL"
+    )]
+    shared_cache: Option<String>,
+
+    /// Print more: `-v` shows every command that's run and everything it prints, and `-vv` also shows trace logs.
+    /// `RUST_LOG` overrides the log level.
+    #[arg(short, long, global = true, action = clap::ArgAction::Count, conflicts_with = "quiet")]
+    verbose: u8,
+
+    /// When to color output: `auto` colors it when it's going to a terminal, following `NO_COLOR`, `CLICOLOR`, and
+    /// `CLICOLOR_FORCE`.
+    #[arg(long, global = true, env = "JUNDLER_COLOR", value_enum, value_name = "WHEN", default_value_t = ColorChoice::Auto)]
+    pub color: ColorChoice,
+
+    /// Print plain ASCII, without emoji or Unicode spinners, for consoles that can't show them. On by default if the
+    /// terminal doesn't use UTF-8.
+    #[arg(long, global = true, env = "JUNDLER_PLAIN", default_value_t = false, value_parser = FalseyValueParser::new())]
+    pub plain: bool,
+
+    /// How to show progress: `human` for spinners, or `json` for newline-delimited JSON events on stdout (steps
+    /// starting, finishing and failing, download progress, and warnings), with everything else on stderr.
+    #[arg(long, global = true, env = "JUNDLER_PROGRESS", value_enum, default_value_t = ProgressFormat::Human)]
+    pub progress: ProgressFormat,
+
+    /// How to print the error that stopped a command: `human`, or `json` for a single line of JSON on stderr with its
+    /// class, exit code, message, and causes.
+    #[arg(long, global = true, env = "JUNDLER_ERROR_FORMAT", value_enum, default_value_t = ErrorFormat::Human)]
+    pub error_format: ErrorFormat,
+
+    /// Write everything to a file as well: debug logs, every command that's run and all it prints, steps, and
+    /// warnings. The terminal still only shows what `-v`/`-q` ask for.
+    #[arg(long, global = true, env = "JUNDLER_LOG_FILE", value_name = "PATH")]
+    pub log_file: Option<PathBuf>,
+
+    /// Only print warnings and errors, without any spinners.
+    #[arg(short, long, global = true, env = "JUNDLER_QUIET", default_value_t = false, value_parser = FalseyValueParser::new())]
+    quiet: bool,
+
+    /// Don't ask before doing anything that can't be undone, like cleaning the cache. Without a terminal to ask on,
+    /// those fail unless this is passed.
+    #[arg(short, long, global = true, env = "JUNDLER_YES", default_value_t = false, value_parser = FalseyValueParser::new())]
+    yes: bool,
+}
+
+impl Cli {
+    /// Gets the action that's currently being performed, as a human-readable string.
+    pub fn action(&self) -> &str {
+        match &self.action {
+            Action::Clean { .. } => "Cleaning",
+            Action::Build { dry_run: true, .. }
+            | Action::Build {
+                plan_out: Some(_), ..
+            } => "Planning the build",
+            Action::Build { .. } => "Building",
+            Action::Watch { .. } => "Watching",
+            Action::Run { .. } => "Building",
+            Action::Test { .. } => "Testing",
+            Action::Doctor { .. } => "Checking your environment",
+            Action::Migrate { .. } => "Migrating",
+            Action::Inspect { .. } => "Inspecting",
+            Action::Diff { .. } => "Comparing builds",
+            Action::Ci { .. } => "Setting up CI",
+            Action::Config { .. } => "Resolving the configuration",
+            Action::Fetch { .. } => "Fetching",
+            Action::Cache { .. } => "Checking the cache",
+            Action::Audit { .. } => "Auditing the cache",
+        }
+    }
+
+    /// Gets how much to print, from the `-v` and `-q` flags.
+    pub fn verbosity(&self) -> Verbosity {
+        if self.json_only() {
+            Verbosity::Silent
+        } else {
+            Verbosity::from_flags(self.verbose, self.quiet)
+        }
+    }
+
+    /// Gets what Node.js and everything else are downloaded with: through the cache shared between machines if there is
+    /// one, or else straight from the internet.
+    fn downloader(&self) -> Arc<dyn Downloader> {
+        match self.shared_cache() {
+            Some(shared_cache) => Arc::new(StorageDownloader::new(
+                storage::open(&shared_cache),
+                Arc::new(HttpDownloader),
+            )),
+            None => Arc::new(HttpDownloader),
+        }
+    }
+
+    /// Gets the cache of downloads shared between machines, if there is one: from `--shared-cache` (or
+    /// `JUNDLER_SHARED_CACHE`), then the project's configuration.
+    fn shared_cache(&self) -> Option<String> {
+        self.shared_cache.clone().or_else(|| {
+            let project_dir = self.config_dir()?;
+            let (_, package_config) = get_configs(project_dir).ok()?;
+
+            // Directories in the configuration are relative to the project, like the cache directory
+            package_config.jundler.shared_cache.map(|shared_cache| {
+                if shared_cache.contains("://") {
+                    shared_cache
+                } else {
+                    project_dir.join(shared_cache).display().to_string()
+                }
+            })
+        })
+    }
+
+    /// Gets the directory of the project whose configuration is used for everything that isn't specific to one build,
+    /// like the cache directory. Only local projects are used, and only when there's one of them.
+    fn config_dir(&self) -> Option<&Path> {
+        match &self.action {
+            Action::Build { args, .. }
+            | Action::Watch { args }
+            | Action::Run { args, .. }
+            | Action::Test { args, .. }
+            | Action::Config {
+                action: ConfigAction::Show { args, .. },
+            } => args.local_project_dir(),
+            Action::Fetch { project_dir, .. } | Action::Doctor { project_dir } => Some(project_dir),
+            _ => Some(Path::new(".")),
+        }
+    }
+
+    /// Whether a JSON report or progress events are printed to stdout, in which case nothing else should be.
+    pub fn json(&self) -> bool {
+        self.progress == ProgressFormat::Json
+            || matches!(
+                self.action,
+                Action::Build { json: true, .. }
+                    | Action::Diff { json: true, .. }
+                    | Action::Audit { json: true }
+                    | Action::Config {
+                        action: ConfigAction::Show { json: true, .. }
+                    }
+            )
+    }
+
+    /// Whether the only output is a single JSON document on stdout, with `--quiet --json`: the report (or settings) if
+    /// the command succeeds, or the error if it doesn't.
+    pub fn json_only(&self) -> bool {
+        self.quiet
+            && matches!(
+                self.action,
+                Action::Build { json: true, .. }
+                    | Action::Diff { json: true, .. }
+                    | Action::Audit { json: true }
+                    | Action::Config {
+                        action: ConfigAction::Show { json: true, .. }
+                    }
+            )
+    }
+
+    /// Whether the build is only planned, not run.
+    fn dry_run(&self) -> bool {
+        matches!(
+            self.action,
+            Action::Build { dry_run: true, .. }
+                | Action::Build {
+                    plan_out: Some(_),
+                    ..
+                }
+        )
+    }
+
+    /// Whether the build is planned into a plan file, or built from one. Those builds are always frozen, so the plan
+    /// pins the checksums of the binaries they use.
+    fn plan_file(&self) -> bool {
+        matches!(
+            self.action,
+            Action::Build {
+                plan_out: Some(_),
+                ..
+            } | Action::Build {
+                plan_in: Some(_),
+                ..
+            }
+        )
+    }
+
+    /// Prints a status line to stdout, or stderr if stdout is reserved for JSON. Nothing is printed when quiet.
+    fn status(&self, message: String) {
+        if self.quiet {
+            return;
+        }
+
+        if self.json() {
+            eprintln!("{}", text(&message));
+        } else {
+            println!("{}", text(&message));
+        }
+    }
+
+    /// Asks whether to go ahead with something that can't be undone, unless `--yes` was passed. As there's no one to
+    /// ask without a terminal, that's an error instead.
+    fn confirm(&self, question: &str) -> Result<bool> {
+        if self.yes {
+            return Ok(true);
+        }
+
+        let term = Term::stderr();
+
+        if !term.is_term() || !io::stdin().is_terminal() {
+            return Err(anyhow!(
+                "{} Can't ask without a terminal, so pass `--yes` to go ahead.",
+                question
+            ))
+            .classify(ErrorClass::Config);
+        }
+
+        term.write_str(&text(&format!("❓ {} [y/N] ", question)))?;
+
+        let answer = term.read_line()?;
+
+        Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+    }
+
+    /// Gets the options for a build, working out the version of Node.js from the project if it isn't given.
+    fn build_options(
+        &self,
+        builder: &Builder,
+        args: &BuildArgs,
+        project: &Project,
+    ) -> Result<BuildOptions> {
+        let node_version = match &args.node_version {
+            Some(node_version) => node_version.clone(),
+
+            None => {
+                // Dry runs don't download anything, so they can't look up the release index either
+                let (node_version, source) = if args.frozen || self.dry_run() || self.plan_file() {
+                    resolve_locked_node_version(&project.dir)?
+                } else {
+                    resolve_node_version(&project.dir, builder.downloader())?
+                };
+
+                self.status(format!(
+                    "📌 Using Node.js v{} from {}",
+                    node_version, source
+                ));
+
+                node_version
+            }
+        };
+
+        let mut options = args.options(node_version)?;
+
+        if self.plan_file() {
+            options.frozen = true;
+        }
+
+        // Projects cloned from git are built in the cache, so put the outputs here instead
+        if project.cloned {
+            options.output_dir =
+                Some(env::current_dir().context("Could not get the current directory!")?);
+        }
+
+        Ok(options)
+    }
+
+    /// Runs the command-line interface for `dotbak` based on the user's input.
+    pub fn run(&self) -> Result<()> {
+        let started = Instant::now();
+
+        // The cache path is printed on its own, so it can be used in scripts, i.e. for CI cache keys
+        if let Action::Cache {
+            action: CacheAction::Path,
+        } = &self.action
+        {
+            println!("{}", self.resolve_cache_dir()?.0.display());
+
+            return Ok(());
+        }
+
+        // So is the schema, so it can be redirected straight to a file
+        if let Action::Config {
+            action: ConfigAction::Schema { file },
+        } = &self.action
+        {
+            println!("{}", serde_json::to_string_pretty(&schema::schema(*file))?);
+
+            return Ok(());
+        }
+
+        self.status(format!("⏳ {}...", self.action()));
+
+        // The doctor runs without a builder, as it checks everything the builder needs to be created
+        if let Action::Doctor { project_dir } = &self.action {
+            return actions::doctor(self.cache_dir().as_deref(), project_dir);
+        }
+
+        // Inspecting only reads the binary
+        if let Action::Inspect { binary } = &self.action {
+            print_inspection(binary, &metadata::inspect(binary)?);
+
+            return Ok(());
+        }
+
+        // Comparing builds only reads their reports or binaries
+        if let Action::Diff { old, new, json } = &self.action {
+            return actions::diff(old, new, *json);
+        }
+
+        // Serving the cache only needs the cache directory, and runs until it's stopped
+        if let Action::Cache {
+            action: CacheAction::Serve { listen },
+        } = &self.action
+        {
+            return self.serve_cache(listen);
+        }
+
+        // Migrating only writes configuration, so it doesn't need a builder either
+        if let Action::Migrate {
+            from,
+            project_dir,
+            force,
+        } = &self.action
+        {
+            return actions::migrate(project_dir, *from, *force);
+        }
+
+        if let Action::Ci {
+            action:
+                CiAction::Init {
+                    release,
+                    project_dir,
+                    force,
+                    ..
+                },
+        } = &self.action
+        {
+            return self.init_ci(project_dir, *release, *force);
+        }
+
+        // Showing the configuration only reads it
+        if let Action::Config {
+            action: ConfigAction::Show { args, json },
+        } = &self.action
+        {
+            return self.show_config(args, *json);
+        }
+
+        let mut reporter = if self.json() {
+            TerminalReporter::new_stderr(MAX_MSG_LEN)
+        } else {
+            TerminalReporter::new(MAX_MSG_LEN)
+        };
+
+        reporter.set_verbosity(self.verbosity());
+
+        let reporter = Arc::new(reporter);
+        let (cache_dir, cache_warning) = self.resolve_cache_dir()?;
+        let mut builder = Builder::with_reporter(cache_dir, reporter.clone())?;
+
+        builder.set_downloader(self.downloader());
+
+        if !self.quiet {
+            builder
+                .tracker
+                .warn("This is experimental and may not work as expected.");
+            builder.tracker.warn("Submit an issue at https://github.com/cogsandsquigs/jundler if you encounter any problems.");
+        }
+
+        // The notices above are shown on every run, so they're left out of the recap of warnings at the end.
+        let notices = builder.tracker.recording().warnings.len();
+
+        if let Some(warning) = cache_warning {
+            builder.tracker.warn(warning);
+        }
+
+        // The built application to run after everything is done, if any.
+        let mut app_to_run = None;
+
+        // The test suite to run against the built application after everything is done, if any.
+        let mut tests_to_run = None;
+
+        // Run the action.
+        match &self.action {
+            Action::Build {
+                args,
+                json,
+                plan_out,
+                ..
+            } if self.dry_run() => self.plan(&mut builder, args, *json, plan_out.as_deref())?,
+
+            Action::Build {
+                args,
+                report,
+                json,
+                plan_in,
+                ..
+            } => {
+                let outputs = self.build(&mut builder, &reporter, args, plan_in.as_deref())?;
+
+                if report.is_some() || *json {
+                    actions::report(
+                        &builder,
+                        &outputs,
+                        report.as_deref(),
+                        *json,
+                        started.elapsed(),
+                    )?;
+                }
+            }
+
+            Action::Watch { args } => self.watch(&mut builder, args)?,
+
+            Action::Run { args, app_args } => {
+                let (output_dir, app_path) = self.build_to_run(&mut builder, args)?;
+
+                app_to_run = Some((output_dir, app_path, app_args));
+            }
+
+            Action::Test { args, command } => {
+                tests_to_run = Some(self.build_to_test(&mut builder, &reporter, args, command)?);
+            }
+
+            Action::Clean {
+                scope,
+                project_dir,
+                dry_run,
+            } => self.clean(&mut builder, *scope, project_dir, *dry_run)?,
+
+            Action::Fetch {
+                node_version,
+                targets,
+                project_dir,
+            } => self.fetch(&mut builder, node_version.as_ref(), targets, project_dir)?,
+
+            Action::Cache {
+                action: CacheAction::Stats,
+            } => print_cache_stats(&builder.cache_stats()?),
+
+            Action::Cache {
+                action: CacheAction::Verify,
+            } => actions::verify_cache(&builder)?,
+
+            Action::Audit { json } => actions::audit(&builder, *json)?,
+
+            Action::Cache {
+                action: CacheAction::Path,
+            } => unreachable!("The cache path is printed without a builder!"),
+
+            Action::Cache {
+                action: CacheAction::Serve { .. },
+            } => unreachable!("The cache is served without a builder!"),
+
+            Action::Doctor { .. } => unreachable!("The doctor runs without a builder!"),
+
+            Action::Inspect { .. } => unreachable!("Binaries are inspected without a builder!"),
+
+            Action::Diff { .. } => unreachable!("Builds are compared without a builder!"),
+
+            Action::Migrate { .. } => unreachable!("Migrations run without a builder!"),
+
+            Action::Ci { .. } => unreachable!("CI is set up without a builder!"),
+
+            Action::Config { .. } => unreachable!("The configuration is shown without a builder!"),
+        }
+
+        if matches!(
+            self.action,
+            Action::Build { .. } | Action::Run { .. } | Action::Test { .. }
+        ) && !self.dry_run()
+        {
+            reporter.print_timings(&builder.tracker.recording(), started.elapsed());
+        }
+
+        self.status(format!(
+            "✨ Done! {}",
+            console::style(format!("[{}]", HumanDuration(started.elapsed())))
+                .bold()
+                .dim(),
+        ));
+
+        reporter.print_warnings(&builder.tracker.recording().warnings[notices..]);
+
+        if let Some((output_dir, app_path, app_args)) = app_to_run {
+            self.status(RUN_APP_MSG.to_string());
+
+            // Stdio is inherited, so the application's output is streamed straight through.
+            let status = Command::new(&app_path)
+                .args(app_args)
+                .status()
+                .context(format!("Error running {}", app_path.display()))?;
+
+            // `process::exit` doesn't run destructors, so clean up the temporary directory first.
+            drop(output_dir);
+
+            // Exit with the same code as the application
+            if !status.success() {
+                process::exit(status.code().unwrap_or(1));
+            }
+        }
+
+        if let Some((project_dir, command, app_path)) = tests_to_run {
+            self.status(format!("{}: {}", TEST_SUITE_MSG, command));
+
+            let status = run_tests(&project_dir, &command, &app_path)?;
+
+            // Exit with the same code as the test suite, so CI fails with it
+            if !status.success() {
+                process::exit(status.code().unwrap_or(1));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// An enum of actions to perform.
+#[derive(Subcommand, Debug)]
+pub enum Action {
+    /// Build the project.
+    Build {
+        #[command(flatten)]
+        args: BuildArgs,
+
+        /// Write a JSON report of the build (outputs, checksums, versions, step durations, and warnings) to a file.
+        #[arg(long, env = "JUNDLER_REPORT", value_name = "PATH")]
+        report: Option<PathBuf>,
+
+        /// Print a JSON report of the build to stdout. Everything else is printed to stderr instead, and with
+        /// `--quiet`, nothing else is printed at all: if the build fails, the error is printed to stdout as JSON
+        /// instead of the report.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+
+        /// Print what the build would do (what would be downloaded, which steps would run, and where the binary would
+        /// go) without building anything. Nothing is downloaded, so the version of Node.js has to be exact, pinned in
+        /// `jundler.lock`, or passed with `--node-version`. With `--json`, the plan is printed as JSON.
+        #[arg(long, default_value_t = false, conflicts_with = "report")]
+        dry_run: bool,
+
+        /// Write the plan of the build (the versions of Node.js and everything else, where binaries are downloaded
+        /// from, the steps, the outputs, and every setting) to a file as JSON without building anything, so it can be
+        /// reviewed before it's built with `--plan-in`. Planned builds are frozen, so the checksums of the binaries
+        /// they use have to be pinned in `jundler.lock` (with `jundler fetch`).
+        #[arg(
+            long,
+            env = "JUNDLER_PLAN_OUT",
+            value_name = "PATH",
+            conflicts_with_all = ["report", "dry_run", "plan_in"]
+        )]
+        plan_out: Option<PathBuf>,
+
+        /// Build the plan in a file written with `--plan-out`. Each project is only built if its build would still do
+        /// exactly what the plan says, with the same versions, URLs, checksums, project inputs, and settings; the
+        /// build fails, saying what changed, otherwise. Builds are frozen, like with `--frozen`.
+        #[arg(
+            long,
+            env = "JUNDLER_PLAN_IN",
+            value_name = "PATH",
+            conflicts_with = "dry_run"
+        )]
+        plan_in: Option<PathBuf>,
+    },
+
+    /// Watch the project for changes, and rebuild it whenever something changes.
+    Watch {
+        #[command(flatten)]
+        args: BuildArgs,
+    },
+
+    /// Build the project for this machine and run it straight away. Any arguments after `--` are passed on to the
+    /// application.
+    Run {
+        #[command(flatten)]
+        args: BuildArgs,
+
+        /// The arguments to pass to the application.
+        #[arg(last = true)]
+        app_args: Vec<String>,
+    },
+
+    /// Build the project for this machine, then run its test suite against the built binary, whose path is in
+    /// `JUNDLER_BINARY`. The test command is the one after `--`, or `test` in the project's configuration, or its
+    /// `test` script.
+    Test {
+        #[command(flatten)]
+        args: BuildArgs,
+
+        /// The command to test the binary with, i.e. `bats test/`.
+        #[arg(last = true)]
+        command: Vec<String>,
+    },
+
+    /// Clean the cache, or only part of it, or the build outputs in a project.
+    Clean {
+        /// What to clean: `node` for the Node.js binaries in the cache, `esbuild` for ESBuild, `deps` for the cached
+        /// dependencies of projects, or `project` for the built binaries (and their archives, packages, checksums, and
+        /// signatures) in the project. Everything in the cache by default.
+        scope: Option<CleanScope>,
+
+        /// The path to the directory of the project to clean, for `project`.
+        #[clap(default_value = ".")]
+        project_dir: PathBuf,
+
+        /// List what would be removed, and how much space it takes up, without removing anything.
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Download the Node.js binaries (for this machine and each target) and ESBuild into the cache without building
+    /// anything, i.e. to warm up a CI cache or a Docker image layer before the build. Their checksums are pinned in the
+    /// project's `jundler.lock`, for `--frozen` builds.
+    Fetch {
+        /// The version of Node.js to download. By default, this is read from the project's `.nvmrc` or
+        /// `engines.node`, or is the latest LTS release.
+        #[arg(short, long, env = "JUNDLER_NODE_VERSION")]
+        node_version: Option<Version>,
+
+        /// The targets to download Node.js for, i.e. `linux-x64,darwin-arm64`. By default, these are the project's
+        /// `jundler.targets`, or this machine.
+        #[arg(short, long, env = "JUNDLER_TARGETS", value_delimiter = ',')]
+        targets: Vec<Target>,
+
+        /// The path to the directory of the project to download for.
+        #[clap(default_value = ".")]
+        project_dir: PathBuf,
+    },
+
+    /// Show where the cache is, and what's in it.
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+
+    /// List every cached Node.js and ESBuild binary with where it was downloaded from, its checksum, and when it was
+    /// downloaded, and check the cached versions of Node.js against the vulnerabilities published by the Node.js
+    /// security working group. Fails if any of them are vulnerable.
+    Audit {
+        /// Print the cached binaries as JSON to stdout instead, with everything else on stderr.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// Check that everything a build needs is available, and suggest fixes for anything that isn't.
+    Doctor {
+        /// The path to the directory of the project to check for.
+        #[clap(default_value = ".")]
+        project_dir: PathBuf,
+    },
+
+    /// Show how a built binary was built: the version of Node.js, when it was built, the hash of its bundle, and the
+    /// platform it's for.
+    Inspect {
+        /// The path to the built binary.
+        binary: PathBuf,
+    },
+
+    /// Compare two builds: how much the binary grew or shrank, what changed in the bundle, and which versions of
+    /// Node.js, ESBuild, and bundled packages changed. Builds are JSON reports from `jundler build --report`, which
+    /// are compared target by target, or built binaries, which only record their size, versions, and bundle hash.
+    Diff {
+        /// The build to compare against, i.e. from the base branch: a build report, or a binary.
+        old: PathBuf,
+
+        /// The build to compare: a build report, or a binary.
+        new: PathBuf,
+
+        /// Print the differences as JSON to stdout instead, with everything else on stderr.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// Set up continuous integration for the project.
+    Ci {
+        #[command(subcommand)]
+        action: CiAction,
+    },
+
+    /// Show the configuration builds of the project use, merged from the command line, `JUNDLER_*` environment
+    /// variables, `jundler.toml` (or `package.json`), and the defaults, along with where each value came from.
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
+    /// Migrate a project from another tool, writing its `sea-config.json` and Jundler configuration.
+    Migrate {
+        /// The tool the project is built with now.
+        #[arg(long)]
+        from: MigrateFrom,
+
+        /// The path to the directory of the project to migrate.
+        #[clap(default_value = ".")]
+        project_dir: PathBuf,
+
+        /// Overwrite the project's `sea-config.json` and `jundler.toml` if it already has them.
+        #[arg(short, long, default_value_t = false)]
+        force: bool,
+    },
+}
+
+/// Actions for the cache.
+#[derive(Subcommand, Debug)]
+pub enum CacheAction {
+    /// Print the path to the cache directory, and nothing else.
+    Path,
+
+    /// Print how much space the cache takes up: the cached Node.js and ESBuild binaries with their sizes and when
+    /// they were last used, and the cached builds and projects.
+    Stats,
+
+    /// Check every cached Node.js and ESBuild binary against the checksum it was cached with, to find any that were
+    /// corrupted on disk. Fails if any were.
+    Verify,
+
+    /// Serve downloads to a team over HTTP, so each Node.js archive, ESBuild package, and runtime is only downloaded
+    /// from the internet once. Builds use the server with `--shared-cache http://<this machine>:<port>`. Anything
+    /// the server doesn't have yet, it downloads and keeps in the `shared` folder of the cache.
+    Serve {
+        /// The address to listen on. Use `0.0.0.0:<port>` to serve other machines.
+        #[arg(long, env = "JUNDLER_LISTEN", default_value = "127.0.0.1:8080")]
+        listen: SocketAddr,
+    },
+}
+
+/// Actions for continuous integration.
+#[derive(Subcommand, Debug)]
+pub enum CiAction {
+    /// Write a workflow that builds release archives of the project for each of its targets (from `jundler.targets`
+    /// in `package.json`) whenever a version tag is pushed.
+    Init {
+        /// Write a GitHub Actions workflow, to `.github/workflows/release.yml`.
+        #[arg(long, required = true)]
+        github: bool,
+
+        /// Also publish the archives, with their checksums, as a GitHub release of the tag.
+        #[arg(long, default_value_t = false)]
+        release: bool,
+
+        /// The path to the directory of the project.
+        #[clap(default_value = ".")]
+        project_dir: PathBuf,
+
+        /// Overwrite the workflow if it already exists.
+        #[arg(short, long, default_value_t = false)]
+        force: bool,
+    },
+}
+
+/// Actions for the configuration.
+#[derive(Subcommand, Debug)]
+#[allow(clippy::large_enum_variant)] // Parsed once, so its size doesn't matter
+pub enum ConfigAction {
+    /// Print the value of every setting a build with these options would use, and where it came from.
+    Show {
+        #[command(flatten)]
+        args: BuildArgs,
+
+        /// Print the settings as JSON to stdout instead, with everything else on stderr.
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// Print the JSON Schema of a configuration file, for editors to autocomplete it and CI to validate it.
+    Schema {
+        /// The file to print the schema of: `jundler` for `jundler.toml`, `package-json` for the `jundler` field of
+        /// `package.json`, or `sea-config`.
+        #[arg(value_enum, default_value_t = SchemaFile::Jundler)]
+        file: SchemaFile,
+    },
+}
+
+/// Arguments for building a project.
+#[derive(Args, Debug)]
+pub struct BuildArgs {
+    /// The path to the directory where the project to build is located. Note that the output binary will be
+    /// placed in this directory as well. Can also be a single JavaScript or TypeScript file without a `package.json`,
+    /// which is bundled into a binary named after it, next to it, or the URL of a git repository to clone and build
+    /// into the current directory, i.e. `https://github.com/org/repo#v1.2.3` for the `v1.2.3` tag. Several projects
+    /// (or a glob like `packages/*`, which matches every directory with a `package.json`) can be built at once,
+    /// sharing the Node.js and ESBuild downloads.
+    #[clap(default_value = ".")]
+    project_dirs: Vec<PathBuf>,
+
+    /// Build a package from the npm registry instead of a project, i.e. `--package cowsay@1.6.0`. The package is
+    /// downloaded and bundled into a binary named after its command (its `bin` entry), in the current directory.
+    #[arg(long, env = "JUNDLER_PACKAGE", value_name = "SPEC")]
+    package: Option<String>,
+
+    /// The version of Node.js you want to bundle with your application. Note that there should not be any "v" prefix.
+    /// By default, this is read from the project's `.nvmrc` or `engines.node`, or is the latest LTS release.
+    #[arg(short, long, env = "JUNDLER_NODE_VERSION")]
+    node_version: Option<Version>,
+
+    /// The platform you're building for, i.e. `linux`, `darwin`, or `win`. This machine's by default.
+    #[arg(short, long, env = "JUNDLER_OS")]
+    os: Option<Os>,
+
+    /// The architecture you're building for, i.e. `x64`, `x86`, or `arm64`. This machine's by default.
+    #[arg(short, long, env = "JUNDLER_ARCH")]
+    arch: Option<Arch>,
+
+    /// The platform and architecture you're building for together, instead of `--os` and `--arch`, i.e. `linux-x64`,
+    /// `darwin-arm64`, or a Rust-style triple like `x86_64-pc-windows-msvc`.
+    #[arg(short, long, env = "JUNDLER_TARGET", conflicts_with_all = ["os", "arch"])]
+    target: Option<Target>,
+
+    /// Bundle the project into a single JS file instead of just compiling the `sea-config.json` main entrypoint. This
+    /// will also bundle the Node.js runtime.
+    #[arg(short, long, env = "JUNDLER_BUNDLE", default_value_t = false, value_parser = FalseyValueParser::new())]
+    bundle: bool,
+
+    /// How to copy the project into the build directory. `auto` uses copy-on-write reflinks where the filesystem
+    /// supports them, and `hardlink` links files instead of copying them.
+    #[arg(long, env = "JUNDLER_COPY_STRATEGY", default_value_t = CopyStrategy::default())]
+    copy_strategy: CopyStrategy,
+
+    /// Rebuild everything, even if nothing has changed since the last build.
+    #[arg(short, long, env = "JUNDLER_FORCE", default_value_t = false, value_parser = FalseyValueParser::new())]
+    force: bool,
+
+    /// The package manager to install dependencies with. By default, this is detected from the project's lockfile.
+    #[arg(long, env = "JUNDLER_PACKAGE_MANAGER")]
+    package_manager: Option<PackageManager>,
+
+    /// The runtime to build the binary with. `bun` and `deno` compile the project with `bun build --compile` and
+    /// `deno compile` instead of Node.js's single executable applications, and download the runtime like Node.js.
+    #[arg(long, value_enum, env = "JUNDLER_RUNTIME", default_value_t = Runtime::Node)]
+    runtime: Runtime,
+
+    /// Strip symbols from the Node.js binary before injecting the application, which usually saves 10-20 MB. Only
+    /// applies to Linux and macOS targets, and needs `llvm-strip` (or `strip` when building for the host OS).
+    #[arg(long, env = "JUNDLER_STRIP", default_value_t = false, value_parser = FalseyValueParser::new())]
+    strip: bool,
+
+    /// Build a universal macOS binary that runs natively on both Intel and Apple Silicon Macs. Only works with
+    /// `--os macos`, and overrides `--arch`. Needs `lipo` (or `llvm-lipo` on other platforms).
+    #[arg(long, env = "JUNDLER_UNIVERSAL", default_value_t = false, value_parser = FalseyValueParser::new())]
+    universal: bool,
+
+    /// Make the build reproducible, so that building the same project twice gives bit-identical binaries.
+    /// Dependencies are installed strictly from the lockfile, and every timestamp is set to `SOURCE_DATE_EPOCH` (or
+    /// 1980-01-01 if it isn't set). Setting `SOURCE_DATE_EPOCH` alone doesn't make the build reproducible.
+    #[arg(long, env = "JUNDLER_REPRODUCIBLE", default_value_t = false, value_parser = FalseyValueParser::new())]
+    reproducible: bool,
+
+    /// Build without changing Jundler's cache, i.e. for hermetic CI. Fails if anything would have to be downloaded,
+    /// or if a Node.js or ESBuild binary isn't pinned in the project's `jundler.lock` (or doesn't match it), so run
+    /// `jundler fetch` first. Dependencies are installed strictly from the lockfile.
+    #[arg(long, env = "JUNDLER_FROZEN", default_value_t = false, value_parser = FalseyValueParser::new())]
+    frozen: bool,
+
+    /// Pass these variables from the environment through to the commands the build runs, i.e. `NPM_TOKEN` for a
+    /// private registry. Those commands (npm, ESBuild, Node.js, `postject`, and `signtool`) otherwise only get what
+    /// they need to run, like `PATH`, `HOME`, and proxy settings, so secrets don't leak into install scripts. Names
+    /// ending in `*` pass every variable with that prefix, i.e. `npm_config_*`.
+    #[arg(
+        long,
+        env = "JUNDLER_PASS_ENV",
+        value_name = "VAR",
+        value_delimiter = ','
+    )]
+    pass_env: Vec<String>,
+
+    /// The `.npmrc` to install dependencies with instead of your own (`~/.npmrc`), i.e. one with the registries and
+    /// auth tokens that CI uses. The project's `.npmrc` (or the nearest one above it, in workspaces) is used either way,
+    /// and the variables these files interpolate, like `${NPM_TOKEN}`, are passed through to the install.
+    #[arg(long, env = "JUNDLER_NPMRC", value_name = "PATH")]
+    npmrc: Option<PathBuf>,
+
+    /// What to do if the version of Node.js has published vulnerabilities: `warn` about them and build anyways, `fail`
+    /// the build, or don't check at all (`off`). The vulnerability database is cached for a day, and frozen builds only
+    /// use the cached copy. Either way, the warning or error names the first release that fixed them.
+    #[arg(long, env = "JUNDLER_NODE_ADVISORIES", value_name = "POLICY", default_value_t = AdvisoryPolicy::default())]
+    node_advisories: AdvisoryPolicy,
+
+    /// When building for another architecture, rebuild native dependencies that don't publish prebuilt addons for it
+    /// with `node-gyp`, against the headers of the version of Node.js. Works for Linux and macOS targets, on a host with
+    /// the same OS. Needs Python and a C++ toolchain that can build for the target, see `--native-toolchain`.
+    #[arg(long, env = "JUNDLER_REBUILD_NATIVE", default_value_t = false, value_parser = FalseyValueParser::new())]
+    rebuild_native: bool,
+
+    /// The prefix of the cross toolchain to rebuild native dependencies with, i.e. `aarch64-linux-gnu-` for
+    /// `aarch64-linux-gnu-gcc` and friends. Without one, the compilers in `CC` and `CXX` (passed with `--pass-env`) or
+    /// the system's are used.
+    #[arg(
+        long,
+        env = "JUNDLER_NATIVE_TOOLCHAIN",
+        value_name = "PREFIX",
+        requires = "rebuild_native"
+    )]
+    native_toolchain: Option<String>,
+
+    /// Run the built binary once as a smoke test, with the arguments given by `--test-exec=ARGS` (split on whitespace),
+    /// and fail the build if it exits with an error or doesn't exit within a minute. Binaries for another architecture
+    /// are run under emulation: QEMU user mode on Linux, Rosetta 2 on Apple silicon, or Windows on Arm's own. If the
+    /// binary can't run here, the build fails and says why.
+    #[arg(
+        long,
+        env = "JUNDLER_TEST_EXEC",
+        value_name = "ARGS",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "",
+        allow_hyphen_values = true
+    )]
+    test_exec: Option<String>,
+
+    /// Fail the build if the built binary is bigger than this, i.e. `80MB` or `75MiB`, and show what its size is made
+    /// of. Overrides `maxSize` in the project's configuration, which can give each target its own.
+    #[arg(long, env = "JUNDLER_MAX_SIZE", value_name = "SIZE", value_parser = parse_size)]
+    max_size: Option<u64>,
+
+    /// What to do if the built binary is bigger than its maximum size: `fail` the build, or just `warn` about it.
+    #[arg(long, env = "JUNDLER_MAX_SIZE_POLICY", value_name = "POLICY", default_value_t = MaxSizePolicy::default())]
+    max_size_policy: MaxSizePolicy,
+
+    /// Write the SHA256 checksum of the built binary next to it. By default, this adds it to a `SHA256SUMS` file that
+    /// can be checked with `sha256sum -c`. Pick another format with `--checksums=FORMAT`.
+    #[arg(
+        long,
+        env = "JUNDLER_CHECKSUMS",
+        value_name = "FORMAT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "gnu"
+    )]
+    checksums: Option<ChecksumFormat>,
+
+    /// Write the licenses of every dependency included in the application to a `THIRD_PARTY_NOTICES` file next to
+    /// the binary.
+    #[arg(long, env = "JUNDLER_LICENSES", default_value_t = false, value_parser = FalseyValueParser::new())]
+    licenses: bool,
+
+    /// Embed the licenses of every dependency included in the application in the binary, so that running it with
+    /// `--licenses` prints them.
+    #[arg(long, env = "JUNDLER_EMBED_LICENSES", default_value_t = false, value_parser = FalseyValueParser::new())]
+    embed_licenses: bool,
+
+    /// Package the built binary into a release archive named like `<name>-v<version>-<os>-<arch>.tar.gz`, along with
+    /// the project's README and license, and the checksums and licenses if they're written.
+    #[arg(long, env = "JUNDLER_ARCHIVE", value_name = "FORMAT")]
+    archive: Option<ArchiveFormat>,
+
+    /// Write a Homebrew formula (`<name>.rb`) and a Scoop manifest (`<name>.json`) for every release archive in the
+    /// output directory, with their URLs and SHA256 checksums filled in. Build every target into the same directory
+    /// to get complete manifests.
+    #[arg(long, env = "JUNDLER_MANIFESTS", default_value_t = false, requires = "archive", value_parser = FalseyValueParser::new())]
+    manifests: bool,
+
+    /// The URL that release archives are uploaded to, for `--manifests`. `{version}` is replaced with the version,
+    /// i.e. `https://github.com/me/mytool/releases/download/v{version}`.
+    #[arg(long, env = "JUNDLER_RELEASE_URL", value_name = "URL")]
+    release_url: Option<String>,
+
+    /// The identity to codesign macOS binaries with, i.e. "Developer ID Application: ...", so they can be notarized
+    /// and distributed. Signs with the hardened runtime and a secure timestamp. Binaries are ad-hoc signed by default,
+    /// which only lets them run on the machine they were built on.
+    #[arg(long, env = "JUNDLER_CODESIGN_IDENTITY", value_name = "IDENTITY")]
+    codesign_identity: Option<String>,
+
+    /// Notarize macOS binaries with Apple after signing them, so Gatekeeper lets them run on any Mac. Needs a
+    /// `--codesign-identity`, and the credentials in `jundler.macos.notarize` in `package.json`. Can take a few
+    /// minutes.
+    #[arg(long, env = "JUNDLER_NOTARIZE", default_value_t = false, value_parser = FalseyValueParser::new())]
+    notarize: bool,
+
+    /// Don't sign the built binary, even when building for this machine's OS. Useful for quick local builds, machines
+    /// without signing tools, or signing in a separate step. macOS binaries have to be signed before they can run.
+    #[arg(long, env = "JUNDLER_NO_SIGN", default_value_t = false, value_parser = FalseyValueParser::new())]
+    no_sign: bool,
+
+    /// The PFX (or P12) file with the certificate to sign Windows binaries, or macOS binaries built on other OSes
+    /// (with `rcodesign`), with. Its password is read from the `JUNDLER_CERTIFICATE_PASSWORD` environment variable.
+    #[arg(long, env = "JUNDLER_SIGN_CERTIFICATE", value_name = "PATH")]
+    sign_certificate: Option<PathBuf>,
+
+    /// The SHA1 thumbprint of the certificate in the certificate store to sign Windows binaries with.
+    #[arg(long, env = "JUNDLER_SIGN_THUMBPRINT", value_name = "THUMBPRINT")]
+    sign_thumbprint: Option<String>,
+
+    /// The URL of an RFC 3161 timestamp server to timestamp the signatures of Windows binaries with.
+    #[arg(long, env = "JUNDLER_TIMESTAMP_URL", value_name = "URL")]
+    timestamp_url: Option<String>,
+
+    /// The description of Windows binaries in their signature, shown when Windows asks for permission to run them.
+    #[arg(long, env = "JUNDLER_SIGN_DESCRIPTION", value_name = "DESCRIPTION")]
+    sign_description: Option<String>,
+
+    /// Write the SLSA provenance of the outputs next to the binary, as `<binary>.intoto.json`: an in-toto statement of
+    /// the outputs' digests, the git commit and lockfile they were built from, the digests of the Node.js archives,
+    /// and the version of jundler. It's signed along with everything else with `--signatures`.
+    #[arg(long, env = "JUNDLER_PROVENANCE", default_value_t = false, value_parser = FalseyValueParser::new())]
+    provenance: bool,
+
+    /// Write a detached signature of every output (the binary, checksums, and archive) next to it, so users can
+    /// verify their downloads. GPG signatures are written to `<file>.sig`, minisign signatures to `<file>.minisig`,
+    /// and cosign's Sigstore bundles to `<file>.sigstore.json`. Without a key, cosign signs keylessly, with the OIDC
+    /// identity of the CI workflow (or by logging in with a browser).
+    #[arg(long, env = "JUNDLER_SIGNATURES", value_name = "TOOL")]
+    signatures: Option<SignatureFormat>,
+
+    /// The GPG key ID, the path to the minisign secret key, or the cosign key (a path or a KMS URI) to write detached
+    /// signatures with. Its passphrase is read from the `JUNDLER_GPG_PASSPHRASE`, `JUNDLER_MINISIGN_PASSWORD`, or
+    /// `COSIGN_PASSWORD` environment variable.
+    #[arg(
+        long,
+        env = "JUNDLER_SIGNING_KEY",
+        value_name = "KEY",
+        requires = "signatures",
+        hide_env_values = true
+    )]
+    signing_key: Option<String>,
+
+    /// Package the Linux binary into `.deb` or `.rpm` packages that install it to `/usr/bin`, or an AppImage, with the
+    /// name, version, description, license, and author from `package.json`. Can be given more than once, or as a
+    /// comma-separated list. `.rpm` packages need `rpmbuild`, and AppImages need `appimagetool`.
+    #[arg(
+        long,
+        env = "JUNDLER_LINUX_PACKAGE",
+        value_name = "FORMAT",
+        value_delimiter = ','
+    )]
+    linux_package: Vec<LinuxPackageFormat>,
+
+    /// Build an MSI installer for the Windows binary that installs it to Program Files and adds it to the `PATH`,
+    /// configured in the `msi` section of `jundler.toml` (or `jundler.msi` in `package.json`). Needs `wixl` (from
+    /// msitools) or WiX 3.
+    #[arg(long, env = "JUNDLER_MSI", default_value_t = false, value_parser = FalseyValueParser::new())]
+    msi: bool,
+
+    /// Build a container image of the Linux binary and tag it, i.e. `--docker myorg/mytool:1.2.3`. The image is based
+    /// on `gcr.io/distroless/cc-debian12` (or `jundler.docker.baseImage` in `package.json`), and its build context
+    /// is written to `<name>-docker`, so it can be built later if Docker isn't installed.
+    #[arg(long, env = "JUNDLER_DOCKER", value_name = "IMAGE:TAG")]
+    docker: Option<String>,
+
+    /// The template to name the built binary with, so builds for different targets don't overwrite each other, i.e.
+    /// `{name}-{version}-{os}-{arch}{ext}` for `mytool-1.2.3-linux-x64`. Can use `{name}`, `{version}`, `{os}`,
+    /// `{arch}`, and `{ext}` (`.exe` on Windows). Also set by `jundler.outputName` in `package.json`.
+    #[arg(long, env = "JUNDLER_OUTPUT_NAME", value_name = "TEMPLATE")]
+    output_name: Option<String>,
+}
+
+impl BuildArgs {
+    /// Gets the directory of the project to build, if it's one local project (not a glob, a git repository, or a
+    /// package from the npm registry).
+    fn local_project_dir(&self) -> Option<&Path> {
+        match self.project_dirs.as_slice() {
+            [project_dir]
+                if self.package.is_none()
+                    && git_source(project_dir).is_none()
+                    && !is_glob(project_dir) =>
+            {
+                Some(project_dir)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether anything is built from elsewhere: a package from the npm registry, or a git repository.
+    fn is_remote(&self) -> bool {
+        self.package.is_some()
+            || self
+                .project_dirs
+                .iter()
+                .any(|project_dir| git_source(project_dir).is_some())
+    }
+
+    /// Gets the projects to build, with canonicalized directories. Globs are expanded, and git URLs are cloned first.
+    fn projects(&self, builder: &mut Builder) -> Result<Vec<Project>> {
+        if self.frozen && self.is_remote() {
+            return Err(anyhow!(
+                "Frozen builds can't download a package from the npm registry or clone a git repository!"
+            ));
+        }
+
+        // Packages from the npm registry are built from the current directory
+        if self.package.is_some() {
+            return Ok(vec![Project {
+                dir: env::current_dir().context("Could not get the current directory!")?,
+                cloned: false,
+            }]);
+        }
+
+        let mut projects = vec![];
+
+        for project_dir in &self.project_dirs {
+            if let Some(source) = git_source(project_dir) {
+                projects.push(Project {
+                    dir: builder.clone_git(&source)?,
+                    cloned: true,
+                });
+            } else if is_glob(project_dir) {
+                let matches = expand_glob(project_dir)?;
+
+                if matches.is_empty() {
+                    return Err(anyhow!("No projects match {}!", project_dir.display()));
+                }
+
+                projects.extend(
+                    matches
+                        .into_iter()
+                        .map(|dir| Project { dir, cloned: false }),
+                );
+            } else {
+                projects.push(Project {
+                    dir: project_dir.canonicalize().context(format!(
+                        "Invalid project directory, script, or git URL {}!",
+                        project_dir.display()
+                    ))?,
+                    cloned: false,
+                });
+            }
+        }
+
+        Ok(projects)
+    }
+
+    /// Gets the one project to build, for actions that can't build more than one.
+    fn project(&self, builder: &mut Builder) -> Result<Project> {
+        let mut projects = self.projects(builder)?;
+
+        match projects.len() {
+            1 => Ok(projects.remove(0)),
+            _ => Err(anyhow!("Only one project can be watched or run at a time!")),
+        }
+    }
+
+    /// Builds the project, or the package from the npm registry if one is given.
+    fn build(
+        &self,
+        builder: &mut Builder,
+        project_dir: &Path,
+        options: &BuildOptions,
+    ) -> Result<BuildOutput> {
+        match &self.package {
+            Some(spec) => builder.build_package(spec, options),
+            None => builder.build(project_dir, options),
+        }
+    }
+
+    /// Gets the options for the build, with the resolved version of Node.js. Fails if no target is given, and this
+    /// machine's platform isn't supported.
+    fn options(&self, node_version: Version) -> Result<BuildOptions> {
+        let (target_os, target_arch) = match self.target {
+            Some(target) => (target.os, target.arch),
+            None => (
+                self.os.map_or_else(Os::host, Ok)?,
+                self.arch.map_or_else(Arch::host, Ok)?,
+            ),
+        };
+
+        Ok(BuildOptions {
+            node_version,
+            target_os,
+            target_arch,
+            bundle: self.bundle,
+            copy_strategy: self.copy_strategy,
+            force: self.force,
+            output_dir: None,
+            package_manager: self.package_manager,
+            runtime: self.runtime,
+            strip: self.strip,
+            universal: self.universal,
+            reproducible: self.reproducible,
+            checksums: self.checksums,
+            licenses: self.licenses,
+            embed_licenses: self.embed_licenses,
+            archive: self.archive,
+            codesign_identity: self.codesign_identity.clone(),
+            notarize: self.notarize,
+            sign: !self.no_sign,
+            sign_certificate: self.sign_certificate.clone(),
+            windows_sign: WindowsSignOptions {
+                thumbprint: self.sign_thumbprint.clone(),
+                timestamp_url: self.timestamp_url.clone(),
+                description: self.sign_description.clone(),
+            },
+            provenance: self.provenance,
+            signatures: self.signatures,
+            signing_key: self.signing_key.clone(),
+            manifests: self.manifests,
+            release_url: self.release_url.clone(),
+            linux_packages: self.linux_package.clone(),
+            msi: self.msi,
+            docker_image: self.docker.clone(),
+            output_name: self.output_name.clone(),
+            frozen: self.frozen,
+            pass_env: self.pass_env.clone(),
+            npmrc: self.npmrc.clone(),
+            node_advisories: self.node_advisories,
+            rebuild_native: self.rebuild_native,
+            native_toolchain: self.native_toolchain.clone(),
+            test_exec: self
+                .test_exec
+                .as_ref()
+                .map(|args| args.split_whitespace().map(str::to_string).collect()),
+            max_size: self.max_size,
+            max_size_policy: self.max_size_policy,
+        })
+    }
+}
+
+/// A project to build.
+struct Project {
+    /// The canonicalized directory of the project (or path of the script).
+    dir: PathBuf,
+
+    /// Whether the project was cloned from a git repository into the cache.
+    cloned: bool,
+}
+
+/// Gets the git repository to build, if a project is a git URL.
+fn git_source(project_dir: &Path) -> Option<GitSource> {
+    project_dir.to_str().and_then(GitSource::parse)
+}
+
+/// Whether a project path is a glob, like `packages/*`.
+fn is_glob(project_dir: &Path) -> bool {
+    project_dir.to_string_lossy().contains(['*', '?', '[', '{'])
+}
+
+/// Finds the project directories (with a `package.json`) that match a glob, sorted by path.
+fn expand_glob(pattern: &Path) -> Result<Vec<PathBuf>> {
+    // Walk from the part of the path before the first component with a wildcard
+    let base: PathBuf = pattern
+        .components()
+        .take_while(|component| !is_glob(Path::new(component.as_os_str())))
+        .collect();
+    let base = if base.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        base
+    };
+
+    if !base.is_dir() {
+        return Ok(vec![]);
+    }
+
+    let glob = pattern
+        .strip_prefix(&base)
+        .unwrap_or(pattern)
+        .to_string_lossy()
+        .replace('\\', "/");
+
+    let mut overrides = OverrideBuilder::new(&base);
+    overrides
+        .add(&glob)
+        .context(format!("Invalid glob {}", pattern.display()))?;
+    let overrides = overrides.build()?;
+
+    let mut matches = vec![];
+
+    let walker = WalkBuilder::new(&base)
+        .standard_filters(false)
+        .filter_entry(|entry| !matches!(entry.file_name().to_str(), Some("node_modules" | ".git")))
+        .build();
+
+    for entry in walker {
+        let entry = entry?;
+        let path = entry.path();
+
+        if path.join("package.json").is_file() && overrides.matched(path, true).is_whitelist() {
+            matches.push(path.canonicalize()?);
+        }
+    }
+
+    matches.sort();
+
+    Ok(matches)
+}
@@ -0,0 +1,408 @@
+use crate::builder::{
+    audit::AuditedBinary, cache_stats::CacheStats, diff::BuildDiff, metadata::Inspection,
+    plan::BuildPlan, settings::Setting, BuildOutput,
+};
+use crate::ui::{print_line, TerminalReporter};
+use indicatif::HumanBytes;
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::time::SystemTime;
+use time::OffsetDateTime;
+
+/// The most files in a bundle to list the changes of.
+const MAX_LISTED_INPUTS: usize = 10;
+
+/// Prints how builds changed.
+pub(super) fn print_diffs(diffs: &[BuildDiff]) {
+    for diff in diffs {
+        print_line(&format!(
+            "📊 {}: {} → {} {}",
+            diff.target,
+            HumanBytes(diff.old_size),
+            HumanBytes(diff.new_size),
+            console::style(format!("({})", signed_bytes(diff.size_delta()))).dim()
+        ));
+
+        for (name, change) in [
+            ("Runtime", &diff.runtime),
+            ("ESBuild", &diff.esbuild_version),
+        ] {
+            if let Some(change) = change {
+                print_line(&format!(
+                    "   {}: {} → {}",
+                    console::style(name).bold(),
+                    change.old.as_deref().unwrap_or("-"),
+                    change.new.as_deref().unwrap_or("-")
+                ));
+            }
+        }
+
+        if let Some(changed) = diff.bundle_changed {
+            print_line(&format!(
+                "   {}: {}",
+                console::style("Bundle").bold(),
+                if changed { "changed" } else { "unchanged" }
+            ));
+        }
+
+        if !diff.contents_compared {
+            print_line(&format!(
+                "   {}",
+                console::style("Only build reports of bundled builds record what's in the bundle.")
+                    .dim()
+            ));
+
+            continue;
+        }
+
+        if !diff.packages.is_empty() {
+            print_line(&format!("   {}:", console::style("Packages").bold()));
+        }
+
+        for package in &diff.packages {
+            let versions = |versions: &BTreeSet<String>| {
+                versions.iter().cloned().collect::<Vec<_>>().join(", ")
+            };
+
+            print_line(&match (package.old.is_empty(), package.new.is_empty()) {
+                (true, _) => format!("      + {} {}", package.name, versions(&package.new)),
+                (_, true) => format!("      - {} {}", package.name, versions(&package.old)),
+                _ => format!(
+                    "      ~ {} {} → {}",
+                    package.name,
+                    versions(&package.old),
+                    versions(&package.new)
+                ),
+            });
+        }
+
+        if !diff.inputs.is_empty() {
+            print_line(&format!("   {}:", console::style("Bundle changes").bold()));
+        }
+
+        for input in diff.inputs.iter().take(MAX_LISTED_INPUTS) {
+            print_line(&format!(
+                "      {:>10} {}",
+                signed_bytes(input.delta()),
+                input.path
+            ));
+        }
+
+        if diff.inputs.len() > MAX_LISTED_INPUTS {
+            print_line(&format!(
+                "      {}",
+                console::style(format!(
+                    "…and {} more",
+                    diff.inputs.len() - MAX_LISTED_INPUTS
+                ))
+                .dim()
+            ));
+        }
+    }
+}
+
+/// Formats a change in size, with its sign.
+fn signed_bytes(delta: i64) -> String {
+    format!(
+        "{}{}",
+        if delta < 0 { "-" } else { "+" },
+        HumanBytes(delta.unsigned_abs())
+    )
+}
+
+/// Prints what's in the cache, and how much space it takes up.
+pub(super) fn print_cache_stats(stats: &CacheStats) {
+    print_line(&format!(
+        "📁 {} {}",
+        stats.dir.display(),
+        console::style(format!("({})", HumanBytes(stats.total_bytes))).dim()
+    ));
+
+    for (name, binaries) in [("Node.js", &stats.node), ("ESBuild", &stats.esbuild)] {
+        print_line(&format!(
+            "   {}: {}, {}",
+            console::style(name).bold(),
+            count(binaries.len(), "binary", "binaries"),
+            HumanBytes(binaries.iter().map(|binary| binary.bytes).sum())
+        ));
+
+        for binary in binaries {
+            print_line(&format!(
+                "      {:<24} {:>10}{}",
+                binary.name,
+                HumanBytes(binary.bytes).to_string(),
+                console::style(last_used(binary.last_used)).dim()
+            ));
+        }
+    }
+
+    for (name, singular, plural, section) in [
+        (
+            "Builds",
+            "cached SEA blob",
+            "cached SEA blobs",
+            &stats.builds,
+        ),
+        (
+            "Dependencies",
+            "cached install",
+            "cached installs",
+            &stats.deps,
+        ),
+        ("Sources", "project", "projects", &stats.sources),
+    ] {
+        print_line(&format!(
+            "   {}: {}, {}{}",
+            console::style(name).bold(),
+            count(section.entries, singular, plural),
+            HumanBytes(section.bytes),
+            console::style(last_used(section.last_used)).dim()
+        ));
+    }
+}
+
+/// Prints every cached binary with where it came from, and the vulnerabilities of those that have any.
+pub(super) fn print_audit(binaries: &[AuditedBinary]) {
+    if binaries.is_empty() {
+        print_line("🔍 Nothing is cached yet.");
+
+        return;
+    }
+
+    for binary in binaries {
+        let status = if binary.vulnerable() {
+            console::style(count(
+                binary.vulnerabilities.len(),
+                "vulnerability",
+                "vulnerabilities",
+            ))
+            .red()
+            .bold()
+        } else {
+            console::style("no known vulnerabilities".to_string()).green()
+        };
+
+        print_line(&format!(
+            "🔍 {} v{} {} {}",
+            console::style(&binary.tool).bold(),
+            binary.version,
+            binary.target,
+            status
+        ));
+
+        for (name, value) in [
+            ("Origin", Some(binary.origin.clone())),
+            ("Origin SHA256", binary.origin_sha256.clone()),
+            ("Cached SHA256", Some(binary.cached_sha256.clone())),
+            (
+                "Downloaded",
+                binary
+                    .downloaded
+                    .and_then(|time| OffsetDateTime::from_unix_timestamp(time).ok())
+                    .map(|time| time.date().to_string()),
+            ),
+        ] {
+            print_line(&format!(
+                "   {}: {}",
+                console::style(name).bold(),
+                value.as_deref().unwrap_or("unknown")
+            ));
+        }
+
+        for vulnerability in &binary.vulnerabilities {
+            print_line(&format!(
+                "   {} {}{}",
+                console::style("!").red().bold(),
+                if vulnerability.cves.is_empty() {
+                    "No CVE".to_string()
+                } else {
+                    vulnerability.cves.join(", ")
+                },
+                vulnerability
+                    .patched
+                    .as_ref()
+                    .map(|patched| format!(", patched in {}", patched))
+                    .unwrap_or_default()
+            ));
+
+            if let Some(reference) = &vulnerability.reference {
+                print_line(&format!("     {}", console::style(reference).dim()));
+            }
+        }
+    }
+}
+
+/// Prints the settings builds of a project use, lined up, with where each came from.
+pub(super) fn print_settings(project_dir: &Path, settings: &[Setting]) {
+    print_line(&format!("⚙️  {}", project_dir.display()));
+
+    let width = settings
+        .iter()
+        .map(|setting| setting.key.len())
+        .max()
+        .unwrap_or(0);
+
+    for setting in settings {
+        print_line(&format!(
+            "   {:<width$}  {} {}",
+            setting.key,
+            setting.value.as_deref().unwrap_or("-"),
+            console::style(format!("({})", setting.source)).dim(),
+        ));
+    }
+}
+
+/// Prints what a build would do.
+pub(super) fn print_plan(plan: &BuildPlan) {
+    print_line(&format!(
+        "📋 {} {}",
+        plan.project_dir.display(),
+        console::style(format!(
+            "({} v{}, {})",
+            plan.runtime, plan.runtime_version, plan.target
+        ))
+        .dim()
+    ));
+
+    if plan.downloads.is_empty() {
+        print_line(&format!(
+            "   {}: nothing, everything is cached",
+            console::style("Downloads").bold()
+        ));
+    } else {
+        print_line(&format!("   {}:", console::style("Downloads").bold()));
+
+        for download in &plan.downloads {
+            print_line(&format!("      {}", download));
+        }
+    }
+
+    print_line(&format!("   {}:", console::style("Steps").bold()));
+
+    for step in &plan.steps {
+        print_line(&format!("      {}", step));
+    }
+
+    print_line(&format!(
+        "   {}: {}",
+        console::style("Output").bold(),
+        plan.app_path.display()
+    ));
+}
+
+/// Prints what a build wrote, what it was built for, and what the size of the binary is made of.
+pub(super) fn print_output(reporter: &TerminalReporter, output: &BuildOutput) {
+    let target = if output.universal {
+        format!("{}-universal", output.target_os)
+    } else {
+        format!("{}-{}", output.target_os, output.target_arch)
+    };
+
+    reporter.println(format!(
+        "📦 {} {}",
+        output.app_path.display(),
+        console::style(format!(
+            "({}, {} v{}{}, {:.2}s)",
+            target,
+            output.runtime,
+            output
+                .runtime
+                .version()
+                .unwrap_or_else(|| output.node_version.clone()),
+            if output.cached { ", cached" } else { "" },
+            output.duration.as_secs_f64()
+        ))
+        .dim()
+    ));
+
+    for file in &output.files {
+        reporter.println(format!(
+            "   {} {}",
+            file.path.display(),
+            console::style(format!("({})", HumanBytes(file.size))).dim()
+        ));
+    }
+
+    if let Some(size) = &output.size {
+        reporter.println(format!("   {}:", console::style("Size").bold()));
+
+        for line in size.to_string().lines() {
+            reporter.println(format!("     {}", line));
+        }
+    }
+}
+
+/// Prints what was found in a built binary.
+pub(super) fn print_inspection(binary: &Path, inspection: &Inspection) {
+    print_line(&format!("🔍 {}", binary.display()));
+
+    let fuse = match inspection.fuse {
+        Some(true) => "flipped, so the application runs when the binary does",
+        Some(false) => "not flipped, so this is a plain Node.js binary",
+        None => "not found, so this isn't a Node.js binary",
+    };
+
+    print_line(&format!(
+        "   {}: {}",
+        console::style("SEA fuse").bold(),
+        fuse
+    ));
+
+    let Some(metadata) = &inspection.metadata else {
+        if inspection.fuse == Some(true) {
+            print_line("   No build metadata, so it was built without jundler, or by an older version of it.");
+        }
+
+        return;
+    };
+
+    let built_at = OffsetDateTime::from_unix_timestamp(metadata.built_at)
+        .map(|time| {
+            format!(
+                "{} {:02}:{:02}:{:02} UTC",
+                time.date(),
+                time.hour(),
+                time.minute(),
+                time.second()
+            )
+        })
+        .unwrap_or_else(|_| metadata.built_at.to_string());
+
+    for (name, value) in [
+        (
+            "Application",
+            format!(
+                "{} {}",
+                metadata.name,
+                metadata.version.as_deref().unwrap_or_default()
+            ),
+        ),
+        ("Node.js", format!("v{}", metadata.node_version)),
+        ("Target", metadata.target.clone()),
+        ("Built at", built_at),
+        ("Bundle hash", metadata.bundle_hash.clone()),
+        (
+            "Built with",
+            format!("jundler v{}", metadata.jundler_version),
+        ),
+    ] {
+        print_line(&format!(
+            "   {}: {}",
+            console::style(name).bold(),
+            value.trim()
+        ));
+    }
+}
+
+/// Counts things, i.e. `1 file` or `2 files`.
+pub(super) fn count(count: usize, singular: &str, plural: &str) -> String {
+    format!("{} {}", count, if count == 1 { singular } else { plural })
+}
+
+/// Describes when something in the cache was last used, by date (in UTC), to go after its size.
+fn last_used(time: Option<SystemTime>) -> String {
+    match time {
+        Some(time) => format!("  last used {}", OffsetDateTime::from(time).date()),
+        None => String::new(),
+    }
+}
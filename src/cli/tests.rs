@@ -0,0 +1,430 @@
+#![cfg(test)]
+
+use super::*;
+use crate::builder::downloader::FileDownloader;
+use crate::builder::settings::{Setting, Source};
+use clap::{ArgMatches, Command, CommandFactory, FromArgMatches};
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::iter;
+use tempdir::TempDir;
+
+/// Has the options of `command`, and those of its subcommands, read their environment variables from `vars` instead of
+/// the environment the tests run in, so they neither depend on it nor change it.
+fn with_env(command: Command, vars: &[(&str, &'static str)]) -> Command {
+    command
+        .mut_args(|arg| {
+            let value = vars
+                .iter()
+                .find(|(name, _)| arg.get_env() == Some(OsStr::new(name)))
+                .map(|(_, value)| *value);
+
+            // Clap reads the variables as soon as the options are defined, so their values are given as defaults
+            match value {
+                Some(value) => arg.env(None).default_value(value),
+                None => arg.env(None),
+            }
+        })
+        .mut_subcommands(|subcommand| with_env(subcommand, vars))
+}
+
+/// Parses the arguments after `jundler` in an environment with only `vars` set. Returns the matches as well, which say
+/// where each value came from.
+fn parse_in(args: &[&str], vars: &[(&str, &'static str)]) -> (Cli, ArgMatches) {
+    let matches = with_env(Cli::command(), vars)
+        .try_get_matches_from(iter::once("jundler").chain(args.iter().copied()))
+        .unwrap();
+
+    (Cli::from_arg_matches(&matches).unwrap(), matches)
+}
+
+/// Parses the arguments after `jundler` in an empty environment.
+fn parse(args: &[&str]) -> Cli {
+    parse_in(args, &[]).0
+}
+
+/// Gets the build arguments of `jundler build` or `jundler config show`.
+fn build_args(cli: &Cli) -> &BuildArgs {
+    match &cli.action {
+        Action::Build { args, .. }
+        | Action::Config {
+            action: ConfigAction::Show { args, .. },
+        } => args,
+        action => panic!("{:?} doesn't build anything!", action),
+    }
+}
+
+/// Writes a project with the `jundler` configuration in its `package.json`.
+fn write_project(dir: &Path, jundler: &str) {
+    fs::create_dir_all(dir).unwrap();
+    fs::write(
+        dir.join("package.json"),
+        format!(r#"{{ "name": "app", "jundler": {} }}"#, jundler),
+    )
+    .unwrap();
+    fs::write(
+        dir.join("sea-config.json"),
+        r#"{ "main": "index.js", "output": "sea-prep.blob" }"#,
+    )
+    .unwrap();
+}
+
+/// Test that options are read from their `JUNDLER_*` environment variables, and that the command line overrides them.
+#[test]
+fn env_overrides() {
+    let vars = [
+        ("JUNDLER_NODE_VERSION", "22.3.0"),
+        ("JUNDLER_TARGET", "linux-arm64"),
+        ("JUNDLER_BUNDLE", "true"),
+        ("JUNDLER_PASS_ENV", "NPM_TOKEN,npm_config_*"),
+        ("JUNDLER_CACHE_DIR", "/var/cache/jundler"),
+        ("JUNDLER_QUIET", "1"),
+    ];
+
+    let (cli, _) = parse_in(&["build"], &vars);
+    let args = build_args(&cli);
+
+    assert_eq!(args.node_version, Some(Version::new(22, 3, 0)));
+    assert_eq!(args.target, Some("linux-arm64".parse().unwrap()));
+    assert!(args.bundle);
+    assert_eq!(args.pass_env, ["NPM_TOKEN", "npm_config_*"]);
+    assert_eq!(cli.cache_dir, Some(PathBuf::from("/var/cache/jundler")));
+    assert!(cli.quiet);
+
+    let (cli, _) = parse_in(
+        &[
+            "build",
+            "--node-version",
+            "20.0.0",
+            "--target",
+            "darwin-x64",
+            "--pass-env",
+            "CI",
+        ],
+        &vars,
+    );
+    let args = build_args(&cli);
+
+    assert_eq!(args.node_version, Some(Version::new(20, 0, 0)));
+    assert_eq!(args.target, Some("darwin-x64".parse().unwrap()));
+    assert_eq!(args.pass_env, ["CI"]);
+
+    // Flags are only turned on by truthy values
+    let (cli, _) = parse_in(
+        &["build"],
+        &[("JUNDLER_BUNDLE", "false"), ("JUNDLER_QUIET", "0")],
+    );
+
+    assert!(!build_args(&cli).bundle);
+    assert!(!cli.quiet);
+}
+
+/// Test that `--checksums` only takes a format after `=`, so the project after it isn't mistaken for one.
+#[test]
+fn checksums_require_equals() {
+    let cli = parse(&["build", "--checksums", "./app"]);
+    let args = build_args(&cli);
+
+    assert_eq!(args.checksums, Some(ChecksumFormat::Gnu));
+    assert_eq!(args.project_dirs, [PathBuf::from("./app")]);
+
+    let cli = parse(&["build", "--checksums=sidecar", "./app"]);
+    let args = build_args(&cli);
+
+    assert_eq!(args.checksums, Some(ChecksumFormat::Sidecar));
+    assert_eq!(args.project_dirs, [PathBuf::from("./app")]);
+
+    assert_eq!(build_args(&parse(&["build", "./app"])).checksums, None);
+}
+
+/// Test that `--test-exec` only takes its arguments after `=`, so the project after it isn't mistaken for them.
+#[test]
+fn test_exec_requires_equals() {
+    let cli = parse(&["build", "--test-exec", "./app"]);
+    let args = build_args(&cli);
+
+    assert_eq!(args.test_exec.as_deref(), Some(""));
+    assert_eq!(args.project_dirs, [PathBuf::from("./app")]);
+
+    let cli = parse(&["build", "--test-exec=--version", "./app"]);
+    let args = build_args(&cli);
+
+    assert_eq!(args.test_exec.as_deref(), Some("--version"));
+    assert_eq!(args.project_dirs, [PathBuf::from("./app")]);
+
+    assert_eq!(build_args(&parse(&["build", "./app"])).test_exec, None);
+}
+
+/// Test that `config show` says where each setting came from (the command line, the environment, the project's
+/// configuration, or the defaults), and that with `--json`, its JSON is all that's printed to stdout.
+#[test]
+fn config_show() {
+    let project = TempDir::new("project").unwrap();
+    write_project(project.path(), r#"{ "cacheDir": "cache" }"#);
+
+    let vars = [("JUNDLER_STRIP", "true")];
+    let (cli, matches) = parse_in(
+        &[
+            "config",
+            "show",
+            "--node-version",
+            "22.3.0",
+            "--json",
+            project.path().to_str().unwrap(),
+        ],
+        &vars,
+    );
+
+    assert!(cli.json());
+    assert!(!cli.json_only());
+    assert_eq!(build_args(&cli).local_project_dir(), Some(project.path()));
+
+    let settings = cli
+        .settings(
+            project.path(),
+            build_args(&cli),
+            &matches,
+            vars.map(|(name, value)| (OsString::from(name), OsString::from(value))),
+            &FileDownloader::new(project.path().to_path_buf()),
+        )
+        .unwrap();
+
+    let setting = |key: &str| {
+        settings
+            .iter()
+            .find(|setting| setting.key == key)
+            .unwrap()
+            .clone()
+    };
+
+    assert_eq!(
+        setting("node-version"),
+        Setting::new("node-version", "22.3.0", Source::CommandLine)
+    );
+    assert_eq!(
+        setting("strip"),
+        Setting::new(
+            "strip",
+            true,
+            Source::Environment("JUNDLER_STRIP".to_string())
+        )
+    );
+    assert_eq!(setting("force").source, Source::Default);
+    assert_eq!(
+        setting("cache-dir"),
+        Setting::new(
+            "cache-dir",
+            project.path().join("cache").display(),
+            Source::Config("jundler.cacheDir".to_string(), "package.json")
+        )
+    );
+    assert_eq!(setting("shared-cache").value, None);
+
+    // With `--quiet`, the settings take the place of everything else
+    let cli = parse(&[
+        "config",
+        "show",
+        "--json",
+        "--quiet",
+        project.path().to_str().unwrap(),
+    ]);
+
+    assert!(cli.json_only());
+}
+
+/// Test that `clean --dry-run` only lists what would be removed, and that cleaning with `--yes` removes it without
+/// asking.
+#[test]
+fn clean_dry_run() {
+    let cache_dir = TempDir::new("cache").unwrap();
+    let mut builder = Builder::new(cache_dir.path().to_path_buf()).unwrap();
+    let deps = cache_dir.path().join("deps");
+
+    fs::create_dir_all(deps.join("slot").join("node_modules")).unwrap();
+    fs::write(deps.join("slot.key"), "key").unwrap();
+
+    let cli = parse(&["clean", "deps", "--dry-run"]);
+
+    let Action::Clean {
+        scope,
+        project_dir,
+        dry_run,
+    } = &cli.action
+    else {
+        panic!("{:?} isn't `clean`!", cli.action);
+    };
+
+    assert_eq!((*scope, *dry_run), (Some(CleanScope::Deps), true));
+
+    cli.clean(&mut builder, *scope, project_dir, *dry_run)
+        .unwrap();
+
+    assert!(deps.join("slot.key").exists());
+    assert!(deps.join("slot").join("node_modules").exists());
+
+    parse(&["clean", "deps", "--yes"])
+        .clean(&mut builder, *scope, project_dir, false)
+        .unwrap();
+
+    assert!(!deps.join("slot.key").exists());
+    assert!(!deps.join("slot").exists());
+}
+
+/// Test that the cache directory is the one given, then the project's `cacheDir`, and that if the user's cache
+/// directory can't be written to, a directory of the user's own in the temporary directory is used instead.
+#[test]
+fn cache_dir_resolution() {
+    let project = TempDir::new("project").unwrap();
+    let given = TempDir::new("given").unwrap();
+    write_project(project.path(), r#"{ "cacheDir": "cache" }"#);
+
+    let cli = parse(&[
+        "build",
+        "--cache-dir",
+        given.path().join("jundler").to_str().unwrap(),
+        project.path().to_str().unwrap(),
+    ]);
+
+    assert_eq!(
+        cli.resolve_cache_dir().unwrap(),
+        (given.path().join("jundler"), None)
+    );
+    assert!(given.path().join("jundler").is_dir());
+
+    let cli = parse(&["build", project.path().to_str().unwrap()]);
+
+    assert_eq!(
+        cli.resolve_cache_dir().unwrap(),
+        (project.path().join("cache"), None)
+    );
+
+    let blocked = TempDir::new("blocked").unwrap();
+    let not_a_dir = blocked.path().join("not-a-dir");
+    let project_dir = blocked.path().join("project");
+
+    fs::write(&not_a_dir, "").unwrap();
+    write_project(&project_dir, "{}");
+
+    let cli = parse(&["build", project_dir.to_str().unwrap()]);
+
+    let user_cache_dir = blocked.path().join("user-cache");
+    assert_eq!(
+        cli.resolve_cache_dir_in(Some(user_cache_dir.clone()))
+            .unwrap(),
+        (user_cache_dir, None)
+    );
+
+    let (cache_dir, warning) = cli
+        .resolve_cache_dir_in(Some(not_a_dir.join("jundler")))
+        .unwrap();
+
+    assert_eq!(cache_dir, temp_cache_dir());
+    assert!(warning.unwrap().contains("not-a-dir"));
+    assert!(!project_dir.join(".jundler-cache").exists());
+}
+
+/// Test that the fallback cache directory in the temporary directory is named after the user, created for them
+/// alone, and refused if anyone else can get into it or it's a symlink.
+#[cfg(unix)]
+#[test]
+fn private_cache_dir() {
+    use cache_dir::prepare_private_dir;
+    use std::os::unix::fs::{symlink, PermissionsExt};
+
+    // SAFETY: `geteuid` can't fail, and doesn't touch any memory
+    let uid = unsafe { libc::geteuid() };
+
+    assert_eq!(
+        temp_cache_dir(),
+        env::temp_dir().join(format!("jundler-{}", uid))
+    );
+
+    let tmp = TempDir::new("private").unwrap();
+    let dir = tmp.path().join("jundler");
+
+    prepare_private_dir(&dir).unwrap();
+    assert_eq!(
+        fs::metadata(&dir).unwrap().permissions().mode() & 0o777,
+        0o700
+    );
+
+    // It's used again as long as it's still the user's alone
+    prepare_private_dir(&dir).unwrap();
+
+    fs::set_permissions(&dir, fs::Permissions::from_mode(0o755)).unwrap();
+    assert_eq!(
+        prepare_private_dir(&dir).unwrap_err().kind(),
+        io::ErrorKind::PermissionDenied
+    );
+
+    fs::set_permissions(&dir, fs::Permissions::from_mode(0o700)).unwrap();
+
+    let link = tmp.path().join("link");
+    symlink(&dir, &link).unwrap();
+
+    assert_eq!(
+        prepare_private_dir(&link).unwrap_err().kind(),
+        io::ErrorKind::PermissionDenied
+    );
+}
+
+/// Test that several projects, and globs of them, are built together in order, and that only a single local project
+/// is used for the configuration.
+#[test]
+fn batch_builds() {
+    let root = TempDir::new("batch").unwrap();
+    let packages = root.path().join("packages");
+
+    write_project(&packages.join("b"), "{}");
+    write_project(&packages.join("a"), "{}");
+    write_project(&packages.join("a").join("node_modules").join("dep"), "{}");
+    fs::create_dir_all(packages.join("c")).unwrap();
+
+    let a = packages.join("a").canonicalize().unwrap();
+    let b = packages.join("b").canonicalize().unwrap();
+
+    let cache_dir = TempDir::new("cache").unwrap();
+    let mut builder = Builder::new(cache_dir.path().to_path_buf()).unwrap();
+
+    let dirs = |args: &BuildArgs, builder: &mut Builder| {
+        args.projects(builder)
+            .unwrap()
+            .into_iter()
+            .map(|project| project.dir)
+            .collect::<Vec<_>>()
+    };
+
+    // Globs match every directory with a `package.json`, sorted, but not the packages in `node_modules`
+    let cli = parse(&["build", packages.join("*").to_str().unwrap()]);
+    let args = build_args(&cli);
+
+    assert_eq!(args.local_project_dir(), None);
+    assert_eq!(dirs(args, &mut builder), [a.clone(), b.clone()]);
+    assert!(args.project(&mut builder).is_err());
+
+    // Projects are built in the order they're given
+    let cli = parse(&["build", b.to_str().unwrap(), a.to_str().unwrap()]);
+
+    assert_eq!(build_args(&cli).local_project_dir(), None);
+    assert_eq!(dirs(build_args(&cli), &mut builder), [b.clone(), a.clone()]);
+
+    let cli = parse(&["build", a.to_str().unwrap()]);
+
+    assert_eq!(build_args(&cli).local_project_dir(), Some(a.as_path()));
+    assert_eq!(build_args(&cli).project(&mut builder).unwrap().dir, a);
+
+    let cli = parse(&["build", root.path().join("missing/*").to_str().unwrap()]);
+
+    assert!(build_args(&cli).projects(&mut builder).is_err());
+
+    // Packages and git repositories are remote, and can't be built frozen
+    let cli = parse(&["build", "https://github.com/org/repo#v1.2.3"]);
+
+    assert!(build_args(&cli).is_remote());
+    assert_eq!(build_args(&cli).local_project_dir(), None);
+
+    let cli = parse(&["build", "--package", "cowsay@1.6.0", "--frozen"]);
+
+    assert!(build_args(&cli).is_remote());
+    assert!(build_args(&cli).projects(&mut builder).is_err());
+}
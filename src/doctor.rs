@@ -0,0 +1,314 @@
+use crate::builder::package_manager::PackageManager;
+use crate::builder::platforms::{Arch, Os};
+use crate::cli::temp_cache_dir;
+use crate::ui::plain::text;
+use crate::ui::print_line;
+use console::style;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+use sysinfo::Disks;
+
+/// The URLs that builds need to reach: Node.js downloads, and the npm registry for esbuild and dependencies.
+const REQUIRED_URLS: &[&str] = &[
+    "https://nodejs.org/dist/index.json",
+    "https://registry.npmjs.org/",
+];
+
+/// How long to wait for each URL before giving up on it.
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The least free disk space a build should have. A build needs room for the host and target Node.js binaries (about
+/// 100 MB each, unpacked), the project's dependencies, and the built binary itself.
+const MIN_FREE_SPACE: u64 = 1024 * 1024 * 1024;
+
+/// How bad the result of a check is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// Everything is fine.
+    Ok,
+
+    /// Builds will work, but some features may not.
+    Warning,
+
+    /// Builds will fail.
+    Error,
+}
+
+/// The result of checking a single part of the environment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    /// What was checked.
+    pub name: String,
+
+    /// How the check went.
+    pub status: Status,
+
+    /// What was found.
+    pub detail: String,
+
+    /// How to fix the problem, if there is one.
+    pub fix: Option<String>,
+}
+
+impl Check {
+    /// A check that passed.
+    fn ok(name: impl ToString, detail: impl ToString) -> Check {
+        Check {
+            name: name.to_string(),
+            status: Status::Ok,
+            detail: detail.to_string(),
+            fix: None,
+        }
+    }
+
+    /// A check that found a problem, along with how to fix it.
+    fn problem(
+        name: impl ToString,
+        status: Status,
+        detail: impl ToString,
+        fix: impl ToString,
+    ) -> Check {
+        Check {
+            name: name.to_string(),
+            status,
+            detail: detail.to_string(),
+            fix: Some(fix.to_string()),
+        }
+    }
+
+    /// Prints the check to the terminal, with the fix underneath it if there's a problem.
+    pub fn print(&self) {
+        let icon = match self.status {
+            Status::Ok => style(text("✓")).green(),
+            Status::Warning => style(text("!")).yellow(),
+            Status::Error => style(text("✗")).red(),
+        };
+
+        print_line(&format!(
+            "   {} {}: {}",
+            icon,
+            style(&self.name).bold(),
+            self.detail
+        ));
+
+        if let Some(fix) = &self.fix {
+            print_line(&format!(
+                "      {} {}",
+                style(text("↳")).dim(),
+                style(fix).dim()
+            ));
+        }
+    }
+}
+
+/// Checks everything a build of the project at `project_dir` needs, with the cache at `cache_dir`.
+pub fn run_checks(cache_dir: Option<&Path>, project_dir: &Path) -> Vec<Check> {
+    let mut checks = vec![check_platform(), check_cache_dir(cache_dir)];
+
+    checks.extend(REQUIRED_URLS.iter().map(|url| check_network(url)));
+
+    checks.push(check_tool(
+        "node",
+        &["--version"],
+        Status::Warning,
+        "Optional: jundler downloads its own Node.js, but falls back to the installed one if nodejs.org can't be reached.",
+    ));
+
+    // The tools depend on the host, so there's nothing more to check if it isn't supported
+    if let Ok(host_os) = Os::host() {
+        let package_manager = PackageManager::detect(project_dir);
+
+        // Package managers pinned with `packageManager` are run through Corepack, which fetches them itself
+        let pinned = PackageManager::pinned(project_dir)
+            .is_some_and(|(pinned, _)| pinned == package_manager && pinned.supports_corepack());
+
+        if package_manager != PackageManager::Npm && !pinned {
+            checks.push(check_tool(
+                &package_manager.program(host_os),
+                &["--version"],
+                Status::Error,
+                format!(
+                    "Install {0}, or pass `--package-manager npm` to install dependencies with npm instead.",
+                    package_manager
+                ),
+            ));
+        }
+
+        match host_os {
+            Os::MacOS => checks.push(check_tool(
+                "codesign",
+                &["--help"],
+                Status::Warning,
+                "Install the Xcode command line tools with `xcode-select --install` to codesign macOS binaries.",
+            )),
+
+            Os::Windows => checks.push(check_tool(
+                "signtool",
+                &["/?"],
+                Status::Warning,
+                "Install the Windows SDK to sign Windows binaries.",
+            )),
+
+            Os::Linux => {}
+        }
+
+        if host_os != Os::MacOS {
+            checks.push(check_tool(
+                "rcodesign",
+                &["--version"],
+                Status::Warning,
+                "Optional: install rcodesign (from apple-codesign) to sign and notarize macOS binaries on this OS.",
+            ));
+        }
+    }
+
+    if let Some(cache_dir) = cache_dir {
+        checks.push(check_disk_space("Cache disk space", cache_dir));
+    }
+
+    checks.push(check_disk_space("Build disk space", &std::env::temp_dir()));
+
+    checks
+}
+
+/// Checks that jundler can build on this machine's operating system and architecture.
+fn check_platform() -> Check {
+    const NAME: &str = "Platform";
+
+    match Os::host().and_then(|os| Ok((os, Arch::host()?))) {
+        Ok((os, arch)) => Check::ok(NAME, format!("{}-{}", os, arch)),
+        Err(err) => Check::problem(
+            NAME,
+            Status::Error,
+            err,
+            "Run jundler on Linux, macOS, or Windows, and build for other platforms with `--target`.",
+        ),
+    }
+}
+
+/// Checks that the cache directory exists (or can be created) and is writable.
+fn check_cache_dir(cache_dir: Option<&Path>) -> Check {
+    const NAME: &str = "Cache directory";
+
+    let Some(cache_dir) = cache_dir else {
+        return Check::problem(
+            NAME,
+            Status::Warning,
+            format!(
+                "Could not find a cache directory for this user, so builds cache in {} instead",
+                temp_cache_dir().display()
+            ),
+            "Set `XDG_CACHE_HOME` (Linux), make sure your home directory exists, or pass `--cache-dir`.",
+        );
+    };
+
+    let probe = cache_dir.join(".jundler-doctor");
+
+    let result = fs::create_dir_all(cache_dir)
+        .and_then(|_| fs::write(&probe, "jundler"))
+        .and_then(|_| fs::remove_file(&probe));
+
+    match result {
+        Ok(()) => Check::ok(NAME, format!("{} is writable", cache_dir.display())),
+        Err(err) => Check::problem(
+            NAME,
+            Status::Error,
+            format!("{} is not writable ({})", cache_dir.display(), err),
+            format!(
+                "Check the permissions of {}, or free up space on its disk.",
+                cache_dir.display()
+            ),
+        ),
+    }
+}
+
+/// Checks that a URL can be reached.
+fn check_network(url: &str) -> Check {
+    let name = format!("Network ({})", url.split('/').nth(2).unwrap_or(url));
+
+    let result = reqwest::blocking::Client::builder()
+        .timeout(NETWORK_TIMEOUT)
+        .build()
+        .and_then(|client| client.head(url).send())
+        .and_then(|response| response.error_for_status());
+
+    match result {
+        Ok(_) => Check::ok(name, format!("{} is reachable", url)),
+        Err(err) => Check::problem(
+            name,
+            Status::Error,
+            format!("Could not reach {} ({})", url, err),
+            "Check your internet connection, and set `HTTPS_PROXY` if you're behind a proxy.",
+        ),
+    }
+}
+
+/// Checks that a tool is installed, by running it with `args`.
+fn check_tool(tool: &str, args: &[&str], status: Status, fix: impl ToString) -> Check {
+    match Command::new(tool).args(args).output() {
+        Ok(output) => {
+            // Some tools (like `codesign`) exit with an error when just printing their help, so only care that they
+            // ran at all.
+            let version = String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .next()
+                .map(|line| line.trim().to_string())
+                .filter(|line| !line.is_empty() && output.status.success());
+
+            Check::ok(
+                tool,
+                match version {
+                    Some(version) => format!("found {}", version),
+                    None => "found".to_string(),
+                },
+            )
+        }
+
+        Err(err) => Check::problem(tool, status, format!("not found ({})", err), fix),
+    }
+}
+
+/// Checks that the disk holding `path` has enough free space for a build.
+fn check_disk_space(name: &str, path: &Path) -> Check {
+    let path = path.canonicalize().unwrap_or_else(|_| PathBuf::from(path));
+    let disks = Disks::new_with_refreshed_list();
+
+    // The disk holding the path is the one with the longest mount point that the path is inside of.
+    let disk = disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len());
+
+    let Some(disk) = disk else {
+        return Check::problem(
+            name,
+            Status::Warning,
+            format!("Could not find the disk holding {}", path.display()),
+            "Make sure there's at least 1 GB of free space for builds.",
+        );
+    };
+
+    let free = disk.available_space();
+    let detail = format!(
+        "{} free on {}",
+        indicatif::HumanBytes(free),
+        disk.mount_point().display()
+    );
+
+    if free >= MIN_FREE_SPACE {
+        Check::ok(name, detail)
+    } else {
+        Check::problem(
+            name,
+            Status::Warning,
+            detail,
+            format!(
+                "Free up space on {}, or run `jundler clean` to clear out old Node.js binaries.",
+                disk.mount_point().display()
+            ),
+        )
+    }
+}
@@ -1,43 +1,69 @@
-mod builder;
+mod ci;
 mod cli;
-mod js_config;
-mod ui;
+mod doctor;
+mod migrate;
 
-use anyhow::Result;
+use anyhow::Context;
+use builder::errors::{ErrorFormat, ErrorReport};
 use clap::Parser;
 use cli::Cli;
-use std::{env, fs, path::PathBuf};
+use env_logger::WriteStyle;
+use jundler_core::{builder, js_config, ui};
+use std::process::ExitCode;
+use ui::color::ColorChoice;
 
-fn main() -> Result<()> {
+fn main() -> ExitCode {
     amend_panic_with_issue_msg();
 
-    // Default the log level to info if it's not set.
-    if env::var("RUST_LOG").is_err() {
-        env::set_var("RUST_LOG", "info")
-    }
+    let cli = Cli::parse();
 
-    env_logger::Builder::from_default_env()
-        .format_timestamp(None)
-        .init();
+    ui::plain::set_plain(cli.plain || ui::plain::detect_plain());
+    ui::events::set_enabled(cli.progress == ui::events::ProgressFormat::Json && !cli.json_only());
+    cli.color.apply(cli.json());
 
-    let cli = Cli::parse();
+    let exit_code = match run(&cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            let report = ErrorReport::new(&err);
+
+            ui::log_file::error(&format!("{:?}", err));
 
-    cli.run()?;
+            let json =
+                serde_json::to_string(&report).expect("Error reports are always serializable!");
 
-    Ok(())
+            // With `--quiet --json`, the error takes the place of the report on stdout
+            match cli.error_format {
+                _ if cli.json_only() => println!("{}", json),
+                ErrorFormat::Human => eprintln!("{}", report.render()),
+                ErrorFormat::Json => eprintln!("{}", json),
+            }
+
+            ExitCode::from(report.exit_code)
+        }
+    };
+
+    ui::log_file::flush();
+
+    exit_code
 }
 
-/// Get the user's cache directory.
-/// TODO: Error handling
-fn get_cache_dir() -> PathBuf {
-    let cache_dir = dirs::cache_dir().unwrap().join("jundler");
+/// Sets up logging and runs the command.
+fn run(cli: &Cli) -> anyhow::Result<()> {
+    // Log at the level from `-v`/`-q`, unless `RUST_LOG` overrides it.
+    let logger = env_logger::Builder::new()
+        .filter_level(cli.verbosity().log_level())
+        .parse_default_env()
+        .format_timestamp(None)
+        .write_style(match cli.color {
+            ColorChoice::Auto => WriteStyle::Auto,
+            ColorChoice::Always => WriteStyle::Always,
+            ColorChoice::Never => WriteStyle::Never,
+        })
+        .build();
 
-    // If the dir doesn't exist, make it
-    if !cache_dir.exists() {
-        fs::create_dir_all(&cache_dir).unwrap();
-    }
+    ui::log_file::init(logger, cli.log_file.as_deref()).context("Could not create the log file")?;
 
-    cache_dir
+    cli.run()
 }
 
 /// OVerride panic messages with a message to submit an issue at the git repo.
@@ -49,7 +75,7 @@ fn amend_panic_with_issue_msg() {
 
         println!();
 
-        println!("{}", console::style("This panic most likely should not have happened (unless your OS is very weird). However, Jundler is experimental and these types of things can happen.").yellow());
+        println!("{}", console::style("This panic most likely should not have happened. However, Jundler is experimental and these types of things can happen.").yellow());
         println!("{}", console::style("If you feel that this panic was unjustified or unreasonable, submit an issue at https://github.com/cogsandsquigs/jundler if you encounter any problems.").yellow());
         println!("{}", console::style("If you aren't sure what to do, submit an issue just in case. Better safe than sorry ;).").yellow());
     }));
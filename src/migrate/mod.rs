@@ -0,0 +1,250 @@
+mod nexe;
+mod pkg;
+mod tests;
+
+use crate::builder::platforms::{Arch, Os, Target};
+use crate::js_config::{SEAConfig, CONFIG_FILE_NAME};
+use anyhow::{anyhow, Context, Result};
+use ignore::overrides::OverrideBuilder;
+use ignore::WalkBuilder;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The oldest major version of Node.js that can build single executable applications with assets.
+const MIN_NODE_MAJOR: u64 = 20;
+
+/// The tools that projects can be migrated to Jundler from.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrateFrom {
+    /// `vercel/pkg`, configured in the `pkg` section of `package.json`.
+    Pkg,
+
+    /// nexe, from the `nexe` command in the `scripts` of `package.json`.
+    Nexe,
+}
+
+impl fmt::Display for MigrateFrom {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MigrateFrom::Pkg => write!(f, "pkg"),
+            MigrateFrom::Nexe => write!(f, "nexe"),
+        }
+    }
+}
+
+/// A project's configuration for another tool, translated to Jundler's.
+#[derive(Debug, Clone)]
+pub struct Migration {
+    /// The `sea-config.json` to build the project with.
+    pub sea_config: SEAConfig,
+
+    /// The keys to set in the project's Jundler configuration.
+    pub jundler_config: toml::Table,
+
+    /// The major version of Node.js the project was built with, to pin in `.nvmrc`.
+    pub node_major: Option<u64>,
+
+    /// The targets the project was built for.
+    pub targets: Vec<Target>,
+
+    /// Anything that couldn't be translated, or that works differently with Jundler.
+    pub notes: Vec<String>,
+}
+
+impl Migration {
+    /// Translates the configuration of a project for `from` into Jundler's.
+    pub fn new(project_dir: &Path, from: MigrateFrom) -> Result<Migration> {
+        let package_json = read_package_json(project_dir)?;
+
+        match from {
+            MigrateFrom::Pkg => pkg::migrate(project_dir, &package_json),
+            MigrateFrom::Nexe => nexe::migrate(project_dir, &package_json),
+        }
+    }
+
+    /// Writes the migrated configuration to the project: `sea-config.json`, the Jundler configuration and targets (to
+    /// `jundler.toml`, along with anything already in the `jundler` section of `package.json`), and `.nvmrc` if the
+    /// project doesn't pin a version of Node.js yet. Existing files are only overwritten with `force`. Returns the
+    /// paths to the written files.
+    pub fn write(&self, project_dir: &Path, force: bool) -> Result<Vec<PathBuf>> {
+        let package_json = read_package_json(project_dir)?;
+        let sea_config_path = project_dir.join("sea-config.json");
+        let jundler_config_path = project_dir.join(CONFIG_FILE_NAME);
+
+        for path in [&sea_config_path, &jundler_config_path] {
+            if path.exists() && !force {
+                return Err(anyhow!(
+                    "{} already exists! Pass `--force` to overwrite it.",
+                    path.display()
+                ));
+            }
+        }
+
+        let mut written = vec![];
+
+        fs::write(
+            &sea_config_path,
+            serde_json::to_string_pretty(&self.sea_config)? + "\n",
+        )
+        .context("Could not write `sea-config.json`!")?;
+
+        written.push(sea_config_path);
+
+        let mut migrated_config = self.jundler_config.clone();
+
+        if !self.targets.is_empty() {
+            migrated_config.insert(
+                "targets".to_string(),
+                toml::Value::Array(
+                    self.targets
+                        .iter()
+                        .map(|target| toml::Value::String(target.to_string()))
+                        .collect(),
+                ),
+            );
+        }
+
+        if !migrated_config.is_empty() {
+            // `jundler.toml` replaces the `jundler` section of `package.json`, so keep what's already there
+            let mut jundler_config = match package_json.get("jundler") {
+                Some(existing) => toml::Table::try_from(existing)
+                    .context("Could not convert the `jundler` section of `package.json`!")?,
+                None => toml::Table::new(),
+            };
+
+            jundler_config.extend(migrated_config);
+
+            fs::write(
+                &jundler_config_path,
+                toml::to_string_pretty(&jundler_config)?,
+            )
+            .context(format!("Could not write `{}`!", CONFIG_FILE_NAME))?;
+
+            written.push(jundler_config_path);
+        }
+
+        let pins_version = [".nvmrc", ".node-version"]
+            .iter()
+            .any(|file| project_dir.join(file).exists())
+            || package_json["engines"]["node"].is_string();
+
+        if let (Some(major), false) = (self.node_major, pins_version) {
+            let nvmrc_path = project_dir.join(".nvmrc");
+
+            fs::write(&nvmrc_path, format!("{}\n", major)).context("Could not write `.nvmrc`!")?;
+
+            written.push(nvmrc_path);
+        }
+
+        Ok(written)
+    }
+
+    /// Gets the commands to build the project for each of its targets with.
+    pub fn build_commands(&self) -> Vec<String> {
+        if self.targets.is_empty() {
+            return vec!["jundler build --bundle".to_string()];
+        }
+
+        self.targets
+            .iter()
+            .map(|target| format!("jundler build --bundle --target {}", target))
+            .collect()
+    }
+}
+
+/// Reads the project's `package.json`.
+fn read_package_json(project_dir: &Path) -> Result<Value> {
+    serde_json::from_str(
+        &fs::read_to_string(project_dir.join("package.json"))
+            .context("Could not find or open the `package.json` file!")?,
+    )
+    .context("Could not parse the `package.json` file!")
+}
+
+/// Parses a target named like `linux-x64`, filling in the OS or architecture of this machine if it's left out.
+fn parse_target(target: &str) -> Option<Target> {
+    // Neither can be filled in on a machine jundler doesn't support
+    let (host_os, host_arch) = (Os::host().ok(), Arch::host().ok());
+
+    let candidates = match target.split('-').filter(|part| !part.is_empty()).count() {
+        0 => vec![format!("{}-{}", host_os?, host_arch?)],
+        1 => [
+            host_arch.map(|host_arch| format!("{}-{}", target, host_arch)),
+            host_os.map(|host_os| format!("{}-{}", host_os, target)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect(),
+        _ => vec![target.to_string()],
+    };
+
+    candidates
+        .iter()
+        .find_map(|candidate| candidate.parse().ok())
+}
+
+/// Finds the files in the project matching any of the globs, as asset keys (paths relative to the project, with `/`)
+/// mapped to the same paths.
+fn find_assets(project_dir: &Path, globs: &[String]) -> Result<BTreeMap<String, String>> {
+    // Without any globs, the walker would match every file
+    if globs.is_empty() {
+        return Ok(BTreeMap::new());
+    }
+
+    let mut overrides = OverrideBuilder::new(project_dir);
+
+    for glob in globs {
+        overrides
+            .add(glob.trim_start_matches("./"))
+            .context(format!("Invalid asset glob `{}`", glob))?;
+    }
+
+    let walker = WalkBuilder::new(project_dir)
+        .standard_filters(false)
+        .overrides(overrides.build()?)
+        .build();
+
+    let mut assets = BTreeMap::new();
+
+    for entry in walker {
+        let entry = entry?;
+
+        if !entry
+            .file_type()
+            .is_some_and(|file_type| file_type.is_file())
+        {
+            continue;
+        }
+
+        let key = entry
+            .path()
+            .strip_prefix(project_dir)?
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        assets.insert(key.clone(), key);
+    }
+
+    Ok(assets)
+}
+
+/// Gets the major version of Node.js to pin from the versions a project was built with, and notes if it's too old.
+fn node_major(majors: &[u64], notes: &mut Vec<String>) -> Option<u64> {
+    let major = majors.iter().max().copied()?;
+
+    if major < MIN_NODE_MAJOR {
+        notes.push(format!(
+            "The project was built with Node.js {}, but single executable applications need Node.js {} or newer, so the latest LTS release is used instead.",
+            major, MIN_NODE_MAJOR
+        ));
+
+        return None;
+    }
+
+    Some(major)
+}
@@ -0,0 +1,321 @@
+use super::{find_assets, node_major, parse_target, Migration};
+use crate::js_config::SEAConfig;
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// The options of nexe that take a value, besides the `--rc-*` ones.
+const VALUE_OPTIONS: &[&str] = &[
+    "-i",
+    "--input",
+    "-o",
+    "--output",
+    "-t",
+    "--target",
+    "-r",
+    "--resource",
+    "-n",
+    "--name",
+    "--ico",
+    "--cwd",
+    "--temp",
+    "--python",
+    "--plugin",
+    "--make",
+    "--configure",
+    "--vcBuild",
+    "--snapshot",
+];
+
+/// The `--rc-*` options of nexe, and the keys of the Windows configuration they become.
+const RC_OPTIONS: &[(&str, &str)] = &[
+    ("CompanyName", "companyName"),
+    ("ProductName", "productName"),
+    ("FileDescription", "fileDescription"),
+    ("FileVersion", "fileVersion"),
+    ("ProductVersion", "productVersion"),
+    ("LegalCopyright", "copyright"),
+];
+
+/// An invocation of the nexe CLI.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NexeCommand {
+    /// The entrypoint, from `--input` or the first argument.
+    pub input: Option<String>,
+
+    /// The path of the built binary.
+    pub output: Option<String>,
+
+    /// The targets to build for, like `linux-x64-14.15.3`.
+    pub targets: Vec<String>,
+
+    /// The globs of the resources to embed.
+    pub resources: Vec<String>,
+
+    /// The `--rc-*` options for Windows resources, without the `--rc-` prefix.
+    pub rc: BTreeMap<String, String>,
+
+    /// Any other options given, which have no equivalent.
+    pub other: Vec<String>,
+}
+
+impl NexeCommand {
+    /// Parses the arguments given to nexe.
+    pub fn parse(args: &[String]) -> NexeCommand {
+        let mut command = NexeCommand::default();
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            // Options can be given as `--option value` or `--option=value`
+            let (option, inline_value) = match arg.split_once('=') {
+                Some((option, value)) if option.starts_with('-') => {
+                    (option, Some(value.to_string()))
+                }
+                _ => (arg.as_str(), None),
+            };
+
+            if !option.starts_with('-') {
+                command.input.get_or_insert_with(|| option.to_string());
+                continue;
+            }
+
+            let takes_value = VALUE_OPTIONS.contains(&option) || option.starts_with("--rc-");
+
+            let value = match (takes_value, inline_value) {
+                (true, Some(value)) => value,
+                (true, None) => match args.next() {
+                    Some(value) => value.clone(),
+                    None => break,
+                },
+                (false, _) => {
+                    command.other.push(option.to_string());
+                    continue;
+                }
+            };
+
+            match option {
+                "-i" | "--input" => command.input = Some(value),
+                "-o" | "--output" => command.output = Some(value),
+                "-t" | "--target" => command.targets.push(value),
+                "-r" | "--resource" => command.resources.push(value),
+                rc if rc.starts_with("--rc-") => {
+                    command.rc.insert(rc["--rc-".len()..].to_string(), value);
+                }
+                option => command.other.push(option.to_string()),
+            }
+        }
+
+        command
+    }
+
+    /// Finds the first invocation of nexe in the `scripts` of `package.json`.
+    pub fn find(package_json: &Value) -> Option<NexeCommand> {
+        let scripts = package_json["scripts"].as_object()?;
+
+        scripts
+            .values()
+            .filter_map(Value::as_str)
+            .flat_map(|script| {
+                // Split up chained commands, like `tsc && nexe dist/index.js`
+                shell_words(script)
+                    .split(|word| matches!(word.as_str(), "&&" | "||" | ";" | "|"))
+                    .map(|words| words.to_vec())
+                    .collect::<Vec<_>>()
+            })
+            .find_map(|words| {
+                let position = words
+                    .iter()
+                    .position(|word| word == "nexe" || word.ends_with("/nexe"))?;
+
+                Some(NexeCommand::parse(&words[position + 1..]))
+            })
+    }
+}
+
+/// Translates the nexe invocation in the `scripts` of `package.json` into Jundler's configuration.
+pub fn migrate(project_dir: &Path, package_json: &Value) -> Result<Migration> {
+    let mut notes = vec![];
+
+    let command = NexeCommand::find(package_json).unwrap_or_else(|| {
+        notes.push("There's no `nexe` command in the `scripts` of `package.json`, so only the entrypoint was migrated.".to_string());
+        NexeCommand::default()
+    });
+
+    // nexe builds the `main` of the package if there's no input, like Node.js
+    let main = command
+        .input
+        .as_deref()
+        .or(package_json["main"].as_str())
+        .unwrap_or("index.js")
+        .trim_start_matches("./")
+        .to_string();
+
+    if !Path::new(&main).is_relative() {
+        return Err(anyhow!(
+            "The entrypoint {} has to be inside the project!",
+            main
+        ));
+    }
+
+    let assets = find_assets(project_dir, &command.resources)?;
+
+    if !assets.is_empty() {
+        notes.push(format!(
+            "{} resource(s) are embedded in the binary. Read them with `require('node:sea').getAsset(<path>)` instead of from the filesystem, as `fs` can't see them like it could with nexe.",
+            assets.len()
+        ));
+    }
+
+    for option in &command.other {
+        notes.push(format!(
+            "nexe's `{}` option has no equivalent in Jundler, and was left out.",
+            option
+        ));
+    }
+
+    let mut majors = vec![];
+    let mut targets = vec![];
+
+    for target in &command.targets {
+        let mut parts = vec![];
+
+        for part in target.split('-') {
+            match part {
+                version if version.starts_with(|c: char| c.is_ascii_digit()) => {
+                    if let Some(Ok(major)) = version.split('.').next().map(str::parse) {
+                        majors.push(major);
+                    }
+                }
+                "mac" => parts.push("darwin"),
+                "alpine" => {
+                    notes.push(format!(
+                        "Jundler builds with the official glibc Node.js binaries, so `{}` is built for regular Linux.",
+                        target
+                    ));
+                    parts.push("linux");
+                }
+                part => parts.push(part),
+            }
+        }
+
+        match parse_target(&parts.join("-")) {
+            Some(target) if !targets.contains(&target) => targets.push(target),
+            Some(_) => {}
+            None => notes.push(format!("Unsupported target `{}` was left out.", target)),
+        }
+    }
+
+    let mut jundler_config = toml::Table::new();
+
+    if let Some(output) = &command.output {
+        let output_path = Path::new(output);
+
+        if output_path
+            .parent()
+            .is_some_and(|parent| !parent.as_os_str().is_empty())
+        {
+            notes.push(format!(
+                "Binaries are built into the project directory, not {}.",
+                output_path.parent().unwrap_or(output_path).display()
+            ));
+        }
+
+        if let Some(stem) = output_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(|name| name.trim_end_matches(".exe"))
+        {
+            // Builds for several targets need their own names, or they overwrite each other
+            let template = if targets.len() > 1 {
+                format!("{}-{{os}}-{{arch}}{{ext}}", stem)
+            } else {
+                format!("{}{{ext}}", stem)
+            };
+
+            jundler_config.insert("outputName".to_string(), toml::Value::String(template));
+        }
+    }
+
+    let mut windows_config = toml::Table::new();
+
+    for (rc, value) in &command.rc {
+        match RC_OPTIONS.iter().find(|(option, _)| option == rc) {
+            Some((_, key)) => {
+                windows_config.insert(key.to_string(), toml::Value::String(value.clone()));
+            }
+            None => notes.push(format!(
+                "nexe's `--rc-{}` option has no equivalent in Jundler, and was left out.",
+                rc
+            )),
+        }
+    }
+
+    if !windows_config.is_empty() {
+        jundler_config.insert("windows".to_string(), toml::Value::Table(windows_config));
+    }
+
+    Ok(Migration {
+        sea_config: SEAConfig {
+            main,
+            output: "sea-prep.blob".to_string(),
+            assets,
+            other: BTreeMap::from([(
+                "disableExperimentalSEAWarning".to_string(),
+                Value::Bool(true),
+            )]),
+        },
+        jundler_config,
+        node_major: node_major(&majors, &mut notes),
+        targets,
+        notes,
+    })
+}
+
+/// Splits a shell command into words, like a POSIX shell would without expanding anything.
+fn shell_words(command: &str) -> Vec<String> {
+    let mut words = vec![];
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = command.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                word.extend(chars.by_ref().take_while(|&c| c != '\''));
+            }
+            '"' => {
+                in_word = true;
+
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => word.extend(chars.next()),
+                        c => word.push(c),
+                    }
+                }
+            }
+            '\\' => {
+                in_word = true;
+                word.extend(chars.next());
+            }
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+
+    if in_word {
+        words.push(word);
+    }
+
+    words
+}
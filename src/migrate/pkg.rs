@@ -0,0 +1,125 @@
+use super::{find_assets, node_major, parse_target, Migration};
+use crate::js_config::SEAConfig;
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Translates the `pkg` section of `package.json` into Jundler's configuration.
+pub fn migrate(project_dir: &Path, package_json: &Value) -> Result<Migration> {
+    let config = &package_json["pkg"];
+    let mut notes = vec![];
+
+    if config.is_null() {
+        notes.push(
+            "There's no `pkg` section in `package.json`, so only the entrypoint was migrated."
+                .to_string(),
+        );
+    }
+
+    // pkg builds the `bin` of the package, or its `main` if it has none
+    let main = match &package_json["bin"] {
+        Value::String(bin) => Some(bin.as_str()),
+        Value::Object(bins) => bins.values().next().and_then(Value::as_str),
+        _ => package_json["main"].as_str(),
+    }
+    .ok_or_else(|| {
+        anyhow!("Could not find the entrypoint! Set `bin` or `main` in `package.json`.")
+    })?
+    .trim_start_matches("./")
+    .to_string();
+
+    let assets = find_assets(project_dir, &strings(&config["assets"]))?;
+
+    if !assets.is_empty() {
+        notes.push(format!(
+            "{} asset(s) are embedded in the binary. Read them with `require('node:sea').getAsset(<path>)` instead of from the filesystem, as `fs` can't see them like it could with pkg.",
+            assets.len()
+        ));
+    }
+
+    if !strings(&config["scripts"]).is_empty() {
+        notes.push("pkg's `scripts` aren't needed: everything the entrypoint `require`s is bundled with `--bundle`. Scripts that are only loaded dynamically have to be `require`d statically instead.".to_string());
+    }
+
+    for option in ["patches", "compress", "options", "outputPath"] {
+        if !config[option].is_null() {
+            notes.push(format!(
+                "pkg's `{}` option has no equivalent in Jundler, and was left out.",
+                option
+            ));
+        }
+    }
+
+    let mut majors = vec![];
+    let mut targets = vec![];
+
+    for target in strings(&config["targets"]) {
+        let mut parts = vec![];
+
+        for part in target.split('-') {
+            match part {
+                node if node.starts_with("node") => {
+                    if let Ok(major) = node["node".len()..].parse() {
+                        majors.push(major);
+                    }
+                }
+                "latest" | "host" => {}
+                "alpine" | "linuxstatic" => {
+                    notes.push(format!(
+                        "Jundler builds with the official glibc Node.js binaries, so `{}` is built for regular Linux.",
+                        target
+                    ));
+                    parts.push("linux");
+                }
+                part => parts.push(part),
+            }
+        }
+
+        match parse_target(&parts.join("-")) {
+            Some(target) if !targets.contains(&target) => targets.push(target),
+            Some(_) => {}
+            None => notes.push(format!("Unsupported target `{}` was left out.", target)),
+        }
+    }
+
+    let mut jundler_config = toml::Table::new();
+
+    // pkg names the binaries after their target when building more than one, so builds don't overwrite each other
+    if targets.len() > 1 {
+        jundler_config.insert(
+            "outputName".to_string(),
+            toml::Value::String("{name}-{os}-{arch}{ext}".to_string()),
+        );
+    }
+
+    Ok(Migration {
+        sea_config: SEAConfig {
+            main,
+            output: "sea-prep.blob".to_string(),
+            assets,
+            other: BTreeMap::from([(
+                "disableExperimentalSEAWarning".to_string(),
+                Value::Bool(true),
+            )]),
+        },
+        jundler_config,
+        node_major: node_major(&majors, &mut notes),
+        targets,
+        notes,
+    })
+}
+
+/// Gets a list of strings (like globs or targets) from pkg's configuration, which can be a single string or an array
+/// of them.
+fn strings(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(string) => vec![string.clone()],
+        Value::Array(strings) => strings
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        _ => vec![],
+    }
+}
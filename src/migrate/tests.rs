@@ -0,0 +1,174 @@
+#![cfg(test)]
+
+use super::*;
+use crate::builder::platforms::{Arch, Os};
+use tempdir::TempDir;
+
+/// Test that a pkg configuration is translated into a `sea-config.json`, Jundler configuration, and targets.
+#[test]
+fn migrate_from_pkg() {
+    let project = TempDir::new("project").unwrap();
+
+    fs::write(
+        project.path().join("package.json"),
+        r#"{
+            "name": "mytool",
+            "bin": { "mytool": "./bin/cli.js" },
+            "jundler": { "windows": { "longPathAware": true } },
+            "pkg": {
+                "assets": ["views/**/*", "./config.json"],
+                "scripts": "lib/plugins/*.js",
+                "targets": ["node20-linux-x64", "node20-macos-arm64", "node20-alpine-x64", "node20-freebsd-x64"],
+                "outputPath": "dist"
+            }
+        }"#,
+    )
+    .unwrap();
+    fs::create_dir_all(project.path().join("views/partials")).unwrap();
+    fs::write(project.path().join("views/index.html"), "").unwrap();
+    fs::write(project.path().join("views/partials/header.html"), "").unwrap();
+    fs::write(project.path().join("config.json"), "{}").unwrap();
+    fs::write(project.path().join("other.json"), "{}").unwrap();
+
+    let migration = Migration::new(project.path(), MigrateFrom::Pkg).unwrap();
+
+    assert_eq!(migration.sea_config.main, "bin/cli.js");
+    assert_eq!(
+        migration.sea_config.assets.keys().collect::<Vec<_>>(),
+        [
+            "config.json",
+            "views/index.html",
+            "views/partials/header.html"
+        ]
+    );
+    assert_eq!(
+        migration.targets,
+        [
+            Target {
+                os: Os::Linux,
+                arch: Arch::X64
+            },
+            Target {
+                os: Os::MacOS,
+                arch: Arch::Arm64
+            },
+        ]
+    );
+    assert_eq!(migration.node_major, Some(20));
+    assert_eq!(
+        migration.jundler_config["outputName"].as_str(),
+        Some("{name}-{os}-{arch}{ext}")
+    );
+    assert_eq!(
+        migration.build_commands(),
+        [
+            "jundler build --bundle --target linux-x64",
+            "jundler build --bundle --target darwin-arm64"
+        ]
+    );
+    assert!(migration.notes.iter().any(|note| note.contains("freebsd")));
+    assert!(migration
+        .notes
+        .iter()
+        .any(|note| note.contains("outputPath")));
+
+    let written = migration.write(project.path(), false).unwrap();
+
+    assert_eq!(written.len(), 3);
+    assert_eq!(
+        fs::read_to_string(project.path().join(".nvmrc")).unwrap(),
+        "20\n"
+    );
+
+    // The existing `jundler` section is kept, as `jundler.toml` replaces it
+    let jundler_config: toml::Table =
+        toml::from_str(&fs::read_to_string(project.path().join(CONFIG_FILE_NAME)).unwrap())
+            .unwrap();
+
+    assert_eq!(
+        jundler_config["windows"]["longPathAware"].as_bool(),
+        Some(true)
+    );
+
+    let jundler_config = crate::js_config::JundlerConfig::from_project(project.path())
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(jundler_config.targets, migration.targets);
+    assert!(jundler_config.windows.long_path_aware.unwrap());
+
+    // Projects without assets don't embed anything
+    assert!(find_assets(project.path(), &[]).unwrap().is_empty());
+
+    // Nothing is overwritten without `force`
+    assert!(migration.write(project.path(), false).is_err());
+    assert!(migration.write(project.path(), true).is_ok());
+}
+
+/// Test that a nexe command in the `scripts` of `package.json` is found and translated.
+#[test]
+fn migrate_from_nexe() {
+    let project = TempDir::new("project").unwrap();
+
+    fs::write(
+        project.path().join("package.json"),
+        r#"{
+            "name": "mytool",
+            "main": "index.js",
+            "scripts": {
+                "build": "tsc && npx nexe dist/cli.js -o bin/mytool.exe -t windows-x64-20.11.1 --target=mac-arm64-20.11.1 -r 'assets/**/*' --rc-CompanyName \"Acme, Inc.\" --rc-InternalName mytool --ico icon.ico --verbose",
+                "test": "node --test"
+            }
+        }"#,
+    )
+    .unwrap();
+    fs::create_dir_all(project.path().join("assets")).unwrap();
+    fs::write(project.path().join("assets/logo.txt"), "").unwrap();
+
+    let command = nexe::NexeCommand::find(&read_package_json(project.path()).unwrap()).unwrap();
+
+    assert_eq!(command.input.as_deref(), Some("dist/cli.js"));
+    assert_eq!(command.output.as_deref(), Some("bin/mytool.exe"));
+    assert_eq!(
+        command.targets,
+        ["windows-x64-20.11.1", "mac-arm64-20.11.1"]
+    );
+    assert_eq!(command.resources, ["assets/**/*"]);
+    assert_eq!(command.rc["CompanyName"], "Acme, Inc.");
+    assert_eq!(command.other, ["--ico", "--verbose"]);
+
+    let migration = Migration::new(project.path(), MigrateFrom::Nexe).unwrap();
+
+    assert_eq!(migration.sea_config.main, "dist/cli.js");
+    assert_eq!(
+        migration.sea_config.assets.keys().collect::<Vec<_>>(),
+        ["assets/logo.txt"]
+    );
+    assert_eq!(
+        migration.targets,
+        [
+            Target {
+                os: Os::Windows,
+                arch: Arch::X64
+            },
+            Target {
+                os: Os::MacOS,
+                arch: Arch::Arm64
+            },
+        ]
+    );
+    assert_eq!(migration.node_major, Some(20));
+    assert_eq!(
+        migration.jundler_config["outputName"].as_str(),
+        Some("mytool-{os}-{arch}{ext}")
+    );
+    assert_eq!(
+        migration.jundler_config["windows"]["companyName"].as_str(),
+        Some("Acme, Inc.")
+    );
+    assert!(migration
+        .notes
+        .iter()
+        .any(|note| note.contains("--rc-InternalName")));
+    assert!(migration.notes.iter().any(|note| note.contains("bin")));
+}
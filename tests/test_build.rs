@@ -1,12 +1,31 @@
 use assert_fs::{fixture::PathCopy, TempDir};
-use std::env;
 use std::path::PathBuf;
 use std::process::Command;
+use std::{env, fs};
 
 // Function to generate lit runner for a fixture directory. Why do this instead of just
 // searching for all shell files in the directory? Because we want to be able to only test some
 // of the files in the directory, and we want to be able to pass in constants to the tests.
 fn runner(name: &str, jundler_args: &[&str], expected_stdout: &str, expected_stderr: &str) {
+    let tmp_dir = build(name, jundler_args, &[]);
+
+    // Run the generated file
+    let result = Command::new(tmp_dir.path().join(name)).output().unwrap();
+
+    // Print outputs for debugging
+    println!("GENERATED BINARY ({}) OUTPUT", name);
+    println!("----------------------------------------------------");
+    println!("status: {}", result.status);
+    println!("stdout:\n{}", String::from_utf8_lossy(&result.stdout));
+    println!("stderr:\n{}", String::from_utf8_lossy(&result.stderr));
+
+    assert!(result.status.success());
+    assert!(String::from_utf8_lossy(&result.stdout).contains(expected_stdout));
+    assert!(String::from_utf8_lossy(&result.stderr).contains(expected_stderr));
+}
+
+// Builds a copy of a fixture directory (with `extra_files` added to it) in a new tmp dir, and returns the tmp dir.
+fn build(name: &str, jundler_args: &[&str], extra_files: &[(&str, &str)]) -> TempDir {
     // Create tmp dir for test
     let tmp_dir = TempDir::new().unwrap();
 
@@ -14,6 +33,10 @@ fn runner(name: &str, jundler_args: &[&str], expected_stdout: &str, expected_std
     let fixture_path = PathBuf::from("tests/fixtures").join(name);
     tmp_dir.copy_from(fixture_path, &["**/*"]).unwrap();
 
+    for (path, contents) in extra_files {
+        fs::write(tmp_dir.path().join(path), contents).unwrap();
+    }
+
     // Set the RUST_LOG environment variable to debug so we can see the output of the build process.
     env::set_var("RUST_LOG", "debug");
 
@@ -36,19 +59,7 @@ fn runner(name: &str, jundler_args: &[&str], expected_stdout: &str, expected_std
 
     assert!(result.status.success());
 
-    // Run the generated file
-    let result = Command::new(tmp_dir.path().join(name)).output().unwrap();
-
-    // Print outputs for debugging
-    println!("GENERATED BINARY ({}) OUTPUT", name);
-    println!("----------------------------------------------------");
-    println!("status: {}", result.status);
-    println!("stdout:\n{}", String::from_utf8_lossy(&result.stdout));
-    println!("stderr:\n{}", String::from_utf8_lossy(&result.stderr));
-
-    assert!(result.status.success());
-    assert!(String::from_utf8_lossy(&result.stdout).contains(expected_stdout));
-    assert!(String::from_utf8_lossy(&result.stderr).contains(expected_stderr));
+    tmp_dir
 }
 
 #[test]
@@ -66,6 +77,25 @@ fn simple_ts() {
     runner("simple-ts", &[], "Hello, world!", "");
 }
 
+// Two reproducible builds of the same project, in different directories, must be bit-identical.
+#[test]
+fn reproducible() {
+    // Reproducible builds install strictly from the lockfile, so the fixture needs one
+    let lockfile = (
+        "package-lock.json",
+        r#"{"name": "simple-bundle", "version": "1.0.0", "lockfileVersion": 3, "requires": true, "packages": {"": {"name": "simple-bundle", "version": "1.0.0"}}}"#,
+    );
+
+    let first = build("simple-bundle", &["--reproducible", "--force"], &[lockfile]);
+    let second = build("simple-bundle", &["--reproducible", "--force"], &[lockfile]);
+
+    assert!(
+        fs::read(first.path().join("simple-bundle")).unwrap()
+            == fs::read(second.path().join("simple-bundle")).unwrap(),
+        "Reproducible builds of the same project differ!"
+    );
+}
+
 // NOTE: This requires a binary of `node` (version 22.3.0) to be installed in `tests/fixtures/custom-node/node`
 #[ignore]
 #[test]